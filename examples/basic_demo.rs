@@ -117,13 +117,16 @@ impl App for BasicDemo {
             },
             render: sanji_engine::RenderConfig {
                 backend: "auto".to_string(),
-                msaa_samples: 4,
+                anti_aliasing: sanji_engine::render::AntiAliasing::Msaa(4),
                 max_texture_size: 4096,
+                ..Default::default()
             },
             assets: sanji_engine::AssetConfig {
                 asset_folder: "assets".to_string(),
                 cache_size: 1024 * 1024 * 256, // 256MB
+                ..Default::default()
             },
+            ..Default::default()
         }
     }
 }
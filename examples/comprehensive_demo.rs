@@ -41,11 +41,11 @@ fn main() -> EngineResult<()> {
     let mut last_time = Instant::now();
     let mut frame_count = 0u64;
 
-    engine.run(move |engine, delta_time| {
+    engine.run_with(move |engine, frame| {
         frame_count += 1;
         
         // 更新游戏逻辑
-        update_demo_logic(engine, delta_time)?;
+        update_demo_logic(engine, frame.delta_time)?;
         
         // 渲染帧
         render_frame(engine)?;
@@ -296,7 +296,7 @@ fn create_directional_light(engine: &mut Engine) -> EngineResult<Entity> {
     // 变换组件
     let transform = TransformComponent {
         position: Vec3::new(0.0, 10.0, 0.0),
-        rotation: Quat::from_euler(glam::EulerRot::XYZ, -0.8, 0.3, 0.0),
+        rotation: sanji_engine::math::euler_to_quat(Vec3::new(-0.8, 0.3, 0.0)),
         ..Default::default()
     };
 
@@ -380,7 +380,7 @@ fn create_demo_camera(engine: &mut Engine) -> EngineResult<Entity> {
     // 变换组件
     let transform = TransformComponent {
         position: Vec3::new(0.0, 5.0, 10.0),
-        rotation: Quat::from_euler(glam::EulerRot::XYZ, -0.3, 0.0, 0.0),
+        rotation: sanji_engine::math::euler_to_quat(Vec3::new(-0.3, 0.0, 0.0)),
         ..Default::default()
     };
 
@@ -638,7 +638,7 @@ fn update_demo_logic(engine: &mut Engine, delta_time: f32) -> EngineResult<()> {
     // 旋转魔法球
     if let Some(sphere_entity) = engine.find_entity_by_name("Magic Sphere") {
         if let Some(transform) = engine.world.get_component_mut::<TransformComponent>(sphere_entity) {
-            transform.rotation *= Quat::from_euler(glam::EulerRot::Y, delta_time, 0.0, 0.0);
+            transform.rotation *= Quat::from_rotation_y(delta_time);
         }
     }
 
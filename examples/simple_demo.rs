@@ -47,13 +47,16 @@ impl App for SimpleDemo {
             },
             render: RenderConfig {
                 backend: "auto".to_string(),
-                msaa_samples: 1,
+                anti_aliasing: sanji_engine::render::AntiAliasing::None,
                 max_texture_size: 2048,
+                ..Default::default()
             },
             assets: AssetConfig {
                 asset_folder: "assets".to_string(),
                 cache_size: 1024 * 1024 * 128, // 128MB
+                ..Default::default()
             },
+            ..Default::default()
         }
     }
 }
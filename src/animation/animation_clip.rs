@@ -4,6 +4,7 @@ use crate::math::Vec3;
 use crate::EngineResult;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
 
 /// 动画剪辑
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +50,18 @@ pub enum KeyframeValue {
     Color([f32; 4]),
 }
 
+impl Keyframe {
+    /// 创建无切线的关键帧
+    pub fn new(time: f32, value: KeyframeValue) -> Self {
+        Self {
+            time,
+            value,
+            tangent_in: None,
+            tangent_out: None,
+        }
+    }
+}
+
 impl AnimationClip {
     /// 创建新的动画剪辑
     pub fn new(name: impl Into<String>, duration: f32) -> Self {
@@ -64,6 +77,31 @@ impl AnimationClip {
         self.tracks.push(track);
     }
 
+    /// 所有轨道(供编辑器读取曲线)
+    pub fn tracks(&self) -> &[AnimationTrack] {
+        &self.tracks
+    }
+
+    /// 可编辑的轨道
+    pub fn tracks_mut(&mut self) -> &mut [AnimationTrack] {
+        &mut self.tracks
+    }
+
+    /// 按目标名称查找轨道
+    pub fn track(&self, target: &str) -> Option<&AnimationTrack> {
+        self.tracks.iter().find(|track| track.target == target)
+    }
+
+    /// 按目标名称查找可编辑轨道
+    pub fn track_mut(&mut self, target: &str) -> Option<&mut AnimationTrack> {
+        self.tracks.iter_mut().find(|track| track.target == target)
+    }
+
+    /// 移除轨道
+    pub fn remove_track(&mut self, index: usize) -> Option<AnimationTrack> {
+        (index < self.tracks.len()).then(|| self.tracks.remove(index))
+    }
+
     /// 获取指定时间的动画值
     pub fn sample(&self, time: f32) -> HashMap<String, KeyframeValue> {
         let mut result = HashMap::new();
@@ -88,11 +126,36 @@ impl AnimationTrack {
         }
     }
 
-    /// 添加关键帧
-    pub fn add_keyframe(&mut self, keyframe: Keyframe) {
-        self.keyframes.push(keyframe);
-        // 按时间排序
-        self.keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+    /// 添加关键帧，按时间插入并返回其索引(同一时间的关键帧排在已有关键帧之后)
+    pub fn add_keyframe(&mut self, keyframe: Keyframe) -> usize {
+        let index = self.keyframes.partition_point(|key| key.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+        index
+    }
+
+    /// 移除关键帧
+    pub fn remove_keyframe(&mut self, index: usize) -> Option<Keyframe> {
+        (index < self.keyframes.len()).then(|| self.keyframes.remove(index))
+    }
+
+    /// 所有关键帧(按时间排序)
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// 可编辑的关键帧，修改结束后自动按时间重新排序
+    pub fn keyframes_mut(&mut self) -> KeyframesMut<'_> {
+        KeyframesMut { keyframes: &mut self.keyframes }
+    }
+
+    /// 按时间重新排序关键帧
+    pub fn sort_keyframes(&mut self) {
+        sort_by_time(&mut self.keyframes);
+    }
+
+    /// 第一个和最后一个关键帧的时间
+    pub fn time_range(&self) -> Option<(f32, f32)> {
+        Some((self.keyframes.first()?.time, self.keyframes.last()?.time))
     }
 
     /// 采样指定时间的值
@@ -159,3 +222,93 @@ impl AnimationTrack {
         }
     }
 }
+
+/// 关键帧的可变借用，释放时恢复时间顺序，保证采样时关键帧有序
+pub struct KeyframesMut<'a> {
+    keyframes: &'a mut Vec<Keyframe>,
+}
+
+impl Deref for KeyframesMut<'_> {
+    type Target = Vec<Keyframe>;
+
+    fn deref(&self) -> &Self::Target {
+        self.keyframes
+    }
+}
+
+impl DerefMut for KeyframesMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.keyframes
+    }
+}
+
+impl Drop for KeyframesMut<'_> {
+    fn drop(&mut self) {
+        sort_by_time(self.keyframes);
+    }
+}
+
+/// 稳定排序，时间相同的关键帧保持原有顺序
+fn sort_by_time(keyframes: &mut [Keyframe]) {
+    keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn float_at(track: &AnimationTrack, time: f32) -> f32 {
+        match track.sample(time) {
+            Some(KeyframeValue::Float(value)) => value,
+            other => panic!("unexpected sample {:?}", other),
+        }
+    }
+
+    fn times(track: &AnimationTrack) -> Vec<f32> {
+        track.keyframes().iter().map(|key| key.time).collect()
+    }
+
+    #[test]
+    fn inserted_key_is_sampled_between_neighbours() {
+        let mut track = AnimationTrack::new("door", AnimationProperty::Alpha);
+        track.add_keyframe(Keyframe::new(0.0, KeyframeValue::Float(0.0)));
+        track.add_keyframe(Keyframe::new(2.0, KeyframeValue::Float(1.0)));
+        assert_eq!(float_at(&track, 1.0), 0.5);
+
+        assert_eq!(track.add_keyframe(Keyframe::new(1.0, KeyframeValue::Float(4.0))), 1);
+        assert_eq!(times(&track), vec![0.0, 1.0, 2.0]);
+        assert_eq!(float_at(&track, 1.0), 4.0);
+        assert_eq!(float_at(&track, 0.5), 2.0);
+        assert_eq!(track.time_range(), Some((0.0, 2.0)));
+    }
+
+    #[test]
+    fn edited_keys_are_resorted() {
+        let mut track = AnimationTrack::new("door", AnimationProperty::Alpha);
+        for (time, value) in [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)] {
+            track.add_keyframe(Keyframe::new(time, KeyframeValue::Float(value)));
+        }
+
+        // 把第一个关键帧拖到末尾之后
+        track.keyframes_mut()[0].time = 3.0;
+        assert_eq!(times(&track), vec![1.0, 2.0, 3.0]);
+
+        // 同一时间插入的关键帧排在已有关键帧之后
+        assert_eq!(track.add_keyframe(Keyframe::new(2.0, KeyframeValue::Float(9.0))), 2);
+        assert!(track.remove_keyframe(10).is_none());
+        assert_eq!(track.remove_keyframe(0).map(|key| key.time), Some(1.0));
+    }
+
+    #[test]
+    fn clip_tracks_are_editable_by_target() {
+        let mut clip = AnimationClip::new("open", 2.0);
+        clip.add_track(AnimationTrack::new("door", AnimationProperty::Alpha));
+        clip.track_mut("door").unwrap().add_keyframe(Keyframe::new(0.5, KeyframeValue::Float(1.0)));
+
+        assert_eq!(clip.track("door").unwrap().keyframes().len(), 1);
+        assert!(clip.track("window").is_none());
+        assert!(clip.remove_track(1).is_none());
+        assert_eq!(clip.remove_track(0).map(|track| track.target), Some("door".to_string()));
+        assert!(clip.tracks().is_empty());
+    }
+}
@@ -1,7 +1,8 @@
 //! 动画播放器系统
 
-use crate::animation::{AnimationClip, KeyframeValue};
-use crate::ecs::{Component, Entity};
+use crate::animation::{AnimationClip, AnimationProperty, KeyframeValue};
+use crate::ecs::{Component, Entity, Transform};
+use crate::math::{Quat, Vec3};
 use crate::EngineResult;
 use serde::{Serialize, Deserialize};
 use specs::VecStorage;
@@ -16,6 +17,12 @@ pub struct Animator {
     pub is_playing: bool,
     pub is_looping: bool,
     pub clips: HashMap<String, AnimationClip>,
+    /// 是否提取根运动，开启后根骨骼的位移和旋转不再写入姿态
+    #[serde(default)]
+    pub root_motion: bool,
+    /// 根骨骼(轨道目标)名称
+    #[serde(default = "Animator::default_root_motion_target")]
+    pub root_motion_target: String,
 }
 
 impl Component for Animator {
@@ -31,6 +38,8 @@ impl Default for Animator {
             is_playing: false,
             is_looping: true,
             clips: HashMap::new(),
+            root_motion: false,
+            root_motion_target: Self::default_root_motion_target(),
         }
     }
 }
@@ -41,6 +50,22 @@ impl Animator {
         Self::default()
     }
 
+    fn default_root_motion_target() -> String {
+        "root".to_string()
+    }
+
+    /// 以target为根骨骼启用根运动
+    pub fn with_root_motion(mut self, target: impl Into<String>) -> Self {
+        self.root_motion = true;
+        self.root_motion_target = target.into();
+        self
+    }
+
+    /// 开启或关闭根运动
+    pub fn set_root_motion_enabled(&mut self, enabled: bool) {
+        self.root_motion = enabled;
+    }
+
     /// 添加动画剪辑
     pub fn add_clip(&mut self, clip: AnimationClip) {
         self.clips.insert(clip.name.clone(), clip);
@@ -107,7 +132,99 @@ impl Animator {
             }
         }
 
-        Some(clip.sample(self.time))
+        let mut pose = clip.sample(self.time);
+        if self.root_motion {
+            pose.remove(&self.root_motion_target);
+        }
+        Some(pose)
+    }
+
+    /// 下一次 `update(delta_time)` 期间根骨骼的位移和旋转
+    ///
+    /// 位移以本帧起始时根骨骼的朝向为参考系，循环播放跨越剪辑结尾时会累加各段的运动；
+    /// 在 `update` 之前调用，结果通过 `apply_root_motion` 作用到实体的Transform或交给角色控制器。
+    pub fn root_motion_delta(&self, delta_time: f32) -> (Vec3, Quat) {
+        let identity = (Vec3::ZERO, Quat::IDENTITY);
+        if !self.root_motion || !self.is_playing {
+            return identity;
+        }
+        let Some(clip) = self.current_clip.as_ref().and_then(|name| self.clips.get(name)) else {
+            return identity;
+        };
+        let advance = delta_time * self.speed;
+        if clip.duration <= 0.0 || advance <= 0.0 {
+            return identity;
+        }
+
+        if !self.is_looping {
+            let end = (self.time + advance).min(clip.duration);
+            return self.root_motion_segment(clip, self.time.min(end), end);
+        }
+
+        // 当前位置到剪辑结尾(或本帧终点)
+        let cursor = self.time.clamp(0.0, clip.duration);
+        let first_end = (cursor + advance).min(clip.duration);
+        let mut total = self.root_motion_segment(clip, cursor, first_end);
+        if first_end < clip.duration {
+            return total;
+        }
+
+        // 整圈按次数一次性累加，剩余部分从剪辑开头采样；不逐圈循环，避免浮点步进无法前进时卡死
+        let remaining = (advance - (clip.duration - cursor)).max(0.0);
+        let whole_loops = (remaining / clip.duration).floor();
+        let partial = (remaining - whole_loops * clip.duration).clamp(0.0, clip.duration);
+        let full_loop = self.root_motion_segment(clip, 0.0, clip.duration);
+        total = Self::compose_root_motion(total, Self::repeat_root_motion(full_loop, whole_loops as u64));
+        if partial > 0.0 {
+            total = Self::compose_root_motion(total, self.root_motion_segment(clip, 0.0, partial));
+        }
+        total
+    }
+
+    /// 先执行a再执行b的根运动
+    fn compose_root_motion(a: (Vec3, Quat), b: (Vec3, Quat)) -> (Vec3, Quat) {
+        (a.0 + a.1 * b.0, (a.1 * b.1).normalize())
+    }
+
+    /// 同一段根运动重复count次(按二进制拆分，O(log count))
+    fn repeat_root_motion(mut motion: (Vec3, Quat), mut count: u64) -> (Vec3, Quat) {
+        let mut result = (Vec3::ZERO, Quat::IDENTITY);
+        while count > 0 {
+            if count & 1 == 1 {
+                result = Self::compose_root_motion(result, motion);
+            }
+            motion = Self::compose_root_motion(motion, motion);
+            count >>= 1;
+        }
+        result
+    }
+
+    /// 把根运动作用到Transform：位移按实体当前朝向旋转后累加
+    pub fn apply_root_motion(transform: &mut Transform, (translation, rotation): (Vec3, Quat)) {
+        transform.position += transform.rotation * translation;
+        transform.rotation = (transform.rotation * rotation).normalize();
+    }
+
+    /// 根骨骼从from到to(同一循环内)的运动
+    fn root_motion_segment(&self, clip: &AnimationClip, from: f32, to: f32) -> (Vec3, Quat) {
+        let (start_position, start_rotation) = self.root_pose(clip, from);
+        let (end_position, end_rotation) = self.root_pose(clip, to);
+        let inverse = start_rotation.inverse();
+        (inverse * (end_position - start_position), (inverse * end_rotation).normalize())
+    }
+
+    /// 根骨骼在time时的位置和旋转，缺少对应轨道时为原点和单位旋转
+    fn root_pose(&self, clip: &AnimationClip, time: f32) -> (Vec3, Quat) {
+        let mut position = Vec3::ZERO;
+        let mut rotation = Quat::IDENTITY;
+        for track in clip.tracks.iter().filter(|track| track.target == self.root_motion_target) {
+            match (&track.property, track.sample(time)) {
+                (AnimationProperty::Position, Some(KeyframeValue::Vec3(value))) => position = value,
+                (AnimationProperty::Rotation, Some(KeyframeValue::Quaternion(value))) => rotation = value,
+                _ => {}
+            }
+        }
+        (position, rotation)
     }
 
     /// 获取当前播放进度 (0.0 - 1.0)
@@ -273,3 +390,113 @@ impl Default for AnimationBlender {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::{AnimationTrack, Keyframe};
+
+    /// 1秒内根骨骼沿+X移动1单位的循环剪辑
+    fn walking_animator() -> Animator {
+        let mut track = AnimationTrack::new("root", AnimationProperty::Position);
+        track.add_keyframe(Keyframe::new(0.0, KeyframeValue::Vec3(Vec3::ZERO)));
+        track.add_keyframe(Keyframe::new(1.0, KeyframeValue::Vec3(Vec3::X)));
+        let mut clip = AnimationClip::new("walk", 1.0);
+        clip.add_track(track);
+
+        let mut animator = Animator::new().with_root_motion("root");
+        animator.add_clip(clip);
+        animator.play("walk").unwrap();
+        animator
+    }
+
+    #[test]
+    fn root_motion_within_clip() {
+        let mut animator = walking_animator();
+        animator.time = 0.25;
+        let (translation, _) = animator.root_motion_delta(0.5);
+        assert!((translation - Vec3::new(0.5, 0.0, 0.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn root_motion_accumulates_across_loops() {
+        let mut animator = walking_animator();
+        animator.time = 0.5;
+        let (translation, _) = animator.root_motion_delta(3.25);
+        assert!((translation - Vec3::new(3.25, 0.0, 0.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn root_motion_stops_at_end_without_looping() {
+        let mut animator = walking_animator();
+        animator.set_looping(false);
+        animator.time = 0.5;
+        let (translation, _) = animator.root_motion_delta(3.0);
+        assert!((translation - Vec3::new(0.5, 0.0, 0.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn root_motion_terminates_for_extreme_deltas() {
+        let mut animator = walking_animator();
+        animator.time = 1.0 - f32::EPSILON;
+        let (translation, _) = animator.root_motion_delta(1.0e-9);
+        assert!(translation.is_finite());
+
+        let (translation, _) = animator.root_motion_delta(1.0e7);
+        assert!(translation.is_finite());
+        assert!(translation.x > 0.0);
+    }
+
+    #[test]
+    fn summed_frame_deltas_equal_clip_displacement() {
+        let mut animator = walking_animator();
+        animator.set_looping(false);
+        let mut transform = Transform::default();
+        for _ in 0..60 {
+            let delta = animator.root_motion_delta(1.0 / 60.0);
+            Animator::apply_root_motion(&mut transform, delta);
+            let pose = animator.update(1.0 / 60.0);
+            // 根骨骼位移由实体承担，不再出现在姿态中
+            assert!(pose.map_or(true, |pose| !pose.contains_key("root")));
+        }
+        assert!((transform.position - Vec3::X).length() < 1e-4);
+    }
+
+    #[test]
+    fn root_motion_is_relative_to_root_rotation() {
+        let mut position = AnimationTrack::new("root", AnimationProperty::Position);
+        position.add_keyframe(Keyframe::new(0.0, KeyframeValue::Vec3(Vec3::ZERO)));
+        position.add_keyframe(Keyframe::new(1.0, KeyframeValue::Vec3(Vec3::Z)));
+        let mut rotation = AnimationTrack::new("root", AnimationProperty::Rotation);
+        let quarter_turn = Quat::from_rotation_y(std::f32::consts::FRAC_PI_2);
+        rotation.add_keyframe(Keyframe::new(0.0, KeyframeValue::Quaternion(quarter_turn)));
+        rotation.add_keyframe(Keyframe::new(1.0, KeyframeValue::Quaternion(quarter_turn)));
+        let mut clip = AnimationClip::new("strafe", 1.0);
+        clip.add_track(position);
+        clip.add_track(rotation);
+
+        let mut animator = Animator::new().with_root_motion("root");
+        animator.add_clip(clip);
+        animator.play("strafe").unwrap();
+
+        // 根骨骼朝向+X时沿世界Z移动，相对自身是向右
+        let (translation, turn) = animator.root_motion_delta(1.0);
+        assert!((translation - quarter_turn.inverse() * Vec3::Z).length() < 1e-4);
+        assert!(turn.angle_between(Quat::IDENTITY) < 1e-3);
+    }
+
+    #[test]
+    fn disabled_or_paused_root_motion_is_identity() {
+        let mut animator = walking_animator();
+        animator.set_root_motion_enabled(false);
+        assert_eq!(animator.root_motion_delta(0.5), (Vec3::ZERO, Quat::IDENTITY));
+        // 关闭后根骨骼姿态照常输出
+        assert!(animator.update(0.5).unwrap().contains_key("root"));
+
+        animator.set_root_motion_enabled(true);
+        animator.pause();
+        assert_eq!(animator.root_motion_delta(0.5), (Vec3::ZERO, Quat::IDENTITY));
+        animator.resume();
+        assert_eq!(animator.root_motion_delta(0.0), (Vec3::ZERO, Quat::IDENTITY));
+    }
+}
@@ -173,6 +173,12 @@ impl Skeleton {
             .collect()
     }
 
+    /// 由姿势计算蒙皮矩阵调色板，可直接上传给 `JointPalette`
+    pub fn compute_pose(&self, pose: &SkeletalPose) -> Vec<Mat4> {
+        let global_transforms = self.compute_global_transforms(&pose.bone_transforms);
+        self.compute_skinning_matrices(&global_transforms)
+    }
+
     /// 设置骨骼的绑定姿势
     pub fn set_bind_pose(&mut self, bone_index: usize, transform: Transform) {
         if let Some(bone) = self.bones.get_mut(bone_index) {
@@ -385,8 +391,7 @@ impl SkeletalAnimationSystem {
 
     /// 获取当前的蒙皮矩阵
     pub fn get_skinning_matrices(&self) -> Vec<Mat4> {
-        let global_transforms = self.skeleton.compute_global_transforms(&self.current_pose.bone_transforms);
-        self.skeleton.compute_skinning_matrices(&global_transforms)
+        self.skeleton.compute_pose(&self.current_pose)
     }
 
     /// 设置姿势
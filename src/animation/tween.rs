@@ -17,6 +17,9 @@ pub struct Tween<T> {
     pub is_looping: bool,
     pub ping_pong: bool,
     pub reverse: bool,
+    /// 使用真实时间播放，游戏暂停或慢动作时照常运行(UI动画)
+    #[serde(default)]
+    pub use_unscaled_time: bool,
 }
 
 impl<T> Tween<T>
@@ -36,6 +39,7 @@ where
             is_looping: false,
             ping_pong: false,
             reverse: false,
+            use_unscaled_time: false,
         }
     }
 
@@ -57,6 +61,18 @@ where
         self
     }
 
+    /// 设置使用真实时间播放
+    pub fn with_unscaled_time(mut self, use_unscaled_time: bool) -> Self {
+        self.use_unscaled_time = use_unscaled_time;
+        self
+    }
+
+    /// 按缩放后的游戏帧时间和真实帧时间更新，使用哪个由 `use_unscaled_time` 决定
+    pub fn update_with_time(&mut self, delta_time: f32, unscaled_delta_time: f32) -> T {
+        let dt = if self.use_unscaled_time { unscaled_delta_time } else { delta_time };
+        self.update(dt)
+    }
+
     /// 开始播放
     pub fn play(&mut self) {
         self.is_playing = true;
@@ -359,29 +375,34 @@ impl TweenManager {
 
     /// 更新所有补间
     pub fn update(&mut self, delta_time: f32) {
+        self.update_with_time(delta_time, delta_time);
+    }
+
+    /// 世界补间按缩放后的 `delta_time` 推进(暂停时冻结)，`use_unscaled_time` 的补间按真实时间推进
+    pub fn update_with_time(&mut self, delta_time: f32, unscaled_delta_time: f32) {
         // 更新并移除已完成的补间
         self.float_tweens.retain_mut(|(_, tween)| {
-            tween.update(delta_time);
+            tween.update_with_time(delta_time, unscaled_delta_time);
             !tween.is_finished()
         });
 
         self.vec2_tweens.retain_mut(|(_, tween)| {
-            tween.update(delta_time);
+            tween.update_with_time(delta_time, unscaled_delta_time);
             !tween.is_finished()
         });
 
         self.vec3_tweens.retain_mut(|(_, tween)| {
-            tween.update(delta_time);
+            tween.update_with_time(delta_time, unscaled_delta_time);
             !tween.is_finished()
         });
 
         self.vec4_tweens.retain_mut(|(_, tween)| {
-            tween.update(delta_time);
+            tween.update_with_time(delta_time, unscaled_delta_time);
             !tween.is_finished()
         });
 
         self.color_tweens.retain_mut(|(_, tween)| {
-            tween.update(delta_time);
+            tween.update_with_time(delta_time, unscaled_delta_time);
             !tween.is_finished()
         });
     }
@@ -435,3 +456,48 @@ impl TweenManager {
         self.color_tweens.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unscaled_tween_keeps_running_while_paused() {
+        let mut manager = TweenManager::new();
+        let mut world = Tween::new(0.0, 1.0, 1.0);
+        world.play();
+        let mut ui = Tween::new(0.0, 1.0, 1.0).with_unscaled_time(true);
+        ui.play();
+        manager.add_float_tween("world", world);
+        manager.add_float_tween("ui", ui);
+
+        // 暂停时游戏帧时间为0，真实帧时间照常
+        for _ in 0..4 {
+            manager.update_with_time(0.0, 0.125);
+        }
+        assert_eq!(manager.get_float("world"), Some(0.0));
+        assert_eq!(manager.get_float("ui"), Some(0.5));
+
+        manager.update_with_time(0.25, 0.125);
+        assert_eq!(manager.get_float("world"), Some(0.25));
+        assert_eq!(manager.get_float("ui"), Some(0.625));
+    }
+
+    #[test]
+    fn tween_update_with_time_picks_delta() {
+        let mut tween = Tween::new(0.0f32, 10.0, 2.0);
+        tween.play();
+        assert_eq!(tween.update_with_time(1.0, 0.5), 5.0);
+
+        tween.use_unscaled_time = true;
+        assert_eq!(tween.update_with_time(1.0, 0.5), 7.5);
+    }
+
+    #[test]
+    fn unscaled_flag_defaults_to_off_when_missing() {
+        let mut json = serde_json::to_value(Tween::new(0.0f32, 1.0, 1.0).with_unscaled_time(true)).unwrap();
+        json.as_object_mut().unwrap().remove("use_unscaled_time");
+        let tween: Tween<f32> = serde_json::from_value(json).unwrap();
+        assert!(!tween.use_unscaled_time);
+    }
+}
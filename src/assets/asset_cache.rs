@@ -1,9 +1,8 @@
 //! 资源缓存系统
 
-use crate::assets::{AssetHandle, AssetId, UntypedAssetHandle};
+use crate::assets::{AssetHandle, AssetId, AssetSlot, AssetState, ErasedAssetSlot};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
-use std::any::Any;
 
 /// 缓存策略
 #[derive(Debug, Clone, Copy)]
@@ -16,10 +15,9 @@ pub enum CacheStrategy {
     RefCount,
 }
 
-/// 缓存条目
-#[derive(Debug)]
+/// 缓存条目，持有资源槽位(与句柄共享)
 struct CacheEntry {
-    resource: Arc<dyn Any + Send + Sync>,
+    slot: Arc<dyn ErasedAssetSlot>,
     access_count: u64,
     last_access: std::time::Instant,
     strategy: CacheStrategy,
@@ -30,13 +28,13 @@ struct CacheEntry {
 
 impl CacheEntry {
     fn new<T: Send + Sync + 'static>(
-        resource: Arc<T>, 
+        slot: Arc<AssetSlot<T>>, 
         path: String, 
         strategy: CacheStrategy,
         size_bytes: usize
     ) -> Self {
         Self {
-            resource: resource as Arc<dyn Any + Send + Sync>,
+            slot: slot as Arc<dyn ErasedAssetSlot>,
             access_count: 0,
             last_access: std::time::Instant::now(),
             strategy,
@@ -51,9 +49,13 @@ impl CacheEntry {
         self.last_access = std::time::Instant::now();
     }
 
+    fn typed_slot<T: Send + Sync + 'static>(&self) -> Option<Arc<AssetSlot<T>>> {
+        Arc::clone(&self.slot).into_any().downcast().ok()
+    }
+
     fn get<T: Send + Sync + 'static>(&mut self) -> Option<Arc<T>> {
         self.access();
-        self.resource.clone().downcast().ok()
+        self.typed_slot::<T>().and_then(|slot| slot.resource())
     }
 
     fn should_cleanup(&self) -> bool {
@@ -64,8 +66,8 @@ impl CacheEntry {
                 self.last_access.elapsed().as_secs() > 300
             },
             CacheStrategy::RefCount => {
-                // 如果只有缓存持有引用，则可以清理
-                Arc::strong_count(&self.resource) <= 1
+                // 如果只有缓存持有引用(没有强句柄)，则可以清理
+                Arc::strong_count(&self.slot) <= 1
             },
         }
     }
@@ -106,10 +108,21 @@ impl AssetCache {
         strategy: CacheStrategy,
         size_bytes: usize
     ) -> AssetHandle<T> {
-        let path = path.into();
-        let handle = AssetHandle::new(id, &resource, &path);
-        
-        let entry = CacheEntry::new(resource, path.clone(), strategy, size_bytes);
+        let handle = AssetHandle::new(id, &resource, path);
+        self.insert_handle(&handle, strategy, size_bytes);
+        handle
+    }
+
+    /// 将句柄的槽位放入缓存，句柄可以处于加载中状态
+    pub fn insert_handle<T: Send + Sync + 'static>(
+        &self,
+        handle: &AssetHandle<T>,
+        strategy: CacheStrategy,
+        size_bytes: usize
+    ) {
+        let path = handle.path().to_string();
+        let id = handle.id();
+        let entry = CacheEntry::new(Arc::clone(handle.slot()), path.clone(), strategy, size_bytes);
         
         {
             let mut entries = self.entries.write().unwrap();
@@ -128,8 +141,6 @@ impl AssetCache {
         
         // 检查是否需要清理
         self.maybe_cleanup();
-        
-        handle
     }
 
     /// 通过ID获取资源
@@ -153,6 +164,27 @@ impl AssetCache {
         }
     }
 
+    /// 通过ID获取强句柄，与缓存共享加载状态
+    pub fn handle<T: Send + Sync + 'static>(&self, id: AssetId) -> Option<AssetHandle<T>> {
+        let mut entries = self.entries.write().unwrap();
+        let entry = entries.get_mut(&id)?;
+        entry.access();
+        let slot = entry.typed_slot::<T>()?;
+        Some(AssetHandle::from_slot(id, slot, entry.path.clone()))
+    }
+
+    /// 通过路径获取强句柄
+    pub fn handle_by_path<T: Send + Sync + 'static>(&self, path: &str) -> Option<AssetHandle<T>> {
+        let id = self.id_of_path(path)?;
+        self.handle(id)
+    }
+
+    /// 获取资源的加载状态
+    pub fn state(&self, id: AssetId) -> Option<AssetState> {
+        let entries = self.entries.read().unwrap();
+        entries.get(&id).map(|entry| entry.slot.state())
+    }
+
     /// 检查资源是否在缓存中
     pub fn contains(&self, id: AssetId) -> bool {
         let entries = self.entries.read().unwrap();
@@ -165,6 +197,12 @@ impl AssetCache {
         path_to_id.contains_key(path)
     }
 
+    /// 通过路径获取资源ID
+    pub fn id_of_path(&self, path: &str) -> Option<AssetId> {
+        let path_to_id = self.path_to_id.read().unwrap();
+        path_to_id.get(path).copied()
+    }
+
     /// 移除资源
     pub fn remove(&self, id: AssetId) -> bool {
         let mut entries = self.entries.write().unwrap();
@@ -266,7 +304,8 @@ impl AssetCache {
         
         entries.values()
             .filter(|entry| entry.type_name == target_type)
-            .filter_map(|entry| entry.resource.clone().downcast().ok())
+            .filter_map(|entry| entry.typed_slot::<T>())
+            .filter_map(|slot| slot.resource())
             .collect()
     }
 
@@ -319,3 +358,52 @@ impl Default for AssetCache {
         Self::new(512 * 1024 * 1024)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weak_handle_fails_after_eviction() {
+        let cache = AssetCache::new(1024);
+        let handle = cache.insert(1, Arc::new(vec![0u8; 16]), "mesh.bin", CacheStrategy::RefCount, 16);
+        let weak = handle.downgrade();
+
+        // 强句柄存活时不会被清理
+        assert_eq!(cache.cleanup(), 0);
+        assert!(weak.upgrade().is_some());
+
+        drop(handle);
+        assert!(weak.is_alive());
+        assert_eq!(cache.cleanup(), 1);
+        assert!(!cache.contains(1));
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn cached_handle_shares_loading_state() {
+        let cache = AssetCache::new(1024);
+        let loading: AssetHandle<String> = AssetHandle::loading(7, "level.txt");
+        cache.insert_handle(&loading, CacheStrategy::Permanent, 8);
+        assert_eq!(cache.state(7), Some(AssetState::Loading));
+        assert!(cache.get::<String>(7).is_none());
+
+        loading.slot().set_loaded(Arc::new(String::from("关卡")));
+        let handle = cache.handle_by_path::<String>("level.txt").unwrap();
+        assert_eq!(handle.id(), 7);
+        assert_eq!(cache.state(7), Some(AssetState::Loaded));
+        assert_eq!(handle.get().unwrap().as_str(), "关卡");
+        assert!(cache.handle::<u32>(7).is_none());
+    }
+
+    #[test]
+    fn permanent_entries_survive_cleanup() {
+        let cache = AssetCache::new(1024);
+        let handle = cache.insert(2, Arc::new(1u64), "config.bin", CacheStrategy::Permanent, 8);
+        let weak = handle.downgrade();
+        drop(handle);
+
+        assert_eq!(cache.cleanup(), 0);
+        assert!(weak.upgrade().is_some());
+    }
+}
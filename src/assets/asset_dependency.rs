@@ -0,0 +1,183 @@
+//! 资源依赖图
+//!
+//! 记录资源之间的引用关系(材质引用纹理、场景引用材质和网格等)，
+//! 用于在卸载资源时判断是否仍有其他已加载资源依赖它。
+
+use crate::assets::AssetId;
+use std::collections::{HashMap, HashSet};
+
+/// 资源依赖图
+#[derive(Debug, Default, Clone)]
+pub struct AssetDependencyGraph {
+    /// 资源 -> 它依赖的资源
+    dependencies: HashMap<AssetId, HashSet<AssetId>>,
+    /// 资源 -> 依赖它的资源
+    dependents: HashMap<AssetId, HashSet<AssetId>>,
+}
+
+impl AssetDependencyGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录 `dependent` 依赖 `dependency`，会形成环时返回false
+    pub fn add_dependency(&mut self, dependent: AssetId, dependency: AssetId) -> bool {
+        if dependent == dependency || self.depends_on(dependency, dependent) {
+            return false;
+        }
+
+        self.dependencies.entry(dependent).or_default().insert(dependency);
+        self.dependents.entry(dependency).or_default().insert(dependent);
+        true
+    }
+
+    /// 移除一条依赖关系
+    pub fn remove_dependency(&mut self, dependent: AssetId, dependency: AssetId) -> bool {
+        let removed = self.dependencies
+            .get_mut(&dependent)
+            .map_or(false, |deps| deps.remove(&dependency));
+
+        if let Some(users) = self.dependents.get_mut(&dependency) {
+            users.remove(&dependent);
+        }
+        self.prune(dependent);
+        self.prune(dependency);
+        removed
+    }
+
+    /// 从图中移除资源及其所有边
+    pub fn remove_asset(&mut self, id: AssetId) {
+        if let Some(deps) = self.dependencies.remove(&id) {
+            for dependency in deps {
+                if let Some(users) = self.dependents.get_mut(&dependency) {
+                    users.remove(&id);
+                }
+                self.prune(dependency);
+            }
+        }
+        if let Some(users) = self.dependents.remove(&id) {
+            for dependent in users {
+                if let Some(deps) = self.dependencies.get_mut(&dependent) {
+                    deps.remove(&id);
+                }
+                self.prune(dependent);
+            }
+        }
+    }
+
+    /// 获取资源直接依赖的资源
+    pub fn dependencies_of(&self, id: AssetId) -> Vec<AssetId> {
+        self.dependencies.get(&id)
+            .map(|deps| deps.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// 获取直接依赖该资源的资源
+    pub fn dependents_of(&self, id: AssetId) -> Vec<AssetId> {
+        self.dependents.get(&id)
+            .map(|users| users.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// 是否有资源依赖该资源
+    pub fn has_dependents(&self, id: AssetId) -> bool {
+        self.dependents.get(&id).map_or(false, |users| !users.is_empty())
+    }
+
+    /// `id` 是否(直接或间接)依赖 `target`
+    pub fn depends_on(&self, id: AssetId, target: AssetId) -> bool {
+        let mut stack = vec![id];
+        let mut visited = HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            if let Some(deps) = self.dependencies.get(&current) {
+                if deps.contains(&target) {
+                    return true;
+                }
+                stack.extend(deps.iter().copied());
+            }
+        }
+        false
+    }
+
+    /// 获取所有(直接或间接)依赖该资源的资源，按卸载顺序排列(最外层的依赖者在前)
+    pub fn transitive_dependents(&self, id: AssetId) -> Vec<AssetId> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        // 后序遍历保证依赖者排在被依赖者之前，最后一项是资源本身
+        self.visit_dependents(id, &mut visited, &mut order);
+        order.pop();
+        order
+    }
+
+    /// 清空依赖图
+    pub fn clear(&mut self) {
+        self.dependencies.clear();
+        self.dependents.clear();
+    }
+
+    fn visit_dependents(&self, id: AssetId, visited: &mut HashSet<AssetId>, order: &mut Vec<AssetId>) {
+        if !visited.insert(id) {
+            return;
+        }
+        if let Some(users) = self.dependents.get(&id) {
+            for &user in users {
+                self.visit_dependents(user, visited, order);
+            }
+        }
+        order.push(id);
+    }
+
+    fn prune(&mut self, id: AssetId) {
+        if self.dependencies.get(&id).map_or(false, |deps| deps.is_empty()) {
+            self.dependencies.remove(&id);
+        }
+        if self.dependents.get(&id).map_or(false, |users| users.is_empty()) {
+            self.dependents.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXTURE: AssetId = 1;
+    const MATERIAL: AssetId = 2;
+    const PREFAB: AssetId = 3;
+
+    fn chain() -> AssetDependencyGraph {
+        let mut graph = AssetDependencyGraph::new();
+        assert!(graph.add_dependency(MATERIAL, TEXTURE));
+        assert!(graph.add_dependency(PREFAB, MATERIAL));
+        graph
+    }
+
+    #[test]
+    fn rejects_self_and_cyclic_dependencies() {
+        let mut graph = chain();
+        assert!(!graph.add_dependency(TEXTURE, TEXTURE));
+        assert!(!graph.add_dependency(TEXTURE, PREFAB));
+        assert!(graph.depends_on(PREFAB, TEXTURE));
+        assert!(!graph.depends_on(TEXTURE, PREFAB));
+    }
+
+    #[test]
+    fn transitive_dependents_list_outermost_first() {
+        let graph = chain();
+        assert_eq!(graph.transitive_dependents(TEXTURE), vec![PREFAB, MATERIAL]);
+        assert!(graph.transitive_dependents(PREFAB).is_empty());
+    }
+
+    #[test]
+    fn removing_asset_drops_its_edges() {
+        let mut graph = chain();
+        graph.remove_asset(MATERIAL);
+        assert!(!graph.has_dependents(TEXTURE));
+        assert!(graph.dependencies_of(PREFAB).is_empty());
+        assert!(!graph.remove_dependency(MATERIAL, TEXTURE));
+    }
+}
@@ -1,30 +1,142 @@
 //! 资源句柄系统
+//!
+//! `AssetHandle` 是强引用，持有期间资源不会被引用计数策略清理；`WeakAssetHandle` 只观察资源，
+//! 可在资源仍存活时升级为强句柄。同一资源的所有句柄共享一个槽位，加载状态在句柄间同步。
+//! 重新加载时新资源写入同一槽位并递增代数，持有句柄的一方无需重新获取，
+//! 由代数判断基于旧资源创建的GPU对象是否需要重建。
 
-use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, Weak};
 use std::any::Any;
 use std::fmt;
 
 /// 资源ID类型
 pub type AssetId = u64;
 
-/// 资源句柄 - 用于安全地引用资源
-#[derive(Clone)]
+/// 资源加载状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetState {
+    /// 正在加载
+    Loading,
+    /// 已加载，可通过句柄获取
+    Loaded,
+    /// 加载失败及原因
+    Failed(String),
+}
+
+impl AssetState {
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, AssetState::Loaded)
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, AssetState::Failed(_))
+    }
+}
+
+/// 句柄共享的资源槽位
+pub(crate) struct AssetSlot<T> {
+    state: RwLock<AssetState>,
+    resource: RwLock<Option<Arc<T>>>,
+    /// 每写入一次资源加1，加载中为0
+    generation: AtomicU64,
+}
+
+impl<T> AssetSlot<T> {
+    pub(crate) fn loading() -> Self {
+        Self {
+            state: RwLock::new(AssetState::Loading),
+            resource: RwLock::new(None),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn loaded(resource: Arc<T>) -> Self {
+        Self {
+            state: RwLock::new(AssetState::Loaded),
+            resource: RwLock::new(Some(resource)),
+            generation: AtomicU64::new(1),
+        }
+    }
+
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn state(&self) -> AssetState {
+        self.state.read().unwrap().clone()
+    }
+
+    pub(crate) fn resource(&self) -> Option<Arc<T>> {
+        self.resource.read().unwrap().clone()
+    }
+
+    /// 加载完成，替换槽位中的资源并递增代数
+    pub(crate) fn set_loaded(&self, resource: Arc<T>) {
+        *self.resource.write().unwrap() = Some(resource);
+        *self.state.write().unwrap() = AssetState::Loaded;
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// 加载失败，保留之前已加载的资源(例如重新加载失败时)
+    pub(crate) fn set_failed(&self, error: impl Into<String>) {
+        *self.state.write().unwrap() = AssetState::Failed(error.into());
+    }
+}
+
+/// 类型擦除的槽位，供无类型句柄和缓存查询状态
+pub(crate) trait ErasedAssetSlot: Send + Sync {
+    fn state(&self) -> AssetState;
+
+    fn generation(&self) -> u64;
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<T: Send + Sync + 'static> ErasedAssetSlot for AssetSlot<T> {
+    fn state(&self) -> AssetState {
+        AssetSlot::state(self)
+    }
+
+    fn generation(&self) -> u64 {
+        AssetSlot::generation(self)
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+/// 资源句柄 - 强引用，持有期间资源保持存活
 pub struct AssetHandle<T> {
     id: AssetId,
-    inner: Weak<T>,
+    slot: Arc<AssetSlot<T>>,
     path: String,
 }
 
 impl<T> AssetHandle<T> {
-    /// 创建新的资源句柄
+    /// 创建已加载资源的句柄
     pub fn new(id: AssetId, resource: &Arc<T>, path: impl Into<String>) -> Self {
+        Self::from_slot(id, Arc::new(AssetSlot::loaded(Arc::clone(resource))), path)
+    }
+
+    /// 创建加载中的句柄，资源就绪后由加载方填充
+    pub fn loading(id: AssetId, path: impl Into<String>) -> Self {
+        Self::from_slot(id, Arc::new(AssetSlot::loading()), path)
+    }
+
+    pub(crate) fn from_slot(id: AssetId, slot: Arc<AssetSlot<T>>, path: impl Into<String>) -> Self {
         Self {
             id,
-            inner: Arc::downgrade(resource),
+            slot,
             path: path.into(),
         }
     }
 
+    pub(crate) fn slot(&self) -> &Arc<AssetSlot<T>> {
+        &self.slot
+    }
+
     /// 获取资源ID
     pub fn id(&self) -> AssetId {
         self.id
@@ -35,24 +147,53 @@ impl<T> AssetHandle<T> {
         &self.path
     }
 
-    /// 尝试获取资源
+    /// 获取加载状态
+    pub fn state(&self) -> AssetState {
+        self.slot.state()
+    }
+
+    /// 尝试获取资源，加载中或加载失败时返回None
     pub fn get(&self) -> Option<Arc<T>> {
-        self.inner.upgrade()
+        self.slot.resource()
     }
 
-    /// 检查资源是否仍然有效
+    /// 资源代数，每次(重新)加载完成时加1，加载中为0
+    pub fn generation(&self) -> u64 {
+        self.slot.generation()
+    }
+
+    /// 检查资源是否已加载可用
     pub fn is_valid(&self) -> bool {
-        self.inner.strong_count() > 0
+        self.state().is_loaded()
+    }
+
+    /// 创建不持有资源的弱句柄
+    pub fn downgrade(&self) -> WeakAssetHandle<T> {
+        WeakAssetHandle {
+            id: self.id,
+            slot: Arc::downgrade(&self.slot),
+            path: self.path.clone(),
+        }
     }
 
     /// 获取弱引用计数
     pub fn weak_count(&self) -> usize {
-        self.inner.weak_count()
+        Arc::weak_count(&self.slot)
     }
 
-    /// 获取强引用计数
+    /// 获取强引用计数(包括缓存持有的一份)
     pub fn strong_count(&self) -> usize {
-        self.inner.strong_count()
+        Arc::strong_count(&self.slot)
+    }
+}
+
+impl<T> Clone for AssetHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            slot: Arc::clone(&self.slot),
+            path: self.path.clone(),
+        }
     }
 }
 
@@ -61,7 +202,7 @@ impl<T> fmt::Debug for AssetHandle<T> {
         f.debug_struct("AssetHandle")
             .field("id", &self.id)
             .field("path", &self.path)
-            .field("valid", &self.is_valid())
+            .field("state", &self.state())
             .finish()
     }
 }
@@ -80,11 +221,81 @@ impl<T> std::hash::Hash for AssetHandle<T> {
     }
 }
 
+/// 弱资源句柄 - 不阻止资源被清理，适合缓存索引和编辑器界面
+pub struct WeakAssetHandle<T> {
+    id: AssetId,
+    slot: Weak<AssetSlot<T>>,
+    path: String,
+}
+
+impl<T> WeakAssetHandle<T> {
+    /// 获取资源ID
+    pub fn id(&self) -> AssetId {
+        self.id
+    }
+
+    /// 获取资源路径
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// 升级为强句柄，资源已被释放时返回None
+    pub fn upgrade(&self) -> Option<AssetHandle<T>> {
+        self.slot
+            .upgrade()
+            .map(|slot| AssetHandle::from_slot(self.id, slot, self.path.clone()))
+    }
+
+    /// 获取加载状态，资源已被释放时返回None
+    pub fn state(&self) -> Option<AssetState> {
+        self.slot.upgrade().map(|slot| slot.state())
+    }
+
+    /// 资源是否仍然存活
+    pub fn is_alive(&self) -> bool {
+        self.slot.strong_count() > 0
+    }
+}
+
+impl<T> Clone for WeakAssetHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            slot: Weak::clone(&self.slot),
+            path: self.path.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for WeakAssetHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakAssetHandle")
+            .field("id", &self.id)
+            .field("path", &self.path)
+            .field("alive", &self.is_alive())
+            .finish()
+    }
+}
+
+impl<T> PartialEq for WeakAssetHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for WeakAssetHandle<T> {}
+
+impl<T> std::hash::Hash for WeakAssetHandle<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
 /// 无类型资源句柄
 #[derive(Clone)]
 pub struct UntypedAssetHandle {
     id: AssetId,
-    inner: Weak<dyn Any + Send + Sync>,
+    slot: Arc<dyn ErasedAssetSlot>,
     path: String,
     type_name: &'static str,
 }
@@ -92,10 +303,15 @@ pub struct UntypedAssetHandle {
 impl UntypedAssetHandle {
     /// 创建新的无类型资源句柄
     pub fn new<T: Send + Sync + 'static>(id: AssetId, resource: &Arc<T>, path: impl Into<String>) -> Self {
+        Self::from_handle(AssetHandle::new(id, resource, path))
+    }
+
+    /// 擦除类型化句柄的类型，与原句柄共享资源
+    pub fn from_handle<T: Send + Sync + 'static>(handle: AssetHandle<T>) -> Self {
         Self {
-            id,
-            inner: Arc::downgrade(resource) as Weak<dyn Any + Send + Sync>,
-            path: path.into(),
+            id: handle.id,
+            slot: handle.slot as Arc<dyn ErasedAssetSlot>,
+            path: handle.path,
             type_name: std::any::type_name::<T>(),
         }
     }
@@ -115,21 +331,37 @@ impl UntypedAssetHandle {
         self.type_name
     }
 
+    /// 获取加载状态
+    pub fn state(&self) -> AssetState {
+        self.slot.state()
+    }
+
+    /// 资源代数，每次(重新)加载完成时加1
+    pub fn generation(&self) -> u64 {
+        self.slot.generation()
+    }
+
     /// 尝试转换为类型化句柄
     pub fn typed<T: Send + Sync + 'static>(&self) -> Option<AssetHandle<T>> {
-        if std::any::type_name::<T>() == self.type_name {
-            if let Some(arc) = self.inner.upgrade() {
-                if let Ok(typed_arc) = arc.downcast::<T>() {
-                    return Some(AssetHandle::new(self.id, &typed_arc, &self.path));
-                }
-            }
+        if std::any::type_name::<T>() != self.type_name {
+            return None;
         }
-        None
+        Arc::clone(&self.slot)
+            .into_any()
+            .downcast::<AssetSlot<T>>()
+            .ok()
+            .map(|slot| AssetHandle::from_slot(self.id, slot, self.path.clone()))
     }
 
-    /// 检查资源是否仍然有效
+    /// 检查资源是否已加载可用
     pub fn is_valid(&self) -> bool {
-        self.inner.strong_count() > 0
+        self.state().is_loaded()
+    }
+}
+
+impl<T: Send + Sync + 'static> From<AssetHandle<T>> for UntypedAssetHandle {
+    fn from(handle: AssetHandle<T>) -> Self {
+        Self::from_handle(handle)
     }
 }
 
@@ -139,7 +371,7 @@ impl fmt::Debug for UntypedAssetHandle {
             .field("id", &self.id)
             .field("path", &self.path)
             .field("type_name", &self.type_name)
-            .field("valid", &self.is_valid())
+            .field("state", &self.state())
             .finish()
     }
 }
@@ -180,3 +412,64 @@ impl Default for AssetHandleManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weak_handle_upgrades_while_strong_alive() {
+        let handle = AssetHandle::new(1, &Arc::new(String::from("石头")), "stone.txt");
+        let weak = handle.downgrade();
+        assert_eq!(handle.weak_count(), 1);
+
+        let upgraded = weak.upgrade().unwrap();
+        assert_eq!(upgraded, handle);
+        assert_eq!(upgraded.get().unwrap().as_str(), "石头");
+        assert_eq!(weak.state(), Some(AssetState::Loaded));
+
+        drop(handle);
+        drop(upgraded);
+        assert!(!weak.is_alive());
+        assert!(weak.upgrade().is_none());
+        assert_eq!(weak.state(), None);
+    }
+
+    #[test]
+    fn loading_handle_shares_state_with_clones() {
+        let handle: AssetHandle<String> = AssetHandle::loading(2, "pending.txt");
+        let clone = handle.clone();
+        assert_eq!(clone.state(), AssetState::Loading);
+        assert!(clone.get().is_none());
+        assert_eq!(clone.generation(), 0);
+
+        handle.slot().set_loaded(Arc::new(String::from("完成")));
+        assert!(clone.is_valid());
+        assert_eq!(clone.get().unwrap().as_str(), "完成");
+        assert_eq!(clone.generation(), 1);
+    }
+
+    #[test]
+    fn failed_reload_keeps_previous_resource() {
+        let handle = AssetHandle::new(3, &Arc::new(1u32), "value.bin");
+        handle.slot().set_failed("文件损坏");
+
+        assert_eq!(handle.state(), AssetState::Failed("文件损坏".to_string()));
+        assert!(handle.state().is_failed());
+        assert!(!handle.is_valid());
+        assert_eq!(handle.get().as_deref(), Some(&1));
+    }
+
+    #[test]
+    fn untyped_handle_round_trips_to_typed() {
+        let handle = AssetHandle::new(4, &Arc::new(2.5f32), "scale.bin");
+        let untyped = UntypedAssetHandle::from(handle.clone());
+        assert_eq!(untyped.state(), AssetState::Loaded);
+        assert!(untyped.typed::<u32>().is_none());
+
+        let typed = untyped.typed::<f32>().unwrap();
+        handle.slot().set_loaded(Arc::new(3.0));
+        assert_eq!(typed.get().as_deref(), Some(&3.0));
+        assert_eq!(untyped.generation(), 2);
+    }
+}
@@ -0,0 +1,744 @@
+//! 资源导入管线
+//!
+//! 源文件(网格、纹理、音频)按导入设置处理后，以引擎原生的二进制格式写入导入缓存目录(默认 `.import`)。
+//! 产物文件名包含源文件内容哈希与相关设置的哈希，源文件和设置都未变化时直接命中缓存，不再重复处理。
+
+use crate::animation::{AnimationClip, AnimationProperty, KeyframeValue};
+use crate::assets::asset_loader::{AssetLoader, MeshLoader};
+use crate::render::Mesh;
+use crate::serialization::utils::calculate_checksum;
+use crate::{EngineError, EngineResult};
+use glam::Quat;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// 产物格式版本，格式变化时递增以使旧缓存失效
+pub const IMPORT_FORMAT_VERSION: u32 = 2;
+
+/// 产物文件头
+const ARTIFACT_MAGIC: &[u8; 4] = b"SJIA";
+
+/// 可导入的资源类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImportAssetKind {
+    Mesh,
+    Texture,
+    Audio,
+}
+
+impl ImportAssetKind {
+    /// 按扩展名判断资源类型
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "obj" | "fbx" | "gltf" | "glb" => Some(Self::Mesh),
+            "png" | "jpg" | "jpeg" | "bmp" | "tga" => Some(Self::Texture),
+            "wav" | "ogg" | "mp3" | "flac" => Some(Self::Audio),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension().and_then(|extension| extension.to_str()).and_then(Self::from_extension)
+    }
+
+    /// 产物扩展名
+    pub fn artifact_extension(&self) -> &'static str {
+        match self {
+            Self::Mesh => "sjmesh",
+            Self::Texture => "sjtex",
+            Self::Audio => "sjaudio",
+        }
+    }
+}
+
+/// 音频导入质量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AudioQuality {
+    Low,
+    Medium,
+    High,
+    Lossless,
+}
+
+impl AudioQuality {
+    /// 目标采样率，None表示保持原采样率
+    pub fn sample_rate(&self) -> Option<u32> {
+        match self {
+            Self::Low => Some(22_050),
+            Self::Medium => Some(32_000),
+            Self::High => Some(44_100),
+            Self::Lossless => None,
+        }
+    }
+}
+
+/// 源文件的上方向轴，引擎约定为Y轴向上(右手系，-Z为前方)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum UpAxis {
+    /// glTF等Y轴向上的格式，无需转换
+    #[default]
+    Y,
+    /// Blender、3ds Max等DCC工具常用的Z轴向上
+    Z,
+}
+
+impl UpAxis {
+    /// 从该坐标系转换到引擎坐标系的旋转
+    pub fn to_engine_rotation(&self) -> Quat {
+        match self {
+            UpAxis::Y => Quat::IDENTITY,
+            // 绕X轴-90°：+Z变为+Y，+Y变为-Z
+            UpAxis::Z => Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+        }
+    }
+
+    /// 把网格的顶点位置和法线转换到引擎坐标系
+    pub fn convert_mesh(&self, mesh: &mut Mesh) {
+        if *self == UpAxis::Y {
+            return;
+        }
+        let rotation = self.to_engine_rotation();
+        for vertex in mesh.vertices_mut().iter_mut() {
+            vertex.position = rotation * vertex.position;
+            vertex.normal = rotation * vertex.normal;
+        }
+    }
+
+    /// 把动画剪辑的位置、旋转和缩放轨道转换到引擎坐标系
+    pub fn convert_animation(&self, clip: &mut AnimationClip) {
+        if *self == UpAxis::Y {
+            return;
+        }
+        let rotation = self.to_engine_rotation();
+        let inverse = rotation.inverse();
+        for track in clip.tracks_mut() {
+            let property = track.property.clone();
+            for keyframe in track.keyframes.iter_mut() {
+                match (&property, &mut keyframe.value) {
+                    (AnimationProperty::Position, KeyframeValue::Vec3(value)) => {
+                        *value = rotation * *value;
+                        keyframe.tangent_in = keyframe.tangent_in.map(|tangent| rotation * tangent);
+                        keyframe.tangent_out = keyframe.tangent_out.map(|tangent| rotation * tangent);
+                    }
+                    // 旋转换基：先转回源坐标系，旋转后再转到引擎坐标系
+                    (AnimationProperty::Rotation, KeyframeValue::Quaternion(value)) => {
+                        *value = (rotation * *value * inverse).normalize();
+                    }
+                    // 轴对齐的换基只交换缩放分量
+                    (AnimationProperty::Scale, KeyframeValue::Vec3(value)) => {
+                        *value = (rotation * *value).abs();
+                        keyframe.tangent_in = keyframe.tangent_in.map(|tangent| (rotation * tangent).abs());
+                        keyframe.tangent_out = keyframe.tangent_out.map(|tangent| (rotation * tangent).abs());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// 导入设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetImportSettings {
+    // 网格
+    pub scale_factor: f32,
+    pub generate_normals: bool,
+    /// 焊接重合顶点
+    pub optimize_mesh: bool,
+    /// 平滑法线的折角(度)
+    pub smoothing_angle: f32,
+    /// 源文件的上方向轴，导入时转换到引擎的Y轴向上
+    #[serde(default)]
+    pub source_up_axis: UpAxis,
+
+    // 纹理
+    pub generate_mipmaps: bool,
+    /// 产物数据使用deflate压缩
+    pub compress_texture: bool,
+    /// 最长边上限，超出时等比缩小
+    pub max_texture_size: u32,
+
+    // 音频
+    /// 产物数据使用deflate压缩
+    pub compress_audio: bool,
+    pub audio_quality: AudioQuality,
+}
+
+impl Default for AssetImportSettings {
+    fn default() -> Self {
+        Self {
+            scale_factor: 1.0,
+            generate_normals: true,
+            optimize_mesh: true,
+            smoothing_angle: 60.0,
+            source_up_axis: UpAxis::Y,
+            generate_mipmaps: true,
+            compress_texture: true,
+            max_texture_size: 2048,
+            compress_audio: false,
+            audio_quality: AudioQuality::High,
+        }
+    }
+}
+
+impl AssetImportSettings {
+    /// 影响该类型产物的设置，用作缓存键的一部分
+    pub fn cache_key(&self, kind: ImportAssetKind) -> String {
+        match kind {
+            ImportAssetKind::Mesh => format!(
+                "scale={};normals={};optimize={};smoothing={};up={:?}",
+                self.scale_factor, self.generate_normals, self.optimize_mesh, self.smoothing_angle, self.source_up_axis
+            ),
+            ImportAssetKind::Texture => format!(
+                "mipmaps={};compress={};max={}",
+                self.generate_mipmaps, self.compress_texture, self.max_texture_size
+            ),
+            ImportAssetKind::Audio => format!("compress={};quality={:?}", self.compress_audio, self.audio_quality),
+        }
+    }
+
+    fn compresses(&self, kind: ImportAssetKind) -> bool {
+        match kind {
+            ImportAssetKind::Mesh => false,
+            ImportAssetKind::Texture => self.compress_texture,
+            ImportAssetKind::Audio => self.compress_audio,
+        }
+    }
+}
+
+/// 处理后的纹理：RGBA8，mips[0]为原始(缩放后)尺寸
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedTexture {
+    pub width: u32,
+    pub height: u32,
+    pub mips: Vec<Vec<u8>>,
+}
+
+/// 处理后的音频：交错的f32样本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedAudio {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Vec<f32>,
+}
+
+/// 导入产物
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ImportedAsset {
+    Mesh(Mesh),
+    Texture(ImportedTexture),
+    Audio(ImportedAudio),
+}
+
+impl ImportedAsset {
+    pub fn kind(&self) -> ImportAssetKind {
+        match self {
+            Self::Mesh(_) => ImportAssetKind::Mesh,
+            Self::Texture(_) => ImportAssetKind::Texture,
+            Self::Audio(_) => ImportAssetKind::Audio,
+        }
+    }
+}
+
+/// 一次导入的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportResult {
+    pub kind: ImportAssetKind,
+    /// 产物文件
+    pub artifact_path: PathBuf,
+    /// 是否直接使用了缓存中的产物
+    pub cache_hit: bool,
+}
+
+/// 导入统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportStats {
+    /// 实际执行处理的次数
+    pub processed: u32,
+    pub cache_hits: u32,
+}
+
+/// 资源导入管线
+#[derive(Debug, Clone)]
+pub struct AssetImportPipeline {
+    cache_dir: PathBuf,
+    stats: ImportStats,
+}
+
+impl Default for AssetImportPipeline {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CACHE_DIR)
+    }
+}
+
+impl AssetImportPipeline {
+    /// 默认导入缓存目录
+    pub const DEFAULT_CACHE_DIR: &'static str = ".import";
+
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            stats: ImportStats::default(),
+        }
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn stats(&self) -> ImportStats {
+        self.stats
+    }
+
+    /// 导入源文件；源文件内容与相关设置都未变化时直接返回缓存的产物
+    pub fn import(&mut self, source: impl AsRef<Path>, settings: &AssetImportSettings) -> EngineResult<ImportResult> {
+        let source = source.as_ref();
+        let kind = ImportAssetKind::from_path(source)
+            .ok_or_else(|| EngineError::AssetError(format!("不支持导入的文件类型: {}", source.display())))?;
+        let bytes = std::fs::read(source)
+            .map_err(|e| EngineError::AssetError(format!("读取导入源文件失败 {}: {}", source.display(), e)))?;
+
+        let artifact_path = self.artifact_path(source, kind, &bytes, settings);
+        if artifact_path.exists() {
+            self.stats.cache_hits += 1;
+            return Ok(ImportResult { kind, artifact_path, cache_hit: true });
+        }
+
+        let asset = Self::process(source, kind, &bytes, settings)?;
+        std::fs::create_dir_all(&self.cache_dir)?;
+        self.remove_stale_artifacts(source, kind, &artifact_path);
+        Self::write_artifact(&artifact_path, &asset, settings.compresses(kind))?;
+
+        self.stats.processed += 1;
+        log::info!("已导入 {} -> {}", source.display(), artifact_path.display());
+        Ok(ImportResult { kind, artifact_path, cache_hit: false })
+    }
+
+    /// 源文件在缓存中的产物路径：`<目录>/<源路径哈希>_<文件名>-<内容与设置哈希>.<扩展名>`
+    pub fn artifact_path(&self, source: &Path, kind: ImportAssetKind, bytes: &[u8], settings: &AssetImportSettings) -> PathBuf {
+        let key = calculate_checksum(
+            format!(
+                "{}|{:?}|{}|{}",
+                calculate_checksum(bytes),
+                kind,
+                settings.cache_key(kind),
+                IMPORT_FORMAT_VERSION
+            )
+            .as_bytes(),
+        );
+        self.cache_dir.join(format!("{}-{}.{}", Self::artifact_prefix(source), &key[..16], kind.artifact_extension()))
+    }
+
+    fn artifact_prefix(source: &Path) -> String {
+        let path_hash = calculate_checksum(source.to_string_lossy().replace('\\', "/").as_bytes());
+        let stem = source.file_stem().and_then(|stem| stem.to_str()).unwrap_or("asset");
+        format!("{}_{}", &path_hash[..8], stem)
+    }
+
+    /// 删除同一源文件的旧产物
+    fn remove_stale_artifacts(&self, source: &Path, kind: ImportAssetKind, keep: &Path) {
+        let prefix = format!("{}-", Self::artifact_prefix(source));
+        let Ok(entries) = std::fs::read_dir(&self.cache_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_stale = path != keep
+                && path.extension().and_then(|extension| extension.to_str()) == Some(kind.artifact_extension())
+                && path.file_name().and_then(|name| name.to_str()).map_or(false, |name| name.starts_with(&prefix));
+            if is_stale {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    log::warn!("删除过期导入产物失败 {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    fn process(source: &Path, kind: ImportAssetKind, bytes: &[u8], settings: &AssetImportSettings) -> EngineResult<ImportedAsset> {
+        Ok(match kind {
+            ImportAssetKind::Mesh => ImportedAsset::Mesh(Self::process_mesh(source, bytes, settings)?),
+            ImportAssetKind::Texture => ImportedAsset::Texture(Self::process_texture(source, bytes, settings)?),
+            ImportAssetKind::Audio => ImportedAsset::Audio(Self::process_audio(source, settings)?),
+        })
+    }
+
+    fn process_mesh(source: &Path, bytes: &[u8], settings: &AssetImportSettings) -> EngineResult<Mesh> {
+        let mut mesh = MeshLoader.load_bytes(bytes, source)?;
+
+        if settings.scale_factor != 1.0 {
+            for vertex in mesh.vertices_mut().iter_mut() {
+                vertex.position *= settings.scale_factor;
+            }
+        }
+        settings.source_up_axis.convert_mesh(&mut mesh);
+        if settings.optimize_mesh {
+            mesh.weld_vertices(Mesh::DEFAULT_WELD_EPSILON);
+        }
+        if settings.generate_normals {
+            mesh.smooth_normals(settings.smoothing_angle.to_radians());
+        }
+        mesh.recalculate_bounds();
+        Ok(mesh)
+    }
+
+    fn process_texture(source: &Path, bytes: &[u8], settings: &AssetImportSettings) -> EngineResult<ImportedTexture> {
+        let mut image = image::load_from_memory(bytes)
+            .map_err(|e| EngineError::AssetError(format!("解码纹理失败 {}: {}", source.display(), e)))?
+            .to_rgba8();
+
+        let max_size = settings.max_texture_size.max(1);
+        let (width, height) = image.dimensions();
+        if width.max(height) > max_size {
+            let scale = max_size as f32 / width.max(height) as f32;
+            let new_width = ((width as f32 * scale).round() as u32).max(1);
+            let new_height = ((height as f32 * scale).round() as u32).max(1);
+            image = image::imageops::resize(&image, new_width, new_height, image::imageops::FilterType::Triangle);
+        }
+
+        let (width, height) = image.dimensions();
+        let mut mips = vec![image.as_raw().clone()];
+        if settings.generate_mipmaps {
+            let (mut mip_width, mut mip_height) = (width, height);
+            while mip_width > 1 || mip_height > 1 {
+                mip_width = (mip_width / 2).max(1);
+                mip_height = (mip_height / 2).max(1);
+                let mip = image::imageops::resize(&image, mip_width, mip_height, image::imageops::FilterType::Triangle);
+                mips.push(mip.into_raw());
+            }
+        }
+
+        Ok(ImportedTexture { width, height, mips })
+    }
+
+    fn process_audio(source: &Path, settings: &AssetImportSettings) -> EngineResult<ImportedAudio> {
+        let decoded = Self::decode_audio(source)?;
+        Ok(match settings.audio_quality.sample_rate() {
+            Some(sample_rate) if sample_rate != decoded.sample_rate => Self::resample(&decoded, sample_rate),
+            _ => decoded,
+        })
+    }
+
+    #[cfg(feature = "audio")]
+    fn decode_audio(source: &Path) -> EngineResult<ImportedAudio> {
+        use rodio::Source;
+
+        let file = std::fs::File::open(source)?;
+        let decoder = rodio::Decoder::new(std::io::BufReader::new(file))
+            .map_err(|e| EngineError::AssetError(format!("解码音频失败 {}: {}", source.display(), e)))?;
+        let sample_rate = decoder.sample_rate();
+        let channels = decoder.channels();
+        let samples = decoder.map(|sample| sample as f32 / i16::MAX as f32).collect();
+        Ok(ImportedAudio { sample_rate, channels, samples })
+    }
+
+    #[cfg(not(feature = "audio"))]
+    fn decode_audio(source: &Path) -> EngineResult<ImportedAudio> {
+        Err(EngineError::AssetError(format!("导入音频需要启用audio特性: {}", source.display())).into())
+    }
+
+    /// 线性插值重采样
+    fn resample(audio: &ImportedAudio, sample_rate: u32) -> ImportedAudio {
+        let channels = audio.channels.max(1) as usize;
+        let frames = audio.samples.len() / channels;
+        let ratio = audio.sample_rate as f64 / sample_rate as f64;
+        let output_frames = (frames as f64 / ratio).floor() as usize;
+
+        let mut samples = Vec::with_capacity(output_frames * channels);
+        for frame in 0..output_frames {
+            let position = frame as f64 * ratio;
+            let index = position as usize;
+            let next = (index + 1).min(frames.saturating_sub(1));
+            let t = (position - index as f64) as f32;
+            for channel in 0..channels {
+                let a = audio.samples[index * channels + channel];
+                let b = audio.samples[next * channels + channel];
+                samples.push(a + (b - a) * t);
+            }
+        }
+
+        ImportedAudio { sample_rate, channels: audio.channels, samples }
+    }
+
+    /// 写入产物：文件头(魔数、版本、压缩标记) + bincode编码的数据
+    fn write_artifact(path: &Path, asset: &ImportedAsset, compress: bool) -> EngineResult<()> {
+        let payload = bincode::serialize(asset)
+            .map_err(|e| EngineError::AssetError(format!("编码导入产物失败: {}", e)))?;
+
+        let mut output = Vec::with_capacity(payload.len() + 9);
+        output.extend_from_slice(ARTIFACT_MAGIC);
+        output.extend_from_slice(&IMPORT_FORMAT_VERSION.to_le_bytes());
+        output.push(compress as u8);
+        if compress {
+            let mut encoder = flate2::write::DeflateEncoder::new(output, flate2::Compression::default());
+            encoder.write_all(&payload)?;
+            output = encoder.finish()?;
+        } else {
+            output.extend_from_slice(&payload);
+        }
+
+        // 先写临时文件再重命名，避免中断后留下半个产物被当作缓存命中
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, &output)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// 读取导入产物
+    pub fn load_artifact(path: impl AsRef<Path>) -> EngineResult<ImportedAsset> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        if bytes.len() < 9 || &bytes[..4] != ARTIFACT_MAGIC {
+            return Err(EngineError::AssetError(format!("不是有效的导入产物: {}", path.display())).into());
+        }
+        let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        if version != IMPORT_FORMAT_VERSION {
+            return Err(EngineError::AssetError(format!(
+                "导入产物版本 {} 与当前版本 {} 不一致: {}",
+                version,
+                IMPORT_FORMAT_VERSION,
+                path.display()
+            ))
+            .into());
+        }
+
+        let payload = if bytes[8] != 0 {
+            let mut decoded = Vec::new();
+            flate2::read::DeflateDecoder::new(&bytes[9..]).read_to_end(&mut decoded)?;
+            decoded
+        } else {
+            bytes[9..].to_vec()
+        };
+        bincode::deserialize(&payload)
+            .map_err(|e| EngineError::AssetError(format!("解码导入产物失败 {}: {}", path.display(), e)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sanji_import_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        image::RgbaImage::from_fn(width, height, |x, y| image::Rgba([x as u8 * 16, y as u8 * 16, 128, 255]))
+            .save(path)
+            .unwrap();
+    }
+
+    fn artifacts(dir: &Path) -> usize {
+        std::fs::read_dir(dir).map_or(0, |entries| entries.count())
+    }
+
+    #[test]
+    fn reimporting_unchanged_texture_is_a_cache_hit() {
+        let dir = temp_dir("texture");
+        let source = dir.join("brick.png");
+        write_png(&source, 8, 4);
+        let mut pipeline = AssetImportPipeline::new(dir.join(".import"));
+        let settings = AssetImportSettings::default();
+
+        let first = pipeline.import(&source, &settings).unwrap();
+        assert!(!first.cache_hit);
+        assert_eq!(first.kind, ImportAssetKind::Texture);
+        let modified = std::fs::metadata(&first.artifact_path).unwrap().modified().unwrap();
+
+        let second = pipeline.import(&source, &settings).unwrap();
+        assert!(second.cache_hit);
+        assert_eq!(second.artifact_path, first.artifact_path);
+        assert_eq!(pipeline.stats(), ImportStats { processed: 1, cache_hits: 1 });
+        assert_eq!(std::fs::metadata(&second.artifact_path).unwrap().modified().unwrap(), modified);
+
+        let ImportedAsset::Texture(texture) = AssetImportPipeline::load_artifact(&second.artifact_path).unwrap() else {
+            panic!("产物不是纹理");
+        };
+        assert_eq!((texture.width, texture.height), (8, 4));
+        // 8x4 -> 4x2 -> 2x1 -> 1x1
+        assert_eq!(texture.mips.len(), 4);
+        assert_eq!(texture.mips[0].len(), 8 * 4 * 4);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn changed_settings_or_source_reprocess_and_replace_artifact() {
+        let dir = temp_dir("reimport");
+        let source = dir.join("icon.png");
+        write_png(&source, 16, 16);
+        let cache = dir.join(".import");
+        let mut pipeline = AssetImportPipeline::new(&cache);
+
+        let original = pipeline.import(&source, &AssetImportSettings::default()).unwrap();
+
+        // 只影响音频的设置不使纹理缓存失效
+        let audio_only = AssetImportSettings { compress_audio: true, ..Default::default() };
+        assert!(pipeline.import(&source, &audio_only).unwrap().cache_hit);
+
+        let smaller = AssetImportSettings { max_texture_size: 4, generate_mipmaps: false, compress_texture: false, ..Default::default() };
+        let resized = pipeline.import(&source, &smaller).unwrap();
+        assert!(!resized.cache_hit);
+        assert_ne!(resized.artifact_path, original.artifact_path);
+        assert!(!original.artifact_path.exists());
+        assert_eq!(artifacts(&cache), 1);
+        let ImportedAsset::Texture(texture) = AssetImportPipeline::load_artifact(&resized.artifact_path).unwrap() else {
+            panic!("产物不是纹理");
+        };
+        assert_eq!((texture.width, texture.height, texture.mips.len()), (4, 4, 1));
+
+        write_png(&source, 2, 2);
+        assert!(!pipeline.import(&source, &smaller).unwrap().cache_hit);
+        assert_eq!(pipeline.stats().processed, 3);
+        assert_eq!(artifacts(&cache), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn mesh_import_applies_scale() {
+        let dir = temp_dir("mesh");
+        let source = dir.join("triangle.obj");
+        std::fs::write(&source, "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nvn 0 0 1\nvn 0 0 1\nf 1 2 3\n").unwrap();
+        let mut pipeline = AssetImportPipeline::new(dir.join(".import"));
+        let settings = AssetImportSettings { scale_factor: 2.0, generate_normals: false, ..Default::default() };
+
+        let result = pipeline.import(&source, &settings).unwrap();
+        assert!(result.artifact_path.extension().map_or(false, |extension| extension == "sjmesh"));
+        let ImportedAsset::Mesh(mesh) = AssetImportPipeline::load_artifact(&result.artifact_path).unwrap() else {
+            panic!("产物不是网格");
+        };
+        let positions: Vec<_> = mesh.vertices.iter().map(|vertex| vertex.position).collect();
+        assert_eq!(positions, [glam::Vec3::ZERO, glam::Vec3::new(2.0, 0.0, 0.0), glam::Vec3::new(0.0, 2.0, 0.0)]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unsupported_or_invalid_inputs_are_errors() {
+        let dir = temp_dir("invalid");
+        let mut pipeline = AssetImportPipeline::new(dir.join(".import"));
+        let notes = dir.join("notes.txt");
+        std::fs::write(&notes, "not an asset").unwrap();
+        assert!(pipeline.import(&notes, &AssetImportSettings::default()).is_err());
+        assert!(pipeline.import(dir.join("missing.png"), &AssetImportSettings::default()).is_err());
+
+        let bogus = dir.join("bogus.sjtex");
+        std::fs::write(&bogus, b"NOPE0000").unwrap();
+        assert!(AssetImportPipeline::load_artifact(&bogus).is_err());
+        assert_eq!(pipeline.stats(), ImportStats::default());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn resample_halves_frame_count() {
+        let audio = ImportedAudio { sample_rate: 44_100, channels: 2, samples: (0..8).map(|i| i as f32).collect() };
+        let resampled = AssetImportPipeline::resample(&audio, 22_050);
+        assert_eq!(resampled.sample_rate, 22_050);
+        assert_eq!(resampled.channels, 2);
+        // 4帧 -> 2帧，取第0、2帧
+        assert_eq!(resampled.samples, [0.0, 1.0, 4.0, 5.0]);
+        assert_eq!(AudioQuality::Lossless.sample_rate(), None);
+    }
+
+    fn assert_vec_eq(a: glam::Vec3, b: glam::Vec3) {
+        assert!((a - b).length() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    /// Z轴向上的长方体：宽2(X)、深4(Y)、高3(Z)
+    const Z_UP_BOX_OBJ: &str = "\
+v -1 -2 0\nv 1 -2 0\nv 1 2 0\nv -1 2 0\nv -1 -2 3\nv 1 -2 3\nv 1 2 3\nv -1 2 3\n\
+f 1 3 2\nf 1 4 3\nf 5 6 7\nf 5 7 8\nf 1 2 6\nf 1 6 5\n\
+f 2 3 7\nf 2 7 6\nf 3 4 8\nf 3 8 7\nf 4 1 5\nf 4 5 8\n";
+
+    #[test]
+    fn z_up_box_is_converted_to_y_up() {
+        let dir = temp_dir("up_axis");
+        let source = dir.join("box.obj");
+        std::fs::write(&source, Z_UP_BOX_OBJ).unwrap();
+        let mut pipeline = AssetImportPipeline::new(dir.join(".import"));
+
+        let y_up = pipeline.import(&source, &AssetImportSettings::default()).unwrap();
+        let settings = AssetImportSettings { source_up_axis: UpAxis::Z, ..Default::default() };
+        let z_up = pipeline.import(&source, &settings).unwrap();
+        // 上方向轴参与缓存键
+        assert!(!z_up.cache_hit);
+        assert_ne!(z_up.artifact_path, y_up.artifact_path);
+
+        let ImportedAsset::Mesh(mesh) = AssetImportPipeline::load_artifact(&z_up.artifact_path).unwrap() else {
+            panic!("产物不是网格");
+        };
+        // 高度沿+Y，原来的+Y(深度)变为-Z
+        let bounds = mesh.local_aabb();
+        assert_vec_eq(bounds.min, glam::Vec3::new(-1.0, 0.0, -2.0));
+        assert_vec_eq(bounds.max, glam::Vec3::new(1.0, 3.0, 2.0));
+        let has = |point: glam::Vec3| mesh.vertices.iter().any(|vertex| (vertex.position - point).length() < 1e-5);
+        assert!(has(glam::Vec3::new(1.0, 3.0, -2.0)));
+        assert!(has(glam::Vec3::new(-1.0, 0.0, 2.0)));
+
+        // 原顶面(z=3)重新生成的法线朝上
+        assert!(mesh
+            .vertices
+            .iter()
+            .any(|vertex| (vertex.position.y - 3.0).abs() < 1e-5 && (vertex.normal - glam::Vec3::Y).length() < 1e-4));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn up_axis_rotation_maps_z_to_y() {
+        let rotation = UpAxis::Z.to_engine_rotation();
+        assert_vec_eq(rotation * glam::Vec3::Z, glam::Vec3::Y);
+        assert_vec_eq(rotation * glam::Vec3::Y, glam::Vec3::NEG_Z);
+        assert_vec_eq(rotation * glam::Vec3::X, glam::Vec3::X);
+        assert_eq!(UpAxis::Y.to_engine_rotation(), Quat::IDENTITY);
+
+        // 顶点位置和法线一起旋转
+        let vertex = crate::render::MeshVertex { position: glam::Vec3::new(1.0, 2.0, 3.0), normal: glam::Vec3::Z, ..Default::default() };
+        let mut mesh = Mesh::from_geometry("quad", vec![vertex], Vec::new());
+        UpAxis::Z.convert_mesh(&mut mesh);
+        assert_vec_eq(mesh.vertices[0].position, glam::Vec3::new(1.0, 3.0, -2.0));
+        assert_vec_eq(mesh.vertices[0].normal, glam::Vec3::Y);
+
+        let mut value = serde_json::to_value(&AssetImportSettings { source_up_axis: UpAxis::Z, ..Default::default() }).unwrap();
+        value.as_object_mut().unwrap().remove("source_up_axis");
+        let settings: AssetImportSettings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.source_up_axis, UpAxis::Y);
+    }
+
+    #[test]
+    fn z_up_animation_tracks_are_converted() {
+        use crate::animation::animation_clip::{AnimationTrack, Keyframe};
+
+        let mut clip = AnimationClip::new("jump", 1.0);
+        let mut position = AnimationTrack::new("root", AnimationProperty::Position);
+        let mut key = Keyframe::new(0.5, KeyframeValue::Vec3(glam::Vec3::new(1.0, 0.0, 2.0)));
+        key.tangent_out = Some(glam::Vec3::Z);
+        position.add_keyframe(key);
+        clip.add_track(position);
+        let mut rotation = AnimationTrack::new("root", AnimationProperty::Rotation);
+        // Z轴向上坐标系中绕上方向(Z)转90°
+        rotation.add_keyframe(Keyframe::new(0.0, KeyframeValue::Quaternion(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2))));
+        clip.add_track(rotation);
+        let mut scale = AnimationTrack::new("root", AnimationProperty::Scale);
+        scale.add_keyframe(Keyframe::new(0.0, KeyframeValue::Vec3(glam::Vec3::new(1.0, 2.0, 3.0))));
+        clip.add_track(scale);
+
+        UpAxis::Z.convert_animation(&mut clip);
+
+        let KeyframeValue::Vec3(moved) = clip.tracks[0].keyframes[0].value else { panic!("位置轨道类型错误") };
+        assert_vec_eq(moved, glam::Vec3::new(1.0, 2.0, 0.0));
+        assert_vec_eq(clip.tracks[0].keyframes[0].tangent_out.unwrap(), glam::Vec3::Y);
+
+        // 换基后是绕引擎上方向(Y)的旋转
+        let KeyframeValue::Quaternion(turned) = clip.tracks[1].keyframes[0].value else { panic!("旋转轨道类型错误") };
+        assert!(turned.abs_diff_eq(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2), 1e-5));
+
+        let KeyframeValue::Vec3(scaled) = clip.tracks[2].keyframes[0].value else { panic!("缩放轨道类型错误") };
+        assert_vec_eq(scaled, glam::Vec3::new(1.0, 3.0, 2.0));
+    }
+}
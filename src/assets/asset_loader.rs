@@ -21,6 +21,92 @@ pub trait AssetLoader: Send + Sync {
     fn supports_extension(&self, extension: &str) -> bool {
         self.extensions().contains(&extension)
     }
+
+    /// 是否能直接从字节切片解析(可使用内存映射读取)
+    fn supports_bytes(&self) -> bool {
+        false
+    }
+
+    /// 从文件内容解析资源，path仅用于推断格式和命名
+    fn load_bytes(&self, _bytes: &[u8], path: &Path) -> EngineResult<Self::Asset> {
+        Err(EngineError::AssetError(format!("加载器不支持从内存解析: {:?}", path)).into())
+    }
+
+    /// 资源引用的其他资源路径，资源管理器会加载它们并记录依赖关系
+    fn dependencies(&self, _asset: &Self::Asset) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// 文件内容：普通读取的缓冲区或内存映射
+pub enum AssetBytes {
+    Buffered(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl AssetBytes {
+    /// 是否为内存映射
+    pub fn is_mapped(&self) -> bool {
+        match self {
+            AssetBytes::Buffered(_) => false,
+            #[cfg(feature = "mmap")]
+            AssetBytes::Mapped(_) => true,
+        }
+    }
+}
+
+impl std::ops::Deref for AssetBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            AssetBytes::Buffered(data) => data,
+            #[cfg(feature = "mmap")]
+            AssetBytes::Mapped(map) => map,
+        }
+    }
+}
+
+/// 超过该大小的文件使用内存映射读取
+pub const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// 读取资源文件，启用mmap特性时大文件使用内存映射，映射失败则回退到普通读取
+pub fn read_asset_bytes(path: &Path) -> EngineResult<AssetBytes> {
+    #[cfg(feature = "mmap")]
+    {
+        let file = std::fs::File::open(path)
+            .map_err(|e| EngineError::AssetError(format!("打开资源文件失败 {:?}: {}", path, e)))?;
+        let large = file.metadata().map(|m| m.len() >= MMAP_THRESHOLD).unwrap_or(false);
+        if large {
+            // 安全性：映射期间文件被外部修改会导致读取到不一致的数据，资源文件视为只读
+            match unsafe { memmap2::Mmap::map(&file) } {
+                Ok(map) => return Ok(AssetBytes::Mapped(map)),
+                Err(e) => log::warn!("内存映射失败 {:?}: {}，改为普通读取", path, e),
+            }
+        }
+    }
+
+    let data = std::fs::read(path)
+        .map_err(|e| EngineError::AssetError(format!("读取资源文件失败 {:?}: {}", path, e)))?;
+    Ok(AssetBytes::Buffered(data))
+}
+
+/// 读取文件后交给加载器解析，支持字节解析的加载器走(可能映射的)字节路径
+pub fn load_with<L: AssetLoader + ?Sized>(loader: &L, path: &Path) -> EngineResult<L::Asset> {
+    if loader.supports_bytes() {
+        let bytes = read_asset_bytes(path)?;
+        loader.load_bytes(&bytes, path)
+    } else {
+        loader.load(path)
+    }
+}
+
+fn file_stem(path: &Path, fallback: &str) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(fallback)
+        .to_string()
 }
 
 /// 纹理加载器
@@ -36,6 +122,18 @@ impl AssetLoader for TextureLoader {
     fn load(&self, path: &Path) -> EngineResult<Self::Asset> {
         Texture::from_file(path)
     }
+
+    fn supports_bytes(&self) -> bool {
+        true
+    }
+
+    fn load_bytes(&self, bytes: &[u8], path: &Path) -> EngineResult<Self::Asset> {
+        let name = file_stem(path, "未知纹理");
+        match image::ImageFormat::from_path(path) {
+            Ok(format) => Texture::from_memory_with_format(bytes, format, name),
+            Err(_) => Texture::from_memory(bytes, name),
+        }
+    }
 }
 
 /// 着色器加载器
@@ -80,6 +178,18 @@ impl AssetLoader for MaterialLoader {
         
         Ok(material)
     }
+
+    fn dependencies(&self, material: &Material) -> Vec<String> {
+        material_texture_paths(material)
+    }
+}
+
+/// 材质引用的纹理路径(排序去重)
+pub(crate) fn material_texture_paths(material: &Material) -> Vec<String> {
+    let mut paths: Vec<String> = material.textures.values().cloned().collect();
+    paths.sort();
+    paths.dedup();
+    paths
 }
 
 /// 网格加载器 (简化版)
@@ -106,6 +216,25 @@ impl AssetLoader for MeshLoader {
             }
         }
     }
+
+    fn supports_bytes(&self) -> bool {
+        true
+    }
+
+    fn load_bytes(&self, bytes: &[u8], path: &Path) -> EngineResult<Self::Asset> {
+        let extension = path.extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("");
+
+        match extension.to_lowercase().as_str() {
+            "obj" => {
+                let content = std::str::from_utf8(bytes)
+                    .map_err(|e| EngineError::AssetError(format!("OBJ文件不是有效的UTF-8: {}", e)))?;
+                Ok(self.parse_obj(content, path))
+            }
+            _ => self.load(path),
+        }
+    }
 }
 
 impl MeshLoader {
@@ -113,7 +242,11 @@ impl MeshLoader {
         // 简化的OBJ加载器实现
         let content = std::fs::read_to_string(path)
             .map_err(|e| EngineError::AssetError(format!("读取OBJ文件失败: {}", e)))?;
-        
+
+        Ok(self.parse_obj(&content, path))
+    }
+
+    fn parse_obj(&self, content: &str, path: &Path) -> Mesh {
         let mut positions = Vec::new();
         let mut normals = Vec::new();
         let mut tex_coords = Vec::new();
@@ -184,18 +317,21 @@ impl MeshLoader {
                 normal,
                 tex_coords: tex_coord,
                 color: glam::Vec3::ONE,
+                ..Default::default()
             });
         }
         
         // 生成索引
         mesh.indices = (0..mesh.vertices.len()).map(|i| i as u32).collect();
         
-        // 如果没有法线，计算法线
+        // 如果没有法线，焊接重合顶点后按折痕角度生成法线
         if normals.is_empty() {
-            mesh.calculate_normals();
+            mesh.weld_vertices(Mesh::DEFAULT_WELD_EPSILON);
+            mesh.smooth_normals(Mesh::DEFAULT_SMOOTHING_ANGLE);
         }
         
-        Ok(mesh)
+        mesh.recalculate_bounds();
+        mesh
     }
 }
 
@@ -215,14 +351,23 @@ impl AssetLoader for AudioLoader {
             .map_err(|e| EngineError::AssetError(format!("读取音频文件失败: {}", e)))?;
         
         Ok(AudioClip {
-            name: path.file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("audio")
-                .to_string(),
+            name: file_stem(path, "audio"),
             data,
             format: AudioFormat::Unknown,
         })
     }
+
+    fn supports_bytes(&self) -> bool {
+        true
+    }
+
+    fn load_bytes(&self, bytes: &[u8], path: &Path) -> EngineResult<Self::Asset> {
+        Ok(AudioClip {
+            name: file_stem(path, "audio"),
+            data: bytes.to_vec(),
+            format: AudioFormat::Unknown,
+        })
+    }
 }
 
 /// 音频剪辑 (简化)
@@ -252,26 +397,44 @@ pub trait ErasedAssetLoader: Send + Sync {
     fn extensions(&self) -> &[&str];
     fn load(&self, path: &Path) -> EngineResult<Arc<dyn Any + Send + Sync>>;
     fn type_name(&self) -> &'static str;
+
+    /// 资源引用的其他资源路径，asset为该加载器加载出的资源
+    fn dependencies(&self, _asset: &(dyn Any + Send + Sync)) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// 类型擦除包装器
-struct TypeErasedLoader<L: AssetLoader> {
+pub(crate) struct TypeErasedLoader<L: AssetLoader> {
     loader: L,
 }
 
+impl<L: AssetLoader> TypeErasedLoader<L> {
+    pub(crate) fn new(loader: L) -> Self {
+        Self { loader }
+    }
+}
+
 impl<L: AssetLoader> ErasedAssetLoader for TypeErasedLoader<L> {
     fn extensions(&self) -> &[&str] {
         self.loader.extensions()
     }
 
     fn load(&self, path: &Path) -> EngineResult<Arc<dyn Any + Send + Sync>> {
-        let asset = self.loader.load(path)?;
+        let asset = load_with(&self.loader, path)?;
         Ok(Arc::new(asset) as Arc<dyn Any + Send + Sync>)
     }
 
     fn type_name(&self) -> &'static str {
         std::any::type_name::<L::Asset>()
     }
+
+    fn dependencies(&self, asset: &(dyn Any + Send + Sync)) -> Vec<String> {
+        asset
+            .downcast_ref::<L::Asset>()
+            .map(|asset| self.loader.dependencies(asset))
+            .unwrap_or_default()
+    }
 }
 
 impl AssetLoaderRegistry {
@@ -284,7 +447,7 @@ impl AssetLoaderRegistry {
 
     /// 注册资源加载器
     pub fn register<L: AssetLoader + 'static>(&mut self, loader: L) {
-        self.loaders.push(Box::new(TypeErasedLoader { loader }));
+        self.loaders.push(Box::new(TypeErasedLoader::new(loader)));
     }
 
     /// 根据文件扩展名查找加载器
@@ -331,3 +494,62 @@ impl Default for AssetLoaderRegistry {
         registry
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("sanji_loader_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn small_files_are_read_into_a_buffer() {
+        let path = temp_file("small.bin", b"sanji");
+        let bytes = read_asset_bytes(&path).unwrap();
+        assert!(!bytes.is_mapped());
+        assert_eq!(&*bytes, b"sanji");
+        assert!(read_asset_bytes(&path.with_file_name("missing.bin")).is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn large_files_are_memory_mapped() {
+        let contents = vec![7u8; MMAP_THRESHOLD as usize];
+        let path = temp_file("large.bin", &contents);
+        let bytes = read_asset_bytes(&path).unwrap();
+        assert!(bytes.is_mapped());
+        assert_eq!(&*bytes, contents.as_slice());
+    }
+
+    #[test]
+    fn byte_path_matches_file_path_for_obj() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nf 1/1 2/2 3/3\n";
+        let path = temp_file("triangle.obj", obj.as_bytes());
+
+        let from_bytes = load_with(&MeshLoader, &path).unwrap();
+        let from_file = MeshLoader.load(&path).unwrap();
+        assert_eq!(from_bytes.vertices.len(), 3);
+        assert_eq!(from_bytes.vertices.len(), from_file.vertices.len());
+        assert_eq!(from_bytes.indices, from_file.indices);
+
+        assert!(MeshLoader.load_bytes(&[0xff, 0xfe], &path).is_err());
+    }
+
+    #[test]
+    fn texture_loader_decodes_bytes_by_extension() {
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(2, 3, image::Rgba([10, 20, 30, 255])));
+        let mut png = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut png, image::ImageFormat::Png).unwrap();
+        let path = temp_file("pixel.png", png.get_ref());
+
+        let texture = load_with(&TextureLoader, &path).unwrap();
+        assert_eq!((texture.descriptor.width, texture.descriptor.height), (2, 3));
+        assert_eq!(texture.name, "pixel");
+        assert_eq!(&texture.data[..4], &[10, 20, 30, 255]);
+    }
+}
@@ -1,40 +1,89 @@
 //! 资源管理器
 
 use crate::{EngineResult, EngineError};
-use crate::assets::{AssetHandle, AssetLoader, AssetCache, AssetHandleManager, CacheStrategy, ErasedAssetLoader};
+use crate::assets::{AssetHandle, AssetId, AssetState, AssetLoader, AssetCache, AssetHandleManager, AssetDependencyGraph, AssetVfs, CacheStrategy, ErasedAssetLoader, AssetManifest, PreloadReport, UntypedAssetHandle};
+use crate::assets::asset_loader::{TypeErasedLoader, material_texture_paths};
 use crate::render::{Texture, Mesh, Material, Shader};
-use crate::events::{EventSystem, AssetLoadedEvent, AssetLoadFailedEvent};
+use crate::events::{Event, EventSender, EventSystem, AssetLoadedEvent, AssetLoadFailedEvent, AssetReloadedEvent};
 
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{mpsc, Arc, Mutex, RwLock};
 use std::path::{Path, PathBuf};
 
+/// 按加载器的资源类型加载路径，注册加载器时生成，用于不知道资源类型的预加载
+type UntypedLoadFn = fn(&mut AssetManager, &str) -> EngineResult<UntypedAssetHandle>;
+
+/// 加载器解码出的资源及其引用的资源路径
+type DecodedAsset = EngineResult<(Arc<dyn Any + Send + Sync>, Vec<String>)>;
+
+/// 按加载器的资源类型把解码结果放入缓存，注册加载器时生成，用于后台解码的预加载
+type UntypedFinishFn = fn(&mut AssetManager, String, DecodedAsset) -> EngineResult<UntypedAssetHandle>;
+
+/// 已找到加载器、等待解码的资源
+struct PendingLoad {
+    /// 缓存键
+    path: String,
+    full_path: PathBuf,
+    extension: String,
+    loader: Arc<dyn ErasedAssetLoader>,
+}
+
+impl PendingLoad {
+    /// 读取并解码资源，可在工作线程中执行
+    fn decode(&self) -> DecodedAsset {
+        let asset = self.loader.load(&self.full_path)?;
+        let dependencies = self.loader.dependencies(asset.as_ref());
+        Ok((asset, dependencies))
+    }
+}
+
 /// 资源管理器 - 统一管理所有游戏资源
 pub struct AssetManager {
     /// 资源加载器
-    loaders: HashMap<String, Box<dyn ErasedAssetLoader>>,
+    loaders: HashMap<String, Arc<dyn ErasedAssetLoader>>,
+    /// 按扩展名的无类型加载入口
+    untyped_loaders: HashMap<String, UntypedLoadFn>,
+    /// 按扩展名把后台解码结果放入缓存的入口
+    untyped_finishers: HashMap<String, UntypedFinishFn>,
     /// 资源缓存
     cache: AssetCache,
     /// 句柄管理器
     handle_manager: AssetHandleManager,
-    /// 资源根目录
+    /// 默认资源根目录(优先级0)
     asset_root: PathBuf,
+    /// 资源搜索路径
+    vfs: AssetVfs,
     /// 默认缓存策略
     default_cache_strategy: CacheStrategy,
     /// 事件系统引用
     event_system: Option<Arc<RwLock<EventSystem>>>,
+    /// 事件发送器(优先于事件系统引用使用)
+    event_sender: Option<EventSender>,
+    /// 资源依赖图
+    dependencies: AssetDependencyGraph,
+    /// 最近一次加载失败的路径及原因
+    load_failures: HashMap<String, String>,
 }
 
 impl AssetManager {
     /// 创建新的资源管理器
     pub fn new() -> EngineResult<Self> {
+        let mut vfs = AssetVfs::new();
+        vfs.add_search_path("assets", 0);
         let mut manager = Self {
             loaders: HashMap::new(),
+            untyped_loaders: HashMap::new(),
+            untyped_finishers: HashMap::new(),
             cache: AssetCache::default(),
             handle_manager: AssetHandleManager::new(),
             asset_root: PathBuf::from("assets"),
+            vfs,
             default_cache_strategy: CacheStrategy::RefCount,
             event_system: None,
+            event_sender: None,
+            dependencies: AssetDependencyGraph::new(),
+            load_failures: HashMap::new(),
         };
 
         // 注册默认加载器
@@ -43,9 +92,31 @@ impl AssetManager {
         Ok(manager)
     }
 
-    /// 设置资源根目录
+    /// 设置默认资源根目录(优先级0)
     pub fn set_asset_root(&mut self, path: impl Into<PathBuf>) {
+        self.vfs.remove_search_path(&self.asset_root);
         self.asset_root = path.into();
+        self.vfs.add_search_path(self.asset_root.clone(), 0);
+    }
+
+    /// 添加资源搜索路径，优先级高的根目录中的同名文件会覆盖低优先级的
+    pub fn add_search_path(&mut self, path: impl Into<PathBuf>, priority: i32) {
+        self.vfs.add_search_path(path, priority);
+    }
+
+    /// 移除资源搜索路径
+    pub fn remove_search_path(&mut self, path: impl AsRef<Path>) -> bool {
+        self.vfs.remove_search_path(path)
+    }
+
+    /// 资源虚拟文件系统
+    pub fn vfs(&self) -> &AssetVfs {
+        &self.vfs
+    }
+
+    /// 把资源路径或 `res://` 虚拟路径解析为实际文件路径
+    pub fn resolve_path(&self, path: impl AsRef<Path>) -> Option<PathBuf> {
+        self.vfs.resolve(&path.as_ref().to_string_lossy())
     }
 
     /// 设置事件系统
@@ -53,14 +124,26 @@ impl AssetManager {
         self.event_system = Some(event_system);
     }
 
+    /// 设置事件发送器，资源加载事件会自动通过它发布
+    pub fn set_event_sender(&mut self, sender: EventSender) {
+        self.event_sender = Some(sender);
+    }
+
     /// 设置默认缓存策略
     pub fn set_default_cache_strategy(&mut self, strategy: CacheStrategy) {
         self.default_cache_strategy = strategy;
     }
 
     /// 注册资源加载器
-    pub fn register_loader<L: AssetLoader + ErasedAssetLoader + 'static>(&mut self, extension: impl Into<String>, loader: L) {
-        self.loaders.insert(extension.into(), Box::new(loader));
+    pub fn register_loader<L: AssetLoader + 'static>(&mut self, extension: impl Into<String>, loader: L) {
+        let extension = extension.into();
+        self.untyped_loaders.insert(extension.clone(), |manager: &mut AssetManager, path: &str| {
+            manager.load::<L::Asset>(path).map(UntypedAssetHandle::from)
+        });
+        self.untyped_finishers.insert(extension.clone(), |manager: &mut AssetManager, path: String, decoded: DecodedAsset| {
+            manager.finish_load::<L::Asset>(path, decoded).map(UntypedAssetHandle::from)
+        });
+        self.loaders.insert(extension, Arc::new(TypeErasedLoader::new(loader)));
     }
 
     /// 注册默认加载器
@@ -89,19 +172,42 @@ impl AssetManager {
 
     /// 同步加载资源
     pub fn load<T: Send + Sync + 'static>(&mut self, path: impl AsRef<Path>) -> EngineResult<AssetHandle<T>> {
-        let path = path.as_ref();
-        let full_path = self.asset_root.join(path);
-        let path_str = path.to_string_lossy().to_string();
-
-        // 检查缓存
-        if let Some(resource) = self.cache.get_by_path::<T>(&path_str) {
-            return Ok(AssetHandle::new(
-                self.handle_manager.generate_id(),
-                &resource,
-                path_str
-            ));
+        let raw_path = path.as_ref().to_string_lossy();
+        // 检查缓存，复用缓存中的槽位，保证依赖图中的ID稳定且句柄共享加载状态
+        if let Some(handle) = self.cache.handle_by_path::<T>(&AssetVfs::normalize(&raw_path)) {
+            return Ok(handle);
+        }
+
+        let pending = self.pending_load(&raw_path)?;
+        let decoded = pending.decode();
+        self.finish_load(pending.path, decoded)
+    }
+
+    /// 异步加载资源，读取和解码在工作线程中进行
+    pub async fn load_async<T: Send + Sync + 'static>(&mut self, path: impl AsRef<Path>) -> EngineResult<AssetHandle<T>> {
+        let raw_path = path.as_ref().to_string_lossy().into_owned();
+        if let Some(handle) = self.cache.handle_by_path::<T>(&AssetVfs::normalize(&raw_path)) {
+            return Ok(handle);
         }
 
+        let pending = self.pending_load(&raw_path)?;
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let path = pending.path.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(pending.decode());
+        });
+        let decoded = receiver.await.unwrap_or_else(|_| {
+            Err(EngineError::AssetError(format!("加载线程意外退出: {}", path)).into())
+        });
+        self.finish_load(path, decoded)
+    }
+
+    /// 解析路径并查找加载器，找不到加载器时记录失败
+    fn pending_load(&mut self, raw_path: &str) -> EngineResult<PendingLoad> {
+        let full_path = self.vfs.resolve_or_default(raw_path);
+        // 缓存键不含虚拟路径前缀，`res://a.png` 与 `a.png` 指向同一资源
+        let path = AssetVfs::normalize(raw_path);
+
         // 获取文件扩展名
         let extension = full_path.extension()
             .and_then(|ext| ext.to_str())
@@ -109,12 +215,24 @@ impl AssetManager {
             .to_lowercase();
 
         // 查找对应的加载器
-        let loader = self.loaders.get(&extension)
-            .ok_or_else(|| EngineError::AssetError(format!("没有找到扩展名 '{}' 的加载器", extension)))?;
+        match self.loaders.get(&extension) {
+            Some(loader) => Ok(PendingLoad { path, full_path, loader: Arc::clone(loader), extension }),
+            None => {
+                let error = format!("没有找到扩展名 '{}' 的加载器", extension);
+                self.load_failures.insert(path, error.clone());
+                Err(EngineError::AssetError(error).into())
+            }
+        }
+    }
+
+    /// 把解码结果放入缓存并加载其引用的资源，期间已被其他加载放入缓存时复用缓存
+    fn finish_load<T: Send + Sync + 'static>(&mut self, path_str: String, decoded: DecodedAsset) -> EngineResult<AssetHandle<T>> {
+        if let Some(handle) = self.cache.handle_by_path::<T>(&path_str) {
+            return Ok(handle);
+        }
 
-        // 加载资源
-        match loader.load(&full_path) {
-            Ok(resource_any) => {
+        match decoded {
+            Ok((resource_any, dependencies)) => {
                 // 尝试转换为目标类型
                 if let Ok(resource) = resource_any.downcast::<T>() {
                     // 计算资源大小 (简化估算)
@@ -129,6 +247,9 @@ impl AssetManager {
                         size_bytes
                     );
 
+                    self.load_failures.remove(&path_str);
+                    self.load_dependencies(handle.id(), &path_str, dependencies);
+
                     // 发送加载成功事件
                     self.emit_asset_loaded(&path_str, std::any::type_name::<T>());
 
@@ -138,23 +259,19 @@ impl AssetManager {
                         std::any::type_name::<T>(), 
                         "unknown");
                     self.emit_asset_load_failed(&path_str, &error);
+                    self.load_failures.insert(path_str, error.clone());
                     Err(EngineError::AssetError(error).into())
                 }
             }
             Err(e) => {
                 let error = format!("加载资源失败: {}", e);
                 self.emit_asset_load_failed(&path_str, &error);
+                self.load_failures.insert(path_str, error);
                 Err(e)
             }
         }
     }
 
-    /// 异步加载资源 (简化版本，实际需要完整的异步实现)
-    pub async fn load_async<T: Send + Sync + 'static>(&mut self, path: impl AsRef<Path>) -> EngineResult<AssetHandle<T>> {
-        // 在实际实现中，这里应该使用异步文件IO和工作线程
-        self.load(path)
-    }
-
     /// 通过句柄获取资源
     pub fn get<T: Send + Sync + 'static>(&self, handle: &AssetHandle<T>) -> Option<Arc<T>> {
         handle.get()
@@ -162,23 +279,107 @@ impl AssetManager {
 
     /// 检查资源是否已加载
     pub fn is_loaded(&self, path: impl AsRef<Path>) -> bool {
-        let path_str = path.as_ref().to_string_lossy().to_string();
+        let path_str = AssetVfs::normalize(&path.as_ref().to_string_lossy());
         self.cache.contains_path(&path_str)
     }
 
-    /// 卸载资源
+    /// 查询路径对应资源的加载状态，从未加载过时返回None
+    pub fn asset_state(&self, path: impl AsRef<Path>) -> Option<AssetState> {
+        let path_str = AssetVfs::normalize(&path.as_ref().to_string_lossy());
+        if let Some(error) = self.load_failures.get(&path_str) {
+            return Some(AssetState::Failed(error.clone()));
+        }
+        self.cache.id_of_path(&path_str).and_then(|id| self.cache.state(id))
+    }
+
+    /// 卸载资源，仍有已加载的资源依赖它时拒绝卸载
     pub fn unload(&mut self, handle: &AssetHandle<impl Send + Sync>) -> bool {
-        self.cache.remove(handle.id())
+        self.unload_by_id(handle.id())
     }
 
-    /// 通过路径卸载资源
+    /// 通过ID卸载资源，仍有已加载的资源依赖它时拒绝卸载
+    pub fn unload_by_id(&mut self, id: AssetId) -> bool {
+        let live_dependents = self.dependents_of(id);
+        if !live_dependents.is_empty() {
+            log::warn!("资源 {} 仍被 {} 个资源依赖，拒绝卸载: {:?}", id, live_dependents.len(), live_dependents);
+            return false;
+        }
+
+        self.dependencies.remove_asset(id);
+        self.cache.remove(id)
+    }
+
+    /// 级联卸载资源：先卸载所有(直接或间接)依赖它的资源，再卸载自身，返回卸载的资源数量
+    pub fn unload_cascade(&mut self, handle: &AssetHandle<impl Send + Sync>) -> usize {
+        let id = handle.id();
+        let mut unloaded = 0;
+
+        for dependent in self.dependencies.transitive_dependents(id) {
+            self.dependencies.remove_asset(dependent);
+            if self.cache.remove(dependent) {
+                unloaded += 1;
+            }
+        }
+
+        self.dependencies.remove_asset(id);
+        if self.cache.remove(id) {
+            unloaded += 1;
+        }
+        unloaded
+    }
+
+    /// 记录资源依赖关系(`dependent` 引用 `dependency`)，会形成循环依赖时返回false
+    pub fn add_dependency<A, B>(&mut self, dependent: &AssetHandle<A>, dependency: &AssetHandle<B>) -> bool {
+        let added = self.dependencies.add_dependency(dependent.id(), dependency.id());
+        if !added {
+            log::warn!("忽略循环资源依赖: {} -> {}", dependent.path(), dependency.path());
+        }
+        added
+    }
+
+    /// 移除资源依赖关系
+    pub fn remove_dependency<A, B>(&mut self, dependent: &AssetHandle<A>, dependency: &AssetHandle<B>) -> bool {
+        self.dependencies.remove_dependency(dependent.id(), dependency.id())
+    }
+
+    /// 获取仍在缓存中且直接依赖该资源的资源ID
+    pub fn dependents_of(&self, id: AssetId) -> Vec<AssetId> {
+        self.dependencies.dependents_of(id)
+            .into_iter()
+            .filter(|&dependent| self.cache.contains(dependent))
+            .collect()
+    }
+
+    /// 获取该资源直接依赖的资源ID
+    pub fn dependencies_of(&self, id: AssetId) -> Vec<AssetId> {
+        self.dependencies.dependencies_of(id)
+    }
+
+    /// 获取依赖图
+    pub fn dependency_graph(&self) -> &AssetDependencyGraph {
+        &self.dependencies
+    }
+
+    /// 通过路径卸载资源，仍有已加载的资源依赖它时拒绝卸载
     pub fn unload_by_path(&mut self, path: impl AsRef<Path>) -> bool {
-        let path_str = path.as_ref().to_string_lossy().to_string();
-        if let Some(resource) = self.cache.get_by_path::<()>(&path_str) {
-            // 这里需要一个方法来获取资源ID，简化处理
-            false // 临时返回false
-        } else {
-            false
+        let path_str = AssetVfs::normalize(&path.as_ref().to_string_lossy());
+        match self.cache.id_of_path(&path_str) {
+            Some(id) => self.unload_by_id(id),
+            None => false,
+        }
+    }
+
+    /// 加载资源引用的其他资源并记录依赖关系，依赖加载失败只记录日志
+    fn load_dependencies(&mut self, id: AssetId, path: &str, dependencies: Vec<String>) {
+        for dependency in dependencies {
+            match self.load_untyped(&dependency) {
+                Ok(handle) => {
+                    if !self.dependencies.add_dependency(id, handle.id()) {
+                        log::warn!("忽略循环资源依赖: {} -> {}", path, dependency);
+                    }
+                }
+                Err(e) => log::warn!("资源 {} 引用的 {} 加载失败: {}", path, dependency, e),
+            }
         }
     }
 
@@ -188,8 +389,9 @@ impl AssetManager {
     }
 
     /// 清空所有缓存
-    pub fn clear_cache(&self) {
+    pub fn clear_cache(&mut self) {
         self.cache.clear();
+        self.dependencies.clear();
     }
 
     /// 获取缓存统计
@@ -203,16 +405,56 @@ impl AssetManager {
     }
 
     /// 重新加载资源
+    ///
+    /// 已加载过的资源从文件重新读取后写入原槽位，已有的句柄直接看到新数据，代数加1；
+    /// 重新加载失败时保留旧资源并把状态置为失败。未加载过的资源按普通加载处理。
     pub fn reload<T: Send + Sync + 'static>(&mut self, path: impl AsRef<Path>) -> EngineResult<AssetHandle<T>> {
-        let path_str = path.as_ref().to_string_lossy().to_string();
-        
-        // 先从缓存中移除
-        if let Some(_) = self.cache.get_by_path::<T>(&path_str) {
-            // self.cache.remove_by_path(&path_str); // 需要实现这个方法
+        let raw_path = path.as_ref().to_string_lossy().into_owned();
+        let path_str = AssetVfs::normalize(&raw_path);
+        let Some(handle) = self.cache.handle_by_path::<T>(&path_str) else {
+            return self.load(&raw_path);
+        };
+
+        let full_path = self.vfs.resolve_or_default(&raw_path);
+        let extension = full_path.extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let result = match self.loaders.get(&extension) {
+            Some(loader) => loader.load(&full_path).and_then(|resource_any| {
+                let dependencies = loader.dependencies(resource_any.as_ref());
+                resource_any.downcast::<T>().map(|resource| (resource, dependencies)).map_err(|_| {
+                    EngineError::AssetError(format!("资源类型不匹配: {}", std::any::type_name::<T>())).into()
+                })
+            }),
+            None => Err(EngineError::AssetError(format!("没有找到扩展名 '{}' 的加载器", extension)).into()),
+        };
+
+        match result {
+            Ok((resource, dependencies)) => {
+                handle.slot().set_loaded(resource);
+                self.load_failures.remove(&path_str);
+                // 引用的资源可能已改变，重建依赖关系
+                for dependency in self.dependencies.dependencies_of(handle.id()) {
+                    self.dependencies.remove_dependency(handle.id(), dependency);
+                }
+                self.load_dependencies(handle.id(), &path_str, dependencies);
+                self.emit(AssetReloadedEvent {
+                    asset_path: path_str,
+                    asset_type: std::any::type_name::<T>().to_string(),
+                    generation: handle.generation(),
+                });
+                Ok(handle)
+            }
+            Err(e) => {
+                let error = format!("重新加载资源失败: {}", e);
+                handle.slot().set_failed(error.clone());
+                self.emit_asset_load_failed(&path_str, &error);
+                self.load_failures.insert(path_str, error);
+                Err(e)
+            }
         }
-        
-        // 重新加载
-        self.load(path)
     }
 
     /// 批量加载资源
@@ -248,26 +490,132 @@ impl AssetManager {
         results
     }
 
-    /// 发送资源加载成功事件
-    fn emit_asset_loaded(&self, path: &str, asset_type: &str) {
-        if let Some(event_system) = &self.event_system {
-            if let Ok(mut events) = event_system.write() {
-                events.publish(AssetLoadedEvent {
-                    asset_path: path.to_string(),
-                    asset_type: asset_type.to_string(),
-                });
+    /// 按扩展名对应加载器的资源类型加载，返回无类型句柄
+    pub fn load_untyped(&mut self, path: impl AsRef<Path>) -> EngineResult<UntypedAssetHandle> {
+        let path_str = path.as_ref().to_string_lossy().into_owned();
+        let extension = Path::new(&path_str).extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        match self.untyped_loaders.get(&extension).copied() {
+            Some(load) => load(self, &path_str),
+            None => {
+                let error = format!("没有找到扩展名 '{}' 的加载器", extension);
+                self.load_failures.insert(AssetVfs::normalize(&path_str), error.clone());
+                Err(EngineError::AssetError(error).into())
+            }
+        }
+    }
+
+    /// 读取资源清单(通过搜索路径解析)并预加载其中的所有资源
+    ///
+    /// 每处理完一个资源回调一次进度(0.0-1.0)，失败的资源记录在报告中，不中断其余资源的加载。
+    pub fn preload_manifest(&mut self, path: impl AsRef<Path>, progress: impl FnMut(f32)) -> EngineResult<PreloadReport> {
+        let manifest_path = self.vfs.resolve_or_default(&path.as_ref().to_string_lossy());
+        let manifest = AssetManifest::from_file(&manifest_path)?;
+        Ok(self.preload(&manifest, progress))
+    }
+
+    /// 预加载清单中的资源，已在缓存中的资源直接复用
+    ///
+    /// 未缓存的资源在工作线程中读取和解码，每完成一个就放入缓存并回调进度；
+    /// 报告中的句柄和失败按清单顺序排列。
+    pub fn preload(&mut self, manifest: &AssetManifest, mut progress: impl FnMut(f32)) -> PreloadReport {
+        let total = manifest.assets.len();
+        let mut results: Vec<Option<EngineResult<UntypedAssetHandle>>> = (0..total).map(|_| None).collect();
+        let mut completed = 0;
+        progress(0.0);
+
+        let mut pending = VecDeque::new();
+        for (index, asset_path) in manifest.assets.iter().enumerate() {
+            let cached = self.cache.id_of_path(&AssetVfs::normalize(asset_path)).is_some();
+            let result = if cached {
+                self.load_untyped(asset_path)
+            } else {
+                match self.pending_load(asset_path) {
+                    Ok(load) => {
+                        pending.push_back((index, load));
+                        continue;
+                    }
+                    Err(e) => Err(e),
+                }
+            };
+            results[index] = Some(result);
+            completed += 1;
+            progress(completed as f32 / total as f32);
+        }
+
+        // 工作线程从队列取资源解码，主线程按完成顺序放入缓存
+        let workers = std::thread::available_parallelism().map_or(1, |count| count.get()).min(pending.len());
+        let queue = Arc::new(Mutex::new(pending));
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..workers {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            std::thread::spawn(move || loop {
+                let Some((index, load)) = queue.lock().unwrap().pop_front() else {
+                    break;
+                };
+                let decoded = load.decode();
+                if sender.send((index, load.path, load.extension, decoded)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        for (index, path, extension, decoded) in receiver {
+            let result = match self.untyped_finishers.get(&extension).copied() {
+                Some(finish) => finish(self, path, decoded),
+                None => Err(EngineError::AssetError(format!("没有找到扩展名 '{}' 的加载器", extension)).into()),
+            };
+            results[index] = Some(result);
+            completed += 1;
+            progress(completed as f32 / total as f32);
+        }
+
+        let mut report = PreloadReport::default();
+        for (asset_path, result) in manifest.assets.iter().zip(results) {
+            match result {
+                Some(Ok(handle)) => report.handles.push(handle),
+                Some(Err(e)) => {
+                    log::warn!("预加载资源 {} 失败: {}", asset_path, e);
+                    report.failures.push((asset_path.clone(), e.to_string()));
+                }
+                None => report.failures.push((asset_path.clone(), "加载线程意外退出".to_string())),
             }
         }
+
+        if total == 0 {
+            progress(1.0);
+        }
+        report
+    }
+
+    /// 发送资源加载成功事件
+    fn emit_asset_loaded(&self, path: &str, asset_type: &str) {
+        self.emit(AssetLoadedEvent {
+            asset_path: path.to_string(),
+            asset_type: asset_type.to_string(),
+        });
     }
 
     /// 发送资源加载失败事件
     fn emit_asset_load_failed(&self, path: &str, error: &str) {
-        if let Some(event_system) = &self.event_system {
+        self.emit(AssetLoadFailedEvent {
+            asset_path: path.to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    /// 通过事件发送器或事件系统发布事件
+    fn emit<T: Event + 'static>(&self, event: T) {
+        if let Some(sender) = &self.event_sender {
+            sender.send(event);
+        } else if let Some(event_system) = &self.event_system {
             if let Ok(mut events) = event_system.write() {
-                events.publish(AssetLoadFailedEvent {
-                    asset_path: path.to_string(),
-                    error: error.to_string(),
-                });
+                events.publish(event);
             }
         }
     }
@@ -347,14 +695,18 @@ impl AssetLoader for MaterialLoader {
         let content = std::fs::read_to_string(path)
             .map_err(|e| EngineError::IoError(e))?;
 
-        // 简化的JSON材质加载
-        let material = Material::default();
+        let material: Material = serde_json::from_str(&content)
+            .map_err(|e| EngineError::SerializationError(e))?;
         Ok(material)
     }
 
     fn extensions(&self) -> &[&str] {
         &["json"]
     }
+
+    fn dependencies(&self, material: &Material) -> Vec<String> {
+        material_texture_paths(material)
+    }
 }
 
 impl Default for AssetManager {
@@ -362,3 +714,218 @@ impl Default for AssetManager {
         Self::new().unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::TextureSlot;
+
+    /// 把文本文件当作纹理的测试加载器
+    struct TextLoader;
+
+    impl AssetLoader for TextLoader {
+        type Asset = String;
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn load(&self, path: &Path) -> EngineResult<String> {
+            std::fs::read_to_string(path).map_err(|e| EngineError::IoError(e).into())
+        }
+    }
+
+    /// 在临时目录中写入一个引用两张纹理的材质
+    fn manager_with_material(name: &str) -> (AssetManager, PathBuf) {
+        let root = std::env::temp_dir().join(format!("sanji_assets_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("albedo.txt"), "albedo").unwrap();
+        std::fs::write(root.join("normal.txt"), "normal").unwrap();
+        let material = Material::default()
+            .with_texture(TextureSlot::BaseColor, "albedo.txt")
+            .with_texture(TextureSlot::Normal, "normal.txt");
+        std::fs::write(root.join("stone.json"), serde_json::to_string(&material).unwrap()).unwrap();
+
+        let mut manager = AssetManager::new().unwrap();
+        manager.set_asset_root(&root);
+        manager.register_loader("txt", TextLoader);
+        manager.register_loader("json", MaterialLoader);
+        (manager, root)
+    }
+
+    #[test]
+    fn loading_material_records_texture_dependencies() {
+        let (mut manager, root) = manager_with_material("deps");
+        let material = manager.load::<Material>("stone.json").unwrap();
+
+        assert!(manager.is_loaded("albedo.txt"));
+        assert!(manager.is_loaded("normal.txt"));
+        assert_eq!(manager.dependencies_of(material.id()).len(), 2);
+
+        // 材质仍引用纹理时不能单独卸载纹理
+        assert!(!manager.unload_by_path("albedo.txt"));
+        assert!(manager.unload_by_path("stone.json"));
+        assert!(manager.unload_by_path("albedo.txt"));
+        assert!(!manager.unload_by_path("albedo.txt"));
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn unload_cascade_frees_dependents() {
+        let (mut manager, root) = manager_with_material("cascade");
+        let material = manager.load::<Material>("stone.json").unwrap();
+        let albedo = manager.load::<String>("albedo.txt").unwrap();
+
+        assert_eq!(manager.unload_cascade(&albedo), 2);
+        assert!(!manager.is_loaded("stone.json"));
+        assert!(!manager.is_loaded("albedo.txt"));
+        assert!(manager.is_loaded("normal.txt"));
+        assert!(manager.dependencies_of(material.id()).is_empty());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn virtual_paths_resolve_through_search_paths() {
+        let (mut manager, root) = manager_with_material("vfs");
+        let overrides = root.join("overrides");
+        std::fs::create_dir_all(&overrides).unwrap();
+        std::fs::write(overrides.join("albedo.txt"), "override").unwrap();
+        manager.add_search_path(&overrides, 10);
+
+        let albedo = manager.load::<String>("res://albedo.txt").unwrap();
+        assert_eq!(albedo.get().unwrap().as_str(), "override");
+        // 虚拟路径与相对路径共用缓存键
+        assert!(manager.is_loaded("albedo.txt"));
+        assert_eq!(manager.resolve_path("normal.txt"), Some(root.join("normal.txt")));
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn unload_by_path_ignores_unknown_paths() {
+        let mut manager = AssetManager::new().unwrap();
+        assert!(!manager.unload_by_path("missing.png"));
+    }
+
+    #[test]
+    fn asset_state_reports_loaded_and_failed() {
+        let (mut manager, root) = manager_with_material("state");
+        assert_eq!(manager.asset_state("albedo.txt"), None);
+
+        let albedo = manager.load::<String>("albedo.txt").unwrap();
+        assert_eq!(manager.asset_state("albedo.txt"), Some(AssetState::Loaded));
+        // 再次加载返回共享槽位的句柄
+        let again = manager.load::<String>("albedo.txt").unwrap();
+        assert_eq!(again.id(), albedo.id());
+        assert_eq!(again.generation(), albedo.generation());
+
+        assert!(manager.load::<String>("missing.txt").is_err());
+        assert!(manager.asset_state("missing.txt").unwrap().is_failed());
+        assert!(manager.load::<String>("model.xyz").is_err());
+        assert!(manager.asset_state("model.xyz").unwrap().is_failed());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn preload_manifest_reports_progress_and_fills_cache() {
+        let (mut manager, root) = manager_with_material("preload");
+        let manifest = AssetManifest::new("level1")
+            .with_asset("albedo.txt")
+            .with_asset("normal.txt")
+            .with_asset("stone.json")
+            .with_asset("missing.txt");
+        std::fs::write(root.join("level1.json"), serde_json::to_string(&manifest).unwrap()).unwrap();
+
+        let mut progress = Vec::new();
+        let report = manager.preload_manifest("level1.json", |value| progress.push(value)).unwrap();
+
+        assert_eq!(progress, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(report.handles.len(), 3);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].0, "missing.txt");
+        assert!(!report.is_complete());
+        for path in ["albedo.txt", "normal.txt", "stone.json"] {
+            assert!(manager.is_loaded(path), "{} not loaded", path);
+        }
+        // 无类型句柄保留加载器的资源类型
+        assert_eq!(report.handles[2].typed::<Material>().unwrap().id(), report.loaded_ids()[2]);
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    /// 记录解码所在线程的测试加载器
+    struct ThreadRecordingLoader(Arc<Mutex<Vec<std::thread::ThreadId>>>);
+
+    impl AssetLoader for ThreadRecordingLoader {
+        type Asset = String;
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+
+        fn load(&self, path: &Path) -> EngineResult<String> {
+            self.0.lock().unwrap().push(std::thread::current().id());
+            TextLoader.load(path)
+        }
+    }
+
+    #[test]
+    fn preload_and_load_async_decode_on_worker_threads() {
+        let (mut manager, root) = manager_with_material("preload_async");
+        let threads = Arc::new(Mutex::new(Vec::new()));
+        manager.register_loader("txt", ThreadRecordingLoader(threads.clone()));
+
+        let manifest = AssetManifest::new("level2").with_asset("albedo.txt").with_asset("albedo.txt");
+        let mut progress = Vec::new();
+        let report = manager.preload(&manifest, |value| progress.push(value));
+        assert_eq!(progress, vec![0.0, 0.5, 1.0]);
+        // 重复路径共用同一个缓存槽位
+        assert_eq!(report.loaded_ids()[0], report.loaded_ids()[1]);
+
+        let normal = pollster::block_on(manager.load_async::<String>("normal.txt")).unwrap();
+        assert_eq!(normal.get().unwrap().as_str(), "normal");
+        let main_thread = std::thread::current().id();
+        assert!(threads.lock().unwrap().iter().all(|&id| id != main_thread));
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn empty_manifest_completes_immediately() {
+        let mut manager = AssetManager::new().unwrap();
+        let mut progress = Vec::new();
+        let report = manager.preload(&AssetManifest::new("empty"), |value| progress.push(value));
+        assert_eq!(progress, vec![0.0, 1.0]);
+        assert!(report.is_complete());
+        assert!(manager.preload_manifest("no_such_manifest.json", |_| {}).is_err());
+    }
+
+    #[test]
+    fn reload_updates_existing_handles_in_place() {
+        let (mut manager, root) = manager_with_material("reload");
+        let albedo = manager.load::<String>("albedo.txt").unwrap();
+        assert_eq!(albedo.generation(), 1);
+
+        std::fs::write(root.join("albedo.txt"), "albedo v2").unwrap();
+        let reloaded = manager.reload::<String>("albedo.txt").unwrap();
+
+        // 旧句柄无需重新获取即可看到新数据
+        assert_eq!(reloaded.id(), albedo.id());
+        assert_eq!(albedo.generation(), 2);
+        assert_eq!(albedo.get().unwrap().as_str(), "albedo v2");
+
+        // 重新加载失败时保留旧数据，代数不变
+        std::fs::remove_file(root.join("albedo.txt")).unwrap();
+        assert!(manager.reload::<String>("albedo.txt").is_err());
+        assert_eq!(albedo.generation(), 2);
+        assert_eq!(albedo.get().unwrap().as_str(), "albedo v2");
+        assert!(!albedo.is_valid());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn reload_of_unloaded_asset_loads_it() {
+        let (mut manager, root) = manager_with_material("reload_fresh");
+        let normal = manager.reload::<String>("normal.txt").unwrap();
+        assert_eq!(normal.generation(), 1);
+        assert!(manager.is_loaded("normal.txt"));
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,115 @@
+//! 资源清单
+//!
+//! 清单列出加载界面需要预先加载的资源路径，由 `AssetManager::preload_manifest` 逐个加载并报告进度。
+//! 支持json和yaml格式：
+//!
+//! ```json
+//! { "name": "level1", "assets": ["textures/ground.png", "meshes/tree.obj"] }
+//! ```
+
+use crate::{EngineError, EngineResult};
+use crate::assets::{AssetId, UntypedAssetHandle};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 资源清单
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssetManifest {
+    /// 清单名称
+    #[serde(default)]
+    pub name: String,
+    /// 资源路径(相对资源搜索路径，可使用虚拟路径前缀)
+    pub assets: Vec<String>,
+}
+
+impl AssetManifest {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), assets: Vec::new() }
+    }
+
+    pub fn with_asset(mut self, path: impl Into<String>) -> Self {
+        self.assets.push(path.into());
+        self
+    }
+
+    /// 按扩展名解析json或yaml清单
+    pub fn from_file(path: impl AsRef<Path>) -> EngineResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| EngineError::AssetError(format!("读取资源清单失败 {:?}: {}", path, e)))?;
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+
+        match extension.as_str() {
+            "json" => Ok(serde_json::from_str(&content)?),
+            "yaml" | "yml" => serde_yaml::from_str(&content)
+                .map_err(|e| anyhow::anyhow!("解析资源清单 {:?} 失败: {}", path, e)),
+            _ => Err(EngineError::AssetError(format!("不支持的资源清单格式: {:?}", path)).into()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+/// 预加载结果
+///
+/// 持有所有加载成功的资源句柄，引用计数缓存策略下丢弃报告会让预加载的资源在清理时被回收。
+#[derive(Debug, Default, Clone)]
+pub struct PreloadReport {
+    /// 加载成功的资源
+    pub handles: Vec<UntypedAssetHandle>,
+    /// 加载失败的路径及原因
+    pub failures: Vec<(String, String)>,
+}
+
+impl PreloadReport {
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    pub fn loaded_ids(&self) -> Vec<AssetId> {
+        self.handles.iter().map(|handle| handle.id()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sanji_manifest_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn json_and_yaml_manifests_parse() {
+        let json = temp_path("level.json");
+        std::fs::write(&json, r#"{ "name": "level1", "assets": ["textures/ground.png", "meshes/tree.obj"] }"#).unwrap();
+        let yaml = temp_path("level.yaml");
+        std::fs::write(&yaml, "assets:\n  - res://ui/logo.png\n").unwrap();
+
+        let manifest = AssetManifest::from_file(&json).unwrap();
+        assert_eq!(manifest, AssetManifest::new("level1").with_asset("textures/ground.png").with_asset("meshes/tree.obj"));
+
+        // 名称可省略
+        let manifest = AssetManifest::from_file(&yaml).unwrap();
+        assert_eq!(manifest.name, "");
+        assert_eq!(manifest.len(), 1);
+
+        let _ = std::fs::remove_file(json);
+        let _ = std::fs::remove_file(yaml);
+    }
+
+    #[test]
+    fn unsupported_or_missing_manifests_fail() {
+        let toml = temp_path("level.toml");
+        std::fs::write(&toml, "assets = []").unwrap();
+        assert!(AssetManifest::from_file(&toml).is_err());
+        assert!(AssetManifest::from_file(temp_path("missing.json")).is_err());
+        let _ = std::fs::remove_file(toml);
+    }
+}
@@ -0,0 +1,147 @@
+//! 资源虚拟文件系统
+//!
+//! 资源可以分布在多个搜索根目录中(游戏本体、Mod、共享库)，
+//! 虚拟路径 `res://models/x.glb` 按优先级从高到低在各根目录中查找，第一个存在的文件生效。
+
+use std::path::{Path, PathBuf};
+
+/// 虚拟路径前缀
+pub const VIRTUAL_PATH_PREFIX: &str = "res://";
+
+/// 资源搜索根目录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPath {
+    pub root: PathBuf,
+    /// 数值越大越先查找
+    pub priority: i32,
+}
+
+/// 资源虚拟文件系统
+#[derive(Debug, Clone, Default)]
+pub struct AssetVfs {
+    /// 按优先级从高到低排列，同优先级保持添加顺序
+    search_paths: Vec<SearchPath>,
+}
+
+impl AssetVfs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加搜索根目录，已存在时更新优先级
+    pub fn add_search_path(&mut self, root: impl Into<PathBuf>, priority: i32) {
+        let root = root.into();
+        self.search_paths.retain(|path| path.root != root);
+        let index = self.search_paths.partition_point(|path| path.priority >= priority);
+        self.search_paths.insert(index, SearchPath { root, priority });
+    }
+
+    /// 移除搜索根目录
+    pub fn remove_search_path(&mut self, root: impl AsRef<Path>) -> bool {
+        let root = root.as_ref();
+        let before = self.search_paths.len();
+        self.search_paths.retain(|path| path.root != root);
+        self.search_paths.len() != before
+    }
+
+    /// 所有搜索根目录(按查找顺序)
+    pub fn search_paths(&self) -> &[SearchPath] {
+        &self.search_paths
+    }
+
+    /// 是否为虚拟路径
+    pub fn is_virtual(path: &str) -> bool {
+        path.starts_with(VIRTUAL_PATH_PREFIX)
+    }
+
+    /// 去掉虚拟路径前缀，得到相对于搜索根目录的路径，用作缓存键
+    pub fn normalize(path: &str) -> String {
+        match path.strip_prefix(VIRTUAL_PATH_PREFIX) {
+            Some(relative) => relative.trim_start_matches('/').replace('\\', "/"),
+            None => path.replace('\\', "/"),
+        }
+    }
+
+    /// 按优先级解析路径，返回第一个存在的文件
+    ///
+    /// 绝对路径直接返回，不经过搜索根目录。
+    pub fn resolve(&self, path: &str) -> Option<PathBuf> {
+        if !Self::is_virtual(path) && Path::new(path).is_absolute() {
+            return Some(PathBuf::from(path));
+        }
+
+        let relative = Self::normalize(path);
+        self.search_paths
+            .iter()
+            .map(|search_path| search_path.root.join(&relative))
+            .find(|candidate| candidate.exists())
+    }
+
+    /// 解析路径，找不到时返回最高优先级根目录下的路径，便于加载器报告具体的缺失文件
+    pub fn resolve_or_default(&self, path: &str) -> PathBuf {
+        self.resolve(path).unwrap_or_else(|| {
+            let relative = Self::normalize(path);
+            match self.search_paths.first() {
+                Some(search_path) => search_path.root.join(relative),
+                None => PathBuf::from(relative),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 创建两个根目录，都包含同名文件 `models/x.txt`，base另有 `base_only.txt`
+    fn two_roots(name: &str) -> (PathBuf, PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("sanji_vfs_{}_{}", name, std::process::id()));
+        let (base, mods) = (dir.join("base"), dir.join("mods"));
+        for root in [&base, &mods] {
+            std::fs::create_dir_all(root.join("models")).unwrap();
+            std::fs::write(root.join("models/x.txt"), "x").unwrap();
+        }
+        std::fs::write(base.join("base_only.txt"), "base").unwrap();
+        (dir, base, mods)
+    }
+
+    #[test]
+    fn higher_priority_root_shadows_lower() {
+        let (dir, base, mods) = two_roots("shadow");
+        let mut vfs = AssetVfs::new();
+        vfs.add_search_path(&base, 0);
+        vfs.add_search_path(&mods, 10);
+
+        assert_eq!(vfs.resolve("res://models/x.txt"), Some(mods.join("models/x.txt")));
+        assert_eq!(vfs.resolve("models/x.txt"), Some(mods.join("models/x.txt")));
+        // 高优先级根目录中没有的文件回退到低优先级
+        assert_eq!(vfs.resolve("res://base_only.txt"), Some(base.join("base_only.txt")));
+        assert_eq!(vfs.resolve("res://missing.txt"), None);
+        assert_eq!(vfs.resolve_or_default("res://missing.txt"), mods.join("missing.txt"));
+
+        vfs.remove_search_path(&mods);
+        assert_eq!(vfs.resolve("res://models/x.txt"), Some(base.join("models/x.txt")));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn search_paths_are_ordered_by_priority() {
+        let mut vfs = AssetVfs::new();
+        vfs.add_search_path("a", 0);
+        vfs.add_search_path("b", 5);
+        vfs.add_search_path("c", 0);
+        // 重复添加时更新优先级
+        vfs.add_search_path("a", 10);
+
+        let roots: Vec<_> = vfs.search_paths().iter().map(|path| path.root.to_str().unwrap()).collect();
+        assert_eq!(roots, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn virtual_prefix_is_normalized() {
+        assert!(AssetVfs::is_virtual("res://a.png"));
+        assert!(!AssetVfs::is_virtual("a.png"));
+        assert_eq!(AssetVfs::normalize("res:///models\\x.glb"), "models/x.glb");
+        assert_eq!(AssetVfs::normalize("models/x.glb"), "models/x.glb");
+    }
+}
@@ -4,8 +4,16 @@ pub mod asset_manager;
 pub mod asset_loader;
 pub mod asset_cache;
 pub mod asset_handle;
+pub mod asset_dependency;
+pub mod asset_vfs;
+pub mod asset_manifest;
+pub mod asset_import;
 
 pub use asset_manager::*;
 pub use asset_loader::{AssetLoader, AssetLoaderRegistry, ErasedAssetLoader};
 pub use asset_cache::*;
 pub use asset_handle::*;
+pub use asset_dependency::*;
+pub use asset_vfs::*;
+pub use asset_manifest::*;
+pub use asset_import::*;
@@ -0,0 +1,475 @@
+//! 音频效果(DSP)
+//!
+//! 效果按顺序组成 `AudioEffectChain`，可挂在单个音频源或 `AudioBus` 上，例如水下的低通滤波、房间混响。
+//! 样本为交错排列的f32，声道数在创建效果时指定。
+
+use std::f32::consts::PI;
+use std::fmt;
+
+/// 音频效果
+pub trait AudioEffect: Send + Sync {
+    /// 效果名称
+    fn name(&self) -> &str;
+
+    /// 原地处理一段交错样本
+    fn process(&mut self, samples: &mut [f32]);
+
+    /// 清除内部状态(尾音、滤波器历史)
+    fn reset(&mut self) {}
+}
+
+/// 一阶低通滤波器
+#[derive(Debug, Clone)]
+pub struct LowPass {
+    cutoff_hz: f32,
+    sample_rate: u32,
+    channels: usize,
+    /// 平滑系数，由截止频率计算
+    alpha: f32,
+    /// 每个声道上一次的输出
+    previous: Vec<f32>,
+}
+
+impl LowPass {
+    pub fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        let mut filter = Self {
+            cutoff_hz,
+            sample_rate: sample_rate.max(1),
+            channels: 1,
+            alpha: 1.0,
+            previous: vec![0.0],
+        };
+        filter.set_cutoff(cutoff_hz);
+        filter
+    }
+
+    pub fn with_channels(mut self, channels: u16) -> Self {
+        self.channels = channels.max(1) as usize;
+        self.previous = vec![0.0; self.channels];
+        self
+    }
+
+    pub fn cutoff(&self) -> f32 {
+        self.cutoff_hz
+    }
+
+    /// 设置截止频率(限制在奈奎斯特频率以内)
+    pub fn set_cutoff(&mut self, cutoff_hz: f32) {
+        let nyquist = self.sample_rate as f32 * 0.5;
+        self.cutoff_hz = cutoff_hz.clamp(1.0, nyquist);
+        let rc = 1.0 / (2.0 * PI * self.cutoff_hz);
+        let dt = 1.0 / self.sample_rate as f32;
+        self.alpha = dt / (rc + dt);
+    }
+}
+
+impl AudioEffect for LowPass {
+    fn name(&self) -> &str {
+        "LowPass"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_mut(self.channels) {
+            for (sample, previous) in frame.iter_mut().zip(self.previous.iter_mut()) {
+                *previous += self.alpha * (*sample - *previous);
+                *sample = *previous;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.previous.iter_mut().for_each(|value| *value = 0.0);
+    }
+}
+
+/// 带阻尼的反馈梳状滤波器
+#[derive(Debug, Clone)]
+struct CombFilter {
+    buffer: Vec<f32>,
+    index: usize,
+    filter_store: f32,
+}
+
+impl CombFilter {
+    fn new(length: usize) -> Self {
+        Self { buffer: vec![0.0; length.max(1)], index: 0, filter_store: 0.0 }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        self.buffer[self.index] = input + self.filter_store * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+
+    fn clear(&mut self) {
+        self.buffer.iter_mut().for_each(|value| *value = 0.0);
+        self.filter_store = 0.0;
+    }
+}
+
+/// 全通滤波器，用于扩散回声
+#[derive(Debug, Clone)]
+struct AllPassFilter {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl AllPassFilter {
+    const FEEDBACK: f32 = 0.5;
+
+    fn new(length: usize) -> Self {
+        Self { buffer: vec![0.0; length.max(1)], index: 0 }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.index];
+        let output = buffered - input;
+        self.buffer[self.index] = input + buffered * Self::FEEDBACK;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+
+    fn clear(&mut self) {
+        self.buffer.iter_mut().for_each(|value| *value = 0.0);
+    }
+}
+
+/// 单个声道的混响网络
+#[derive(Debug, Clone)]
+struct ReverbChannel {
+    combs: Vec<CombFilter>,
+    all_passes: Vec<AllPassFilter>,
+}
+
+/// Schroeder/Freeverb式混响：并联梳状滤波器 + 串联全通滤波器
+#[derive(Debug, Clone)]
+pub struct Reverb {
+    /// 房间大小(0-1)，决定尾音长度
+    pub room_size: f32,
+    /// 高频阻尼(0-1)，越大尾音越闷
+    pub damping: f32,
+    /// 湿声比例
+    pub wet: f32,
+    /// 干声比例
+    pub dry: f32,
+    sample_rate: u32,
+    channels: Vec<ReverbChannel>,
+}
+
+impl Reverb {
+    /// 44.1kHz下的梳状滤波器延迟(样本)
+    const COMB_TUNING: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+    const ALL_PASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+    /// 声道间的延迟差，产生立体声宽度
+    const STEREO_SPREAD: usize = 23;
+
+    pub fn new(sample_rate: u32) -> Self {
+        let mut reverb = Self {
+            room_size: 0.5,
+            damping: 0.5,
+            wet: 0.3,
+            dry: 1.0,
+            sample_rate: sample_rate.max(1),
+            channels: Vec::new(),
+        };
+        reverb.configure(1);
+        reverb
+    }
+
+    /// 房间混响预设
+    pub fn room(sample_rate: u32) -> Self {
+        Self::new(sample_rate).with_room_size(0.6).with_damping(0.4).with_wet(0.3)
+    }
+
+    /// 大厅混响预设
+    pub fn hall(sample_rate: u32) -> Self {
+        Self::new(sample_rate).with_room_size(0.85).with_damping(0.3).with_wet(0.45)
+    }
+
+    pub fn with_channels(mut self, channels: u16) -> Self {
+        self.configure(channels.max(1) as usize);
+        self
+    }
+
+    pub fn with_room_size(mut self, room_size: f32) -> Self {
+        self.room_size = room_size.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_wet(mut self, wet: f32) -> Self {
+        self.wet = wet.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_dry(mut self, dry: f32) -> Self {
+        self.dry = dry.clamp(0.0, 1.0);
+        self
+    }
+
+    fn configure(&mut self, channels: usize) {
+        let scale = self.sample_rate as f32 / 44100.0;
+        let scaled = |samples: usize| ((samples as f32 * scale) as usize).max(1);
+
+        self.channels = (0..channels)
+            .map(|channel| {
+                let spread = Self::STEREO_SPREAD * channel;
+                ReverbChannel {
+                    combs: Self::COMB_TUNING.iter().map(|&length| CombFilter::new(scaled(length + spread))).collect(),
+                    all_passes: Self::ALL_PASS_TUNING
+                        .iter()
+                        .map(|&length| AllPassFilter::new(scaled(length + spread)))
+                        .collect(),
+                }
+            })
+            .collect();
+    }
+}
+
+impl AudioEffect for Reverb {
+    fn name(&self) -> &str {
+        "Reverb"
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        let feedback = 0.7 + self.room_size * 0.28;
+        let damping = self.damping * 0.4;
+        // 并联梳状滤波器的输入增益，防止叠加后溢出
+        let input_gain = 0.015;
+        let channel_count = self.channels.len();
+
+        for frame in samples.chunks_mut(channel_count) {
+            for (sample, channel) in frame.iter_mut().zip(self.channels.iter_mut()) {
+                let input = *sample * input_gain;
+                let mut wet: f32 = channel.combs.iter_mut().map(|comb| comb.process(input, feedback, damping)).sum();
+                for all_pass in channel.all_passes.iter_mut() {
+                    wet = all_pass.process(wet);
+                }
+                *sample = *sample * self.dry + wet * self.wet;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        for channel in self.channels.iter_mut() {
+            channel.combs.iter_mut().for_each(CombFilter::clear);
+            channel.all_passes.iter_mut().for_each(AllPassFilter::clear);
+        }
+    }
+}
+
+/// 效果链，按添加顺序依次处理
+#[derive(Default)]
+pub struct AudioEffectChain {
+    effects: Vec<Box<dyn AudioEffect>>,
+    /// 旁通时不做任何处理
+    pub bypass: bool,
+}
+
+impl AudioEffectChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_effect(mut self, effect: impl AudioEffect + 'static) -> Self {
+        self.push(effect);
+        self
+    }
+
+    pub fn push(&mut self, effect: impl AudioEffect + 'static) {
+        self.effects.push(Box::new(effect));
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Box<dyn AudioEffect>> {
+        (index < self.effects.len()).then(|| self.effects.remove(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    pub fn effect_names(&self) -> Vec<&str> {
+        self.effects.iter().map(|effect| effect.name()).collect()
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if self.bypass {
+            return;
+        }
+        for effect in self.effects.iter_mut() {
+            effect.process(samples);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for effect in self.effects.iter_mut() {
+            effect.reset();
+        }
+    }
+}
+
+impl fmt::Debug for AudioEffectChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioEffectChain")
+            .field("effects", &self.effect_names())
+            .field("bypass", &self.bypass)
+            .finish()
+    }
+}
+
+/// 音频总线：汇总多个音频源后统一施加效果和音量
+#[derive(Debug)]
+pub struct AudioBus {
+    pub name: String,
+    pub volume: f32,
+    pub muted: bool,
+    pub effects: AudioEffectChain,
+}
+
+impl AudioBus {
+    /// 主总线名称，未指定总线的音频源都输出到这里
+    pub const MASTER: &'static str = "master";
+
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            volume: 1.0,
+            muted: false,
+            effects: AudioEffectChain::new(),
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_effect(mut self, effect: impl AudioEffect + 'static) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// 处理总线上汇总后的样本
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if self.muted {
+            samples.iter_mut().for_each(|sample| *sample = 0.0);
+            return;
+        }
+        self.effects.process(samples);
+        if self.volume != 1.0 {
+            samples.iter_mut().for_each(|sample| *sample *= self.volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    fn sine(frequency: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| (2.0 * PI * frequency * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    /// 跳过起始瞬态后的峰值
+    fn peak(samples: &[f32]) -> f32 {
+        samples[samples.len() / 2..].iter().fold(0.0f32, |max, sample| max.max(sample.abs()))
+    }
+
+    #[test]
+    fn low_pass_attenuates_high_frequencies_more() {
+        let mut low = sine(100.0, 4410);
+        let mut high = sine(8000.0, 4410);
+
+        LowPass::new(500.0, SAMPLE_RATE).process(&mut low);
+        LowPass::new(500.0, SAMPLE_RATE).process(&mut high);
+
+        assert!(peak(&low) > 0.9);
+        assert!(peak(&high) < 0.2);
+        assert!(peak(&high) < peak(&low) * 0.25);
+    }
+
+    #[test]
+    fn low_pass_cutoff_is_clamped_to_nyquist() {
+        let mut filter = LowPass::new(100_000.0, SAMPLE_RATE);
+        assert_eq!(filter.cutoff(), SAMPLE_RATE as f32 * 0.5);
+        filter.set_cutoff(0.0);
+        assert_eq!(filter.cutoff(), 1.0);
+    }
+
+    #[test]
+    fn stereo_low_pass_filters_channels_independently() {
+        let mut filter = LowPass::new(500.0, SAMPLE_RATE).with_channels(2);
+        // 左声道恒为1，右声道恒为0
+        let mut samples: Vec<f32> = (0..2000).flat_map(|_| [1.0, 0.0]).collect();
+        filter.process(&mut samples);
+
+        assert!((samples[samples.len() - 2] - 1.0).abs() < 1e-3);
+        assert_eq!(samples[samples.len() - 1], 0.0);
+    }
+
+    #[test]
+    fn reverb_adds_tail_after_impulse() {
+        let mut reverb = Reverb::room(SAMPLE_RATE).with_dry(0.0);
+        let mut samples = vec![0.0; SAMPLE_RATE as usize / 2];
+        samples[0] = 1.0;
+        reverb.process(&mut samples);
+
+        // 最短梳状延迟之后出现尾音，且保持有界
+        assert!(samples[2000..].iter().any(|sample| sample.abs() > 1e-4));
+        assert!(samples.iter().all(|sample| sample.is_finite() && sample.abs() < 1.0));
+
+        reverb.reset();
+        let mut silence = vec![0.0; 4410];
+        reverb.process(&mut silence);
+        assert!(silence.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn chain_runs_effects_in_order_and_respects_bypass() {
+        let mut chain = AudioEffectChain::new()
+            .with_effect(LowPass::new(500.0, SAMPLE_RATE))
+            .with_effect(Reverb::new(SAMPLE_RATE));
+        assert_eq!(chain.effect_names(), vec!["LowPass", "Reverb"]);
+
+        chain.bypass = true;
+        let original = sine(8000.0, 512);
+        let mut samples = original.clone();
+        chain.process(&mut samples);
+        assert_eq!(samples, original);
+
+        assert_eq!(chain.remove(0).map(|effect| effect.name().to_string()), Some("LowPass".to_string()));
+        assert!(chain.remove(5).is_none());
+        assert_eq!(chain.len(), 1);
+    }
+
+    #[test]
+    fn bus_applies_volume_and_mute() {
+        let mut bus = AudioBus::new("sfx").with_volume(0.5);
+        let mut samples = vec![1.0, -1.0];
+        bus.process(&mut samples);
+        assert_eq!(samples, vec![0.5, -0.5]);
+
+        bus.muted = true;
+        let mut samples = vec![1.0, -1.0];
+        bus.process(&mut samples);
+        assert_eq!(samples, vec![0.0, 0.0]);
+    }
+}
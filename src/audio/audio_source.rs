@@ -33,6 +33,9 @@ pub struct AudioSource {
     pub spread: f32,
     /// 优先级 (0 = 最高优先级, 256 = 最低优先级)
     pub priority: u8,
+    /// 输出的音频总线，总线上的效果(混响、低通)会作用于该音频源
+    #[serde(default = "default_output_bus")]
+    pub output_bus: String,
     /// 是否正在播放
     #[serde(skip)]
     pub is_playing: bool,
@@ -59,6 +62,7 @@ impl Default for AudioSource {
             doppler_level: 1.0,
             spread: 0.0,
             priority: 128,
+            output_bus: default_output_bus(),
             is_playing: false,
             is_paused: false,
             time: 0.0,
@@ -66,6 +70,10 @@ impl Default for AudioSource {
     }
 }
 
+fn default_output_bus() -> String {
+    crate::audio::AudioBus::MASTER.to_string()
+}
+
 /// 音频衰减模式
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum AudioRolloffMode {
@@ -156,6 +164,11 @@ impl AudioSource {
     }
 
     /// 设置多普勒效应等级
+    pub fn with_output_bus(mut self, bus: impl Into<String>) -> Self {
+        self.output_bus = bus.into();
+        self
+    }
+
     pub fn with_doppler_level(mut self, level: f32) -> Self {
         self.doppler_level = level.clamp(0.0, 5.0);
         self
@@ -1,7 +1,7 @@
 //! 音频系统实现
 
 use crate::{EngineResult, EngineError};
-use crate::audio::{AudioSource, AudioListener};
+use crate::audio::{AudioSource, AudioListener, AudioBus, AudioEffect, AudioEffectChain};
 use crate::math::Vec3;
 
 use std::collections::HashMap;
@@ -140,6 +140,8 @@ pub struct AudioSystem {
     active_sources: HashMap<Entity, AudioSourceState>,
     /// 音频监听器
     listener: AudioListener,
+    /// 音频总线，始终包含主总线
+    buses: HashMap<String, AudioBus>,
     /// 是否初始化
     initialized: bool,
     /// 是否静音
@@ -157,6 +159,27 @@ struct AudioSourceState {
     looping: bool,
     position_3d: Option<Vec3>,
     velocity_3d: Option<Vec3>,
+    /// 该音频源自身的效果链
+    effects: AudioEffectChain,
+    /// 输出到的总线
+    bus: String,
+}
+
+impl AudioSourceState {
+    fn new(clip: Arc<AudioClip>, volume: f32) -> Self {
+        Self {
+            clip,
+            position: 0,
+            state: PlaybackState::Playing,
+            volume,
+            pitch: 1.0,
+            looping: false,
+            position_3d: None,
+            velocity_3d: None,
+            effects: AudioEffectChain::new(),
+            bus: AudioBus::MASTER.to_string(),
+        }
+    }
 }
 
 impl AudioSystem {
@@ -167,6 +190,7 @@ impl AudioSystem {
             clips: HashMap::new(),
             active_sources: HashMap::new(),
             listener: AudioListener::new(),
+            buses: HashMap::from([(AudioBus::MASTER.to_string(), AudioBus::new(AudioBus::MASTER))]),
             initialized: false,
             muted: false,
         };
@@ -206,16 +230,7 @@ impl AudioSystem {
             .ok_or_else(|| EngineError::AssetError(format!("音频剪辑未找到: {}", clip_name)))?
             .clone();
 
-        let source_state = AudioSourceState {
-            clip,
-            position: 0,
-            state: PlaybackState::Playing,
-            volume: 1.0,
-            pitch: 1.0,
-            looping: false,
-            position_3d: None,
-            velocity_3d: None,
-        };
+        let source_state = AudioSourceState::new(clip, 1.0);
 
         self.active_sources.insert(entity, source_state);
         log::debug!("开始播放音频: {} (实体: {:?})", clip_name, entity);
@@ -231,16 +246,7 @@ impl AudioSystem {
         // Create a temporary entity ID for one-shot audio
         let temp_entity_id = rand::random::<u32>();
         
-        let source_state = AudioSourceState {
-            clip: clip.clone(),
-            position: 0,
-            state: PlaybackState::Playing,
-            volume,
-            pitch: 1.0,
-            looping: false,
-            position_3d: None,
-            velocity_3d: None,
-        };
+        let source_state = AudioSourceState::new(clip.clone(), volume);
 
         // Use temporary workaround for entity insertion
         // TODO: Properly handle entity creation for one-shot audio
@@ -343,6 +349,121 @@ impl AudioSystem {
         Ok(())
     }
 
+    /// 添加音频总线，同名总线会被替换
+    pub fn add_bus(&mut self, bus: AudioBus) {
+        self.buses.insert(bus.name.clone(), bus);
+    }
+
+    /// 移除音频总线，输出到该总线的音频源改为输出到主总线
+    pub fn remove_bus(&mut self, name: &str) -> Option<AudioBus> {
+        if name == AudioBus::MASTER {
+            return None;
+        }
+        let bus = self.buses.remove(name)?;
+        for source in self.active_sources.values_mut().filter(|source| source.bus == name) {
+            source.bus = AudioBus::MASTER.to_string();
+        }
+        Some(bus)
+    }
+
+    pub fn bus(&self, name: &str) -> Option<&AudioBus> {
+        self.buses.get(name)
+    }
+
+    pub fn bus_mut(&mut self, name: &str) -> Option<&mut AudioBus> {
+        self.buses.get_mut(name)
+    }
+
+    /// 设置音频源输出的总线
+    pub fn set_source_bus(&mut self, entity: Entity, bus_name: &str) -> EngineResult<()> {
+        if !self.buses.contains_key(bus_name) {
+            return Err(EngineError::AssetError(format!("音频总线未找到: {}", bus_name)).into());
+        }
+        if let Some(source) = self.active_sources.get_mut(&entity) {
+            source.bus = bus_name.to_string();
+        }
+        Ok(())
+    }
+
+    /// 为音频源添加效果
+    pub fn add_source_effect(&mut self, entity: Entity, effect: impl AudioEffect + 'static) {
+        if let Some(source) = self.active_sources.get_mut(&entity) {
+            source.effects.push(effect);
+        }
+    }
+
+    /// 音频源的效果链
+    pub fn source_effects_mut(&mut self, entity: Entity) -> Option<&mut AudioEffectChain> {
+        self.active_sources.get_mut(&entity).map(|source| &mut source.effects)
+    }
+
+    /// 混合当前播放中的音频源到单声道输出缓冲
+    ///
+    /// 每个音频源从当前播放位置读取 `output.len()` 个样本(多声道剪辑取平均)，经自身效果链后按音量
+    /// 累加到所属总线；各总线处理后汇总到主总线，最后乘以主音量。播放位置由 `update` 推进。
+    pub fn mix(&mut self, output: &mut [f32]) {
+        output.iter_mut().for_each(|sample| *sample = 0.0);
+        if !self.initialized || self.muted {
+            return;
+        }
+
+        let frames = output.len();
+        let mut bus_buffers: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut source_buffer = vec![0.0; frames];
+
+        for source in self.active_sources.values_mut() {
+            if source.state != PlaybackState::Playing {
+                continue;
+            }
+
+            let channels = source.clip.channels.max(1) as usize;
+            let total_frames = source.clip.data.len() / channels;
+            if total_frames == 0 {
+                continue;
+            }
+            let start_frame = source.position / channels;
+            let looping = source.looping || source.clip.looping;
+            for (i, sample) in source_buffer.iter_mut().enumerate() {
+                let mut frame = start_frame + i;
+                if frame >= total_frames {
+                    if !looping {
+                        *sample = 0.0;
+                        continue;
+                    }
+                    frame %= total_frames;
+                }
+                let frame_samples = &source.clip.data[frame * channels..(frame + 1) * channels];
+                *sample = frame_samples.iter().sum::<f32>() / channels as f32;
+            }
+
+            source.effects.process(&mut source_buffer);
+
+            let bus = if self.buses.contains_key(&source.bus) { source.bus.as_str() } else { AudioBus::MASTER };
+            let bus_buffer = bus_buffers.entry(bus.to_string()).or_insert_with(|| vec![0.0; frames]);
+            for (mixed, sample) in bus_buffer.iter_mut().zip(source_buffer.iter()) {
+                *mixed += sample * source.volume;
+            }
+        }
+
+        // 子总线先处理，再汇入主总线
+        let mut master_buffer = bus_buffers.remove(AudioBus::MASTER).unwrap_or_else(|| vec![0.0; frames]);
+        for (name, mut buffer) in bus_buffers {
+            if let Some(bus) = self.buses.get_mut(&name) {
+                bus.process(&mut buffer);
+            }
+            for (mixed, sample) in master_buffer.iter_mut().zip(buffer.iter()) {
+                *mixed += sample;
+            }
+        }
+        if let Some(master) = self.buses.get_mut(AudioBus::MASTER) {
+            master.process(&mut master_buffer);
+        }
+
+        for (sample, mixed) in output.iter_mut().zip(master_buffer.iter()) {
+            *sample = mixed * self.config.master_volume;
+        }
+    }
+
     /// 设置监听器位置
     pub fn set_listener_position(&mut self, position: Vec3) {
         self.listener.set_position(position);
@@ -455,3 +576,78 @@ impl Default for AudioSystem {
         Self::new(AudioConfig::default()).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::LowPass;
+    use specs::{Builder, World, WorldExt};
+
+    /// 恒定值的单声道循环剪辑
+    fn constant_clip(name: &str, value: f32) -> AudioClip {
+        let mut clip = AudioClip::new(name, vec![value; 64], 44100, 1);
+        clip.looping = true;
+        clip
+    }
+
+    #[test]
+    fn bus_volume_applies_to_routed_sources() {
+        let mut world = World::new();
+        let music = world.create_entity().build();
+        let sfx = world.create_entity().build();
+
+        let mut audio = AudioSystem::default();
+        audio.add_clip(constant_clip("music", 0.5));
+        audio.add_clip(constant_clip("sfx", 0.25));
+        audio.add_bus(AudioBus::new("music").with_volume(0.5));
+        audio.play_clip("music", music).unwrap();
+        audio.play_clip("sfx", sfx).unwrap();
+        audio.set_source_bus(music, "music").unwrap();
+        assert!(audio.set_source_bus(sfx, "missing").is_err());
+
+        let mut output = vec![0.0; 16];
+        audio.mix(&mut output);
+        // 音乐经总线减半后与直接输出到主总线的音效相加
+        assert!(output.iter().all(|&sample| (sample - 0.5).abs() < 1e-6));
+
+        audio.bus_mut("music").unwrap().muted = true;
+        audio.mix(&mut output);
+        assert!(output.iter().all(|&sample| (sample - 0.25).abs() < 1e-6));
+    }
+
+    #[test]
+    fn removing_bus_reroutes_to_master() {
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+
+        let mut audio = AudioSystem::default();
+        audio.add_clip(constant_clip("tone", 1.0));
+        audio.add_bus(AudioBus::new("quiet").with_volume(0.0));
+        audio.play_clip("tone", entity).unwrap();
+        audio.set_source_bus(entity, "quiet").unwrap();
+        assert!(audio.remove_bus(AudioBus::MASTER).is_none());
+        assert!(audio.remove_bus("quiet").is_some());
+
+        let mut output = vec![0.0; 8];
+        audio.mix(&mut output);
+        assert!(output.iter().all(|&sample| (sample - 1.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn source_effects_process_before_bus() {
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+
+        let mut audio = AudioSystem::default();
+        // 奈奎斯特频率的方波，低通后几乎被消除
+        let data = (0..256).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 }).collect();
+        audio.add_clip(AudioClip::new("buzz", data, 44100, 1));
+        audio.play_clip("buzz", entity).unwrap();
+        audio.add_source_effect(entity, LowPass::new(200.0, 44100));
+        assert_eq!(audio.source_effects_mut(entity).unwrap().len(), 1);
+
+        let mut output = vec![0.0; 256];
+        audio.mix(&mut output);
+        assert!(output[128..].iter().all(|sample| sample.abs() < 0.1));
+    }
+}
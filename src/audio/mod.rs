@@ -3,7 +3,9 @@
 pub mod audio_system;
 pub mod audio_source;
 pub mod audio_listener;
+pub mod audio_effect;
 
 pub use audio_system::*;
 pub use audio_source::*;
 pub use audio_listener::*;
+pub use audio_effect::*;
@@ -1,6 +1,6 @@
 //! 应用程序抽象层
 
-use crate::{EngineConfig, EngineResult, Engine};
+use crate::{EngineConfig, EngineResult, Engine, FileLogConfig};
 
 /// 游戏应用程序trait
 pub trait App {
@@ -29,6 +29,7 @@ pub trait App {
 pub struct AppBuilder<T: App> {
     app: T,
     config: Option<EngineConfig>,
+    file_log: Option<FileLogConfig>,
 }
 
 impl<T: App> AppBuilder<T> {
@@ -37,6 +38,7 @@ impl<T: App> AppBuilder<T> {
         Self {
             app,
             config: None,
+            file_log: None,
         }
     }
 
@@ -63,14 +65,23 @@ impl<T: App> AppBuilder<T> {
         self
     }
 
+    /// 除控制台外同时写入轮转日志文件
+    pub fn with_file_log(mut self, file_log: FileLogConfig) -> Self {
+        self.file_log = Some(file_log);
+        self
+    }
+
     /// 运行应用程序
     pub fn run(mut self) -> EngineResult<()> {
         let config = self.config.unwrap_or_else(|| self.app.config());
         
         // 初始化日志
-        env_logger::Builder::from_default_env()
-            .filter_level(log::LevelFilter::Info)
-            .init();
+        match self.file_log.take() {
+            Some(file_log) => crate::core::init_env_logging_with_file(file_log)?,
+            None => env_logger::Builder::from_default_env()
+                .filter_level(log::LevelFilter::Info)
+                .init(),
+        }
 
         log::info!("启动应用程序: {}", config.window.title);
         
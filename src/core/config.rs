@@ -0,0 +1,254 @@
+//! 引擎配置分层
+//!
+//! 最终的 `EngineConfig` 按 默认值 < 配置文件 < 环境变量 < 代码覆盖 的优先级合并，与调用顺序无关。
+//! 配置文件(toml/json/yaml)只需写出要修改的字段；环境变量形如 `SANJI_WINDOW_WIDTH=1280`，
+//! 前缀之后的 `<节>_<字段>` 定位配置项，字符串字段直接取原值，其他字段按JSON解析。
+
+use crate::{EngineConfig, EngineError, EngineResult};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// 环境变量前缀
+pub const CONFIG_ENV_PREFIX: &str = "SANJI_";
+
+/// 代码覆盖
+type ConfigOverride = Box<dyn FnOnce(&mut EngineConfig)>;
+
+/// 分层配置构建器
+#[derive(Default)]
+pub struct EngineConfigBuilder {
+    file: Option<PathBuf>,
+    /// None表示不读取环境变量，Some(None)读取进程环境，Some(Some(vars))使用给定变量
+    env: Option<Option<Vec<(String, String)>>>,
+    overrides: Vec<ConfigOverride>,
+}
+
+impl EngineConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 配置文件层，按扩展名解析为toml、json或yaml
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// 环境变量层，构建时读取进程环境中以 `SANJI_` 开头的变量
+    pub fn with_env(mut self) -> Self {
+        self.env = Some(None);
+        self
+    }
+
+    /// 使用给定的变量代替进程环境(工具和脚本中使用)
+    pub fn with_env_vars<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = Some(Some(vars.into_iter().map(|(key, value)| (key.into(), value.into())).collect()));
+        self
+    }
+
+    /// 代码覆盖层，按添加顺序在最后应用
+    pub fn with_override(mut self, apply: impl FnOnce(&mut EngineConfig) + 'static) -> Self {
+        self.overrides.push(Box::new(apply));
+        self
+    }
+
+    /// 合并所有层得到最终配置
+    pub fn build(self) -> EngineResult<EngineConfig> {
+        let mut value = serde_json::to_value(EngineConfig::default())?;
+
+        if let Some(path) = &self.file {
+            merge_values(&mut value, read_config_file(path)?);
+        }
+
+        match self.env {
+            Some(Some(vars)) => apply_env_overrides(&mut value, vars),
+            Some(None) => apply_env_overrides(&mut value, std::env::vars()),
+            None => {}
+        }
+
+        let mut config: EngineConfig = serde_json::from_value(value)
+            .map_err(|e| anyhow::anyhow!("引擎配置无效: {}", e))?;
+
+        for apply in self.overrides {
+            apply(&mut config);
+        }
+        Ok(config)
+    }
+}
+
+impl EngineConfig {
+    /// 分层配置构建器
+    pub fn builder() -> EngineConfigBuilder {
+        EngineConfigBuilder::new()
+    }
+
+    /// 读取配置文件，未写出的字段取默认值
+    pub fn from_file(path: impl AsRef<Path>) -> EngineResult<Self> {
+        EngineConfigBuilder::new().with_file(path.as_ref()).build()
+    }
+
+    /// 在当前配置上应用 `SANJI_` 环境变量
+    pub fn with_env_overrides(self) -> EngineResult<Self> {
+        let mut value = serde_json::to_value(self)?;
+        apply_env_overrides(&mut value, std::env::vars());
+        serde_json::from_value(value).map_err(|e| anyhow::anyhow!("环境变量覆盖后的引擎配置无效: {}", e))
+    }
+}
+
+fn read_config_file(path: &Path) -> EngineResult<Value> {
+    let content = std::fs::read_to_string(path)?;
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+
+    let value = match extension.as_str() {
+        "toml" => toml::from_str(&content).map_err(|e| anyhow::anyhow!("解析配置文件 {:?} 失败: {}", path, e))?,
+        "json" => serde_json::from_str(&content)?,
+        "yaml" | "yml" => serde_yaml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("解析配置文件 {:?} 失败: {}", path, e))?,
+        _ => {
+            return Err(EngineError::AssetError(format!("不支持的配置文件格式: {:?}", path)).into());
+        }
+    };
+    Ok(value)
+}
+
+/// 把 `overlay` 递归合并到 `base`，对象按字段合并，其他值整体替换
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn apply_env_overrides(value: &mut Value, vars: impl IntoIterator<Item = (String, String)>) {
+    for (key, raw) in vars {
+        let Some(name) = key.strip_prefix(CONFIG_ENV_PREFIX) else {
+            continue;
+        };
+        if !set_by_env_name(value, &name.to_lowercase(), &raw) {
+            log::warn!("环境变量 {} 不对应任何配置项，已忽略", key);
+        }
+    }
+}
+
+/// 按 `节_字段` 形式的名称定位配置项并赋值，字段名本身可以包含下划线
+fn set_by_env_name(value: &mut Value, name: &str, raw: &str) -> bool {
+    let Value::Object(map) = value else {
+        return false;
+    };
+
+    if let Some(leaf) = map.get_mut(name) {
+        if !leaf.is_object() {
+            *leaf = parse_env_value(leaf, raw);
+            return true;
+        }
+    }
+
+    // 优先尝试更长的节名
+    let mut sections: Vec<&String> = map
+        .keys()
+        .filter(|key| name.len() > key.len() && name.starts_with(key.as_str()) && name.as_bytes()[key.len()] == b'_')
+        .collect();
+    sections.sort_by_key(|key| std::cmp::Reverse(key.len()));
+    let sections: Vec<String> = sections.into_iter().cloned().collect();
+
+    sections.into_iter().any(|section| {
+        let rest = &name[section.len() + 1..];
+        map.get_mut(&section).map_or(false, |child| set_by_env_name(child, rest, raw))
+    })
+}
+
+fn parse_env_value(current: &Value, raw: &str) -> Value {
+    if current.is_string() {
+        return Value::String(raw.to_string());
+    }
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("sanji_config_{}_{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn layers_apply_in_precedence_order() {
+        let path = temp_config("layers.toml", "[window]\nwidth = 800\nheight = 600\n");
+
+        let from_file = EngineConfig::builder().with_file(&path).build().unwrap();
+        assert_eq!(from_file.window.width, 800);
+
+        // 环境变量覆盖文件，代码覆盖环境变量，与调用顺序无关
+        let config = EngineConfig::builder()
+            .with_override(|config| config.window.width = 640)
+            .with_env_vars([("SANJI_WINDOW_WIDTH", "1280"), ("SANJI_WINDOW_HEIGHT", "720")])
+            .with_file(&path)
+            .build()
+            .unwrap();
+        assert_eq!(config.window.width, 640);
+        assert_eq!(config.window.height, 720);
+        assert_eq!(config.window.title, EngineConfig::default().window.title);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn env_names_resolve_fields_with_underscores() {
+        let config = EngineConfig::builder()
+            .with_env_vars([
+                ("SANJI_RENDER_MAX_TEXTURE_SIZE", "2048"),
+                ("SANJI_ASSETS_ASSET_FOLDER", "content"),
+                ("SANJI_WINDOW_TITLE", "123"),
+                ("SANJI_WINDOW_VSYNC", "false"),
+                ("SANJI_UNKNOWN_FIELD", "1"),
+                ("OTHER_WINDOW_WIDTH", "1"),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(config.render.max_texture_size, 2048);
+        assert_eq!(config.assets.asset_folder, "content");
+        // 字符串字段保持原值，不按JSON解析为数字
+        assert_eq!(config.window.title, "123");
+        assert!(!config.window.vsync);
+        assert_eq!(config.window.width, EngineConfig::default().window.width);
+    }
+
+    #[test]
+    fn invalid_env_value_is_an_error() {
+        let result = EngineConfig::builder().with_env_vars([("SANJI_WINDOW_WIDTH", "wide")]).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn json_and_yaml_files_merge_partially() {
+        let json = temp_config("partial.json", r#"{"window": {"title": "编辑器"}}"#);
+        let yaml = temp_config("partial.yaml", "render:\n  max_texture_size: 4096\n");
+
+        let from_json = EngineConfig::from_file(&json).unwrap();
+        assert_eq!(from_json.window.title, "编辑器");
+        assert_eq!(from_json.window.width, EngineConfig::default().window.width);
+        assert_eq!(EngineConfig::from_file(&yaml).unwrap().render.max_texture_size, 4096);
+
+        let unsupported = temp_config("config.ini", "width=1");
+        assert!(EngineConfig::from_file(&unsupported).is_err());
+        for path in [json, yaml, unsupported] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -7,22 +7,86 @@ use crate::assets::AssetManager;
 use crate::scene::SceneManager;
 use crate::input::InputManager;
 use crate::time::TimeManager;
-use crate::events::EventSystem;
+use crate::events::{EventSystem, KeyReleasedEvent, MouseButtonPressedEvent, MouseButtonReleasedEvent, SubsystemErrorEvent};
 
 use winit::{
     event::{ElementState, Event, MouseButton, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
     window::{Window, WindowId, WindowBuilder},
     dpi::PhysicalPosition,
-    keyboard::KeyCode,
+    keyboard::{KeyCode, PhysicalKey},
 };
 
+use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 保留的子系统错误记录上限
+const MAX_SUBSYSTEM_ERRORS: usize = 64;
+
+/// 主循环中单独隔离错误的子系统
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EngineSubsystem {
+    /// ECS系统(含物理、音频、脚本等注册到ECS的系统)
+    Ecs,
+    Scene,
+    Render,
+    /// 游戏逻辑更新回调
+    Game,
+}
+
+impl EngineSubsystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EngineSubsystem::Ecs => "ecs",
+            EngineSubsystem::Scene => "scene",
+            EngineSubsystem::Render => "render",
+            EngineSubsystem::Game => "game",
+        }
+    }
+}
+
+/// 子系统错误记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubsystemError {
+    pub subsystem: EngineSubsystem,
+    /// 出错时的帧号
+    pub frame: u64,
+    pub message: String,
+}
+
+/// 每帧统计快照，可廉价复制，供游戏逻辑和UI读取
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    pub frame: u64,
+    pub fps: f32,
+    /// 帧时间(毫秒)
+    pub frame_time_ms: f32,
+    pub draw_calls: u32,
+    pub entity_count: usize,
+}
+
+/// 每帧传给游戏更新回调的时间信息
+///
+/// 输入和事件通过回调收到的 `&mut Engine` 读取(`input()`、`event_system_mut()`)。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameContext {
+    /// 按时间缩放后的帧时间，暂停时为0
+    pub delta_time: f32,
+    /// 真实帧时间，用于暂停菜单等始终运行的逻辑
+    pub unscaled_delta_time: f32,
+    pub total_time: f32,
+    pub frame: u64,
+}
+
+/// 游戏更新回调，在输入更新之后、ECS与场景更新之前调用
+pub type UpdateCallback = Box<dyn FnMut(&mut Engine, &FrameContext) -> EngineResult<()>>;
 
 /// 核心游戏引擎
 pub struct Engine {
     config: EngineConfig,
     window: Option<Arc<Window>>,
+    game_window: crate::core::window::Window,
     render_system: Option<RenderSystem>,
     ecs_world: ECSWorld,
     asset_manager: AssetManager,
@@ -30,6 +94,15 @@ pub struct Engine {
     input_manager: InputManager,
     time_manager: TimeManager,
     event_system: EventSystem,
+    frame_stats: FrameStats,
+    /// 最近的子系统错误
+    subsystem_errors: VecDeque<SubsystemError>,
+    /// 上一次运行失败的子系统，成功运行一次后恢复
+    degraded_subsystems: HashSet<EngineSubsystem>,
+    /// 每帧调用的游戏逻辑
+    update_callback: Option<UpdateCallback>,
+    /// 回调执行期间是否被替换或移除，为真时不再放回正在执行的回调
+    update_callback_changed: bool,
     running: bool,
 }
 
@@ -37,26 +110,294 @@ impl Engine {
     /// 创建新的引擎实例
     pub fn new(config: EngineConfig) -> EngineResult<Self> {
         log::info!("初始化Sanji游戏引擎...");
+
+        // 子系统通过共享的事件发送器自动发布生命周期事件
+        let event_system = EventSystem::new();
+        let mut asset_manager = AssetManager::new()?;
+        asset_manager.set_event_sender(event_system.sender());
+        asset_manager.set_asset_root(&config.assets.asset_folder);
+        for (path, priority) in &config.assets.search_paths {
+            asset_manager.add_search_path(path, *priority);
+        }
+        let mut scene_manager = SceneManager::new();
+        scene_manager.set_event_sender(event_system.sender());
         
+        let game_window = crate::core::window::Window::new(config.window.title.clone());
+        let mut input_manager = InputManager::new();
+        input_manager.gamepads_mut().set_config(config.input.gamepad.clone());
+
         Ok(Self {
             config,
             window: None,
+            game_window,
             render_system: None,
             ecs_world: ECSWorld::new()?,
-            asset_manager: AssetManager::new()?,
-            scene_manager: SceneManager::new(),
-            input_manager: InputManager::new(),
+            asset_manager,
+            scene_manager,
+            input_manager,
             time_manager: TimeManager::new(),
-            event_system: EventSystem::new(),
+            event_system,
+            frame_stats: FrameStats::default(),
+            subsystem_errors: VecDeque::new(),
+            degraded_subsystems: HashSet::new(),
+            update_callback: None,
+            update_callback_changed: false,
             running: false,
         })
     }
 
+    /// 创建无窗口、无渲染的引擎，用于专用服务器，配合 `run_headless` 运行
+    pub fn new_headless(config: EngineConfig) -> EngineResult<Self> {
+        log::info!("以无头模式运行，不创建窗口和渲染系统");
+        Self::new(config)
+    }
+
+    /// 上一帧的统计快照
+    pub fn frame_stats(&self) -> FrameStats {
+        self.frame_stats
+    }
+
+    /// 手动推进一帧(更新并渲染)，用于无窗口运行或逐帧调试
+    pub fn step(&mut self) -> EngineResult<()> {
+        self.update()?;
+        self.render()
+    }
+
+    /// 最近的子系统错误(从旧到新)
+    pub fn subsystem_errors(&self) -> impl Iterator<Item = &SubsystemError> {
+        self.subsystem_errors.iter()
+    }
+
+    /// 子系统是否处于降级状态(上一次运行失败)
+    pub fn is_degraded(&self, subsystem: EngineSubsystem) -> bool {
+        self.degraded_subsystems.contains(&subsystem)
+    }
+
+    /// 是否有任何子系统处于降级状态
+    pub fn is_running_degraded(&self) -> bool {
+        !self.degraded_subsystems.is_empty()
+    }
+
+    /// 清空子系统错误记录
+    pub fn clear_subsystem_errors(&mut self) {
+        self.subsystem_errors.clear();
+    }
+
+    /// 记录子系统运行结果：失败时记录日志、发布错误事件并标记为降级，主循环继续运行
+    fn record_subsystem_result(&mut self, subsystem: EngineSubsystem, result: EngineResult<()>) -> bool {
+        let error = match result {
+            Ok(()) => {
+                if self.degraded_subsystems.remove(&subsystem) {
+                    log::info!("子系统 {} 已恢复", subsystem.name());
+                }
+                return true;
+            }
+            Err(error) => error,
+        };
+
+        let frame = self.time_manager.frame_count();
+        let message = error.to_string();
+        let frame_field = frame.to_string();
+        crate::core::log_structured(
+            log::Level::Error,
+            "sanji::engine",
+            &[("subsystem", subsystem.name()), ("frame", &frame_field)],
+            &format!("子系统 {} 运行失败: {}", subsystem.name(), message),
+        );
+
+        self.event_system.publish(SubsystemErrorEvent {
+            subsystem,
+            frame,
+            message: message.clone(),
+        });
+
+        if self.subsystem_errors.len() >= MAX_SUBSYSTEM_ERRORS {
+            self.subsystem_errors.pop_front();
+        }
+        self.subsystem_errors.push_back(SubsystemError { subsystem, frame, message });
+        self.degraded_subsystems.insert(subsystem);
+        false
+    }
+
+    /// 获取事件系统
+    pub fn event_system(&self) -> &EventSystem {
+        &self.event_system
+    }
+
+    /// 获取可变事件系统(用于订阅事件)
+    pub fn event_system_mut(&mut self) -> &mut EventSystem {
+        &mut self.event_system
+    }
+
+    /// 获取游戏窗口(运行时修改标题和图标)
+    pub fn window(&self) -> &crate::core::window::Window {
+        &self.game_window
+    }
+
+    /// 获取可变游戏窗口
+    pub fn window_mut(&mut self) -> &mut crate::core::window::Window {
+        &mut self.game_window
+    }
+
+    /// 获取输入管理器，更新回调中读取本帧的按键与鼠标状态
+    pub fn input(&self) -> &InputManager {
+        &self.input_manager
+    }
+
+    /// 获取可变输入管理器(切换输入映射、注入按键)
+    pub fn input_mut(&mut self) -> &mut InputManager {
+        &mut self.input_manager
+    }
+
+    /// 获取时间管理器
+    pub fn time(&self) -> &TimeManager {
+        &self.time_manager
+    }
+
+    /// 获取可变时间管理器(时间缩放、暂停)
+    pub fn time_mut(&mut self) -> &mut TimeManager {
+        &mut self.time_manager
+    }
+
+    /// 暂停游戏时间：ECS、场景、动画和世界粒子停止推进，使用非缩放时间的UI效果继续运行
+    pub fn pause(&mut self) {
+        self.time_manager.pause();
+    }
+
+    pub fn resume(&mut self) {
+        self.time_manager.resume();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.time_manager.is_paused()
+    }
+
+    /// 获取ECS世界
+    pub fn ecs_world(&self) -> &ECSWorld {
+        &self.ecs_world
+    }
+
+    pub fn ecs_world_mut(&mut self) -> &mut ECSWorld {
+        &mut self.ecs_world
+    }
+
+    /// 设置每帧调用的游戏更新回调，替换之前的回调
+    pub fn set_update_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&mut Engine, &FrameContext) -> EngineResult<()> + 'static,
+    {
+        self.update_callback = Some(Box::new(callback));
+        self.update_callback_changed = true;
+    }
+
+    /// 移除游戏更新回调，在回调中调用时从下一帧起生效
+    pub fn clear_update_callback(&mut self) {
+        self.update_callback = None;
+        self.update_callback_changed = true;
+    }
+
+    /// 获取资源管理器
+    pub fn asset_manager_mut(&mut self) -> &mut AssetManager {
+        &mut self.asset_manager
+    }
+
+    /// 获取场景管理器
+    pub fn scene_manager_mut(&mut self) -> &mut SceneManager {
+        &mut self.scene_manager
+    }
+
     /// 使用默认配置创建引擎
     pub fn default() -> EngineResult<Self> {
         Self::new(EngineConfig::default())
     }
 
+    /// 设置游戏更新回调并运行引擎
+    pub fn run_with<F>(mut self, update: F) -> EngineResult<()>
+    where
+        F: FnMut(&mut Engine, &FrameContext) -> EngineResult<()> + 'static,
+    {
+        self.set_update_callback(update);
+        self.run()
+    }
+
+    /// 无头模式：以tick_hz的固定频率只运行模拟(游戏回调、ECS、场景和事件)，不创建窗口和渲染
+    ///
+    /// 每次tick的帧时间固定为1/tick_hz，tick按起始时刻对齐排期，不累积睡眠误差。
+    /// 落后超过 `MAX_HEADLESS_CATCH_UP` 个tick时丢弃积压并重新排期。回调中调用 `stop()` 结束循环。
+    /// 循环结束(包括出错)后恢复原来的帧时间上限和更新回调。
+    pub fn run_headless<F>(&mut self, tick_hz: f32, update: F) -> EngineResult<()>
+    where
+        F: FnMut(&mut Engine, &FrameContext) -> EngineResult<()> + 'static,
+    {
+        if !(tick_hz.is_finite() && tick_hz > 0.0) {
+            return Err(anyhow::anyhow!("无效的tick频率: {}", tick_hz));
+        }
+
+        let tick = Duration::from_secs_f64(1.0 / tick_hz as f64);
+        let previous_max_delta = self.time_manager.max_delta();
+        if previous_max_delta < tick.as_secs_f32() {
+            self.time_manager.set_max_delta(tick.as_secs_f32());
+        }
+        let previous_callback = self.update_callback.take();
+        self.set_update_callback(update);
+
+        let result = self.run_headless_loop(tick_hz, tick);
+
+        self.time_manager.set_max_delta(previous_max_delta);
+        self.update_callback = previous_callback;
+        result
+    }
+
+    fn run_headless_loop(&mut self, tick_hz: f32, tick: Duration) -> EngineResult<()> {
+        let tick_seconds = tick.as_secs_f32();
+        log::info!("启动无头主循环，频率 {}Hz", tick_hz);
+        self.running = true;
+        let mut next_tick = Instant::now();
+        while self.running {
+            self.time_manager.advance(tick_seconds);
+            self.simulate()?;
+
+            next_tick += tick;
+            let now = Instant::now();
+            if now < next_tick {
+                Self::sleep_until(next_tick);
+            } else if now - next_tick > tick * Self::MAX_HEADLESS_CATCH_UP {
+                log::warn!("无头主循环落后 {:?}，丢弃积压的tick", now - next_tick);
+                next_tick = now;
+            }
+        }
+        Ok(())
+    }
+
+    /// 无头模式最多连续追赶的tick数
+    const MAX_HEADLESS_CATCH_UP: u32 = 5;
+
+    /// 睡眠到deadline，最后一小段自旋等待以弥补系统睡眠精度
+    fn sleep_until(deadline: Instant) {
+        const SPIN_THRESHOLD: Duration = Duration::from_millis(1);
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return;
+            }
+            let remaining = deadline - now;
+            if remaining > SPIN_THRESHOLD {
+                std::thread::sleep(remaining - SPIN_THRESHOLD);
+            } else {
+                std::hint::spin_loop();
+            }
+        }
+    }
+
+    /// 请求结束主循环(窗口或无头模式)，在当前帧结束后生效
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
     /// 运行引擎
     pub fn run(mut self) -> EngineResult<()> {
         let event_loop = EventLoop::new()?;
@@ -86,6 +427,7 @@ impl Engine {
             }
         }
         
+        self.game_window.attach(window.clone());
         self.window = Some(window);
         log::info!("引擎窗口创建成功");
         
@@ -128,33 +470,82 @@ impl Engine {
     fn update(&mut self) -> EngineResult<()> {
         // 更新时间管理器
         self.time_manager.update();
-        let delta_time = self.time_manager.delta_time();
+        self.simulate()
+    }
+
+    /// 以时间管理器的当前帧时间运行一帧模拟
+    fn simulate(&mut self) -> EngineResult<()> {
+        let delta_time = self.time_manager.scaled_delta_time();
+        let unscaled_delta_time = self.time_manager.delta_time();
         
         // 更新输入管理器
         self.input_manager.update();
+
+        // 游戏逻辑读取本帧输入，发布的事件在本帧末尾分发
+        if let Some(mut callback) = self.update_callback.take() {
+            self.update_callback_changed = false;
+            let context = FrameContext {
+                delta_time,
+                unscaled_delta_time,
+                total_time: self.time_manager.total_time(),
+                frame: self.time_manager.frame_count(),
+            };
+            let game_result = callback(self, &context);
+            // 回调中设置了新回调或移除了回调时不放回原回调
+            if !self.update_callback_changed {
+                self.update_callback = Some(callback);
+            }
+            self.record_subsystem_result(EngineSubsystem::Game, game_result);
+        }
         
-        // 更新ECS系统
-        self.ecs_world.update(delta_time)?;
-        
-        // 更新场景管理器
-        self.scene_manager.update(delta_time)?;
+        // 各子系统的错误单独隔离，失败时降级运行而不中断主循环
+        self.ecs_world.set_fixed_steps(self.time_manager.fixed_timestep());
+        let ecs_result = self.ecs_world.update_with_time(delta_time, unscaled_delta_time);
+        self.record_subsystem_result(EngineSubsystem::Ecs, ecs_result);
+
+        let scene_result = self.scene_manager.update(delta_time);
+        self.record_subsystem_result(EngineSubsystem::Scene, scene_result);
+
+        // 分发本帧产生的事件
+        self.event_system.process_events();
+
+        // FPS每秒统计一次，第一秒内用帧时间估算
+        let fps = match self.time_manager.fps() {
+            fps if fps > 0.0 => fps,
+            _ if unscaled_delta_time > 0.0 => 1.0 / unscaled_delta_time,
+            _ => 0.0,
+        };
+        self.frame_stats = FrameStats {
+            frame: self.time_manager.frame_count(),
+            fps,
+            frame_time_ms: self.time_manager.delta_time_ms(),
+            draw_calls: self.frame_stats.draw_calls,
+            entity_count: self.ecs_world.entity_count(),
+        };
         
         Ok(())
     }
 
     /// 引擎渲染
     fn render(&mut self) -> EngineResult<()> {
-        if let Some(ref mut render_system) = self.render_system {
-            render_system.begin_frame()?;
-            
+        let Some(ref mut render_system) = self.render_system else {
+            return Ok(());
+        };
+
+        let result = render_system.begin_frame().and_then(|_| {
             // 渲染当前场景
-            if let Some(current_scene) = self.scene_manager.current_scene() {
-                render_system.render_scene(current_scene, &self.ecs_world)?;
-            }
-            
-            render_system.end_frame()?;
-        }
-        
+            let scene_result = match self.scene_manager.current_scene() {
+                Some(current_scene) => render_system.render_scene(current_scene, &self.ecs_world),
+                None => Ok(()),
+            };
+            // 场景渲染失败时仍结束本帧，避免帧状态残留
+            let end_result = render_system.end_frame();
+            scene_result.and(end_result)
+        });
+        self.frame_stats.draw_calls = render_system.draw_calls();
+        crate::performance::get_global_monitor().update_render_stats(render_system.render_stats().clone());
+
+        self.record_subsystem_result(EngineSubsystem::Render, result);
         Ok(())
     }
 
@@ -163,10 +554,12 @@ impl Engine {
         match event {
             WindowEvent::CloseRequested => {
                 log::info!("收到窗口关闭请求");
+                self.event_system.publish_window_closed();
                 self.running = false;
             }
             WindowEvent::Resized(physical_size) => {
                 log::info!("窗口大小调整为: {:?}", physical_size);
+                self.event_system.publish_window_resized(physical_size.width, physical_size.height);
                 if let Some(ref mut render_system) = self.render_system {
                     if let Err(e) = render_system.resize(physical_size.width, physical_size.height) {
                         log::error!("调整渲染系统大小失败: {}", e);
@@ -174,13 +567,36 @@ impl Engine {
                 }
             }
             WindowEvent::KeyboardInput { event, .. } => {
+                if let PhysicalKey::Code(key_code) = event.physical_key {
+                    match event.state {
+                        ElementState::Pressed => self.event_system.publish_key_pressed(key_code, event.repeat),
+                        ElementState::Released => self.event_system.publish(KeyReleasedEvent { key_code }),
+                    }
+                }
+                // 物理按键用于绑定，文本单独发布给文本框
+                if let Some(text) = crate::input::key_event_text(&event) {
+                    self.event_system.publish_text_input(text);
+                }
                 self.input_manager.handle_keyboard_input(event);
             }
+            WindowEvent::Ime(ime) => {
+                if let winit::event::Ime::Commit(text) = &ime {
+                    self.event_system.publish_text_input(text.clone());
+                }
+                self.input_manager.handle_ime(&ime);
+            }
             WindowEvent::MouseInput { state, button, .. } => {
                 self.input_manager.handle_mouse_input(button, state);
+                let position = self.input_manager.mouse().position();
+                match state {
+                    ElementState::Pressed => self.event_system.publish(MouseButtonPressedEvent { button, position }),
+                    ElementState::Released => self.event_system.publish(MouseButtonReleasedEvent { button, position }),
+                }
             }
             WindowEvent::CursorMoved { position, .. } => {
-                self.input_manager.handle_mouse_move(position);
+                let delta = self.input_manager.handle_mouse_move(position);
+                let position = self.input_manager.mouse().position();
+                self.event_system.publish_mouse_moved(position, delta);
             }
             WindowEvent::RedrawRequested => {
                 if let Err(e) = self.render() {
@@ -193,3 +609,250 @@ impl Engine {
 }
 
 // ApplicationHandler implementation removed - using traditional winit event loop
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn run_headless_restores_max_delta_and_callback() {
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        let max_delta = engine.time().max_delta();
+        let outer_calls = Rc::new(Cell::new(0));
+        let calls = outer_calls.clone();
+        engine.set_update_callback(move |_, _| {
+            calls.set(calls.get() + 1);
+            Ok(())
+        });
+
+        let ticks = Rc::new(Cell::new(0));
+        let counter = ticks.clone();
+        engine.run_headless(8.0, move |engine, context| {
+            assert_eq!(context.delta_time, 0.125);
+            counter.set(counter.get() + 1);
+            if counter.get() == 2 {
+                engine.stop();
+            }
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(ticks.get(), 2);
+        assert_eq!(outer_calls.get(), 0);
+        assert_eq!(engine.time().max_delta(), max_delta);
+
+        engine.step().unwrap();
+        assert_eq!(outer_calls.get(), 1);
+    }
+
+    #[test]
+    fn run_headless_ticks_at_requested_rate() {
+        const TICK_HZ: f32 = 100.0;
+        const DURATION: Duration = Duration::from_millis(500);
+
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        let ticks = Rc::new(Cell::new(0u32));
+        let counter = ticks.clone();
+        let start = Instant::now();
+        engine.run_headless(TICK_HZ, move |engine, context| {
+            assert!((context.delta_time - 1.0 / TICK_HZ).abs() < 1e-6);
+            counter.set(counter.get() + 1);
+            if start.elapsed() >= DURATION {
+                engine.stop();
+            }
+            Ok(())
+        }).unwrap();
+
+        // tick按起始时刻排期，墙钟时间内的tick数应接近 tick_hz × duration
+        let expected = TICK_HZ * DURATION.as_secs_f32();
+        let ticks = ticks.get() as f32;
+        assert!((ticks - expected).abs() <= expected * 0.1 + 1.0, "{} ticks, expected {}", ticks, expected);
+        // 模拟时间只由tick数决定
+        assert!((engine.time().total_time() - ticks / TICK_HZ).abs() < 1e-3);
+        assert!(!engine.is_running());
+    }
+
+    #[test]
+    fn frame_stats_track_steps_and_entities() {
+        use specs::Builder;
+
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        assert_eq!(engine.frame_stats(), FrameStats::default());
+        for _ in 0..3 {
+            engine.ecs_world_mut().create_entity().build();
+        }
+
+        let mut last_frame = 0;
+        for _ in 0..3 {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            engine.step().unwrap();
+            let stats = engine.frame_stats();
+            assert!(stats.frame > last_frame);
+            last_frame = stats.frame;
+        }
+
+        let stats = engine.frame_stats();
+        assert!(stats.fps > 0.0 && stats.fps.is_finite());
+        assert!(stats.frame_time_ms > 0.0);
+        assert_eq!(stats.entity_count, 3);
+        // 无窗口运行时没有绘制调用
+        assert_eq!(stats.draw_calls, 0);
+    }
+
+    #[test]
+    fn run_headless_rejects_invalid_rate() {
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        assert!(engine.run_headless(0.0, |_, _| Ok(())).is_err());
+        assert!(engine.run_headless(f32::NAN, |_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn subsystem_error_is_recorded_and_engine_keeps_running() {
+        use std::sync::{Arc, Mutex};
+
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        let published = Arc::new(Mutex::new(Vec::new()));
+        let sink = published.clone();
+        engine.event_system_mut().subscribe::<SubsystemErrorEvent, _>(move |event| {
+            sink.lock().unwrap().push((event.subsystem, event.message.clone()));
+        });
+
+        engine.step().unwrap();
+        assert!(!engine.is_running_degraded());
+
+        // 模拟场景子系统在这一帧失败
+        let ok = engine.record_subsystem_result(EngineSubsystem::Scene, Err(EngineError::RenderError("加载失败".to_string()).into()));
+        assert!(!ok);
+        assert!(engine.is_degraded(EngineSubsystem::Scene));
+        assert!(!engine.is_degraded(EngineSubsystem::Ecs));
+
+        // 后续帧继续运行，场景子系统成功后恢复，错误记录保留
+        let frame = engine.frame_stats().frame;
+        engine.step().unwrap();
+        engine.step().unwrap();
+        assert!(engine.frame_stats().frame > frame);
+        assert!(!engine.is_running_degraded());
+
+        let errors: Vec<_> = engine.subsystem_errors().cloned().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].subsystem, EngineSubsystem::Scene);
+        assert!(errors[0].message.contains("加载失败"));
+        assert_eq!(published.lock().unwrap().as_slice(), &[(EngineSubsystem::Scene, errors[0].message.clone())]);
+
+        engine.clear_subsystem_errors();
+        assert_eq!(engine.subsystem_errors().count(), 0);
+    }
+
+    #[test]
+    fn subsystem_errors_are_bounded() {
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        for i in 0..MAX_SUBSYSTEM_ERRORS + 5 {
+            engine.record_subsystem_result(EngineSubsystem::Render, Err(EngineError::RenderError(i.to_string()).into()));
+        }
+        assert_eq!(engine.subsystem_errors().count(), MAX_SUBSYSTEM_ERRORS);
+        assert!(engine.subsystem_errors().next().unwrap().message.ends_with('5'));
+    }
+
+    #[test]
+    fn update_callback_reads_simulated_key_press() {
+        use winit::keyboard::KeyCode;
+
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        let jumps = Rc::new(Cell::new(0));
+        let frames = Rc::new(std::cell::RefCell::new(Vec::new()));
+        let (jump_counter, frame_log) = (jumps.clone(), frames.clone());
+        engine.set_update_callback(move |engine, context| {
+            frame_log.borrow_mut().push(context.frame);
+            if engine.input().keyboard().is_key_just_pressed(KeyCode::Space) {
+                jump_counter.set(jump_counter.get() + 1);
+            }
+            Ok(())
+        });
+
+        engine.step().unwrap();
+        assert_eq!(jumps.get(), 0);
+
+        // 注入的按键在下一帧的输入更新后可见，按住不会重复触发
+        engine.input_mut().simulate_key(KeyCode::Space, true);
+        engine.step().unwrap();
+        engine.step().unwrap();
+        assert_eq!(jumps.get(), 1);
+
+        engine.input_mut().simulate_key(KeyCode::Space, false);
+        engine.step().unwrap();
+        engine.input_mut().simulate_key(KeyCode::Space, true);
+        engine.step().unwrap();
+        assert_eq!(jumps.get(), 2);
+
+        let frames = frames.borrow();
+        assert_eq!(frames.len(), 5);
+        assert!(frames.windows(2).all(|pair| pair[1] > pair[0]));
+    }
+
+    #[test]
+    fn update_callback_errors_degrade_game_subsystem() {
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        engine.set_update_callback(|engine, _| {
+            // 回调中替换的新回调在之后的帧生效
+            engine.set_update_callback(|_, _| Ok(()));
+            Err(EngineError::RenderError("游戏逻辑失败".to_string()).into())
+        });
+
+        engine.step().unwrap();
+        assert!(engine.is_degraded(EngineSubsystem::Game));
+        engine.step().unwrap();
+        assert!(!engine.is_degraded(EngineSubsystem::Game));
+        assert_eq!(engine.subsystem_errors().filter(|error| error.subsystem == EngineSubsystem::Game).count(), 1);
+
+        engine.clear_update_callback();
+        engine.step().unwrap();
+    }
+
+    #[test]
+    fn failing_behavior_degrades_ecs_subsystem() {
+        use crate::ecs::{Behavior, BehaviorContext, ScriptComponent};
+        use specs::Builder;
+
+        struct FailOnce(bool);
+
+        impl Behavior for FailOnce {
+            fn on_update(&mut self, ctx: &mut BehaviorContext, _delta_time: f32) {
+                if !std::mem::replace(&mut self.0, true) {
+                    ctx.report_error("脚本异常");
+                }
+            }
+        }
+
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        engine
+            .ecs_world_mut()
+            .create_entity()
+            .with(ScriptComponent::new().with_behavior(FailOnce(false)))
+            .build();
+
+        engine.step().unwrap();
+        assert!(engine.is_degraded(EngineSubsystem::Ecs));
+        let error = engine.subsystem_errors().find(|error| error.subsystem == EngineSubsystem::Ecs).unwrap();
+        assert!(error.message.contains("脚本异常"), "{}", error.message);
+
+        engine.step().unwrap();
+        assert!(!engine.is_degraded(EngineSubsystem::Ecs));
+    }
+
+    #[test]
+    fn update_callback_can_clear_itself() {
+        let mut engine = Engine::new_headless(EngineConfig::default()).unwrap();
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        engine.set_update_callback(move |engine, _| {
+            counter.set(counter.get() + 1);
+            engine.clear_update_callback();
+            Ok(())
+        });
+
+        engine.step().unwrap();
+        engine.step().unwrap();
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -1,8 +1,10 @@
 //! 日志和调试系统
 
 use log::{Level, LevelFilter};
+use std::collections::{HashMap, VecDeque};
 use std::io::Write;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 use chrono::{DateTime, Utc};
 
 /// 日志配置
@@ -22,6 +24,39 @@ pub struct LogConfig {
     pub show_thread_id: bool,
     /// 是否显示文件位置
     pub show_location: bool,
+    /// 内存中保留的最近日志条数，0表示不保留
+    #[serde(default = "LogConfig::default_buffer_capacity")]
+    pub buffer_capacity: usize,
+    /// 单个日志文件的最大字节数，超过后轮转，0表示不轮转
+    #[serde(default = "LogConfig::default_max_file_size")]
+    pub max_file_size: u64,
+    /// 保留的轮转文件数量
+    #[serde(default = "LogConfig::default_max_files")]
+    pub max_files: usize,
+}
+
+impl LogConfig {
+    fn default_buffer_capacity() -> usize {
+        1024
+    }
+
+    fn default_max_file_size() -> u64 {
+        10 * 1024 * 1024
+    }
+
+    fn default_max_files() -> usize {
+        5
+    }
+
+    /// 对应的文件日志配置
+    pub fn file_sink_config(&self) -> FileLogConfig {
+        FileLogConfig {
+            path: PathBuf::from(&self.file_path),
+            level: parse_level_filter(&self.level),
+            max_file_size: self.max_file_size,
+            max_files: self.max_files,
+        }
+    }
 }
 
 impl Default for LogConfig {
@@ -34,25 +69,446 @@ impl Default for LogConfig {
             show_timestamp: true,
             show_thread_id: false,
             show_location: false,
+            buffer_capacity: Self::default_buffer_capacity(),
+            max_file_size: Self::default_max_file_size(),
+            max_files: Self::default_max_files(),
+        }
+    }
+}
+
+/// 结构化日志记录
+///
+/// 键值字段来自 `log::warn!(subsystem = "render"; "...")` 或 `log_structured`，便于遥测按字段过滤。
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuredRecord {
+    pub level: Level,
+    pub target: String,
+    pub fields: HashMap<String, String>,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl StructuredRecord {
+    /// 字段值
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields.get(key).map(String::as_str)
+    }
+}
+
+/// 日志查询条件，所有已设置的条件都满足才匹配
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogFilter {
+    /// 精确匹配的级别
+    pub level: Option<Level>,
+    /// 至少这么严重(warn匹配warn和error)
+    pub min_level: Option<Level>,
+    /// target前缀
+    pub target: Option<String>,
+    /// 必须存在且相等的字段
+    pub fields: Vec<(String, String)>,
+    /// 消息包含的文本
+    pub message_contains: Option<String>,
+}
+
+impl LogFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    pub fn with_min_level(mut self, level: Level) -> Self {
+        self.min_level = Some(level);
+        self
+    }
+
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn with_field(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn with_message(mut self, text: impl Into<String>) -> Self {
+        self.message_contains = Some(text.into());
+        self
+    }
+
+    /// 解析 `subsystem=render level=warn` 形式的查询，
+    /// level/min_level/target/message为内置条件，其余键匹配字段
+    pub fn parse(query: &str) -> Option<Self> {
+        let mut filter = Self::new();
+        for term in query.split_whitespace() {
+            let (key, value) = term.split_once('=')?;
+            filter = match key {
+                "level" => filter.with_level(value.parse().ok()?),
+                "min_level" => filter.with_min_level(value.parse().ok()?),
+                "target" => filter.with_target(value),
+                "message" => filter.with_message(value),
+                _ => filter.with_field(key, value),
+            };
+        }
+        Some(filter)
+    }
+
+    pub fn matches(&self, record: &StructuredRecord) -> bool {
+        self.level.map_or(true, |level| record.level == level)
+            && self.min_level.map_or(true, |level| record.level <= level)
+            && self.target.as_ref().map_or(true, |target| record.target.starts_with(target.as_str()))
+            && self.fields.iter().all(|(key, value)| record.field(key) == Some(value.as_str()))
+            && self.message_contains.as_ref().map_or(true, |text| record.message.contains(text.as_str()))
+    }
+}
+
+/// 最近日志的环形缓冲
+struct LogBuffer {
+    records: VecDeque<StructuredRecord>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    fn push(&mut self, record: StructuredRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+}
+
+fn log_buffer() -> &'static Mutex<LogBuffer> {
+    static BUFFER: OnceLock<Mutex<LogBuffer>> = OnceLock::new();
+    BUFFER.get_or_init(|| {
+        Mutex::new(LogBuffer {
+            records: VecDeque::new(),
+            capacity: LogConfig::default_buffer_capacity(),
+        })
+    })
+}
+
+/// 收集日志记录中的键值字段
+struct FieldCollector(HashMap<String, String>);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for FieldCollector {
+    fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+        self.0.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+impl StructuredRecord {
+    fn from_log_record(record: &log::Record) -> Self {
+        let mut collector = FieldCollector(HashMap::new());
+        let _ = record.key_values().visit(&mut collector);
+        Self {
+            level: record.level(),
+            target: record.target().to_string(),
+            fields: collector.0,
+            message: record.args().to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// 把记录写入缓冲(日志系统之外的来源也可直接写入)
+pub fn capture_record(record: StructuredRecord) {
+    if let Ok(mut buffer) = log_buffer().lock() {
+        buffer.push(record);
+    }
+}
+
+/// 查询缓冲中匹配的记录，按时间从旧到新
+pub fn query(filter: &LogFilter) -> Vec<StructuredRecord> {
+    log_buffer()
+        .lock()
+        .map(|buffer| buffer.records.iter().filter(|record| filter.matches(record)).cloned().collect())
+        .unwrap_or_default()
+}
+
+/// 最近的count条记录，按时间从旧到新
+pub fn recent_records(count: usize) -> Vec<StructuredRecord> {
+    log_buffer()
+        .lock()
+        .map(|buffer| buffer.records.iter().skip(buffer.records.len().saturating_sub(count)).cloned().collect())
+        .unwrap_or_default()
+}
+
+/// 修改缓冲容量，超出的旧记录被丢弃
+pub fn set_log_buffer_capacity(capacity: usize) {
+    if let Ok(mut buffer) = log_buffer().lock() {
+        buffer.capacity = capacity;
+        let excess = buffer.records.len().saturating_sub(capacity);
+        buffer.records.drain(..excess);
+    }
+}
+
+/// 清空日志缓冲
+pub fn clear_log_buffer() {
+    if let Ok(mut buffer) = log_buffer().lock() {
+        buffer.records.clear();
+    }
+}
+
+/// 输出带字段的日志，字段在运行时才确定时使用(编译期已知时直接用 `log::warn!(key = value; ...)`)
+pub fn log_structured(level: Level, target: &str, fields: &[(&str, &str)], message: &str) {
+    log::logger().log(
+        &log::Record::builder()
+            .level(level)
+            .target(target)
+            .key_values(&fields)
+            .args(format_args!("{}", message))
+            .build(),
+    );
+}
+
+/// 解析日志级别名称，无法识别时为info
+fn parse_level_filter(level: &str) -> LevelFilter {
+    match level {
+        "trace" => LevelFilter::Trace,
+        "debug" => LevelFilter::Debug,
+        "info" => LevelFilter::Info,
+        "warn" => LevelFilter::Warn,
+        "error" => LevelFilter::Error,
+        "off" => LevelFilter::Off,
+        _ => LevelFilter::Info,
+    }
+}
+
+/// 文件日志配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileLogConfig {
+    /// 当前日志文件路径，轮转后的文件依次为 `<path>.1`(最新) ... `<path>.N`(最旧)
+    pub path: PathBuf,
+    /// 写入文件的最低级别
+    pub level: LevelFilter,
+    /// 单个文件的最大字节数，0表示不轮转
+    pub max_file_size: u64,
+    /// 保留的轮转文件数量，0表示轮转时直接清空当前文件
+    pub max_files: usize,
+}
+
+impl Default for FileLogConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("sanji_engine.log"),
+            level: LevelFilter::Info,
+            max_file_size: LogConfig::default_max_file_size(),
+            max_files: LogConfig::default_max_files(),
         }
     }
 }
 
+impl FileLogConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), ..Default::default() }
+    }
+
+    pub fn with_level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    pub fn with_max_files(mut self, count: usize) -> Self {
+        self.max_files = count;
+        self
+    }
+
+    /// 第index个轮转文件的路径(从1开始，1最新)
+    pub fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+}
+
+struct SinkFile {
+    file: std::fs::File,
+    size: u64,
+}
+
+/// 按大小轮转的文件日志
+pub struct RotatingFileSink {
+    config: FileLogConfig,
+    state: Mutex<SinkFile>,
+}
+
+impl RotatingFileSink {
+    pub fn new(config: FileLogConfig) -> crate::EngineResult<Self> {
+        if let Some(parent) = config.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(crate::EngineError::IoError)?;
+        }
+        let file = Self::open(&config.path, false)?;
+        let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        Ok(Self {
+            config,
+            state: Mutex::new(SinkFile { file, size }),
+        })
+    }
+
+    pub fn config(&self) -> &FileLogConfig {
+        &self.config
+    }
+
+    pub fn enabled(&self, level: Level) -> bool {
+        level <= self.config.level
+    }
+
+    /// 写入一行，写入后超过大小上限时先轮转
+    pub fn write_line(&self, line: &str) -> crate::EngineResult<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| crate::EngineError::RenderError("文件日志锁已损坏".to_string()))?;
+
+        let bytes = line.len() as u64 + 1;
+        if self.config.max_file_size > 0 && state.size > 0 && state.size + bytes > self.config.max_file_size {
+            self.rotate(&mut state)?;
+        }
+
+        writeln!(state.file, "{}", line).map_err(crate::EngineError::IoError)?;
+        state.size += bytes;
+        Ok(())
+    }
+
+    pub fn flush(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            let _ = state.file.flush();
+        }
+    }
+
+    /// 当前文件改名为 `.1`，已有的轮转文件依次后移，超出数量的最旧文件被删除
+    fn rotate(&self, state: &mut SinkFile) -> crate::EngineResult<()> {
+        let _ = state.file.flush();
+
+        if self.config.max_files > 0 {
+            let oldest = self.config.rotated_path(self.config.max_files);
+            if oldest.exists() {
+                std::fs::remove_file(&oldest).map_err(crate::EngineError::IoError)?;
+            }
+            for index in (1..self.config.max_files).rev() {
+                let from = self.config.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.config.rotated_path(index + 1)).map_err(crate::EngineError::IoError)?;
+                }
+            }
+            std::fs::rename(&self.config.path, self.config.rotated_path(1)).map_err(crate::EngineError::IoError)?;
+        }
+
+        state.file = Self::open(&self.config.path, self.config.max_files == 0)?;
+        state.size = 0;
+        Ok(())
+    }
+
+    fn open(path: &Path, truncate: bool) -> crate::EngineResult<std::fs::File> {
+        let mut options = std::fs::OpenOptions::new();
+        options.create(true);
+        if truncate {
+            options.write(true).truncate(true);
+        } else {
+            options.append(true);
+        }
+        Ok(options.open(path).map_err(crate::EngineError::IoError)?)
+    }
+}
+
+/// 去掉ANSI颜色码
+fn strip_ansi_colors(message: &str) -> String {
+    message
+        .replace("\x1b[31m", "")
+        .replace("\x1b[33m", "")
+        .replace("\x1b[32m", "")
+        .replace("\x1b[34m", "")
+        .replace("\x1b[37m", "")
+        .replace("\x1b[0m", "")
+}
+
+/// env_logger输出到控制台，同时写入轮转日志文件
+struct EnvFileLogger {
+    env: env_logger::Logger,
+    file: RotatingFileSink,
+}
+
+impl EnvFileLogger {
+    fn format_file_line(record: &log::Record) -> String {
+        let mut line = format!(
+            "[{}] [{}] {}: {}",
+            Utc::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        let mut fields = FieldCollector(HashMap::new());
+        let _ = record.key_values().visit(&mut fields);
+        let mut fields: Vec<_> = fields.0.into_iter().collect();
+        fields.sort();
+        for (key, value) in fields {
+            line.push_str(&format!(" {}={}", key, value));
+        }
+        line
+    }
+}
+
+impl log::Log for EnvFileLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.env.enabled(metadata) || self.file.enabled(metadata.level())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        capture_record(StructuredRecord::from_log_record(record));
+        self.env.log(record);
+        if self.file.enabled(record.level()) {
+            let _ = self.file.write_line(&Self::format_file_line(record));
+        }
+    }
+
+    fn flush(&self) {
+        self.env.flush();
+        self.file.flush();
+    }
+}
+
+/// 初始化env_logger(RUST_LOG，默认info)并同时写入轮转日志文件
+pub fn init_env_logging_with_file(file_config: FileLogConfig) -> crate::EngineResult<()> {
+    let env = env_logger::Builder::new()
+        .filter_level(LevelFilter::Info)
+        .parse_default_env()
+        .build();
+    let file = RotatingFileSink::new(file_config)?;
+    let max_level = env.filter().max(file.config().level);
+
+    log::set_boxed_logger(Box::new(EnvFileLogger { env, file }))
+        .map_err(|e| crate::EngineError::RenderError(format!("初始化日志系统失败: {}", e)))?;
+    log::set_max_level(max_level);
+    Ok(())
+}
+
 /// 自定义日志格式化器
 struct SanjiLogger {
     config: LogConfig,
-    file_writer: Option<Mutex<std::fs::File>>,
+    file_writer: Option<RotatingFileSink>,
 }
 
 impl SanjiLogger {
     fn new(config: LogConfig) -> crate::EngineResult<Self> {
         let file_writer = if config.file_output {
-            let file = std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&config.file_path)
-                .map_err(|e| crate::EngineError::IoError(e))?;
-            Some(Mutex::new(file))
+            Some(RotatingFileSink::new(config.file_sink_config())?)
         } else {
             None
         };
@@ -95,6 +551,15 @@ impl SanjiLogger {
         // 消息内容
         message.push_str(&record.args().to_string());
 
+        // 结构化字段
+        let mut fields = FieldCollector(HashMap::new());
+        let _ = record.key_values().visit(&mut fields);
+        let mut fields: Vec<_> = fields.0.into_iter().collect();
+        fields.sort();
+        for (key, value) in fields {
+            message.push_str(&format!(" {}={}", key, value));
+        }
+
         // 文件位置
         if self.config.show_location {
             if let (Some(file), Some(line)) = (record.file(), record.line()) {
@@ -108,20 +573,12 @@ impl SanjiLogger {
 
 impl log::Log for SanjiLogger {
     fn enabled(&self, metadata: &log::Metadata) -> bool {
-        let level_filter = match self.config.level.as_str() {
-            "trace" => LevelFilter::Trace,
-            "debug" => LevelFilter::Debug,
-            "info" => LevelFilter::Info,
-            "warn" => LevelFilter::Warn,
-            "error" => LevelFilter::Error,
-            "off" => LevelFilter::Off,
-            _ => LevelFilter::Info,
-        };
-        metadata.level() <= level_filter
+        metadata.level() <= parse_level_filter(&self.config.level)
     }
 
     fn log(&self, record: &log::Record) {
         if self.enabled(record.metadata()) {
+            capture_record(StructuredRecord::from_log_record(record));
             let message = self.format_message(record);
 
             // 输出到控制台
@@ -131,20 +588,15 @@ impl log::Log for SanjiLogger {
 
             // 输出到文件
             if let Some(ref file_writer) = self.file_writer {
-                if let Ok(mut file) = file_writer.lock() {
-                    let file_message = format!("{}\n", message.replace("\x1b[31m", "").replace("\x1b[33m", "").replace("\x1b[32m", "").replace("\x1b[34m", "").replace("\x1b[37m", "").replace("\x1b[0m", ""));
-                    let _ = file.write_all(file_message.as_bytes());
-                    let _ = file.flush();
-                }
+                let _ = file_writer.write_line(&strip_ansi_colors(&message));
+                file_writer.flush();
             }
         }
     }
 
     fn flush(&self) {
         if let Some(ref file_writer) = self.file_writer {
-            if let Ok(mut file) = file_writer.lock() {
-                let _ = file.flush();
-            }
+            file_writer.flush();
         }
     }
 }
@@ -153,21 +605,12 @@ impl log::Log for SanjiLogger {
 pub fn init_logging(config: LogConfig) -> crate::EngineResult<()> {
     let logger = SanjiLogger::new(config)?;
     let level_str = logger.config.level.clone();
+    set_log_buffer_capacity(logger.config.buffer_capacity);
     
     log::set_boxed_logger(Box::new(logger))
         .map_err(|e| crate::EngineError::RenderError(format!("初始化日志系统失败: {}", e)))?;
     
-    let level_filter = match level_str.as_str() {
-        "trace" => LevelFilter::Trace,
-        "debug" => LevelFilter::Debug,
-        "info" => LevelFilter::Info,
-        "warn" => LevelFilter::Warn,
-        "error" => LevelFilter::Error,
-        "off" => LevelFilter::Off,
-        _ => LevelFilter::Info,
-    };
-    
-    log::set_max_level(level_filter);
+    log::set_max_level(parse_level_filter(&level_str));
     
     log::info!("Sanji引擎日志系统已初始化");
     Ok(())
@@ -370,3 +813,152 @@ impl DebugOverlay {
         log::info!("性能分析显示: {}", if self.show_profiler { "开启" } else { "关闭" });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 其他测试也会写入全局缓冲，每个测试使用独立的target
+    fn record(level: Level, target: &str, fields: &[(&str, &str)], message: &str) -> StructuredRecord {
+        StructuredRecord::from_log_record(
+            &log::Record::builder()
+                .level(level)
+                .target(target)
+                .key_values(&fields)
+                .args(format_args!("{}", message))
+                .build(),
+        )
+    }
+
+    #[test]
+    fn log_record_fields_are_collected() {
+        let record = record(Level::Warn, "test_fields", &[("subsystem", "render"), ("frame", "42")], "丢帧");
+        assert_eq!(record.level, Level::Warn);
+        assert_eq!(record.target, "test_fields");
+        assert_eq!(record.field("subsystem"), Some("render"));
+        assert_eq!(record.field("frame"), Some("42"));
+        assert_eq!(record.field("missing"), None);
+        assert_eq!(record.message, "丢帧");
+    }
+
+    #[test]
+    fn query_returns_only_matching_records() {
+        let target = "test_query";
+        capture_record(record(Level::Warn, target, &[("subsystem", "render")], "纹理过大"));
+        capture_record(record(Level::Info, target, &[("subsystem", "render")], "帧开始"));
+        capture_record(record(Level::Warn, target, &[("subsystem", "audio")], "缓冲不足"));
+        capture_record(record(Level::Error, target, &[("subsystem", "render")], "设备丢失"));
+
+        let filter = LogFilter::parse("subsystem=render level=warn").unwrap().with_target(target);
+        let records = query(&filter);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "纹理过大");
+
+        let at_least_warn = LogFilter::new().with_target(target).with_field("subsystem", "render").with_min_level(Level::Warn);
+        let messages: Vec<_> = query(&at_least_warn).into_iter().map(|record| record.message).collect();
+        assert_eq!(messages, vec!["纹理过大", "设备丢失"]);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_terms() {
+        assert!(LogFilter::parse("subsystem").is_none());
+        assert!(LogFilter::parse("level=loud").is_none());
+        let filter = LogFilter::parse("target=sanji message=丢帧").unwrap();
+        assert_eq!(filter, LogFilter::new().with_target("sanji").with_message("丢帧"));
+    }
+
+    fn log_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sanji_log_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn volume_triggers_one_rotation() {
+        let dir = log_dir("volume");
+        let config = FileLogConfig::new(dir.join("nested").join("engine.log")).with_max_file_size(1024).with_max_files(3);
+        let sink = RotatingFileSink::new(config.clone()).unwrap();
+        // 每行32字节，50行共1600字节，只够轮转一次
+        for index in 0..50 {
+            sink.write_line(&format!("record {:04} {}", index, "x".repeat(19))).unwrap();
+        }
+        sink.flush();
+
+        assert!(config.rotated_path(1).exists());
+        assert!(!config.rotated_path(2).exists());
+        let rotated = std::fs::read_to_string(config.rotated_path(1)).unwrap();
+        let current = std::fs::read_to_string(&config.path).unwrap();
+        assert_eq!(rotated.lines().count(), 32);
+        assert!(rotated.starts_with("record 0000"));
+        assert_eq!(current.lines().count(), 18);
+        assert!(current.lines().last().unwrap().starts_with("record 0049"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotating_sink_keeps_max_files() {
+        let dir = log_dir("rotate");
+        let config = FileLogConfig::new(dir.join("engine.log")).with_max_file_size(16).with_max_files(2);
+        let sink = RotatingFileSink::new(config.clone()).unwrap();
+        for line in ["first line", "second line", "third line", "fourth line"] {
+            sink.write_line(line).unwrap();
+        }
+        sink.flush();
+
+        assert_eq!(std::fs::read_to_string(&config.path).unwrap(), "fourth line\n");
+        assert_eq!(std::fs::read_to_string(config.rotated_path(1)).unwrap(), "third line\n");
+        assert_eq!(std::fs::read_to_string(config.rotated_path(2)).unwrap(), "second line\n");
+        assert!(!config.rotated_path(3).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn zero_max_files_truncates_and_reopen_appends() {
+        let dir = log_dir("truncate");
+        let config = FileLogConfig::new(dir.join("engine.log")).with_max_file_size(16).with_max_files(0);
+        let sink = RotatingFileSink::new(config.clone()).unwrap();
+        sink.write_line("first line").unwrap();
+        sink.write_line("second line").unwrap();
+        sink.flush();
+        assert_eq!(std::fs::read_to_string(&config.path).unwrap(), "second line\n");
+        assert!(!config.rotated_path(1).exists());
+        drop(sink);
+
+        // 重新打开时沿用已有大小，下一行就触发清空
+        let sink = RotatingFileSink::new(config.clone()).unwrap();
+        sink.write_line("third line").unwrap();
+        sink.flush();
+        assert_eq!(std::fs::read_to_string(&config.path).unwrap(), "third line\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_config_follows_log_config() {
+        let config = LogConfig { level: "warn".to_string(), file_path: "logs/game.log".to_string(), max_file_size: 64, max_files: 7, ..LogConfig::default() };
+        let file_config = config.file_sink_config();
+        assert_eq!(file_config, FileLogConfig::new("logs/game.log").with_level(LevelFilter::Warn).with_max_file_size(64).with_max_files(7));
+        assert_eq!(file_config.rotated_path(3), PathBuf::from("logs/game.log.3"));
+        assert_eq!(parse_level_filter("verbose"), LevelFilter::Info);
+
+        let dir = log_dir("level");
+        let sink = RotatingFileSink::new(FileLogConfig::new(dir.join("engine.log")).with_level(LevelFilter::Warn)).unwrap();
+        assert!(sink.enabled(Level::Error));
+        assert!(!sink.enabled(Level::Info));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_lines_include_sorted_fields_without_colors() {
+        let fields = [("subsystem", "render"), ("frame", "42")];
+        let line = EnvFileLogger::format_file_line(
+            &log::Record::builder()
+                .level(Level::Warn)
+                .target("test_file_line")
+                .key_values(&fields)
+                .args(format_args!("丢帧"))
+                .build(),
+        );
+        assert!(line.ends_with("[WARN] test_file_line: 丢帧 frame=42 subsystem=render"), "{}", line);
+        assert_eq!(strip_ansi_colors("\x1b[33m[WARN]\x1b[0m 丢帧"), "[WARN] 丢帧");
+    }
+}
@@ -1,9 +1,15 @@
 //! 核心引擎模块
 
 pub mod engine;
+pub mod config;
 pub mod app;
 pub mod logging;
+pub mod pool;
+pub mod window;
 
 pub use engine::*;
+pub use config::*;
 pub use app::*;
 pub use logging::*;
+pub use pool::*;
+pub use window::*;
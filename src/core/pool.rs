@@ -0,0 +1,212 @@
+//! 对象池
+//!
+//! 频繁生成和销毁的对象(粒子、子弹等)通过对象池复用实例，避免反复分配内存。
+
+/// 对象池统计，`created` 对应真实的内存分配次数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// 新创建的实例数
+    pub created: usize,
+    /// 复用的实例数
+    pub reused: usize,
+    /// 归还的实例数
+    pub released: usize,
+    /// 空闲列表已满而被丢弃的实例数
+    pub discarded: usize,
+}
+
+impl PoolStats {
+    /// 复用率
+    pub fn reuse_ratio(&self) -> f32 {
+        let total = self.created + self.reused;
+        if total == 0 {
+            0.0
+        } else {
+            self.reused as f32 / total as f32
+        }
+    }
+}
+
+/// 通用对象池
+pub struct Pool<T> {
+    free: Vec<T>,
+    factory: Box<dyn Fn() -> T + Send + Sync>,
+    reset: Option<Box<dyn Fn(&mut T) + Send + Sync>>,
+    max_free: usize,
+    in_use: usize,
+    stats: PoolStats,
+}
+
+impl<T> Pool<T> {
+    /// 空闲列表的默认上限
+    pub const DEFAULT_MAX_FREE: usize = 1024;
+
+    /// 使用工厂函数创建对象池
+    pub fn new(factory: impl Fn() -> T + Send + Sync + 'static) -> Self {
+        Self {
+            free: Vec::new(),
+            factory: Box::new(factory),
+            reset: None,
+            max_free: Self::DEFAULT_MAX_FREE,
+            in_use: 0,
+            stats: PoolStats::default(),
+        }
+    }
+
+    /// 设置归还时的重置函数
+    pub fn with_reset(mut self, reset: impl Fn(&mut T) + Send + Sync + 'static) -> Self {
+        self.reset = Some(Box::new(reset));
+        self
+    }
+
+    /// 设置空闲列表上限，超出的归还实例直接丢弃
+    pub fn with_max_free(mut self, max_free: usize) -> Self {
+        self.max_free = max_free;
+        self.free.truncate(max_free);
+        self
+    }
+
+    /// 预先创建实例
+    pub fn prewarm(&mut self, count: usize) {
+        let count = count.min(self.max_free.saturating_sub(self.free.len()));
+        self.free.reserve(count);
+        for _ in 0..count {
+            self.free.push((self.factory)());
+            self.stats.created += 1;
+        }
+    }
+
+    /// 取出一个实例，优先复用空闲实例
+    pub fn acquire(&mut self) -> T {
+        self.in_use += 1;
+        match self.free.pop() {
+            Some(item) => {
+                self.stats.reused += 1;
+                item
+            }
+            None => {
+                self.stats.created += 1;
+                (self.factory)()
+            }
+        }
+    }
+
+    /// 归还实例
+    pub fn release(&mut self, mut item: T) {
+        self.in_use = self.in_use.saturating_sub(1);
+        self.stats.released += 1;
+
+        if self.free.len() >= self.max_free {
+            self.stats.discarded += 1;
+            return;
+        }
+        if let Some(reset) = &self.reset {
+            reset(&mut item);
+        }
+        self.free.push(item);
+    }
+
+    /// 批量归还实例
+    pub fn release_all(&mut self, items: impl IntoIterator<Item = T>) {
+        for item in items {
+            self.release(item);
+        }
+    }
+
+    /// 空闲实例数
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// 已取出未归还的实例数
+    pub fn in_use(&self) -> usize {
+        self.in_use
+    }
+
+    /// 空闲列表的容量
+    pub fn capacity(&self) -> usize {
+        self.free.capacity()
+    }
+
+    /// 统计信息
+    pub fn stats(&self) -> PoolStats {
+        self.stats
+    }
+
+    /// 释放所有空闲实例
+    pub fn shrink(&mut self) {
+        self.free.clear();
+        self.free.shrink_to_fit();
+    }
+}
+
+impl<T: Default + 'static> Default for Pool<T> {
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T> std::fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pool")
+            .field("free", &self.free.len())
+            .field("in_use", &self.in_use)
+            .field("max_free", &self.max_free)
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire_release_loop_keeps_capacity_stable() {
+        let mut pool: Pool<Vec<u8>> = Pool::new(|| Vec::with_capacity(64));
+        let mut items: Vec<_> = (0..8).map(|_| pool.acquire()).collect();
+        pool.release_all(items.drain(..));
+        let capacity = pool.capacity();
+
+        for _ in 0..1000 {
+            items.extend((0..8).map(|_| pool.acquire()));
+            pool.release_all(items.drain(..));
+        }
+
+        assert_eq!(pool.capacity(), capacity);
+        assert_eq!(pool.free_count(), 8);
+        assert_eq!(pool.in_use(), 0);
+        // 只有第一轮真正分配，其余全部复用空闲实例
+        assert_eq!(pool.stats().created, 8);
+        assert_eq!(pool.stats().reused, 8000);
+    }
+
+    #[test]
+    fn released_items_are_reset_and_reused() {
+        let mut pool = Pool::new(Vec::<i32>::new).with_reset(|v| v.clear());
+        let mut item = pool.acquire();
+        item.extend([1, 2, 3]);
+        let buffer = item.as_ptr();
+        pool.release(item);
+
+        let reused = pool.acquire();
+        assert!(reused.is_empty());
+        assert_eq!(reused.as_ptr(), buffer);
+        assert_eq!(pool.stats().reuse_ratio(), 0.5);
+    }
+
+    #[test]
+    fn free_list_is_bounded() {
+        let mut pool = Pool::<u32>::default().with_max_free(2);
+        let items: Vec<_> = (0..4).map(|_| pool.acquire()).collect();
+        pool.release_all(items);
+
+        assert_eq!(pool.free_count(), 2);
+        assert_eq!(pool.stats().discarded, 2);
+
+        pool.prewarm(10);
+        assert_eq!(pool.free_count(), 2);
+        pool.shrink();
+        assert_eq!(pool.free_count(), 0);
+    }
+}
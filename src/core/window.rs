@@ -0,0 +1,181 @@
+//! 游戏窗口
+//!
+//! 在运行时修改窗口标题和图标，实际调用由 `WindowBackend` 转发给winit窗口。
+
+use crate::{EngineResult, EngineError};
+use crate::assets::AssetHandle;
+use crate::render::{Texture, TextureFormat};
+use std::sync::Arc;
+use winit::window::Icon;
+
+/// 窗口后端
+pub trait WindowBackend: Send + Sync {
+    fn set_title(&self, title: &str);
+    fn set_icon(&self, icon: Option<Icon>);
+}
+
+impl WindowBackend for winit::window::Window {
+    fn set_title(&self, title: &str) {
+        winit::window::Window::set_title(self, title);
+    }
+
+    fn set_icon(&self, icon: Option<Icon>) {
+        self.set_window_icon(icon);
+    }
+}
+
+/// 游戏窗口状态
+pub struct Window {
+    title: String,
+    icon_size: Option<(u32, u32)>,
+    backend: Option<Arc<dyn WindowBackend>>,
+}
+
+impl Window {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            icon_size: None,
+            backend: None,
+        }
+    }
+
+    /// 绑定实际窗口，并应用已设置的标题
+    pub fn attach(&mut self, backend: Arc<dyn WindowBackend>) {
+        backend.set_title(&self.title);
+        self.backend = Some(backend);
+    }
+
+    /// 当前标题
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// 设置窗口标题
+    pub fn set_title(&mut self, title: &str) {
+        if self.title == title {
+            return;
+        }
+        self.title = title.to_string();
+        if let Some(backend) = &self.backend {
+            backend.set_title(title);
+        }
+    }
+
+    /// 使用纹理资源设置窗口图标
+    pub fn set_icon(&mut self, texture: AssetHandle<Texture>) -> EngineResult<()> {
+        let texture = texture
+            .get()
+            .ok_or_else(|| EngineError::AssetError(format!("图标纹理未加载: {}", texture.path())))?;
+        let icon = icon_from_texture(&texture)?;
+        self.icon_size = Some((texture.descriptor.width, texture.descriptor.height));
+        if let Some(backend) = &self.backend {
+            backend.set_icon(Some(icon));
+        }
+        Ok(())
+    }
+
+    /// 移除窗口图标
+    pub fn clear_icon(&mut self) {
+        self.icon_size = None;
+        if let Some(backend) = &self.backend {
+            backend.set_icon(None);
+        }
+    }
+
+    /// 当前图标尺寸
+    pub fn icon_size(&self) -> Option<(u32, u32)> {
+        self.icon_size
+    }
+}
+
+/// 将纹理转换为winit图标(RGBA8)
+pub fn icon_from_texture(texture: &Texture) -> EngineResult<Icon> {
+    let (width, height) = (texture.descriptor.width, texture.descriptor.height);
+    let rgba = match texture.descriptor.format {
+        TextureFormat::Rgba8 => texture.data.clone(),
+        TextureFormat::Rgb8 => texture.data
+            .chunks_exact(3)
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+            .collect(),
+        TextureFormat::R8 => texture.data
+            .iter()
+            .flat_map(|&value| [value, value, value, 255])
+            .collect(),
+        format => {
+            return Err(EngineError::AssetError(format!("不支持的图标纹理格式: {:?}", format)).into());
+        }
+    };
+
+    Icon::from_rgba(rgba, width, height)
+        .map_err(|e| EngineError::AssetError(format!("创建窗口图标失败: {}", e)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::TextureDescriptor;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingBackend {
+        titles: Mutex<Vec<String>>,
+        icons: Mutex<Vec<bool>>,
+    }
+
+    impl WindowBackend for RecordingBackend {
+        fn set_title(&self, title: &str) {
+            self.titles.lock().unwrap().push(title.to_string());
+        }
+
+        fn set_icon(&self, icon: Option<Icon>) {
+            self.icons.lock().unwrap().push(icon.is_some());
+        }
+    }
+
+    fn texture_handle(texture: Texture) -> AssetHandle<Texture> {
+        AssetHandle::new(1, &Arc::new(texture), "icon.png")
+    }
+
+    #[test]
+    fn title_is_forwarded_once_attached() {
+        let backend = Arc::new(RecordingBackend::default());
+        let mut window = Window::new("Sanji");
+        window.set_title("Before");
+        window.attach(backend.clone());
+        window.set_title("After");
+        window.set_title("After");
+
+        assert_eq!(window.title(), "After");
+        assert_eq!(*backend.titles.lock().unwrap(), vec!["Before", "After"]);
+    }
+
+    #[test]
+    fn icon_is_set_from_loaded_texture() {
+        let backend = Arc::new(RecordingBackend::default());
+        let mut window = Window::new("Sanji");
+        window.attach(backend.clone());
+
+        window.set_icon(texture_handle(Texture::solid_color(16, 16, [255, 0, 0, 255]))).unwrap();
+        assert_eq!(window.icon_size(), Some((16, 16)));
+        window.clear_icon();
+        assert_eq!(window.icon_size(), None);
+        assert_eq!(*backend.icons.lock().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn unloaded_icon_texture_is_an_error() {
+        let mut window = Window::new("Sanji");
+        assert!(window.set_icon(AssetHandle::loading(1, "icon.png")).is_err());
+        assert_eq!(window.icon_size(), None);
+    }
+
+    #[test]
+    fn rgb_and_gray_textures_expand_to_rgba() {
+        let descriptor = |format| TextureDescriptor { width: 2, height: 1, format, ..Default::default() };
+        assert!(icon_from_texture(&Texture::new(descriptor(TextureFormat::Rgb8), vec![0; 6], "rgb")).is_ok());
+        assert!(icon_from_texture(&Texture::new(descriptor(TextureFormat::R8), vec![0; 2], "gray")).is_ok());
+        // 数据长度与尺寸不符时失败
+        assert!(icon_from_texture(&Texture::new(descriptor(TextureFormat::Rgba8), vec![0; 4], "short")).is_err());
+    }
+}
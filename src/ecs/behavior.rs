@@ -0,0 +1,287 @@
+//! 实体行为脚本
+//!
+//! 逐实体的游戏逻辑以实现 `Behavior` 的普通Rust结构体编写，挂在实体的 `ScriptComponent` 上，
+//! 由 `BehaviorSystem` 每帧驱动：首次运行前调用一次 `on_start`，之后每帧先投递发给该实体的事件，再调用 `on_update`。
+//! 行为事件只在实体之间传递，与全局 `EventSystem` 相互独立。
+
+use crate::ecs::component::Transform;
+use crate::ecs::SubsystemErrors;
+use specs::{Entity, WriteStorage};
+use std::collections::HashMap;
+
+/// 发给实体行为的事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct BehaviorEvent {
+    pub name: String,
+    /// 发送事件的实体，由外部代码发送时为None
+    pub sender: Option<Entity>,
+    /// 可选的数值参数
+    pub value: f32,
+}
+
+impl BehaviorEvent {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            sender: None,
+            value: 0.0,
+        }
+    }
+
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn is(&self, name: &str) -> bool {
+        self.name == name
+    }
+}
+
+/// 待投递的行为事件，在下一次 `BehaviorSystem` 运行时送达
+#[derive(Debug, Default)]
+pub struct BehaviorEvents {
+    pending: Vec<(Entity, BehaviorEvent)>,
+}
+
+impl BehaviorEvents {
+    /// 向目标实体的行为发送事件
+    pub fn send(&mut self, target: Entity, event: BehaviorEvent) {
+        self.pending.push((target, event));
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// 取出所有待投递事件，按目标实体分组并保持发送顺序
+    pub(crate) fn take_grouped(&mut self) -> HashMap<Entity, Vec<BehaviorEvent>> {
+        let mut grouped: HashMap<Entity, Vec<BehaviorEvent>> = HashMap::new();
+        for (target, event) in self.pending.drain(..) {
+            grouped.entry(target).or_default().push(event);
+        }
+        grouped
+    }
+}
+
+/// 行为回调的上下文：当前实体、帧时间、实体变换、事件发送和错误报告
+pub struct BehaviorContext<'a, 's> {
+    pub entity: Entity,
+    pub delta_time: f32,
+    pub total_time: f32,
+    transforms: &'a mut WriteStorage<'s, Transform>,
+    outbox: &'a mut Vec<(Entity, BehaviorEvent)>,
+    errors: &'a mut SubsystemErrors,
+}
+
+impl<'a, 's> BehaviorContext<'a, 's> {
+    pub(crate) fn new(
+        entity: Entity,
+        delta_time: f32,
+        total_time: f32,
+        transforms: &'a mut WriteStorage<'s, Transform>,
+        outbox: &'a mut Vec<(Entity, BehaviorEvent)>,
+        errors: &'a mut SubsystemErrors,
+    ) -> Self {
+        Self {
+            entity,
+            delta_time,
+            total_time,
+            transforms,
+            outbox,
+            errors,
+        }
+    }
+
+    /// 当前实体的变换
+    pub fn transform(&self) -> Option<&Transform> {
+        self.transforms.get(self.entity)
+    }
+
+    pub fn transform_mut(&mut self) -> Option<&mut Transform> {
+        self.transforms.get_mut(self.entity)
+    }
+
+    /// 其他实体的变换
+    pub fn transform_of(&self, entity: Entity) -> Option<&Transform> {
+        self.transforms.get(entity)
+    }
+
+    /// 向其他实体(或自己)发送事件，下一帧送达
+    pub fn send(&mut self, target: Entity, event: BehaviorEvent) {
+        let event = BehaviorEvent {
+            sender: Some(self.entity),
+            ..event
+        };
+        self.outbox.push((target, event));
+    }
+
+    /// 报告行为运行失败，本帧结束后由引擎记录并把ECS子系统标记为降级
+    pub fn report_error(&mut self, error: impl std::fmt::Display) {
+        self.errors.report("behavior", format!("实体{}: {}", self.entity.id(), error));
+    }
+}
+
+/// 实体行为
+pub trait Behavior: Send + Sync {
+    /// 行为名称，用于调试和按名称查找
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// 第一次更新之前调用一次
+    fn on_start(&mut self, _ctx: &mut BehaviorContext) {}
+
+    /// 每帧调用一次
+    fn on_update(&mut self, ctx: &mut BehaviorContext, delta_time: f32);
+
+    /// 收到发给该实体的事件
+    fn on_event(&mut self, _ctx: &mut BehaviorContext, _event: &BehaviorEvent) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{ECSWorld, ScriptComponent};
+    use glam::Vec3;
+    use specs::{Builder, WorldExt};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// 统计回调次数并沿X轴移动实体
+    #[derive(Default)]
+    struct Counter {
+        starts: Arc<AtomicUsize>,
+        updates: Arc<AtomicUsize>,
+    }
+
+    impl Behavior for Counter {
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        fn on_start(&mut self, _ctx: &mut BehaviorContext) {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_update(&mut self, ctx: &mut BehaviorContext, delta_time: f32) {
+            self.updates.fetch_add(1, Ordering::SeqCst);
+            if let Some(transform) = ctx.transform_mut() {
+                transform.position.x += delta_time;
+            }
+        }
+    }
+
+    /// 收到ping时回复pong，记录收到的事件
+    struct Echo {
+        received: Arc<Mutex<Vec<BehaviorEvent>>>,
+    }
+
+    impl Behavior for Echo {
+        fn on_update(&mut self, _ctx: &mut BehaviorContext, _delta_time: f32) {}
+
+        fn on_event(&mut self, ctx: &mut BehaviorContext, event: &BehaviorEvent) {
+            self.received.lock().unwrap().push(event.clone());
+            if let (true, Some(sender)) = (event.is("ping"), event.sender) {
+                ctx.send(sender, BehaviorEvent::new("pong").with_value(event.value * 2.0));
+            }
+        }
+    }
+
+    /// 前几帧报告错误
+    struct Flaky {
+        failures: u32,
+    }
+
+    impl Behavior for Flaky {
+        fn on_update(&mut self, ctx: &mut BehaviorContext, _delta_time: f32) {
+            if self.failures > 0 {
+                self.failures -= 1;
+                ctx.report_error("找不到目标");
+            }
+        }
+    }
+
+    #[test]
+    fn reported_errors_fail_the_ecs_update() {
+        let mut world = ECSWorld::new().unwrap();
+        let entity = world
+            .create_entity()
+            .with(ScriptComponent::new().with_behavior(Flaky { failures: 1 }))
+            .build();
+
+        let error = world.update(0.1).unwrap_err().to_string();
+        assert!(error.contains(&format!("behavior: 实体{}: 找不到目标", entity.id())), "{}", error);
+        // 错误只属于当帧
+        world.update(0.1).unwrap();
+    }
+
+    #[test]
+    fn counter_starts_once_and_updates_every_frame() {
+        let mut world = ECSWorld::new().unwrap();
+        let counter = Counter::default();
+        let (starts, updates) = (counter.starts.clone(), counter.updates.clone());
+        let entity = world
+            .create_entity()
+            .with(Transform::default())
+            .with(ScriptComponent::new().with_behavior(counter))
+            .build();
+
+        for _ in 0..5 {
+            world.update(0.25).unwrap();
+        }
+
+        assert_eq!(starts.load(Ordering::SeqCst), 1);
+        assert_eq!(updates.load(Ordering::SeqCst), 5);
+        let transforms = world.world().read_storage::<Transform>();
+        assert_eq!(transforms.get(entity).unwrap().position, Vec3::new(1.25, 0.0, 0.0));
+    }
+
+    #[test]
+    fn events_are_delivered_to_target_on_next_frame() {
+        let mut world = ECSWorld::new().unwrap();
+        let (a_log, b_log) = (Arc::new(Mutex::new(Vec::new())), Arc::new(Mutex::new(Vec::new())));
+        let a = world
+            .create_entity()
+            .with(ScriptComponent::new().with_behavior(Echo { received: a_log.clone() }))
+            .build();
+        let b = world
+            .create_entity()
+            .with(ScriptComponent::new().with_behavior(Echo { received: b_log.clone() }))
+            .build();
+
+        // 模拟a发给b的ping
+        world.world().write_resource::<BehaviorEvents>().send(b, BehaviorEvent {
+            sender: Some(a),
+            ..BehaviorEvent::new("ping").with_value(1.5)
+        });
+        world.update(0.1).unwrap();
+        assert_eq!(b_log.lock().unwrap().len(), 1);
+        // 回复在下一帧才送达
+        assert!(a_log.lock().unwrap().is_empty());
+
+        world.update(0.1).unwrap();
+        let a_received = a_log.lock().unwrap();
+        assert_eq!(a_received.len(), 1);
+        assert!(a_received[0].is("pong"));
+        assert_eq!(a_received[0].sender, Some(b));
+        assert_eq!(a_received[0].value, 3.0);
+        assert!(world.world().read_resource::<BehaviorEvents>().is_empty());
+    }
+
+    #[test]
+    fn script_component_manages_behaviors_by_name() {
+        let mut script = ScriptComponent::new().with_behavior(Counter::default());
+        script.add_behavior(Echo { received: Arc::default() });
+        assert_eq!(script.len(), 2);
+        assert_eq!(script.behavior_names()[0], "counter");
+
+        assert!(script.remove_behavior("counter"));
+        assert!(!script.remove_behavior("counter"));
+        assert_eq!(script.len(), 1);
+    }
+}
@@ -1,9 +1,12 @@
 //! 核心组件定义
 
-use crate::render::{Camera as RenderCamera, Mesh, Material};
-use glam::{Vec3, Quat, Mat4};
+use crate::render::{Camera as RenderCamera, Fog, Mesh, Material, Skybox};
+use crate::math::{smoothstep, Coordinate, Spline};
+use crate::ecs::behavior::{Behavior, BehaviorContext, BehaviorEvent};
+use glam::{Vec2, Vec3, Vec4, Quat, Mat4};
 use serde::{Deserialize, Serialize};
-use specs::{Component, VecStorage, DenseVecStorage, HashMapStorage};
+use std::sync::Arc;
+use specs::{Component, VecStorage, DenseVecStorage, HashMapStorage, NullStorage};
 use specs_derive::Component;
 
 /// 变换组件 - 定义对象的位置、旋转和缩放
@@ -16,15 +19,13 @@ pub struct Transform {
     pub rotation: Quat,
     /// 缩放
     pub scale: Vec3,
-    /// 局部变换矩阵(缓存)
+    /// 计算缓存矩阵时的位置、旋转和缩放，与当前值不同即视为缓存失效，
+    /// 因此直接修改公开字段也会触发重新计算；反序列化后为None
     #[serde(skip)]
-    pub local_matrix: Mat4,
+    cached_inputs: Option<(Vec3, Quat, Vec3)>,
     /// 世界变换矩阵(缓存)
     #[serde(skip)]
-    pub world_matrix: Mat4,
-    /// 是否需要更新矩阵
-    #[serde(skip)]
-    pub dirty: bool,
+    cached_world_matrix: Mat4,
 }
 
 impl Default for Transform {
@@ -33,9 +34,8 @@ impl Default for Transform {
             position: Vec3::ZERO,
             rotation: Quat::IDENTITY,
             scale: Vec3::ONE,
-            local_matrix: Mat4::IDENTITY,
-            world_matrix: Mat4::IDENTITY,
-            dirty: true,
+            cached_inputs: None,
+            cached_world_matrix: Mat4::IDENTITY,
         }
     }
 }
@@ -46,70 +46,136 @@ impl Transform {
         Self::default()
     }
 
+    /// 构建指定位置的变换
+    pub fn with_position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// 构建指定旋转的变换
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// 构建指定缩放的变换
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
     /// 设置位置
     pub fn set_position(&mut self, position: Vec3) {
         self.position = position;
-        self.dirty = true;
     }
 
     /// 设置旋转
     pub fn set_rotation(&mut self, rotation: Quat) {
         self.rotation = rotation;
-        self.dirty = true;
     }
 
     /// 设置缩放
     pub fn set_scale(&mut self, scale: Vec3) {
         self.scale = scale;
-        self.dirty = true;
+    }
+
+    /// 角度表示的欧拉角(x=俯仰, y=偏航, z=翻滚)，顺序见 `Coordinate::EULER_ORDER`
+    pub fn euler_degrees(&self) -> Vec3 {
+        Coordinate::euler_degrees(self.rotation)
+    }
+
+    /// 按角度欧拉角设置旋转
+    pub fn set_euler_degrees(&mut self, degrees: Vec3) {
+        self.set_rotation(Coordinate::rotation_from_euler_degrees(degrees));
     }
 
     /// 平移
     pub fn translate(&mut self, translation: Vec3) {
         self.position += translation;
-        self.dirty = true;
     }
 
     /// 旋转
     pub fn rotate(&mut self, rotation: Quat) {
         self.rotation = self.rotation * rotation;
-        self.dirty = true;
     }
 
     /// 缩放
     pub fn scale_by(&mut self, scale: Vec3) {
         self.scale *= scale;
-        self.dirty = true;
     }
 
     /// 获取前方向量
     pub fn forward(&self) -> Vec3 {
-        self.rotation * Vec3::NEG_Z
+        self.rotation * Coordinate::FORWARD
     }
 
     /// 获取右方向量
     pub fn right(&self) -> Vec3 {
-        self.rotation * Vec3::X
+        self.rotation * Coordinate::RIGHT
     }
 
     /// 获取上方向量
     pub fn up(&self) -> Vec3 {
-        self.rotation * Vec3::Y
+        self.rotation * Coordinate::UP
+    }
+
+    /// 旋转使前方向量(-Z)朝向目标点
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        self.look_to(target - self.position, up);
+    }
+
+    /// 旋转使前方向量(-Z)朝向指定方向
+    pub fn look_to(&mut self, direction: Vec3, up: Vec3) {
+        if direction.normalize_or_zero() == Vec3::ZERO {
+            return;
+        }
+
+        self.rotation = Coordinate::rotation_looking_to(direction, up);
+    }
+
+    /// 构建朝向目标点的变换
+    pub fn looking_at(mut self, target: Vec3, up: Vec3) -> Self {
+        self.look_at(target, up);
+        self
+    }
+
+    /// 强制下次重新计算矩阵
+    pub fn mark_dirty(&mut self) {
+        self.cached_inputs = None;
+    }
+
+    /// 矩阵缓存是否失效(位置、旋转或缩放与计算缓存时不同)
+    pub fn is_dirty(&self) -> bool {
+        self.cached_inputs != Some((self.position, self.rotation, self.scale))
     }
 
     /// 更新变换矩阵
     pub fn update_matrices(&mut self) {
-        if self.dirty {
-            self.local_matrix = Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.position);
-            self.world_matrix = self.local_matrix; // 简化版本，不考虑父子关系
-            self.dirty = false;
+        if self.is_dirty() {
+            // 简化版本，不考虑父子关系
+            self.cached_world_matrix = self.local_matrix();
+            self.cached_inputs = Some((self.position, self.rotation, self.scale));
         }
     }
 
-    /// 获取世界矩阵
+    /// 获取世界矩阵，仅在变换被修改后重新计算
     pub fn world_matrix(&mut self) -> Mat4 {
         self.update_matrices();
-        self.world_matrix
+        self.cached_world_matrix
+    }
+
+    /// 不更新缓存的世界矩阵：缓存失效时按当前位置、旋转和缩放计算
+    pub fn current_world_matrix(&self) -> Mat4 {
+        if self.is_dirty() {
+            self.local_matrix()
+        } else {
+            self.cached_world_matrix
+        }
+    }
+
+    /// 由当前位置、旋转和缩放计算的局部矩阵
+    pub fn local_matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.position)
     }
 }
 
@@ -146,12 +212,432 @@ impl MeshRenderer {
     }
 }
 
+/// 精灵渲染组件(2D)
+///
+/// 精灵位于Transform的XY平面内，尺寸以世界单位表示并受Transform缩放影响。
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[storage(VecStorage)]
+pub struct SpriteRenderer {
+    /// 纹理名称，相同纹理的精灵合批绘制
+    pub texture_name: String,
+    /// 纹理中的UV矩形(左上角最小值)，用于图集中的子图
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+    /// 颜色叠加(与纹理颜色相乘)
+    pub color: Vec4,
+    pub size: Vec2,
+    /// 轴心点，(0.5, 0.5)为中心
+    pub pivot: Vec2,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// 排序顺序，值大的绘制在上层
+    pub sorting_order: i32,
+    pub visible: bool,
+}
+
+impl Default for SpriteRenderer {
+    fn default() -> Self {
+        Self {
+            texture_name: "default".to_string(),
+            uv_min: Vec2::ZERO,
+            uv_max: Vec2::ONE,
+            color: Vec4::ONE,
+            size: Vec2::ONE,
+            pivot: Vec2::splat(0.5),
+            flip_x: false,
+            flip_y: false,
+            sorting_order: 0,
+            visible: true,
+        }
+    }
+}
+
+impl SpriteRenderer {
+    pub fn new(texture_name: impl Into<String>) -> Self {
+        Self {
+            texture_name: texture_name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 设置图集中的UV矩形
+    pub fn with_uv_rect(mut self, uv_min: Vec2, uv_max: Vec2) -> Self {
+        self.uv_min = uv_min;
+        self.uv_max = uv_max;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_sorting_order(mut self, sorting_order: i32) -> Self {
+        self.sorting_order = sorting_order;
+        self
+    }
+
+    /// 应用翻转后的UV矩形，返回(左上角UV, 右下角UV)
+    pub fn flipped_uv_rect(&self) -> (Vec2, Vec2) {
+        let (mut min, mut max) = (self.uv_min, self.uv_max);
+        if self.flip_x {
+            std::mem::swap(&mut min.x, &mut max.x);
+        }
+        if self.flip_y {
+            std::mem::swap(&mut min.y, &mut max.y);
+        }
+        (min, max)
+    }
+}
+
+/// 精灵图集中的一帧
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpriteSheetFrame {
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+}
+
+/// 精灵图集：同一纹理中按播放顺序排列的帧
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpriteSheet {
+    pub texture_name: String,
+    pub frames: Vec<SpriteSheetFrame>,
+}
+
+impl SpriteSheet {
+    pub fn new(texture_name: impl Into<String>, frames: Vec<SpriteSheetFrame>) -> Self {
+        Self {
+            texture_name: texture_name.into(),
+            frames,
+        }
+    }
+
+    /// 把纹理等分为columns x rows的网格，按行从左上角取前frame_count帧(0表示全部)
+    pub fn from_grid(texture_name: impl Into<String>, columns: u32, rows: u32, frame_count: u32) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let total = columns * rows;
+        let count = if frame_count == 0 { total } else { frame_count.min(total) };
+        let cell = Vec2::new(1.0 / columns as f32, 1.0 / rows as f32);
+
+        let frames = (0..count)
+            .map(|index| {
+                let uv_min = Vec2::new((index % columns) as f32, (index / columns) as f32) * cell;
+                SpriteSheetFrame { uv_min, uv_max: uv_min + cell }
+            })
+            .collect();
+        Self::new(texture_name, frames)
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn frame(&self, index: usize) -> Option<&SpriteSheetFrame> {
+        self.frames.get(index)
+    }
+}
+
+/// 精灵帧动画组件 - 按帧率切换同实体 `SpriteRenderer` 的纹理与UV矩形
+#[derive(Component, Debug, Clone)]
+#[storage(DenseVecStorage)]
+pub struct SpriteAnimation {
+    pub sheet: Arc<SpriteSheet>,
+    /// 每秒帧数
+    pub fps: f32,
+    pub mode: PathLoopMode,
+    pub playing: bool,
+    /// 当前显示的帧索引
+    pub current_frame: usize,
+    /// 当前帧已显示的时间(秒)
+    pub frame_time: f32,
+    /// 往返模式下的当前方向(1或-1)
+    pub direction: i32,
+    /// Once模式下是否已停在最后一帧
+    pub finished: bool,
+    /// 使用真实时间播放，游戏暂停或慢动作时继续以正常速度运行(UI动画)
+    pub use_unscaled_time: bool,
+}
+
+impl SpriteAnimation {
+    pub fn new(sheet: Arc<SpriteSheet>, fps: f32) -> Self {
+        Self {
+            sheet,
+            fps,
+            mode: PathLoopMode::Loop,
+            playing: true,
+            current_frame: 0,
+            frame_time: 0.0,
+            direction: 1,
+            finished: false,
+            use_unscaled_time: false,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: PathLoopMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_unscaled_time(mut self, use_unscaled_time: bool) -> Self {
+        self.use_unscaled_time = use_unscaled_time;
+        self
+    }
+
+    /// 前进delta_time秒，返回期间到达最后一帧的次数
+    pub fn advance(&mut self, delta_time: f32) -> u32 {
+        let frame_count = self.sheet.frame_count();
+        if !self.playing || self.finished || frame_count < 2 || self.fps <= 0.0 {
+            return 0;
+        }
+
+        let frame_duration = 1.0 / self.fps;
+        let last = frame_count - 1;
+        let mut last_frame_hits = 0;
+        self.frame_time += delta_time;
+        while self.frame_time >= frame_duration {
+            self.frame_time -= frame_duration;
+            match self.mode {
+                PathLoopMode::Once => {
+                    self.current_frame = (self.current_frame + 1).min(last);
+                }
+                PathLoopMode::Loop => {
+                    self.current_frame = (self.current_frame + 1) % frame_count;
+                }
+                PathLoopMode::PingPong => {
+                    // 到达两端时反向，端点帧不重复显示
+                    if (self.direction > 0 && self.current_frame >= last) || (self.direction < 0 && self.current_frame == 0) {
+                        self.direction = -self.direction;
+                    }
+                    self.current_frame = (self.current_frame as i32 + self.direction) as usize;
+                }
+            }
+
+            if self.current_frame == last {
+                last_frame_hits += 1;
+                if self.mode == PathLoopMode::Once {
+                    self.finished = true;
+                    self.frame_time = 0.0;
+                    break;
+                }
+            }
+        }
+        last_frame_hits
+    }
+
+    /// 当前帧，图集为空时为None
+    pub fn current(&self) -> Option<&SpriteSheetFrame> {
+        self.sheet.frame(self.current_frame)
+    }
+
+    /// 把当前帧写入精灵渲染组件
+    pub fn apply(&self, sprite: &mut SpriteRenderer) {
+        if let Some(frame) = self.current() {
+            sprite.uv_min = frame.uv_min;
+            sprite.uv_max = frame.uv_max;
+        }
+        if sprite.texture_name != self.sheet.texture_name {
+            sprite.texture_name.clone_from(&self.sheet.texture_name);
+        }
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// 回到第一帧并开始播放
+    pub fn restart(&mut self) {
+        self.current_frame = 0;
+        self.frame_time = 0.0;
+        self.direction = 1;
+        self.finished = false;
+        self.playing = true;
+    }
+}
+
+/// 瓦片
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tile {
+    /// 图集中的瓦片索引，按行从左上角开始编号
+    pub index: u32,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+impl Tile {
+    pub fn new(index: u32) -> Self {
+        Self { index, flip_x: false, flip_y: false }
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+}
+
+/// 瓦片地图组件(2D)
+///
+/// 网格原点(0, 0)位于Transform位置，x向右、y向上；每个实体是一个图层，按 `sorting_order` 叠加。
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[storage(VecStorage)]
+pub struct Tilemap {
+    /// 图集纹理名称
+    pub tileset: String,
+    /// 图集的列数与行数
+    pub tileset_columns: u32,
+    pub tileset_rows: u32,
+    width: u32,
+    height: u32,
+    /// 单个瓦片的世界尺寸
+    pub tile_size: Vec2,
+    /// 按行存储的瓦片，None为空格
+    tiles: Vec<Option<Tile>>,
+    pub color: Vec4,
+    pub sorting_order: i32,
+    pub visible: bool,
+    /// 瓦片修改计数，渲染器据此判断是否需要重建区块
+    #[serde(skip)]
+    revision: u64,
+}
+
+impl Tilemap {
+    pub fn new(tileset: impl Into<String>, tileset_columns: u32, tileset_rows: u32, width: u32, height: u32) -> Self {
+        Self {
+            tileset: tileset.into(),
+            tileset_columns: tileset_columns.max(1),
+            tileset_rows: tileset_rows.max(1),
+            width,
+            height,
+            tile_size: Vec2::ONE,
+            tiles: vec![None; (width * height) as usize],
+            color: Vec4::ONE,
+            sorting_order: 0,
+            visible: true,
+            revision: 0,
+        }
+    }
+
+    pub fn with_tile_size(mut self, tile_size: Vec2) -> Self {
+        self.tile_size = tile_size;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_sorting_order(mut self, sorting_order: i32) -> Self {
+        self.sorting_order = sorting_order;
+        self
+    }
+
+    /// 由按行排列的索引填充，负数为空格
+    pub fn with_indices(mut self, indices: &[i32]) -> Self {
+        for (slot, &index) in self.tiles.iter_mut().zip(indices) {
+            *slot = u32::try_from(index).ok().map(Tile::new);
+        }
+        self.revision += 1;
+        self
+    }
+
+    fn slot(&self, x: u32, y: u32) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| (y * self.width + x) as usize)
+    }
+
+    pub fn tile(&self, x: u32, y: u32) -> Option<Tile> {
+        self.slot(x, y).and_then(|slot| self.tiles[slot])
+    }
+
+    /// 设置瓦片，越界时忽略
+    pub fn set_tile(&mut self, x: u32, y: u32, tile: Option<Tile>) {
+        if let Some(slot) = self.slot(x, y) {
+            if self.tiles[slot] != tile {
+                self.tiles[slot] = tile;
+                self.revision += 1;
+            }
+        }
+    }
+
+    /// 调整网格尺寸，保留重叠部分的瓦片
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let mut tiles = vec![None; (width * height) as usize];
+        for y in 0..height.min(self.height) {
+            for x in 0..width.min(self.width) {
+                tiles[(y * width + x) as usize] = self.tile(x, y);
+            }
+        }
+        self.tiles = tiles;
+        self.width = width;
+        self.height = height;
+        self.revision += 1;
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// 非空瓦片数量
+    pub fn tile_count(&self) -> usize {
+        self.tiles.iter().filter(|tile| tile.is_some()).count()
+    }
+
+    /// 瓦片索引在图集中的UV矩形(未翻转)，索引超出图集时返回None
+    pub fn atlas_uv_rect(&self, index: u32) -> Option<(Vec2, Vec2)> {
+        if index >= self.tileset_columns * self.tileset_rows {
+            return None;
+        }
+        let cell = Vec2::new(1.0 / self.tileset_columns as f32, 1.0 / self.tileset_rows as f32);
+        let min = Vec2::new((index % self.tileset_columns) as f32, (index / self.tileset_columns) as f32) * cell;
+        Some((min, min + cell))
+    }
+
+    /// 应用翻转后的瓦片UV矩形
+    pub fn tile_uv_rect(&self, tile: Tile) -> Option<(Vec2, Vec2)> {
+        let (mut min, mut max) = self.atlas_uv_rect(tile.index)?;
+        if tile.flip_x {
+            std::mem::swap(&mut min.x, &mut max.x);
+        }
+        if tile.flip_y {
+            std::mem::swap(&mut min.y, &mut max.y);
+        }
+        Some((min, max))
+    }
+}
+
 /// 相机组件
 #[derive(Component, Debug, Clone)]
 #[storage(VecStorage)]
 pub struct Camera {
     pub camera: RenderCamera,
     pub render_target: Option<String>,
+    /// 背景天空盒(在不透明几何体之前绘制)
+    pub skybox: Skybox,
+    /// 距离雾
+    pub fog: Fog,
 }
 
 impl Default for Camera {
@@ -159,10 +645,26 @@ impl Default for Camera {
         Self {
             camera: RenderCamera::default(),
             render_target: None,
+            skybox: Skybox::default(),
+            fog: Fog::default(),
         }
     }
 }
 
+impl Camera {
+    /// 设置天空盒
+    pub fn with_skybox(mut self, skybox: Skybox) -> Self {
+        self.skybox = skybox;
+        self
+    }
+
+    /// 设置距离雾
+    pub fn with_fog(mut self, fog: Fog) -> Self {
+        self.fog = fog;
+        self
+    }
+}
+
 /// 光源类型
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum LightType {
@@ -179,7 +681,10 @@ pub struct Light {
     pub color: Vec3,
     pub intensity: f32,
     pub range: f32, // 点光源和聚光灯的范围
-    pub spot_angle: f32, // 聚光灯的角度
+    pub spot_angle: f32, // 聚光灯的角度(外锥半角)
+    /// 聚光灯内锥半角，内锥以内强度不衰减，内外锥之间平滑过渡到0
+    #[serde(default = "Light::default_spot_inner_angle")]
+    pub spot_inner_angle: f32,
     pub cast_shadows: bool,
 }
 
@@ -191,11 +696,41 @@ impl Default for Light {
             intensity: 1.0,
             range: 10.0,
             spot_angle: 45.0_f32.to_radians(),
+            spot_inner_angle: Self::default_spot_inner_angle(),
             cast_shadows: false,
         }
     }
 }
 
+impl Light {
+    fn default_spot_inner_angle() -> f32 {
+        36.0_f32.to_radians()
+    }
+
+    /// 设置聚光灯内外锥半角(弧度)
+    pub fn with_spot_angles(mut self, inner: f32, outer: f32) -> Self {
+        self.spot_angle = outer.max(0.0);
+        self.spot_inner_angle = inner.clamp(0.0, self.spot_angle);
+        self
+    }
+
+    /// 按半影比例设置内锥：0为硬边，1为从中心开始衰减
+    pub fn with_spot_penumbra(mut self, penumbra: f32) -> Self {
+        self.spot_inner_angle = self.spot_angle * (1.0 - penumbra.clamp(0.0, 1.0));
+        self
+    }
+
+    /// 聚光灯锥体衰减，`cos_angle` 为光轴与指向着色点方向夹角的余弦，与着色器中的计算一致
+    pub fn spot_cone_falloff(&self, cos_angle: f32) -> f32 {
+        if self.light_type != LightType::Spot {
+            return 1.0;
+        }
+        let cos_outer = self.spot_angle.cos();
+        let cos_inner = self.spot_inner_angle.min(self.spot_angle).cos().max(cos_outer + 1e-4);
+        smoothstep(cos_outer, cos_inner, cos_angle)
+    }
+}
+
 /// 刚体组件(简化版物理)
 #[derive(Component, Debug, Clone, Serialize, Deserialize)]
 #[storage(VecStorage)]
@@ -274,3 +809,436 @@ impl Default for Tag {
         Self::new()
     }
 }
+
+/// 行为脚本组件 - 挂载若干 `Behavior`，由 `BehaviorSystem` 按添加顺序逐个驱动
+#[derive(Component, Default)]
+#[storage(DenseVecStorage)]
+pub struct ScriptComponent {
+    behaviors: Vec<ScriptSlot>,
+}
+
+struct ScriptSlot {
+    behavior: Box<dyn Behavior>,
+    started: bool,
+}
+
+impl ScriptComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_behavior(mut self, behavior: impl Behavior + 'static) -> Self {
+        self.add_behavior(behavior);
+        self
+    }
+
+    /// 添加行为，下一次系统运行时先调用其 `on_start`
+    pub fn add_behavior(&mut self, behavior: impl Behavior + 'static) {
+        self.behaviors.push(ScriptSlot {
+            behavior: Box::new(behavior),
+            started: false,
+        });
+    }
+
+    /// 按名称移除行为
+    pub fn remove_behavior(&mut self, name: &str) -> bool {
+        let count = self.behaviors.len();
+        self.behaviors.retain(|slot| slot.behavior.name() != name);
+        self.behaviors.len() != count
+    }
+
+    pub fn behavior_names(&self) -> Vec<&str> {
+        self.behaviors.iter().map(|slot| slot.behavior.name()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.behaviors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.behaviors.is_empty()
+    }
+
+    /// 运行一帧：未启动的行为先启动，再投递事件，最后更新
+    pub(crate) fn run(&mut self, ctx: &mut BehaviorContext, events: &[BehaviorEvent]) {
+        let delta_time = ctx.delta_time;
+        for slot in &mut self.behaviors {
+            if !slot.started {
+                slot.behavior.on_start(ctx);
+                slot.started = true;
+            }
+            for event in events {
+                slot.behavior.on_event(ctx, event);
+            }
+            slot.behavior.on_update(ctx, delta_time);
+        }
+    }
+}
+
+impl std::fmt::Debug for ScriptComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptComponent")
+            .field("behaviors", &self.behavior_names())
+            .finish()
+    }
+}
+
+/// 池化标记组件：实体已归还到 `EntityPool`，系统应跳过它
+#[derive(Component, Debug, Clone, Copy, Default)]
+#[storage(NullStorage)]
+pub struct Pooled;
+
+/// 路径结束后的行为
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PathLoopMode {
+    /// 到达终点后停止
+    #[default]
+    Once,
+    /// 回到起点重新开始
+    Loop,
+    /// 在两端之间往返
+    PingPong,
+}
+
+/// 路径跟随组件 - 沿样条按弧长匀速移动(镜头轨道、移动平台)
+#[derive(Component, Debug, Clone)]
+#[storage(DenseVecStorage)]
+pub struct PathFollower {
+    pub spline: Arc<Spline>,
+    /// 移动速度(单位/秒)
+    pub speed: f32,
+    pub mode: PathLoopMode,
+    /// 是否朝向切线方向
+    pub orient_to_path: bool,
+    /// 距离起点的弧长
+    pub distance: f32,
+    /// 往返模式下的当前方向(1或-1)
+    pub direction: f32,
+    /// Once模式下是否已到达终点
+    pub finished: bool,
+}
+
+impl PathFollower {
+    pub fn new(spline: Arc<Spline>, speed: f32) -> Self {
+        Self {
+            spline,
+            speed,
+            mode: PathLoopMode::Once,
+            orient_to_path: true,
+            distance: 0.0,
+            direction: 1.0,
+            finished: false,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: PathLoopMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_orientation(mut self, orient_to_path: bool) -> Self {
+        self.orient_to_path = orient_to_path;
+        self
+    }
+
+    /// 前进delta_time秒
+    pub fn advance(&mut self, delta_time: f32) {
+        let length = self.spline.length();
+        if self.finished || length <= 0.0 {
+            return;
+        }
+
+        self.distance += self.speed * self.direction * delta_time;
+        match self.mode {
+            PathLoopMode::Once => {
+                if self.distance >= length || self.distance <= 0.0 {
+                    self.distance = self.distance.clamp(0.0, length);
+                    self.finished = true;
+                }
+            }
+            PathLoopMode::Loop => {
+                self.distance = self.distance.rem_euclid(length);
+            }
+            PathLoopMode::PingPong => {
+                // 越过端点的部分反向折返
+                while self.distance > length || self.distance < 0.0 {
+                    if self.distance > length {
+                        self.distance = 2.0 * length - self.distance;
+                    } else {
+                        self.distance = -self.distance;
+                    }
+                    self.direction = -self.direction;
+                }
+            }
+        }
+    }
+
+    /// 当前位置
+    pub fn position(&self) -> Vec3 {
+        self.spline.point_at_distance(self.distance)
+    }
+
+    /// 当前移动方向(单位向量)
+    pub fn heading(&self) -> Vec3 {
+        self.spline.tangent_at_distance(self.distance) * self.direction.signum()
+    }
+
+    /// 回到起点
+    pub fn restart(&mut self) {
+        self.distance = 0.0;
+        self.direction = 1.0;
+        self.finished = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn look_at_points_forward_at_target() {
+        let mut transform = Transform::new();
+        transform.set_position(Vec3::new(1.0, 2.0, 3.0));
+        transform.look_at(Vec3::new(1.0, 2.0, -7.0) + Vec3::X * 10.0, Vec3::Y);
+
+        let expected = (Vec3::new(11.0, 2.0, -7.0) - transform.position).normalize();
+        assert!(transform.forward().abs_diff_eq(expected, 1e-5));
+        assert!(transform.right().dot(Vec3::Y).abs() < 1e-5);
+        assert!(transform.is_dirty());
+    }
+
+    #[test]
+    fn look_at_own_position_keeps_rotation() {
+        let mut transform = Transform::new();
+        transform.set_euler_degrees(Vec3::new(0.0, 30.0, 0.0));
+        let rotation = transform.rotation;
+        transform.look_at(transform.position, Vec3::Y);
+        assert_eq!(transform.rotation, rotation);
+    }
+
+    #[test]
+    fn world_matrix_is_cached_until_modified() {
+        let mut transform = Transform::new();
+        transform.set_position(Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(transform.world_matrix().w_axis.truncate(), Vec3::new(1.0, 0.0, 0.0));
+        assert!(!transform.is_dirty());
+
+        transform.translate(Vec3::Y);
+        assert!(transform.is_dirty());
+        assert_eq!(transform.world_matrix().w_axis.truncate(), Vec3::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn writing_public_fields_invalidates_cache() {
+        let mut transform = Transform::new().with_position(Vec3::X);
+        transform.update_matrices();
+        assert!(!transform.is_dirty());
+
+        transform.position.y = 2.0;
+        assert!(transform.is_dirty());
+        assert_eq!(transform.current_world_matrix().w_axis.truncate(), Vec3::new(1.0, 2.0, 0.0));
+        assert_eq!(transform.world_matrix().w_axis.truncate(), Vec3::new(1.0, 2.0, 0.0));
+
+        // 写回相同的值不会使缓存失效
+        transform.scale = Vec3::ONE;
+        assert!(!transform.is_dirty());
+        transform.mark_dirty();
+        assert!(transform.is_dirty());
+    }
+
+    #[test]
+    fn deserialized_transform_recomputes_matrix() {
+        let mut transform = Transform::new();
+        transform.set_position(Vec3::new(4.0, 5.0, 6.0));
+        transform.update_matrices();
+        let json = serde_json::to_string(&transform).unwrap();
+
+        let mut loaded: Transform = serde_json::from_str(&json).unwrap();
+        assert!(loaded.is_dirty());
+        assert_eq!(loaded.world_matrix().w_axis.truncate(), Vec3::new(4.0, 5.0, 6.0));
+    }
+
+    fn straight_path(length: f32) -> Arc<Spline> {
+        Arc::new(Spline::new([Vec3::ZERO, Vec3::new(length, 0.0, 0.0)]))
+    }
+
+    #[test]
+    fn path_follower_once_stops_at_end() {
+        let mut follower = PathFollower::new(straight_path(4.0), 2.0);
+        follower.advance(1.0);
+        assert!(!follower.finished);
+        follower.advance(1.5);
+        assert!(follower.finished);
+        assert_eq!(follower.distance, follower.spline.length());
+
+        follower.restart();
+        assert_eq!(follower.distance, 0.0);
+        assert!(!follower.finished);
+    }
+
+    #[test]
+    fn path_follower_loop_and_ping_pong_wrap() {
+        let path = straight_path(4.0);
+        let length = path.length();
+
+        let mut looping = PathFollower::new(path.clone(), length).with_mode(PathLoopMode::Loop);
+        looping.advance(1.25);
+        assert!((looping.distance - length * 0.25).abs() < 1e-4);
+
+        let mut ping_pong = PathFollower::new(path, length).with_mode(PathLoopMode::PingPong);
+        ping_pong.advance(1.25);
+        assert!((ping_pong.distance - length * 0.75).abs() < 1e-4);
+        assert_eq!(ping_pong.direction, -1.0);
+        assert!(ping_pong.heading().abs_diff_eq(Vec3::NEG_X, 1e-4));
+    }
+
+    #[test]
+    fn sprite_flip_swaps_uv_rect() {
+        let sprite = SpriteRenderer::new("atlas").with_uv_rect(Vec2::new(0.25, 0.5), Vec2::new(0.5, 0.75));
+        assert_eq!(sprite.flipped_uv_rect(), (Vec2::new(0.25, 0.5), Vec2::new(0.5, 0.75)));
+
+        let flip_x = sprite.clone().with_flip(true, false);
+        assert_eq!(flip_x.flipped_uv_rect(), (Vec2::new(0.5, 0.5), Vec2::new(0.25, 0.75)));
+
+        let flip_both = sprite.with_flip(true, true);
+        assert_eq!(flip_both.flipped_uv_rect(), (Vec2::new(0.5, 0.75), Vec2::new(0.25, 0.5)));
+    }
+
+    #[test]
+    fn tilemap_edits_bump_revision_and_resize_keeps_overlap() {
+        let mut tilemap = Tilemap::new("tiles", 4, 2, 3, 2).with_indices(&[0, 1, 2, 3, 4, 5]);
+        let revision = tilemap.revision();
+        tilemap.set_tile(1, 1, Some(Tile::new(4)));
+        assert_eq!(tilemap.revision(), revision);
+        tilemap.set_tile(9, 9, None);
+        assert_eq!(tilemap.revision(), revision);
+        tilemap.set_tile(1, 1, None);
+        assert_eq!(tilemap.revision(), revision + 1);
+
+        tilemap.resize(2, 3);
+        assert_eq!((tilemap.width(), tilemap.height()), (2, 3));
+        assert_eq!(tilemap.tile(1, 0), Some(Tile::new(1)));
+        assert_eq!(tilemap.tile(0, 1), Some(Tile::new(3)));
+        assert_eq!(tilemap.tile(0, 2), None);
+        assert_eq!(tilemap.tile_count(), 3);
+
+        assert_eq!(tilemap.atlas_uv_rect(5), Some((Vec2::new(0.25, 0.5), Vec2::new(0.5, 1.0))));
+        assert_eq!(tilemap.atlas_uv_rect(8), None);
+    }
+
+    fn spot(inner_degrees: f32, outer_degrees: f32) -> Light {
+        Light {
+            light_type: LightType::Spot,
+            ..Default::default()
+        }
+        .with_spot_angles(inner_degrees.to_radians(), outer_degrees.to_radians())
+    }
+
+    #[test]
+    fn spot_falloff_is_full_inside_inner_and_zero_outside_outer() {
+        let light = spot(20.0, 40.0);
+        let falloff = |degrees: f32| light.spot_cone_falloff(degrees.to_radians().cos());
+
+        assert_eq!(falloff(0.0), 1.0);
+        assert_eq!(falloff(19.0), 1.0);
+        assert_eq!(falloff(41.0), 0.0);
+        assert_eq!(falloff(90.0), 0.0);
+
+        // 内外锥之间单调平滑过渡
+        let samples: Vec<f32> = (20..=40).map(|degrees| falloff(degrees as f32)).collect();
+        assert!(samples.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert!(samples.windows(2).all(|pair| pair[0] - pair[1] < 0.15));
+        assert!(falloff(30.0) > 0.2 && falloff(30.0) < 0.8);
+    }
+
+    #[test]
+    fn spot_angles_are_clamped_and_penumbra_sets_inner() {
+        let light = spot(60.0, 30.0);
+        assert_eq!(light.spot_inner_angle, light.spot_angle);
+        // 内外锥相同时仍为硬边，不产生NaN
+        assert_eq!(light.spot_cone_falloff(1.0), 1.0);
+        assert_eq!(light.spot_cone_falloff(31.0_f32.to_radians().cos()), 0.0);
+
+        let soft = spot(0.0, 40.0).with_spot_penumbra(0.25);
+        assert!((soft.spot_inner_angle - 30.0_f32.to_radians()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn non_spot_lights_have_no_cone_falloff() {
+        let light = Light {
+            light_type: LightType::Point,
+            ..Default::default()
+        };
+        assert_eq!(light.spot_cone_falloff(-1.0), 1.0);
+    }
+
+    #[test]
+    fn old_light_data_gets_default_inner_angle() {
+        let mut value = serde_json::to_value(Light::default()).unwrap();
+        value.as_object_mut().unwrap().remove("spot_inner_angle");
+        let light: Light = serde_json::from_value(value).unwrap();
+        assert!((light.spot_inner_angle - 36.0_f32.to_radians()).abs() < 1e-6);
+    }
+
+    fn four_frame_animation(mode: PathLoopMode) -> SpriteAnimation {
+        SpriteAnimation::new(Arc::new(SpriteSheet::from_grid("hero", 2, 2, 0)), 10.0).with_mode(mode)
+    }
+
+    /// 以固定步长推进，记录每步之后的帧索引
+    fn frames(animation: &mut SpriteAnimation, steps: usize, delta_time: f32) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                animation.advance(delta_time);
+                animation.current_frame
+            })
+            .collect()
+    }
+
+    #[test]
+    fn looping_animation_shows_expected_frames_over_time() {
+        let mut animation = four_frame_animation(PathLoopMode::Loop);
+        // 10fps，每0.05秒采样一次
+        assert_eq!(frames(&mut animation, 10, 0.05), [0, 1, 1, 2, 2, 3, 3, 0, 0, 1]);
+
+        // 一次跨过多帧，期间经过最后一帧一次
+        let mut animation = four_frame_animation(PathLoopMode::Loop);
+        assert_eq!(animation.advance(0.55), 1);
+        assert_eq!(animation.current_frame, 1);
+    }
+
+    #[test]
+    fn once_stops_on_last_frame_and_ping_pong_reverses() {
+        let mut once = four_frame_animation(PathLoopMode::Once);
+        assert_eq!(frames(&mut once, 6, 0.1), [1, 2, 3, 3, 3, 3]);
+        assert!(once.finished);
+        once.restart();
+        assert_eq!((once.current_frame, once.finished), (0, false));
+
+        let mut ping_pong = four_frame_animation(PathLoopMode::PingPong);
+        assert_eq!(frames(&mut ping_pong, 8, 0.1), [1, 2, 3, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn paused_animation_holds_frame_and_apply_sets_uvs() {
+        let mut animation = four_frame_animation(PathLoopMode::Loop);
+        frames(&mut animation, 3, 0.1);
+        animation.pause();
+        assert_eq!(animation.advance(1.0), 0);
+        assert_eq!(animation.current_frame, 3);
+
+        let mut sprite = SpriteRenderer::new("placeholder");
+        animation.apply(&mut sprite);
+        assert_eq!(sprite.texture_name, "hero");
+        assert_eq!((sprite.uv_min, sprite.uv_max), (Vec2::new(0.5, 0.5), Vec2::ONE));
+    }
+
+    #[test]
+    fn grid_sheet_limits_frame_count() {
+        let sheet = SpriteSheet::from_grid("explosion", 4, 2, 6);
+        assert_eq!(sheet.frame_count(), 6);
+        assert_eq!(sheet.frame(5).unwrap().uv_min, Vec2::new(0.25, 0.5));
+        assert_eq!(sheet.frame(5).unwrap().uv_max, Vec2::new(0.5, 1.0));
+        assert!(sheet.frame(6).is_none());
+        assert_eq!(SpriteSheet::from_grid("all", 3, 3, 100).frame_count(), 9);
+    }
+}
@@ -245,3 +245,106 @@ impl EntityQuery {
         None
     }
 }
+
+/// 可复用实体池
+///
+/// 归还的实体保留组件存储，只打上 `Pooled` 标记，系统会跳过它们；
+/// 再次取出时移除标记，由调用者重新设置组件数据。
+#[derive(Debug, Default)]
+pub struct EntityPool {
+    free: Vec<Entity>,
+    stats: crate::core::PoolStats,
+}
+
+impl EntityPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取出一个实体，没有可复用的实体时新建
+    pub fn acquire(&mut self, world: &mut World) -> Entity {
+        while let Some(entity) = self.free.pop() {
+            if world.is_alive(entity) {
+                world.write_storage::<Pooled>().remove(entity);
+                self.stats.reused += 1;
+                return entity;
+            }
+        }
+        self.stats.created += 1;
+        world.create_entity().build()
+    }
+
+    /// 归还实体
+    pub fn release(&mut self, world: &mut World, entity: Entity) {
+        if !world.is_alive(entity) {
+            return;
+        }
+        self.stats.released += 1;
+        if world.write_storage::<Pooled>().insert(entity, Pooled).is_ok() {
+            self.free.push(entity);
+        }
+    }
+
+    /// 空闲实体数
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// 统计信息
+    pub fn stats(&self) -> crate::core::PoolStats {
+        self.stats
+    }
+
+    /// 删除池中所有空闲实体
+    pub fn clear(&mut self, world: &mut World) {
+        for entity in self.free.drain(..) {
+            let _ = world.delete_entity(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::ECSWorld;
+
+    #[test]
+    fn entity_pool_reuses_released_entities() {
+        let mut ecs = ECSWorld::new().unwrap();
+        let world = ecs.world_mut();
+        let mut pool = EntityPool::new();
+
+        let first = pool.acquire(world);
+        pool.release(world, first);
+        assert_eq!(pool.free_count(), 1);
+        assert!(world.read_storage::<Pooled>().contains(first));
+
+        let second = pool.acquire(world);
+        assert_eq!(second, first);
+        assert!(!world.read_storage::<Pooled>().contains(second));
+        assert_eq!(pool.stats().created, 1);
+        assert_eq!(pool.stats().reused, 1);
+    }
+
+    #[test]
+    fn entity_pool_skips_deleted_entities() {
+        let mut ecs = ECSWorld::new().unwrap();
+        let world = ecs.world_mut();
+        let mut pool = EntityPool::new();
+
+        let entity = pool.acquire(world);
+        pool.release(world, entity);
+        world.delete_entity(entity).unwrap();
+        world.maintain();
+
+        let fresh = pool.acquire(world);
+        assert_ne!(fresh, entity);
+        assert_eq!(pool.stats().created, 2);
+
+        pool.release(world, fresh);
+        pool.clear(world);
+        world.maintain();
+        assert!(!world.is_alive(fresh));
+        assert_eq!(pool.free_count(), 0);
+    }
+}
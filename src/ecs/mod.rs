@@ -5,12 +5,20 @@ pub mod entity;
 pub mod component;
 pub mod system;
 pub mod query;
+pub mod snapshot;
+pub mod storage;
+pub mod schedule;
+pub mod behavior;
 
 pub use world::*;
 pub use entity::*;
 pub use component::*;
 pub use system::*;
 pub use query::*;
+pub use snapshot::*;
+pub use storage::*;
+pub use schedule::*;
+pub use behavior::*;
 
 // 重新导出specs的常用类型
 pub use specs::{
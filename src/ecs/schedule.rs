@@ -0,0 +1,257 @@
+//! 系统调度
+//!
+//! `SystemSchedule` 包装specs的调度器：并行模式下由rayon线程池同时运行组件访问不冲突的系统，
+//! 声明的依赖(`with_system` 的deps)始终先于依赖方执行；单线程模式按添加顺序依次运行。
+//! 两种模式结果相同，只有资源读写冲突的系统会被串行化。
+
+use specs::{Dispatcher, DispatcherBuilder, System, World};
+
+/// 调度方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DispatchMode {
+    /// 无冲突的系统在线程池中并行运行
+    #[default]
+    Parallel,
+    /// 在当前线程按顺序运行(调试、单核平台)
+    Sequential,
+}
+
+impl DispatchMode {
+    /// 只有一个可用核心时退回单线程
+    pub fn detect() -> Self {
+        match std::thread::available_parallelism() {
+            Ok(threads) if threads.get() > 1 => DispatchMode::Parallel,
+            _ => DispatchMode::Sequential,
+        }
+    }
+}
+
+/// 系统调度构建器
+pub struct SystemScheduleBuilder {
+    builder: DispatcherBuilder<'static, 'static>,
+    mode: DispatchMode,
+    system_names: Vec<String>,
+}
+
+impl SystemScheduleBuilder {
+    pub fn new() -> Self {
+        Self {
+            builder: DispatcherBuilder::new(),
+            mode: DispatchMode::detect(),
+            system_names: Vec::new(),
+        }
+    }
+
+    /// 添加系统，deps中的系统先于它运行(必须已添加)
+    pub fn with_system<S>(mut self, system: S, name: &str, deps: &[&str]) -> Self
+    where
+        S: for<'a> System<'a> + Send + 'static,
+    {
+        self.builder.add(system, name, deps);
+        self.system_names.push(name.to_string());
+        self
+    }
+
+    /// 添加屏障：之后添加的系统在之前的全部系统完成后才运行
+    pub fn with_barrier(mut self) -> Self {
+        self.builder.add_barrier();
+        self
+    }
+
+    pub fn with_mode(mut self, mode: DispatchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn build(self) -> SystemSchedule {
+        SystemSchedule {
+            dispatcher: self.builder.build(),
+            mode: self.mode,
+            system_names: self.system_names,
+        }
+    }
+}
+
+impl Default for SystemScheduleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 构建好的系统调度
+pub struct SystemSchedule {
+    dispatcher: Dispatcher<'static, 'static>,
+    mode: DispatchMode,
+    system_names: Vec<String>,
+}
+
+impl SystemSchedule {
+    pub fn builder() -> SystemScheduleBuilder {
+        SystemScheduleBuilder::new()
+    }
+
+    /// 注册系统用到的组件与资源(按Default创建缺失的资源)
+    pub fn setup(&mut self, world: &mut World) {
+        self.dispatcher.setup(world);
+    }
+
+    /// 按当前模式运行一次所有系统
+    pub fn dispatch(&mut self, world: &World) {
+        match self.mode {
+            DispatchMode::Parallel => self.dispatch_parallel(world),
+            DispatchMode::Sequential => self.dispatch_sequential(world),
+        }
+    }
+
+    /// 在线程池中并行运行组件访问不冲突的系统
+    pub fn dispatch_parallel(&mut self, world: &World) {
+        self.dispatcher.dispatch_par(world);
+    }
+
+    /// 在当前线程依次运行所有系统
+    pub fn dispatch_sequential(&mut self, world: &World) {
+        self.dispatcher.dispatch_seq(world);
+    }
+
+    pub fn mode(&self) -> DispatchMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: DispatchMode) {
+        self.mode = mode;
+    }
+
+    /// 按添加顺序的系统名
+    pub fn system_names(&self) -> &[String] {
+        &self.system_names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::{Builder, Component, Join, ReadStorage, VecStorage, Write, WorldExt, WriteStorage};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Health(i32);
+
+    impl Component for Health {
+        type Storage = VecStorage<Self>;
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Speed(f32);
+
+    impl Component for Speed {
+        type Storage = VecStorage<Self>;
+    }
+
+    /// 记录系统运行顺序
+    #[derive(Default)]
+    struct RunLog(Vec<&'static str>);
+
+    /// 只写Health，模拟耗时的系统
+    struct RegenSystem;
+
+    impl<'a> System<'a> for RegenSystem {
+        type SystemData = WriteStorage<'a, Health>;
+
+        fn run(&mut self, mut health: Self::SystemData) {
+            std::thread::sleep(Duration::from_millis(5));
+            for health in (&mut health).join() {
+                health.0 += 3;
+            }
+        }
+    }
+
+    /// 只写Speed，与RegenSystem互不冲突
+    struct DragSystem;
+
+    impl<'a> System<'a> for DragSystem {
+        type SystemData = WriteStorage<'a, Speed>;
+
+        fn run(&mut self, mut speed: Self::SystemData) {
+            std::thread::sleep(Duration::from_millis(5));
+            for speed in (&mut speed).join() {
+                speed.0 *= 0.5;
+            }
+        }
+    }
+
+    /// 依赖前两个系统，检查它们本帧都已运行
+    #[derive(Default)]
+    struct ReportSystem {
+        runs: i32,
+    }
+
+    impl<'a> System<'a> for ReportSystem {
+        type SystemData = (ReadStorage<'a, Health>, ReadStorage<'a, Speed>, Write<'a, RunLog>);
+
+        fn run(&mut self, (health, speed, mut log): Self::SystemData) {
+            self.runs += 1;
+            let expected_speed = 8.0 / 2f32.powi(self.runs);
+            let ready = (&health, &speed)
+                .join()
+                .all(|(health, speed)| health.0 == 100 + 3 * self.runs && speed.0 == expected_speed);
+            log.0.push(if ready { "report" } else { "report_too_early" });
+        }
+    }
+
+    fn run_with(mode: DispatchMode) -> (Vec<(i32, f32)>, Vec<&'static str>) {
+        let mut world = World::new();
+        let mut schedule = SystemSchedule::builder()
+            .with_system(RegenSystem, "regen", &[])
+            .with_system(DragSystem, "drag", &[])
+            .with_system(ReportSystem::default(), "report", &["regen", "drag"])
+            .with_mode(mode)
+            .build();
+        schedule.setup(&mut world);
+        for _ in 0..10 {
+            world.create_entity().with(Health(100)).with(Speed(8.0)).build();
+        }
+
+        for _ in 0..2 {
+            schedule.dispatch(&world);
+            world.maintain();
+        }
+
+        let values = (&world.read_storage::<Health>(), &world.read_storage::<Speed>())
+            .join()
+            .map(|(health, speed)| (health.0, speed.0))
+            .collect();
+        let log = std::mem::take(&mut world.write_resource::<RunLog>().0);
+        (values, log)
+    }
+
+    #[test]
+    fn parallel_dispatch_matches_sequential() {
+        let start = std::time::Instant::now();
+        let (parallel, parallel_log) = run_with(DispatchMode::Parallel);
+        let (sequential, sequential_log) = run_with(DispatchMode::Sequential);
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.len(), 10);
+        assert_eq!(parallel[0], (106, 2.0));
+        // 依赖声明在并行模式下同样生效
+        assert_eq!(parallel_log, vec!["report", "report"]);
+        assert_eq!(parallel_log, sequential_log);
+        // 两种模式下两个耗时系统每帧都运行了
+        assert!(start.elapsed() >= Duration::from_millis(2 * 2 * 5));
+    }
+
+    #[test]
+    fn builder_records_systems_and_mode() {
+        let mut schedule = SystemSchedule::builder()
+            .with_system(RegenSystem, "regen", &[])
+            .with_barrier()
+            .with_system(DragSystem, "drag", &[])
+            .with_mode(DispatchMode::Sequential)
+            .build();
+        assert_eq!(schedule.system_names(), ["regen".to_string(), "drag".to_string()]);
+        assert_eq!(schedule.mode(), DispatchMode::Sequential);
+
+        schedule.set_mode(DispatchMode::Parallel);
+        assert_eq!(schedule.mode(), DispatchMode::Parallel);
+    }
+}
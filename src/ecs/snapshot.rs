@@ -0,0 +1,199 @@
+//! 世界快照
+//!
+//! 快照克隆所有已登记组件类型的数据，用于编辑器停止运行时回到运行前的状态、回放和网络回滚。
+//! 核心组件在 `ECSWorld::new` 中自动登记，其他组件(如物理组件)需调用
+//! `ECSWorld::register_snapshot_component` 后才会被捕获。
+
+use crate::ecs::world::sort_entities;
+use specs::{Builder, Component, Entity, Join, World, WorldExt};
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// 某个组件类型的全部数据
+type ComponentData = Box<dyn Any + Send + Sync>;
+
+/// 捕获与还原单个组件类型的存储
+pub(crate) trait ComponentSnapshotter: Send + Sync {
+    fn type_name(&self) -> &'static str;
+
+    /// 克隆所有(实体, 组件)
+    fn capture(&self, world: &World) -> ComponentData;
+
+    /// 清空存储并写回快照数据，entity_map给出快照实体到当前实体的映射
+    fn restore(&self, world: &World, data: &ComponentData, entity_map: &HashMap<Entity, Entity>);
+}
+
+pub(crate) struct TypedSnapshotter<T>(PhantomData<fn() -> T>);
+
+impl<T> TypedSnapshotter<T> {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> ComponentSnapshotter for TypedSnapshotter<T>
+where
+    T: Component + Clone + Send + Sync,
+{
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn capture(&self, world: &World) -> ComponentData {
+        let entities = world.entities();
+        let storage = world.read_storage::<T>();
+        let components: Vec<(Entity, T)> = (&entities, &storage)
+            .join()
+            .map(|(entity, component)| (entity, component.clone()))
+            .collect();
+        Box::new(components)
+    }
+
+    fn restore(&self, world: &World, data: &ComponentData, entity_map: &HashMap<Entity, Entity>) {
+        let Some(components) = data.downcast_ref::<Vec<(Entity, T)>>() else {
+            return;
+        };
+
+        let mut storage = world.write_storage::<T>();
+        storage.clear();
+        for (entity, component) in components {
+            let target = entity_map.get(entity).copied().unwrap_or(*entity);
+            if let Err(e) = storage.insert(target, component.clone()) {
+                log::warn!("还原组件 {} 失败: {:?}", self.type_name(), e);
+            }
+        }
+    }
+}
+
+/// 世界状态快照
+pub struct WorldSnapshot {
+    /// 快照时的存活实体，按(id, generation)排序
+    pub(crate) entities: Vec<Entity>,
+    /// 组件类型名 -> 组件数据
+    pub(crate) components: HashMap<&'static str, ComponentData>,
+}
+
+impl WorldSnapshot {
+    pub(crate) fn capture(world: &World, snapshotters: &[Box<dyn ComponentSnapshotter>]) -> Self {
+        let mut entities: Vec<Entity> = world.entities().join().collect();
+        sort_entities(&mut entities);
+
+        let components = snapshotters
+            .iter()
+            .map(|snapshotter| (snapshotter.type_name(), snapshotter.capture(world)))
+            .collect();
+
+        Self { entities, components }
+    }
+
+    /// 把世界还原到快照状态，返回因已被删除而重新创建的实体(快照实体 -> 新实体)
+    ///
+    /// 快照之后创建的实体会被删除；快照中存在但之后被删除的实体会以新的ID重建。
+    /// 快照之后才登记的组件类型不受影响。
+    pub(crate) fn restore(
+        &self,
+        world: &mut World,
+        snapshotters: &[Box<dyn ComponentSnapshotter>],
+    ) -> HashMap<Entity, Entity> {
+        let snapshot_entities: HashSet<Entity> = self.entities.iter().copied().collect();
+
+        // 删除快照之后创建的实体
+        let mut created: Vec<Entity> = world
+            .entities()
+            .join()
+            .filter(|entity| !snapshot_entities.contains(entity))
+            .collect();
+        sort_entities(&mut created);
+        if let Err((e, index)) = world.delete_entities(&created) {
+            log::warn!("还原快照时删除实体失败(第{}个): {:?}", index, e);
+        }
+        world.maintain();
+
+        // 重建已被删除的实体
+        let mut entity_map = HashMap::new();
+        for &entity in &self.entities {
+            if !world.entities().is_alive(entity) {
+                entity_map.insert(entity, world.create_entity().build());
+            }
+        }
+
+        for snapshotter in snapshotters {
+            if let Some(data) = self.components.get(snapshotter.type_name()) {
+                snapshotter.restore(world, data, &entity_map);
+            }
+        }
+        world.maintain();
+
+        entity_map
+    }
+
+    /// 快照时的存活实体
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// 捕获的组件类型名
+    pub fn component_types(&self) -> Vec<&'static str> {
+        let mut types: Vec<&'static str> = self.components.keys().copied().collect();
+        types.sort_unstable();
+        types
+    }
+}
+
+impl fmt::Debug for WorldSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorldSnapshot")
+            .field("entities", &self.entities.len())
+            .field("components", &self.component_types())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::component::{Name, Transform};
+    use specs::Builder;
+
+    fn snapshotters() -> Vec<Box<dyn ComponentSnapshotter>> {
+        vec![Box::new(TypedSnapshotter::<Transform>::new()), Box::new(TypedSnapshotter::<Name>::new())]
+    }
+
+    #[test]
+    fn capture_records_sorted_entities_and_types() {
+        let mut world = World::new();
+        world.register::<Transform>();
+        world.register::<Name>();
+        let entities: Vec<Entity> = (0..3).map(|_| world.create_entity().with(Name::new("a")).build()).collect();
+
+        let snapshot = WorldSnapshot::capture(&world, &snapshotters());
+        assert_eq!(snapshot.entities(), &entities[..]);
+        assert_eq!(snapshot.component_types(), vec![std::any::type_name::<Name>(), std::any::type_name::<Transform>()]);
+        assert!(format!("{:?}", snapshot).contains("entities: 3"));
+    }
+
+    #[test]
+    fn types_missing_from_snapshot_are_left_alone() {
+        let mut world = World::new();
+        world.register::<Transform>();
+        world.register::<Name>();
+        let entity = world.create_entity().with(Name::new("before")).build();
+
+        // 只捕获Transform
+        let transform_only: Vec<Box<dyn ComponentSnapshotter>> = vec![Box::new(TypedSnapshotter::<Transform>::new())];
+        let snapshot = WorldSnapshot::capture(&world, &transform_only);
+
+        world.write_storage::<Name>().get_mut(entity).unwrap().name = "after".to_string();
+        world.write_storage::<Transform>().insert(entity, Transform::default()).unwrap();
+        assert!(snapshot.restore(&mut world, &snapshotters()).is_empty());
+
+        assert_eq!(world.read_storage::<Name>().get(entity).unwrap().name, "after");
+        assert!(world.read_storage::<Transform>().get(entity).is_none());
+    }
+}
@@ -0,0 +1,107 @@
+//! 组件存储选择
+//!
+//! specs的存储类型是组件类型的一部分(`Component::Storage`)，定义组件时用 `#[storage(...)]` 选择：
+//!
+//! | 存储 | 内存 | 适用场景 |
+//! |------|------|----------|
+//! | `VecStorage` | 按最大实体ID分配，每个实体一个槽位 | 绝大多数实体都有的组件(Transform) |
+//! | `DenseVecStorage` | 紧凑数组加一层索引，只为拥有组件的实体分配 | 较常见但不是人人都有的组件 |
+//! | `HashMapStorage` | 哈希表，迭代较慢 | 很少实体拥有的组件(Camera、Light) |
+//! | `NullStorage` | 不占数据，只记录标记位 | 零大小的标记组件(Pooled) |
+//!
+//! 无法修改定义的组件(例如来自其他crate)可以用 `Packed`、`Dense`、`Sparse` 包装来改变存储方式，
+//! 再通过 `ECSWorld::register_component` 注册。specs不支持为存储预分配实体容量，
+//! `VecStorage` 会随最大实体ID增长，大量实体的稀疏组件应避免使用它。
+
+use serde::{Deserialize, Serialize};
+use specs::{Component, DenseVecStorage, HashMapStorage, VecStorage};
+use std::ops::{Deref, DerefMut};
+
+macro_rules! storage_wrapper {
+    ($(#[$meta:meta])* $name:ident, $storage:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name<T>(pub T);
+
+        impl<T: Send + Sync + 'static> Component for $name<T> {
+            type Storage = $storage<Self>;
+        }
+
+        impl<T> $name<T> {
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> From<T> for $name<T> {
+            fn from(value: T) -> Self {
+                Self(value)
+            }
+        }
+
+        impl<T> Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+    };
+}
+
+storage_wrapper!(
+    /// 以 `VecStorage` 存储的组件，适合几乎所有实体都拥有的数据
+    Packed,
+    VecStorage
+);
+
+storage_wrapper!(
+    /// 以 `DenseVecStorage` 存储的组件，迭代快且只为拥有组件的实体分配
+    Dense,
+    DenseVecStorage
+);
+
+storage_wrapper!(
+    /// 以 `HashMapStorage` 存储的组件，适合只有少数实体拥有的数据
+    Sparse,
+    HashMapStorage
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::TypeId;
+
+    fn storage_of<T: Component>() -> TypeId
+    where
+        T::Storage: 'static,
+    {
+        TypeId::of::<T::Storage>()
+    }
+
+    #[test]
+    fn wrappers_select_their_storage() {
+        assert_eq!(storage_of::<Packed<f32>>(), TypeId::of::<VecStorage<Packed<f32>>>());
+        assert_eq!(storage_of::<Dense<f32>>(), TypeId::of::<DenseVecStorage<Dense<f32>>>());
+        assert_eq!(storage_of::<Sparse<f32>>(), TypeId::of::<HashMapStorage<Sparse<f32>>>());
+    }
+
+    #[test]
+    fn wrappers_deref_to_inner_value() {
+        let mut health = Sparse::from(vec![1, 2]);
+        health.push(3);
+        assert_eq!(health.len(), 3);
+        assert_eq!(health.into_inner(), vec![1, 2, 3]);
+
+        // 序列化时与内部值相同
+        assert_eq!(serde_json::to_string(&Packed(5u32)).unwrap(), "5");
+        assert_eq!(serde_json::from_str::<Dense<String>>("\"boss\"").unwrap(), Dense("boss".to_string()));
+    }
+}
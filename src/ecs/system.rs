@@ -1,9 +1,10 @@
 //! ECS系统定义
 
 use crate::ecs::component::*;
-use crate::ecs::world::TimeResource;
+use crate::ecs::behavior::{BehaviorContext, BehaviorEvents};
+use crate::ecs::world::{SpriteAnimationEvent, SpriteAnimationEventKind, SpriteAnimationEvents, SubsystemErrors, TimeResource};
 
-use specs::{System, SystemData, ReadStorage, WriteStorage, Read, Join};
+use specs::{System, SystemData, ReadStorage, WriteStorage, Read, Write, Join};
 use glam::Vec3;
 
 /// 变换系统 - 更新变换矩阵
@@ -25,6 +26,107 @@ impl<'a> System<'a> for TransformSystem {
     }
 }
 
+/// 路径跟随系统 - 沿样条推进实体并更新变换
+pub struct PathFollowSystem;
+
+impl PathFollowSystem {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> System<'a> for PathFollowSystem {
+    type SystemData = (
+        WriteStorage<'a, PathFollower>,
+        WriteStorage<'a, Transform>,
+        Read<'a, TimeResource>,
+        ReadStorage<'a, Pooled>,
+    );
+
+    fn run(&mut self, (mut followers, mut transforms, time, pooled): Self::SystemData) {
+        for (follower, transform, _) in (&mut followers, &mut transforms, !&pooled).join() {
+            follower.advance(time.delta_time);
+            transform.set_position(follower.position());
+            if follower.orient_to_path {
+                transform.look_to(follower.heading(), Vec3::Y);
+            }
+        }
+    }
+}
+
+/// 精灵动画系统 - 推进帧动画并更新精灵的UV矩形，本帧的播放事件写入 `SpriteAnimationEvents`
+pub struct SpriteAnimationSystem;
+
+impl SpriteAnimationSystem {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> System<'a> for SpriteAnimationSystem {
+    type SystemData = (
+        specs::Entities<'a>,
+        WriteStorage<'a, SpriteAnimation>,
+        WriteStorage<'a, SpriteRenderer>,
+        Read<'a, TimeResource>,
+        Write<'a, SpriteAnimationEvents>,
+        ReadStorage<'a, Pooled>,
+    );
+
+    fn run(&mut self, (entities, mut animations, mut sprites, time, mut events, pooled): Self::SystemData) {
+        events.clear();
+        for (entity, animation, sprite, _) in (&entities, &mut animations, &mut sprites, !&pooled).join() {
+            let was_finished = animation.finished;
+            let last_frame_hits = animation.advance(time.delta(animation.use_unscaled_time));
+            animation.apply(sprite);
+
+            for _ in 0..last_frame_hits {
+                events.push(SpriteAnimationEvent { entity, kind: SpriteAnimationEventKind::LastFrame });
+            }
+            if animation.finished && !was_finished {
+                events.push(SpriteAnimationEvent { entity, kind: SpriteAnimationEventKind::Finished });
+            }
+        }
+    }
+}
+
+/// 行为系统 - 驱动实体上 `ScriptComponent` 挂载的行为
+pub struct BehaviorSystem;
+
+impl BehaviorSystem {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<'a> System<'a> for BehaviorSystem {
+    type SystemData = (
+        specs::Entities<'a>,
+        WriteStorage<'a, ScriptComponent>,
+        WriteStorage<'a, Transform>,
+        Read<'a, TimeResource>,
+        Write<'a, BehaviorEvents>,
+        ReadStorage<'a, Pooled>,
+        Write<'a, SubsystemErrors>,
+    );
+
+    fn run(&mut self, (entities, mut scripts, mut transforms, time, mut events, pooled, mut errors): Self::SystemData) {
+        // 上一帧发出的事件本帧送达，本帧发出的留到下一帧
+        let mut inbox = events.take_grouped();
+        let mut outbox = Vec::new();
+
+        for (entity, script, _) in (&entities, &mut scripts, !&pooled).join() {
+            let entity_events = inbox.remove(&entity).unwrap_or_default();
+            let mut ctx = BehaviorContext::new(entity, time.delta_time, time.total_time, &mut transforms, &mut outbox, &mut errors);
+            script.run(&mut ctx, &entity_events);
+        }
+
+        for (target, event) in outbox {
+            events.send(target, event);
+        }
+    }
+}
+
 /// 渲染系统 - 处理渲染相关逻辑
 pub struct RenderSystem;
 
@@ -39,13 +141,14 @@ impl<'a> System<'a> for RenderSystem {
         ReadStorage<'a, Transform>,
         ReadStorage<'a, MeshRenderer>,
         ReadStorage<'a, Camera>,
+        ReadStorage<'a, Pooled>,
     );
 
-    fn run(&mut self, (transforms, renderers, cameras): Self::SystemData) {
+    fn run(&mut self, (transforms, renderers, cameras, pooled): Self::SystemData) {
         // 收集所有需要渲染的对象
         let mut render_items = Vec::new();
         
-        for (transform, renderer) in (&transforms, &renderers).join() {
+        for (transform, renderer, _) in (&transforms, &renderers, !&pooled).join() {
             if renderer.visible {
                 render_items.push((transform, renderer));
             }
@@ -86,12 +189,13 @@ impl<'a> System<'a> for PhysicsSystem {
         WriteStorage<'a, Transform>,
         WriteStorage<'a, RigidBody>,
         Read<'a, TimeResource>,
+        ReadStorage<'a, Pooled>,
     );
 
-    fn run(&mut self, (mut transforms, mut rigidbodies, time): Self::SystemData) {
+    fn run(&mut self, (mut transforms, mut rigidbodies, time, pooled): Self::SystemData) {
         let delta_time = time.delta_time;
         
-        for (transform, rigidbody) in (&mut transforms, &mut rigidbodies).join() {
+        for (transform, rigidbody, _) in (&mut transforms, &mut rigidbodies, !&pooled).join() {
             if rigidbody.is_kinematic {
                 continue;
             }
@@ -157,9 +261,10 @@ impl<'a> System<'a> for LifecycleSystem {
     type SystemData = (
         specs::Entities<'a>,
         ReadStorage<'a, Tag>,
+        Write<'a, SubsystemErrors>,
     );
 
-    fn run(&mut self, (entities, tags): Self::SystemData) {
+    fn run(&mut self, (entities, tags, mut errors): Self::SystemData) {
         let mut to_delete = Vec::new();
         
         for (entity, tag) in (&entities, &tags).join() {
@@ -171,8 +276,96 @@ impl<'a> System<'a> for LifecycleSystem {
         // 删除标记为销毁的实体
         for entity in to_delete {
             if let Err(e) = entities.delete(entity) {
-                log::error!("删除实体失败: {:?}", e);
+                errors.report("lifecycle", format!("删除实体失败: {:?}", e));
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::ECSWorld;
+    use crate::math::Spline;
+    use specs::{Builder, WorldExt};
+    use std::sync::Arc;
+
+    #[test]
+    fn path_follow_system_reaches_end_and_faces_tangent() {
+        let mut world = ECSWorld::new().unwrap();
+        world.setup_default_resources();
+        let spline = Arc::new(Spline::new([Vec3::ZERO, Vec3::new(0.0, 0.0, 10.0)]));
+        let length = spline.length();
+        let entity = world
+            .create_entity()
+            .with(Transform::default())
+            .with(PathFollower::new(spline, length / 2.0))
+            .build();
+
+        // 速度为长度的一半，1秒后到达中点，2秒后到达终点
+        for _ in 0..4 {
+            world.update_with_time(0.25, 0.25).unwrap();
+        }
+        {
+            let transforms = world.world().read_storage::<Transform>();
+            let transform = transforms.get(entity).unwrap();
+            assert!((transform.position.z - 5.0).abs() < 0.05);
+            assert!(transform.forward().abs_diff_eq(Vec3::Z, 1e-4));
+        }
+
+        // 多推进一帧，越过终点后停在终点
+        for _ in 0..5 {
+            world.update_with_time(0.25, 0.25).unwrap();
+        }
+        let followers = world.world().read_storage::<PathFollower>();
+        assert!(followers.get(entity).unwrap().finished);
+        let transforms = world.world().read_storage::<Transform>();
+        assert!(transforms.get(entity).unwrap().position.abs_diff_eq(Vec3::new(0.0, 0.0, 10.0), 1e-4));
+    }
+
+    #[test]
+    fn sprite_animation_system_updates_uvs_and_reports_events() {
+        let mut world = ECSWorld::new().unwrap();
+        world.setup_default_resources();
+        let sheet = Arc::new(SpriteSheet::from_grid("coin", 4, 1, 0));
+        let looping = world
+            .create_entity()
+            .with(SpriteRenderer::new("coin"))
+            .with(SpriteAnimation::new(sheet.clone(), 10.0).with_mode(PathLoopMode::Loop))
+            .build();
+        let once = world
+            .create_entity()
+            .with(SpriteRenderer::new("coin"))
+            .with(SpriteAnimation::new(sheet, 10.0).with_mode(PathLoopMode::Once))
+            .build();
+
+        let events_for = |world: &ECSWorld, entity| {
+            let events = world.get_resource::<SpriteAnimationEvents>().unwrap();
+            events.for_entity(entity).map(|event| event.kind).collect::<Vec<_>>()
+        };
+
+        for _ in 0..2 {
+            world.update_with_time(0.1, 0.1).unwrap();
+            assert!(world.get_resource::<SpriteAnimationEvents>().unwrap().is_empty());
+        }
+        {
+            let sprites = world.world().read_storage::<SpriteRenderer>();
+            let sprite = sprites.get(looping).unwrap();
+            assert_eq!((sprite.uv_min.x, sprite.uv_max.x), (0.5, 0.75));
+        }
+
+        world.update_with_time(0.1, 0.1).unwrap();
+        assert_eq!(events_for(&world, looping), [SpriteAnimationEventKind::LastFrame]);
+        assert_eq!(
+            events_for(&world, once),
+            [SpriteAnimationEventKind::LastFrame, SpriteAnimationEventKind::Finished]
+        );
+
+        // 事件只保留一帧，结束的Once动画不再产生事件
+        world.update_with_time(0.1, 0.1).unwrap();
+        assert!(world.get_resource::<SpriteAnimationEvents>().unwrap().is_empty());
+        let animations = world.world().read_storage::<SpriteAnimation>();
+        assert_eq!(animations.get(looping).unwrap().current_frame, 0);
+        assert_eq!(animations.get(once).unwrap().current_frame, 3);
+    }
+}
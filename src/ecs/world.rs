@@ -3,40 +3,114 @@
 use crate::{EngineResult, EngineError};
 use crate::ecs::component::*;
 use crate::ecs::system::*;
+use crate::ecs::snapshot::{ComponentSnapshotter, TypedSnapshotter, WorldSnapshot};
+use crate::ecs::schedule::{DispatchMode, SystemSchedule};
+use crate::time::FixedTimestep;
 
-use specs::{World, WorldExt, DispatcherBuilder, Dispatcher, RunNow, Component};
+use specs::{World, WorldExt, Component};
 
 /// ECS世界包装器
 pub struct ECSWorld {
     world: World,
-    dispatcher: Option<Dispatcher<'static, 'static>>,
+    schedule: Option<SystemSchedule>,
+    /// 参与快照的组件类型
+    snapshotters: Vec<Box<dyn ComponentSnapshotter>>,
+    /// 未由外部写入固定步数时，`update_with_time` 用它累加帧时间
+    fixed_step: FixedTimestep,
+    /// 本帧是否已通过 `set_fixed_steps` 写入固定步数
+    fixed_steps_set: bool,
 }
 
 impl ECSWorld {
     /// 创建新的ECS世界
     pub fn new() -> EngineResult<Self> {
         let mut world = World::new();
-        
-        // 注册核心组件
-        world.register::<Transform>();
-        world.register::<MeshRenderer>();
-        world.register::<Camera>();
-        world.register::<Light>();
-        world.register::<RigidBody>();
-        world.register::<Name>();
-        world.register::<Tag>();
-
-        // 创建系统调度器
-        let dispatcher = DispatcherBuilder::new()
-            .with(TransformSystem::new(), "transform", &[])
-            .with(RenderSystem::new(), "render", &["transform"])
-            .with(PhysicsSystem::new(), "physics", &[])
+        // 帧时间和固定步数由 `update_with_time`/`set_fixed_steps` 每帧写入
+        world.insert(TimeResource::default());
+        world.insert(SubsystemErrors::default());
+
+        // 创建系统调度，无依赖关系且组件访问不冲突的系统并行运行
+        let schedule = SystemSchedule::builder()
+            .with_system(BehaviorSystem::new(), "behavior", &[])
+            .with_system(PathFollowSystem::new(), "path_follow", &["behavior"])
+            .with_system(SpriteAnimationSystem::new(), "sprite_animation", &[])
+            .with_system(TransformSystem::new(), "transform", &["path_follow"])
+            .with_system(RenderSystem::new(), "render", &["transform", "sprite_animation"])
+            .with_system(PhysicsSystem::new(), "physics", &[])
             .build();
 
-        Ok(Self {
+        let mut ecs_world = Self {
             world,
-            dispatcher: Some(dispatcher),
-        })
+            schedule: Some(schedule),
+            snapshotters: Vec::new(),
+            fixed_step: FixedTimestep::default(),
+            fixed_steps_set: false,
+        };
+
+        // 调度中的系统按各自的SystemData注册组件并插入默认资源，新增系统无需手动插入
+        if let Some(schedule) = ecs_world.schedule.as_mut() {
+            schedule.setup(&mut ecs_world.world);
+        }
+
+        // 注册核心组件
+        ecs_world.register_snapshot_component::<Transform>();
+        ecs_world.register_snapshot_component::<MeshRenderer>();
+        ecs_world.register_snapshot_component::<SpriteRenderer>();
+        ecs_world.register_snapshot_component::<Tilemap>();
+        ecs_world.register_snapshot_component::<Camera>();
+        ecs_world.register_snapshot_component::<Light>();
+        ecs_world.register_snapshot_component::<RigidBody>();
+        ecs_world.register_snapshot_component::<Name>();
+        ecs_world.register_snapshot_component::<Tag>();
+        ecs_world.register_snapshot_component::<Pooled>();
+        ecs_world.register_snapshot_component::<PathFollower>();
+        ecs_world.register_snapshot_component::<SpriteAnimation>();
+        // 行为持有运行时状态，不参与快照
+        ecs_world.register_component::<ScriptComponent>();
+
+        Ok(ecs_world)
+    }
+
+    /// 注册组件，存储类型由组件的 `Component::Storage` 决定(见 `ecs::storage`)
+    pub fn register_component<T: Component>(&mut self)
+    where
+        T::Storage: Default,
+    {
+        self.world.register::<T>();
+    }
+
+    /// 用自定义的存储实例注册组件，用于需要特殊构造的存储；已注册的组件不受影响
+    pub fn register_component_with_storage<T, F>(&mut self, storage: F)
+    where
+        T: Component,
+        F: FnOnce() -> T::Storage,
+    {
+        self.world.register_with_storage::<F, T>(storage);
+    }
+
+    /// 注册组件并让其参与快照，重复调用不会重复登记
+    pub fn register_snapshot_component<T>(&mut self)
+    where
+        T: Component + Clone + Send + Sync,
+        T::Storage: Default,
+    {
+        self.world.register::<T>();
+        let type_name = std::any::type_name::<T>();
+        if !self.snapshotters.iter().any(|snapshotter| snapshotter.type_name() == type_name) {
+            self.snapshotters.push(Box::new(TypedSnapshotter::<T>::new()));
+        }
+    }
+
+    /// 捕获所有实体及已登记组件的当前状态
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot::capture(&self.world, &self.snapshotters)
+    }
+
+    /// 还原到快照状态，返回被重建实体的映射(快照实体 -> 新实体)
+    ///
+    /// 快照之后创建的实体会被删除，之后删除的实体以新ID重建，其余实体保持不变。
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) -> std::collections::HashMap<specs::Entity, specs::Entity> {
+        snapshot.restore(&mut self.world, &self.snapshotters)
     }
 
     /// 获取内部World的可变引用
@@ -56,22 +130,81 @@ impl ECSWorld {
 
     /// 更新ECS系统
     pub fn update(&mut self, delta_time: f32) -> EngineResult<()> {
+        self.update_with_time(delta_time, delta_time)
+    }
+
+    /// 以缩放后的游戏帧时间和真实帧时间更新ECS系统
+    ///
+    /// 世界内的效果使用 `delta_time`，暂停时冻结；标记了使用非缩放时间的UI/常驻效果使用 `unscaled_delta_time`。
+    /// 本帧未调用 `set_fixed_steps` 时，固定步数由内部累加器根据 `delta_time` 计算。
+    /// 系统写入 `SubsystemErrors` 的错误在调度结束后合并返回，世界状态照常维护。
+    pub fn update_with_time(&mut self, delta_time: f32, unscaled_delta_time: f32) -> EngineResult<()> {
+        if !self.fixed_steps_set {
+            self.fixed_step.accumulate(delta_time);
+            let fixed_step = self.fixed_step.clone();
+            self.publish_fixed_steps(&fixed_step);
+        }
+
         // 更新时间资源
         {
             let mut time_res = self.world.write_resource::<TimeResource>();
             time_res.delta_time = delta_time;
+            time_res.unscaled_delta_time = unscaled_delta_time;
             time_res.total_time += delta_time;
         }
 
-        // 运行系统调度器
-        if let Some(ref mut dispatcher) = self.dispatcher {
-            dispatcher.dispatch(&self.world);
+        // 运行系统调度
+        if let Some(ref mut schedule) = self.schedule {
+            schedule.dispatch(&self.world);
         }
 
+        // 固定步数只在当帧有效，避免下一帧重放
+        self.world.write_resource::<TimeResource>().fixed_steps = 0;
+        self.fixed_steps_set = false;
+
         // 维护世界状态
         self.world.maintain();
 
-        Ok(())
+        self.world.write_resource::<SubsystemErrors>().take_result()
+    }
+
+    /// 写入本帧的固定步数、步长和步数上限，在 `update_with_time` 之前调用
+    ///
+    /// 引擎用 `TimeManager` 的累加器驱动时调用，本帧不再使用内部累加器。
+    pub fn set_fixed_steps(&mut self, fixed_step: &FixedTimestep) {
+        self.fixed_step.set_timestep(fixed_step.timestep());
+        self.fixed_step.set_max_substeps(fixed_step.max_substeps());
+        self.publish_fixed_steps(fixed_step);
+        self.fixed_steps_set = true;
+    }
+
+    fn publish_fixed_steps(&mut self, fixed_step: &FixedTimestep) {
+        let mut time_res = self.world.write_resource::<TimeResource>();
+        time_res.fixed_steps = fixed_step.last_steps();
+        time_res.fixed_timestep = fixed_step.timestep();
+        time_res.max_substeps = fixed_step.max_substeps();
+    }
+
+    /// 内部固定步长累加器，可调整步长和每帧最大步数
+    pub fn fixed_timestep_mut(&mut self) -> &mut FixedTimestep {
+        &mut self.fixed_step
+    }
+
+    /// 替换系统调度，新调度用到的组件与资源会被注册
+    pub fn set_schedule(&mut self, mut schedule: SystemSchedule) {
+        schedule.setup(&mut self.world);
+        self.schedule = Some(schedule);
+    }
+
+    pub fn schedule(&self) -> Option<&SystemSchedule> {
+        self.schedule.as_ref()
+    }
+
+    /// 切换并行/单线程调度
+    pub fn set_dispatch_mode(&mut self, mode: DispatchMode) {
+        if let Some(schedule) = &mut self.schedule {
+            schedule.set_mode(mode);
+        }
     }
 
     /// 添加资源
@@ -110,24 +243,475 @@ impl ECSWorld {
         (&entities, &storage).join().map(|(e, _)| e).collect()
     }
 
+    /// 按(id, generation)排序的所有存活实体，迭代顺序与创建、删除的先后无关
+    pub fn entities_sorted(&self) -> Vec<specs::Entity> {
+        sorted_entities(&self.world)
+    }
+
+    /// 按(id, generation)排序的带有指定组件的实体，用于需要可复现结果的系统
+    pub fn find_entities_with_sorted<T: Component>(&self) -> Vec<specs::Entity>
+    where
+        T::Storage: Default,
+    {
+        let mut entities = self.find_entities_with::<T>();
+        sort_entities(&mut entities);
+        entities
+    }
+
     /// 删除实体
     pub fn delete_entity(&mut self, entity: specs::Entity) -> EngineResult<()> {
         Ok(self.world
             .delete_entity(entity)
             .map_err(|e| EngineError::RenderError(format!("删除实体失败: {:?}", e)))?)
     }
+
+    /// 批量删除实体，已失效的实体会被跳过
+    pub fn delete_entities(&mut self, entities: &[specs::Entity]) -> EngineResult<usize> {
+        let alive: Vec<specs::Entity> = {
+            let entity_storage = self.world.entities();
+            entities.iter().copied().filter(|&e| entity_storage.is_alive(e)).collect()
+        };
+
+        self.world
+            .delete_entities(&alive)
+            .map_err(|(e, index)| EngineError::RenderError(format!("删除实体失败(第{}个): {:?}", index, e)))?;
+        self.world.maintain();
+
+        Ok(alive.len())
+    }
+
+    /// 删除所有实体及其组件，世界可继续用于创建新实体
+    pub fn clear(&mut self) {
+        self.world.delete_all();
+        self.world.maintain();
+    }
+
+    /// 存活实体数量
+    pub fn entity_count(&self) -> usize {
+        use specs::Join;
+        self.world.entities().join().count()
+    }
+
+    /// 世界是否没有任何实体
+    pub fn is_empty(&self) -> bool {
+        self.entity_count() == 0
+    }
+}
+
+/// 实体的稳定排序键(id, generation)
+pub fn entity_sort_key(entity: specs::Entity) -> (u32, i32) {
+    (entity.id(), entity.gen().id())
+}
+
+/// 按(id, generation)排序实体
+pub fn sort_entities(entities: &mut [specs::Entity]) {
+    entities.sort_unstable_by_key(|&entity| entity_sort_key(entity));
+}
+
+/// 按(id, generation)排序的所有存活实体
+pub fn sorted_entities(world: &World) -> Vec<specs::Entity> {
+    use specs::Join;
+    let mut entities: Vec<specs::Entity> = world.entities().join().collect();
+    sort_entities(&mut entities);
+    entities
+}
+
+/// 系统运行中的错误
+///
+/// 系统无法返回错误，失败时写入此资源；`ECSWorld::update_with_time` 在调度结束后取出并作为本帧结果返回，
+/// 引擎据此把ECS子系统标记为降级。
+#[derive(Debug, Default)]
+pub struct SubsystemErrors {
+    errors: Vec<(String, String)>,
+}
+
+impl SubsystemErrors {
+    /// 记录某个系统本帧的错误
+    pub fn report(&mut self, system: impl Into<String>, error: impl std::fmt::Display) {
+        self.errors.push((system.into(), error.to_string()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// 取出本帧的错误并合并为一个结果，没有错误时返回Ok
+    pub fn take_result(&mut self) -> EngineResult<()> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let message = self
+            .errors
+            .drain(..)
+            .map(|(system, message)| format!("{}: {}", system, message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(anyhow::anyhow!("系统运行失败: {}", message))
+    }
 }
 
 /// 时间资源
 #[derive(Debug, Default)]
 pub struct TimeResource {
+    /// 按时间缩放后的帧时间，暂停时为0
     pub delta_time: f32,
+    /// 真实帧时间
+    pub unscaled_delta_time: f32,
+    /// 累计的游戏时间
     pub total_time: f32,
+    /// 本帧需要执行的固定步数，来自 `TimeManager` 或 `ECSWorld` 的固定步长累加器，只在当帧有效
+    pub fixed_steps: u32,
+    /// 固定步长(秒)
+    pub fixed_timestep: f32,
+    /// 每帧最大固定步数，物理系统按它限制子步数
+    pub max_substeps: u32,
+}
+
+impl TimeResource {
+    /// 按效果是否使用非缩放时间选择帧时间
+    pub fn delta(&self, use_unscaled_time: bool) -> f32 {
+        if use_unscaled_time {
+            self.unscaled_delta_time
+        } else {
+            self.delta_time
+        }
+    }
+}
+
+/// 精灵动画播放事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpriteAnimationEventKind {
+    /// 显示到最后一帧(循环动画每轮一次)
+    LastFrame,
+    /// Once模式播放结束
+    Finished,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpriteAnimationEvent {
+    pub entity: specs::Entity,
+    pub kind: SpriteAnimationEventKind,
+}
+
+/// 本帧的精灵动画事件，每次 `SpriteAnimationSystem` 运行时重置
+#[derive(Debug, Default)]
+pub struct SpriteAnimationEvents {
+    events: Vec<SpriteAnimationEvent>,
+}
+
+impl SpriteAnimationEvents {
+    pub fn push(&mut self, event: SpriteAnimationEvent) {
+        self.events.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SpriteAnimationEvent> {
+        self.events.iter()
+    }
+
+    /// 指定实体本帧的事件
+    pub fn for_entity(&self, entity: specs::Entity) -> impl Iterator<Item = &SpriteAnimationEvent> {
+        self.events.iter().filter(move |event| event.entity == entity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
 }
 
 impl ECSWorld {
     /// 初始化默认资源
     pub fn setup_default_resources(&mut self) {
         self.add_resource(TimeResource::default());
+        self.add_resource(SpriteAnimationEvents::default());
+        self.add_resource(crate::ecs::behavior::BehaviorEvents::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::{Builder, Join};
+
+    #[test]
+    fn clear_removes_everything_and_world_stays_usable() {
+        let mut world = ECSWorld::new().unwrap();
+        for _ in 0..100 {
+            world.create_entity().with(Transform::default()).build();
+        }
+        assert_eq!(world.entity_count(), 100);
+
+        world.clear();
+        assert!(world.is_empty());
+        assert_eq!(world.world().read_storage::<Transform>().join().count(), 0);
+
+        let entity = world.create_entity().with(Transform::default()).build();
+        assert_eq!(world.entity_count(), 1);
+        assert!(world.world().read_storage::<Transform>().get(entity).is_some());
+    }
+
+    #[test]
+    fn delete_entities_skips_dead_entities() {
+        let mut world = ECSWorld::new().unwrap();
+        let entities: Vec<_> = (0..4).map(|_| world.create_entity().build()).collect();
+
+        assert_eq!(world.delete_entities(&entities[..2]).unwrap(), 2);
+        // 已删除的实体被忽略
+        assert_eq!(world.delete_entities(&entities).unwrap(), 2);
+        assert!(world.is_empty());
+    }
+
+    #[test]
+    fn sorted_entities_ignore_creation_and_deletion_order() {
+        let mut world = ECSWorld::new().unwrap();
+        let entities: Vec<_> = (0..5).map(|_| world.create_entity().build()).collect();
+        world.delete_entities(&[entities[3], entities[1]]).unwrap();
+        // 新实体复用已删除的id
+        let reused = world.create_entity().with(Transform::default()).build();
+        world.create_entity().with(Transform::default()).build();
+
+        let sorted = world.entities_sorted();
+        assert_eq!(sorted.len(), 5);
+        assert!(sorted.windows(2).all(|pair| entity_sort_key(pair[0]) < entity_sort_key(pair[1])));
+        assert!(sorted.contains(&reused));
+
+        let with_transform = world.find_entities_with_sorted::<Transform>();
+        assert_eq!(with_transform.len(), 2);
+        assert!(entity_sort_key(with_transform[0]) < entity_sort_key(with_transform[1]));
+    }
+
+    #[test]
+    fn fixed_steps_are_published_to_time_resource() {
+        let mut world = ECSWorld::new().unwrap();
+        let mut fixed = FixedTimestep::new(0.125, 5);
+        fixed.accumulate(0.375);
+        world.set_fixed_steps(&fixed);
+
+        let time = world.world().read_resource::<TimeResource>();
+        assert_eq!(time.fixed_steps, 3);
+        assert_eq!(time.fixed_timestep, 0.125);
+        assert_eq!(time.max_substeps, 5);
+    }
+
+    #[test]
+    fn update_accumulates_fixed_steps_and_does_not_replay_them() {
+        let mut world = ECSWorld::new().unwrap();
+        world.fixed_timestep_mut().set_timestep(0.25);
+        world.set_schedule(
+            SystemSchedule::builder()
+                .with_system(FixedStepCounter, "counter", &[])
+                .build(),
+        );
+        world.add_resource(FixedStepLog::default());
+
+        world.update(0.625).unwrap();
+        world.update(0.125).unwrap();
+        // 外部写入的步数只用于当帧
+        let mut fixed = FixedTimestep::new(0.25, 8);
+        fixed.accumulate(1.0);
+        world.set_fixed_steps(&fixed);
+        world.update(0.0).unwrap();
+        world.update(0.0).unwrap();
+
+        assert_eq!(world.world().read_resource::<FixedStepLog>().0, vec![2, 1, 4, 0]);
+        assert_eq!(world.world().read_resource::<TimeResource>().fixed_steps, 0);
+    }
+
+    /// 记录每帧看到的固定步数
+    #[derive(Default)]
+    struct FixedStepLog(Vec<u32>);
+
+    struct FixedStepCounter;
+
+    impl<'a> specs::System<'a> for FixedStepCounter {
+        type SystemData = (specs::Read<'a, TimeResource>, specs::Write<'a, FixedStepLog>);
+
+        fn run(&mut self, (time, mut log): Self::SystemData) {
+            log.0.push(time.fixed_steps);
+        }
+    }
+
+    /// 只在测试中使用、需要手动登记快照的组件
+    #[derive(Debug, Clone, PartialEq)]
+    struct Health(i32);
+
+    impl Component for Health {
+        type Storage = specs::VecStorage<Self>;
+    }
+
+    /// 未登记快照的组件
+    #[derive(Debug, Clone, PartialEq)]
+    struct Score(i32);
+
+    impl Component for Score {
+        type Storage = specs::VecStorage<Self>;
+    }
+
+    fn transform_at(x: f32) -> Transform {
+        Transform::new().with_position(glam::Vec3::new(x, 0.0, 0.0))
+    }
+
+    /// (实体, 位置, 名称, 标签, 生命值)，按实体排序
+    fn state(world: &ECSWorld) -> Vec<(specs::Entity, Option<glam::Vec3>, Option<String>, Option<Vec<String>>, Option<i32>)> {
+        let transforms = world.world().read_storage::<Transform>();
+        let names = world.world().read_storage::<Name>();
+        let tags = world.world().read_storage::<Tag>();
+        let health = world.world().read_storage::<Health>();
+        world
+            .entities_sorted()
+            .into_iter()
+            .map(|entity| {
+                (
+                    entity,
+                    transforms.get(entity).map(|transform| transform.position),
+                    names.get(entity).map(|name| name.name.clone()),
+                    tags.get(entity).map(|tag| tag.tags.clone()),
+                    health.get(entity).map(|health| health.0),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn restore_returns_components_to_snapshot_values() {
+        let mut world = ECSWorld::new().unwrap();
+        world.register_snapshot_component::<Health>();
+        let mut tag = Tag::new();
+        tag.tags.push("enemy".to_string());
+
+        let player = world.create_entity().with(transform_at(1.0)).with(Name::new("player")).with(Health(100)).build();
+        let enemy = world.create_entity().with(transform_at(5.0)).with(Name::new("enemy")).with(tag).with(Health(30)).build();
+        let prop = world.create_entity().with(transform_at(-2.0)).build();
+
+        let snapshot = world.snapshot();
+        let before = state(&world);
+        assert_eq!(snapshot.entity_count(), 3);
+        assert!(snapshot.component_types().contains(&std::any::type_name::<Health>()));
+
+        {
+            let ecs = world.world_mut();
+            ecs.write_storage::<Transform>().get_mut(player).unwrap().position.x = 50.0;
+            ecs.write_storage::<Name>().get_mut(player).unwrap().name = "renamed".to_string();
+            ecs.write_storage::<Health>().get_mut(enemy).unwrap().0 = 0;
+            ecs.write_storage::<Tag>().remove(enemy);
+            ecs.write_storage::<Health>().insert(prop, Health(7)).unwrap();
+        }
+        world.create_entity().with(transform_at(9.0)).with(Name::new("spawned")).build();
+        assert_ne!(state(&world), before);
+
+        let recreated = world.restore(&snapshot);
+        assert!(recreated.is_empty());
+        assert_eq!(state(&world), before);
+        assert_eq!(world.entity_count(), 3);
+    }
+
+    #[test]
+    fn deleted_entities_are_recreated_with_their_components() {
+        let mut world = ECSWorld::new().unwrap();
+        let kept = world.create_entity().with(transform_at(1.0)).build();
+        let deleted = world.create_entity().with(transform_at(2.0)).with(Name::new("crate")).build();
+        let snapshot = world.snapshot();
+
+        world.delete_entity(deleted).unwrap();
+        world.world_mut().maintain();
+        let recreated = world.restore(&snapshot);
+
+        let new_entity = recreated[&deleted];
+        assert!(world.world().is_alive(new_entity));
+        assert_eq!(world.entity_count(), 2);
+        let transforms = world.world().read_storage::<Transform>();
+        assert_eq!(transforms.get(kept).unwrap().position.x, 1.0);
+        assert_eq!(transforms.get(new_entity).unwrap().position.x, 2.0);
+        assert_eq!(world.world().read_storage::<Name>().get(new_entity).unwrap().name, "crate");
+    }
+
+    #[test]
+    fn unregistered_components_are_not_captured() {
+        let mut world = ECSWorld::new().unwrap();
+        world.world_mut().register::<Score>();
+        let entity = world.create_entity().with(Score(1)).build();
+        let snapshot = world.snapshot();
+
+        world.world_mut().write_storage::<Score>().insert(entity, Score(99)).unwrap();
+        world.restore(&snapshot);
+        assert_eq!(world.world().read_storage::<Score>().get(entity), Some(&Score(99)));
+
+        // 重复登记不会重复捕获
+        world.register_snapshot_component::<Health>();
+        world.register_snapshot_component::<Health>();
+        let types = world.snapshot().component_types();
+        assert_eq!(types.iter().filter(|name| **name == std::any::type_name::<Health>()).count(), 1);
+    }
+
+    /// 用于自定义存储注册的组件
+    #[derive(Debug, Clone, PartialEq)]
+    struct Velocity(f32);
+
+    impl Component for Velocity {
+        type Storage = specs::HashMapStorage<Self>;
+    }
+
+    #[test]
+    fn sparse_and_dense_components_read_and_write() {
+        use crate::ecs::storage::{Dense, Packed, Sparse};
+
+        let mut world = ECSWorld::new().unwrap();
+        world.register_component::<Packed<glam::Vec3>>();
+        world.register_component::<Sparse<String>>();
+        world.register_component::<Dense<u32>>();
+
+        let entities: Vec<_> = (0..200)
+            .map(|i| world.create_entity().with(Packed(glam::Vec3::splat(i as f32))).with(Dense(i as u32)).build())
+            .collect();
+        // 只有少数实体拥有稀疏组件
+        let boss = entities[150];
+        world.world().write_storage::<Sparse<String>>().insert(boss, Sparse("boss".to_string())).unwrap();
+        world.world().write_storage::<Sparse<String>>().insert(entities[3], "minion".to_string().into()).unwrap();
+
+        for packed in (&mut world.world().write_storage::<Packed<glam::Vec3>>()).join() {
+            packed.y += 1.0;
+        }
+        world.world().write_storage::<Sparse<String>>().get_mut(boss).unwrap().push_str("_phase2");
+
+        let packed = world.world().read_storage::<Packed<glam::Vec3>>();
+        let sparse = world.world().read_storage::<Sparse<String>>();
+        let dense = world.world().read_storage::<Dense<u32>>();
+        assert_eq!(packed.get(entities[150]).unwrap().0, glam::Vec3::new(150.0, 151.0, 150.0));
+        assert_eq!(sparse.join().count(), 2);
+        assert_eq!(sparse.get(boss).unwrap().as_str(), "boss_phase2");
+        assert!(sparse.get(entities[0]).is_none());
+        assert_eq!(dense.join().map(|value| value.0).sum::<u32>(), (0..200u32).sum::<u32>());
+    }
+
+    #[test]
+    fn custom_storage_registration_keeps_existing_storage() {
+        let mut world = ECSWorld::new().unwrap();
+        world.register_component_with_storage::<Velocity, _>(specs::HashMapStorage::default);
+        let entity = world.create_entity().with(Velocity(2.0)).build();
+
+        // 再次注册不会替换已有数据
+        world.register_component_with_storage::<Velocity, _>(specs::HashMapStorage::default);
+        world.register_component::<Velocity>();
+        assert_eq!(world.world().read_storage::<Velocity>().get(entity), Some(&Velocity(2.0)));
+    }
+
+    #[test]
+    fn dispatch_mode_switch_keeps_default_systems() {
+        let mut world = ECSWorld::new().unwrap();
+        let names = world.schedule().unwrap().system_names().to_vec();
+        assert!(names.iter().any(|name| name == "transform"));
+
+        world.set_dispatch_mode(DispatchMode::Sequential);
+        assert_eq!(world.schedule().unwrap().mode(), DispatchMode::Sequential);
+        world.create_entity().with(Transform::default()).build();
+        world.update(1.0 / 60.0).unwrap();
+        assert_eq!(world.schedule().unwrap().system_names(), names.as_slice());
     }
 }
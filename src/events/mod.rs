@@ -1,8 +1,12 @@
 //! 事件系统
 
-use std::collections::{HashMap, VecDeque};
+pub mod queue;
+
+pub use queue::*;
+
+use std::collections::HashMap;
 use std::any::{Any, TypeId};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 /// 事件trait - 所有事件都必须实现此trait
 pub trait Event: Any + Send + Sync {
@@ -46,7 +50,7 @@ pub struct EventSystem {
     /// 事件监听器
     listeners: HashMap<TypeId, Vec<EventListener>>,
     /// 事件队列
-    event_queue: Arc<Mutex<VecDeque<Box<dyn Any + Send + Sync>>>>,
+    event_queue: Arc<EventQueue>,
     /// 是否启用即时模式
     immediate_mode: bool,
 }
@@ -54,13 +58,37 @@ pub struct EventSystem {
 impl EventSystem {
     /// 创建新的事件系统
     pub fn new() -> Self {
+        Self::with_queue_config(EventQueueConfig::default())
+    }
+
+    /// 使用指定的队列配置创建事件系统
+    pub fn with_queue_config(config: EventQueueConfig) -> Self {
         Self {
             listeners: HashMap::new(),
-            event_queue: Arc::new(Mutex::new(VecDeque::new())),
+            event_queue: Arc::new(EventQueue::new(config)),
             immediate_mode: false,
         }
     }
 
+    /// 设置队列容量与溢出策略
+    pub fn set_queue_config(&mut self, config: EventQueueConfig) {
+        self.event_queue.set_config(config);
+    }
+
+    pub fn queue_config(&self) -> EventQueueConfig {
+        self.event_queue.config()
+    }
+
+    /// 队列统计(排队数、峰值、丢弃数)
+    pub fn queue_stats(&self) -> EventQueueStats {
+        self.event_queue.stats()
+    }
+
+    /// 重置队列统计
+    pub fn reset_queue_stats(&mut self) {
+        self.event_queue.reset_stats();
+    }
+
     /// 设置即时模式
     pub fn set_immediate_mode(&mut self, immediate: bool) {
         self.immediate_mode = immediate;
@@ -86,8 +114,11 @@ impl EventSystem {
         if self.immediate_mode {
             self.handle_event_immediate(&event);
         } else {
-            let mut queue = self.event_queue.lock().unwrap();
-            queue.push_back(Box::new(event));
+            if let Err(event) = self.event_queue.try_push(Box::new(event)) {
+                // Block策略：在本线程阻塞会死锁，先分发已排队的事件腾出空间
+                self.process_events();
+                self.event_queue.push_blocking(event);
+            }
         }
     }
 
@@ -103,10 +134,7 @@ impl EventSystem {
 
     /// 处理事件队列
     pub fn process_events(&mut self) {
-        let events: Vec<Box<dyn Any + Send + Sync>> = {
-            let mut queue = self.event_queue.lock().unwrap();
-            queue.drain(..).collect()
-        };
+        let events = self.event_queue.drain();
 
         for event in events {
             // 获取事件类型ID
@@ -123,14 +151,12 @@ impl EventSystem {
 
     /// 清空事件队列
     pub fn clear_queue(&mut self) {
-        let mut queue = self.event_queue.lock().unwrap();
-        queue.clear();
+        self.event_queue.clear();
     }
 
     /// 获取队列中的事件数量
     pub fn queue_size(&self) -> usize {
-        let queue = self.event_queue.lock().unwrap();
-        queue.len()
+        self.event_queue.len()
     }
 
     /// 取消订阅所有事件
@@ -188,6 +214,18 @@ impl Event for KeyPressedEvent {
     }
 }
 
+/// 文本输入事件(经过键盘布局和输入法处理后的字符)
+#[derive(Debug, Clone)]
+pub struct TextInputEvent {
+    pub text: String,
+}
+
+impl Event for TextInputEvent {
+    fn event_name(&self) -> &'static str {
+        "TextInput"
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct KeyReleasedEvent {
     pub key_code: winit::keyboard::KeyCode,
@@ -271,6 +309,21 @@ impl Event for AssetLoadedEvent {
     }
 }
 
+/// 资源在原句柄槽位中重新加载完成
+#[derive(Debug, Clone)]
+pub struct AssetReloadedEvent {
+    pub asset_path: String,
+    pub asset_type: String,
+    /// 重新加载后的代数
+    pub generation: u64,
+}
+
+impl Event for AssetReloadedEvent {
+    fn event_name(&self) -> &'static str {
+        "AssetReloaded"
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AssetLoadFailedEvent {
     pub asset_path: String,
@@ -283,21 +336,34 @@ impl Event for AssetLoadFailedEvent {
     }
 }
 
+/// 子系统运行失败事件，引擎会继续以降级模式运行
+#[derive(Debug, Clone)]
+pub struct SubsystemErrorEvent {
+    pub subsystem: crate::core::EngineSubsystem,
+    pub frame: u64,
+    pub message: String,
+}
+
+impl Event for SubsystemErrorEvent {
+    fn event_name(&self) -> &'static str {
+        "SubsystemError"
+    }
+}
+
 /// 事件发送器 - 线程安全的事件发送接口
 #[derive(Clone)]
 pub struct EventSender {
-    event_queue: Arc<Mutex<VecDeque<Box<dyn Any + Send + Sync>>>>,
+    event_queue: Arc<EventQueue>,
 }
 
 impl EventSender {
-    pub fn new(event_queue: Arc<Mutex<VecDeque<Box<dyn Any + Send + Sync>>>>) -> Self {
+    pub(crate) fn new(event_queue: Arc<EventQueue>) -> Self {
         Self { event_queue }
     }
 
-    /// 发送事件
+    /// 发送事件，队列已满时按溢出策略处理(Block策略会等待空位)
     pub fn send<T: Event + 'static>(&self, event: T) {
-        let mut queue = self.event_queue.lock().unwrap();
-        queue.push_back(Box::new(event));
+        self.event_queue.push_blocking(Box::new(event));
     }
 }
 
@@ -323,8 +389,70 @@ impl EventSystem {
         self.publish(KeyPressedEvent { key_code, repeat });
     }
 
+    /// 发布文本输入事件
+    pub fn publish_text_input(&mut self, text: impl Into<String>) {
+        self.publish(TextInputEvent { text: text.into() });
+    }
+
     /// 发布鼠标事件
     pub fn publish_mouse_moved(&mut self, position: glam::Vec2, delta: glam::Vec2) {
         self.publish(MouseMovedEvent { position, delta });
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recorded_widths(events: &mut EventSystem) -> Arc<Mutex<Vec<u32>>> {
+        let widths = Arc::new(Mutex::new(Vec::new()));
+        let sink = widths.clone();
+        events.subscribe::<WindowResizedEvent, _>(move |event| sink.lock().unwrap().push(event.width));
+        widths
+    }
+
+    #[test]
+    fn bounded_queue_drops_oldest_and_counts_drops() {
+        let mut events = EventSystem::with_queue_config(EventQueueConfig::bounded(2, OverflowPolicy::DropOldest));
+        let widths = recorded_widths(&mut events);
+
+        for width in 1..=5 {
+            events.publish_window_resized(width, 1);
+        }
+        assert_eq!(events.queue_size(), 2);
+        assert_eq!(events.queue_stats().dropped, 3);
+
+        events.process_events();
+        assert_eq!(*widths.lock().unwrap(), vec![4, 5]);
+        assert_eq!(events.queue_stats().queued, 0);
+    }
+
+    #[test]
+    fn block_policy_on_owner_thread_dispatches_instead_of_deadlocking() {
+        let mut events = EventSystem::with_queue_config(EventQueueConfig::bounded(2, OverflowPolicy::Block));
+        let widths = recorded_widths(&mut events);
+
+        for width in 1..=5 {
+            events.publish_window_resized(width, 1);
+        }
+        // 队列满时先分发已排队的事件，不丢弃
+        assert_eq!(*widths.lock().unwrap(), vec![1, 2, 3, 4]);
+        events.process_events();
+        assert_eq!(*widths.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(events.queue_stats().dropped, 0);
+    }
+
+    #[test]
+    fn sender_respects_queue_policy() {
+        let mut events = EventSystem::with_queue_config(EventQueueConfig::bounded(1, OverflowPolicy::DropNewest));
+        let widths = recorded_widths(&mut events);
+        let sender = events.sender();
+        sender.send(WindowResizedEvent { width: 7, height: 1 });
+        sender.send(WindowResizedEvent { width: 8, height: 1 });
+
+        events.process_events();
+        assert_eq!(*widths.lock().unwrap(), vec![7]);
+        assert_eq!(events.queue_stats().dropped, 1);
+    }
+}
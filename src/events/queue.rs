@@ -0,0 +1,287 @@
+//! 有界事件队列
+//!
+//! 延迟模式下发布的事件先进入队列，`EventSystem::process_events` 时统一分发。
+//! 队列可设置容量上限，满时按 `OverflowPolicy` 丢弃事件或阻塞发送方，丢弃数量记入统计。
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// 队列中的事件
+pub(crate) type QueuedEvent = Box<dyn Any + Send + Sync>;
+
+/// 队列满时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// 丢弃最早的事件，保留新事件
+    #[default]
+    DropOldest,
+    /// 丢弃新事件
+    DropNewest,
+    /// 阻塞发送方直到队列有空位
+    ///
+    /// `EventSender` 所在线程会等待 `process_events` 腾出空间；
+    /// 在拥有 `EventSystem` 的线程上发布时改为先分发已排队的事件，避免自身死锁。
+    Block,
+}
+
+/// 事件队列配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventQueueConfig {
+    /// 最大事件数，None为不限制
+    pub capacity: Option<usize>,
+    pub overflow_policy: OverflowPolicy,
+    /// Block策略下发送方的最长等待时间，超时后按DropNewest处理
+    pub block_timeout: Duration,
+}
+
+impl Default for EventQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: None,
+            overflow_policy: OverflowPolicy::DropOldest,
+            block_timeout: Duration::from_millis(100),
+        }
+    }
+}
+
+impl EventQueueConfig {
+    pub fn bounded(capacity: usize, overflow_policy: OverflowPolicy) -> Self {
+        Self {
+            capacity: Some(capacity.max(1)),
+            overflow_policy,
+            ..Default::default()
+        }
+    }
+}
+
+/// 事件队列统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventQueueStats {
+    /// 当前排队的事件数
+    pub queued: usize,
+    /// 队列达到过的最大长度
+    pub peak_queued: usize,
+    /// 因溢出被丢弃的事件总数
+    pub dropped: u64,
+    /// 发送方因Block策略等待的次数
+    pub blocked: u64,
+}
+
+struct QueueState {
+    events: VecDeque<QueuedEvent>,
+    config: EventQueueConfig,
+    stats: EventQueueStats,
+}
+
+/// 线程安全的有界事件队列
+pub(crate) struct EventQueue {
+    state: Mutex<QueueState>,
+    space_available: Condvar,
+}
+
+impl EventQueue {
+    pub(crate) fn new(config: EventQueueConfig) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                events: VecDeque::new(),
+                config,
+                stats: EventQueueStats::default(),
+            }),
+            space_available: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap()
+    }
+
+    /// 不阻塞地入队，Block策略下队列已满时原样返回事件，由调用方先腾出空间
+    pub(crate) fn try_push(&self, event: QueuedEvent) -> Result<(), QueuedEvent> {
+        let mut state = self.lock();
+        if Self::is_full(&state) && state.config.overflow_policy == OverflowPolicy::Block {
+            return Err(event);
+        }
+        Self::push_locked(&mut state, event);
+        Ok(())
+    }
+
+    /// 入队，Block策略下等待空位(最长block_timeout)，超时后丢弃该事件
+    pub(crate) fn push_blocking(&self, event: QueuedEvent) {
+        let mut state = self.lock();
+        if Self::is_full(&state) && state.config.overflow_policy == OverflowPolicy::Block {
+            state.stats.blocked += 1;
+            let timeout = state.config.block_timeout;
+            let (guard, _) = self
+                .space_available
+                .wait_timeout_while(state, timeout, |state| {
+                    Self::is_full(state) && state.config.overflow_policy == OverflowPolicy::Block
+                })
+                .unwrap();
+            state = guard;
+
+            if Self::is_full(&state) && state.config.overflow_policy == OverflowPolicy::Block {
+                state.stats.dropped += 1;
+                return;
+            }
+        }
+        Self::push_locked(&mut state, event);
+    }
+
+    fn is_full(state: &QueueState) -> bool {
+        state.config.capacity.map_or(false, |capacity| state.events.len() >= capacity)
+    }
+
+    fn push_locked(state: &mut QueueState, event: QueuedEvent) {
+        if Self::is_full(state) {
+            match state.config.overflow_policy {
+                OverflowPolicy::DropOldest => {
+                    state.events.pop_front();
+                    state.stats.dropped += 1;
+                }
+                OverflowPolicy::DropNewest => {
+                    state.stats.dropped += 1;
+                    return;
+                }
+                // 调用方已确保Block策略下队列有空位
+                OverflowPolicy::Block => {}
+            }
+        }
+        state.events.push_back(event);
+        state.stats.peak_queued = state.stats.peak_queued.max(state.events.len());
+    }
+
+    /// 取出全部事件
+    pub(crate) fn drain(&self) -> Vec<QueuedEvent> {
+        let events = self.lock().events.drain(..).collect();
+        self.space_available.notify_all();
+        events
+    }
+
+    pub(crate) fn clear(&self) {
+        self.lock().events.clear();
+        self.space_available.notify_all();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.lock().events.len()
+    }
+
+    pub(crate) fn config(&self) -> EventQueueConfig {
+        self.lock().config
+    }
+
+    /// 修改配置，容量缩小时按新策略裁剪已有事件(Block策略裁剪最早的事件)
+    pub(crate) fn set_config(&self, config: EventQueueConfig) {
+        let mut state = self.lock();
+        state.config = config;
+        if let Some(capacity) = config.capacity {
+            while state.events.len() > capacity {
+                match config.overflow_policy {
+                    OverflowPolicy::DropNewest => state.events.pop_back(),
+                    _ => state.events.pop_front(),
+                };
+                state.stats.dropped += 1;
+            }
+        }
+        drop(state);
+        self.space_available.notify_all();
+    }
+
+    pub(crate) fn stats(&self) -> EventQueueStats {
+        let state = self.lock();
+        EventQueueStats {
+            queued: state.events.len(),
+            ..state.stats
+        }
+    }
+
+    pub(crate) fn reset_stats(&self) {
+        let mut state = self.lock();
+        state.stats = EventQueueStats {
+            peak_queued: state.events.len(),
+            ..Default::default()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drained_values(queue: &EventQueue) -> Vec<u32> {
+        queue.drain().into_iter().map(|event| *event.downcast::<u32>().unwrap()).collect()
+    }
+
+    #[test]
+    fn drop_oldest_keeps_newest_events() {
+        let queue = EventQueue::new(EventQueueConfig::bounded(3, OverflowPolicy::DropOldest));
+        for value in 0..5u32 {
+            queue.try_push(Box::new(value)).unwrap();
+        }
+        assert_eq!(queue.stats(), EventQueueStats { queued: 3, peak_queued: 3, dropped: 2, blocked: 0 });
+        assert_eq!(drained_values(&queue), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn drop_newest_keeps_oldest_events() {
+        let queue = EventQueue::new(EventQueueConfig::bounded(3, OverflowPolicy::DropNewest));
+        for value in 0..5u32 {
+            queue.push_blocking(Box::new(value));
+        }
+        assert_eq!(queue.stats().dropped, 2);
+        assert_eq!(drained_values(&queue), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn block_returns_event_or_times_out() {
+        let config = EventQueueConfig {
+            block_timeout: Duration::from_millis(10),
+            ..EventQueueConfig::bounded(1, OverflowPolicy::Block)
+        };
+        let queue = EventQueue::new(config);
+        queue.try_push(Box::new(1u32)).unwrap();
+        let rejected = queue.try_push(Box::new(2u32)).unwrap_err();
+        assert_eq!(*rejected.downcast::<u32>().unwrap(), 2);
+
+        // 没有消费者时等待超时并丢弃
+        queue.push_blocking(Box::new(3u32));
+        assert_eq!(queue.stats(), EventQueueStats { queued: 1, peak_queued: 1, dropped: 1, blocked: 1 });
+    }
+
+    #[test]
+    fn blocked_sender_resumes_after_drain() {
+        let config = EventQueueConfig {
+            block_timeout: Duration::from_secs(10),
+            ..EventQueueConfig::bounded(1, OverflowPolicy::Block)
+        };
+        let queue = std::sync::Arc::new(EventQueue::new(config));
+        queue.try_push(Box::new(1u32)).unwrap();
+
+        let sender_queue = queue.clone();
+        let sender = std::thread::spawn(move || sender_queue.push_blocking(Box::new(2u32)));
+        while queue.stats().blocked == 0 {
+            std::thread::yield_now();
+        }
+        assert_eq!(drained_values(&queue), vec![1]);
+        sender.join().unwrap();
+
+        assert_eq!(drained_values(&queue), vec![2]);
+        assert_eq!(queue.stats().dropped, 0);
+    }
+
+    #[test]
+    fn shrinking_capacity_trims_by_policy() {
+        let queue = EventQueue::new(EventQueueConfig::default());
+        for value in 0..4u32 {
+            queue.try_push(Box::new(value)).unwrap();
+        }
+        queue.set_config(EventQueueConfig::bounded(2, OverflowPolicy::DropNewest));
+        assert_eq!(queue.stats().dropped, 2);
+        assert_eq!(drained_values(&queue), vec![0, 1]);
+
+        queue.reset_stats();
+        assert_eq!(queue.stats(), EventQueueStats::default());
+    }
+}
@@ -1,7 +1,11 @@
 //! 游戏手柄输入处理
 
 use glam::Vec2;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::input::{NullRumbleBackend, RumbleBackend, RumbleEffect, RumbleResult};
+use crate::EngineResult;
 
 /// 游戏手柄按键
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -73,6 +77,8 @@ pub struct GamepadState {
     id: u32,
     /// 手柄名称
     name: String,
+    /// 轴响应配置
+    config: GamepadConfig,
 }
 
 impl GamepadState {
@@ -84,9 +90,20 @@ impl GamepadState {
             connected: true,
             id,
             name: name.into(),
+            config: GamepadConfig::default(),
         }
     }
 
+    /// 设置轴响应配置
+    pub fn set_config(&mut self, config: GamepadConfig) {
+        self.config = config;
+    }
+
+    /// 获取轴响应配置
+    pub fn config(&self) -> &GamepadConfig {
+        &self.config
+    }
+
     /// 更新按键状态
     pub fn update(&mut self) {
         for (_, state) in self.button_states.iter_mut() {
@@ -112,9 +129,19 @@ impl GamepadState {
         self.button_states.insert(button, new_state);
     }
 
-    /// 设置轴值
+    /// 设置轴原始值，扳机轴越过阈值时同步更新对应的扳机按键
     pub fn set_axis_value(&mut self, axis: GamepadAxis, value: f32) {
         self.axis_values.insert(axis, value);
+
+        let trigger_button = match axis {
+            GamepadAxis::LeftTrigger => Some(GamepadButton::LeftTrigger),
+            GamepadAxis::RightTrigger => Some(GamepadButton::RightTrigger),
+            _ => None,
+        };
+        if let Some(button) = trigger_button {
+            let pressed = self.is_trigger_pressed(axis);
+            self.set_button_state(button, pressed);
+        }
     }
 
     /// 检查按键是否被按下
@@ -141,35 +168,55 @@ impl GamepadState {
         )
     }
 
-    /// 获取轴值
+    /// 获取轴原始值
     pub fn get_axis_value(&self, axis: GamepadAxis) -> f32 {
         self.axis_values.get(&axis).copied().unwrap_or(0.0)
     }
 
-    /// 获取左摇杆值
+    /// 获取经过死区、饱和度和响应曲线处理后的轴值
+    pub fn axis_value(&self, axis: GamepadAxis) -> f32 {
+        match axis {
+            GamepadAxis::LeftStickX => self.left_stick().x,
+            GamepadAxis::LeftStickY => self.left_stick().y,
+            GamepadAxis::RightStickX => self.right_stick().x,
+            GamepadAxis::RightStickY => self.right_stick().y,
+            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => {
+                self.config.triggers.apply(self.get_axis_value(axis))
+            }
+            GamepadAxis::Custom(_) => self.get_axis_value(axis).clamp(-1.0, 1.0),
+        }
+    }
+
+    /// 获取左摇杆值(径向死区处理后)
     pub fn left_stick(&self) -> Vec2 {
-        Vec2::new(
-            self.get_axis_value(GamepadAxis::LeftStickX),
-            self.get_axis_value(GamepadAxis::LeftStickY)
-        )
+        let raw = self.raw_stick(GamepadAxis::LeftStickX, GamepadAxis::LeftStickY);
+        self.config.left_stick.apply_radial(raw)
     }
 
-    /// 获取右摇杆值
+    /// 获取右摇杆值(径向死区处理后)
     pub fn right_stick(&self) -> Vec2 {
-        Vec2::new(
-            self.get_axis_value(GamepadAxis::RightStickX),
-            self.get_axis_value(GamepadAxis::RightStickY)
-        )
+        let raw = self.raw_stick(GamepadAxis::RightStickX, GamepadAxis::RightStickY);
+        self.config.right_stick.apply_radial(raw)
     }
 
-    /// 获取扳机值
+    /// 获取扳机值(死区处理后)
     pub fn triggers(&self) -> Vec2 {
         Vec2::new(
-            self.get_axis_value(GamepadAxis::LeftTrigger),
-            self.get_axis_value(GamepadAxis::RightTrigger)
+            self.axis_value(GamepadAxis::LeftTrigger),
+            self.axis_value(GamepadAxis::RightTrigger)
         )
     }
 
+    /// 扳机是否越过按下阈值
+    pub fn is_trigger_pressed(&self, axis: GamepadAxis) -> bool {
+        self.get_axis_value(axis) >= self.config.trigger_threshold
+    }
+
+    fn raw_stick(&self, x: GamepadAxis, y: GamepadAxis) -> Vec2 {
+        let y_sign = if self.config.invert_y { -1.0 } else { 1.0 };
+        Vec2::new(self.get_axis_value(x), self.get_axis_value(y) * y_sign)
+    }
+
     /// 是否连接
     pub fn is_connected(&self) -> bool {
         self.connected
@@ -202,6 +249,10 @@ impl GamepadState {
 pub struct GamepadManager {
     gamepads: HashMap<u32, GamepadState>,
     next_id: u32,
+    config: GamepadConfig,
+    rumble_backend: Box<dyn RumbleBackend>,
+    /// 正在震动的手柄及开始时间
+    active_rumble: HashMap<u32, (RumbleEffect, Instant)>,
 }
 
 impl GamepadManager {
@@ -210,7 +261,40 @@ impl GamepadManager {
         Self {
             gamepads: HashMap::new(),
             next_id: 0,
+            config: GamepadConfig::default(),
+            rumble_backend: Box::new(NullRumbleBackend),
+            active_rumble: HashMap::new(),
+        }
+    }
+
+    /// 使用指定的震动后端
+    pub fn with_rumble_backend(mut self, backend: impl RumbleBackend + 'static) -> Self {
+        self.set_rumble_backend(backend);
+        self
+    }
+
+    /// 替换震动后端，正在进行的震动会被丢弃
+    pub fn set_rumble_backend(&mut self, backend: impl RumbleBackend + 'static) {
+        self.rumble_backend = Box::new(backend);
+        self.active_rumble.clear();
+    }
+
+    /// 当前震动后端
+    pub fn rumble_backend(&self) -> &dyn RumbleBackend {
+        self.rumble_backend.as_ref()
+    }
+
+    /// 设置轴响应配置并应用到所有手柄
+    pub fn set_config(&mut self, config: GamepadConfig) {
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.set_config(config.clone());
         }
+        self.config = config;
+    }
+
+    /// 获取轴响应配置
+    pub fn config(&self) -> &GamepadConfig {
+        &self.config
     }
 
     /// 更新所有游戏手柄
@@ -218,6 +302,63 @@ impl GamepadManager {
         for gamepad in self.gamepads.values_mut() {
             gamepad.update();
         }
+
+        let now = Instant::now();
+        self.active_rumble
+            .retain(|_, (effect, started)| now.duration_since(*started) < effect.duration);
+    }
+
+    /// 让手柄震动，强度为0-1，持续duration后自动停止
+    ///
+    /// 手柄不支持震动时不做任何事，返回 `RumbleResult::Unsupported`；强度都为0等同于 `stop_rumble`。
+    pub fn set_rumble(&mut self, id: u32, low_frequency: f32, high_frequency: f32, duration: Duration) -> EngineResult<RumbleResult> {
+        if !self.gamepads.get(&id).map_or(false, |gamepad| gamepad.is_connected()) {
+            return Ok(RumbleResult::NotConnected);
+        }
+        if !self.rumble_backend.supports_rumble(id) {
+            return Ok(RumbleResult::Unsupported(format!(
+                "手柄 {} 不支持震动(后端: {})",
+                id,
+                self.rumble_backend.name()
+            )));
+        }
+
+        let effect = RumbleEffect::new(low_frequency, high_frequency, duration);
+        if effect.is_silent() || duration.is_zero() {
+            self.stop_rumble(id)?;
+            return Ok(RumbleResult::Started);
+        }
+
+        self.rumble_backend.play(id, effect)?;
+        self.active_rumble.insert(id, (effect, Instant::now()));
+        Ok(RumbleResult::Started)
+    }
+
+    /// 停止手柄正在进行的震动
+    pub fn stop_rumble(&mut self, id: u32) -> EngineResult<()> {
+        if self.active_rumble.remove(&id).is_some() {
+            self.rumble_backend.stop(id)?;
+        }
+        Ok(())
+    }
+
+    /// 停止所有手柄的震动
+    pub fn stop_all_rumble(&mut self) -> EngineResult<()> {
+        let ids: Vec<u32> = self.active_rumble.keys().copied().collect();
+        for id in ids {
+            self.stop_rumble(id)?;
+        }
+        Ok(())
+    }
+
+    /// 手柄正在进行的震动
+    pub fn active_rumble(&self, id: u32) -> Option<RumbleEffect> {
+        self.active_rumble.get(&id).map(|(effect, _)| *effect)
+    }
+
+    /// 手柄是否正在震动
+    pub fn is_rumbling(&self, id: u32) -> bool {
+        self.active_rumble.contains_key(&id)
     }
 
     /// 连接新的游戏手柄
@@ -225,7 +366,8 @@ impl GamepadManager {
         let id = self.next_id;
         self.next_id += 1;
         
-        let gamepad = GamepadState::new(id, name);
+        let mut gamepad = GamepadState::new(id, name);
+        gamepad.set_config(self.config.clone());
         self.gamepads.insert(id, gamepad);
         
         id
@@ -236,11 +378,20 @@ impl GamepadManager {
         if let Some(gamepad) = self.gamepads.get_mut(&id) {
             gamepad.set_connected(false);
         }
+        self.release_rumble(id);
     }
 
     /// 移除游戏手柄
     pub fn remove_gamepad(&mut self, id: u32) {
         self.gamepads.remove(&id);
+        self.release_rumble(id);
+    }
+
+    /// 手柄离开时释放震动，设备可能已不可用，失败只记录日志
+    fn release_rumble(&mut self, id: u32) {
+        if let Err(e) = self.stop_rumble(id) {
+            log::warn!("停止手柄 {} 的震动失败: {}", id, e);
+        }
     }
 
     /// 获取游戏手柄
@@ -285,13 +436,120 @@ impl Default for GamepadManager {
     }
 }
 
+/// 轴响应曲线
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    Linear,
+    Squared,
+    Cubic,
+    /// 指数曲线 t^n
+    Power(f32),
+    /// 自定义分段线性曲线，点为(输入, 输出)，按输入升序排列
+    Custom(Vec<(f32, f32)>),
+}
+
+impl Default for ResponseCurve {
+    fn default() -> Self {
+        ResponseCurve::Linear
+    }
+}
+
+impl ResponseCurve {
+    /// 对0-1范围的输入求值
+    pub fn evaluate(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            ResponseCurve::Linear => t,
+            ResponseCurve::Squared => t * t,
+            ResponseCurve::Cubic => t * t * t,
+            ResponseCurve::Power(exponent) => t.powf(exponent.max(0.0)),
+            ResponseCurve::Custom(points) => {
+                let Some(&(first_x, first_y)) = points.first() else {
+                    return t;
+                };
+                if t <= first_x {
+                    return first_y;
+                }
+                for pair in points.windows(2) {
+                    let (x0, y0) = pair[0];
+                    let (x1, y1) = pair[1];
+                    if t <= x1 {
+                        let span = x1 - x0;
+                        if span <= f32::EPSILON {
+                            return y1;
+                        }
+                        return y0 + (y1 - y0) * (t - x0) / span;
+                    }
+                }
+                points.last().map(|&(_, y)| y).unwrap_or(t)
+            }
+        }
+    }
+}
+
+/// 轴响应设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AxisResponse {
+    /// 死区，低于该值的输入视为0
+    pub deadzone: f32,
+    /// 饱和度，高于该值的输入视为1
+    pub saturation: f32,
+    /// 响应曲线
+    pub curve: ResponseCurve,
+}
+
+impl AxisResponse {
+    pub fn new(deadzone: f32, saturation: f32) -> Self {
+        Self {
+            deadzone,
+            saturation,
+            curve: ResponseCurve::Linear,
+        }
+    }
+
+    pub fn with_curve(mut self, curve: ResponseCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// 处理单轴值(保留符号)
+    pub fn apply(&self, value: f32) -> f32 {
+        value.signum() * self.map_magnitude(value.abs())
+    }
+
+    /// 径向处理摇杆向量，保持方向不变
+    pub fn apply_radial(&self, value: Vec2) -> Vec2 {
+        let magnitude = value.length();
+        if magnitude <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+        value / magnitude * self.map_magnitude(magnitude)
+    }
+
+    fn map_magnitude(&self, magnitude: f32) -> f32 {
+        if magnitude <= self.deadzone {
+            return 0.0;
+        }
+        let range = (self.saturation - self.deadzone).max(f32::EPSILON);
+        let t = ((magnitude - self.deadzone) / range).clamp(0.0, 1.0);
+        self.curve.evaluate(t)
+    }
+}
+
 /// 游戏手柄配置
-#[derive(Debug, Clone)]
+///
+/// 仍接受旧版的 `stick_deadzone`/`trigger_deadzone`，分别作为左右摇杆和扳机的死区。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "GamepadConfigFile")]
 pub struct GamepadConfig {
-    /// 摇杆死区
-    pub stick_deadzone: f32,
-    /// 扳机死区
-    pub trigger_deadzone: f32,
+    /// 左摇杆响应
+    pub left_stick: AxisResponse,
+    /// 右摇杆响应
+    pub right_stick: AxisResponse,
+    /// 扳机响应
+    pub triggers: AxisResponse,
+    /// 扳机视为按下的阈值
+    pub trigger_threshold: f32,
     /// 是否反转Y轴
     pub invert_y: bool,
     /// 振动强度
@@ -303,8 +561,10 @@ pub struct GamepadConfig {
 impl Default for GamepadConfig {
     fn default() -> Self {
         Self {
-            stick_deadzone: 0.1,
-            trigger_deadzone: 0.05,
+            left_stick: AxisResponse::new(0.1, 0.95),
+            right_stick: AxisResponse::new(0.1, 0.95),
+            triggers: AxisResponse::new(0.05, 1.0),
+            trigger_threshold: 0.5,
             invert_y: false,
             vibration_strength: 1.0,
             vibration_enabled: true,
@@ -312,6 +572,62 @@ impl Default for GamepadConfig {
     }
 }
 
+/// 配置文件中的手柄配置，兼容旧版字段
+#[derive(Deserialize)]
+#[serde(default)]
+struct GamepadConfigFile {
+    left_stick: AxisResponse,
+    right_stick: AxisResponse,
+    triggers: AxisResponse,
+    trigger_threshold: f32,
+    invert_y: bool,
+    vibration_strength: f32,
+    vibration_enabled: bool,
+    /// 旧版摇杆死区
+    stick_deadzone: Option<f32>,
+    /// 旧版扳机死区
+    trigger_deadzone: Option<f32>,
+}
+
+impl Default for GamepadConfigFile {
+    fn default() -> Self {
+        let config = GamepadConfig::default();
+        Self {
+            left_stick: config.left_stick,
+            right_stick: config.right_stick,
+            triggers: config.triggers,
+            trigger_threshold: config.trigger_threshold,
+            invert_y: config.invert_y,
+            vibration_strength: config.vibration_strength,
+            vibration_enabled: config.vibration_enabled,
+            stick_deadzone: None,
+            trigger_deadzone: None,
+        }
+    }
+}
+
+impl From<GamepadConfigFile> for GamepadConfig {
+    fn from(file: GamepadConfigFile) -> Self {
+        let mut config = Self {
+            left_stick: file.left_stick,
+            right_stick: file.right_stick,
+            triggers: file.triggers,
+            trigger_threshold: file.trigger_threshold,
+            invert_y: file.invert_y,
+            vibration_strength: file.vibration_strength,
+            vibration_enabled: file.vibration_enabled,
+        };
+        if let Some(deadzone) = file.stick_deadzone {
+            config.left_stick.deadzone = deadzone;
+            config.right_stick.deadzone = deadzone;
+        }
+        if let Some(deadzone) = file.trigger_deadzone {
+            config.triggers.deadzone = deadzone;
+        }
+        config
+    }
+}
+
 /// 应用死区处理
 pub fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
     if value.abs() < deadzone {
@@ -335,3 +651,143 @@ pub fn apply_vector_deadzone(vec: Vec2, deadzone: f32) -> Vec2 {
         vec.normalize() * adjusted_magnitude.clamp(0.0, 1.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_response_applies_deadzone_and_saturation() {
+        let response = AxisResponse::new(0.1, 0.9);
+        assert_eq!(response.apply(0.05), 0.0);
+        assert_eq!(response.apply(-0.95), -1.0);
+        assert!((response.apply(0.5) - 0.5).abs() < 1e-5);
+
+        let radial = response.apply_radial(Vec2::new(0.0, 0.05));
+        assert_eq!(radial, Vec2::ZERO);
+    }
+
+    #[test]
+    fn config_accepts_legacy_deadzones() {
+        let config: GamepadConfig = serde_json::from_str(
+            r#"{ "stick_deadzone": 0.2, "trigger_deadzone": 0.15, "invert_y": true }"#,
+        ).unwrap();
+        assert_eq!(config.left_stick.deadzone, 0.2);
+        assert_eq!(config.right_stick.deadzone, 0.2);
+        assert_eq!(config.triggers.deadzone, 0.15);
+        assert!(config.invert_y);
+        assert_eq!(config.left_stick.saturation, GamepadConfig::default().left_stick.saturation);
+    }
+
+    #[test]
+    fn config_round_trips() {
+        let mut config = GamepadConfig::default();
+        config.right_stick = AxisResponse::new(0.25, 0.8);
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("stick_deadzone"));
+        let parsed: GamepadConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, config);
+    }
+
+    #[test]
+    fn manager_applies_config_to_new_gamepads() {
+        let mut manager = GamepadManager::new();
+        let mut config = GamepadConfig::default();
+        config.trigger_threshold = 0.8;
+        manager.set_config(config.clone());
+        let id = manager.connect_gamepad("pad");
+        assert_eq!(manager.get_gamepad(id).unwrap().config(), &config);
+    }
+
+    /// 记录调用的模拟震动后端，只有列出的手柄支持震动
+    #[derive(Clone, Default)]
+    struct MockRumbleBackend {
+        supported: Vec<u32>,
+        played: std::sync::Arc<std::sync::Mutex<Vec<(u32, RumbleEffect)>>>,
+        stopped: std::sync::Arc<std::sync::Mutex<Vec<u32>>>,
+    }
+
+    impl RumbleBackend for MockRumbleBackend {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        fn supports_rumble(&self, gamepad_id: u32) -> bool {
+            self.supported.contains(&gamepad_id)
+        }
+
+        fn play(&mut self, gamepad_id: u32, effect: RumbleEffect) -> EngineResult<()> {
+            self.played.lock().unwrap().push((gamepad_id, effect));
+            Ok(())
+        }
+
+        fn stop(&mut self, gamepad_id: u32) -> EngineResult<()> {
+            self.stopped.lock().unwrap().push(gamepad_id);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn set_rumble_records_strengths_and_duration() {
+        let mut manager = GamepadManager::new();
+        let id = manager.connect_gamepad("pad");
+        let backend = MockRumbleBackend { supported: vec![id], ..Default::default() };
+        manager.set_rumble_backend(backend.clone());
+
+        let duration = Duration::from_millis(250);
+        let result = manager.set_rumble(id, 0.75, 1.5, duration).unwrap();
+        assert!(result.is_started());
+        // 强度限制在0-1
+        let expected = RumbleEffect { low_frequency: 0.75, high_frequency: 1.0, duration };
+        assert_eq!(backend.played.lock().unwrap().as_slice(), &[(id, expected)]);
+        assert_eq!(manager.active_rumble(id), Some(expected));
+
+        manager.stop_rumble(id).unwrap();
+        assert!(!manager.is_rumbling(id));
+        assert_eq!(backend.stopped.lock().unwrap().as_slice(), &[id]);
+    }
+
+    #[test]
+    fn unsupported_and_missing_gamepads_are_reported() {
+        let mut manager = GamepadManager::new();
+        let id = manager.connect_gamepad("pad");
+
+        // 默认后端不支持震动
+        match manager.set_rumble(id, 1.0, 1.0, Duration::from_secs(1)).unwrap() {
+            RumbleResult::Unsupported(reason) => assert!(reason.contains("null")),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(!manager.is_rumbling(id));
+        assert_eq!(manager.set_rumble(id + 100, 1.0, 1.0, Duration::from_secs(1)).unwrap(), RumbleResult::NotConnected);
+    }
+
+    #[test]
+    fn silent_rumble_stops_and_disconnect_releases() {
+        let mut manager = GamepadManager::new();
+        let id = manager.connect_gamepad("pad");
+        let backend = MockRumbleBackend { supported: vec![id], ..Default::default() };
+        manager.set_rumble_backend(backend.clone());
+
+        manager.set_rumble(id, 1.0, 0.0, Duration::from_secs(5)).unwrap();
+        assert!(manager.set_rumble(id, 0.0, 0.0, Duration::from_secs(5)).unwrap().is_started());
+        assert!(!manager.is_rumbling(id));
+        assert_eq!(backend.stopped.lock().unwrap().len(), 1);
+
+        manager.set_rumble(id, 0.5, 0.5, Duration::from_secs(5)).unwrap();
+        manager.disconnect_gamepad(id);
+        assert!(!manager.is_rumbling(id));
+        assert_eq!(backend.stopped.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn expired_rumble_is_cleared_on_update() {
+        let mut manager = GamepadManager::new();
+        let id = manager.connect_gamepad("pad");
+        manager.set_rumble_backend(MockRumbleBackend { supported: vec![id], ..Default::default() });
+
+        manager.set_rumble(id, 1.0, 1.0, Duration::from_millis(1)).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        manager.update();
+        assert!(!manager.is_rumbling(id));
+    }
+}
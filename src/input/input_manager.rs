@@ -1,7 +1,7 @@
 //! 输入管理器
 
-use crate::input::{KeyboardState, MouseState, InputMap};
-use winit::event::{KeyEvent, MouseButton, ElementState};
+use crate::input::{KeyboardState, MouseState, InputMap, TextInput, GamepadManager};
+use winit::event::{KeyEvent, MouseButton, ElementState, Ime};
 use winit::dpi::PhysicalPosition;
 use std::collections::HashMap;
 
@@ -9,6 +9,7 @@ use std::collections::HashMap;
 pub struct InputManager {
     keyboard: KeyboardState,
     mouse: MouseState,
+    gamepads: GamepadManager,
     input_maps: HashMap<String, InputMap>,
     current_input_map: Option<String>,
 }
@@ -19,15 +20,19 @@ impl InputManager {
         Self {
             keyboard: KeyboardState::new(),
             mouse: MouseState::new(),
+            gamepads: GamepadManager::new(),
             input_maps: HashMap::new(),
             current_input_map: None,
         }
     }
 
-    /// 更新输入状态 (每帧调用)
+    /// 更新输入状态 (每帧开始、游戏逻辑读取输入之前调用)
+    ///
+    /// 对比设备本帧与上一帧的状态快照，得到刚按下/刚释放等边沿状态，整帧内保持不变。
     pub fn update(&mut self) {
         self.keyboard.update();
         self.mouse.update();
+        self.gamepads.update();
     }
 
     /// 处理键盘输入事件
@@ -35,14 +40,29 @@ impl InputManager {
         self.keyboard.handle_key_event(event);
     }
 
+    /// 注入按键(回放、自动化测试)，在下次update时生效
+    pub fn simulate_key(&mut self, key: winit::keyboard::KeyCode, pressed: bool) {
+        self.keyboard.simulate_key(key, pressed);
+    }
+
+    /// 处理输入法事件
+    pub fn handle_ime(&mut self, ime: &Ime) {
+        self.keyboard.handle_ime(ime);
+    }
+
+    /// 本帧的文本输入，供文本框使用(与按键绑定分开)
+    pub fn text_input(&self) -> &[TextInput] {
+        self.keyboard.text_input()
+    }
+
     /// 处理鼠标按键事件
     pub fn handle_mouse_input(&mut self, button: MouseButton, state: ElementState) {
         self.mouse.handle_button_input(button, state);
     }
 
-    /// 处理鼠标移动事件
-    pub fn handle_mouse_move(&mut self, position: PhysicalPosition<f64>) {
-        self.mouse.handle_mouse_move(position);
+    /// 处理鼠标移动事件，返回这次移动的增量
+    pub fn handle_mouse_move(&mut self, position: PhysicalPosition<f64>) -> glam::Vec2 {
+        self.mouse.handle_mouse_move(position)
     }
 
     /// 获取键盘状态
@@ -55,6 +75,16 @@ impl InputManager {
         &self.mouse
     }
 
+    /// 获取游戏手柄管理器
+    pub fn gamepads(&self) -> &GamepadManager {
+        &self.gamepads
+    }
+
+    /// 获取可变游戏手柄管理器
+    pub fn gamepads_mut(&mut self) -> &mut GamepadManager {
+        &mut self.gamepads
+    }
+
     /// 添加输入映射
     pub fn add_input_map(&mut self, name: impl Into<String>, input_map: InputMap) {
         self.input_maps.insert(name.into(), input_map);
@@ -189,3 +219,25 @@ impl Default for InputManager {
         manager
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_snapshots_mouse_state_once_per_frame() {
+        let mut input = InputManager::new();
+        input.handle_mouse_input(MouseButton::Left, ElementState::Pressed);
+        assert_eq!(input.handle_mouse_move(PhysicalPosition::new(4.0, 3.0)), glam::Vec2::new(4.0, 3.0));
+        // update之前读取的仍是上一帧的状态
+        assert!(!input.mouse().is_button_pressed(MouseButton::Left));
+
+        input.update();
+        assert!(input.mouse().is_button_just_pressed(MouseButton::Left));
+        assert_eq!(input.mouse().delta(), glam::Vec2::new(4.0, 3.0));
+
+        input.update();
+        assert!(!input.mouse().is_button_just_pressed(MouseButton::Left));
+        assert!(input.mouse().is_button_pressed(MouseButton::Left));
+    }
+}
@@ -1,12 +1,51 @@
 //! 键盘输入处理
 
-use winit::event::{KeyEvent, ElementState};
+use winit::event::{KeyEvent, ElementState, Ime};
 use winit::keyboard::{KeyCode, PhysicalKey};
 use std::collections::HashSet;
 
+/// 文本输入
+///
+/// 与物理按键分开传递：按键绑定使用与布局无关的 `KeyCode`，
+/// 文本框使用这里经过键盘布局和输入法处理后的字符。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextInput {
+    /// 已提交的文本(按键产生的字符或输入法上屏的文本)
+    Text(String),
+    /// 输入法组字中的文本，cursor为组字文本内的字节范围
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+}
+
+/// 按键事件产生的文本，控制字符(退格、回车等)不算文本输入
+pub fn key_event_text(event: &KeyEvent) -> Option<String> {
+    if event.state != ElementState::Pressed {
+        return None;
+    }
+    let text = event.text.as_ref()?;
+    if text.is_empty() || text.chars().any(char::is_control) {
+        return None;
+    }
+    Some(text.to_string())
+}
+
 /// 键盘状态管理器
+///
+/// 事件到达时只更新设备实时状态；`update` 在每帧开始时对比本帧与上一帧的快照得到
+/// 刚按下/刚释放的键，整帧内查询结果保持一致。两次 `update` 之间按下又释放的键
+/// 在下一帧同时算作刚按下和刚释放，不会丢失。
 pub struct KeyboardState {
-    /// 当前按下的键
+    /// 设备实时按下的键，由事件更新
+    live_keys: HashSet<KeyCode>,
+    /// 自上次update以来按下过的键
+    pressed_since_update: HashSet<KeyCode>,
+    /// 自上次update以来释放过的键
+    released_since_update: HashSet<KeyCode>,
+    /// 自上次update以来的文本输入
+    pending_text: Vec<TextInput>,
+    /// 本帧按下的键
     current_keys: HashSet<KeyCode>,
     /// 上一帧按下的键
     previous_keys: HashSet<KeyCode>,
@@ -14,44 +53,102 @@ pub struct KeyboardState {
     just_pressed: HashSet<KeyCode>,
     /// 刚释放的键
     just_released: HashSet<KeyCode>,
+    /// 本帧的文本输入
+    text_input: Vec<TextInput>,
 }
 
 impl KeyboardState {
     /// 创建新的键盘状态
     pub fn new() -> Self {
         Self {
+            live_keys: HashSet::new(),
+            pressed_since_update: HashSet::new(),
+            released_since_update: HashSet::new(),
+            pending_text: Vec::new(),
             current_keys: HashSet::new(),
             previous_keys: HashSet::new(),
             just_pressed: HashSet::new(),
             just_released: HashSet::new(),
+            text_input: Vec::new(),
         }
     }
 
-    /// 处理键盘事件
+    /// 处理键盘事件，物理按键进入按键状态，布局相关的字符进入文本输入
     pub fn handle_key_event(&mut self, event: KeyEvent) {
+        if let Some(text) = key_event_text(&event) {
+            self.pending_text.push(TextInput::Text(text));
+        }
+
         if let PhysicalKey::Code(key_code) = event.physical_key {
             match event.state {
-                ElementState::Pressed => {
-                    if !self.current_keys.contains(&key_code) {
-                        self.current_keys.insert(key_code);
-                        self.just_pressed.insert(key_code);
-                    }
-                }
-                ElementState::Released => {
-                    if self.current_keys.contains(&key_code) {
-                        self.current_keys.remove(&key_code);
-                        self.just_released.insert(key_code);
-                    }
-                }
+                ElementState::Pressed => self.press(key_code),
+                ElementState::Released => self.release(key_code),
             }
         }
     }
 
-    /// 更新状态 (每帧调用)
+    /// 注入按键(回放、自动化测试)，与真实按键事件一样在下次update时生效
+    pub fn simulate_key(&mut self, key: KeyCode, pressed: bool) {
+        if pressed {
+            self.press(key);
+        } else {
+            self.release(key);
+        }
+    }
+
+    fn press(&mut self, key: KeyCode) {
+        // 按住时的系统重复按键不算新的按下
+        if self.live_keys.insert(key) {
+            self.pressed_since_update.insert(key);
+        }
+    }
+
+    fn release(&mut self, key: KeyCode) {
+        if self.live_keys.remove(&key) {
+            self.released_since_update.insert(key);
+        }
+    }
+
+    /// 处理输入法事件
+    pub fn handle_ime(&mut self, ime: &Ime) {
+        match ime {
+            Ime::Preedit(text, cursor) => self.pending_text.push(TextInput::Preedit {
+                text: text.clone(),
+                cursor: *cursor,
+            }),
+            Ime::Commit(text) if !text.is_empty() => self.pending_text.push(TextInput::Text(text.clone())),
+            _ => {}
+        }
+    }
+
+    /// 更新状态 (每帧开始、读取输入之前调用)
+    ///
+    /// 拍下设备当前状态作为本帧快照，与上一帧快照的差异即为刚按下/刚释放的键。
     pub fn update(&mut self) {
-        self.previous_keys.clone_from(&self.current_keys);
-        self.just_pressed.clear();
-        self.just_released.clear();
+        self.previous_keys = std::mem::replace(&mut self.current_keys, self.live_keys.clone());
+
+        self.just_pressed = self.current_keys.difference(&self.previous_keys).copied().collect();
+        self.just_pressed.extend(self.pressed_since_update.drain());
+        self.just_released = self.previous_keys.difference(&self.current_keys).copied().collect();
+        self.just_released.extend(self.released_since_update.drain());
+
+        self.text_input = std::mem::take(&mut self.pending_text);
+    }
+
+    /// 本帧的文本输入(按到达顺序)
+    pub fn text_input(&self) -> &[TextInput] {
+        &self.text_input
+    }
+
+    /// 本帧已提交的文本
+    pub fn typed_text(&self) -> String {
+        self.text_input
+            .iter()
+            .filter_map(|input| match input {
+                TextInput::Text(text) => Some(text.as_str()),
+                TextInput::Preedit { .. } => None,
+            })
+            .collect()
     }
 
     /// 检查键是否当前被按下
@@ -116,26 +213,30 @@ impl KeyboardState {
 
     /// 重置所有状态
     pub fn reset(&mut self) {
+        self.live_keys.clear();
+        self.pressed_since_update.clear();
+        self.released_since_update.clear();
+        self.pending_text.clear();
         self.current_keys.clear();
         self.previous_keys.clear();
         self.just_pressed.clear();
         self.just_released.clear();
+        self.text_input.clear();
     }
 
-    /// 模拟按键按下 (用于测试)
+    /// 模拟文本输入 (用于测试)，下一次update后可见
+    pub fn simulate_text_input(&mut self, text: impl Into<String>) {
+        self.pending_text.push(TextInput::Text(text.into()));
+    }
+
+    /// 模拟按键按下 (用于测试)，下一次update后可见
     pub fn simulate_key_press(&mut self, key: KeyCode) {
-        if !self.current_keys.contains(&key) {
-            self.current_keys.insert(key);
-            self.just_pressed.insert(key);
-        }
+        self.press(key);
     }
 
-    /// 模拟按键释放 (用于测试)
+    /// 模拟按键释放 (用于测试)，下一次update后可见
     pub fn simulate_key_release(&mut self, key: KeyCode) {
-        if self.current_keys.contains(&key) {
-            self.current_keys.remove(&key);
-            self.just_released.insert(key);
-        }
+        self.release(key);
     }
 }
 
@@ -273,3 +374,116 @@ impl CommonKeyCombinations {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_presses_and_text_use_separate_channels() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.simulate_key_press(KeyCode::KeyQ);
+        // 例如AZERTY布局下同一物理键输入的是 'a'
+        keyboard.simulate_text_input("a");
+        keyboard.update();
+
+        assert!(keyboard.is_key_down(KeyCode::KeyQ));
+        assert!(keyboard.is_key_up(KeyCode::KeyA));
+        assert_eq!(keyboard.text_input(), &[TextInput::Text("a".to_string())]);
+        assert_eq!(keyboard.typed_text(), "a");
+    }
+
+    #[test]
+    fn ime_preedit_is_not_committed_text() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.handle_ime(&Ime::Preedit("ni".to_string(), Some((2, 2))));
+        keyboard.handle_ime(&Ime::Commit("你".to_string()));
+        keyboard.handle_ime(&Ime::Commit(String::new()));
+        keyboard.update();
+
+        assert_eq!(keyboard.text_input().len(), 2);
+        assert_eq!(keyboard.text_input()[0], TextInput::Preedit { text: "ni".to_string(), cursor: Some((2, 2)) });
+        assert_eq!(keyboard.typed_text(), "你");
+        // 组字不影响按键状态
+        assert!(!keyboard.any_key_down());
+    }
+
+    #[test]
+    fn text_input_lasts_one_frame() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.simulate_text_input("x");
+        keyboard.simulate_key_press(KeyCode::Space);
+        keyboard.update();
+        keyboard.update();
+
+        assert!(keyboard.text_input().is_empty());
+        assert!(keyboard.is_key_down(KeyCode::Space));
+    }
+
+    #[test]
+    fn just_pressed_only_on_transition_frame() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.update();
+        assert!(keyboard.is_key_up(KeyCode::KeyW));
+
+        keyboard.simulate_key_press(KeyCode::KeyW);
+        keyboard.update();
+        assert!(keyboard.is_key_just_pressed(KeyCode::KeyW));
+        assert!(keyboard.is_key_down(KeyCode::KeyW));
+
+        // 按住时的系统重复按键不会再次触发
+        keyboard.simulate_key_press(KeyCode::KeyW);
+        keyboard.update();
+        assert!(!keyboard.is_key_just_pressed(KeyCode::KeyW));
+        assert!(keyboard.is_key_down(KeyCode::KeyW));
+        assert!(keyboard.was_key_down(KeyCode::KeyW));
+
+        keyboard.simulate_key_release(KeyCode::KeyW);
+        keyboard.update();
+        assert!(keyboard.is_key_just_released(KeyCode::KeyW));
+        assert!(keyboard.is_key_up(KeyCode::KeyW));
+
+        keyboard.update();
+        assert!(!keyboard.is_key_just_released(KeyCode::KeyW));
+    }
+
+    #[test]
+    fn tap_within_one_frame_reports_both_edges() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.simulate_key_press(KeyCode::Space);
+        keyboard.simulate_key_release(KeyCode::Space);
+        keyboard.update();
+
+        assert!(keyboard.is_key_just_pressed(KeyCode::Space));
+        assert!(keyboard.is_key_just_released(KeyCode::Space));
+        assert!(!keyboard.is_key_down(KeyCode::Space));
+    }
+
+    #[test]
+    fn edges_are_stable_until_next_update() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.simulate_key_press(KeyCode::KeyE);
+        keyboard.update();
+        // 帧内到达的释放事件在下次update前不影响本帧状态
+        keyboard.simulate_key_release(KeyCode::KeyE);
+        assert!(keyboard.is_key_just_pressed(KeyCode::KeyE));
+        assert!(keyboard.is_key_down(KeyCode::KeyE));
+        assert_eq!(keyboard.just_pressed_keys(), vec![KeyCode::KeyE]);
+    }
+
+    #[test]
+    fn simulated_tap_within_one_frame_is_not_lost() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.simulate_key(KeyCode::Enter, true);
+        keyboard.simulate_key(KeyCode::Enter, false);
+        assert!(!keyboard.is_key_just_pressed(KeyCode::Enter));
+
+        keyboard.update();
+        assert!(keyboard.is_key_just_pressed(KeyCode::Enter));
+        assert!(keyboard.is_key_just_released(KeyCode::Enter));
+        assert!(keyboard.is_key_up(KeyCode::Enter));
+
+        keyboard.update();
+        assert!(!keyboard.is_key_just_pressed(KeyCode::Enter));
+    }
+}
@@ -5,12 +5,14 @@ pub mod keyboard;
 pub mod mouse;
 pub mod gamepad;
 pub mod input_map;
+pub mod rumble;
 
 pub use input_manager::*;
 pub use keyboard::*;
 pub use mouse::*;
 pub use gamepad::*;
 pub use input_map::*;
+pub use rumble::*;
 
 // 重新导出winit的输入相关类型
 pub use winit::{
@@ -3,7 +3,7 @@
 use winit::event::{MouseButton, ElementState};
 use winit::dpi::PhysicalPosition;
 use glam::Vec2;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// 鼠标按键状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,20 +15,33 @@ pub enum MouseButtonState {
 }
 
 /// 鼠标状态管理器
+///
+/// 与键盘相同，事件只更新设备实时状态，`update` 在每帧开始时由前后两帧的快照差异得到按键边沿，
+/// 并把两次update之间累计的移动和滚轮增量作为本帧增量。
 #[derive(Debug)]
 pub struct MouseState {
-    /// 按键状态
+    /// 本帧按键状态
     button_states: HashMap<MouseButton, MouseButtonState>,
     /// 按键持续时间
     button_durations: HashMap<MouseButton, f32>,
+    /// 设备实时按下的按键，由事件更新
+    live_buttons: HashSet<MouseButton>,
+    /// 自上次update以来按下过的按键
+    pressed_since_update: HashSet<MouseButton>,
+    /// 自上次update以来释放过的按键
+    released_since_update: HashSet<MouseButton>,
     /// 当前鼠标位置
     position: Vec2,
     /// 上一帧鼠标位置
     last_position: Vec2,
-    /// 鼠标移动增量
+    /// 本帧鼠标移动增量
     delta: Vec2,
-    /// 滚轮增量
+    /// 本帧滚轮增量
     scroll_delta: Vec2,
+    /// 自上次update以来累计的移动增量
+    pending_delta: Vec2,
+    /// 自上次update以来累计的滚轮增量
+    pending_scroll: Vec2,
     /// 是否在窗口内
     is_in_window: bool,
 }
@@ -39,67 +52,87 @@ impl MouseState {
         Self {
             button_states: HashMap::new(),
             button_durations: HashMap::new(),
+            live_buttons: HashSet::new(),
+            pressed_since_update: HashSet::new(),
+            released_since_update: HashSet::new(),
             position: Vec2::ZERO,
             last_position: Vec2::ZERO,
             delta: Vec2::ZERO,
             scroll_delta: Vec2::ZERO,
+            pending_delta: Vec2::ZERO,
+            pending_scroll: Vec2::ZERO,
             is_in_window: true,
         }
     }
 
-    /// 更新鼠标状态 (每帧调用)
+    /// 更新鼠标状态 (每帧开始、读取输入之前调用)
     pub fn update(&mut self) {
-        // 更新按键状态
-        for (_, state) in self.button_states.iter_mut() {
-            match *state {
-                MouseButtonState::JustPressed => *state = MouseButtonState::Pressed,
-                MouseButtonState::JustReleased => *state = MouseButtonState::Released,
-                _ => {}
+        let buttons: HashSet<MouseButton> = self
+            .button_states
+            .keys()
+            .chain(self.live_buttons.iter())
+            .chain(self.pressed_since_update.iter())
+            .copied()
+            .collect();
+
+        for button in buttons {
+            let was_pressed = self.is_button_pressed(button);
+            let is_pressed = self.live_buttons.contains(&button);
+            let pressed = self.pressed_since_update.contains(&button) || (is_pressed && !was_pressed);
+            let released = self.released_since_update.contains(&button) || (was_pressed && !is_pressed);
+
+            // 一帧内按下又释放时先报告按下，下一帧再报告释放
+            let state = if pressed {
+                MouseButtonState::JustPressed
+            } else if released {
+                MouseButtonState::JustReleased
+            } else if is_pressed {
+                MouseButtonState::Pressed
+            } else {
+                MouseButtonState::Released
+            };
+
+            if state == MouseButtonState::JustPressed {
+                self.button_durations.insert(button, 0.0);
             }
+            self.button_states.insert(button, state);
         }
+        self.pressed_since_update.clear();
+        self.released_since_update.clear();
 
-        // 重置增量
-        self.delta = Vec2::ZERO;
-        self.scroll_delta = Vec2::ZERO;
+        self.delta = std::mem::take(&mut self.pending_delta);
+        self.scroll_delta = std::mem::take(&mut self.pending_scroll);
     }
 
     /// 处理鼠标按键输入
     pub fn handle_button_input(&mut self, button: MouseButton, state: ElementState) {
-        let new_state = match state {
+        match state {
             ElementState::Pressed => {
-                let current_state = self.button_states.get(&button).unwrap_or(&MouseButtonState::Released);
-                match current_state {
-                    MouseButtonState::Released | MouseButtonState::JustReleased => MouseButtonState::JustPressed,
-                    _ => MouseButtonState::Pressed,
+                if self.live_buttons.insert(button) {
+                    self.pressed_since_update.insert(button);
                 }
             }
             ElementState::Released => {
-                let current_state = self.button_states.get(&button).unwrap_or(&MouseButtonState::Released);
-                match current_state {
-                    MouseButtonState::Pressed | MouseButtonState::JustPressed => MouseButtonState::JustReleased,
-                    _ => MouseButtonState::Released,
+                if self.live_buttons.remove(&button) {
+                    self.released_since_update.insert(button);
                 }
             }
-        };
-
-        self.button_states.insert(button, new_state);
-        
-        // 重置持续时间如果是新按下
-        if new_state == MouseButtonState::JustPressed {
-            self.button_durations.insert(button, 0.0);
         }
     }
 
-    /// 处理鼠标移动
-    pub fn handle_mouse_move(&mut self, position: PhysicalPosition<f64>) {
+    /// 处理鼠标移动，返回这次移动的增量
+    pub fn handle_mouse_move(&mut self, position: PhysicalPosition<f64>) -> Vec2 {
+        let position = Vec2::new(position.x as f32, position.y as f32);
+        let delta = position - self.position;
         self.last_position = self.position;
-        self.position = Vec2::new(position.x as f32, position.y as f32);
-        self.delta = self.position - self.last_position;
+        self.position = position;
+        self.pending_delta += delta;
+        delta
     }
 
     /// 处理滚轮滚动
     pub fn handle_scroll(&mut self, delta: Vec2) {
-        self.scroll_delta += delta;
+        self.pending_scroll += delta;
     }
 
     /// 设置鼠标是否在窗口内
@@ -165,10 +198,15 @@ impl MouseState {
     pub fn reset(&mut self) {
         self.button_states.clear();
         self.button_durations.clear();
+        self.live_buttons.clear();
+        self.pressed_since_update.clear();
+        self.released_since_update.clear();
         self.position = Vec2::ZERO;
         self.last_position = Vec2::ZERO;
         self.delta = Vec2::ZERO;
         self.scroll_delta = Vec2::ZERO;
+        self.pending_delta = Vec2::ZERO;
+        self.pending_scroll = Vec2::ZERO;
         self.is_in_window = true;
     }
 
@@ -310,3 +348,62 @@ impl Default for MouseController {
         Self::new(MouseConfig::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn button_edges_follow_frame_snapshots() {
+        let mut mouse = MouseState::new();
+        mouse.handle_button_input(MouseButton::Left, ElementState::Pressed);
+        mouse.update();
+        assert!(mouse.is_button_just_pressed(MouseButton::Left));
+        assert!(mouse.is_button_pressed(MouseButton::Left));
+
+        mouse.update();
+        assert!(!mouse.is_button_just_pressed(MouseButton::Left));
+        assert!(mouse.is_button_pressed(MouseButton::Left));
+
+        mouse.handle_button_input(MouseButton::Left, ElementState::Released);
+        mouse.update();
+        assert!(mouse.is_button_just_released(MouseButton::Left));
+        assert!(!mouse.is_button_pressed(MouseButton::Left));
+
+        mouse.update();
+        assert!(!mouse.is_button_just_released(MouseButton::Left));
+    }
+
+    #[test]
+    fn click_within_one_frame_spans_two_frames() {
+        let mut mouse = MouseState::new();
+        mouse.handle_button_input(MouseButton::Right, ElementState::Pressed);
+        mouse.handle_button_input(MouseButton::Right, ElementState::Released);
+
+        mouse.update();
+        assert!(mouse.is_button_just_pressed(MouseButton::Right));
+        mouse.update();
+        assert!(mouse.is_button_just_released(MouseButton::Right));
+        mouse.update();
+        assert!(!mouse.is_button_pressed(MouseButton::Right));
+    }
+
+    #[test]
+    fn motion_and_scroll_accumulate_until_update() {
+        let mut mouse = MouseState::new();
+        mouse.handle_mouse_move(PhysicalPosition::new(10.0, 0.0));
+        mouse.handle_mouse_move(PhysicalPosition::new(15.0, 5.0));
+        mouse.handle_scroll(Vec2::new(0.0, 1.0));
+        mouse.handle_scroll(Vec2::new(0.0, 2.0));
+        assert_eq!(mouse.delta(), Vec2::ZERO);
+
+        mouse.update();
+        assert_eq!(mouse.delta(), Vec2::new(15.0, 5.0));
+        assert_eq!(mouse.scroll_delta(), Vec2::new(0.0, 3.0));
+        assert_eq!(mouse.position(), Vec2::new(15.0, 5.0));
+
+        mouse.update();
+        assert_eq!(mouse.delta(), Vec2::ZERO);
+        assert_eq!(mouse.scroll_delta(), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,193 @@
+//! 手柄震动(力反馈)
+//!
+//! `GamepadManager` 通过 `RumbleBackend` 驱动震动马达。默认后端不支持震动，所有请求返回
+//! `RumbleResult::Unsupported`；启用 `gamepad` 特性后可使用基于gilrs力反馈的 `GilrsRumbleBackend`。
+
+use crate::EngineResult;
+use std::fmt;
+use std::time::Duration;
+
+/// 震动参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RumbleEffect {
+    /// 低频(大)马达强度(0-1)
+    pub low_frequency: f32,
+    /// 高频(小)马达强度(0-1)
+    pub high_frequency: f32,
+    /// 持续时间
+    pub duration: Duration,
+}
+
+impl RumbleEffect {
+    pub fn new(low_frequency: f32, high_frequency: f32, duration: Duration) -> Self {
+        Self {
+            low_frequency: low_frequency.clamp(0.0, 1.0),
+            high_frequency: high_frequency.clamp(0.0, 1.0),
+            duration,
+        }
+    }
+
+    /// 两个马达强度都为0
+    pub fn is_silent(&self) -> bool {
+        self.low_frequency <= 0.0 && self.high_frequency <= 0.0
+    }
+}
+
+/// 震动请求的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RumbleResult {
+    /// 已开始震动
+    Started,
+    /// 设备不支持震动，请求被忽略
+    Unsupported(String),
+    /// 手柄不存在或已断开
+    NotConnected,
+}
+
+impl RumbleResult {
+    pub fn is_started(&self) -> bool {
+        matches!(self, RumbleResult::Started)
+    }
+}
+
+/// 震动后端
+pub trait RumbleBackend {
+    /// 后端名称
+    fn name(&self) -> &str;
+
+    /// 设备是否支持震动
+    fn supports_rumble(&self, gamepad_id: u32) -> bool;
+
+    /// 开始震动，替换该手柄正在进行的震动
+    fn play(&mut self, gamepad_id: u32, effect: RumbleEffect) -> EngineResult<()>;
+
+    /// 停止该手柄的震动
+    fn stop(&mut self, gamepad_id: u32) -> EngineResult<()>;
+}
+
+impl fmt::Debug for dyn RumbleBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RumbleBackend").field("name", &self.name()).finish()
+    }
+}
+
+/// 不支持震动的后端(默认)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullRumbleBackend;
+
+impl RumbleBackend for NullRumbleBackend {
+    fn name(&self) -> &str {
+        "null"
+    }
+
+    fn supports_rumble(&self, _gamepad_id: u32) -> bool {
+        false
+    }
+
+    fn play(&mut self, _gamepad_id: u32, _effect: RumbleEffect) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn stop(&mut self, _gamepad_id: u32) -> EngineResult<()> {
+        Ok(())
+    }
+}
+
+/// 基于gilrs力反馈的后端，引擎手柄ID需先通过 `bind` 关联到gilrs手柄
+#[cfg(feature = "gamepad")]
+pub struct GilrsRumbleBackend {
+    gilrs: gilrs::Gilrs,
+    bindings: std::collections::HashMap<u32, gilrs::GamepadId>,
+    /// 正在播放的效果，丢弃即停止
+    effects: std::collections::HashMap<u32, gilrs::ff::Effect>,
+}
+
+#[cfg(feature = "gamepad")]
+impl GilrsRumbleBackend {
+    pub fn new() -> EngineResult<Self> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| anyhow::anyhow!("初始化gilrs失败: {}", e))?;
+        Ok(Self::from_gilrs(gilrs))
+    }
+
+    pub fn from_gilrs(gilrs: gilrs::Gilrs) -> Self {
+        Self {
+            gilrs,
+            bindings: std::collections::HashMap::new(),
+            effects: std::collections::HashMap::new(),
+        }
+    }
+
+    /// 关联引擎手柄ID与gilrs手柄
+    pub fn bind(&mut self, gamepad_id: u32, gilrs_id: gilrs::GamepadId) {
+        self.bindings.insert(gamepad_id, gilrs_id);
+    }
+
+    /// 解除关联并停止震动
+    pub fn unbind(&mut self, gamepad_id: u32) {
+        self.bindings.remove(&gamepad_id);
+        self.effects.remove(&gamepad_id);
+    }
+
+    pub fn gilrs(&self) -> &gilrs::Gilrs {
+        &self.gilrs
+    }
+
+    pub fn gilrs_mut(&mut self) -> &mut gilrs::Gilrs {
+        &mut self.gilrs
+    }
+
+    fn magnitude(strength: f32) -> u16 {
+        (strength.clamp(0.0, 1.0) * u16::MAX as f32) as u16
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl RumbleBackend for GilrsRumbleBackend {
+    fn name(&self) -> &str {
+        "gilrs"
+    }
+
+    fn supports_rumble(&self, gamepad_id: u32) -> bool {
+        self.bindings
+            .get(&gamepad_id)
+            .and_then(|&id| self.gilrs.connected_gamepad(id))
+            .map_or(false, |gamepad| gamepad.is_ff_supported())
+    }
+
+    fn play(&mut self, gamepad_id: u32, effect: RumbleEffect) -> EngineResult<()> {
+        use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Repeat, Replay, Ticks};
+
+        let Some(&gilrs_id) = self.bindings.get(&gamepad_id) else {
+            return Ok(());
+        };
+        let ticks = Ticks::from_ms(effect.duration.as_millis().min(u32::MAX as u128) as u32);
+        let scheduling = Replay { play_for: ticks, ..Default::default() };
+
+        let ff_effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Strong { magnitude: Self::magnitude(effect.low_frequency) },
+                scheduling,
+                ..Default::default()
+            })
+            .add_effect(BaseEffect {
+                kind: BaseEffectType::Weak { magnitude: Self::magnitude(effect.high_frequency) },
+                scheduling,
+                ..Default::default()
+            })
+            .repeat(Repeat::For(ticks))
+            .gamepads(&[gilrs_id])
+            .finish(&mut self.gilrs)
+            .map_err(|e| anyhow::anyhow!("创建手柄震动效果失败: {}", e))?;
+        ff_effect.play().map_err(|e| anyhow::anyhow!("播放手柄震动失败: {}", e))?;
+
+        self.effects.insert(gamepad_id, ff_effect);
+        Ok(())
+    }
+
+    fn stop(&mut self, gamepad_id: u32) -> EngineResult<()> {
+        if let Some(effect) = self.effects.remove(&gamepad_id) {
+            effect.stop().map_err(|e| anyhow::anyhow!("停止手柄震动失败: {}", e))?;
+        }
+        Ok(())
+    }
+}
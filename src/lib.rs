@@ -52,6 +52,8 @@ pub struct EngineConfig {
     pub window: WindowConfig,
     pub render: RenderConfig,
     pub assets: AssetConfig,
+    #[serde(default)]
+    pub input: InputConfig,
 }
 
 impl Default for EngineConfig {
@@ -60,6 +62,7 @@ impl Default for EngineConfig {
             window: WindowConfig::default(),
             render: RenderConfig::default(),
             assets: AssetConfig::default(),
+            input: InputConfig::default(),
         }
     }
 }
@@ -88,16 +91,26 @@ impl Default for WindowConfig {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RenderConfig {
     pub backend: String,
-    pub msaa_samples: u32,
+    /// 旧配置中的 `msaa_samples = N` 仍可读取，等同于 `Msaa(N)`(N<=1时为None)
+    #[serde(default, alias = "msaa_samples", deserialize_with = "render::AntiAliasing::deserialize_compat")]
+    pub anti_aliasing: render::AntiAliasing,
     pub max_texture_size: u32,
+    /// 在途帧数(2或3)，每帧资源按此数量轮换
+    #[serde(default = "default_frames_in_flight")]
+    pub frames_in_flight: u32,
+}
+
+fn default_frames_in_flight() -> u32 {
+    2
 }
 
 impl Default for RenderConfig {
     fn default() -> Self {
         Self {
             backend: "auto".to_string(),
-            msaa_samples: 4,
+            anti_aliasing: render::AntiAliasing::Msaa(4),
             max_texture_size: 8192,
+            frames_in_flight: default_frames_in_flight(),
         }
     }
 }
@@ -106,6 +119,9 @@ impl Default for RenderConfig {
 pub struct AssetConfig {
     pub asset_folder: String,
     pub cache_size: usize,
+    /// 额外的资源搜索路径(目录, 优先级)，`asset_folder` 的优先级为0
+    #[serde(default)]
+    pub search_paths: Vec<(String, i32)>,
 }
 
 impl Default for AssetConfig {
@@ -113,6 +129,13 @@ impl Default for AssetConfig {
         Self {
             asset_folder: "assets".to_string(),
             cache_size: 1024 * 1024 * 512, // 512MB
+            search_paths: Vec::new(),
         }
     }
 }
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct InputConfig {
+    #[serde(default)]
+    pub gamepad: input::GamepadConfig,
+}
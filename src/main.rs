@@ -16,9 +16,10 @@ use sanji_engine::*;
 use sanji_engine::core::*;
 // use sanji_engine::render::*; // Commented to avoid conflicts
 use sanji_engine::ecs::*;
-use sanji_engine::math::Vec3;
+use sanji_engine::math::{Coordinate, Vec3};
 use sanji_engine::scene::*;
 use sanji_engine::assets::*;
+use sanji_engine::render::{HeadlessThumbnailRenderer, ThumbnailSource};
 
 fn main() -> eframe::Result<()> {
     env_logger::init();
@@ -48,6 +49,8 @@ pub struct SanjiEngineEditor {
     // Editor state
     selected_entity: Option<specs::Entity>,
     selected_asset: Option<String>,
+    /// World state captured when Play was pressed, restored on Stop
+    play_snapshot: Option<WorldSnapshot>,
     
     // UI state
     show_hierarchy: bool,
@@ -62,6 +65,8 @@ pub struct SanjiEngineEditor {
     
     // Tools state
     current_tool: EditorTool,
+    snap_settings: GizmoSnapSettings,
+    gizmo_drag: Option<GizmoDrag>,
     
     // Scene view
     scene_camera_pos: [f32; 3],
@@ -75,12 +80,33 @@ pub struct SanjiEngineEditor {
     // Asset import system
     show_asset_import_dialog: bool,
     current_import: Option<AssetImportInfo>,
+    import_pipeline: AssetImportPipeline,
     
     // 3D Rendering system
     render_system: Option<Arc<Mutex<RenderSystem>>>,
     scene_3d_camera: Scene3DCamera,
+    
+    // Project browser thumbnails
+    thumbnail_renderer: Option<HeadlessThumbnailRenderer>,
+    thumbnail_renderer_failed: bool,
+    thumbnail_textures: std::collections::HashMap<String, egui::TextureHandle>,
+    
+    // Scene view rendered offscreen by the engine
+    scene_view_texture: Option<egui::TextureHandle>,
+    scene_view_render_failed: bool,
+
+    // Immediate-mode lines and labels drawn over the scene view each frame
+    debug_draw: sanji_engine::render::DebugDraw,
+
+    // Geometry used for viewport click selection, keyed by mesh name
+    picking_meshes: std::collections::HashMap<String, Arc<sanji_engine::render::Mesh>>,
 }
 
+/// Pixel size of rendered asset thumbnails
+const THUMBNAIL_SIZE: u32 = 64;
+/// Displayed size of thumbnails in the project browser
+const THUMBNAIL_DISPLAY_SIZE: f32 = 20.0;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum EditorTool {
     Select,
@@ -89,30 +115,108 @@ enum EditorTool {
     Scale,
 }
 
+/// Increments the transform gizmos snap to while Ctrl (Cmd on macOS) is held
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GizmoSnapSettings {
+    /// Grid size in world units
+    position_step: f32,
+    /// Angle step in degrees
+    rotation_step: f32,
+    scale_step: f32,
+}
+
+impl Default for GizmoSnapSettings {
+    fn default() -> Self {
+        Self {
+            position_step: 0.5,
+            rotation_step: 15.0,
+            scale_step: 0.1,
+        }
+    }
+}
+
+impl GizmoSnapSettings {
+    fn snap_position(&self, value: f32) -> f32 {
+        sanji_engine::math::snap(value, self.position_step)
+    }
+
+    fn snap_angle(&self, radians: f32) -> f32 {
+        sanji_engine::math::snap(radians.to_degrees(), self.rotation_step).to_radians()
+    }
+
+    /// Never snaps down to zero so the object can't collapse
+    fn snap_scale(&self, value: f32) -> f32 {
+        sanji_engine::math::snap(value, self.scale_step).max(self.scale_step.max(0.001))
+    }
+}
+
+/// Gizmo handle picked when a drag starts
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GizmoAxis {
+    X,
+    Y,
+    Z,
+    Uniform,
+}
+
+impl GizmoAxis {
+    fn direction(&self) -> glam::Vec3 {
+        match self {
+            GizmoAxis::X => glam::Vec3::X,
+            GizmoAxis::Y => glam::Vec3::Y,
+            GizmoAxis::Z => glam::Vec3::Z,
+            GizmoAxis::Uniform => glam::Vec3::ONE,
+        }
+    }
+}
+
+/// Transform gizmo drag in progress
+#[derive(Debug, Clone)]
+struct GizmoDrag {
+    entity: specs::Entity,
+    axis: GizmoAxis,
+    /// Transform when the drag started; the result is always recomputed from it
+    start: Transform,
+    /// Accumulated pointer movement in screen pixels
+    pointer_delta: egui::Vec2,
+}
+
 /// Professional 3D Camera for Scene View
+///
+/// Orbit camera around a target point. Orientation is stored as a quaternion so
+/// orbiting past the poles never clamps or flips.
 #[derive(Debug, Clone)]
 struct Scene3DCamera {
+    pub target: glam::Vec3,
+    pub distance: f32,
+    pub orientation: glam::Quat,
+    /// Derived from target, distance and orientation in `update_matrices`
     pub position: glam::Vec3,
-    pub rotation: glam::Vec3,
     pub fov: f32,
     pub near: f32,
     pub far: f32,
     pub aspect_ratio: f32,
     pub view_matrix: glam::Mat4,
     pub projection_matrix: glam::Mat4,
+    /// Pending focus animation (target, distance)
+    focus_goal: Option<(glam::Vec3, f32)>,
 }
 
 impl Default for Scene3DCamera {
     fn default() -> Self {
+        let position = glam::Vec3::new(5.0, 5.0, 5.0);
         let mut camera = Self {
-            position: glam::Vec3::new(5.0, 5.0, 5.0),
-            rotation: glam::Vec3::new(-30.0, 45.0, 0.0),
+            target: glam::Vec3::ZERO,
+            distance: position.length(),
+            orientation: Self::orientation_towards(-position),
+            position,
             fov: 60.0,
             near: 0.1,
             far: 1000.0,
             aspect_ratio: 16.0 / 9.0,
             view_matrix: glam::Mat4::IDENTITY,
             projection_matrix: glam::Mat4::IDENTITY,
+            focus_goal: None,
         };
         camera.update_matrices();
         camera
@@ -120,32 +224,115 @@ impl Default for Scene3DCamera {
 }
 
 impl Scene3DCamera {
+    const ORBIT_SPEED: f32 = 0.01;
+    const PAN_SPEED: f32 = 0.01;
+    const ZOOM_SPEED: f32 = 0.001;
+    const MIN_DISTANCE: f32 = 0.1;
+    /// Distance used when focusing on an object
+    pub const FOCUS_DISTANCE: f32 = 8.0;
+
+    /// Orientation whose forward (-Z) points along `direction`
+    fn orientation_towards(direction: glam::Vec3) -> glam::Quat {
+        Coordinate::rotation_looking_to(direction, Coordinate::UP)
+    }
+
+    pub fn forward(&self) -> glam::Vec3 {
+        self.orientation * Coordinate::FORWARD
+    }
+
+    pub fn right(&self) -> glam::Vec3 {
+        self.orientation * Coordinate::RIGHT
+    }
+
+    pub fn up(&self) -> glam::Vec3 {
+        self.orientation * Coordinate::UP
+    }
+
+    /// World-space ray through a pixel of the viewport (origin at the viewport's top-left)
+    pub fn screen_point_to_ray(&self, screen_pos: glam::Vec2, screen_size: glam::Vec2) -> sanji_engine::math::Ray {
+        let ndc_x = 2.0 * screen_pos.x / screen_size.x.max(1.0) - 1.0;
+        let ndc_y = 1.0 - 2.0 * screen_pos.y / screen_size.y.max(1.0);
+
+        // Unproject onto the near and far planes (wgpu depth range is [0, 1])
+        let inverse = (self.projection_matrix * self.view_matrix).inverse();
+        let near = inverse.project_point3(glam::Vec3::new(ndc_x, ndc_y, 0.0));
+        let far = inverse.project_point3(glam::Vec3::new(ndc_x, ndc_y, 1.0));
+        sanji_engine::math::Ray::new(near, far - near)
+    }
+
     pub fn update_matrices(&mut self) {
+        self.position = self.target - self.forward() * self.distance;
+
         // Calculate view matrix
-        let rotation = glam::Quat::from_euler(
-            glam::EulerRot::YXZ,
-            self.rotation.y.to_radians(),
-            self.rotation.x.to_radians(),
-            self.rotation.z.to_radians(),
-        );
-        
-        let forward = rotation * -glam::Vec3::Z;
-        let up = rotation * glam::Vec3::Y;
-        
-        self.view_matrix = glam::Mat4::look_at_rh(
+        self.view_matrix = Coordinate::look_at(
             self.position,
-            self.position + forward,
-            up,
+            self.target,
+            self.up(),
         );
         
         // Calculate projection matrix
-        self.projection_matrix = glam::Mat4::perspective_rh(
+        self.projection_matrix = Coordinate::perspective(
             self.fov.to_radians(),
             self.aspect_ratio,
             self.near,
             self.far,
         );
     }
+
+    /// Orbit around the target: yaw about world up, pitch about the camera's right axis
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        let yaw_rotation = glam::Quat::from_rotation_y(yaw);
+        let pitch_rotation = glam::Quat::from_rotation_x(pitch);
+        self.orientation = (yaw_rotation * self.orientation * pitch_rotation).normalize();
+        self.update_matrices();
+    }
+
+    /// Move the target (and camera) in the view plane
+    pub fn pan(&mut self, delta: glam::Vec2) {
+        // Scale pan with distance so it feels consistent at any zoom level
+        let scale = self.distance.max(1.0) * Self::PAN_SPEED * 0.2;
+        self.target += self.right() * -delta.x * scale + self.up() * delta.y * scale;
+        self.update_matrices();
+    }
+
+    /// Move towards/away from the target
+    pub fn zoom(&mut self, amount: f32) {
+        self.distance = (self.distance * (1.0 - amount)).max(Self::MIN_DISTANCE);
+        self.update_matrices();
+    }
+
+    /// Start a smooth focus on `target`, ending `distance` away from it
+    pub fn focus_on(&mut self, target: glam::Vec3, distance: f32) {
+        self.focus_goal = Some((target, distance.max(Self::MIN_DISTANCE)));
+    }
+
+    /// Jump straight to the focus position without animating
+    pub fn snap_to(&mut self, target: glam::Vec3, distance: f32) {
+        self.focus_goal = None;
+        self.target = target;
+        self.distance = distance.max(Self::MIN_DISTANCE);
+        self.update_matrices();
+    }
+
+    /// Advance the focus animation, returns true while the camera is moving
+    pub fn update_focus(&mut self, delta_time: f32) -> bool {
+        let Some((goal_target, goal_distance)) = self.focus_goal else {
+            return false;
+        };
+
+        // Exponential smoothing, frame-rate independent
+        let t = 1.0 - (-10.0 * delta_time).exp();
+        self.target = self.target.lerp(goal_target, t);
+        self.distance += (goal_distance - self.distance) * t;
+
+        if self.target.distance(goal_target) < 1e-3 && (self.distance - goal_distance).abs() < 1e-3 {
+            self.target = goal_target;
+            self.distance = goal_distance;
+            self.focus_goal = None;
+        }
+        self.update_matrices();
+        true
+    }
     
     pub fn handle_input(&mut self, ui: &egui::Ui, rect: egui::Rect) -> bool {
         let mut camera_changed = false;
@@ -158,9 +345,7 @@ impl Scene3DCamera {
                 let delta = pointer.delta();
                 if delta != egui::Vec2::ZERO {
                     // Orbit rotation
-                    self.rotation.y += delta.x * 0.01;
-                    self.rotation.x += delta.y * 0.01;
-                    self.rotation.x = self.rotation.x.clamp(-90.0, 90.0);
+                    self.orbit(-delta.x * Self::ORBIT_SPEED, -delta.y * Self::ORBIT_SPEED);
                     camera_changed = true;
                 }
             }
@@ -169,17 +354,7 @@ impl Scene3DCamera {
                 let delta = pointer.delta();
                 if delta != egui::Vec2::ZERO {
                     // Pan movement
-                    let rotation = glam::Quat::from_euler(
-                        glam::EulerRot::YXZ,
-                        self.rotation.y.to_radians(),
-                        self.rotation.x.to_radians(),
-                        0.0,
-                    );
-                    let right = rotation * glam::Vec3::X;
-                    let up = rotation * glam::Vec3::Y;
-                    
-                    self.position += right * -delta.x * 0.01;
-                    self.position += up * delta.y * 0.01;
+                    self.pan(glam::Vec2::new(delta.x, delta.y));
                     camera_changed = true;
                 }
             }
@@ -187,17 +362,16 @@ impl Scene3DCamera {
             // Zoom with scroll
             let scroll = ui.input(|i| i.raw_scroll_delta.y);
             if scroll != 0.0 {
-                let rotation = glam::Quat::from_euler(
-                    glam::EulerRot::YXZ,
-                    self.rotation.y.to_radians(),
-                    self.rotation.x.to_radians(),
-                    0.0,
-                );
-                let forward = rotation * -glam::Vec3::Z;
-                self.position += forward * scroll * 0.01;
+                self.zoom(scroll * Self::ZOOM_SPEED);
                 camera_changed = true;
             }
         }
+
+        let delta_time = ui.input(|i| i.stable_dt);
+        if self.update_focus(delta_time) {
+            ui.ctx().request_repaint();
+            camera_changed = true;
+        }
         
         if camera_changed {
             self.update_matrices();
@@ -226,46 +400,6 @@ struct AssetImportInfo {
     import_settings: AssetImportSettings,
 }
 
-#[derive(Debug, Clone)]
-struct AssetImportSettings {
-    // Model settings
-    scale_factor: f32,
-    generate_normals: bool,
-    optimize_mesh: bool,
-    
-    // Texture settings
-    generate_mipmaps: bool,
-    compress_texture: bool,
-    max_texture_size: u32,
-    
-    // Audio settings
-    compress_audio: bool,
-    audio_quality: AudioQuality,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum AudioQuality {
-    Low,
-    Medium,
-    High,
-    Lossless,
-}
-
-impl Default for AssetImportSettings {
-    fn default() -> Self {
-        Self {
-            scale_factor: 1.0,
-            generate_normals: true,
-            optimize_mesh: true,
-            generate_mipmaps: true,
-            compress_texture: true,
-            max_texture_size: 2048,
-            compress_audio: false,
-            audio_quality: AudioQuality::High,
-        }
-    }
-}
-
 impl SanjiEngineEditor {
     pub fn new() -> Self {
         let mut engine_config = EngineConfig::default();
@@ -303,6 +437,7 @@ impl SanjiEngineEditor {
             
             selected_entity: None,
             selected_asset: None,
+            play_snapshot: None,
             
             show_hierarchy: true,
             show_inspector: true,
@@ -313,6 +448,8 @@ impl SanjiEngineEditor {
             
             console_messages: Vec::new(),
             current_tool: EditorTool::Select,
+            snap_settings: GizmoSnapSettings::default(),
+            gizmo_drag: None,
             
             scene_camera_pos: [0.0, 5.0, 10.0],
             scene_camera_rot: [15.0, 0.0, 0.0],
@@ -323,9 +460,19 @@ impl SanjiEngineEditor {
             
             show_asset_import_dialog: false,
             current_import: None,
+            import_pipeline: AssetImportPipeline::default(),
             
             render_system: None, // Will be initialized later
             scene_3d_camera: Scene3DCamera::default(),
+            
+            thumbnail_renderer: None,
+            thumbnail_renderer_failed: false,
+            thumbnail_textures: std::collections::HashMap::new(),
+            scene_view_texture: None,
+            scene_view_render_failed: false,
+
+            debug_draw: sanji_engine::render::DebugDraw::default(),
+            picking_meshes: Self::primitive_picking_meshes(),
         };
         
         // Create default scene
@@ -349,10 +496,7 @@ impl SanjiEngineEditor {
             // Create directional light
             let _light_entity = world.create_entity()
                 .with(Name::new("Directional Light"))
-                .with(Transform {
-                    position: Vec3::new(2.0, 4.0, 2.0),
-                    ..Default::default()
-                })
+                .with(Transform::new().with_position(Vec3::new(2.0, 4.0, 2.0)))
                 .with(Light {
                     light_type: LightType::Directional,
                     color: Vec3::new(1.0, 1.0, 1.0),
@@ -364,28 +508,19 @@ impl SanjiEngineEditor {
             // Create sample objects with real components
             let _cube_entity = world.create_entity()
                 .with(Name::new("Cube"))
-                .with(Transform {
-                    position: Vec3::new(0.0, 1.0, 0.0),
-                    ..Default::default()
-                })
+                .with(Transform::new().with_position(Vec3::new(0.0, 1.0, 0.0)))
                 .with(MeshRenderer::new("cube".to_string(), "default_material".to_string()))
                 .build();
                 
             let _sphere_entity = world.create_entity()
                 .with(Name::new("Sphere"))
-                .with(Transform {
-                    position: Vec3::new(3.0, 1.0, 0.0),
-                    ..Default::default()
-                })
+                .with(Transform::new().with_position(Vec3::new(3.0, 1.0, 0.0)))
                 .with(MeshRenderer::new("sphere".to_string(), "default_material".to_string()))
                 .build();
                 
             let _plane_entity = world.create_entity()
                 .with(Name::new("Ground Plane"))
-                .with(Transform {
-                    scale: Vec3::new(10.0, 1.0, 10.0),
-                    ..Default::default()
-                })
+                .with(Transform::new().with_scale(Vec3::new(10.0, 1.0, 10.0)))
                 .with(MeshRenderer::new("plane".to_string(), "ground_material".to_string()))
                 .build();
         }
@@ -559,15 +694,15 @@ impl SanjiEngineEditor {
                     let name = names.get(entity).map(|n| n.name.clone());
                     let transform = transforms.get(entity).cloned();
                     let has_mesh = mesh_renderers.get(entity).is_some();
-                    let has_camera = cameras.get(entity).is_some();
+                    let camera_fog = cameras.get(entity).map(|camera| camera.fog);
                     let light = lights.get(entity).cloned();
                     
-                    Some((name, transform, has_mesh, has_camera, light))
+                    Some((name, transform, has_mesh, camera_fog, light))
                 } else {
                     None
                 };
                 
-                if let Some((name, transform, has_mesh, has_camera, light)) = entity_data {
+                if let Some((name, transform, has_mesh, camera_fog, light)) = entity_data {
                     // Entity Name
                     if let Some(ref entity_name) = name {
                         ui.horizontal(|ui| {
@@ -578,38 +713,48 @@ impl SanjiEngineEditor {
                     }
                     
                     // Transform Component (Always present)
-                    if let Some(t) = transform {
+                    if let Some(mut t) = transform {
                         egui::CollapsingHeader::new("📐 Transform")
                             .default_open(true)
                             .show(ui, |ui| {
+                                let mut changed = false;
                                 egui::Grid::new("transform_grid").show(ui, |ui| {
                                     ui.label("Position:");
-                                    ui.label(format!("X: {:.2}", t.position.x));
-                                    ui.label(format!("Y: {:.2}", t.position.y));
-                                    ui.label(format!("Z: {:.2}", t.position.z));
+                                    for (value, axis) in [&mut t.position.x, &mut t.position.y, &mut t.position.z].into_iter().zip(["X: ", "Y: ", "Z: "]) {
+                                        changed |= ui.add(egui::DragValue::new(value).speed(0.05).prefix(axis)).changed();
+                                    }
                                     ui.end_row();
                                     
+                                    // Edited as Euler angles in degrees, using the engine's rotation order
+                                    let mut euler = t.euler_degrees().to_array();
+                                    let mut rotation_changed = false;
                                     ui.label("Rotation:");
-                                    ui.label(format!("X: {:.1}°", t.rotation.x.to_degrees()));
-                                    ui.label(format!("Y: {:.1}°", t.rotation.y.to_degrees()));
-                                    ui.label(format!("Z: {:.1}°", t.rotation.z.to_degrees()));
+                                    for (value, axis) in euler.iter_mut().zip(["X: ", "Y: ", "Z: "]) {
+                                        rotation_changed |= ui.add(egui::DragValue::new(value).speed(0.5).prefix(axis).suffix("°")).changed();
+                                    }
+                                    if rotation_changed {
+                                        t.set_euler_degrees(glam::Vec3::from_array(euler));
+                                        changed = true;
+                                    }
                                     ui.end_row();
                                     
                                     ui.label("Scale:");
-                                    ui.label(format!("X: {:.2}", t.scale.x));
-                                    ui.label(format!("Y: {:.2}", t.scale.y));
-                                    ui.label(format!("Z: {:.2}", t.scale.z));
+                                    for (value, axis) in [&mut t.scale.x, &mut t.scale.y, &mut t.scale.z].into_iter().zip(["X: ", "Y: ", "Z: "]) {
+                                        changed |= ui.add(egui::DragValue::new(value).speed(0.01).prefix(axis)).changed();
+                                    }
                                     ui.end_row();
                                 });
+
+                                if changed {
+                                    self.write_transform(entity, &t);
+                                }
                                 
                                 if ui.button("🎯 Focus in Scene View").clicked() {
-                                    // Move 3D camera to focus on this object
-                                    self.scene_3d_camera.position = glam::Vec3::new(
-                                        t.position.x + 5.0,
-                                        t.position.y + 3.0,
-                                        t.position.z + 5.0,
+                                    // Smoothly frame this object in the 3D camera
+                                    self.scene_3d_camera.focus_on(
+                                        glam::Vec3::new(t.position.x, t.position.y, t.position.z),
+                                        Scene3DCamera::FOCUS_DISTANCE,
                                     );
-                                    self.scene_3d_camera.update_matrices();
                                     if let Some(ref name) = name {
                                         self.add_console_message(&format!("Focused camera on {}", name));
                                     } else {
@@ -657,7 +802,7 @@ impl SanjiEngineEditor {
                     }
                     
                     // Camera Component
-                    if has_camera {
+                    if let Some(mut fog) = camera_fog {
                         egui::CollapsingHeader::new("📷 Camera")
                             .show(ui, |ui| {
                                 ui.horizontal(|ui| {
@@ -690,6 +835,11 @@ impl SanjiEngineEditor {
                                 
                                 ui.checkbox(&mut true, "Main Camera");
                                 ui.checkbox(&mut false, "Use Physical Properties");
+
+                                ui.separator();
+                                if self.edit_fog(ui, &mut fog) {
+                                    self.write_camera_fog(entity, fog);
+                                }
                             });
                     }
                     
@@ -732,6 +882,15 @@ impl SanjiEngineEditor {
                                         let mut angle = l.spot_angle.to_degrees();
                                         ui.add(egui::Slider::new(&mut angle, 1.0..=179.0).suffix("°"));
                                     });
+                                    
+                                    ui.horizontal(|ui| {
+                                        ui.label("Inner Angle:");
+                                        let outer = l.spot_angle.to_degrees();
+                                        let mut inner = l.spot_inner_angle.to_degrees().min(outer);
+                                        if ui.add(egui::Slider::new(&mut inner, 0.0..=outer).suffix("°")).changed() {
+                                            self.add_console_message(&format!("Spot inner angle: {:.1}°", inner));
+                                        }
+                                    });
                                 }
                                 
                                 let mut cast_shadows = l.cast_shadows;
@@ -806,6 +965,14 @@ impl SanjiEngineEditor {
         // Handle 3D camera input
         self.scene_3d_camera.aspect_ratio = rect.width() / rect.height();
         let _camera_changed = self.scene_3d_camera.handle_input(ui, rect);
+
+        // Left click selects the nearest entity under the cursor
+        let response = ui.interact(rect, ui.id().with("scene_view_picking"), egui::Sense::click());
+        if response.clicked() {
+            if let Some(pointer) = response.interact_pointer_pos() {
+                self.pick_entity_at(pointer, rect);
+            }
+        }
         
         // Create the 3D rendering area
         ui.allocate_ui_at_rect(rect, |ui| {
@@ -817,10 +984,73 @@ impl SanjiEngineEditor {
             
             // Add gizmos and handles
             self.draw_3d_transform_gizmos(ui, rect);
+
+            // Flush debug lines queued this frame
+            self.draw_debug_overlay(ui.painter(), rect);
         });
     }
     
+    /// Primitive meshes matching the mesh names used by editor-created entities
+    fn primitive_picking_meshes() -> std::collections::HashMap<String, Arc<sanji_engine::render::Mesh>> {
+        use sanji_engine::render::Mesh;
+
+        let mut meshes = std::collections::HashMap::new();
+        meshes.insert("cube".to_string(), Arc::new(Mesh::cube()));
+        meshes.insert("sphere".to_string(), Arc::new(Mesh::sphere(0.5, 24)));
+        meshes.insert("plane".to_string(), Arc::new(Mesh::plane(1.0)));
+        meshes
+    }
+
+    /// Cast a ray through the clicked pixel and select the nearest hit entity
+    fn pick_entity_at(&mut self, pointer: egui::Pos2, rect: egui::Rect) {
+        let local = pointer - rect.min;
+        let ray = self.scene_3d_camera.screen_point_to_ray(
+            glam::Vec2::new(local.x, local.y),
+            glam::Vec2::new(rect.width(), rect.height()),
+        );
+
+        let hit = {
+            let Ok(world) = self.ecs_world.lock() else {
+                return;
+            };
+            // Meshes without known geometry (e.g. cylinders) fall back to their unit bounding box
+            let picker = ScenePicker::from_world(world.world(), |name| self.picking_meshes.get(name).cloned());
+            picker.pick(&ray).map(|hit| {
+                let name = world.world().read_storage::<Name>()
+                    .get(hit.entity)
+                    .map(|name| name.name.clone())
+                    .unwrap_or_else(|| format!("Entity {}", hit.entity.id()));
+                (hit.entity, name)
+            })
+        };
+
+        match hit {
+            Some((entity, name)) => {
+                if self.selected_entity != Some(entity) {
+                    self.selected_entity = Some(entity);
+                    self.add_console_message(&format!("Selected {}", name));
+                }
+            }
+            None => self.selected_entity = None,
+        }
+    }
+
     fn render_professional_3d_scene(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        // Prefer a real render of the ECS world; the painter approximation is only a fallback
+        if self.render_scene_view_texture(ui.ctx(), rect) {
+            if let Some(texture) = &self.scene_view_texture {
+                let painter = ui.painter();
+                painter.image(
+                    texture.id(),
+                    rect,
+                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    Color32::WHITE,
+                );
+                self.draw_world_axis(painter, rect);
+                return;
+            }
+        }
+        
         let painter = ui.painter();
         
         // Professional dark 3D viewport background
@@ -840,6 +1070,48 @@ impl SanjiEngineEditor {
         self.draw_world_axis(painter, rect);
     }
     
+    /// Renders the ECS world from the scene camera into `scene_view_texture`.
+    ///
+    /// eframe runs on the glow backend and egui-wgpu targets a newer wgpu than the engine,
+    /// so the frame is rendered on the engine's own device and read back instead of being
+    /// drawn through a paint callback.
+    fn render_scene_view_texture(&mut self, ctx: &egui::Context, rect: egui::Rect) -> bool {
+        if self.scene_view_render_failed || !self.ensure_offscreen_renderer() {
+            return false;
+        }
+        
+        let pixels_per_point = ctx.pixels_per_point();
+        let width = (rect.width() * pixels_per_point).round().max(1.0) as u32;
+        let height = (rect.height() * pixels_per_point).round().max(1.0) as u32;
+        let view_projection = self.scene_3d_camera.projection_matrix * self.scene_3d_camera.view_matrix;
+        
+        let result = {
+            let (Ok(world), Some(renderer)) = (self.ecs_world.lock(), self.thumbnail_renderer.as_ref()) else {
+                return false;
+            };
+            renderer.render_view(ThumbnailSource::World(&world), width, height, view_projection)
+        };
+        
+        match result {
+            Ok(texture) => {
+                let size = [texture.descriptor.width as usize, texture.descriptor.height as usize];
+                let image = egui::ColorImage::from_rgba_unmultiplied(size, &texture.data);
+                match &mut self.scene_view_texture {
+                    Some(handle) => handle.set(image, egui::TextureOptions::LINEAR),
+                    None => {
+                        self.scene_view_texture = Some(ctx.load_texture("scene_view", image, egui::TextureOptions::LINEAR));
+                    }
+                }
+                true
+            }
+            Err(e) => {
+                self.scene_view_render_failed = true;
+                self.add_console_message(&format!("Scene view rendering failed, using preview mode: {}", e));
+                false
+            }
+        }
+    }
+    
     fn draw_unity_style_grid(&self, painter: &egui::Painter, rect: egui::Rect) {
         let center = rect.center();
         let grid_size = 30.0;
@@ -959,14 +1231,8 @@ impl SanjiEngineEditor {
         // Find the main directional light
         for (_entity, light, transform) in (entities, lights, transforms).join() {
             if light.light_type == LightType::Directional {
-                // Calculate light direction from transform rotation
-                let rotation = glam::Quat::from_euler(
-                    glam::EulerRot::YXZ,
-                    transform.rotation.y.to_radians(),
-                    transform.rotation.x.to_radians(),
-                    transform.rotation.z.to_radians(),
-                );
-                return rotation * -glam::Vec3::Z; // Forward direction
+                // Lights shine along their transform's forward axis
+                return transform.forward();
             }
         }
         
@@ -1346,52 +1612,303 @@ impl SanjiEngineEditor {
     }
     
     fn draw_3d_transform_gizmos(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
-        if let Some(selected_entity) = self.selected_entity {
-            if let Ok(world) = self.ecs_world.lock() {
-                use specs::Join;
-                let _entities = world.world().entities();
-                let transforms = world.world().read_storage::<Transform>();
-                
-                if let Some(transform) = transforms.get(selected_entity) {
-                    // Project 3D position to 2D screen space
-                    let world_pos = transform.position;
-                    let view_proj = self.scene_3d_camera.projection_matrix * self.scene_3d_camera.view_matrix;
-                    let clip_pos = view_proj * glam::Vec4::new(world_pos.x, world_pos.y, world_pos.z, 1.0);
-                    
-                    if clip_pos.w > 0.0 {
-                        let ndc_pos = clip_pos.xyz() / clip_pos.w;
-                        let screen_pos = egui::pos2(
-                            rect.center().x + ndc_pos.x * rect.width() * 0.5,
-                            rect.center().y - ndc_pos.y * rect.height() * 0.5,
-                        );
-                        
-                        // Draw Unity-style 3D gizmos
-                        let painter = ui.painter();
-                        match self.current_tool {
-                            EditorTool::Move => {
-                                self.draw_move_gizmo(painter, screen_pos);
-                            }
-                            EditorTool::Rotate => {
-                                self.draw_rotate_gizmo(painter, screen_pos);
-                            }
-                            EditorTool::Scale => {
-                                self.draw_scale_gizmo(painter, screen_pos);
-                            }
-                            _ => {
-                                // Selection outline
-                                painter.circle_stroke(
-                                    screen_pos, 
-                                    30.0, 
-                                    egui::Stroke::new(2.0, Color32::YELLOW)
-                                );
-                            }
-                        }
+        let Some(selected_entity) = self.selected_entity else {
+            self.gizmo_drag = None;
+            return;
+        };
+        let transform = {
+            let Ok(world) = self.ecs_world.lock() else {
+                return;
+            };
+            let transforms = world.world().read_storage::<Transform>();
+            transforms.get(selected_entity).cloned()
+        };
+        let Some(mut transform) = transform else {
+            return;
+        };
+
+        // Project 3D position to 2D screen space
+        let Some(screen_pos) = self.world_to_screen(transform.position, rect) else {
+            return;
+        };
+
+        if self.current_tool != EditorTool::Select {
+            self.handle_gizmo_drag(ui, rect, selected_entity, &transform, screen_pos);
+        }
+
+        // Draw Unity-style 3D gizmos
+        let painter = ui.painter();
+        match self.current_tool {
+            EditorTool::Move => {
+                self.draw_move_gizmo(painter, screen_pos);
+            }
+            EditorTool::Rotate => {
+                self.draw_rotate_gizmo(painter, screen_pos);
+            }
+            EditorTool::Scale => {
+                self.draw_scale_gizmo(painter, screen_pos);
+            }
+            _ => {
+                // Selection outline: the unit bounds of editor primitives in world space
+                let bounds = sanji_engine::math::AABB::from_center_size(Vec3::ZERO, Vec3::ONE)
+                    .transform(&transform.world_matrix());
+                self.debug_draw.aabb(&bounds, glam::Vec4::new(1.0, 1.0, 0.0, 1.0));
+            }
+        }
+    }
+
+    /// Draw the queued debug lines and labels over the scene view, then clear them
+    fn draw_debug_overlay(&mut self, painter: &egui::Painter, rect: egui::Rect) {
+        let to_color = |color: glam::Vec4| {
+            let [r, g, b, a] = color.to_array().map(|channel| (channel.clamp(0.0, 1.0) * 255.0) as u8);
+            Color32::from_rgba_unmultiplied(r, g, b, a)
+        };
+
+        for line in self.debug_draw.lines() {
+            if let (Some(start), Some(end)) = (self.world_to_screen(line.start, rect), self.world_to_screen(line.end, rect)) {
+                painter.line_segment([start, end], egui::Stroke::new(1.5, to_color(line.color)));
+            }
+        }
+        for text in self.debug_draw.texts() {
+            if let Some(position) = self.world_to_screen(text.position, rect) {
+                painter.text(position, egui::Align2::CENTER_BOTTOM, &text.text, egui::FontId::proportional(12.0), to_color(text.color));
+            }
+        }
+
+        self.debug_draw.end_frame();
+    }
+
+    /// Project a world position into the scene view, None when behind the camera
+    fn world_to_screen(&self, world_pos: glam::Vec3, rect: egui::Rect) -> Option<egui::Pos2> {
+        let view_proj = self.scene_3d_camera.projection_matrix * self.scene_3d_camera.view_matrix;
+        let clip_pos = view_proj * glam::Vec4::new(world_pos.x, world_pos.y, world_pos.z, 1.0);
+        if clip_pos.w <= 0.0 {
+            return None;
+        }
+        let ndc_pos = clip_pos.xyz() / clip_pos.w;
+        Some(egui::pos2(
+            rect.center().x + ndc_pos.x * rect.width() * 0.5,
+            rect.center().y - ndc_pos.y * rect.height() * 0.5,
+        ))
+    }
+
+    /// Start, update and finish dragging the active gizmo
+    fn handle_gizmo_drag(
+        &mut self,
+        ui: &egui::Ui,
+        rect: egui::Rect,
+        entity: specs::Entity,
+        transform: &Transform,
+        center: egui::Pos2,
+    ) {
+        // Only senses drags, so clicks still reach viewport picking underneath
+        let gizmo_rect = egui::Rect::from_center_size(center, egui::vec2(110.0, 110.0));
+        let response = ui.interact(gizmo_rect, ui.id().with("transform_gizmo"), egui::Sense::drag());
+
+        if response.drag_started_by(egui::PointerButton::Primary) {
+            self.gizmo_drag = response
+                .interact_pointer_pos()
+                .and_then(|pointer| self.gizmo_axis_at(pointer, center))
+                .map(|axis| GizmoDrag {
+                    entity,
+                    axis,
+                    start: transform.clone(),
+                    pointer_delta: egui::Vec2::ZERO,
+                });
+        }
+
+        let Some(mut drag) = self.gizmo_drag.clone() else {
+            return;
+        };
+        if drag.entity != entity {
+            self.gizmo_drag = None;
+            return;
+        }
+
+        if response.dragged_by(egui::PointerButton::Primary) {
+            drag.pointer_delta += response.drag_delta();
+            let snapping = ui.input(|i| i.modifiers.command);
+            let updated = self.dragged_transform(&drag, rect, snapping);
+            self.write_transform(entity, &updated);
+            self.gizmo_drag = Some(drag.clone());
+        }
+
+        if response.drag_stopped() {
+            self.gizmo_drag = None;
+            self.add_console_message(&format!("{:?} finished on {:?} axis", self.current_tool, drag.axis));
+        }
+    }
+
+    /// Gizmo handle under the pointer for the current tool
+    fn gizmo_axis_at(&self, pointer: egui::Pos2, center: egui::Pos2) -> Option<GizmoAxis> {
+        const PICK_TOLERANCE: f32 = 8.0;
+
+        match self.current_tool {
+            EditorTool::Move => {
+                let size = 40.0;
+                let handles = [
+                    (GizmoAxis::X, egui::vec2(size, 0.0)),
+                    (GizmoAxis::Y, egui::vec2(0.0, -size)),
+                    (GizmoAxis::Z, egui::vec2(size * 0.7, -size * 0.7)),
+                ];
+                handles
+                    .iter()
+                    .map(|(axis, offset)| {
+                        // Distance from the pointer to the handle's line segment
+                        let t = ((pointer - center).dot(*offset) / offset.length_sq()).clamp(0.0, 1.0);
+                        (*axis, (pointer - (center + *offset * t)).length())
+                    })
+                    .filter(|(_, distance)| *distance <= PICK_TOLERANCE)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(axis, _)| axis)
+            }
+            EditorTool::Rotate => {
+                let radius = 35.0;
+                let distance = (pointer - center).length();
+                [(GizmoAxis::X, radius), (GizmoAxis::Y, radius * 0.8), (GizmoAxis::Z, radius * 1.2)]
+                    .iter()
+                    .map(|(axis, ring)| (*axis, (distance - ring).abs()))
+                    .filter(|(_, offset)| *offset <= PICK_TOLERANCE * 0.5)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(axis, _)| axis)
+            }
+            EditorTool::Scale => {
+                let size = 35.0 + PICK_TOLERANCE;
+                egui::Rect::from_center_size(center, egui::vec2(size, size))
+                    .contains(pointer)
+                    .then_some(GizmoAxis::Uniform)
+            }
+            EditorTool::Select => None,
+        }
+    }
+
+    /// Transform resulting from the drag so far, snapped when requested
+    fn dragged_transform(&self, drag: &GizmoDrag, rect: egui::Rect, snapping: bool) -> Transform {
+        const ROTATE_DEGREES_PER_PIXEL: f32 = 0.5;
+        const SCALE_PER_PIXEL: f32 = 0.01;
+
+        let mut transform = drag.start.clone();
+        let delta = drag.pointer_delta;
+
+        match self.current_tool {
+            EditorTool::Move => {
+                // Map the pointer movement onto the axis as it appears on screen
+                let origin = drag.start.position;
+                let direction = drag.axis.direction();
+                let (Some(from), Some(to)) = (
+                    self.world_to_screen(origin, rect),
+                    self.world_to_screen(origin + direction, rect),
+                ) else {
+                    return transform;
+                };
+                let screen_axis = to - from;
+                if screen_axis.length_sq() < 1e-6 {
+                    return transform;
+                }
+                let amount = delta.dot(screen_axis) / screen_axis.length_sq();
+                let mut position = origin + direction * amount;
+                if snapping {
+                    match drag.axis {
+                        GizmoAxis::X => position.x = self.snap_settings.snap_position(position.x),
+                        GizmoAxis::Y => position.y = self.snap_settings.snap_position(position.y),
+                        GizmoAxis::Z => position.z = self.snap_settings.snap_position(position.z),
+                        GizmoAxis::Uniform => {}
                     }
                 }
+                transform.set_position(position);
+            }
+            EditorTool::Rotate => {
+                let mut angle = ((delta.x - delta.y) * ROTATE_DEGREES_PER_PIXEL).to_radians();
+                if snapping {
+                    angle = self.snap_settings.snap_angle(angle);
+                }
+                let rotation = glam::Quat::from_axis_angle(drag.axis.direction(), angle);
+                transform.set_rotation((rotation * drag.start.rotation).normalize());
+            }
+            EditorTool::Scale => {
+                let factor = (1.0 + (delta.x - delta.y) * SCALE_PER_PIXEL).max(0.01);
+                let mut scale = drag.start.scale * factor;
+                if snapping {
+                    scale = glam::Vec3::new(
+                        self.snap_settings.snap_scale(scale.x),
+                        self.snap_settings.snap_scale(scale.y),
+                        self.snap_settings.snap_scale(scale.z),
+                    );
+                }
+                transform.set_scale(scale);
+            }
+            EditorTool::Select => {}
+        }
+        transform
+    }
+
+    /// Write position, rotation and scale back to the entity's Transform component
+    fn write_transform(&mut self, entity: specs::Entity, transform: &Transform) {
+        if let Ok(world) = self.ecs_world.lock() {
+            let mut transforms = world.world().write_storage::<Transform>();
+            if let Some(target) = transforms.get_mut(entity) {
+                target.set_position(transform.position);
+                target.set_rotation(transform.rotation);
+                target.set_scale(transform.scale);
             }
         }
     }
     
+    /// Fog settings editor, returns true when a value changed
+    fn edit_fog(&self, ui: &mut egui::Ui, fog: &mut sanji_engine::render::Fog) -> bool {
+        use sanji_engine::render::FogMode;
+
+        let mut changed = ui.checkbox(&mut fog.enabled, "Fog").changed();
+        ui.add_enabled_ui(fog.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Mode:");
+                egui::ComboBox::from_id_source("fog_mode")
+                    .selected_text(format!("{:?}", fog.mode))
+                    .show_ui(ui, |ui| {
+                        for mode in [FogMode::Linear, FogMode::Exp, FogMode::Exp2] {
+                            changed |= ui.selectable_value(&mut fog.mode, mode, format!("{:?}", mode)).changed();
+                        }
+                    });
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                let mut color = fog.color.to_array();
+                if ui.color_edit_button_rgb(&mut color).changed() {
+                    fog.color = glam::Vec3::from_array(color);
+                    changed = true;
+                }
+            });
+
+            match fog.mode {
+                FogMode::Linear => {
+                    ui.horizontal(|ui| {
+                        ui.label("Start:");
+                        changed |= ui.add(egui::DragValue::new(&mut fog.start).speed(0.5).range(0.0..=fog.end)).changed();
+                        ui.label("End:");
+                        changed |= ui.add(egui::DragValue::new(&mut fog.end).speed(0.5).range(fog.start..=100000.0)).changed();
+                    });
+                }
+                FogMode::Exp | FogMode::Exp2 => {
+                    ui.horizontal(|ui| {
+                        ui.label("Density:");
+                        changed |= ui.add(egui::DragValue::new(&mut fog.density).speed(0.001).range(0.0..=1.0)).changed();
+                    });
+                }
+            }
+        });
+        changed
+    }
+
+    fn write_camera_fog(&mut self, entity: specs::Entity, fog: sanji_engine::render::Fog) {
+        if let Ok(world) = self.ecs_world.lock() {
+            let mut cameras = world.world().write_storage::<Camera>();
+            if let Some(camera) = cameras.get_mut(entity) {
+                camera.fog = fog;
+            }
+        }
+    }
+
     fn draw_move_gizmo(&self, painter: &egui::Painter, center: egui::Pos2) {
         let size = 40.0;
         // X axis (Red)
@@ -1459,13 +1976,32 @@ impl SanjiEngineEditor {
         ui.heading("Project");
         ui.separator();
         
+        // Thumbnails are rendered once and cached; Refresh re-renders them
+        let ctx = ui.ctx().clone();
+        let material_thumbnail = self.asset_thumbnail(&ctx, "material_preview", |renderer| {
+            renderer.render_thumbnail(ThumbnailSource::Prefab(PrefabType::Sphere), THUMBNAIL_SIZE)
+        });
+        let current_scene_name = self.scene_manager.lock().unwrap().current_scene_name().cloned();
+        let scene_thumbnail = current_scene_name.as_ref().and_then(|name| {
+            let scene_manager = self.scene_manager.clone();
+            let ecs_world = self.ecs_world.clone();
+            self.asset_thumbnail(&ctx, &format!("scene:{}", name), move |renderer| {
+                let scene_manager = scene_manager.lock().unwrap();
+                let ecs_world = ecs_world.lock().unwrap();
+                let scene = scene_manager
+                    .current_scene()
+                    .ok_or_else(|| EngineError::RenderError("No active scene".to_string()))?;
+                renderer.render_thumbnail(ThumbnailSource::Scene { scene, world: &ecs_world }, THUMBNAIL_SIZE)
+            })
+        });
+        
         egui::ScrollArea::vertical()
             .max_width(500.0)
             .show(ui, |ui| {
                 ui.collapsing("Assets", |ui| {
                     let _ = ui.selectable_label(false, "Materials/");
-                    let _ = ui.selectable_label(false, "  DefaultMaterial.mat");
-                    let _ = ui.selectable_label(false, "  MetalMaterial.mat");
+                    Self::thumbnail_label(ui, material_thumbnail, "  DefaultMaterial.mat");
+                    Self::thumbnail_label(ui, material_thumbnail, "  MetalMaterial.mat");
                     let _ = ui.selectable_label(false, "Models/");
                     let _ = ui.selectable_label(false, "  character.fbx");
                     let _ = ui.selectable_label(false, "  environment.obj");
@@ -1476,6 +2012,9 @@ impl SanjiEngineEditor {
                     let _ = ui.selectable_label(false, "  PlayerController.rs");
                     let _ = ui.selectable_label(false, "  GameManager.rs");
                     let _ = ui.selectable_label(false, "Scenes/");
+                    if let Some(name) = &current_scene_name {
+                        Self::thumbnail_label(ui, scene_thumbnail, &format!("  {}.scene", name));
+                    }
                     let _ = ui.selectable_label(false, "  MainScene.scene");
                     let _ = ui.selectable_label(false, "  TestLevel.scene");
                 });
@@ -1488,10 +2027,85 @@ impl SanjiEngineEditor {
             }
             if ui.button("Refresh").clicked() {
                 self.add_console_message("Refreshing asset database...");
+                self.thumbnail_textures.clear();
+                if let Some(renderer) = &mut self.thumbnail_renderer {
+                    renderer.cache_mut().clear();
+                }
+            }
+        });
+    }
+    
+    /// Asset entry with an optional thumbnail in front of the label
+    fn thumbnail_label(ui: &mut egui::Ui, thumbnail: Option<egui::TextureId>, label: &str) {
+        ui.horizontal(|ui| {
+            match thumbnail {
+                Some(texture_id) => {
+                    ui.image((texture_id, Vec2::splat(THUMBNAIL_DISPLAY_SIZE)));
+                }
+                None => {
+                    ui.add_space(THUMBNAIL_DISPLAY_SIZE + ui.spacing().item_spacing.x);
+                }
             }
+            let _ = ui.selectable_label(false, label);
         });
     }
     
+    /// Creates the offscreen renderer shared by thumbnails and the scene view on first use
+    fn ensure_offscreen_renderer(&mut self) -> bool {
+        if self.thumbnail_renderer.is_some() {
+            return true;
+        }
+        if self.thumbnail_renderer_failed {
+            return false;
+        }
+        
+        match HeadlessThumbnailRenderer::new() {
+            Ok(mut renderer) => {
+                // Make every primitive the editor can create renderable, not just the built-in ones
+                for (name, mesh) in &self.picking_meshes {
+                    renderer.renderer_mut().register_mesh(name.clone(), (**mesh).clone());
+                }
+                self.thumbnail_renderer = Some(renderer);
+                true
+            }
+            Err(e) => {
+                self.thumbnail_renderer_failed = true;
+                self.add_console_message(&format!("Offscreen rendering unavailable: {}", e));
+                false
+            }
+        }
+    }
+    
+    /// Returns the cached egui texture for an asset thumbnail, rendering it on first use
+    fn asset_thumbnail(
+        &mut self,
+        ctx: &egui::Context,
+        key: &str,
+        render: impl FnOnce(&mut HeadlessThumbnailRenderer) -> EngineResult<AssetHandle<sanji_engine::render::Texture>>,
+    ) -> Option<egui::TextureId> {
+        if let Some(texture) = self.thumbnail_textures.get(key) {
+            return Some(texture.id());
+        }
+        if !self.ensure_offscreen_renderer() {
+            return None;
+        }
+        let renderer = self.thumbnail_renderer.as_mut()?;
+        
+        let texture = match render(renderer) {
+            Ok(handle) => handle.get()?,
+            Err(e) => {
+                self.add_console_message(&format!("Failed to render thumbnail '{}': {}", key, e));
+                return None;
+            }
+        };
+        let size = [texture.descriptor.width as usize, texture.descriptor.height as usize];
+        let image = egui::ColorImage::from_rgba_unmultiplied(size, &texture.data);
+        let handle = ctx.load_texture(format!("thumbnail:{}", key), image, egui::TextureOptions::LINEAR);
+        let texture_id = handle.id();
+        self.thumbnail_textures.insert(key.to_string(), handle);
+        Some(texture_id)
+    }
+    
     fn show_console_panel(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.heading("Console");
@@ -1767,7 +2381,11 @@ impl SanjiEngineEditor {
         egui::menu::bar(ui, |ui| {
             ui.menu_button("File", |ui| {
                 if ui.button("New Scene").clicked() {
-                    self.add_console_message("Creating new scene...");
+                    if let Ok(mut world) = self.ecs_world.lock() {
+                        world.clear();
+                    }
+                    self.selected_entity = None;
+                    self.add_console_message("Created new empty scene");
                 }
                 if ui.button("Open Scene").clicked() {
                     self.add_console_message("Opening scene file browser...");
@@ -1909,14 +2527,35 @@ impl SanjiEngineEditor {
             
             ui.separator();
             
+            // Gizmo snapping, applied while Ctrl is held during a drag
+            ui.label("Snap (Ctrl):");
+            ui.add(egui::DragValue::new(&mut self.snap_settings.position_step).speed(0.05).range(0.01..=100.0).prefix("Grid "))
+                .on_hover_text("Position grid size");
+            ui.add(egui::DragValue::new(&mut self.snap_settings.rotation_step).speed(1.0).range(1.0..=180.0).suffix("°"))
+                .on_hover_text("Rotation angle step");
+            ui.add(egui::DragValue::new(&mut self.snap_settings.scale_step).speed(0.01).range(0.01..=10.0).prefix("Scale "))
+                .on_hover_text("Scale increment");
+            
+            ui.separator();
+            
             // Play controls
             if ui.button("Play").clicked() {
+                if self.play_snapshot.is_none() {
+                    self.play_snapshot = self.ecs_world.lock().ok().map(|world| world.snapshot());
+                }
                 self.add_console_message("Starting game preview...");
             }
             if ui.button("Pause").clicked() {
                 self.add_console_message("Pausing game...");
             }
             if ui.button("Stop").clicked() {
+                self.selected_entity = None;
+                if let Some(snapshot) = self.play_snapshot.take() {
+                    if let Ok(mut world) = self.ecs_world.lock() {
+                        world.restore(&snapshot);
+                    }
+                    self.add_console_message(&format!("Restored {} entities from before play", snapshot.entity_count()));
+                }
                 self.add_console_message("Stopping game...");
             }
             
@@ -2221,7 +2860,23 @@ impl SanjiEngineEditor {
                                     .range(0.01..=100.0));
                             });
                             ui.checkbox(&mut settings.generate_normals, "Generate Normals");
+                            ui.add_enabled_ui(settings.generate_normals, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label("Smoothing Angle:");
+                                    ui.add(egui::Slider::new(&mut settings.smoothing_angle, 0.0..=180.0)
+                                        .suffix("°"));
+                                });
+                            });
                             ui.checkbox(&mut settings.optimize_mesh, "Optimize Mesh");
+                            ui.horizontal(|ui| {
+                                ui.label("Source Up Axis:");
+                                egui::ComboBox::from_id_source("import_up_axis")
+                                    .selected_text(format!("{:?}-up", settings.source_up_axis))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut settings.source_up_axis, UpAxis::Y, "Y-up (glTF)");
+                                        ui.selectable_value(&mut settings.source_up_axis, UpAxis::Z, "Z-up (Blender, 3ds Max)");
+                                    });
+                            });
                             
                             ui.separator();
                             ui.label("Supported formats: FBX, OBJ, glTF, GLB");
@@ -2254,10 +2909,10 @@ impl SanjiEngineEditor {
                                 egui::ComboBox::from_label("")
                                     .selected_text(format!("{:?}", settings.audio_quality))
                                     .show_ui(ui, |ui| {
-                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::Low, "Low (32kbps)");
-                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::Medium, "Medium (64kbps)");
-                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::High, "High (128kbps)");
-                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::Lossless, "Lossless");
+                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::Low, "Low (22 kHz)");
+                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::Medium, "Medium (32 kHz)");
+                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::High, "High (44.1 kHz)");
+                                        ui.selectable_value(&mut settings.audio_quality, AudioQuality::Lossless, "Lossless (original rate)");
                                     });
                             });
                             
@@ -2357,13 +3012,14 @@ impl SanjiEngineEditor {
             let target_name = import_info.target_name.clone();
             let file_extension = import_info.file_extension.clone();
             let settings = import_info.import_settings.clone();
+            let source_path = import_info.source_path.clone();
             
-            Some((asset_type, target_name, file_extension, settings))
+            Some((asset_type, target_name, file_extension, settings, source_path))
         } else {
             None
         };
         
-        if let Some((asset_type, target_name, file_extension, settings)) = import_data {
+        if let Some((asset_type, target_name, file_extension, settings, source_path)) = import_data {
             self.add_console_message(&format!(
                 "Importing {:?} asset: {} ({})", 
                 asset_type,
@@ -2376,13 +3032,21 @@ impl SanjiEngineEditor {
                 AssetType::Model => {
                     self.add_console_message("Processing 3D model...");
                     self.add_console_message(&format!(
-                        "Model settings - Scale: {:.2}x, Normals: {}, Optimize: {}",
+                        "Model settings - Scale: {:.2}x, Normals: {}, Optimize: {}, Up: {:?}",
                         settings.scale_factor,
                         if settings.generate_normals { "Yes" } else { "No" },
-                        if settings.optimize_mesh { "Yes" } else { "No" }
+                        if settings.optimize_mesh { "Yes" } else { "No" },
+                        settings.source_up_axis
                     ));
-                    self.add_console_message("Generating LOD levels...");
-                    self.add_console_message("Creating material assignments...");
+                    if settings.optimize_mesh {
+                        self.add_console_message("Welding coincident vertices...");
+                    }
+                    if settings.generate_normals {
+                        self.add_console_message(&format!(
+                            "Smoothing normals (crease angle {:.0}°)...",
+                            settings.smoothing_angle
+                        ));
+                    }
                 }
                 AssetType::Texture => {
                     self.add_console_message("Processing texture...");
@@ -2393,7 +3057,6 @@ impl SanjiEngineEditor {
                         if settings.generate_mipmaps { "Yes" } else { "No" },
                         if settings.compress_texture { "Yes" } else { "No" }
                     ));
-                    self.add_console_message("Generating mipmaps...");
                 }
                 AssetType::Audio => {
                     self.add_console_message("Processing audio...");
@@ -2402,17 +3065,98 @@ impl SanjiEngineEditor {
                         settings.audio_quality,
                         if settings.compress_audio { "Yes" } else { "No" }
                     ));
-                    self.add_console_message("Optimizing for 3D spatial audio...");
                 }
                 _ => {
                     self.add_console_message("Processing asset...");
                 }
             }
             
-            self.add_console_message(&format!("✅ Successfully imported: {}", target_name));
-            self.add_console_message("Asset added to project database.");
+            match source_path {
+                Some(source_path) => match self.import_pipeline.import(&source_path, &settings) {
+                    Ok(result) if result.cache_hit => {
+                        self.add_console_message(&format!(
+                            "✅ {} is up to date (cached: {})",
+                            target_name,
+                            result.artifact_path.display()
+                        ));
+                    }
+                    Ok(result) => {
+                        self.add_console_message(&format!(
+                            "✅ Successfully imported: {} -> {}",
+                            target_name,
+                            result.artifact_path.display()
+                        ));
+                    }
+                    Err(e) => {
+                        self.add_console_message(&format!("❌ Failed to import {}: {}", target_name, e));
+                    }
+                },
+                None => {
+                    self.add_console_message(&format!("❌ No source file selected for {}", target_name));
+                }
+            }
         }
         
         self.current_import = None;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orbiting_full_circle_restores_orientation() {
+        let mut camera = Scene3DCamera::default();
+        let start = camera.orientation;
+        let start_position = camera.position;
+
+        let step = std::f32::consts::TAU / 64.0;
+        for _ in 0..64 {
+            camera.orbit(step, 0.0);
+        }
+        assert!(camera.orientation.dot(start).abs() > 0.9999);
+        assert!(camera.position.distance(start_position) < 1e-3);
+
+        // 越过极点的俯仰同样能回到原位
+        for _ in 0..64 {
+            camera.orbit(0.0, step);
+        }
+        assert!(camera.orientation.dot(start).abs() > 0.9999);
+        assert!(camera.position.distance(start_position) < 1e-3);
+    }
+
+    #[test]
+    fn focus_positions_camera_at_distance_from_target() {
+        let mut camera = Scene3DCamera::default();
+        let target = glam::Vec3::new(10.0, 2.0, -4.0);
+        camera.focus_on(target, Scene3DCamera::FOCUS_DISTANCE);
+
+        let mut frames = 0;
+        while camera.update_focus(1.0 / 60.0) {
+            frames += 1;
+            assert!(frames < 600, "focus animation never settled");
+        }
+        assert_eq!(camera.target, target);
+        assert!((camera.position.distance(target) - Scene3DCamera::FOCUS_DISTANCE).abs() < 1e-3);
+        assert!(camera.forward().dot((target - camera.position).normalize()) > 0.9999);
+    }
+
+    #[test]
+    fn zoom_never_passes_through_target() {
+        let mut camera = Scene3DCamera::default();
+        camera.zoom(2.0);
+        assert_eq!(camera.distance, Scene3DCamera::MIN_DISTANCE);
+    }
+
+    #[test]
+    fn gizmo_snapping_rounds_to_configured_increments() {
+        let settings = GizmoSnapSettings { position_step: 0.25, rotation_step: 15.0, scale_step: 0.1 };
+        assert_eq!(settings.snap_position(1.13), 1.25);
+        assert_eq!(settings.snap_position(-0.37), -0.25);
+        assert!((settings.snap_angle(40f32.to_radians()) - 45f32.to_radians()).abs() < 1e-5);
+        assert!((settings.snap_scale(1.26) - 1.3).abs() < 1e-5);
+        // 缩放不会吸附到零
+        assert!((settings.snap_scale(0.01) - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,206 @@
+//! 包围体层次结构(BVH)
+
+use glam::Vec3;
+use crate::math::{Ray, AABB};
+
+/// 叶节点最多包含的图元数
+const MAX_LEAF_SIZE: usize = 2;
+
+/// BVH节点
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounds: AABB,
+    /// 叶节点: 图元在indices中的起始位置；内部节点: 左子节点索引
+    start: usize,
+    /// 叶节点的图元数，内部节点为0
+    count: usize,
+    /// 内部节点的右子节点索引
+    right: usize,
+}
+
+/// 按AABB组织图元的包围体层次结构，用于射线与范围查询
+///
+/// 图元以构建时传入的下标标识。
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    indices: Vec<usize>,
+}
+
+impl Bvh {
+    /// 由图元包围盒构建BVH(沿最长轴按中位数划分)
+    pub fn build(bounds: &[AABB]) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::with_capacity(bounds.len() * 2),
+            indices: (0..bounds.len()).collect(),
+        };
+        if !bounds.is_empty() {
+            bvh.build_node(bounds, 0, bounds.len());
+        }
+        bvh
+    }
+
+    fn build_node(&mut self, bounds: &[AABB], start: usize, end: usize) -> usize {
+        let mut node_bounds = bounds[self.indices[start]];
+        for &index in &self.indices[start + 1..end] {
+            node_bounds.expand_to_include_aabb(&bounds[index]);
+        }
+
+        let node_index = self.nodes.len();
+        self.nodes.push(BvhNode { bounds: node_bounds, start, count: end - start, right: 0 });
+        if end - start <= MAX_LEAF_SIZE {
+            return node_index;
+        }
+
+        // 沿中心点分布最长的轴划分
+        let mut centroid_bounds = AABB::new(bounds[self.indices[start]].center(), bounds[self.indices[start]].center());
+        for &index in &self.indices[start + 1..end] {
+            centroid_bounds.expand_to_include(bounds[index].center());
+        }
+        let size = centroid_bounds.size();
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+
+        let mid = (start + end) / 2;
+        self.indices[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            bounds[a].center()[axis].total_cmp(&bounds[b].center()[axis])
+        });
+
+        let left = self.build_node(bounds, start, mid);
+        let right = self.build_node(bounds, mid, end);
+        let node = &mut self.nodes[node_index];
+        node.start = left;
+        node.count = 0;
+        node.right = right;
+        node_index
+    }
+
+    /// 图元数量
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// 根节点包围盒
+    pub fn bounds(&self) -> Option<AABB> {
+        self.nodes.first().map(|node| node.bounds)
+    }
+
+    /// 可能与射线相交的图元，按射线进入其所在叶节点的距离从近到远排序
+    ///
+    /// 该距离不大于射线到图元本身的距离，可用于提前结束精确测试；射线起点在包围盒内时为0。
+    pub fn ray_candidates(&self, ray: &Ray, max_distance: f32) -> Vec<(usize, f32)> {
+        let mut result = Vec::new();
+        if self.nodes.is_empty() {
+            return result;
+        }
+
+        let inv_dir = Vec3::ONE / ray.direction;
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let Some(distance) = Self::ray_entry(&node.bounds, ray.origin, inv_dir) else {
+                continue;
+            };
+            if distance > max_distance {
+                continue;
+            }
+
+            if node.count > 0 {
+                result.extend(self.indices[node.start..node.start + node.count].iter().map(|&i| (i, distance)));
+            } else {
+                stack.push(node.start);
+                stack.push(node.right);
+            }
+        }
+
+        result.sort_by(|a, b| a.1.total_cmp(&b.1));
+        result
+    }
+
+    /// 包围盒与aabb相交的图元
+    pub fn query_aabb(&self, aabb: &AABB) -> Vec<usize> {
+        let mut result = Vec::new();
+        if self.nodes.is_empty() {
+            return result;
+        }
+
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !node.bounds.intersects(aabb) {
+                continue;
+            }
+            if node.count > 0 {
+                result.extend_from_slice(&self.indices[node.start..node.start + node.count]);
+            } else {
+                stack.push(node.start);
+                stack.push(node.right);
+            }
+        }
+        result
+    }
+
+    /// 射线进入包围盒的距离(slab测试)
+    fn ray_entry(bounds: &AABB, origin: Vec3, inv_dir: Vec3) -> Option<f32> {
+        let t1 = (bounds.min - origin) * inv_dir;
+        let t2 = (bounds.max - origin) * inv_dir;
+        let t_near = t1.min(t2).max_element();
+        let t_far = t1.max(t2).min_element();
+        if t_near > t_far || t_far < 0.0 {
+            None
+        } else {
+            Some(t_near.max(0.0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 沿X轴排列的单位立方体
+    fn boxes(count: usize) -> Vec<AABB> {
+        (0..count)
+            .map(|i| AABB::from_center_size(Vec3::new(i as f32 * 3.0, 0.0, 0.0), Vec3::ONE))
+            .collect()
+    }
+
+    #[test]
+    fn ray_candidates_are_sorted_by_entry_distance() {
+        let bvh = Bvh::build(&boxes(7));
+        assert_eq!(bvh.len(), 7);
+
+        // 从X轴负方向射入，依次穿过所有立方体
+        let ray = Ray::new(Vec3::new(-10.0, 0.0, 0.0), Vec3::X);
+        let candidates = bvh.ray_candidates(&ray, f32::MAX);
+        let mut indices: Vec<_> = candidates.iter().map(|&(index, _)| index).collect();
+        assert!(candidates.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(candidates[0].0, 0);
+        indices.sort();
+        assert_eq!(indices, (0..7).collect::<Vec<_>>());
+
+        // 距离上限之外的叶节点被排除
+        assert!(bvh.ray_candidates(&ray, 15.0).iter().all(|&(index, _)| index < 4));
+
+        let miss = Ray::new(Vec3::new(-10.0, 5.0, 0.0), Vec3::X);
+        assert!(bvh.ray_candidates(&miss, f32::MAX).is_empty());
+    }
+
+    #[test]
+    fn query_aabb_returns_overlapping_primitives() {
+        let bvh = Bvh::build(&boxes(7));
+        let mut hits = bvh.query_aabb(&AABB::from_center_size(Vec3::new(4.5, 0.0, 0.0), Vec3::new(4.0, 1.0, 1.0)));
+        hits.sort();
+        assert_eq!(hits, vec![1, 2]);
+        assert!(Bvh::build(&[]).query_aabb(&AABB::from_center_size(Vec3::ZERO, Vec3::ONE)).is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! 多段颜色渐变
+
+use glam::{Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+
+/// sRGB分量转线性
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// 线性分量转sRGB
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// 渐变关键点，颜色为线性空间RGBA
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    pub position: f32,
+    pub color: Vec4,
+}
+
+impl ColorStop {
+    pub fn new(position: f32, color: Vec4) -> Self {
+        Self { position, color }
+    }
+}
+
+/// 多段颜色渐变，在线性空间插值
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColorRamp {
+    stops: Vec<ColorStop>,
+}
+
+impl ColorRamp {
+    /// 由线性颜色关键点创建，关键点按位置排序
+    pub fn new(stops: impl IntoIterator<Item = ColorStop>) -> Self {
+        let mut ramp = Self { stops: stops.into_iter().collect() };
+        ramp.sort();
+        ramp
+    }
+
+    /// 两色渐变
+    pub fn two_color(start: Vec4, end: Vec4) -> Self {
+        Self::new([ColorStop::new(0.0, start), ColorStop::new(1.0, end)])
+    }
+
+    /// 由sRGB颜色关键点创建(如UI颜色)，插值前转换到线性空间
+    pub fn from_srgb(stops: impl IntoIterator<Item = (f32, Vec4)>) -> Self {
+        Self::new(stops.into_iter().map(|(position, color)| {
+            let linear = Vec3::new(
+                srgb_to_linear(color.x),
+                srgb_to_linear(color.y),
+                srgb_to_linear(color.z),
+            );
+            ColorStop::new(position, linear.extend(color.w))
+        }))
+    }
+
+    /// 添加关键点
+    pub fn add_stop(&mut self, position: f32, color: Vec4) {
+        self.stops.push(ColorStop::new(position, color));
+        self.sort();
+    }
+
+    /// 所有关键点(按位置排序)
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty()
+    }
+
+    /// 计算t处的线性颜色，超出范围时取两端颜色，没有关键点时为白色
+    pub fn evaluate(&self, t: f32) -> Vec4 {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Vec4::ONE,
+        };
+        if t <= first.position {
+            return first.color;
+        }
+        if t >= last.position {
+            return last.color;
+        }
+
+        // 第一个位置大于t的关键点即为所在区间的终点
+        let end = self.stops.partition_point(|stop| stop.position <= t);
+        let (a, b) = (self.stops[end - 1], self.stops[end]);
+        let span = b.position - a.position;
+        if span <= f32::EPSILON {
+            return b.color;
+        }
+        a.color.lerp(b.color, (t - a.position) / span)
+    }
+
+    /// 计算t处的颜色并转换回sRGB
+    pub fn evaluate_srgb(&self, t: f32) -> Vec4 {
+        let color = self.evaluate(t);
+        Vec4::new(
+            linear_to_srgb(color.x),
+            linear_to_srgb(color.y),
+            linear_to_srgb(color.z),
+            color.w,
+        )
+    }
+
+    fn sort(&mut self) {
+        self.stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_stop_ramp_interpolates_linearly() {
+        let ramp = ColorRamp::two_color(Vec4::new(0.0, 0.0, 1.0, 1.0), Vec4::new(1.0, 0.5, 0.0, 0.0));
+
+        assert_eq!(ramp.evaluate(0.0), Vec4::new(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(ramp.evaluate(0.5), Vec4::new(0.5, 0.25, 0.5, 0.5));
+        assert_eq!(ramp.evaluate(1.0), Vec4::new(1.0, 0.5, 0.0, 0.0));
+        // 超出范围取两端颜色
+        assert_eq!(ramp.evaluate(-1.0), ramp.evaluate(0.0));
+        assert_eq!(ramp.evaluate(2.0), ramp.evaluate(1.0));
+    }
+
+    #[test]
+    fn three_stop_ramp_picks_segment() {
+        // 乱序添加的关键点会被排序
+        let ramp = ColorRamp::new([
+            ColorStop::new(1.0, Vec4::splat(0.0)),
+            ColorStop::new(0.0, Vec4::splat(1.0)),
+            ColorStop::new(0.25, Vec4::splat(0.5)),
+        ]);
+
+        assert_eq!(ramp.stops()[1].position, 0.25);
+        assert_eq!(ramp.evaluate(0.125), Vec4::splat(0.75));
+        assert_eq!(ramp.evaluate(0.25), Vec4::splat(0.5));
+        assert_eq!(ramp.evaluate(0.625), Vec4::splat(0.25));
+    }
+
+    #[test]
+    fn srgb_ramp_interpolates_in_linear_space() {
+        let ramp = ColorRamp::from_srgb([(0.0, Vec4::new(0.0, 0.0, 0.0, 1.0)), (1.0, Vec4::ONE)]);
+
+        // 线性空间的中点在sRGB中比0.5更亮
+        let middle = ramp.evaluate_srgb(0.5);
+        assert!((middle.x - 0.735).abs() < 1e-3);
+        assert_eq!(middle.w, 1.0);
+        assert!((ramp.evaluate_srgb(1.0).x - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn empty_ramp_is_white() {
+        assert_eq!(ColorRamp::default().evaluate(0.5), Vec4::ONE);
+    }
+}
@@ -0,0 +1,225 @@
+//! 坐标系约定
+//!
+//! 引擎统一使用右手坐标系：+Y向上，+X向右，物体与相机的前方为-Z。
+//! 投影矩阵输出wgpu的[0, 1]深度范围，欧拉角按YXZ顺序(先偏航、再俯仰、最后翻滚)，编辑器与序列化中以角度表示，
+//! 一律通过 `euler_to_quat` / `quat_to_euler` 转换，不要直接使用 `EulerRot`。
+//! 新增的数学代码应通过 `Coordinate` 获取方向与矩阵，而不是直接调用 `*_rh` / `*_lh` 函数。
+
+use glam::{EulerRot, Mat3, Mat4, Quat, Vec3};
+
+/// 坐标系约定
+pub struct Coordinate;
+
+impl Coordinate {
+    /// 前方向(-Z)
+    pub const FORWARD: Vec3 = Vec3::NEG_Z;
+    /// 上方向(+Y)
+    pub const UP: Vec3 = Vec3::Y;
+    /// 右方向(+X)
+    pub const RIGHT: Vec3 = Vec3::X;
+    /// 欧拉角旋转顺序
+    pub const EULER_ORDER: EulerRot = EulerRot::YXZ;
+
+    pub fn forward() -> Vec3 {
+        Self::FORWARD
+    }
+
+    pub fn up() -> Vec3 {
+        Self::UP
+    }
+
+    pub fn right() -> Vec3 {
+        Self::RIGHT
+    }
+
+    /// 视图矩阵：从eye看向target
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Mat4 {
+        Self::look_to(eye, target - eye, up)
+    }
+
+    /// 视图矩阵：从eye沿direction观察，direction与up平行时自动换用其他参考轴
+    pub fn look_to(eye: Vec3, direction: Vec3, up: Vec3) -> Mat4 {
+        Mat4::look_to_rh(eye, direction, Self::safe_up(direction, up))
+    }
+
+    /// 透视投影矩阵，fovy为弧度
+    pub fn perspective(fovy: f32, aspect_ratio: f32, near: f32, far: f32) -> Mat4 {
+        Mat4::perspective_rh(fovy, aspect_ratio, near, far)
+    }
+
+    /// 正交投影矩阵
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+        Mat4::orthographic_rh(left, right, bottom, top, near, far)
+    }
+
+    /// 使前方向朝向direction的旋转，direction为零向量时返回单位旋转
+    pub fn rotation_looking_to(direction: Vec3, up: Vec3) -> Quat {
+        let forward = direction.normalize_or_zero();
+        if forward == Vec3::ZERO {
+            return Quat::IDENTITY;
+        }
+
+        let right = forward.cross(Self::safe_up(forward, up)).normalize();
+        let true_up = right.cross(forward);
+        Quat::from_mat3(&Mat3::from_cols(right, true_up, -forward)).normalize()
+    }
+
+    /// 由角度表示的欧拉角(x=俯仰, y=偏航, z=翻滚)创建旋转
+    pub fn rotation_from_euler_degrees(degrees: Vec3) -> Quat {
+        euler_to_quat(Vec3::new(degrees.x.to_radians(), degrees.y.to_radians(), degrees.z.to_radians()))
+    }
+
+    /// 旋转对应的角度欧拉角(x=俯仰, y=偏航, z=翻滚)
+    pub fn euler_degrees(rotation: Quat) -> Vec3 {
+        let radians = quat_to_euler(rotation);
+        Vec3::new(radians.x.to_degrees(), radians.y.to_degrees(), radians.z.to_degrees())
+    }
+
+    /// direction与up接近平行时换用其他参考轴，避免视图矩阵退化
+    fn safe_up(direction: Vec3, up: Vec3) -> Vec3 {
+        let direction = direction.normalize_or_zero();
+        if direction.cross(up).length_squared() >= 1e-6 {
+            up
+        } else if direction.y.abs() < 0.99 {
+            Self::UP
+        } else {
+            // 竖直方向时以后方(+Z)作参考
+            -Self::FORWARD
+        }
+    }
+}
+
+/// 由弧度欧拉角(x=俯仰, y=偏航, z=翻滚)按 `Coordinate::EULER_ORDER` 创建旋转
+///
+/// YXZ顺序即先绕Y偏航、再绕局部X俯仰、最后绕局部Z翻滚。引擎内所有欧拉角转换都应经过这里。
+pub fn euler_to_quat(euler: Vec3) -> Quat {
+    Quat::from_euler(Coordinate::EULER_ORDER, euler.y, euler.x, euler.z)
+}
+
+/// 旋转对应的弧度欧拉角(x=俯仰, y=偏航, z=翻滚)，俯仰范围为[-90°, 90°]
+pub fn quat_to_euler(rotation: Quat) -> Vec3 {
+    let (yaw, pitch, roll) = rotation.normalize().to_euler(Coordinate::EULER_ORDER);
+    Vec3::new(pitch, yaw, roll)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Camera;
+
+    fn assert_vec_eq(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn basis_is_right_handed() {
+        assert_eq!(Coordinate::forward(), Coordinate::FORWARD);
+        assert_eq!(Coordinate::right().dot(Coordinate::up()), 0.0);
+        assert_eq!(Coordinate::right().dot(Coordinate::forward()), 0.0);
+        // 右手系：右 × 上 = 后方
+        assert_eq!(Coordinate::right().cross(Coordinate::up()), -Coordinate::forward());
+        assert_eq!(Mat3::from_cols(Coordinate::RIGHT, Coordinate::UP, -Coordinate::FORWARD).determinant(), 1.0);
+    }
+
+    #[test]
+    fn look_at_places_target_in_front() {
+        let eye = Vec3::new(3.0, 2.0, 5.0);
+        let target = Vec3::new(-1.0, 0.5, -2.0);
+        let view = Coordinate::look_at(eye, target, Coordinate::UP);
+        let in_view = view.transform_point3(target);
+        assert!(in_view.x.abs() < 1e-4 && in_view.y.abs() < 1e-4);
+        assert_vec_eq(in_view.normalize(), Coordinate::FORWARD);
+
+        // 竖直观察时不退化
+        let down = Coordinate::look_to(eye, Vec3::NEG_Y, Coordinate::UP);
+        assert!(down.is_finite());
+        assert_vec_eq(down.transform_vector3(Vec3::NEG_Y), Coordinate::FORWARD);
+    }
+
+    #[test]
+    fn projections_map_forward_range_to_zero_one_depth() {
+        let perspective = Coordinate::perspective(1.0, 1.5, 0.5, 50.0);
+        assert!(perspective.project_point3(Coordinate::FORWARD * 0.5).z.abs() < 1e-5);
+        assert!((perspective.project_point3(Coordinate::FORWARD * 50.0).z - 1.0).abs() < 1e-5);
+
+        let orthographic = Coordinate::orthographic(-1.0, 1.0, -1.0, 1.0, 0.5, 50.0);
+        assert!(orthographic.transform_point3(Coordinate::FORWARD * 0.5).z.abs() < 1e-5);
+        assert!((orthographic.transform_point3(Coordinate::RIGHT).x - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rotation_looking_to_turns_forward_to_direction() {
+        for direction in [Vec3::X, Vec3::new(1.0, 2.0, -3.0), Vec3::Y, Vec3::NEG_Y] {
+            let rotation = Coordinate::rotation_looking_to(direction, Coordinate::UP);
+            assert_vec_eq(rotation * Coordinate::FORWARD, direction.normalize());
+        }
+        assert_eq!(Coordinate::rotation_looking_to(Vec3::ZERO, Coordinate::UP), Quat::IDENTITY);
+    }
+
+    #[test]
+    fn euler_angles_use_yaw_pitch_roll_order() {
+        // 偏航90°使前方从-Z转到-X
+        let yaw = Coordinate::rotation_from_euler_degrees(Vec3::new(0.0, 90.0, 0.0));
+        assert_vec_eq(yaw * Coordinate::FORWARD, Vec3::NEG_X);
+
+        let degrees = Vec3::new(30.0, -45.0, 10.0);
+        assert_vec_eq(Coordinate::euler_degrees(Coordinate::rotation_from_euler_degrees(degrees)), degrees);
+        assert_vec_eq(quat_to_euler(euler_to_quat(Vec3::new(0.2, 1.1, -0.4))), Vec3::new(0.2, 1.1, -0.4));
+    }
+
+    #[test]
+    fn camera_matrices_follow_convention() {
+        let mut camera = Camera::default();
+        camera.position = Vec3::new(4.0, 1.0, -2.0);
+        camera.look_at(Vec3::new(0.0, 3.0, 1.0), Coordinate::UP);
+
+        let expected = Coordinate::look_to(camera.position, camera.forward(), Coordinate::UP);
+        assert!(camera.view_matrix().abs_diff_eq(expected, 1e-4));
+        assert!(camera.projection_matrix().abs_diff_eq(
+            Coordinate::perspective(camera.fovy, camera.aspect_ratio, camera.near_plane, camera.far_plane),
+            1e-6,
+        ));
+    }
+
+    #[test]
+    fn euler_round_trips_for_several_angles() {
+        let samples = [
+            Vec3::ZERO,
+            Vec3::new(0.5, 0.0, 0.0),
+            Vec3::new(0.0, -2.5, 0.0),
+            Vec3::new(0.0, 0.0, 3.0),
+            Vec3::new(-1.2, 2.0, 0.7),
+            Vec3::new(1.5, -0.3, -2.9),
+        ];
+        for euler in samples {
+            let rotation = euler_to_quat(euler);
+            // 与文档中的YXZ顺序一致：先偏航，再绕局部X俯仰，最后绕局部Z翻滚
+            let expected = Quat::from_rotation_y(euler.y) * Quat::from_rotation_x(euler.x) * Quat::from_rotation_z(euler.z);
+            assert!(rotation.abs_diff_eq(expected, 1e-5) || rotation.abs_diff_eq(-expected, 1e-5));
+            assert_vec_eq(quat_to_euler(rotation), euler);
+        }
+    }
+
+    #[test]
+    fn euler_outside_pitch_range_gives_same_rotation() {
+        // 俯仰超过90°时欧拉角不唯一，但转换回的旋转相同
+        let euler = Vec3::new(2.0, 0.4, 0.1);
+        let rotation = euler_to_quat(euler);
+        let back = quat_to_euler(rotation);
+        assert!(back.x.abs() <= std::f32::consts::FRAC_PI_2 + 1e-5);
+        assert!(euler_to_quat(back).dot(rotation).abs() > 1.0 - 1e-5);
+    }
+
+    #[test]
+    fn transform_and_camera_share_euler_convention() {
+        let degrees = Vec3::new(-20.0, 135.0, 5.0);
+        let mut transform = crate::ecs::Transform::default();
+        transform.set_euler_degrees(degrees);
+        let mut camera = Camera::default();
+        camera.set_euler_degrees(degrees);
+
+        assert!(transform.rotation.abs_diff_eq(camera.rotation, 1e-6));
+        assert_vec_eq(transform.euler_degrees(), degrees);
+        assert_vec_eq(camera.euler_degrees(), degrees);
+    }
+}
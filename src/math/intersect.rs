@@ -2,6 +2,7 @@
 
 use glam::Vec3;
 use crate::math::bounds::{AABB, BoundingSphere};
+use crate::math::frustum::Plane;
 use crate::math::ray::{Ray, RayHit};
 
 /// 点与几何体相交检测
@@ -297,3 +298,205 @@ impl ClosestPoint {
         (point1, point2)
     }
 }
+
+/// 平行判定的阈值
+const PARALLEL_EPSILON: f32 = 1e-6;
+
+/// 线段与AABB相交，返回线段进入AABB的位置
+///
+/// `distance` 为从起点量起的长度；起点在AABB内部时返回起点，距离为0、法线为零向量。
+/// 退化线段(起点终点重合)按点处理。
+pub fn segment_vs_aabb(start: Vec3, end: Vec3, aabb: &AABB) -> Option<RayHit> {
+    let delta = end - start;
+    let mut t_enter = 0.0f32;
+    let mut t_exit = 1.0f32;
+    let mut normal = Vec3::ZERO;
+
+    for axis in 0..3 {
+        if delta[axis].abs() < PARALLEL_EPSILON {
+            // 与该轴的两个平面平行，起点必须在板内
+            if start[axis] < aabb.min[axis] || start[axis] > aabb.max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let inv = 1.0 / delta[axis];
+        let mut t_near = (aabb.min[axis] - start[axis]) * inv;
+        let mut t_far = (aabb.max[axis] - start[axis]) * inv;
+        let mut axis_normal = Vec3::ZERO;
+        axis_normal[axis] = -delta[axis].signum();
+        if t_near > t_far {
+            std::mem::swap(&mut t_near, &mut t_far);
+        }
+
+        if t_near > t_enter {
+            t_enter = t_near;
+            normal = axis_normal;
+        }
+        t_exit = t_exit.min(t_far);
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+
+    Some(RayHit {
+        point: start + delta * t_enter,
+        normal,
+        distance: delta.length() * t_enter,
+    })
+}
+
+/// 射线与平面相交，射线平行于平面(包括位于平面内)或平面在射线后方时返回None
+pub fn ray_vs_plane(ray: &Ray, plane: &Plane) -> Option<RayHit> {
+    let denom = plane.normal.dot(ray.direction);
+    if denom.abs() < PARALLEL_EPSILON {
+        return None;
+    }
+
+    let t = -plane.distance_to_point(ray.origin) / denom;
+    (t >= 0.0).then(|| RayHit {
+        point: ray.point_at(t),
+        normal: plane.normal,
+        distance: t,
+    })
+}
+
+/// 射线与三角形相交(Möller–Trumbore)，双面检测，擦过边或顶点也算相交
+///
+/// 法线按v0、v1、v2的逆时针顺序计算，不随射线方向翻转。
+pub fn ray_vs_triangle(ray: &Ray, v0: Vec3, v1: Vec3, v2: Vec3) -> Option<RayHit> {
+    // 边界容差，避免恰好落在边上的射线因舍入误差漏检
+    const EDGE_EPSILON: f32 = 1e-6;
+
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+    let h = ray.direction.cross(edge2);
+    let det = edge1.dot(h);
+    if det.abs() < 1e-8 {
+        return None;
+    }
+
+    let inv_det = 1.0 / det;
+    let s = ray.origin - v0;
+    let u = inv_det * s.dot(h);
+    if u < -EDGE_EPSILON || u > 1.0 + EDGE_EPSILON {
+        return None;
+    }
+
+    let q = s.cross(edge1);
+    let v = inv_det * ray.direction.dot(q);
+    if v < -EDGE_EPSILON || u + v > 1.0 + EDGE_EPSILON {
+        return None;
+    }
+
+    let t = inv_det * edge2.dot(q);
+    (t > 1e-8).then(|| RayHit {
+        point: ray.point_at(t),
+        normal: edge1.cross(edge2).normalize(),
+        distance: t,
+    })
+}
+
+/// 球体与AABB相交，返回AABB上离球心最近的点(球心在AABB内时即球心)
+pub fn sphere_vs_aabb(sphere: &BoundingSphere, aabb: &AABB) -> Option<Vec3> {
+    let closest = aabb.closest_point(sphere.center);
+    ((closest - sphere.center).length_squared() <= sphere.radius * sphere.radius).then_some(closest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_box() -> AABB {
+        AABB::new(Vec3::splat(-1.0), Vec3::splat(1.0))
+    }
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        (a - b).length() < 1e-5
+    }
+
+    #[test]
+    fn segment_enters_box_on_near_face() {
+        let hit = segment_vs_aabb(Vec3::new(-3.0, 0.5, 0.0), Vec3::new(3.0, 0.5, 0.0), &unit_box()).unwrap();
+        assert!(close(hit.point, Vec3::new(-1.0, 0.5, 0.0)));
+        assert_eq!(hit.normal, Vec3::NEG_X);
+        assert!((hit.distance - 2.0).abs() < 1e-5);
+
+        // 反方向从+Y面进入
+        let hit = segment_vs_aabb(Vec3::new(0.0, 4.0, 0.0), Vec3::ZERO, &unit_box()).unwrap();
+        assert_eq!(hit.normal, Vec3::Y);
+        assert!((hit.distance - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn segment_misses_short_parallel_and_inside_cases() {
+        // 在到达盒子之前结束
+        assert!(segment_vs_aabb(Vec3::new(-3.0, 0.0, 0.0), Vec3::new(-1.5, 0.0, 0.0), &unit_box()).is_none());
+        // 平行于X轴但在板外
+        assert!(segment_vs_aabb(Vec3::new(-3.0, 2.0, 0.0), Vec3::new(3.0, 2.0, 0.0), &unit_box()).is_none());
+
+        let inside = segment_vs_aabb(Vec3::new(0.5, 0.0, 0.0), Vec3::new(5.0, 0.0, 0.0), &unit_box()).unwrap();
+        assert_eq!((inside.point, inside.normal, inside.distance), (Vec3::new(0.5, 0.0, 0.0), Vec3::ZERO, 0.0));
+
+        // 退化线段按点处理
+        assert!(segment_vs_aabb(Vec3::ZERO, Vec3::ZERO, &unit_box()).is_some());
+        assert!(segment_vs_aabb(Vec3::splat(2.0), Vec3::splat(2.0), &unit_box()).is_none());
+    }
+
+    #[test]
+    fn ray_hits_plane_in_front_only() {
+        let ground = Plane::from_normal_and_point(Vec3::Y, Vec3::new(0.0, 1.0, 0.0));
+        let hit = ray_vs_plane(&Ray::new(Vec3::new(2.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 1.0)), &ground).unwrap();
+        assert!(close(hit.point, Vec3::new(2.0, 1.0, 4.0)));
+        assert_eq!(hit.normal, Vec3::Y);
+        assert!((hit.distance - 4.0 * 2.0f32.sqrt()).abs() < 1e-4);
+
+        // 平面在射线后方
+        assert!(ray_vs_plane(&Ray::new(Vec3::new(0.0, 5.0, 0.0), Vec3::Y), &ground).is_none());
+        // 平行，包括位于平面内
+        assert!(ray_vs_plane(&Ray::new(Vec3::new(0.0, 5.0, 0.0), Vec3::X), &ground).is_none());
+        assert!(ray_vs_plane(&Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::Z), &ground).is_none());
+    }
+
+    #[test]
+    fn ray_hits_triangle_from_both_sides() {
+        let (v0, v1, v2) = (Vec3::ZERO, Vec3::X, Vec3::Y);
+        let hit = ray_vs_triangle(&Ray::new(Vec3::new(0.25, 0.25, 2.0), Vec3::NEG_Z), v0, v1, v2).unwrap();
+        assert!(close(hit.point, Vec3::new(0.25, 0.25, 0.0)));
+        assert!((hit.distance - 2.0).abs() < 1e-5);
+        assert_eq!(hit.normal, Vec3::Z);
+
+        // 背面同样命中，法线不翻转
+        let hit = ray_vs_triangle(&Ray::new(Vec3::new(0.25, 0.25, -2.0), Vec3::Z), v0, v1, v2).unwrap();
+        assert_eq!(hit.normal, Vec3::Z);
+
+        // 三角形外、平行、在射线后方
+        assert!(ray_vs_triangle(&Ray::new(Vec3::new(0.75, 0.75, 2.0), Vec3::NEG_Z), v0, v1, v2).is_none());
+        assert!(ray_vs_triangle(&Ray::new(Vec3::new(0.25, 0.25, 0.0), Vec3::X), v0, v1, v2).is_none());
+        assert!(ray_vs_triangle(&Ray::new(Vec3::new(0.25, 0.25, 2.0), Vec3::Z), v0, v1, v2).is_none());
+    }
+
+    #[test]
+    fn ray_grazing_triangle_edge_and_vertex_hits() {
+        let (v0, v1, v2) = (Vec3::ZERO, Vec3::X, Vec3::Y);
+        // 斜边 u + v = 1
+        assert!(ray_vs_triangle(&Ray::new(Vec3::new(0.5, 0.5, 1.0), Vec3::NEG_Z), v0, v1, v2).is_some());
+        // 底边 v = 0
+        assert!(ray_vs_triangle(&Ray::new(Vec3::new(0.3, 0.0, 1.0), Vec3::NEG_Z), v0, v1, v2).is_some());
+        assert!(ray_vs_triangle(&Ray::new(Vec3::new(1.0, 0.0, 1.0), Vec3::NEG_Z), v0, v1, v2).is_some());
+        // 刚好越过边
+        assert!(ray_vs_triangle(&Ray::new(Vec3::new(0.3, -0.001, 1.0), Vec3::NEG_Z), v0, v1, v2).is_none());
+    }
+
+    #[test]
+    fn sphere_vs_aabb_returns_closest_point() {
+        let aabb = unit_box();
+        assert_eq!(sphere_vs_aabb(&BoundingSphere::new(Vec3::new(1.5, 0.0, 0.0), 0.6), &aabb), Some(Vec3::new(1.0, 0.0, 0.0)));
+        // 球心在盒子内
+        assert_eq!(sphere_vs_aabb(&BoundingSphere::new(Vec3::new(0.2, 0.3, 0.0), 0.1), &aabb), Some(Vec3::new(0.2, 0.3, 0.0)));
+        // 靠近角但未接触：到角的距离为sqrt(0.75) > 0.8
+        assert!(sphere_vs_aabb(&BoundingSphere::new(Vec3::splat(1.5), 0.8), &aabb).is_none());
+        assert_eq!(sphere_vs_aabb(&BoundingSphere::new(Vec3::splat(1.5), 0.9), &aabb), Some(Vec3::ONE));
+    }
+}
@@ -1,18 +1,27 @@
 //! 数学工具库模块
 
 pub mod bounds;
+pub mod bvh;
+pub mod coordinate;
 pub mod ray;
 pub mod frustum;
 pub mod intersect;
 pub mod noise;
 pub mod easing;
+pub mod color_ramp;
+pub mod spline;
+pub mod serde_helpers;
 
 pub use bounds::*;
+pub use bvh::*;
+pub use coordinate::*;
 pub use ray::*;
 pub use frustum::*;
 pub use intersect::*;
 pub use noise::*;
 pub use easing::*;
+pub use color_ramp::*;
+pub use spline::*;
 
 // 重新导出glam的常用类型
 pub use glam::{
@@ -83,6 +92,15 @@ pub fn smootherstep(edge0: f32, edge1: f32, x: f32) -> f32 {
     t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
 }
 
+/// 吸附到最近的step整数倍，step<=0时原样返回
+pub fn snap(value: f32, step: f32) -> f32 {
+    if step <= 0.0 {
+        value
+    } else {
+        (value / step).round() * step
+    }
+}
+
 /// Vec3扩展trait
 pub trait Vec3Ext {
     /// 创建随机单位向量
@@ -142,3 +160,20 @@ impl Vec3Ext for Vec3 {
         r_out_perp + r_out_parallel
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_rounds_to_nearest_step() {
+        assert_eq!(snap(1.26, 0.5), 1.5);
+        assert_eq!(snap(1.24, 0.5), 1.0);
+        assert_eq!(snap(-0.8, 0.5), -1.0);
+        assert_eq!(snap(37.0, 15.0), 30.0);
+        assert_eq!(snap(7.0, 5.0), 5.0);
+        // 非正步长不吸附
+        assert_eq!(snap(1.26, 0.0), 1.26);
+        assert_eq!(snap(1.26, -1.0), 1.26);
+    }
+}
@@ -161,6 +161,29 @@ impl Ray {
         }
     }
 
+    /// 与三角形网格相交测试，返回最近的击中
+    ///
+    /// `indices` 每三个一组构成三角形，越界的三角形会被忽略。
+    pub fn intersect_mesh(&self, positions: &[Vec3], indices: &[u32]) -> Option<RayHit> {
+        let mut closest: Option<RayHit> = None;
+        for triangle in indices.chunks_exact(3) {
+            let (Some(&v0), Some(&v1), Some(&v2)) = (
+                positions.get(triangle[0] as usize),
+                positions.get(triangle[1] as usize),
+                positions.get(triangle[2] as usize),
+            ) else {
+                continue;
+            };
+
+            if let Some(hit) = self.intersect_triangle(v0, v1, v2) {
+                if closest.map_or(true, |c| hit.distance < c.distance) {
+                    closest = Some(hit);
+                }
+            }
+        }
+        closest
+    }
+
     /// 变换射线
     pub fn transform(&self, matrix: &Mat4) -> Self {
         let transformed_origin = matrix.transform_point3(self.origin);
@@ -261,3 +284,35 @@ impl RayFromScreen {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersect_mesh_returns_nearest_triangle() {
+        // z=0与z=-2两个正方形，各两个三角形
+        let positions = vec![
+            Vec3::new(-1.0, -1.0, 0.0), Vec3::new(1.0, -1.0, 0.0), Vec3::new(1.0, 1.0, 0.0), Vec3::new(-1.0, 1.0, 0.0),
+            Vec3::new(-1.0, -1.0, -2.0), Vec3::new(1.0, -1.0, -2.0), Vec3::new(1.0, 1.0, -2.0), Vec3::new(-1.0, 1.0, -2.0),
+        ];
+        let indices = vec![4, 5, 6, 6, 7, 4, 0, 1, 2, 2, 3, 0];
+
+        let ray = Ray::new(Vec3::new(0.25, 0.25, 5.0), Vec3::NEG_Z);
+        let hit = ray.intersect_mesh(&positions, &indices).unwrap();
+        assert!((hit.distance - 5.0).abs() < 1e-5);
+        assert!((hit.point - Vec3::new(0.25, 0.25, 0.0)).length() < 1e-5);
+
+        let miss = Ray::new(Vec3::new(3.0, 0.0, 5.0), Vec3::NEG_Z);
+        assert!(miss.intersect_mesh(&positions, &indices).is_none());
+    }
+
+    #[test]
+    fn intersect_mesh_skips_out_of_range_indices() {
+        let positions = vec![Vec3::new(-1.0, -1.0, 0.0), Vec3::new(1.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0)];
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::NEG_Z);
+        assert!(ray.intersect_mesh(&positions, &[0, 1, 9]).is_none());
+        // 不足三个的尾部索引被忽略
+        assert!(ray.intersect_mesh(&positions, &[0, 1, 9, 0, 1, 2, 0]).is_some());
+    }
+}
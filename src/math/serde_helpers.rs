@@ -0,0 +1,149 @@
+//! glam类型的serde适配器
+//!
+//! glam启用了serde特性，默认即可序列化。这里的适配器把向量、四元数和矩阵
+//! 固定编码为定长浮点数组，在JSON、Binary、MessagePack和YAML中表现一致，
+//! 并且不依赖glam内部的序列化格式。用法：
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Data {
+//!     #[serde(with = "crate::math::serde_helpers::vec3")]
+//!     position: Vec3,
+//!     #[serde(with = "crate::math::serde_helpers::quat_euler_degrees")]
+//!     rotation: Quat,
+//! }
+//! ```
+
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! array_adapter {
+    ($module:ident, $ty:ty, $len:expr, $to_array:ident, |$array:ident| $from_array:expr) => {
+        #[doc = concat!("`", stringify!($ty), "` 编码为 `[f32; ", stringify!($len), "]`")]
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<S: Serializer>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                value.$to_array().serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                let $array = <[f32; $len]>::deserialize(deserializer)?;
+                Ok($from_array)
+            }
+        }
+    };
+}
+
+array_adapter!(vec2, Vec2, 2, to_array, |array| Vec2::from_array(array));
+array_adapter!(vec3, Vec3, 3, to_array, |array| Vec3::from_array(array));
+array_adapter!(vec4, Vec4, 4, to_array, |array| Vec4::from_array(array));
+array_adapter!(quat, Quat, 4, to_array, |array| Quat::from_array(array));
+array_adapter!(mat3, Mat3, 9, to_cols_array, |array| Mat3::from_cols_array(&array));
+array_adapter!(mat4, Mat4, 16, to_cols_array, |array| Mat4::from_cols_array(&array));
+
+/// 四元数编码为YXZ顺序的欧拉角(度)，便于手工编辑，会有浮点精度损失
+pub mod quat_euler_degrees {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Quat, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::math::Coordinate::euler_degrees(*value).to_array().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Quat, D::Error> {
+        let degrees = <[f32; 3]>::deserialize(deserializer)?;
+        Ok(crate::math::Coordinate::rotation_from_euler_degrees(Vec3::from_array(degrees)))
+    }
+}
+
+/// `Option<Vec3>` 编码为 `Option<[f32; 3]>`
+pub mod option_vec3 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<Vec3>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.map(|v| v.to_array()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec3>, D::Error> {
+        let array = Option::<[f32; 3]>::deserialize(deserializer)?;
+        Ok(array.map(Vec3::from_array))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialization::{SerializationContext, SerializationFormat, SerializationManager};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct GlamData {
+        #[serde(with = "vec3")]
+        position: Vec3,
+        #[serde(with = "quat")]
+        rotation: Quat,
+        #[serde(with = "mat4")]
+        matrix: Mat4,
+        #[serde(with = "option_vec3")]
+        pivot: Option<Vec3>,
+    }
+
+    fn sample() -> GlamData {
+        let rotation = Quat::from_rotation_y(0.3) * Quat::from_rotation_x(-1.1);
+        let position = Vec3::new(0.1, -2.5, 1e-7);
+        GlamData {
+            position,
+            rotation,
+            matrix: Mat4::from_scale_rotation_translation(Vec3::new(1.5, 0.2, 3.0), rotation, position),
+            pivot: Some(Vec3::new(f32::MAX, f32::MIN_POSITIVE, -0.0)),
+        }
+    }
+
+    fn manager_round_trip(format: SerializationFormat) -> GlamData {
+        let manager = SerializationManager::new();
+        let context = SerializationContext { format, include_metadata: false, ..Default::default() };
+        let bytes = manager.serialize(&sample(), Some(&context)).unwrap();
+        manager.deserialize(&bytes, Some(&context)).unwrap()
+    }
+
+    #[test]
+    fn round_trips_exactly_in_json() {
+        assert_eq!(manager_round_trip(SerializationFormat::Json), sample());
+    }
+
+    #[test]
+    fn round_trips_exactly_in_binary() {
+        assert_eq!(manager_round_trip(SerializationFormat::Binary), sample());
+    }
+
+    #[test]
+    fn round_trips_exactly_in_message_pack() {
+        let bytes = rmp_serde::to_vec(&sample()).unwrap();
+        assert_eq!(rmp_serde::from_slice::<GlamData>(&bytes).unwrap(), sample());
+    }
+
+    #[test]
+    fn round_trips_exactly_in_yaml() {
+        let text = serde_yaml::to_string(&sample()).unwrap();
+        assert_eq!(serde_yaml::from_str::<GlamData>(&text).unwrap(), sample());
+    }
+
+    #[test]
+    fn encodes_as_plain_arrays() {
+        let json = serde_json::to_value(GlamData { pivot: None, ..sample() }).unwrap();
+        assert_eq!(json["position"].as_array().unwrap().len(), 3);
+        assert_eq!(json["rotation"].as_array().unwrap().len(), 4);
+        assert_eq!(json["matrix"].as_array().unwrap().len(), 16);
+        assert!(json["pivot"].is_null());
+    }
+
+    #[test]
+    fn euler_adapter_preserves_rotation() {
+        #[derive(Serialize, Deserialize)]
+        struct Rotation(#[serde(with = "quat_euler_degrees")] Quat);
+
+        let rotation = Quat::from_rotation_y(0.5) * Quat::from_rotation_x(0.25);
+        let json = serde_json::to_string(&Rotation(rotation)).unwrap();
+        let restored: Rotation = serde_json::from_str(&json).unwrap();
+        assert!(restored.0.dot(rotation).abs() > 0.9999);
+    }
+}
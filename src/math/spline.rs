@@ -0,0 +1,230 @@
+//! Catmull-Rom样条
+//!
+//! 曲线经过所有控制点，按弧长预先采样，便于以恒定速度沿路径移动。
+
+use glam::Vec3;
+
+/// 每段曲线的弧长采样数
+const SAMPLES_PER_SEGMENT: usize = 32;
+
+/// Catmull-Rom样条曲线
+#[derive(Debug, Clone)]
+pub struct Spline {
+    points: Vec<Vec3>,
+    closed: bool,
+    /// 参数 i / (采样总数) 处的累计弧长
+    arc_lengths: Vec<f32>,
+}
+
+impl Spline {
+    /// 创建开放样条，曲线从第一个控制点开始到最后一个结束
+    pub fn new(points: impl IntoIterator<Item = Vec3>) -> Self {
+        let mut spline = Self {
+            points: points.into_iter().collect(),
+            closed: false,
+            arc_lengths: Vec::new(),
+        };
+        spline.rebuild();
+        spline
+    }
+
+    /// 创建闭合样条，最后一个控制点连回第一个
+    pub fn closed(points: impl IntoIterator<Item = Vec3>) -> Self {
+        let mut spline = Self {
+            points: points.into_iter().collect(),
+            closed: true,
+            arc_lengths: Vec::new(),
+        };
+        spline.rebuild();
+        spline
+    }
+
+    /// 控制点
+    pub fn points(&self) -> &[Vec3] {
+        &self.points
+    }
+
+    /// 修改控制点后重新计算弧长
+    pub fn set_points(&mut self, points: impl IntoIterator<Item = Vec3>) {
+        self.points = points.into_iter().collect();
+        self.rebuild();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// 曲线段数
+    pub fn segment_count(&self) -> usize {
+        match self.points.len() {
+            0 | 1 => 0,
+            n if self.closed => n,
+            n => n - 1,
+        }
+    }
+
+    /// 曲线总长度
+    pub fn length(&self) -> f32 {
+        self.arc_lengths.last().copied().unwrap_or(0.0)
+    }
+
+    /// 参数t(0~1，覆盖整条曲线)处的位置
+    pub fn sample(&self, t: f32) -> Vec3 {
+        match self.points.len() {
+            0 => Vec3::ZERO,
+            1 => self.points[0],
+            _ => {
+                let (segment, local_t) = self.locate(t);
+                let [p0, p1, p2, p3] = self.segment_points(segment);
+                catmull_rom(p0, p1, p2, p3, local_t)
+            }
+        }
+    }
+
+    /// 参数t处的单位切线
+    pub fn tangent(&self, t: f32) -> Vec3 {
+        if self.points.len() < 2 {
+            return Vec3::ZERO;
+        }
+        let (segment, local_t) = self.locate(t);
+        let [p0, p1, p2, p3] = self.segment_points(segment);
+        let tangent = catmull_rom_derivative(p0, p1, p2, p3, local_t);
+        if tangent.length_squared() > 1e-12 {
+            tangent.normalize()
+        } else {
+            (p2 - p1).normalize_or_zero()
+        }
+    }
+
+    /// 弧长对应的参数t
+    pub fn distance_to_param(&self, distance: f32) -> f32 {
+        let sample_count = self.arc_lengths.len();
+        if sample_count < 2 || self.length() <= 0.0 {
+            return 0.0;
+        }
+        let distance = distance.clamp(0.0, self.length());
+
+        // 在弧长表中找到所在区间并线性插值
+        let index = self.arc_lengths.partition_point(|&length| length < distance).clamp(1, sample_count - 1);
+        let (start, end) = (self.arc_lengths[index - 1], self.arc_lengths[index]);
+        let fraction = if end > start { (distance - start) / (end - start) } else { 0.0 };
+        ((index - 1) as f32 + fraction) / (sample_count - 1) as f32
+    }
+
+    /// 沿曲线距离起点distance处的位置
+    pub fn point_at_distance(&self, distance: f32) -> Vec3 {
+        self.sample(self.distance_to_param(distance))
+    }
+
+    /// 沿曲线距离起点distance处的单位切线
+    pub fn tangent_at_distance(&self, distance: f32) -> Vec3 {
+        self.tangent(self.distance_to_param(distance))
+    }
+
+    /// 全局参数t转换为(段索引, 段内参数)
+    fn locate(&self, t: f32) -> (usize, f32) {
+        let segments = self.segment_count().max(1);
+        let scaled = t.clamp(0.0, 1.0) * segments as f32;
+        let segment = (scaled.floor() as usize).min(segments - 1);
+        (segment, scaled - segment as f32)
+    }
+
+    /// 段的四个控制点，开放曲线两端重复端点
+    fn segment_points(&self, segment: usize) -> [Vec3; 4] {
+        let n = self.points.len() as isize;
+        let point = |index: isize| {
+            if self.closed {
+                self.points[index.rem_euclid(n) as usize]
+            } else {
+                self.points[index.clamp(0, n - 1) as usize]
+            }
+        };
+        let i = segment as isize;
+        [point(i - 1), point(i), point(i + 1), point(i + 2)]
+    }
+
+    fn rebuild(&mut self) {
+        self.arc_lengths.clear();
+        let segments = self.segment_count();
+        if segments == 0 {
+            return;
+        }
+
+        let sample_count = segments * SAMPLES_PER_SEGMENT;
+        self.arc_lengths.reserve(sample_count + 1);
+        self.arc_lengths.push(0.0);
+
+        let mut previous = self.sample(0.0);
+        let mut length = 0.0;
+        for i in 1..=sample_count {
+            let point = self.sample(i as f32 / sample_count as f32);
+            length += point.distance(previous);
+            self.arc_lengths.push(length);
+            previous = point;
+        }
+    }
+}
+
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * (2.0 * p1
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+fn catmull_rom_derivative(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    0.5 * ((p2 - p0)
+        + 2.0 * (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t
+        + 3.0 * (3.0 * p1 - p0 - 3.0 * p2 + p3) * t2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_passes_through_control_points() {
+        let points = [Vec3::ZERO, Vec3::new(1.0, 2.0, 0.0), Vec3::new(3.0, 2.0, 1.0), Vec3::new(4.0, 0.0, 0.0)];
+        let spline = Spline::new(points);
+        assert_eq!(spline.segment_count(), 3);
+
+        for (i, point) in points.iter().enumerate() {
+            let t = i as f32 / 3.0;
+            assert!(spline.sample(t).distance(*point) < 1e-5, "t={} {:?}", t, spline.sample(t));
+        }
+    }
+
+    #[test]
+    fn straight_line_has_uniform_arc_length() {
+        let spline = Spline::new([Vec3::ZERO, Vec3::new(8.0, 0.0, 0.0)]);
+        assert!((spline.length() - 8.0).abs() < 1e-4);
+
+        // 按弧长取点，端点重复使曲线在两端减速，但弧长参数化后仍是匀速
+        for distance in [0.0, 1.0, 4.0, 7.5, 8.0] {
+            assert!((spline.point_at_distance(distance).x - distance).abs() < 0.05, "distance {}", distance);
+            assert!(spline.tangent_at_distance(distance).abs_diff_eq(Vec3::X, 1e-4));
+        }
+        assert_eq!(spline.point_at_distance(100.0), Vec3::new(8.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn closed_spline_returns_to_start() {
+        let square = [Vec3::ZERO, Vec3::X, Vec3::new(1.0, 0.0, 1.0), Vec3::Z];
+        let spline = Spline::closed(square);
+        assert_eq!(spline.segment_count(), 4);
+        assert!(spline.sample(1.0).distance(Vec3::ZERO) < 1e-5);
+        assert!(spline.length() > 3.0);
+    }
+
+    #[test]
+    fn degenerate_splines_have_no_length() {
+        assert_eq!(Spline::new([]).length(), 0.0);
+        let single = Spline::new([Vec3::ONE]);
+        assert_eq!(single.sample(0.5), Vec3::ONE);
+        assert_eq!(single.tangent(0.5), Vec3::ZERO);
+        assert_eq!(single.distance_to_param(1.0), 0.0);
+    }
+}
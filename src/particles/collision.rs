@@ -0,0 +1,192 @@
+//! 粒子碰撞
+//!
+//! 粒子与一组无限大的静态平面(如地面)碰撞：穿入平面的粒子被推回表面，法向速度按 `bounce` 反弹，
+//! 切向速度按 `friction` 衰减，并可按 `lifetime_loss` 消耗生命。平面是无限大的，
+//! 因此一帧内直接穿过平面的高速粒子也会被拉回。
+
+use crate::math::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// 碰撞平面，满足 `normal·p = distance` 的点构成平面，粒子保持在法线一侧
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CollisionPlane {
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+impl CollisionPlane {
+    pub fn new(normal: Vec3, distance: f32) -> Self {
+        Self { normal: normal.normalize_or_zero(), distance }
+    }
+
+    /// 经过point、法线为normal的平面
+    pub fn from_point_normal(point: Vec3, normal: Vec3) -> Self {
+        let normal = normal.normalize_or_zero();
+        Self { normal, distance: normal.dot(point) }
+    }
+
+    /// 高度为height的水平地面
+    pub fn ground(height: f32) -> Self {
+        Self { normal: Vec3::Y, distance: height }
+    }
+
+    /// 点到平面的有符号距离，法线一侧为正
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) - self.distance
+    }
+}
+
+/// 粒子碰撞模块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParticleCollision {
+    /// 碰撞平面
+    pub planes: Vec<CollisionPlane>,
+    /// 反弹系数，碰撞后保留的法向速度比例(0不反弹，1完全弹性)
+    pub bounce: f32,
+    /// 摩擦，每次碰撞损失的切向速度比例(0-1)
+    pub friction: f32,
+    /// 每次碰撞消耗的生命比例(0-1)，1表示碰撞即死亡
+    pub lifetime_loss: f32,
+    /// 碰撞半径相对粒子大小的比例，0表示按点碰撞
+    pub radius_scale: f32,
+}
+
+impl Default for ParticleCollision {
+    fn default() -> Self {
+        Self {
+            planes: Vec::new(),
+            bounce: 0.5,
+            friction: 0.1,
+            lifetime_loss: 0.0,
+            radius_scale: 0.0,
+        }
+    }
+}
+
+impl ParticleCollision {
+    /// 与高度为height的地面碰撞
+    pub fn ground_plane(height: f32) -> Self {
+        Self::default().with_plane(CollisionPlane::ground(height))
+    }
+
+    pub fn with_plane(mut self, plane: CollisionPlane) -> Self {
+        self.planes.push(plane);
+        self
+    }
+
+    pub fn with_bounce(mut self, bounce: f32) -> Self {
+        self.bounce = bounce.max(0.0);
+        self
+    }
+
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_lifetime_loss(mut self, lifetime_loss: f32) -> Self {
+        self.lifetime_loss = lifetime_loss.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_radius_scale(mut self, radius_scale: f32) -> Self {
+        self.radius_scale = radius_scale.max(0.0);
+        self
+    }
+
+    /// 处理粒子与所有平面的碰撞，返回发生碰撞的平面法线(多个平面时取最后一个)
+    pub fn resolve(&self, position: &mut Vec3, velocity: &mut Vec3, size: f32) -> Option<Vec3> {
+        let radius = size * self.radius_scale;
+        let mut hit_normal = None;
+
+        for plane in &self.planes {
+            let penetration = plane.signed_distance(*position) - radius;
+            if penetration >= 0.0 {
+                continue;
+            }
+            *position -= plane.normal * penetration;
+
+            let normal_speed = plane.normal.dot(*velocity);
+            if normal_speed < 0.0 {
+                let normal_velocity = plane.normal * normal_speed;
+                let tangent_velocity = *velocity - normal_velocity;
+                *velocity = tangent_velocity * (1.0 - self.friction) - normal_velocity * self.bounce;
+            }
+            hit_normal = Some(plane.normal);
+        }
+        hit_normal
+    }
+}
+
+/// 一次粒子碰撞，供碰撞时触发的效果(如溅射的子发射器)使用
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleCollisionEvent {
+    /// 碰撞后的粒子位置
+    pub position: Vec3,
+    /// 碰撞平面法线
+    pub normal: Vec3,
+    /// 碰撞后的速度
+    pub velocity: Vec3,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn penetrating_particle_is_pushed_out_and_bounces() {
+        let collision = ParticleCollision::ground_plane(0.0).with_bounce(0.5).with_friction(0.25);
+        let mut position = Vec3::new(1.0, -0.2, 0.0);
+        let mut velocity = Vec3::new(4.0, -2.0, 0.0);
+
+        assert_eq!(collision.resolve(&mut position, &mut velocity, 1.0), Some(Vec3::Y));
+        assert_eq!(position, Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(velocity, Vec3::new(3.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn particles_above_or_leaving_the_plane_are_untouched() {
+        let collision = ParticleCollision::ground_plane(0.0);
+        let mut position = Vec3::new(0.0, 0.5, 0.0);
+        let mut velocity = Vec3::NEG_Y;
+        assert_eq!(collision.resolve(&mut position, &mut velocity, 1.0), None);
+        assert_eq!((position, velocity), (Vec3::new(0.0, 0.5, 0.0), Vec3::NEG_Y));
+
+        // 已经在离开平面：推回表面但不再反弹
+        let mut position = Vec3::new(0.0, -0.1, 0.0);
+        let mut velocity = Vec3::Y;
+        assert!(collision.resolve(&mut position, &mut velocity, 1.0).is_some());
+        assert_eq!((position, velocity), (Vec3::ZERO, Vec3::Y));
+    }
+
+    #[test]
+    fn radius_keeps_particle_surface_above_plane() {
+        let collision = ParticleCollision::ground_plane(1.0).with_radius_scale(0.5).with_bounce(0.0);
+        let mut position = Vec3::new(0.0, 1.5, 0.0);
+        let mut velocity = Vec3::new(0.0, -1.0, 0.0);
+        assert!(collision.resolve(&mut position, &mut velocity, 2.0).is_some());
+        assert_eq!(position.y, 2.0);
+        assert_eq!(velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn multiple_planes_report_last_hit() {
+        let wall = CollisionPlane::from_point_normal(Vec3::new(5.0, 0.0, 0.0), Vec3::NEG_X * 2.0);
+        assert_eq!(wall.distance, -5.0);
+        assert_eq!(wall.signed_distance(Vec3::new(4.0, 0.0, 0.0)), 1.0);
+
+        let collision = ParticleCollision::ground_plane(0.0).with_plane(wall).with_bounce(1.0).with_friction(0.0);
+        let mut position = Vec3::new(5.5, -0.5, 0.0);
+        let mut velocity = Vec3::new(1.0, -1.0, 0.0);
+        assert_eq!(collision.resolve(&mut position, &mut velocity, 0.0), Some(Vec3::NEG_X));
+        assert_eq!(position, Vec3::new(5.0, 0.0, 0.0));
+        assert_eq!(velocity, Vec3::new(-1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn builder_clamps_parameters() {
+        let collision = ParticleCollision::default().with_bounce(-1.0).with_friction(2.0).with_lifetime_loss(-0.5).with_radius_scale(-1.0);
+        assert_eq!((collision.bounce, collision.friction, collision.lifetime_loss, collision.radius_scale), (0.0, 1.0, 0.0, 0.0));
+        assert_eq!(CollisionPlane::new(Vec3::ZERO, 1.0).normal, Vec3::ZERO);
+    }
+}
@@ -1,8 +1,9 @@
 //! 粒子发射器
 
-use crate::math::{Vec3, Vec2, Quat};
-use crate::particles::{Particle, ParticleState};
+use crate::math::{Vec3, Vec2, Vec4, Quat, ColorRamp, ColorStop};
+use crate::particles::{Particle, ParticleState, GpuParticleSimulation, GpuSimulationParams, ForceModule, ParticleSorter, ParticleCollision, ParticleCollisionEvent};
 use crate::render::RenderSystem;
+use crate::core::{Pool, PoolStats};
 use rand::{Rng, thread_rng};
 use serde::{Deserialize, Serialize};
 
@@ -30,6 +31,13 @@ pub enum BlendMode {
     Screen,     // 屏幕混合
 }
 
+impl BlendMode {
+    /// 结果是否依赖绘制顺序(加法、乘法、屏幕混合满足交换律，无需排序)
+    pub fn requires_sorting(&self) -> bool {
+        matches!(self, BlendMode::Alpha)
+    }
+}
+
 /// 模拟空间
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum SimulationSpace {
@@ -118,44 +126,88 @@ impl VelocityOverLifetime {
 /// 粒子生命周期内的颜色变化
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColorOverLifetime {
-    pub curve: Vec<(f32, [f32; 4])>, // (生命周期百分比, RGBA)
+    pub ramp: ColorRamp,
 }
 
 impl ColorOverLifetime {
+    /// 由 (生命周期百分比, RGBA) 关键点创建
     pub fn new(curve: Vec<(f32, [f32; 4])>) -> Self {
-        Self { curve }
+        Self {
+            ramp: ColorRamp::new(curve.into_iter().map(|(t, color)| ColorStop::new(t, Vec4::from_array(color)))),
+        }
+    }
+
+    pub fn from_ramp(ramp: ColorRamp) -> Self {
+        Self { ramp }
     }
 
     pub fn evaluate(&self, lifetime_ratio: f32) -> [f32; 4] {
-        if self.curve.is_empty() {
-            return [1.0, 1.0, 1.0, 1.0];
-        }
+        self.ramp.evaluate(lifetime_ratio).to_array()
+    }
+}
 
-        if self.curve.len() == 1 {
-            return self.curve[0].1;
+/// 定时爆发：从发射器启动起time秒时发射count个粒子，每隔interval秒重复，共cycles次
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Burst {
+    pub time: f32,
+    pub count: usize,
+    /// 发射次数，0表示无限重复(interval需大于0)
+    pub cycles: u32,
+    pub interval: f32,
+}
+
+impl Burst {
+    /// 无限重复时的最小间隔(秒)，避免极小间隔在一帧内产生海量发射
+    pub const MIN_INFINITE_INTERVAL: f32 = 0.001;
+
+    /// 在time秒时发射一次
+    pub fn new(time: f32, count: usize) -> Self {
+        Self {
+            time,
+            count,
+            cycles: 1,
+            interval: 0.0,
         }
+    }
 
-        // 线性插值
-        for i in 0..self.curve.len() - 1 {
-            let (t1, c1) = self.curve[i];
-            let (t2, c2) = self.curve[i + 1];
+    /// 设置重复次数和间隔
+    pub fn with_cycles(mut self, cycles: u32, interval: f32) -> Self {
+        self.cycles = cycles;
+        self.interval = interval;
+        self
+    }
 
-            if lifetime_ratio >= t1 && lifetime_ratio <= t2 {
-                if t2 == t1 {
-                    return c1;
-                }
-                let factor = (lifetime_ratio - t1) / (t2 - t1);
-                return [
-                    c1[0] + (c2[0] - c1[0]) * factor,
-                    c1[1] + (c2[1] - c1[1]) * factor,
-                    c1[2] + (c2[2] - c1[2]) * factor,
-                    c1[3] + (c2[3] - c1[3]) * factor,
-                ];
-            }
+    /// 是否无限重复
+    pub fn is_infinite(&self) -> bool {
+        self.cycles == 0 && self.interval > 0.0
+    }
+
+    /// 实际使用的重复间隔，无限重复时不小于 `MIN_INFINITE_INTERVAL`
+    pub fn effective_interval(&self) -> f32 {
+        if self.is_infinite() {
+            self.interval.max(Self::MIN_INFINITE_INTERVAL)
+        } else {
+            self.interval.max(0.0)
         }
+    }
 
-        // 如果超出范围，返回最后一个值
-        self.curve.last().unwrap().1
+    /// 第cycle次发射的时间
+    pub fn cycle_time(&self, cycle: u32) -> f32 {
+        self.time + self.effective_interval() * cycle as f32
+    }
+
+    /// 到elapsed秒时已到期的发射次数
+    pub fn due_cycles(&self, elapsed: f32) -> u32 {
+        if elapsed < self.time {
+            return 0;
+        }
+        let cycles = if self.is_infinite() { u32::MAX } else { self.cycles.max(1) };
+        let interval = self.effective_interval();
+        if interval <= 0.0 {
+            return cycles;
+        }
+        let due = (((elapsed - self.time) / interval).floor() as u64).saturating_add(1);
+        due.min(cycles as u64) as u32
     }
 }
 
@@ -171,6 +223,10 @@ pub struct EmitterConfig {
     /// 爆发发射数量（一次性发射）
     pub burst_count: usize,
     
+    /// 定时爆发列表，时间相对发射器启动
+    #[serde(default)]
+    pub bursts: Vec<Burst>,
+
     /// 发射器生命周期（秒，0表示无限）
     pub lifetime: f32,
     
@@ -192,6 +248,10 @@ pub struct EmitterConfig {
     /// 重力影响
     pub gravity: Vec3,
     
+    /// 力场模块，按顺序叠加到重力上
+    #[serde(default)]
+    pub forces: Vec<ForceModule>,
+    
     /// 发射形状
     pub shape: EmissionShape,
     
@@ -213,6 +273,18 @@ pub struct EmitterConfig {
     /// 模拟空间
     pub simulation_space: SimulationSpace,
     
+    /// 碰撞模块
+    #[serde(default)]
+    pub collision: Option<ParticleCollision>,
+    
+    /// 启动时预先模拟到稳定状态(一个最长粒子寿命)，避免循环特效从空逐渐"长出来"
+    #[serde(default)]
+    pub prewarm: bool,
+    
+    /// 使用真实时间模拟，游戏暂停或慢动作时照常运行(UI特效)
+    #[serde(default)]
+    pub use_unscaled_time: bool,
+    
     /// 排序层
     pub sorting_layer: i32,
     
@@ -226,6 +298,7 @@ impl Default for EmitterConfig {
             max_particles: 100,
             emission_rate: 10.0,
             burst_count: 0,
+            bursts: Vec::new(),
             lifetime: 0.0,
             start_lifetime_range: (1.0, 2.0),
             start_speed_range: (1.0, 3.0),
@@ -233,6 +306,7 @@ impl Default for EmitterConfig {
             start_color: [1.0, 1.0, 1.0, 1.0],
             end_color: [1.0, 1.0, 1.0, 0.0],
             gravity: Vec3::new(0.0, -9.81, 0.0),
+            forces: Vec::new(),
             shape: EmissionShape::Point,
             texture_path: None,
             blend_mode: BlendMode::Alpha,
@@ -240,6 +314,9 @@ impl Default for EmitterConfig {
             velocity_over_lifetime: None,
             color_over_lifetime: None,
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: false,
+            use_unscaled_time: false,
             sorting_layer: 0,
             order_in_layer: 0,
         }
@@ -264,9 +341,22 @@ pub struct ParticleEmitter {
     pub scale: Vec3,
     
     state: EmitterState,
+    particle_pool: Pool<Particle>,
     emission_timer: f32,
     lifetime_timer: f32,
+    /// 发射器运行时间，驱动湍流噪声随时间流动
+    elapsed_time: f32,
     burst_emitted: bool,
+    /// 每个定时爆发已发射的次数，与 `config.bursts` 一一对应
+    burst_cycles_fired: Vec<u32>,
+    /// GPU模拟，为None时在CPU上更新粒子
+    gpu_simulation: Option<GpuParticleSimulation>,
+    /// 尚未提交给GPU的累计时间
+    gpu_pending_delta: f32,
+    /// 透明排序后的绘制顺序
+    sorter: ParticleSorter,
+    /// 本次更新中发生的粒子碰撞
+    collision_events: Vec<ParticleCollisionEvent>,
 }
 
 impl ParticleEmitter {
@@ -280,24 +370,141 @@ impl ParticleEmitter {
             rotation: Quat::IDENTITY,
             scale: Vec3::ONE,
             state: EmitterState::Stopped,
+            particle_pool: Self::create_particle_pool(max_particles),
             emission_timer: 0.0,
             lifetime_timer: 0.0,
+            elapsed_time: 0.0,
             burst_emitted: false,
+            burst_cycles_fired: Vec::new(),
+            gpu_simulation: None,
+            gpu_pending_delta: 0.0,
+            sorter: ParticleSorter::new(),
+            collision_events: Vec::new(),
         }
     }
 
-    /// 启动发射器
+    /// 粒子对象池，死亡粒子归还后在下次发射时复用
+    fn create_particle_pool(max_particles: usize) -> Pool<Particle> {
+        Pool::new(|| Particle::new(0, Vec3::ZERO, Vec3::ZERO))
+            .with_reset(|particle| {
+                particle.reset(0, Vec3::ZERO, Vec3::ZERO);
+                particle.user_data.float_data.clear();
+                particle.user_data.int_data.clear();
+                particle.user_data.bool_data.clear();
+            })
+            .with_max_free(max_particles)
+    }
+
+    /// 粒子对象池统计
+    pub fn pool_stats(&self) -> PoolStats {
+        self.particle_pool.stats()
+    }
+
+    /// 当前配置能否在GPU上模拟(计算着色器不支持生命周期曲线和力场)
+    pub fn supports_gpu_simulation(&self) -> bool {
+        self.config.forces.is_empty()
+            && self.config.size_over_lifetime.is_none()
+            && self.config.velocity_over_lifetime.is_none()
+            && self.config.color_over_lifetime.is_none()
+            && self.config.collision.is_none()
+    }
+
+    /// 切换到GPU模拟，设备不支持计算着色器或配置不支持时保持CPU模拟并返回false
+    ///
+    /// 已有的CPU粒子在下次 `dispatch_gpu_simulation` 时上传。
+    pub fn enable_gpu_simulation(&mut self, device: &wgpu::Device) -> bool {
+        if self.gpu_simulation.is_some() {
+            return true;
+        }
+        if !self.supports_gpu_simulation() {
+            log::info!("发射器 {} 使用了生命周期曲线或力场，保持CPU模拟", self.id);
+            return false;
+        }
+
+        match GpuParticleSimulation::new(device, self.config.max_particles) {
+            Ok(simulation) => {
+                self.gpu_simulation = Some(simulation);
+                self.gpu_pending_delta = 0.0;
+                true
+            }
+            Err(e) => {
+                log::info!("发射器 {} 回退到CPU模拟: {}", self.id, e);
+                false
+            }
+        }
+    }
+
+    /// 切换回CPU模拟，GPU上的粒子被丢弃
+    pub fn disable_gpu_simulation(&mut self) {
+        self.gpu_simulation = None;
+        self.gpu_pending_delta = 0.0;
+    }
+
+    /// 是否在GPU上模拟
+    pub fn is_gpu_simulated(&self) -> bool {
+        self.gpu_simulation.is_some()
+    }
+
+    /// GPU模拟状态
+    pub fn gpu_simulation(&self) -> Option<&GpuParticleSimulation> {
+        self.gpu_simulation.as_ref()
+    }
+
+    /// 粒子总数(包括GPU上存活的粒子)
+    pub fn particle_count(&self) -> usize {
+        self.particles.len() + self.gpu_simulation.as_ref().map_or(0, |simulation| simulation.alive_count())
+    }
+
+    /// 上传本帧发射的粒子并记录GPU模拟通道，CPU模拟时不做任何事
+    pub fn dispatch_gpu_simulation(&mut self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder) {
+        let Some(simulation) = self.gpu_simulation.as_mut() else {
+            return;
+        };
+
+        // 新粒子与已有粒子一起经过本帧的模拟步进，与CPU路径一致
+        simulation.spawn(queue, &self.particles);
+        self.particle_pool.release_all(self.particles.drain(..));
+
+        simulation.simulate(queue, encoder, GpuSimulationParams {
+            gravity: self.config.gravity.to_array(),
+            delta_time: self.gpu_pending_delta,
+            start_color: self.config.start_color,
+            end_color: self.config.end_color,
+            ..Default::default()
+        });
+        self.gpu_pending_delta = 0.0;
+    }
+
+    /// 预热时的模拟步长
+    const PREWARM_TIME_STEP: f32 = 1.0 / 30.0;
+
+    /// 启动发射器，配置了预热时先模拟到稳定状态
     pub fn start(&mut self) {
+        self.restart();
+        if self.config.prewarm {
+            self.simulate(self.prewarm_duration(), Self::PREWARM_TIME_STEP);
+        }
+    }
+
+    fn restart(&mut self) {
         self.state = EmitterState::Playing;
         self.emission_timer = 0.0;
         self.lifetime_timer = 0.0;
+        self.elapsed_time = 0.0;
         self.burst_emitted = false;
+        self.burst_cycles_fired.clear();
+    }
+
+    /// 达到稳定状态所需的预热时间：最早发射的粒子恰好达到最长寿命
+    pub fn prewarm_duration(&self) -> f32 {
+        let (min_lifetime, max_lifetime) = self.config.start_lifetime_range;
+        min_lifetime.max(max_lifetime).max(0.0)
     }
 
     /// 停止发射器
     pub fn stop(&mut self) {
         self.state = EmitterState::Stopped;
-        self.particles.clear();
+        self.clear_particles();
     }
 
     /// 暂停发射器
@@ -338,6 +545,11 @@ impl ParticleEmitter {
     pub fn set_config(&mut self, config: EmitterConfig) {
         self.particles.reserve(config.max_particles);
         self.config = config;
+
+        if self.gpu_simulation.is_some() && !self.supports_gpu_simulation() {
+            log::warn!("发射器 {} 的新配置使用了生命周期曲线或力场，切换回CPU模拟", self.id);
+            self.disable_gpu_simulation();
+        }
     }
 
     /// 立即发射爆发粒子
@@ -352,6 +564,7 @@ impl ParticleEmitter {
         if self.state != EmitterState::Playing {
             return;
         }
+        self.elapsed_time += delta_time;
 
         // 更新生命周期
         if self.config.lifetime > 0.0 {
@@ -369,19 +582,52 @@ impl ParticleEmitter {
             self.burst_emitted = true;
         }
 
+        // 发射到期的定时爆发
+        self.emit_scheduled_bursts(available_particles);
+
         // 发射持续粒子
         if self.config.emission_rate > 0.0 {
             self.emission_timer += delta_time;
             let emission_interval = 1.0 / self.config.emission_rate;
             
-            while self.emission_timer >= emission_interval && self.particles.len() < self.config.max_particles && available_particles > 0 {
+            while self.emission_timer >= emission_interval && self.particle_count() < self.config.max_particles && available_particles > 0 {
                 self.emit_particles(1);
                 self.emission_timer -= emission_interval;
             }
         }
 
-        // 更新现有粒子
-        self.update_particles(delta_time);
+        // 更新现有粒子，GPU模拟时累计时间等待下次派发
+        if self.gpu_simulation.is_some() {
+            self.gpu_pending_delta += delta_time;
+        } else {
+            self.update_particles(delta_time);
+        }
+    }
+
+    /// 发射发射时间已到(不晚于elapsed_time)的定时爆发，一帧跨过多次时在粒子上限内补发，
+    /// 没有空间时跳过剩余到期的发射，之后也不再补发
+    fn emit_scheduled_bursts(&mut self, available_particles: usize) {
+        if self.config.bursts.is_empty() {
+            return;
+        }
+        self.burst_cycles_fired.resize(self.config.bursts.len(), 0);
+
+        let mut available = available_particles;
+        for index in 0..self.config.bursts.len() {
+            let burst = self.config.bursts[index];
+            let due = burst.due_cycles(self.elapsed_time);
+            while self.burst_cycles_fired[index] < due && available > 0 {
+                let before = self.particle_count();
+                self.emit_particles(burst.count.min(available));
+                let emitted = self.particle_count() - before;
+                self.burst_cycles_fired[index] += 1;
+                if emitted == 0 {
+                    break;
+                }
+                available -= emitted;
+            }
+            self.burst_cycles_fired[index] = self.burst_cycles_fired[index].max(due);
+        }
     }
 
     /// 发射粒子
@@ -389,11 +635,11 @@ impl ParticleEmitter {
         let mut rng = thread_rng();
         
         for _ in 0..count {
-            if self.particles.len() >= self.config.max_particles {
+            if self.particle_count() >= self.config.max_particles {
                 break;
             }
 
-            let mut particle = Particle::new(0, Vec3::ZERO, Vec3::ZERO);
+            let mut particle = self.particle_pool.acquire();
             
             // 设置初始位置
             particle.position = self.position + self.get_emission_position(&mut rng);
@@ -407,7 +653,6 @@ impl ParticleEmitter {
             particle.max_lifetime = particle.lifetime;
             particle.size = rng.gen_range(self.config.start_size_range.0..=self.config.start_size_range.1);
             particle.color = self.config.start_color;
-            particle.lifetime = 1.0; // Use lifetime field
 
             self.particles.push(particle);
         }
@@ -540,6 +785,9 @@ impl ParticleEmitter {
 
     /// 更新粒子
     fn update_particles(&mut self, delta_time: f32) {
+        let noises: Vec<_> = self.config.forces.iter().map(ForceModule::create_noise).collect();
+        self.collision_events.clear();
+
         for particle in &mut self.particles {
             if particle.lifetime <= 0.0 { // Check lifetime instead of state
                 continue;
@@ -557,6 +805,12 @@ impl ParticleEmitter {
             // 应用重力
             particle.velocity += self.config.gravity * delta_time;
 
+            // 应用力场
+            for (force, noise) in self.config.forces.iter().zip(&noises) {
+                let acceleration = force.acceleration(particle.position, self.position, self.elapsed_time, noise.as_ref());
+                particle.velocity += acceleration * delta_time;
+            }
+
             // 应用生命周期内的速度变化
             if let Some(ref velocity_curve) = self.config.velocity_over_lifetime {
                 let additional_velocity = velocity_curve.evaluate(lifetime_ratio);
@@ -566,6 +820,22 @@ impl ParticleEmitter {
             // 更新位置
             particle.position += particle.velocity * delta_time;
 
+            // 碰撞
+            if let Some(ref collision) = self.config.collision {
+                if let Some(normal) = collision.resolve(&mut particle.position, &mut particle.velocity, particle.size) {
+                    self.collision_events.push(ParticleCollisionEvent {
+                        position: particle.position,
+                        normal,
+                        velocity: particle.velocity,
+                    });
+                    particle.lifetime -= collision.lifetime_loss * particle.max_lifetime;
+                    if particle.lifetime <= 0.0 {
+                        particle.lifetime = 0.0;
+                        continue;
+                    }
+                }
+            }
+
             // 应用生命周期内的大小变化
             if let Some(ref size_curve) = self.config.size_over_lifetime {
                 let base_size = particle.size; // 假设我们存储了初始大小
@@ -586,19 +856,61 @@ impl ParticleEmitter {
         }
     }
 
+    /// 最近一次更新中发生的粒子碰撞
+    pub fn collision_events(&self) -> &[ParticleCollisionEvent] {
+        &self.collision_events
+    }
+
     /// 清理死亡粒子
     pub fn cleanup_dead_particles(&mut self) {
-        self.particles.retain(|p| p.lifetime > 0.0); // Check lifetime instead of state
+        // 死亡粒子归还对象池，粒子顺序不影响渲染(透明排序另行处理)
+        let mut index = 0;
+        while index < self.particles.len() {
+            if self.particles[index].lifetime > 0.0 { // Check lifetime instead of state
+                index += 1;
+            } else {
+                let particle = self.particles.swap_remove(index);
+                self.particle_pool.release(particle);
+            }
+        }
     }
 
     /// 清除所有粒子
     pub fn clear_particles(&mut self) {
-        self.particles.clear();
+        self.particle_pool.release_all(self.particles.drain(..));
+        if let Some(simulation) = self.gpu_simulation.as_mut() {
+            simulation.clear();
+        }
     }
 
     /// 获取活跃粒子数
     pub fn get_active_particle_count(&self) -> usize {
-        self.particles.iter().filter(|p| p.lifetime > 0.0).count() // Check lifetime instead of state
+        let gpu_count = self.gpu_simulation.as_ref().map_or(0, |simulation| simulation.alive_count());
+        self.particles.iter().filter(|p| p.lifetime > 0.0).count() + gpu_count // Check lifetime instead of state
+    }
+
+    /// 计算本帧的绘制顺序(粒子索引)，每个相机渲染前调用
+    ///
+    /// Alpha混合的粒子按到相机的距离从远到近排列，其他混合模式保持原顺序。
+    pub fn sort_particles(&mut self, camera_position: Vec3) -> &[u32] {
+        if !self.config.blend_mode.requires_sorting() {
+            return self.sorter.identity(self.particles.len());
+        }
+
+        let transform = match self.config.simulation_space {
+            SimulationSpace::Local => Some(self.get_transform_matrix()),
+            SimulationSpace::World => None,
+        };
+        let positions = self.particles.iter().map(|particle| match &transform {
+            Some(matrix) => matrix.transform_point3(particle.position),
+            None => particle.position,
+        });
+        self.sorter.sort_back_to_front(positions, camera_position)
+    }
+
+    /// 最近一次 `sort_particles` 得到的绘制顺序
+    pub fn draw_order(&self) -> &[u32] {
+        self.sorter.order()
     }
 
     /// 渲染粒子
@@ -608,7 +920,7 @@ impl ParticleEmitter {
         }
 
         // TODO: 实际渲染逻辑
-        // 这里需要根据blend_mode、texture_path等属性来渲染粒子
+        // 这里需要根据blend_mode、texture_path等属性来渲染粒子，并按draw_order()的顺序提交
         // 可能需要将粒子数据上传到GPU进行批量渲染
     }
 
@@ -619,10 +931,12 @@ impl ParticleEmitter {
 
     /// 重置发射器
     pub fn reset(&mut self) {
-        self.particles.clear();
+        self.clear_particles();
         self.emission_timer = 0.0;
         self.lifetime_timer = 0.0;
+        self.elapsed_time = 0.0;
         self.burst_emitted = false;
+        self.burst_cycles_fired.clear();
         self.state = EmitterState::Stopped;
     }
 
@@ -633,15 +947,335 @@ impl ParticleEmitter {
         }
 
         let old_state = self.state;
-        self.start();
+        self.restart();
+        self.simulate(duration, time_step);
+        self.state = old_state;
+    }
 
+    /// 以固定步长模拟一段时间，不计入发射器生命周期
+    fn simulate(&mut self, duration: f32, time_step: f32) {
+        if duration <= 0.0 || time_step <= 0.0 {
+            return;
+        }
+
+        let lifetime = std::mem::replace(&mut self.config.lifetime, 0.0);
         let mut time = 0.0;
         while time < duration {
             let dt = time_step.min(duration - time);
             self.update(dt, self.config.max_particles);
+            // 预热期间没有外部清理，死亡粒子需及时归还，否则会占满粒子上限
+            self.cleanup_dead_particles();
             time += dt;
         }
+        self.config.lifetime = lifetime;
+        self.lifetime_timer = 0.0;
+    }
+}
 
-        self.state = old_state;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn burst_emitter(bursts: Vec<Burst>, max_particles: usize) -> ParticleEmitter {
+        let config = EmitterConfig {
+            max_particles,
+            emission_rate: 0.0,
+            bursts,
+            start_lifetime_range: (100.0, 100.0),
+            ..Default::default()
+        };
+        let mut emitter = ParticleEmitter::new(1, config);
+        emitter.start();
+        emitter
+    }
+
+    #[test]
+    fn due_cycles_counts_finite_and_infinite_bursts() {
+        let once = Burst::new(1.0, 5);
+        assert_eq!(once.due_cycles(0.5), 0);
+        assert_eq!(once.due_cycles(1.0), 1);
+        assert_eq!(once.due_cycles(10.0), 1);
+
+        let repeated = Burst::new(0.0, 5).with_cycles(3, 0.5);
+        assert_eq!(repeated.due_cycles(0.75), 2);
+        assert_eq!(repeated.due_cycles(100.0), 3);
+
+        let infinite = Burst::new(0.0, 5).with_cycles(0, 0.25);
+        assert!(infinite.is_infinite());
+        assert_eq!(infinite.due_cycles(1.0), 5);
+    }
+
+    #[test]
+    fn infinite_burst_clamps_tiny_interval() {
+        let burst = Burst::new(0.0, 1).with_cycles(0, 1e-9);
+        assert_eq!(burst.effective_interval(), Burst::MIN_INFINITE_INTERVAL);
+        assert!((1000..=1001).contains(&burst.due_cycles(1.0)));
+        assert_eq!(burst.due_cycles(f32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn repeated_bursts_emit_each_due_cycle() {
+        let mut emitter = burst_emitter(vec![Burst::new(0.0, 4).with_cycles(3, 0.5)], 100);
+        emitter.update(0.1, 100);
+        assert_eq!(emitter.particle_count(), 4);
+        emitter.update(0.5, 100);
+        assert_eq!(emitter.particle_count(), 8);
+        // 跨过剩余的发射，且不超过cycles次
+        emitter.update(5.0, 100);
+        assert_eq!(emitter.particle_count(), 12);
+    }
+
+    #[test]
+    fn bursts_fire_at_scheduled_times_only() {
+        let mut emitter = burst_emitter(vec![Burst::new(0.0, 10), Burst::new(1.0, 5)], 100);
+        let mut counts = Vec::new();
+        for _ in 0..16 {
+            emitter.update(0.125, 100);
+            counts.push(emitter.particle_count());
+        }
+
+        // t=0的爆发在第一帧，t=1的爆发在跨过1秒的第8帧，其间数量不变
+        assert!(counts[..7].iter().all(|&count| count == 10), "{:?}", counts);
+        assert!(counts[7..].iter().all(|&count| count == 15), "{:?}", counts);
+
+        // 重新启动后按新的时间线再次发射
+        emitter.clear_particles();
+        emitter.start();
+        emitter.update(0.125, 100);
+        assert_eq!(emitter.particle_count(), 10);
+    }
+
+    #[test]
+    fn missing_bursts_deserialize_as_empty() {
+        let mut value = serde_json::to_value(EmitterConfig {
+            bursts: vec![Burst::new(0.5, 3).with_cycles(2, 0.25)],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(serde_json::from_value::<EmitterConfig>(value.clone()).unwrap().bursts[0].cycles, 2);
+
+        value.as_object_mut().unwrap().remove("bursts");
+        assert!(serde_json::from_value::<EmitterConfig>(value).unwrap().bursts.is_empty());
+    }
+
+    #[test]
+    fn infinite_burst_stops_when_no_particles_available() {
+        let mut emitter = burst_emitter(vec![Burst::new(0.0, 2).with_cycles(0, 1e-9)], 10);
+        emitter.update(1000.0, 10);
+        assert_eq!(emitter.particle_count(), 10);
+        // 没有空间时跳过到期的发射，之后不补发
+        assert_eq!(emitter.burst_cycles_fired[0], Burst::new(0.0, 2).with_cycles(0, 1e-9).due_cycles(1000.0));
+    }
+
+    #[test]
+    fn lifetime_curves_disable_gpu_simulation() {
+        let mut emitter = ParticleEmitter::new(1, EmitterConfig::default());
+        assert!(emitter.supports_gpu_simulation());
+        assert!(!emitter.is_gpu_simulated());
+
+        emitter.set_config(EmitterConfig {
+            color_over_lifetime: Some(ColorOverLifetime::new(vec![(0.0, [1.0; 4]), (1.0, [0.0; 4])])),
+            ..Default::default()
+        });
+        assert!(!emitter.supports_gpu_simulation());
+    }
+
+    #[test]
+    fn cpu_simulation_counts_and_clears_particles() {
+        let mut emitter = burst_emitter(vec![Burst::new(0.0, 6)], 100);
+        emitter.update(0.1, 100);
+        assert!(emitter.gpu_simulation().is_none());
+        assert_eq!(emitter.particle_count(), 6);
+        assert_eq!(emitter.get_active_particle_count(), 6);
+
+        emitter.clear_particles();
+        assert_eq!(emitter.particle_count(), 0);
+    }
+
+    #[test]
+    fn radial_repulsion_pushes_particles_away_from_center() {
+        let config = EmitterConfig {
+            max_particles: 16,
+            emission_rate: 0.0,
+            bursts: vec![Burst::new(0.0, 16)],
+            start_speed_range: (0.0, 0.0),
+            gravity: Vec3::ZERO,
+            shape: EmissionShape::Sphere { radius: 1.0 },
+            forces: vec![ForceModule::radial(5.0, 0.0)],
+            ..Default::default()
+        };
+        let mut emitter = ParticleEmitter::new(1, config);
+        emitter.set_position(Vec3::new(2.0, 0.0, -1.0));
+        emitter.start();
+        emitter.update(0.05, 16);
+
+        let center = Vec3::new(2.0, 0.0, -1.0);
+        let start: Vec<f32> = emitter.particles.iter().map(|p| p.position.distance(center)).collect();
+        assert_eq!(start.len(), 16);
+
+        for _ in 0..5 {
+            emitter.update(0.1, 16);
+        }
+        for (particle, start_distance) in emitter.particles.iter().zip(&start) {
+            assert!(particle.position.distance(center) > *start_distance);
+        }
+    }
+
+    fn emitter_with_particles(config: EmitterConfig, positions: &[Vec3]) -> ParticleEmitter {
+        let mut emitter = ParticleEmitter::new(1, config);
+        emitter.particles = positions
+            .iter()
+            .enumerate()
+            .map(|(i, &position)| Particle::new(i as u64, position, Vec3::ZERO))
+            .collect();
+        emitter
+    }
+
+    #[test]
+    fn alpha_particles_are_drawn_far_to_near() {
+        let positions = [Vec3::new(0.0, 0.0, -2.0), Vec3::new(0.0, 0.0, -9.0), Vec3::new(0.0, 0.0, -5.0)];
+        let mut emitter = emitter_with_particles(EmitterConfig::default(), &positions);
+
+        assert_eq!(emitter.sort_particles(Vec3::ZERO), &[1, 2, 0]);
+        // 相机移到另一侧后顺序反转
+        assert_eq!(emitter.sort_particles(Vec3::new(0.0, 0.0, -20.0)), &[0, 2, 1]);
+        assert_eq!(emitter.draw_order(), &[0, 2, 1]);
+    }
+
+    #[test]
+    fn additive_particles_keep_emission_order() {
+        let config = EmitterConfig {
+            blend_mode: BlendMode::Additive,
+            ..Default::default()
+        };
+        let mut emitter = emitter_with_particles(config, &[Vec3::Z, Vec3::Z * -9.0, Vec3::Z * 3.0]);
+        assert_eq!(emitter.sort_particles(Vec3::ZERO), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn local_space_particles_sort_by_world_position() {
+        let config = EmitterConfig {
+            simulation_space: SimulationSpace::Local,
+            ..Default::default()
+        };
+        // 局部坐标下第0个更远，发射器平移后第1个更远
+        let mut emitter = emitter_with_particles(config, &[Vec3::new(-3.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0)]);
+        assert_eq!(emitter.sort_particles(Vec3::ZERO), &[0, 1]);
+        emitter.set_position(Vec3::new(10.0, 0.0, 0.0));
+        assert_eq!(emitter.sort_particles(Vec3::ZERO), &[1, 0]);
+    }
+
+    /// 从y=2处静止下落的粒子
+    fn falling_emitter(collision: ParticleCollision) -> ParticleEmitter {
+        let config = EmitterConfig {
+            gravity: Vec3::new(0.0, -9.81, 0.0),
+            collision: Some(collision),
+            ..Default::default()
+        };
+        let mut emitter = emitter_with_particles(config, &[Vec3::new(0.0, 2.0, 0.0), Vec3::new(1.0, 3.0, 0.0)]);
+        for particle in &mut emitter.particles {
+            particle.max_lifetime = 10.0;
+            particle.lifetime = 10.0;
+        }
+        emitter
+    }
+
+    #[test]
+    fn falling_particles_stop_at_ground_plane() {
+        let mut emitter = falling_emitter(ParticleCollision::ground_plane(0.0).with_bounce(0.0));
+        assert!(!emitter.supports_gpu_simulation());
+
+        let mut collided = false;
+        for _ in 0..120 {
+            emitter.update_particles(1.0 / 60.0);
+            collided |= !emitter.collision_events().is_empty();
+            assert!(emitter.particles.iter().all(|particle| particle.position.y >= 0.0));
+        }
+        assert!(collided);
+        for particle in &emitter.particles {
+            assert!(particle.position.y.abs() < 1e-5);
+            assert!(particle.velocity.y.abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn bouncing_particles_reverse_and_report_collision() {
+        let mut emitter = falling_emitter(ParticleCollision::ground_plane(0.0).with_bounce(0.8));
+        let mut bounced = None;
+        for _ in 0..120 {
+            emitter.update_particles(1.0 / 60.0);
+            if let Some(event) = emitter.collision_events().first() {
+                bounced = Some(*event);
+                break;
+            }
+        }
+        let event = bounced.expect("粒子应落到地面");
+        assert_eq!(event.normal, Vec3::Y);
+        assert_eq!(event.position.y, 0.0);
+        assert!(event.velocity.y > 0.0);
+        assert_eq!(event.velocity, emitter.particles[0].velocity);
+    }
+
+    #[test]
+    fn collision_lifetime_loss_kills_particles() {
+        let mut emitter = falling_emitter(ParticleCollision::ground_plane(0.0).with_lifetime_loss(1.0));
+        for _ in 0..120 {
+            emitter.update_particles(1.0 / 60.0);
+        }
+        assert!(emitter.particles.iter().all(|particle| particle.lifetime == 0.0));
+    }
+
+    fn alive_particles(emitter: &ParticleEmitter) -> usize {
+        emitter.particles.iter().filter(|particle| particle.lifetime > 0.0).count()
+    }
+
+    #[test]
+    fn prewarmed_fire_starts_near_steady_state() {
+        let mut cold = ParticleEmitter::new(1, EmitterConfig { prewarm: false, ..crate::particles::ParticlePresets::fire() });
+        cold.start();
+        cold.update(1.0 / 60.0, 1000);
+        assert_eq!(cold.particle_count(), 0);
+
+        let config = crate::particles::ParticlePresets::fire();
+        assert!(config.prewarm);
+        let mut warm = ParticleEmitter::new(2, config);
+        assert_eq!(warm.prewarm_duration(), 2.5);
+        warm.start();
+        warm.update(1.0 / 60.0, 1000);
+        // 发射率50、平均寿命2秒，稳定时约100个存活粒子
+        let alive = alive_particles(&warm);
+        assert!((70..=130).contains(&alive), "{}", alive);
+    }
+
+    #[test]
+    fn prewarm_does_not_consume_emitter_lifetime() {
+        let config = EmitterConfig {
+            lifetime: 1.0,
+            emission_rate: 10.0,
+            start_lifetime_range: (3.0, 3.0),
+            prewarm: true,
+            ..Default::default()
+        };
+        let mut emitter = ParticleEmitter::new(1, config);
+        emitter.start();
+
+        assert_eq!(emitter.state, EmitterState::Playing);
+        assert_eq!(emitter.config.lifetime, 1.0);
+        assert_eq!(emitter.lifetime_timer, 0.0);
+        assert!(emitter.particle_count() >= 29);
+
+        // 预热之后生命周期从零开始计时
+        emitter.update(0.5, 1000);
+        assert_eq!(emitter.state, EmitterState::Playing);
+        emitter.update(0.6, 1000);
+        assert_eq!(emitter.state, EmitterState::Stopped);
+    }
+
+    #[test]
+    fn prewarm_defaults_to_off_when_missing() {
+        let mut value = serde_json::to_value(EmitterConfig { prewarm: true, ..Default::default() }).unwrap();
+        value.as_object_mut().unwrap().remove("prewarm");
+        let config: EmitterConfig = serde_json::from_value(value).unwrap();
+        assert!(!config.prewarm);
     }
 }
@@ -0,0 +1,149 @@
+//! 粒子力场模块
+
+use crate::math::{PerlinNoise, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// 噪声采样各分量的偏移，避免三个分量相关
+const TURBULENCE_OFFSETS: [Vec3; 3] = [
+    Vec3::new(0.0, 0.0, 0.0),
+    Vec3::new(31.416, 47.853, 12.793),
+    Vec3::new(-27.119, 13.571, 71.239),
+];
+
+/// 作用于粒子的力(加速度)
+///
+/// 位置参数(center)都相对于发射器位置。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ForceModule {
+    /// 恒定力(如风)
+    Constant { force: Vec3 },
+    /// 径向力，strength为正时排斥、为负时吸引；radius大于0时力随距离线性衰减到0
+    Radial { center: Vec3, strength: f32, radius: f32 },
+    /// 绕轴旋转的涡旋力，pull为正时向轴心收拢
+    Vortex { center: Vec3, axis: Vec3, strength: f32, pull: f32 },
+    /// 噪声驱动的湍流，scroll_speed使噪声场随时间流动
+    Turbulence { strength: f32, frequency: f32, scroll_speed: f32, seed: u32 },
+}
+
+impl ForceModule {
+    /// 恒定力
+    pub fn constant(force: Vec3) -> Self {
+        Self::Constant { force }
+    }
+
+    /// 以发射器为中心的径向力
+    pub fn radial(strength: f32, radius: f32) -> Self {
+        Self::Radial { center: Vec3::ZERO, strength, radius }
+    }
+
+    /// 绕发射器竖直轴的涡旋力
+    pub fn vortex(strength: f32) -> Self {
+        Self::Vortex { center: Vec3::ZERO, axis: Vec3::Y, strength, pull: 0.0 }
+    }
+
+    /// 湍流
+    pub fn turbulence(strength: f32, frequency: f32) -> Self {
+        Self::Turbulence { strength, frequency, scroll_speed: 0.5, seed: 0 }
+    }
+
+    /// 湍流需要的噪声生成器，其他力返回None；每次更新创建一次，供所有粒子共享
+    pub fn create_noise(&self) -> Option<PerlinNoise> {
+        match self {
+            Self::Turbulence { seed, .. } => Some(PerlinNoise::new(*seed)),
+            _ => None,
+        }
+    }
+
+    /// 计算粒子在position处受到的加速度
+    ///
+    /// `origin` 为发射器位置，`time` 为发射器运行时间，`noise` 为 `create_noise` 的结果。
+    pub fn acceleration(&self, position: Vec3, origin: Vec3, time: f32, noise: Option<&PerlinNoise>) -> Vec3 {
+        match self {
+            Self::Constant { force } => *force,
+
+            Self::Radial { center, strength, radius } => {
+                let offset = position - (origin + *center);
+                let distance = offset.length();
+                if *radius > 0.0 && distance >= *radius {
+                    return Vec3::ZERO;
+                }
+                let falloff = if *radius > 0.0 { 1.0 - distance / radius } else { 1.0 };
+                offset.normalize_or_zero() * *strength * falloff
+            }
+
+            Self::Vortex { center, axis, strength, pull } => {
+                let axis = axis.normalize_or_zero();
+                let offset = position - (origin + *center);
+                // 到旋转轴的垂直分量
+                let radial = offset - axis * offset.dot(axis);
+                let tangent = axis.cross(radial).normalize_or_zero();
+                tangent * *strength - radial.normalize_or_zero() * *pull
+            }
+
+            Self::Turbulence { strength, frequency, scroll_speed, .. } => {
+                let Some(noise) = noise else {
+                    return Vec3::ZERO;
+                };
+                let sample_point = position * *frequency + Vec3::splat(time * *scroll_speed);
+                let component = |offset: Vec3| {
+                    let p = sample_point + offset;
+                    noise.noise_3d(p.x, p.y, p.z)
+                };
+                Vec3::new(
+                    component(TURBULENCE_OFFSETS[0]),
+                    component(TURBULENCE_OFFSETS[1]),
+                    component(TURBULENCE_OFFSETS[2]),
+                ) * *strength
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radial_force_repels_attracts_and_falls_off() {
+        let origin = Vec3::new(1.0, 0.0, 0.0);
+        let position = Vec3::new(3.0, 0.0, 0.0);
+
+        let repel = ForceModule::radial(4.0, 0.0);
+        assert_eq!(repel.acceleration(position, origin, 0.0, None), Vec3::new(4.0, 0.0, 0.0));
+
+        let attract = ForceModule::radial(-4.0, 0.0);
+        assert_eq!(attract.acceleration(position, origin, 0.0, None), Vec3::new(-4.0, 0.0, 0.0));
+
+        // 距离2、半径4时衰减一半，半径以外无力
+        let limited = ForceModule::radial(4.0, 4.0);
+        assert_eq!(limited.acceleration(position, origin, 0.0, None), Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(limited.acceleration(Vec3::new(6.0, 0.0, 0.0), origin, 0.0, None), Vec3::ZERO);
+    }
+
+    #[test]
+    fn vortex_force_is_tangent_to_axis() {
+        let vortex = ForceModule::vortex(2.0);
+        let acceleration = vortex.acceleration(Vec3::new(1.0, 5.0, 0.0), Vec3::ZERO, 0.0, None);
+        // Y轴叉乘X方向得到-Z方向
+        assert!((acceleration - Vec3::new(0.0, 0.0, -2.0)).length() < 1e-6);
+
+        let pulling = ForceModule::Vortex { center: Vec3::ZERO, axis: Vec3::Y, strength: 0.0, pull: 1.0 };
+        let acceleration = pulling.acceleration(Vec3::new(2.0, 0.0, 0.0), Vec3::ZERO, 0.0, None);
+        assert!((acceleration - Vec3::new(-1.0, 0.0, 0.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn turbulence_needs_noise_and_is_deterministic() {
+        let turbulence = ForceModule::turbulence(2.0, 0.5);
+        assert!(ForceModule::constant(Vec3::X).create_noise().is_none());
+        assert_eq!(turbulence.acceleration(Vec3::ONE, Vec3::ZERO, 0.0, None), Vec3::ZERO);
+
+        let noise = turbulence.create_noise();
+        assert!(noise.is_some());
+        let a = turbulence.acceleration(Vec3::new(0.3, 1.7, -2.1), Vec3::ZERO, 1.0, noise.as_ref());
+        let b = turbulence.acceleration(Vec3::new(0.3, 1.7, -2.1), Vec3::ZERO, 1.0, noise.as_ref());
+        assert_eq!(a, b);
+        // 每个角的梯度贡献不超过2
+        assert!(a.abs().max_element() <= 2.0 * 2.0);
+    }
+}
@@ -0,0 +1,390 @@
+//! GPU粒子模拟
+//!
+//! 粒子状态常驻GPU存储缓冲，每帧由计算着色器更新，存活粒子的索引和数量写入间接绘制缓冲，
+//! 渲染时用 `draw_indirect` 绘制，粒子数据无需回读CPU。
+//! 发射仍在CPU完成，新粒子写入空闲槽位；CPU只记录每个槽位的剩余寿命用于分配槽位和统计。
+
+use crate::particles::Particle;
+use crate::{EngineError, EngineResult};
+use wgpu::util::DeviceExt;
+
+/// 计算着色器工作组大小，与 particle_simulate.wgsl 保持一致
+const WORKGROUP_SIZE: u32 = 64;
+
+/// 每个粒子绘制的顶点数(两个三角形组成的面片)
+const VERTICES_PER_PARTICLE: u32 = 6;
+
+/// GPU粒子数据，布局与着色器中的 Particle 一致
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GpuParticle {
+    pub position: [f32; 3],
+    pub lifetime: f32,
+    pub velocity: [f32; 3],
+    pub max_lifetime: f32,
+    pub color: [f32; 4],
+    pub size: f32,
+    pub _padding: [f32; 3],
+}
+
+impl From<&Particle> for GpuParticle {
+    fn from(particle: &Particle) -> Self {
+        Self {
+            position: particle.position.to_array(),
+            lifetime: particle.lifetime,
+            velocity: particle.velocity.to_array(),
+            max_lifetime: particle.max_lifetime,
+            color: particle.color,
+            size: particle.size,
+            _padding: [0.0; 3],
+        }
+    }
+}
+
+/// 模拟参数，布局与着色器中的 SimulationParams 一致
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GpuSimulationParams {
+    pub gravity: [f32; 3],
+    pub delta_time: f32,
+    pub start_color: [f32; 4],
+    pub end_color: [f32; 4],
+    pub particle_count: u32,
+    pub _padding: [u32; 3],
+}
+
+/// 单个发射器的GPU粒子模拟
+pub struct GpuParticleSimulation {
+    pipeline: wgpu::ComputePipeline,
+    bind_group: wgpu::BindGroup,
+    params_buffer: wgpu::Buffer,
+    particle_buffer: wgpu::Buffer,
+    alive_index_buffer: wgpu::Buffer,
+    indirect_buffer: wgpu::Buffer,
+    capacity: usize,
+    /// 每个槽位的剩余寿命，与GPU上的模拟同步递减
+    slot_lifetimes: Vec<f32>,
+    /// 已使用的最高槽位+1，计算着色器只处理这一范围
+    high_water: usize,
+    alive_count: usize,
+    next_slot: usize,
+    pending_clear: bool,
+}
+
+impl GpuParticleSimulation {
+    /// 设备是否支持计算着色器和所需的存储缓冲(WebGL2等降级后端不支持)
+    pub fn is_supported(device: &wgpu::Device) -> bool {
+        let limits = device.limits();
+        limits.max_compute_workgroup_size_x >= WORKGROUP_SIZE
+            && limits.max_compute_invocations_per_workgroup >= WORKGROUP_SIZE
+            && limits.max_storage_buffers_per_shader_stage >= 3
+    }
+
+    pub fn new(device: &wgpu::Device, capacity: usize) -> EngineResult<Self> {
+        if !Self::is_supported(device) {
+            return Err(EngineError::RenderError("设备不支持计算着色器，无法使用GPU粒子模拟".to_string()).into());
+        }
+        let capacity = capacity.max(1);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("粒子模拟着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../render/shaders/particle_simulate.wgsl").into()),
+        });
+
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("粒子模拟参数缓冲"),
+            size: std::mem::size_of::<GpuSimulationParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("粒子状态缓冲"),
+            contents: bytemuck::cast_slice(&vec![GpuParticle::default(); capacity]),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
+        });
+        let alive_index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("存活粒子索引缓冲"),
+            size: (capacity * std::mem::size_of::<u32>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+        let indirect_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("粒子间接绘制缓冲"),
+            contents: bytemuck::cast_slice(&Self::draw_args(0)),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::INDIRECT | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let storage_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("粒子模拟绑定组布局"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage_entry(1),
+                storage_entry(2),
+                storage_entry(3),
+            ],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("粒子模拟绑定组"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: params_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: particle_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: alive_index_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: indirect_buffer.as_entire_binding() },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("粒子模拟管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("粒子模拟管线"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "cs_simulate",
+        });
+
+        Ok(Self {
+            pipeline,
+            bind_group,
+            params_buffer,
+            particle_buffer,
+            alive_index_buffer,
+            indirect_buffer,
+            capacity,
+            slot_lifetimes: vec![0.0; capacity],
+            high_water: 0,
+            alive_count: 0,
+            next_slot: 0,
+            pending_clear: false,
+        })
+    }
+
+    /// 间接绘制参数(vertex_count, instance_count, first_vertex, first_instance)
+    fn draw_args(instance_count: u32) -> [u32; 4] {
+        [VERTICES_PER_PARTICLE, instance_count, 0, 0]
+    }
+
+    /// 槽位容量
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 存活粒子数
+    pub fn alive_count(&self) -> usize {
+        self.alive_count
+    }
+
+    /// 把新粒子写入空闲槽位，返回写入的数量(槽位用尽时多余的粒子被丢弃)
+    pub fn spawn(&mut self, queue: &wgpu::Queue, particles: &[Particle]) -> usize {
+        self.flush_clear(queue);
+
+        // 连续槽位合并为一次写入
+        let mut run_start = 0;
+        let mut run: Vec<GpuParticle> = Vec::new();
+        let mut spawned = 0;
+
+        for particle in particles {
+            let Some(slot) = self.find_free_slot() else {
+                break;
+            };
+            if !run.is_empty() && slot != run_start + run.len() {
+                self.write_particles(queue, run_start, &run);
+                run.clear();
+            }
+            if run.is_empty() {
+                run_start = slot;
+            }
+
+            run.push(GpuParticle::from(particle));
+            self.slot_lifetimes[slot] = particle.lifetime;
+            self.high_water = self.high_water.max(slot + 1);
+            self.alive_count += 1;
+            spawned += 1;
+        }
+
+        if !run.is_empty() {
+            self.write_particles(queue, run_start, &run);
+        }
+        spawned
+    }
+
+    fn find_free_slot(&mut self) -> Option<usize> {
+        for offset in 0..self.capacity {
+            let slot = (self.next_slot + offset) % self.capacity;
+            if self.slot_lifetimes[slot] <= 0.0 {
+                self.next_slot = (slot + 1) % self.capacity;
+                return Some(slot);
+            }
+        }
+        None
+    }
+
+    fn write_particles(&self, queue: &wgpu::Queue, first_slot: usize, particles: &[GpuParticle]) {
+        let offset = (first_slot * std::mem::size_of::<GpuParticle>()) as u64;
+        queue.write_buffer(&self.particle_buffer, offset, bytemuck::cast_slice(particles));
+    }
+
+    /// 记录一次模拟步进，计算通道在提交编码器时执行
+    pub fn simulate(&mut self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, params: GpuSimulationParams) {
+        self.flush_clear(queue);
+
+        let particle_count = self.high_water as u32;
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&GpuSimulationParams { particle_count, ..params }));
+        queue.write_buffer(&self.indirect_buffer, 0, bytemuck::cast_slice(&Self::draw_args(0)));
+
+        // CPU侧的槽位寿命与着色器同样递减
+        self.alive_count = 0;
+        for lifetime in &mut self.slot_lifetimes[..self.high_water] {
+            if *lifetime > 0.0 {
+                *lifetime = (*lifetime - params.delta_time).max(0.0);
+                if *lifetime > 0.0 {
+                    self.alive_count += 1;
+                }
+            }
+        }
+
+        if particle_count == 0 {
+            return;
+        }
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("粒子模拟通道"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.dispatch_workgroups(particle_count.div_ceil(WORKGROUP_SIZE), 1, 1);
+    }
+
+    /// 清除所有粒子，GPU缓冲在下次写入时清零
+    pub fn clear(&mut self) {
+        self.slot_lifetimes.fill(0.0);
+        self.high_water = 0;
+        self.alive_count = 0;
+        self.next_slot = 0;
+        self.pending_clear = true;
+    }
+
+    fn flush_clear(&mut self, queue: &wgpu::Queue) {
+        if self.pending_clear {
+            // 用队列写入而不是编码器清除，保证先于本帧的新粒子写入执行
+            queue.write_buffer(&self.particle_buffer, 0, bytemuck::cast_slice(&vec![GpuParticle::default(); self.capacity]));
+            self.pending_clear = false;
+        }
+    }
+
+    /// 绘制存活粒子，调用前需绑定读取粒子缓冲和索引缓冲的渲染管线
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.draw_indirect(&self.indirect_buffer, 0);
+    }
+
+    /// 粒子状态缓冲(渲染时按存活索引读取)
+    pub fn particle_buffer(&self) -> &wgpu::Buffer {
+        &self.particle_buffer
+    }
+
+    /// 存活粒子索引缓冲，第i个实例对应 particle_buffer[alive_indices[i]]
+    pub fn alive_index_buffer(&self) -> &wgpu::Buffer {
+        &self.alive_index_buffer
+    }
+
+    /// 间接绘制参数缓冲
+    pub fn indirect_buffer(&self) -> &wgpu::Buffer {
+        &self.indirect_buffer
+    }
+
+    /// 回读粒子状态(阻塞等待GPU)，用于调试和与CPU路径对比
+    pub fn read_particles(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> EngineResult<Vec<GpuParticle>> {
+        let size = (self.high_water * std::mem::size_of::<GpuParticle>()) as u64;
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("粒子回读缓冲"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("粒子回读编码器"),
+        });
+        encoder.copy_buffer_to_buffer(&self.particle_buffer, 0, &staging_buffer, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = staging_buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        receiver
+            .recv()
+            .map_err(|e| EngineError::RenderError(e.to_string()))?
+            .map_err(|e| EngineError::RenderError(format!("粒子缓冲映射失败: {}", e)))?;
+
+        let data = slice.get_mapped_range();
+        let particles: Vec<GpuParticle> = bytemuck::cast_slice(&data[..]).to_vec();
+        drop(data);
+        staging_buffer.unmap();
+        Ok(particles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec3;
+
+    #[test]
+    fn gpu_particle_copies_particle_state() {
+        let mut particle = Particle::new(7, Vec3::new(1.0, 2.0, 3.0), Vec3::new(0.5, -1.0, 0.25));
+        particle.lifetime = 0.75;
+        particle.max_lifetime = 2.0;
+        particle.size = 1.5;
+        particle.color = [0.25, 0.5, 0.75, 1.0];
+
+        let gpu = GpuParticle::from(&particle);
+        assert_eq!(gpu.position, [1.0, 2.0, 3.0]);
+        assert_eq!(gpu.velocity, [0.5, -1.0, 0.25]);
+        assert_eq!(gpu.lifetime, 0.75);
+        assert_eq!(gpu.max_lifetime, 2.0);
+        assert_eq!(gpu.size, 1.5);
+        assert_eq!(gpu.color, [0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(gpu._padding, [0.0; 3]);
+    }
+
+    #[test]
+    fn gpu_layouts_are_16_byte_aligned() {
+        // 存储/uniform缓冲中的结构体按16字节对齐
+        assert_eq!(std::mem::size_of::<GpuParticle>(), 64);
+        assert_eq!(std::mem::size_of::<GpuSimulationParams>(), 64);
+    }
+}
@@ -4,11 +4,19 @@ pub mod particle;
 pub mod emitter;
 pub mod systems;
 pub mod effects;
+pub mod gpu_simulation;
+pub mod forces;
+pub mod sorting;
+pub mod collision;
 
 pub use particle::{Particle, ParticleState};
-pub use emitter::{ParticleEmitter, EmitterId, EmitterConfig, EmissionShape, BlendMode as EmitterBlendMode, SizeOverLifetime, VelocityOverLifetime, ColorOverLifetime, SimulationSpace};
+pub use emitter::{ParticleEmitter, EmitterId, EmitterConfig, Burst, EmissionShape, BlendMode as EmitterBlendMode, SizeOverLifetime, VelocityOverLifetime, ColorOverLifetime, SimulationSpace};
 pub use systems::*;
 pub use effects::*;
+pub use gpu_simulation::*;
+pub use forces::*;
+pub use sorting::*;
+pub use collision::*;
 
 use crate::math::{Vec3, Vec2};
 use crate::render::RenderSystem;
@@ -45,7 +53,7 @@ impl ParticleSystemManager {
     /// 移除粒子发射器
     pub fn remove_emitter(&mut self, id: EmitterId) -> bool {
         if let Some(emitter) = self.emitters.remove(&id) {
-            self.current_particle_count -= emitter.particles.len();
+            self.current_particle_count = self.current_particle_count.saturating_sub(emitter.particle_count());
             true
         } else {
             false
@@ -92,11 +100,40 @@ impl ParticleSystemManager {
 
     /// 更新所有粒子系统
     pub fn update(&mut self, delta_time: f32) {
+        self.update_with_time(delta_time, delta_time);
+    }
+
+    /// 世界特效按缩放后的 `delta_time` 推进(暂停时冻结)，`use_unscaled_time` 的发射器按真实时间推进
+    pub fn update_with_time(&mut self, delta_time: f32, unscaled_delta_time: f32) {
         self.current_particle_count = 0;
 
         for emitter in self.emitters.values_mut() {
-            emitter.update(delta_time, self.max_particles - self.current_particle_count);
-            self.current_particle_count += emitter.particles.len();
+            let dt = if emitter.config.use_unscaled_time { unscaled_delta_time } else { delta_time };
+            emitter.update(dt, self.max_particles.saturating_sub(self.current_particle_count));
+            self.current_particle_count += emitter.particle_count();
+        }
+    }
+
+    /// 尽可能把发射器切换到GPU模拟，返回切换成功的数量
+    pub fn enable_gpu_simulation(&mut self, device: &wgpu::Device) -> usize {
+        self.emitters
+            .values_mut()
+            .map(|emitter| emitter.enable_gpu_simulation(device))
+            .filter(|&enabled| enabled)
+            .count()
+    }
+
+    /// 记录所有GPU模拟发射器的计算通道，需在更新之后、渲染之前调用
+    pub fn dispatch_gpu_simulation(&mut self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder) {
+        for emitter in self.emitters.values_mut() {
+            emitter.dispatch_gpu_simulation(queue, encoder);
+        }
+    }
+
+    /// 按相机位置计算所有发射器的透明粒子绘制顺序
+    pub fn sort_particles(&mut self, camera_position: Vec3) {
+        for emitter in self.emitters.values_mut() {
+            emitter.sort_particles(camera_position);
         }
     }
 
@@ -113,7 +150,7 @@ impl ParticleSystemManager {
         
         for emitter in self.emitters.values() {
             stats.total_emitters += 1;
-            stats.total_particles += emitter.particles.len();
+            stats.total_particles += emitter.particle_count();
             
             if emitter.is_active() {
                 stats.active_emitters += 1;
@@ -190,6 +227,7 @@ impl ParticlePresets {
             max_particles: 200,
             emission_rate: 50.0,
             burst_count: 0,
+            bursts: Vec::new(),
             lifetime: 2.0,
             start_lifetime_range: (1.5, 2.5),
             start_speed_range: (2.0, 4.0),
@@ -197,6 +235,7 @@ impl ParticlePresets {
             start_color: [1.0, 0.4, 0.0, 1.0],
             end_color: [1.0, 0.0, 0.0, 0.0],
             gravity: Vec3::new(0.0, 1.0, 0.0),
+            forces: Vec::new(),
             shape: EmissionShape::Circle { radius: 0.5 },
             texture_path: Some("assets/textures/fire_particle.png".to_string()),
             blend_mode: EmitterBlendMode::Additive,
@@ -215,6 +254,9 @@ impl ParticlePresets {
                 (1.0, [0.5, 0.0, 0.0, 0.0]),
             ])),
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: true,
+            use_unscaled_time: false,
             sorting_layer: 0,
             order_in_layer: 0,
         }
@@ -226,6 +268,7 @@ impl ParticlePresets {
             max_particles: 150,
             emission_rate: 30.0,
             burst_count: 0,
+            bursts: Vec::new(),
             lifetime: 3.0,
             start_lifetime_range: (2.5, 3.5),
             start_speed_range: (0.5, 1.5),
@@ -233,6 +276,7 @@ impl ParticlePresets {
             start_color: [0.5, 0.5, 0.5, 0.8],
             end_color: [0.3, 0.3, 0.3, 0.0],
             gravity: Vec3::new(0.0, -0.5, 0.0),
+            forces: Vec::new(),
             shape: EmissionShape::Circle { radius: 0.3 },
             texture_path: Some("assets/textures/smoke_particle.png".to_string()),
             blend_mode: EmitterBlendMode::Alpha,
@@ -248,6 +292,9 @@ impl ParticlePresets {
                 (1.0, [0.3, 0.3, 0.3, 0.0]),
             ])),
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: true,
+            use_unscaled_time: false,
             sorting_layer: 0,
             order_in_layer: -1,
         }
@@ -259,6 +306,7 @@ impl ParticlePresets {
             max_particles: 100,
             emission_rate: 0.0,
             burst_count: 100,
+            bursts: Vec::new(),
             lifetime: 1.5,
             start_lifetime_range: (1.0, 2.0),
             start_speed_range: (5.0, 10.0),
@@ -266,6 +314,7 @@ impl ParticlePresets {
             start_color: [1.0, 1.0, 0.5, 1.0],
             end_color: [1.0, 0.0, 0.0, 0.0],
             gravity: Vec3::new(0.0, -2.0, 0.0),
+            forces: Vec::new(),
             shape: EmissionShape::Sphere { radius: 0.1 },
             texture_path: Some("assets/textures/spark_particle.png".to_string()),
             blend_mode: EmitterBlendMode::Additive,
@@ -284,6 +333,9 @@ impl ParticlePresets {
                 (1.0, [0.5, 0.0, 0.0, 0.0]),
             ])),
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: false,
+            use_unscaled_time: false,
             sorting_layer: 1,
             order_in_layer: 0,
         }
@@ -295,6 +347,7 @@ impl ParticlePresets {
             max_particles: 300,
             emission_rate: 20.0,
             burst_count: 0,
+            bursts: Vec::new(),
             lifetime: 10.0,
             start_lifetime_range: (8.0, 12.0),
             start_speed_range: (1.0, 2.0),
@@ -302,6 +355,7 @@ impl ParticlePresets {
             start_color: [1.0, 1.0, 1.0, 0.8],
             end_color: [1.0, 1.0, 1.0, 0.8],
             gravity: Vec3::new(0.0, -1.0, 0.0),
+            forces: Vec::new(),
             shape: EmissionShape::Box { 
                 size: Vec3::new(20.0, 1.0, 20.0) 
             },
@@ -314,6 +368,9 @@ impl ParticlePresets {
             ])),
             color_over_lifetime: None,
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: true,
+            use_unscaled_time: false,
             sorting_layer: 0,
             order_in_layer: 0,
         }
@@ -325,6 +382,7 @@ impl ParticlePresets {
             max_particles: 50,
             emission_rate: 25.0,
             burst_count: 0,
+            bursts: Vec::new(),
             lifetime: 2.0,
             start_lifetime_range: (1.5, 2.5),
             start_speed_range: (1.0, 2.0),
@@ -332,6 +390,7 @@ impl ParticlePresets {
             start_color: [0.3, 0.7, 1.0, 1.0],
             end_color: [0.0, 0.3, 1.0, 0.0],
             gravity: Vec3::ZERO,
+            forces: Vec::new(),
             shape: EmissionShape::Circle { radius: 1.0 },
             texture_path: Some("assets/textures/magic_particle.png".to_string()),
             blend_mode: EmitterBlendMode::Additive,
@@ -350,6 +409,9 @@ impl ParticlePresets {
                 (1.0, [0.0, 0.3, 1.0, 0.0]),
             ])),
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: false,
+            use_unscaled_time: false,
             sorting_layer: 1,
             order_in_layer: 1,
         }
@@ -361,6 +423,7 @@ impl ParticlePresets {
             max_particles: 500,
             emission_rate: 100.0,
             burst_count: 0,
+            bursts: Vec::new(),
             lifetime: 3.0,
             start_lifetime_range: (2.5, 3.5),
             start_speed_range: (8.0, 12.0),
@@ -368,6 +431,7 @@ impl ParticlePresets {
             start_color: [0.7, 0.8, 1.0, 0.6],
             end_color: [0.7, 0.8, 1.0, 0.6],
             gravity: Vec3::new(0.0, -15.0, 0.0),
+            forces: Vec::new(),
             shape: EmissionShape::Box { 
                 size: Vec3::new(50.0, 1.0, 50.0) 
             },
@@ -377,17 +441,67 @@ impl ParticlePresets {
             velocity_over_lifetime: None,
             color_over_lifetime: None,
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: true,
+            use_unscaled_time: false,
             sorting_layer: -1,
             order_in_layer: 0,
         }
     }
 
+    /// 余烬效果：湍流使火星飘忽上升
+    pub fn embers() -> EmitterConfig {
+        EmitterConfig {
+            max_particles: 120,
+            emission_rate: 20.0,
+            burst_count: 0,
+            bursts: Vec::new(),
+            lifetime: 0.0,
+            start_lifetime_range: (2.0, 4.0),
+            start_speed_range: (0.5, 1.5),
+            start_size_range: (0.02, 0.05),
+            start_color: [1.0, 0.6, 0.1, 1.0],
+            end_color: [0.8, 0.1, 0.0, 0.0],
+            gravity: Vec3::ZERO,
+            forces: vec![
+                ForceModule::constant(Vec3::new(0.0, 1.2, 0.0)),
+                ForceModule::Turbulence {
+                    strength: 3.0,
+                    frequency: 0.8,
+                    scroll_speed: 0.6,
+                    seed: 7,
+                },
+            ],
+            shape: EmissionShape::Circle { radius: 0.6 },
+            texture_path: Some("assets/textures/spark_particle.png".to_string()),
+            blend_mode: EmitterBlendMode::Additive,
+            size_over_lifetime: Some(SizeOverLifetime::new(vec![
+                (0.0, 1.0),
+                (0.8, 0.8),
+                (1.0, 0.0),
+            ])),
+            velocity_over_lifetime: None,
+            color_over_lifetime: Some(ColorOverLifetime::new(vec![
+                (0.0, [1.0, 0.8, 0.3, 1.0]),
+                (0.5, [1.0, 0.4, 0.0, 0.9]),
+                (1.0, [0.6, 0.0, 0.0, 0.0]),
+            ])),
+            simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: true,
+            use_unscaled_time: false,
+            sorting_layer: 1,
+            order_in_layer: 0,
+        }
+    }
+
     /// 治疗效果
     pub fn healing() -> EmitterConfig {
         EmitterConfig {
             max_particles: 80,
             emission_rate: 40.0,
             burst_count: 0,
+            bursts: Vec::new(),
             lifetime: 2.5,
             start_lifetime_range: (2.0, 3.0),
             start_speed_range: (1.0, 2.0),
@@ -395,6 +509,7 @@ impl ParticlePresets {
             start_color: [0.3, 1.0, 0.3, 0.8],
             end_color: [0.8, 1.0, 0.8, 0.0],
             gravity: Vec3::new(0.0, -0.5, 0.0),
+            forces: Vec::new(),
             shape: EmissionShape::Circle { radius: 0.5 },
             texture_path: Some("assets/textures/healing_particle.png".to_string()),
             blend_mode: EmitterBlendMode::Additive,
@@ -413,8 +528,60 @@ impl ParticlePresets {
                 (1.0, [0.8, 1.0, 0.8, 0.0]),
             ])),
             simulation_space: SimulationSpace::World,
+            collision: None,
+            prewarm: false,
+            use_unscaled_time: false,
             sorting_layer: 1,
             order_in_layer: 2,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embers_preset_uses_turbulence() {
+        let config = ParticlePresets::embers();
+        assert!(config.forces.iter().any(|force| matches!(force, ForceModule::Turbulence { .. })));
+
+        let mut manager = ParticleSystemManager::new(1000);
+        let id = manager.create_emitter(config);
+        manager.start_emitter(id);
+        for _ in 0..10 {
+            manager.update(0.1);
+        }
+        let emitter = manager.get_emitter(id).unwrap();
+        assert!(emitter.particle_count() > 0);
+    }
+
+    #[test]
+    fn paused_time_freezes_world_emitters_only() {
+        let mut time = crate::time::TimeManager::new();
+        let mut manager = ParticleSystemManager::new(1000);
+        let world = manager.create_emitter(EmitterConfig { prewarm: false, ..ParticlePresets::fire() });
+        let ui = manager.create_emitter(EmitterConfig {
+            prewarm: false,
+            use_unscaled_time: true,
+            ..ParticlePresets::fire()
+        });
+        manager.start_emitter(world);
+        manager.start_emitter(ui);
+
+        time.pause();
+        for _ in 0..10 {
+            time.advance(0.1);
+            manager.update_with_time(time.scaled_delta_time(), time.delta_time());
+        }
+        assert_eq!(manager.get_emitter(world).unwrap().particle_count(), 0);
+        let ui_count = manager.get_emitter(ui).unwrap().particle_count();
+        assert!(ui_count > 0);
+
+        // 恢复后世界特效继续发射
+        time.resume();
+        time.advance(0.1);
+        manager.update_with_time(time.scaled_delta_time(), time.delta_time());
+        assert!(manager.get_emitter(world).unwrap().particle_count() > 0);
+    }
+}
@@ -0,0 +1,159 @@
+//! 粒子透明排序
+//!
+//! 透明混合的粒子需要按到相机的距离从远到近绘制。排序只产生绘制顺序(粒子索引)，不移动粒子数据；
+//! 数量少时用比较排序，超过 `RADIX_SORT_THRESHOLD` 后改用基于距离浮点位模式的基数排序。
+//! 两种方式都是稳定的，距离相同的粒子保持原有顺序，结果可复现。
+
+use crate::math::Vec3;
+
+/// 超过该数量时使用基数排序
+pub const RADIX_SORT_THRESHOLD: usize = 256;
+
+/// 粒子排序器，复用内部缓冲避免每帧分配
+#[derive(Debug, Default, Clone)]
+pub struct ParticleSorter {
+    keys: Vec<u32>,
+    order: Vec<u32>,
+    scratch_keys: Vec<u32>,
+    scratch_order: Vec<u32>,
+}
+
+impl ParticleSorter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按到相机的距离从远到近排序，返回粒子索引
+    pub fn sort_back_to_front(&mut self, positions: impl IntoIterator<Item = Vec3>, camera_position: Vec3) -> &[u32] {
+        self.keys.clear();
+        self.keys.extend(
+            positions
+                .into_iter()
+                .map(|position| Self::far_to_near_key(position.distance_squared(camera_position))),
+        );
+        self.order.clear();
+        self.order.extend(0..self.keys.len() as u32);
+
+        if self.keys.len() > RADIX_SORT_THRESHOLD {
+            self.radix_sort();
+        } else {
+            let keys = &self.keys;
+            self.order.sort_by_key(|&index| keys[index as usize]);
+        }
+        &self.order
+    }
+
+    /// 不排序，按原顺序绘制
+    pub fn identity(&mut self, count: usize) -> &[u32] {
+        self.order.clear();
+        self.order.extend(0..count as u32);
+        &self.order
+    }
+
+    /// 最近一次排序得到的绘制顺序
+    pub fn order(&self) -> &[u32] {
+        &self.order
+    }
+
+    /// 非负浮点数的位模式与数值同序，取反后升序即为从远到近
+    fn far_to_near_key(distance_squared: f32) -> u32 {
+        // NaN经max后变为0
+        !distance_squared.max(0.0).to_bits()
+    }
+
+    /// 每次8位、共4轮的LSD基数排序
+    fn radix_sort(&mut self) {
+        let count = self.keys.len();
+        self.scratch_keys.resize(count, 0);
+        self.scratch_order.resize(count, 0);
+
+        for shift in (0..32).step_by(8) {
+            let mut offsets = [0usize; 256];
+            for &key in &self.keys {
+                offsets[((key >> shift) & 0xFF) as usize] += 1;
+            }
+            // 所有键在这一字节上相同时跳过
+            if offsets.iter().any(|&bucket| bucket == count) {
+                continue;
+            }
+
+            let mut total = 0;
+            for offset in offsets.iter_mut() {
+                let bucket = *offset;
+                *offset = total;
+                total += bucket;
+            }
+
+            for (&key, &index) in self.keys.iter().zip(self.order.iter()) {
+                let bucket = ((key >> shift) & 0xFF) as usize;
+                self.scratch_keys[offsets[bucket]] = key;
+                self.scratch_order[offsets[bucket]] = index;
+                offsets[bucket] += 1;
+            }
+
+            std::mem::swap(&mut self.keys, &mut self.scratch_keys);
+            std::mem::swap(&mut self.order, &mut self.scratch_order);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 伪随机但可复现的位置
+    fn scattered_positions(count: usize) -> Vec<Vec3> {
+        (0..count)
+            .map(|i| {
+                let t = i as f32;
+                Vec3::new((t * 12.9898).sin() * 50.0, (t * 78.233).sin() * 20.0, (t * 37.719).cos() * 50.0)
+            })
+            .collect()
+    }
+
+    fn assert_far_to_near(positions: &[Vec3], order: &[u32], camera: Vec3) {
+        assert_eq!(order.len(), positions.len());
+        let distances: Vec<f32> = order.iter().map(|&i| positions[i as usize].distance_squared(camera)).collect();
+        assert!(distances.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[test]
+    fn small_counts_sort_far_to_near() {
+        let positions = vec![Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, -10.0), Vec3::new(3.0, 0.0, -4.0)];
+        let mut sorter = ParticleSorter::new();
+        assert_eq!(sorter.sort_back_to_front(positions.iter().copied(), Vec3::ZERO), &[1, 2, 0]);
+    }
+
+    #[test]
+    fn radix_sort_matches_comparison_sort() {
+        let camera = Vec3::new(5.0, 2.0, -3.0);
+        let positions = scattered_positions(RADIX_SORT_THRESHOLD * 4);
+        let mut sorter = ParticleSorter::new();
+        let order = sorter.sort_back_to_front(positions.iter().copied(), camera).to_vec();
+        assert_far_to_near(&positions, &order, camera);
+
+        let mut expected: Vec<u32> = (0..positions.len() as u32).collect();
+        expected.sort_by(|&a, &b| {
+            let distance = |i: u32| positions[i as usize].distance_squared(camera);
+            distance(b).partial_cmp(&distance(a)).unwrap()
+        });
+        assert_eq!(order, expected);
+    }
+
+    #[test]
+    fn equal_distances_keep_original_order() {
+        let mut positions = vec![Vec3::X; RADIX_SORT_THRESHOLD + 10];
+        positions[7] = Vec3::X * 5.0;
+        let mut sorter = ParticleSorter::new();
+        let order = sorter.sort_back_to_front(positions.iter().copied(), Vec3::ZERO);
+        assert_eq!(order[0], 7);
+        assert!(order[1..].windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn identity_keeps_submission_order() {
+        let mut sorter = ParticleSorter::new();
+        assert_eq!(sorter.identity(3), &[0, 1, 2]);
+        assert_eq!(sorter.order(), &[0, 1, 2]);
+    }
+}
@@ -3,7 +3,7 @@
 use crate::math::{Vec3, Vec2, Mat4};
 use crate::particles::{ParticleEmitter, EmitterId, EmitterConfig, ParticleStats};
 use crate::render::RenderSystem;
-use crate::ecs::{World, Component, System, Entity};
+use crate::ecs::{World, Component, System, Entity, TimeResource};
 use specs::{WorldExt, Builder};
 use specs::VecStorage;
 use std::collections::HashMap;
@@ -76,13 +76,11 @@ impl ParticleUpdateSystem {
 }
 
 impl<'a> System<'a> for ParticleUpdateSystem {
-    type SystemData = ();
+    type SystemData = specs::Read<'a, TimeResource>;
     
-    fn run(&mut self, _: Self::SystemData) {
-        // Note: 在实际的specs系统中，这个方法会被正确调用
-        // 这里保持简化的实现
-        // 简化的更新逻辑
-        self.particle_manager.update(1.0 / 60.0);
+    fn run(&mut self, time: Self::SystemData) {
+        // 世界特效随游戏时间暂停，UI特效按真实时间继续
+        self.particle_manager.update_with_time(time.delta_time, time.unscaled_delta_time);
     }
 }
 
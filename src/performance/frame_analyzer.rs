@@ -165,6 +165,23 @@ impl FrameAnalyzer {
         }
     }
 
+    /// 直接记录一帧的耗时，用于回放离线采集的会话数据
+    pub fn record_frame_time(&mut self, frame_time: Duration) {
+        let start_time = match (self.frame_starts.back(), self.frame_times.back()) {
+            (Some(start), Some(time)) => *start + *time,
+            _ => Instant::now(),
+        };
+
+        self.frame_times.push_back(frame_time);
+        self.frame_starts.push_back(start_time);
+        self.total_frames += 1;
+
+        while self.frame_times.len() > self.max_history {
+            self.frame_times.pop_front();
+            self.frame_starts.pop_front();
+        }
+    }
+
     /// 获取当前统计
     pub fn get_stats(&self) -> FrameStats {
         if self.frame_times.is_empty() {
@@ -601,3 +618,23 @@ pub enum RegressionSeverity {
     Moderate, // 10-20% 下降
     Severe,   // > 20% 下降
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_frame_times_respect_history_limit() {
+        let mut analyzer = FrameAnalyzer::new(60.0);
+        analyzer.set_max_history(3);
+        for ms in [10, 20, 30, 40] {
+            analyzer.record_frame_time(Duration::from_millis(ms));
+        }
+
+        let stats = analyzer.get_stats();
+        assert_eq!(stats.total_frames, 4);
+        assert_eq!(stats.min_frame_time, Duration::from_millis(20));
+        assert_eq!(stats.max_frame_time, Duration::from_millis(40));
+        assert_eq!(stats.average_frame_time, Duration::from_millis(30));
+    }
+}
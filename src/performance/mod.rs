@@ -13,11 +13,17 @@ pub use memory_tracker::*;
 pub use frame_analyzer::*;
 
 use crate::EngineResult;
+use crate::serialization::utils as serialization_utils;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
+/// 性能会话文件格式版本
+pub const PERFORMANCE_SESSION_VERSION: u32 = 1;
+
 /// 性能统计数据
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PerformanceStats {
     pub frame_time: Duration,
     pub fps: f32,
@@ -30,7 +36,7 @@ pub struct PerformanceStats {
 }
 
 /// 内存使用统计
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MemoryUsage {
     pub total_allocated: usize,
     pub peak_allocated: usize,
@@ -42,7 +48,7 @@ pub struct MemoryUsage {
 }
 
 /// 渲染统计数据
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RenderStats {
     pub draw_calls: u32,
     pub triangles: u32,
@@ -50,12 +56,15 @@ pub struct RenderStats {
     pub texture_switches: u32,
     pub render_targets_switches: u32,
     pub shader_switches: u32,
+    /// 被遮挡剔除跳过的网格绘制数
+    #[serde(default)]
+    pub occlusion_culled: u32,
     pub gpu_memory_usage: usize,
     pub gpu_time: Duration,
 }
 
 /// 物理统计数据
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PhysicsStats {
     pub rigid_bodies: usize,
     pub colliders: usize,
@@ -68,7 +77,7 @@ pub struct PhysicsStats {
 }
 
 /// 音频统计数据
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AudioStats {
     pub active_sources: usize,
     pub total_sources: usize,
@@ -78,6 +87,78 @@ pub struct AudioStats {
     pub latency: Duration,
 }
 
+/// 帧预算：各子系统允许占用的帧时间比例
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrameBudget {
+    /// 目标帧时间
+    pub target_frame_time: Duration,
+    /// 物理模拟占帧预算的比例上限
+    pub physics_fraction: f32,
+    /// 渲染GPU时间占帧预算的比例上限
+    pub render_gpu_fraction: f32,
+}
+
+impl Default for FrameBudget {
+    fn default() -> Self {
+        Self {
+            target_frame_time: Duration::from_micros(16_667),
+            physics_fraction: 0.25,
+            render_gpu_fraction: 0.6,
+        }
+    }
+}
+
+impl FrameBudget {
+    /// 按目标帧率设置帧预算
+    pub fn for_target_fps(fps: f32) -> Self {
+        Self {
+            target_frame_time: Duration::from_secs_f32(1.0 / fps.max(1.0)),
+            ..Default::default()
+        }
+    }
+}
+
+/// 性能监控器配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceMonitorConfig {
+    pub sample_interval: Duration,
+    pub history_size: usize,
+    pub enabled: bool,
+    pub detailed_profiling: bool,
+    pub memory_tracking: bool,
+    pub gpu_profiling: bool,
+    #[serde(default)]
+    pub frame_budget: FrameBudget,
+}
+
+/// 保存到文件的性能会话，供离线分析
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceSession {
+    pub version: u32,
+    /// 保存时间(RFC 3339)
+    pub recorded_at: String,
+    pub config: PerformanceMonitorConfig,
+    pub stats_history: Vec<PerformanceStats>,
+    /// 保存时的详细分析快照(未开启详细分析时为None)
+    pub detailed_breakdown: Option<serde_json::Value>,
+}
+
+impl PerformanceSession {
+    /// 从文件读取会话，格式按扩展名自动识别
+    pub fn load<P: AsRef<Path>>(path: P) -> EngineResult<Self> {
+        let session: Self = serialization_utils::deserialize_auto(path)?;
+        if session.version > PERFORMANCE_SESSION_VERSION {
+            return Err(anyhow::anyhow!("不支持的性能会话版本: {}", session.version));
+        }
+        Ok(session)
+    }
+
+    /// 保存会话到文件，格式按扩展名自动识别
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> EngineResult<()> {
+        serialization_utils::serialize_auto(self, path, true)
+    }
+}
+
 /// 性能监控器
 pub struct PerformanceMonitor {
     profiler: Profiler,
@@ -90,6 +171,12 @@ pub struct PerformanceMonitor {
     last_sample_time: Instant,
     history_size: usize,
     stats_history: Vec<PerformanceStats>,
+    /// 渲染系统上报的最近一帧统计
+    render_stats: RenderStats,
+    /// 物理系统上报的最近一帧统计
+    physics_stats: PhysicsStats,
+    /// 子系统帧预算，用于生成超预算建议
+    frame_budget: FrameBudget,
     
     // 配置
     enabled: bool,
@@ -109,6 +196,9 @@ impl PerformanceMonitor {
             last_sample_time: Instant::now(),
             history_size: 300, // 5秒历史 @ 60 FPS
             stats_history: Vec::new(),
+            render_stats: RenderStats::default(),
+            physics_stats: PhysicsStats::default(),
+            frame_budget: FrameBudget::default(),
             enabled: true,
             detailed_profiling: false,
             memory_tracking: true,
@@ -129,6 +219,73 @@ impl PerformanceMonitor {
         }
     }
 
+    /// 当前配置
+    pub fn config(&self) -> PerformanceMonitorConfig {
+        PerformanceMonitorConfig {
+            sample_interval: self.sample_interval,
+            history_size: self.history_size,
+            enabled: self.enabled,
+            detailed_profiling: self.detailed_profiling,
+            memory_tracking: self.memory_tracking,
+            gpu_profiling: self.gpu_profiling,
+            frame_budget: self.frame_budget,
+        }
+    }
+
+    /// 设置子系统帧预算
+    pub fn set_frame_budget(&mut self, frame_budget: FrameBudget) {
+        self.frame_budget = frame_budget;
+    }
+
+    pub fn frame_budget(&self) -> FrameBudget {
+        self.frame_budget
+    }
+
+    /// 应用配置
+    pub fn apply_config(&mut self, config: &PerformanceMonitorConfig) {
+        self.sample_interval = config.sample_interval;
+        self.set_history_size(config.history_size);
+        self.enabled = config.enabled;
+        self.detailed_profiling = config.detailed_profiling;
+        self.memory_tracking = config.memory_tracking;
+        self.gpu_profiling = config.gpu_profiling;
+        self.frame_budget = config.frame_budget;
+    }
+
+    /// 导出当前采集的会话
+    pub fn session(&self) -> PerformanceSession {
+        let detailed_breakdown = if self.detailed_profiling {
+            serde_json::to_value(self.profiler.get_detailed_breakdown()).ok()
+        } else {
+            None
+        };
+
+        PerformanceSession {
+            version: PERFORMANCE_SESSION_VERSION,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            config: self.config(),
+            stats_history: self.stats_history.clone(),
+            detailed_breakdown,
+        }
+    }
+
+    /// 保存历史记录、配置与详细分析到文件
+    pub fn save_session<P: AsRef<Path>>(&self, path: P) -> EngineResult<()> {
+        self.session().save(path)
+    }
+
+    /// 从文件恢复配置与历史记录，返回读取的会话
+    pub fn load_session<P: AsRef<Path>>(&mut self, path: P) -> EngineResult<PerformanceSession> {
+        let session = PerformanceSession::load(path)?;
+        self.apply_config(&session.config);
+        self.stats_history = session.stats_history.clone();
+        if self.stats_history.len() > self.history_size {
+            let excess = self.stats_history.len() - self.history_size;
+            self.stats_history.drain(..excess);
+        }
+        Ok(session)
+    }
+
     /// 启用/禁用监控
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
@@ -199,6 +356,16 @@ impl PerformanceMonitor {
         }
     }
 
+    /// 更新渲染统计，渲染系统每帧结束时调用
+    pub fn update_render_stats(&mut self, stats: RenderStats) {
+        self.render_stats = stats;
+    }
+
+    /// 更新物理统计，物理系统每次更新后调用
+    pub fn update_physics_stats(&mut self, stats: PhysicsStats) {
+        self.physics_stats = stats;
+    }
+
     /// 获取当前性能统计
     pub fn get_current_stats(&self) -> PerformanceStats {
         if !self.enabled {
@@ -217,8 +384,8 @@ impl PerformanceMonitor {
             fps: frame_stats.fps,
             cpu_usage: self.get_cpu_usage(),
             memory_usage: memory_stats,
-            render_stats: RenderStats::default(), // TODO: 从渲染系统获取
-            physics_stats: PhysicsStats::default(), // TODO: 从物理系统获取
+            render_stats: self.render_stats.clone(),
+            physics_stats: self.physics_stats.clone(),
             audio_stats: AudioStats::default(), // TODO: 从音频系统获取
             custom_stats: self.metrics_collector.get_all_metrics(),
         }
@@ -297,75 +464,12 @@ impl PerformanceMonitor {
 
     /// 获取性能摘要
     fn get_performance_summary(&self) -> PerformanceSummary {
-        if self.stats_history.is_empty() {
-            return PerformanceSummary::default();
-        }
-
-        let mut min_fps = f32::MAX;
-        let mut max_fps = f32::MIN;
-        let mut total_fps = 0.0;
-        let mut frame_time_sum = Duration::ZERO;
-
-        for stats in &self.stats_history {
-            min_fps = min_fps.min(stats.fps);
-            max_fps = max_fps.max(stats.fps);
-            total_fps += stats.fps;
-            frame_time_sum += stats.frame_time;
-        }
-
-        let count = self.stats_history.len() as f32;
-        
-        PerformanceSummary {
-            average_fps: total_fps / count,
-            min_fps,
-            max_fps,
-            average_frame_time: frame_time_sum / self.stats_history.len() as u32,
-            total_samples: self.stats_history.len(),
-            peak_memory: self.stats_history.iter()
-                .map(|s| s.memory_usage.peak_allocated)
-                .max()
-                .unwrap_or(0),
-        }
+        PerformanceSummary::from_history(&self.stats_history)
     }
 
     /// 生成性能优化建议
     fn generate_recommendations(&self) -> Vec<PerformanceRecommendation> {
-        let mut recommendations = Vec::new();
-        let summary = self.get_performance_summary();
-
-        // FPS相关建议
-        if summary.average_fps < 30.0 {
-            recommendations.push(PerformanceRecommendation {
-                category: "FPS".to_string(),
-                severity: Severity::High,
-                title: "低帧率检测".to_string(),
-                description: format!("平均FPS为{:.1}，低于30FPS阈值", summary.average_fps),
-                suggestions: vec![
-                    "检查渲染管线瓶颈".to_string(),
-                    "优化着色器复杂度".to_string(),
-                    "减少绘制调用数量".to_string(),
-                    "启用遮挡剔除".to_string(),
-                ],
-            });
-        }
-
-        // 内存相关建议
-        if summary.peak_memory > 1024 * 1024 * 1024 { // 1GB
-            recommendations.push(PerformanceRecommendation {
-                category: "Memory".to_string(),
-                severity: Severity::Medium,
-                title: "高内存使用".to_string(),
-                description: format!("峰值内存使用达到{:.1}MB", summary.peak_memory as f64 / (1024.0 * 1024.0)),
-                suggestions: vec![
-                    "检查内存泄漏".to_string(),
-                    "优化纹理大小和格式".to_string(),
-                    "实现对象池".to_string(),
-                    "使用流式加载".to_string(),
-                ],
-            });
-        }
-
-        recommendations
+        PerformanceRecommendation::from_summary_with_budget(&self.get_performance_summary(), &self.frame_budget)
     }
 
     /// 导出CSV格式
@@ -413,6 +517,8 @@ impl PerformanceMonitor {
         <div class="metric">Average FPS: {:.1}</div>
         <div class="metric">Min FPS: {:.1}</div>
         <div class="metric">Max FPS: {:.1}</div>
+        <div class="metric">1% Low FPS: {:.1}</div>
+        <div class="metric">5% Low FPS: {:.1}</div>
         <div class="metric">Average Frame Time: {:.2}ms</div>
         <div class="metric">Peak Memory: {:.1}MB</div>
     </div>
@@ -426,6 +532,8 @@ impl PerformanceMonitor {
             report.summary.average_fps,
             report.summary.min_fps,
             report.summary.max_fps,
+            report.summary.p1_low_fps,
+            report.summary.p5_low_fps,
             report.summary.average_frame_time.as_millis(),
             report.summary.peak_memory as f64 / (1024.0 * 1024.0),
             report.recommendations.iter()
@@ -477,15 +585,101 @@ pub struct PerformanceReport {
     pub recommendations: Vec<PerformanceRecommendation>,
 }
 
+impl PerformanceReport {
+    /// 由保存的会话离线重新生成报告
+    ///
+    /// 帧分析按采样的帧时间回放得到；内存分析依赖运行时跟踪数据，离线时为None，
+    /// 详细分析的原始快照保留在 `session.detailed_breakdown` 中。
+    pub fn from_session(session: &PerformanceSession) -> Self {
+        let summary = PerformanceSummary::from_history(&session.stats_history);
+
+        let mut frame_analyzer = FrameAnalyzer::new(60.0);
+        frame_analyzer.set_max_history(session.stats_history.len().max(1));
+        for stats in &session.stats_history {
+            frame_analyzer.record_frame_time(stats.frame_time);
+        }
+
+        Self {
+            recommendations: PerformanceRecommendation::from_summary_with_budget(&summary, &session.config.frame_budget),
+            summary,
+            detailed_breakdown: None,
+            memory_analysis: None,
+            frame_analysis: frame_analyzer.get_analysis(),
+        }
+    }
+}
+
 /// 性能摘要
 #[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct PerformanceSummary {
     pub average_fps: f32,
     pub min_fps: f32,
     pub max_fps: f32,
+    /// 1% low：采样FPS从低到高排列的第1百分位，比min_fps更能反映卡顿且不受单个异常帧影响
+    pub p1_low_fps: f32,
+    /// 5% low：第5百分位FPS
+    pub p5_low_fps: f32,
     pub average_frame_time: Duration,
     pub total_samples: usize,
     pub peak_memory: usize,
+    /// 平均物理模拟时间
+    pub average_physics_time: Duration,
+    /// 平均渲染GPU时间
+    pub average_gpu_time: Duration,
+}
+
+impl PerformanceSummary {
+    /// 由采样历史计算摘要
+    pub fn from_history(history: &[PerformanceStats]) -> Self {
+        if history.is_empty() {
+            return Self::default();
+        }
+
+        let mut min_fps = f32::MAX;
+        let mut max_fps = f32::MIN;
+        let mut total_fps = 0.0;
+        let mut frame_time_sum = Duration::ZERO;
+        let mut physics_time_sum = Duration::ZERO;
+        let mut gpu_time_sum = Duration::ZERO;
+
+        for stats in history {
+            min_fps = min_fps.min(stats.fps);
+            max_fps = max_fps.max(stats.fps);
+            total_fps += stats.fps;
+            frame_time_sum += stats.frame_time;
+            physics_time_sum += stats.physics_stats.simulation_time;
+            gpu_time_sum += stats.render_stats.gpu_time;
+        }
+
+        let count = history.len() as f32;
+        let mut sorted_fps: Vec<f32> = history.iter().map(|stats| stats.fps).collect();
+        sorted_fps.sort_by(|a, b| a.total_cmp(b));
+        
+        Self {
+            average_fps: total_fps / count,
+            min_fps,
+            max_fps,
+            p1_low_fps: Self::percentile(&sorted_fps, 1.0),
+            p5_low_fps: Self::percentile(&sorted_fps, 5.0),
+            average_frame_time: frame_time_sum / history.len() as u32,
+            total_samples: history.len(),
+            peak_memory: history.iter()
+                .map(|s| s.memory_usage.peak_allocated)
+                .max()
+                .unwrap_or(0),
+            average_physics_time: physics_time_sum / history.len() as u32,
+            average_gpu_time: gpu_time_sum / history.len() as u32,
+        }
+    }
+
+    /// 已升序排列数据的第p百分位(最近秩法)，空数据返回0
+    pub fn percentile(sorted: &[f32], p: f32) -> f32 {
+        if sorted.is_empty() {
+            return 0.0;
+        }
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
 }
 
 /// 性能建议
@@ -498,6 +692,119 @@ pub struct PerformanceRecommendation {
     pub suggestions: Vec<String>,
 }
 
+impl PerformanceRecommendation {
+    /// 根据性能摘要生成优化建议(使用默认帧预算)
+    pub fn from_summary(summary: &PerformanceSummary) -> Vec<Self> {
+        Self::from_summary_with_budget(summary, &FrameBudget::default())
+    }
+
+    /// 根据性能摘要与子系统帧预算生成优化建议
+    pub fn from_summary_with_budget(summary: &PerformanceSummary, budget: &FrameBudget) -> Vec<Self> {
+        let mut recommendations = Vec::new();
+
+        // FPS相关建议
+        if summary.average_fps < 30.0 {
+            recommendations.push(Self {
+                category: "FPS".to_string(),
+                severity: Severity::High,
+                title: "低帧率检测".to_string(),
+                description: format!("平均FPS为{:.1}，低于30FPS阈值", summary.average_fps),
+                suggestions: vec![
+                    "检查渲染管线瓶颈".to_string(),
+                    "优化着色器复杂度".to_string(),
+                    "减少绘制调用数量".to_string(),
+                    "启用遮挡剔除".to_string(),
+                ],
+            });
+        }
+
+        // 内存相关建议
+        if summary.peak_memory > 1024 * 1024 * 1024 { // 1GB
+            recommendations.push(Self {
+                category: "Memory".to_string(),
+                severity: Severity::Medium,
+                title: "高内存使用".to_string(),
+                description: format!("峰值内存使用达到{:.1}MB", summary.peak_memory as f64 / (1024.0 * 1024.0)),
+                suggestions: vec![
+                    "检查内存泄漏".to_string(),
+                    "优化纹理大小和格式".to_string(),
+                    "实现对象池".to_string(),
+                    "使用流式加载".to_string(),
+                ],
+            });
+        }
+
+        // 子系统帧预算
+        if summary.total_samples > 0 {
+            if let Some(recommendation) = Self::over_budget(
+                "Physics",
+                "物理",
+                summary.average_physics_time,
+                budget.physics_fraction,
+                budget.target_frame_time,
+                vec![
+                    "减少活动刚体数量或让静止物体休眠".to_string(),
+                    "简化碰撞体形状".to_string(),
+                    "降低物理步进频率或子步数".to_string(),
+                ],
+            ) {
+                recommendations.push(recommendation);
+            }
+
+            if let Some(recommendation) = Self::over_budget(
+                "Render",
+                "渲染GPU",
+                summary.average_gpu_time,
+                budget.render_gpu_fraction,
+                budget.target_frame_time,
+                vec![
+                    "降低阴影与后处理质量".to_string(),
+                    "减少过度绘制和透明物体".to_string(),
+                    "启用LOD与遮挡剔除".to_string(),
+                ],
+            ) {
+                recommendations.push(recommendation);
+            }
+        }
+
+        recommendations
+    }
+
+    /// 子系统平均耗时超过帧预算比例时生成建议，超过整个帧预算时为高严重程度
+    fn over_budget(
+        category: &str,
+        subsystem: &str,
+        average_time: Duration,
+        fraction: f32,
+        target_frame_time: Duration,
+        suggestions: Vec<String>,
+    ) -> Option<Self> {
+        let budget = target_frame_time.as_secs_f32();
+        if budget <= 0.0 {
+            return None;
+        }
+        let usage = average_time.as_secs_f32() / budget;
+        if usage <= fraction {
+            return None;
+        }
+
+        Some(Self {
+            category: category.to_string(),
+            severity: if usage >= 1.0 { Severity::High } else { Severity::Medium },
+            title: format!("{}超出帧预算", subsystem),
+            description: format!(
+                "{}平均耗时{:.2}ms，占帧预算{:.2}ms的{:.0}%，超过{:.0}%的上限",
+                subsystem,
+                average_time.as_secs_f64() * 1000.0,
+                budget * 1000.0,
+                usage * 100.0,
+                fraction * 100.0,
+            ),
+            suggestions,
+        })
+    }
+}
+
 /// 严重程度
 #[derive(Debug, Clone, Copy, serde::Serialize)]
 pub enum Severity {
@@ -535,3 +842,190 @@ macro_rules! record_metric {
         $crate::performance::get_global_monitor().record_metric($name, $value);
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 采样FPS依次为给定值的监控器
+    fn monitor_with_fps(fps: &[f32]) -> PerformanceMonitor {
+        let mut monitor = PerformanceMonitor::new();
+        monitor.stats_history = fps
+            .iter()
+            .map(|&fps| PerformanceStats {
+                fps,
+                frame_time: Duration::from_secs_f32(1.0 / fps),
+                ..Default::default()
+            })
+            .collect();
+        monitor
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sanji_perf_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn saved_session_reloads_with_same_summary() {
+        let mut original = monitor_with_fps(&[60.0, 58.5, 12.25, 45.0, 30.0, 59.75]);
+        original.set_sample_interval(Duration::from_millis(33));
+
+        for name in ["session.json", "session.msgpack"] {
+            let path = temp_path(name);
+            original.save_session(&path).unwrap();
+
+            let mut restored = PerformanceMonitor::new();
+            let session = restored.load_session(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!(session.version, PERFORMANCE_SESSION_VERSION);
+            assert_eq!(restored.config(), original.config());
+            assert_eq!(restored.get_stats_history().len(), 6);
+
+            let (before, after) = (original.get_performance_summary(), restored.get_performance_summary());
+            assert_eq!(after.average_fps, before.average_fps);
+            assert_eq!(after.min_fps, 12.25);
+            assert_eq!(after.max_fps, 60.0);
+            assert_eq!(after.average_frame_time, before.average_frame_time);
+        }
+    }
+
+    #[test]
+    fn report_from_session_regenerates_recommendations() {
+        let session = monitor_with_fps(&[20.0, 25.0, 22.0]).session();
+        let report = PerformanceReport::from_session(&session);
+
+        assert_eq!(report.summary.total_samples, 3);
+        assert!(report.summary.average_fps < 30.0);
+        assert!(report.recommendations.iter().any(|recommendation| recommendation.category == "FPS"));
+        assert_eq!(report.frame_analysis.stats.total_frames, 3);
+        assert!(report.memory_analysis.is_none());
+    }
+
+    #[test]
+    fn newer_session_version_is_rejected() {
+        let mut session = monitor_with_fps(&[60.0]).session();
+        session.version = PERFORMANCE_SESSION_VERSION + 1;
+        let path = temp_path("future.json");
+        session.save(&path).unwrap();
+        assert!(PerformanceSession::load(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn render_stats_reach_current_stats() {
+        let mut monitor = PerformanceMonitor::new();
+        monitor.update_render_stats(RenderStats {
+            draw_calls: 2,
+            triangles: 14,
+            shader_switches: 2,
+            ..Default::default()
+        });
+
+        let stats = monitor.get_current_stats();
+        assert_eq!(stats.render_stats.draw_calls, 2);
+        assert_eq!(stats.render_stats.triangles, 14);
+        assert_eq!(stats.render_stats.shader_switches, 2);
+    }
+
+    /// 每帧物理与GPU耗时固定的60FPS历史
+    fn monitor_with_subsystem_times(physics: Duration, gpu: Duration) -> PerformanceMonitor {
+        let mut monitor = monitor_with_fps(&[60.0; 30]);
+        for stats in monitor.stats_history.iter_mut() {
+            stats.physics_stats.simulation_time = physics;
+            stats.render_stats.gpu_time = gpu;
+        }
+        monitor
+    }
+
+    #[test]
+    fn physics_over_budget_is_recommended() {
+        let frame = Duration::from_micros(16_667);
+        let monitor = monitor_with_subsystem_times(frame.mul_f32(0.8), Duration::from_millis(2));
+
+        let recommendations = monitor.generate_recommendations();
+        let physics: Vec<_> = recommendations.iter().filter(|r| r.category == "Physics").collect();
+        assert_eq!(physics.len(), 1);
+        assert!(physics[0].title.contains("物理"));
+        assert!(matches!(physics[0].severity, Severity::Medium));
+        assert!(recommendations.iter().all(|r| r.category != "Render"));
+    }
+
+    #[test]
+    fn subsystems_within_budget_are_not_recommended() {
+        let monitor = monitor_with_subsystem_times(Duration::from_millis(2), Duration::from_millis(5));
+        assert!(monitor
+            .generate_recommendations()
+            .iter()
+            .all(|r| r.category != "Physics" && r.category != "Render"));
+    }
+
+    #[test]
+    fn frame_budget_fraction_is_configurable() {
+        let mut monitor = monitor_with_subsystem_times(Duration::from_millis(2), Duration::from_millis(8));
+        assert!(monitor.generate_recommendations().iter().all(|r| r.category != "Render"));
+
+        // 30FPS目标下GPU占比降低；收紧比例后重新超出
+        monitor.set_frame_budget(FrameBudget {
+            render_gpu_fraction: 0.2,
+            ..FrameBudget::for_target_fps(30.0)
+        });
+        let recommendations = monitor.generate_recommendations();
+        assert!(recommendations.iter().any(|r| r.category == "Render"));
+        assert!(recommendations.iter().all(|r| r.category != "Physics"));
+    }
+
+    #[test]
+    fn whole_frame_over_budget_is_high_severity() {
+        let summary = PerformanceSummary {
+            total_samples: 1,
+            average_physics_time: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let recommendations = PerformanceRecommendation::from_summary_with_budget(&summary, &FrameBudget::default());
+        let physics = recommendations.iter().find(|r| r.category == "Physics").unwrap();
+        assert!(matches!(physics.severity, Severity::High));
+    }
+
+    #[test]
+    fn physics_stats_reach_current_stats() {
+        let mut monitor = PerformanceMonitor::new();
+        monitor.update_physics_stats(PhysicsStats {
+            rigid_bodies: 3,
+            simulation_time: Duration::from_millis(4),
+            solver_time: Duration::from_millis(3),
+            ..Default::default()
+        });
+
+        let stats = monitor.get_current_stats();
+        assert_eq!(stats.physics_stats.rigid_bodies, 3);
+        assert_eq!(stats.physics_stats.simulation_time, Duration::from_millis(4));
+        assert_eq!(stats.physics_stats.solver_time, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn low_fps_percentiles_ignore_single_outlier() {
+        // 200帧：一帧5fps的异常帧，2帧20fps，7帧40fps，其余60fps，顺序打乱
+        let mut fps = vec![60.0; 190];
+        fps.extend([5.0, 20.0, 20.0]);
+        fps.extend([40.0; 7]);
+        fps.rotate_left(97);
+        let summary = PerformanceSummary::from_history(&monitor_with_fps(&fps).stats_history);
+
+        assert_eq!(summary.total_samples, 200);
+        assert_eq!(summary.min_fps, 5.0);
+        assert_eq!(summary.p1_low_fps, 20.0);
+        assert_eq!(summary.p5_low_fps, 40.0);
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let sorted = [10.0, 20.0, 30.0, 40.0];
+        assert_eq!(PerformanceSummary::percentile(&sorted, 0.0), 10.0);
+        assert_eq!(PerformanceSummary::percentile(&sorted, 1.0), 10.0);
+        assert_eq!(PerformanceSummary::percentile(&sorted, 50.0), 20.0);
+        assert_eq!(PerformanceSummary::percentile(&sorted, 51.0), 30.0);
+        assert_eq!(PerformanceSummary::percentile(&sorted, 150.0), 40.0);
+        assert_eq!(PerformanceSummary::percentile(&[], 5.0), 0.0);
+    }
+}
@@ -0,0 +1,287 @@
+//! 宽相位碰撞检测
+//!
+//! 宽相位根据碰撞体包围盒找出可能碰撞的碰撞体对，交给窄相位精确检测。不同算法适合不同的空间分布：
+//! 扫掠剪枝适合沿某个方向散开的场景，空间哈希适合大小相近且均匀分布的物体，BVH适合大小差异大的物体。
+//! 所有算法都返回包围盒真正相交的下标对 `(i, j)`，`i < j` 且按升序排列，因此结果与算法无关。
+
+use crate::math::{Bvh, Vec3, AABB};
+use std::collections::{HashMap, HashSet};
+
+/// 宽相位算法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BroadPhase {
+    /// 沿分布最广的轴排序后扫掠
+    SweepAndPrune,
+    /// 均匀网格哈希，cell_size为格子边长
+    SpatialHash { cell_size: f32 },
+    /// 包围体层次结构
+    Bvh,
+}
+
+impl Default for BroadPhase {
+    fn default() -> Self {
+        BroadPhase::SweepAndPrune
+    }
+}
+
+impl BroadPhase {
+    /// 创建对应的宽相位实现
+    pub fn create(&self) -> Box<dyn BroadPhaseAlgorithm> {
+        match *self {
+            BroadPhase::SweepAndPrune => Box::new(SweepAndPrune::default()),
+            BroadPhase::SpatialHash { cell_size } => Box::new(SpatialHash::new(cell_size)),
+            BroadPhase::Bvh => Box::new(BvhBroadPhase),
+        }
+    }
+}
+
+/// 宽相位实现
+pub trait BroadPhaseAlgorithm: Send + Sync {
+    /// 算法名称
+    fn name(&self) -> &str;
+
+    /// 包围盒相交的下标对，`i < j` 且按升序排列
+    fn find_pairs(&mut self, bounds: &[AABB]) -> Vec<(usize, usize)>;
+}
+
+/// 扫掠剪枝
+#[derive(Debug, Default, Clone)]
+pub struct SweepAndPrune {
+    /// 复用的排序缓冲
+    order: Vec<usize>,
+}
+
+impl SweepAndPrune {
+    /// 包围盒中心方差最大的轴
+    fn sweep_axis(bounds: &[AABB]) -> usize {
+        let count = bounds.len().max(1) as f32;
+        let mean = bounds.iter().map(|aabb| aabb.center()).sum::<Vec3>() / count;
+        let variance = bounds
+            .iter()
+            .map(|aabb| (aabb.center() - mean) * (aabb.center() - mean))
+            .sum::<Vec3>();
+        if variance.x >= variance.y && variance.x >= variance.z {
+            0
+        } else if variance.y >= variance.z {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+impl BroadPhaseAlgorithm for SweepAndPrune {
+    fn name(&self) -> &str {
+        "SweepAndPrune"
+    }
+
+    fn find_pairs(&mut self, bounds: &[AABB]) -> Vec<(usize, usize)> {
+        let axis = Self::sweep_axis(bounds);
+        self.order.clear();
+        self.order.extend(0..bounds.len());
+        self.order.sort_by(|&a, &b| bounds[a].min[axis].total_cmp(&bounds[b].min[axis]));
+
+        let mut pairs = Vec::new();
+        for (position, &a) in self.order.iter().enumerate() {
+            for &b in &self.order[position + 1..] {
+                // 后续包围盒的起点都超过了a的终点
+                if bounds[b].min[axis] > bounds[a].max[axis] {
+                    break;
+                }
+                if bounds[a].intersects(&bounds[b]) {
+                    pairs.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+/// 空间哈希
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<(i32, i32, i32), Vec<usize>>,
+}
+
+impl SpatialHash {
+    /// 单个包围盒最多占用的格子数，超过的包围盒与所有包围盒直接比较
+    const MAX_CELLS_PER_AABB: i64 = 512;
+
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: if cell_size > 0.0 { cell_size } else { 1.0 },
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    fn cell_range(&self, aabb: &AABB) -> ([i32; 3], [i32; 3]) {
+        let min = (aabb.min / self.cell_size).floor();
+        let max = (aabb.max / self.cell_size).floor();
+        (
+            [min.x as i32, min.y as i32, min.z as i32],
+            [max.x as i32, max.y as i32, max.z as i32],
+        )
+    }
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new(4.0)
+    }
+}
+
+impl BroadPhaseAlgorithm for SpatialHash {
+    fn name(&self) -> &str {
+        "SpatialHash"
+    }
+
+    fn find_pairs(&mut self, bounds: &[AABB]) -> Vec<(usize, usize)> {
+        self.cells.clear();
+        let mut oversized = Vec::new();
+
+        for (index, aabb) in bounds.iter().enumerate() {
+            let (min, max) = self.cell_range(aabb);
+            let cell_count: i64 = (0..3).map(|axis| (max[axis] as i64 - min[axis] as i64 + 1).max(0)).product();
+            if cell_count > Self::MAX_CELLS_PER_AABB {
+                oversized.push(index);
+                continue;
+            }
+            for x in min[0]..=max[0] {
+                for y in min[1]..=max[1] {
+                    for z in min[2]..=max[2] {
+                        self.cells.entry((x, y, z)).or_default().push(index);
+                    }
+                }
+            }
+        }
+
+        let mut pairs = HashSet::new();
+        for indices in self.cells.values() {
+            for (position, &a) in indices.iter().enumerate() {
+                for &b in &indices[position + 1..] {
+                    if bounds[a].intersects(&bounds[b]) {
+                        pairs.insert((a.min(b), a.max(b)));
+                    }
+                }
+            }
+        }
+        for &a in &oversized {
+            for b in 0..bounds.len() {
+                if a != b && bounds[a].intersects(&bounds[b]) {
+                    pairs.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+
+        let mut pairs: Vec<_> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+/// 基于 `math::Bvh` 的宽相位，每步重建
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BvhBroadPhase;
+
+impl BroadPhaseAlgorithm for BvhBroadPhase {
+    fn name(&self) -> &str {
+        "Bvh"
+    }
+
+    fn find_pairs(&mut self, bounds: &[AABB]) -> Vec<(usize, usize)> {
+        let bvh = Bvh::build(bounds);
+        let mut pairs = Vec::new();
+        for (a, aabb) in bounds.iter().enumerate() {
+            // 叶节点包围盒相交不代表其中每个图元都相交
+            pairs.extend(
+                bvh.query_aabb(aabb)
+                    .into_iter()
+                    .filter(|&b| b > a && aabb.intersects(&bounds[b]))
+                    .map(|b| (a, b)),
+            );
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHMS: [BroadPhase; 4] = [
+        BroadPhase::SweepAndPrune,
+        BroadPhase::SpatialHash { cell_size: 2.0 },
+        BroadPhase::SpatialHash { cell_size: 0.25 },
+        BroadPhase::Bvh,
+    ];
+
+    /// 暴力比较所有包围盒
+    fn brute_force(bounds: &[AABB]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for a in 0..bounds.len() {
+            for b in a + 1..bounds.len() {
+                if bounds[a].intersects(&bounds[b]) {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// 固定排布：一排重叠的小盒子、一个覆盖大片区域的地面、远处的孤立盒子和重合的盒子
+    fn arrangement() -> Vec<AABB> {
+        let mut bounds: Vec<AABB> = (0..12)
+            .map(|i| AABB::from_center_size(Vec3::new(i as f32 * 0.8, 0.5, (i % 3) as f32 * 0.7), Vec3::ONE))
+            .collect();
+        bounds.push(AABB::new(Vec3::new(-50.0, -1.0, -50.0), Vec3::new(50.0, 0.1, 50.0)));
+        bounds.push(AABB::from_center_size(Vec3::new(30.0, 20.0, -30.0), Vec3::splat(2.0)));
+        bounds.push(AABB::from_center_size(Vec3::new(30.0, 20.0, -30.0), Vec3::splat(2.0)));
+        bounds.push(AABB::from_center_size(Vec3::new(-20.0, 40.0, 5.0), Vec3::splat(0.5)));
+        bounds
+    }
+
+    #[test]
+    fn all_broad_phases_find_the_same_pairs() {
+        let bounds = arrangement();
+        let expected = brute_force(&bounds);
+        assert!(expected.contains(&(0, 1)));
+        assert!(expected.contains(&(13, 14)));
+        assert!(!expected.iter().any(|&(a, b)| a == 15 || b == 15));
+
+        for algorithm in ALGORITHMS {
+            let mut broad_phase = algorithm.create();
+            assert_eq!(broad_phase.find_pairs(&bounds), expected, "{}", broad_phase.name());
+            // 复用内部缓冲后结果不变
+            assert_eq!(broad_phase.find_pairs(&bounds), expected, "{}", broad_phase.name());
+        }
+    }
+
+    #[test]
+    fn touching_boxes_and_empty_input() {
+        let bounds = [
+            AABB::new(Vec3::ZERO, Vec3::ONE),
+            AABB::new(Vec3::new(1.0, 0.0, 0.0), Vec3::new(2.0, 1.0, 1.0)),
+            AABB::new(Vec3::new(2.5, 0.0, 0.0), Vec3::new(3.0, 1.0, 1.0)),
+        ];
+        let expected = brute_force(&bounds);
+        for algorithm in ALGORITHMS {
+            let mut broad_phase = algorithm.create();
+            assert_eq!(broad_phase.find_pairs(&bounds), expected, "{}", broad_phase.name());
+            assert!(broad_phase.find_pairs(&[]).is_empty());
+        }
+    }
+
+    #[test]
+    fn invalid_cell_size_falls_back() {
+        assert_eq!(SpatialHash::new(0.0).cell_size(), 1.0);
+        assert_eq!(SpatialHash::new(-3.0).cell_size(), 1.0);
+        assert_eq!(BroadPhase::default(), BroadPhase::SweepAndPrune);
+    }
+}
@@ -1,6 +1,8 @@
 //! 碰撞体组件
 
+use crate::assets::AssetHandle;
 use crate::math::{Vec3, AABB, BoundingSphere};
+use crate::render::Mesh;
 use serde::{Deserialize, Serialize};
 use specs::{Component, VecStorage};
 use specs_derive::Component;
@@ -36,6 +38,11 @@ pub enum ColliderShape {
         vertices: Vec<Vec3>,
         indices: Vec<u32>,
     },
+    /// 三角网格(只用于静态几何体)，按网格资源构建三角形BVH做精确检测
+    TriangleMesh {
+        #[serde(with = "crate::physics::triangle_mesh::mesh_handle_path")]
+        mesh: AssetHandle<Mesh>,
+    },
     /// 复合形状（多个形状组合）
     Compound {
         shapes: Vec<(Vec3, ColliderShape)>, // (相对位置, 形状)
@@ -101,6 +108,13 @@ impl ColliderShape {
                     AABB::from_points(&transformed_vertices).unwrap_or_default()
                 }
             }
+            ColliderShape::TriangleMesh { mesh } => match mesh.get() {
+                Some(mesh) => {
+                    let transform = glam::Mat4::from_rotation_translation(rotation, position);
+                    mesh.local_aabb().transform(&transform)
+                }
+                None => AABB::from_center_size(position, Vec3::splat(1.0)),
+            },
             ColliderShape::Compound { shapes } => {
                 if shapes.is_empty() {
                     return AABB::from_center_size(position, Vec3::splat(1.0));
@@ -158,6 +172,13 @@ impl ColliderShape {
                     BoundingSphere::from_points(&transformed_vertices).unwrap_or_default()
                 }
             }
+            ColliderShape::TriangleMesh { mesh } => match mesh.get() {
+                Some(mesh) => {
+                    let sphere = mesh.local_bounds().sphere;
+                    BoundingSphere::new(position + sphere.center, sphere.radius)
+                }
+                None => BoundingSphere::new(position, 1.0),
+            },
             ColliderShape::Compound { shapes } => {
                 if shapes.is_empty() {
                     return BoundingSphere::new(position, 1.0);
@@ -200,6 +221,9 @@ impl ColliderShape {
                 // 网格体积计算比较复杂，这里返回估算值
                 1.0
             }
+            ColliderShape::TriangleMesh { .. } => {
+                0.0 // 静态几何体不参与质量计算
+            }
             ColliderShape::Compound { shapes } => {
                 shapes.iter().map(|(_, shape)| shape.volume()).sum()
             }
@@ -241,6 +265,16 @@ impl ColliderShape {
         }
     }
 
+    /// 创建静态三角网格形状
+    pub fn triangle_mesh(mesh: AssetHandle<Mesh>) -> Self {
+        Self::TriangleMesh { mesh }
+    }
+
+    /// 是否只能用于静态碰撞体
+    pub fn is_static_only(&self) -> bool {
+        matches!(self, ColliderShape::TriangleMesh { .. })
+    }
+
     /// 从顶点创建凸包网格
     pub fn convex_hull(vertices: Vec<Vec3>) -> Self {
         Self::Mesh {
@@ -260,6 +294,9 @@ pub struct Collider {
     pub is_trigger: bool,
     /// 物理材质
     pub material: ColliderMaterial,
+    /// 物理层(0~31)，由世界的碰撞矩阵决定层间是否碰撞
+    #[serde(default)]
+    pub collision_layer: u32,
     /// 碰撞组（用于碰撞过滤）
     pub collision_groups: u32,
     /// 碰撞掩码（与哪些组发生碰撞）
@@ -270,6 +307,11 @@ pub struct Collider {
     /// 缓存的边界球
     #[serde(skip)]
     pub bounding_sphere: Option<BoundingSphere>,
+    /// 最近一次 `update_bounds` 的世界位置与旋转
+    #[serde(skip)]
+    pub position: Vec3,
+    #[serde(skip)]
+    pub rotation: glam::Quat,
     /// 是否启用
     pub enabled: bool,
 }
@@ -280,10 +322,13 @@ impl Default for Collider {
             shape: ColliderShape::default(),
             is_trigger: false,
             material: ColliderMaterial::default(),
+            collision_layer: 0,
             collision_groups: 1,
             collision_mask: u32::MAX,
             aabb: AABB::default(),
             bounding_sphere: None,
+            position: Vec3::ZERO,
+            rotation: glam::Quat::IDENTITY,
             enabled: true,
         }
     }
@@ -304,6 +349,13 @@ impl Collider {
         self
     }
 
+    /// 设置物理层，碰撞组同时设为该层对应的位
+    pub fn with_collision_layer(mut self, layer: u32) -> Self {
+        self.collision_layer = layer;
+        self.collision_groups = 1u32.checked_shl(layer).unwrap_or(0);
+        self
+    }
+
     /// 设置碰撞组
     pub fn with_collision_groups(mut self, groups: u32) -> Self {
         self.collision_groups = groups;
@@ -326,6 +378,8 @@ impl Collider {
     pub fn update_bounds(&mut self, position: Vec3, rotation: glam::Quat) {
         self.aabb = self.shape.compute_aabb(position, rotation);
         self.bounding_sphere = Some(self.shape.compute_bounding_sphere(position));
+        self.position = position;
+        self.rotation = rotation;
     }
 
     /// 检查是否与另一个碰撞体的组匹配
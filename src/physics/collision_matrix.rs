@@ -0,0 +1,74 @@
+//! 物理层碰撞矩阵
+
+use serde::{Deserialize, Serialize};
+
+/// 层碰撞矩阵：决定哪些物理层之间产生碰撞，宽相位据此跳过不需要检测的碰撞对
+///
+/// 每层一行位掩码，矩阵始终对称。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionMatrix {
+    rows: [u32; CollisionMatrix::MAX_LAYERS],
+}
+
+impl CollisionMatrix {
+    /// 物理层数量
+    pub const MAX_LAYERS: usize = 32;
+
+    /// 所有层互相碰撞
+    pub fn new() -> Self {
+        Self {
+            rows: [u32::MAX; Self::MAX_LAYERS],
+        }
+    }
+
+    /// 所有层互不碰撞
+    pub fn none() -> Self {
+        Self {
+            rows: [0; Self::MAX_LAYERS],
+        }
+    }
+
+    /// 设置两层是否碰撞，超出范围的层会被忽略
+    pub fn set_collides(&mut self, layer_a: u32, layer_b: u32, collides: bool) {
+        let (a, b) = (layer_a as usize, layer_b as usize);
+        if a >= Self::MAX_LAYERS || b >= Self::MAX_LAYERS {
+            log::warn!("物理层超出范围: {} / {}", layer_a, layer_b);
+            return;
+        }
+        if collides {
+            self.rows[a] |= 1 << b;
+            self.rows[b] |= 1 << a;
+        } else {
+            self.rows[a] &= !(1 << b);
+            self.rows[b] &= !(1 << a);
+        }
+    }
+
+    /// 两层是否碰撞
+    pub fn collides(&self, layer_a: u32, layer_b: u32) -> bool {
+        let (a, b) = (layer_a as usize, layer_b as usize);
+        a < Self::MAX_LAYERS && b < Self::MAX_LAYERS && self.rows[a] & (1 << b) != 0
+    }
+
+    /// 与指定层碰撞的所有层(位掩码)
+    pub fn layer_mask(&self, layer: u32) -> u32 {
+        self.rows.get(layer as usize).copied().unwrap_or(0)
+    }
+
+    /// 设置某层只与mask中的层碰撞
+    pub fn set_layer_mask(&mut self, layer: u32, mask: u32) {
+        if layer as usize >= Self::MAX_LAYERS {
+            log::warn!("物理层超出范围: {}", layer);
+            return;
+        }
+        for other in 0..Self::MAX_LAYERS as u32 {
+            self.set_collides(layer, other, mask & (1 << other) != 0);
+        }
+    }
+}
+
+impl Default for CollisionMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,8 +4,14 @@ pub mod world;
 pub mod collider;
 pub mod rigid_body;
 pub mod systems;
+pub mod collision_matrix;
+pub mod broad_phase;
+pub mod triangle_mesh;
 
 pub use world::*;
 pub use collider::*;
 pub use rigid_body::*;
 pub use systems::*;
+pub use collision_matrix::*;
+pub use broad_phase::*;
+pub use triangle_mesh::*;
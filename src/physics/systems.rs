@@ -66,17 +66,24 @@ impl<'a> System<'a> for PhysicsSystem {
         ReadStorage<'a, PhysicsRigidBody>,
         ReadStorage<'a, Collider>,
         specs::Read<'a, crate::ecs::TimeResource>,
+        specs::Write<'a, crate::ecs::SubsystemErrors>,
     );
 
-    fn run(&mut self, (entities, mut transforms, rigid_bodies, colliders, time): Self::SystemData) {
-        let delta_time = time.delta_time;
+    fn run(&mut self, (entities, mut transforms, rigid_bodies, colliders, time, mut errors): Self::SystemData) {
+        // 步长和最大步数跟随时间管理器的固定步长
+        if time.fixed_timestep > 0.0 && time.fixed_timestep != self.physics_world.fixed_timestep() {
+            self.physics_world.set_fixed_timestep(time.fixed_timestep);
+        }
+        if time.max_substeps > 0 && time.max_substeps != self.physics_world.fixed_step().max_substeps() {
+            self.physics_world.set_max_substeps(time.max_substeps);
+        }
         
         // 1. 同步ECS Transform到物理世界  
         use specs::Join;
         for (entity, transform, rigid_body) in (&entities, &transforms, &rigid_bodies).join() {
             if let Some(physics_rb) = self.physics_world.get_rigid_body_mut(entity) {
                 // 只有在Transform被修改时才更新物理世界
-                if transform.dirty {
+                if transform.is_dirty() {
                     physics_rb.position = transform.position;
                     physics_rb.rotation = transform.rotation;
                 }
@@ -92,10 +99,12 @@ impl<'a> System<'a> for PhysicsSystem {
         }
         
         // 3. 更新物理世界
-        if let Err(e) = self.physics_world.update(delta_time) {
-            log::error!("物理世界更新失败: {}", e);
+        if let Err(e) = self.physics_world.update_steps(time.fixed_steps) {
+            // 由引擎记录并把ECS子系统标记为降级
+            errors.report("physics", format!("物理世界更新失败: {}", e));
             return;
         }
+        crate::performance::get_global_monitor().update_physics_stats(self.physics_world.performance_stats());
         
         // 4. 同步物理世界的结果回ECS Transform
         for (entity, mut transform) in (&entities, &mut transforms).join() {
@@ -373,3 +382,37 @@ pub mod physics_utils {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{ECSWorld, SystemSchedule};
+    use specs::Builder;
+
+    #[test]
+    fn ecs_update_steps_physics_without_replaying_fixed_steps() {
+        let mut world = ECSWorld::new().unwrap();
+        world.fixed_timestep_mut().set_timestep(0.25);
+        world.register_component::<PhysicsRigidBody>();
+        let entity = world
+            .create_entity()
+            .with(Transform::default())
+            .with(PhysicsRigidBody::dynamic_body())
+            .build();
+
+        let mut physics = PhysicsSystem::default();
+        physics.add_rigid_body(entity, PhysicsRigidBody::dynamic_body());
+        world.set_schedule(SystemSchedule::builder().with_system(physics, "physics", &[]).build());
+
+        let height = |world: &ECSWorld| world.world().read_storage::<Transform>().get(entity).unwrap().position.y;
+
+        // 不经过引擎的 `set_fixed_steps`，`update` 自己累加出固定步数
+        world.update(0.5).unwrap();
+        let after_step = height(&world);
+        assert!(after_step < 0.0);
+
+        // 不足一步时不再重放上一帧的步数
+        world.update(0.0).unwrap();
+        assert_eq!(height(&world), after_step);
+    }
+}
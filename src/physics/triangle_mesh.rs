@@ -0,0 +1,367 @@
+//! 三角网格碰撞体
+//!
+//! 静态关卡几何体的精确碰撞：由网格资源构建三角形BVH，射线和接触检测只精确测试BVH筛出的三角形。
+//! 三角网格碰撞体只能是静态的，不计算质量，也不会被碰撞响应移动。
+
+use crate::assets::{AssetHandle, AssetId};
+use crate::math::{ray_vs_triangle, Bvh, Ray, RayHit, AABB};
+use crate::render::Mesh;
+use glam::{Quat, Vec3};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 三角网格与球体的接触
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshContact {
+    /// 网格表面上离球心最近的点
+    pub point: Vec3,
+    /// 从网格指向球心的法线
+    pub normal: Vec3,
+    pub penetration_depth: f32,
+    pub triangle: usize,
+}
+
+/// 局部空间的三角形及其BVH
+#[derive(Debug, Clone, Default)]
+pub struct TriangleMeshShape {
+    vertices: Vec<Vec3>,
+    triangles: Vec<[u32; 3]>,
+    bvh: Bvh,
+}
+
+impl TriangleMeshShape {
+    /// 由顶点和三角形索引构建，越界的三角形被忽略
+    pub fn new(vertices: Vec<Vec3>, indices: &[u32]) -> Self {
+        let triangles: Vec<[u32; 3]> = indices
+            .chunks_exact(3)
+            .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+            .filter(|triangle| triangle.iter().all(|&index| (index as usize) < vertices.len()))
+            .collect();
+
+        let bounds: Vec<AABB> = triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let (a, b, c) = (vertices[a as usize], vertices[b as usize], vertices[c as usize]);
+                AABB::new(a.min(b).min(c), a.max(b).max(c))
+            })
+            .collect();
+
+        Self {
+            bvh: Bvh::build(&bounds),
+            vertices,
+            triangles,
+        }
+    }
+
+    pub fn from_mesh(mesh: &Mesh) -> Self {
+        let vertices = mesh.vertices.iter().map(|vertex| vertex.position).collect();
+        Self::new(vertices, &mesh.indices)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// 第index个三角形的三个顶点
+    pub fn triangle(&self, index: usize) -> [Vec3; 3] {
+        let [a, b, c] = self.triangles[index];
+        [self.vertices[a as usize], self.vertices[b as usize], self.vertices[c as usize]]
+    }
+
+    /// 局部空间包围盒
+    pub fn local_aabb(&self) -> Option<AABB> {
+        self.bvh.bounds()
+    }
+
+    /// 局部空间射线检测，返回最近的命中与三角形索引
+    ///
+    /// 网格是双面的，命中法线总是朝向射线来的一侧。
+    pub fn raycast(&self, ray: &Ray, max_distance: f32) -> Option<(RayHit, usize)> {
+        let mut closest: Option<(RayHit, usize)> = None;
+        for (index, entry_distance) in self.bvh.ray_candidates(ray, max_distance) {
+            let limit = closest.as_ref().map_or(max_distance, |(hit, _)| hit.distance);
+            // 候选按进入叶节点的距离排序，之后的三角形不可能更近
+            if entry_distance > limit {
+                break;
+            }
+
+            let [a, b, c] = self.triangle(index);
+            if let Some(mut hit) = ray_vs_triangle(ray, a, b, c) {
+                if hit.distance <= limit {
+                    if hit.normal.dot(ray.direction) > 0.0 {
+                        hit.normal = -hit.normal;
+                    }
+                    closest = Some((hit, index));
+                }
+            }
+        }
+        closest
+    }
+
+    /// 局部空间球体接触，返回穿透最深的三角形
+    pub fn contact_sphere(&self, center: Vec3, radius: f32) -> Option<MeshContact> {
+        let query = AABB::from_center_size(center, Vec3::splat(radius * 2.0));
+        let mut deepest: Option<MeshContact> = None;
+        for index in self.bvh.query_aabb(&query) {
+            let [a, b, c] = self.triangle(index);
+            let point = closest_point_on_triangle(center, a, b, c);
+            let offset = center - point;
+            let distance = offset.length();
+            if distance >= radius {
+                continue;
+            }
+
+            // 球心恰好在三角形上时用面法线
+            let normal = if distance > f32::EPSILON {
+                offset / distance
+            } else {
+                (b - a).cross(c - a).normalize_or_zero()
+            };
+            let penetration_depth = radius - distance;
+            if deepest.map_or(true, |contact| penetration_depth > contact.penetration_depth) {
+                deepest = Some(MeshContact { point, normal, penetration_depth, triangle: index });
+            }
+        }
+        deepest
+    }
+
+    /// 网格位于position/rotation时的世界空间射线检测
+    pub fn raycast_world(&self, position: Vec3, rotation: Quat, ray: &Ray, max_distance: f32) -> Option<(RayHit, usize)> {
+        let inverse = rotation.inverse();
+        let local_ray = Ray {
+            origin: inverse * (ray.origin - position),
+            direction: inverse * ray.direction,
+        };
+        self.raycast(&local_ray, max_distance).map(|(hit, index)| {
+            let hit = RayHit::new(position + rotation * hit.point, rotation * hit.normal, hit.distance);
+            (hit, index)
+        })
+    }
+
+    /// 网格位于position/rotation时的世界空间球体接触
+    pub fn contact_sphere_world(&self, position: Vec3, rotation: Quat, center: Vec3, radius: f32) -> Option<MeshContact> {
+        let local_center = rotation.inverse() * (center - position);
+        self.contact_sphere(local_center, radius).map(|contact| MeshContact {
+            point: position + rotation * contact.point,
+            normal: rotation * contact.normal,
+            ..contact
+        })
+    }
+}
+
+/// 三角形上离p最近的点(按Voronoi区域分类)
+fn closest_point_on_triangle(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+/// 按网格资源缓存的三角形BVH，资源重新加载后自动重建
+#[derive(Debug, Default)]
+pub struct TriangleMeshCache {
+    entries: HashMap<AssetId, (u64, Arc<TriangleMeshShape>)>,
+}
+
+impl TriangleMeshCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 确保句柄对应的BVH存在且基于当前代数的网格，网格尚未加载时返回None
+    pub fn refresh(&mut self, handle: &AssetHandle<Mesh>) -> Option<Arc<TriangleMeshShape>> {
+        let generation = handle.generation();
+        if let Some((cached, shape)) = self.entries.get(&handle.id()) {
+            if *cached == generation {
+                return Some(Arc::clone(shape));
+            }
+        }
+
+        let mesh = handle.get()?;
+        let shape = Arc::new(TriangleMeshShape::from_mesh(&mesh));
+        self.entries.insert(handle.id(), (generation, Arc::clone(&shape)));
+        Some(shape)
+    }
+
+    /// 获取已构建的BVH，不检查代数
+    pub fn get(&self, id: AssetId) -> Option<&Arc<TriangleMeshShape>> {
+        self.entries.get(&id).map(|(_, shape)| shape)
+    }
+
+    pub fn remove(&mut self, id: AssetId) -> bool {
+        self.entries.remove(&id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// 网格句柄的serde适配器：按资源路径保存
+///
+/// 反序列化得到ID为0的加载中占位句柄，需用其路径通过 `AssetManager::load` 加载后替换。
+pub mod mesh_handle_path {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(handle: &AssetHandle<Mesh>, serializer: S) -> Result<S::Ok, S::Error> {
+        handle.path().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AssetHandle<Mesh>, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        Ok(AssetHandle::loading(0, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::MeshVertex;
+
+    /// 沿+X升高的斜坡，表面为 y = x / 2 (0 <= x <= 4, -1 <= z <= 1)
+    fn ramp_vertices() -> (Vec<Vec3>, Vec<u32>) {
+        let vertices = vec![
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(4.0, 2.0, -1.0),
+            Vec3::new(4.0, 2.0, 1.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        (vertices, vec![0, 2, 1, 0, 3, 2])
+    }
+
+    fn ramp() -> TriangleMeshShape {
+        let (vertices, indices) = ramp_vertices();
+        TriangleMeshShape::new(vertices, &indices)
+    }
+
+    fn on_ramp(point: Vec3) -> bool {
+        (point.y - point.x / 2.0).abs() < 1e-4 && (0.0..=4.0).contains(&point.x) && point.z.abs() <= 1.0
+    }
+
+    #[test]
+    fn downward_ray_hits_ramp_surface() {
+        let shape = ramp();
+        assert_eq!(shape.triangle_count(), 2);
+
+        for x in [0.5, 1.7, 3.2] {
+            let ray = Ray::new(Vec3::new(x, 10.0, 0.3), Vec3::NEG_Y);
+            let (hit, _) = shape.raycast(&ray, 100.0).unwrap();
+            assert!(on_ramp(hit.point), "{:?}", hit.point);
+            assert!((hit.distance - (10.0 - x / 2.0)).abs() < 1e-4);
+            // 法线朝向射线来的一侧
+            assert!(hit.normal.y > 0.0);
+            assert!((hit.normal - Vec3::new(-1.0, 2.0, 0.0).normalize()).length() < 1e-4);
+        }
+
+        // 从下方射来也能命中(双面)，超出距离或范围外不命中
+        let (hit, _) = shape.raycast(&Ray::new(Vec3::new(2.0, -5.0, 0.0), Vec3::Y), 100.0).unwrap();
+        assert!(on_ramp(hit.point));
+        assert!(hit.normal.y < 0.0);
+        assert!(shape.raycast(&Ray::new(Vec3::new(2.0, 10.0, 0.0), Vec3::NEG_Y), 5.0).is_none());
+        assert!(shape.raycast(&Ray::new(Vec3::new(6.0, 10.0, 0.0), Vec3::NEG_Y), 100.0).is_none());
+    }
+
+    #[test]
+    fn world_raycast_applies_transform() {
+        let shape = ramp();
+        let position = Vec3::new(10.0, 1.0, 0.0);
+        let rotation = Quat::from_rotation_y(std::f32::consts::PI);
+        // 绕Y旋转180°后斜坡沿-X升高，局部x=2处位于世界x=8
+        let ray = Ray::new(Vec3::new(8.0, 10.0, 0.0), Vec3::NEG_Y);
+        let (hit, _) = shape.raycast_world(position, rotation, &ray, 100.0).unwrap();
+        assert!((hit.point - Vec3::new(8.0, 2.0, 0.0)).length() < 1e-4);
+        assert!((hit.distance - 8.0).abs() < 1e-4);
+        assert!(hit.normal.x > 0.0 && hit.normal.y > 0.0);
+    }
+
+    #[test]
+    fn sphere_contact_pushes_off_ramp() {
+        let shape = ramp();
+        let normal = Vec3::new(-1.0, 2.0, 0.0).normalize();
+        let surface = Vec3::new(2.0, 1.0, 0.0);
+        let contact = shape.contact_sphere(surface + normal * 0.3, 0.5).unwrap();
+        assert!(on_ramp(contact.point));
+        assert!((contact.normal - normal).length() < 1e-4);
+        assert!((contact.penetration_depth - 0.2).abs() < 1e-4);
+
+        assert!(shape.contact_sphere(surface + normal * 0.6, 0.5).is_none());
+    }
+
+    #[test]
+    fn out_of_range_triangles_are_ignored() {
+        let (vertices, mut indices) = ramp_vertices();
+        indices.extend([0, 1, 9]);
+        let shape = TriangleMeshShape::new(vertices, &indices);
+        assert_eq!(shape.triangle_count(), 2);
+        let bounds = shape.local_aabb().unwrap();
+        assert_eq!(bounds.max, Vec3::new(4.0, 2.0, 1.0));
+    }
+
+    #[test]
+    fn cache_rebuilds_after_mesh_reload() {
+        let (vertices, indices) = ramp_vertices();
+        let vertices: Vec<MeshVertex> = vertices
+            .into_iter()
+            .map(|position| MeshVertex { position, ..Default::default() })
+            .collect();
+        let handle = AssetHandle::new(7, &Arc::new(Mesh::from_geometry("ramp", vertices.clone(), indices.clone())), "ramp.obj");
+
+        let mut cache = TriangleMeshCache::new();
+        let first = cache.refresh(&handle).unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.refresh(&handle).unwrap()));
+
+        // 重新加载为只有一个三角形的网格
+        handle.slot().set_loaded(Arc::new(Mesh::from_geometry("ramp", vertices, indices[..3].to_vec())));
+        let rebuilt = cache.refresh(&handle).unwrap();
+        assert_eq!(rebuilt.triangle_count(), 1);
+        assert_eq!(cache.len(), 1);
+
+        let loading: AssetHandle<Mesh> = AssetHandle::loading(8, "pending.obj");
+        assert!(cache.refresh(&loading).is_none());
+    }
+}
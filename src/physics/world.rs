@@ -1,10 +1,12 @@
 //! 物理世界管理
 
 use crate::{EngineResult, EngineError};
-use crate::physics::{PhysicsRigidBody, Collider};
+use crate::physics::{PhysicsRigidBody, Collider, ColliderShape, CollisionMatrix, BroadPhase, BroadPhaseAlgorithm, RigidBodyType, TriangleMeshCache};
 use crate::math::{Vec3, AABB, BoundingSphere};
+use crate::time::FixedTimestep;
 
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 use specs::Entity;
 
 /// 物理世界配置
@@ -12,14 +14,20 @@ use specs::Entity;
 pub struct PhysicsConfig {
     /// 重力加速度
     pub gravity: Vec3,
-    /// 时间步长
+    /// 时间步长，在引擎中与 `TimeManager` 的固定步长保持一致
     pub timestep: f32,
-    /// 最大子步数
+    /// 最大子步数，在引擎中与 `TimeManager` 的固定步长上限保持一致
     pub max_substeps: u32,
+    /// 速度求解迭代次数
+    pub velocity_iterations: u32,
+    /// 位置修正迭代次数
+    pub position_iterations: u32,
     /// 启用连续碰撞检测
     pub enable_ccd: bool,
     /// 世界边界
     pub world_bounds: Option<AABB>,
+    /// 宽相位算法
+    pub broad_phase: BroadPhase,
 }
 
 impl Default for PhysicsConfig {
@@ -27,9 +35,12 @@ impl Default for PhysicsConfig {
         Self {
             gravity: Vec3::new(0.0, -9.81, 0.0),
             timestep: 1.0 / 60.0,
-            max_substeps: 4,
+            max_substeps: FixedTimestep::DEFAULT_MAX_SUBSTEPS,
+            velocity_iterations: 8,
+            position_iterations: 3,
             enable_ccd: false,
             world_bounds: Some(AABB::from_center_size(Vec3::ZERO, Vec3::splat(1000.0))),
+            broad_phase: BroadPhase::default(),
         }
     }
 }
@@ -55,30 +66,64 @@ pub struct PhysicsWorld {
     colliders: HashMap<Entity, Collider>,
     /// 碰撞对
     collision_pairs: HashSet<(Entity, Entity)>,
+    /// 层碰撞矩阵
+    collision_matrix: CollisionMatrix,
+    /// 宽相位实现
+    broad_phase: Box<dyn BroadPhaseAlgorithm>,
+    /// 最近一步被层过滤跳过的碰撞对数
+    filtered_pairs: usize,
     /// 碰撞事件缓冲区
     collision_events: Vec<CollisionEvent>,
-    /// 累积时间
-    accumulated_time: f32,
+    /// 三角网格碰撞体的BVH
+    triangle_meshes: TriangleMeshCache,
+    /// 固定步长累加器
+    fixed_step: FixedTimestep,
     /// 是否暂停物理模拟
     paused: bool,
+    /// 最近一次update的各阶段耗时
+    timings: PhysicsTimings,
+}
+
+/// 物理各阶段耗时
+///
+/// 三个阶段之和约等于 `simulation_time`：施力、速度与位置积分计入求解阶段。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhysicsTimings {
+    pub broad_phase_time: Duration,
+    pub narrow_phase_time: Duration,
+    /// 施力、积分与碰撞响应
+    pub solver_time: Duration,
+    pub simulation_time: Duration,
+    /// 本次update执行的固定步数
+    pub steps: u32,
 }
 
 impl PhysicsWorld {
     /// 创建新的物理世界
     pub fn new(config: PhysicsConfig) -> Self {
+        let fixed_step = FixedTimestep::new(config.timestep, config.max_substeps);
+        let broad_phase = config.broad_phase.create();
         Self {
             config,
             rigid_bodies: HashMap::new(),
             colliders: HashMap::new(),
             collision_pairs: HashSet::new(),
+            collision_matrix: CollisionMatrix::default(),
+            broad_phase,
+            filtered_pairs: 0,
             collision_events: Vec::new(),
-            accumulated_time: 0.0,
+            triangle_meshes: TriangleMeshCache::new(),
+            fixed_step,
             paused: false,
+            timings: PhysicsTimings::default(),
         }
     }
 
     /// 添加刚体
-    pub fn add_rigid_body(&mut self, entity: Entity, rigid_body: PhysicsRigidBody) {
+    pub fn add_rigid_body(&mut self, entity: Entity, mut rigid_body: PhysicsRigidBody) {
+        if let Some(collider) = self.colliders.get(&entity) {
+            Self::enforce_static(&collider.shape, &mut rigid_body);
+        }
         self.rigid_bodies.insert(entity, rigid_body);
     }
 
@@ -89,9 +134,24 @@ impl PhysicsWorld {
 
     /// 添加碰撞体
     pub fn add_collider(&mut self, entity: Entity, collider: Collider) {
+        if let ColliderShape::TriangleMesh { mesh } = &collider.shape {
+            self.triangle_meshes.refresh(mesh);
+        }
+        if let Some(rigid_body) = self.rigid_bodies.get_mut(&entity) {
+            Self::enforce_static(&collider.shape, rigid_body);
+        }
         self.colliders.insert(entity, collider);
     }
 
+    /// 三角网格等只能静态使用的形状强制刚体为静态
+    fn enforce_static(shape: &ColliderShape, rigid_body: &mut PhysicsRigidBody) {
+        if shape.is_static_only() && rigid_body.body_type != RigidBodyType::Static {
+            log::warn!("三角网格碰撞体只能用于静态刚体，已改为Static");
+            rigid_body.body_type = RigidBodyType::Static;
+            rigid_body.velocity = Vec3::ZERO;
+        }
+    }
+
     /// 移除碰撞体
     pub fn remove_collider(&mut self, entity: Entity) -> Option<Collider> {
         self.colliders.remove(&entity)
@@ -118,14 +178,24 @@ impl PhysicsWorld {
             return Ok(());
         }
 
-        self.accumulated_time += delta_time;
-        
-        let mut substeps = 0;
-        while self.accumulated_time >= self.config.timestep && substeps < self.config.max_substeps {
+        // 步数受max_substeps限制，超出部分丢弃
+        let substeps = self.fixed_step.accumulate(delta_time);
+        self.update_steps(substeps)
+    }
+
+    /// 执行substeps个固定步长，步数由 `TimeManager` 的固定步长累加器给出(已按最大步数限制)
+    pub fn update_steps(&mut self, substeps: u32) -> EngineResult<()> {
+        if self.paused {
+            return Ok(());
+        }
+
+        self.timings = PhysicsTimings::default();
+        let start = Instant::now();
+        for _ in 0..substeps {
             self.step(self.config.timestep)?;
-            self.accumulated_time -= self.config.timestep;
-            substeps += 1;
         }
+        self.timings.simulation_time = start.elapsed();
+        self.timings.steps = substeps;
         
         Ok(())
     }
@@ -136,15 +206,18 @@ impl PhysicsWorld {
         self.collision_events.clear();
         
         // 1. 应用外力和重力
+        let solver_start = Instant::now();
         self.apply_forces(dt);
         
         // 2. 积分速度
         self.integrate_velocities(dt);
+        self.timings.solver_time += solver_start.elapsed();
         
-        // 3. 检测碰撞
+        // 3. 检测碰撞(宽相位与窄相位分别计时)
         self.detect_collisions();
         
         // 4. 解决碰撞
+        let solver_start = Instant::now();
         self.resolve_collisions(dt);
         
         // 5. 积分位置
@@ -152,6 +225,7 @@ impl PhysicsWorld {
         
         // 6. 更新变换
         self.update_transforms();
+        self.timings.solver_time += solver_start.elapsed();
         
         Ok(())
     }
@@ -197,34 +271,81 @@ impl PhysicsWorld {
 
     /// 检测碰撞
     fn detect_collisions(&mut self) {
+        let broad_start = Instant::now();
         self.collision_pairs.clear();
+        self.filtered_pairs = 0;
+        self.refresh_triangle_meshes();
         
-        let entities: Vec<Entity> = self.colliders.keys().copied().collect();
+        // HashMap迭代顺序不固定，排序后碰撞对与事件顺序可复现
+        let mut entities: Vec<Entity> = self.colliders.keys().copied().collect();
+        crate::ecs::sort_entities(&mut entities);
         
-        // 宽相位碰撞检测 (简单的n^2算法)
-        for i in 0..entities.len() {
-            for j in i + 1..entities.len() {
-                let entity_a = entities[i];
-                let entity_b = entities[j];
-                
-                if let (Some(collider_a), Some(collider_b)) = 
-                    (self.colliders.get(&entity_a), self.colliders.get(&entity_b)) {
-                    
-                    // 检查AABB重叠
-                    if collider_a.aabb.intersects(&collider_b.aabb) {
-                        self.collision_pairs.insert((entity_a, entity_b));
-                    }
-                }
+        // 宽相位：包围盒相交的候选对，再按层碰撞矩阵和碰撞组过滤
+        let bounds: Vec<AABB> = entities.iter().map(|entity| self.colliders[entity].aabb).collect();
+        for (i, j) in self.broad_phase.find_pairs(&bounds) {
+            let (entity_a, entity_b) = (entities[i], entities[j]);
+            if self.should_collide(&self.colliders[&entity_a], &self.colliders[&entity_b]) {
+                self.collision_pairs.insert((entity_a, entity_b));
+            } else {
+                self.filtered_pairs += 1;
             }
         }
         
+        self.timings.broad_phase_time += broad_start.elapsed();
+        
         // 窄相位碰撞检测
-        let collision_pairs: Vec<_> = self.collision_pairs.iter().copied().collect();
+        let narrow_start = Instant::now();
+        let mut collision_pairs: Vec<_> = self.collision_pairs.iter().copied().collect();
+        collision_pairs.sort_unstable_by_key(|&(a, b)| (crate::ecs::entity_sort_key(a), crate::ecs::entity_sort_key(b)));
         for (entity_a, entity_b) in collision_pairs {
             if let Some(collision) = self.narrow_phase_detection(entity_a, entity_b) {
                 self.collision_events.push(collision);
             }
         }
+        self.timings.narrow_phase_time += narrow_start.elapsed();
+    }
+
+    /// 为三角网格碰撞体构建或重建BVH(网格加载完成或重新加载后)
+    fn refresh_triangle_meshes(&mut self) {
+        for collider in self.colliders.values() {
+            if let ColliderShape::TriangleMesh { mesh } = &collider.shape {
+                self.triangle_meshes.refresh(mesh);
+            }
+        }
+    }
+
+    /// 两个碰撞体是否需要检测碰撞
+    pub fn should_collide(&self, collider_a: &Collider, collider_b: &Collider) -> bool {
+        collider_a.enabled
+            && collider_b.enabled
+            && self.collision_matrix.collides(collider_a.collision_layer, collider_b.collision_layer)
+            && collider_a.can_collide_with(collider_b)
+    }
+
+    /// 切换宽相位算法
+    pub fn set_broad_phase(&mut self, broad_phase: BroadPhase) {
+        self.config.broad_phase = broad_phase;
+        self.broad_phase = broad_phase.create();
+    }
+
+    /// 当前宽相位算法
+    pub fn broad_phase(&self) -> BroadPhase {
+        self.config.broad_phase
+    }
+
+    /// 获取层碰撞矩阵
+    pub fn collision_matrix(&self) -> &CollisionMatrix {
+        &self.collision_matrix
+    }
+
+    /// 获取可变层碰撞矩阵
+    pub fn collision_matrix_mut(&mut self) -> &mut CollisionMatrix {
+        &mut self.collision_matrix
+    }
+
+    /// 替换层碰撞矩阵
+    pub fn set_collision_matrix(&mut self, matrix: CollisionMatrix) {
+        self.collision_matrix = matrix;
     }
 
     /// 窄相位碰撞检测
@@ -232,6 +353,17 @@ impl PhysicsWorld {
         let collider_a = self.colliders.get(&entity_a)?;
         let collider_b = self.colliders.get(&entity_b)?;
         
+        match (&collider_a.shape, &collider_b.shape) {
+            (ColliderShape::TriangleMesh { .. }, ColliderShape::TriangleMesh { .. }) => return None,
+            (ColliderShape::TriangleMesh { .. }, _) => {
+                return self.triangle_mesh_contact(entity_a, collider_a, entity_b, collider_b, false);
+            }
+            (_, ColliderShape::TriangleMesh { .. }) => {
+                return self.triangle_mesh_contact(entity_b, collider_b, entity_a, collider_a, true);
+            }
+            _ => {}
+        }
+        
         // 简化的球-球碰撞检测
         if let (Some(sphere_a), Some(sphere_b)) = (&collider_a.bounding_sphere, &collider_b.bounding_sphere) {
             let distance = (sphere_a.center - sphere_b.center).length();
@@ -266,34 +398,95 @@ impl PhysicsWorld {
         None
     }
 
+    /// 三角网格与另一碰撞体(按其边界球近似)的接触
+    ///
+    /// 法线从entity_a指向entity_b，mesh_is_b表示网格是碰撞对中的b。
+    fn triangle_mesh_contact(
+        &self,
+        mesh_entity: Entity,
+        mesh_collider: &Collider,
+        other_entity: Entity,
+        other_collider: &Collider,
+        mesh_is_b: bool,
+    ) -> Option<CollisionEvent> {
+        let ColliderShape::TriangleMesh { mesh } = &mesh_collider.shape else {
+            return None;
+        };
+        let shape = self.triangle_meshes.get(mesh.id())?;
+        let sphere = other_collider.bounding_sphere.as_ref()?;
+        let contact = shape.contact_sphere_world(mesh_collider.position, mesh_collider.rotation, sphere.center, sphere.radius)?;
+
+        let other_velocity = self.rigid_bodies.get(&other_entity).map(|rb| rb.velocity).unwrap_or(Vec3::ZERO);
+        let (entity_a, entity_b, contact_normal, relative_velocity) = if mesh_is_b {
+            (other_entity, mesh_entity, -contact.normal, -other_velocity)
+        } else {
+            (mesh_entity, other_entity, contact.normal, other_velocity)
+        };
+
+        Some(CollisionEvent {
+            entity_a,
+            entity_b,
+            contact_point: contact.point,
+            contact_normal,
+            penetration_depth: contact.penetration_depth,
+            relative_velocity,
+        })
+    }
+
     /// 解决碰撞
-    fn resolve_collisions(&mut self, dt: f32) {
+    fn resolve_collisions(&mut self, _dt: f32) {
         let collision_events = self.collision_events.clone();
-        for collision in collision_events.iter() {
-            self.resolve_collision(collision, dt);
+        if collision_events.is_empty() {
+            return;
+        }
+
+        // 速度迭代：每次迭代基于当前速度重新计算冲量
+        for _ in 0..self.config.velocity_iterations.max(1) {
+            for collision in collision_events.iter() {
+                self.resolve_collision_velocity(collision);
+            }
         }
+
+        // 位置迭代：修正量在迭代间均分，避免过度修正
+        let position_iterations = self.config.position_iterations.max(1);
+        for _ in 0..position_iterations {
+            for collision in collision_events.iter() {
+                self.resolve_collision_position(collision, position_iterations);
+            }
+        }
+    }
+
+    /// 获取碰撞双方的质量
+    fn collision_masses(&self, collision: &CollisionEvent) -> Option<(f32, f32)> {
+        let mass_a = self.body_mass(collision.entity_a)?;
+        let mass_b = self.body_mass(collision.entity_b)?;
+        if mass_a.is_infinite() && mass_b.is_infinite() {
+            return None;
+        }
+        Some((mass_a, mass_b))
+    }
+
+    /// 参与碰撞响应的质量，只能静态使用的碰撞体(三角网格)视为无穷大，可以没有刚体
+    fn body_mass(&self, entity: Entity) -> Option<f32> {
+        let static_only = self.colliders.get(&entity).map_or(false, |collider| collider.shape.is_static_only());
+        if static_only {
+            return Some(f32::INFINITY);
+        }
+        self.rigid_bodies.get(&entity).map(|rb| rb.mass)
     }
 
-    /// 解决单个碰撞
-    fn resolve_collision(&mut self, collision: &CollisionEvent, dt: f32) {
+    /// 解决单个碰撞的速度
+    fn resolve_collision_velocity(&mut self, collision: &CollisionEvent) {
         let restitution = 0.5; // 恢复系数
-        let friction = 0.3;    // 摩擦系数
-        
-        // 获取刚体
-        let (mass_a, vel_a) = if let Some(rb) = self.rigid_bodies.get(&collision.entity_a) {
-            (rb.mass, rb.velocity)
-        } else {
-            return;
-        };
         
-        let (mass_b, vel_b) = if let Some(rb) = self.rigid_bodies.get(&collision.entity_b) {
-            (rb.mass, rb.velocity)
-        } else {
+        let Some((mass_a, mass_b)) = self.collision_masses(collision) else {
             return;
         };
+        let vel_a = self.rigid_bodies.get(&collision.entity_a).map_or(Vec3::ZERO, |rb| rb.velocity);
+        let vel_b = self.rigid_bodies.get(&collision.entity_b).map_or(Vec3::ZERO, |rb| rb.velocity);
         
         // 计算冲量
-        let relative_velocity = collision.relative_velocity;
+        let relative_velocity = vel_b - vel_a;
         let velocity_along_normal = relative_velocity.dot(collision.contact_normal);
         
         // 如果物体正在分离，不需要解决
@@ -319,9 +512,16 @@ impl PhysicsWorld {
                 rb_b.velocity += impulse / mass_b;
             }
         }
-        
+    }
+
+    /// 解决单个碰撞的穿透(位置修正)
+    fn resolve_collision_position(&mut self, collision: &CollisionEvent, iterations: u32) {
+        let Some((mass_a, mass_b)) = self.collision_masses(collision) else {
+            return;
+        };
+
         // 位置修正（防止穿透）
-        let correction_percent = 0.8;
+        let correction_percent = 0.8 / iterations as f32;
         let slop = 0.01;
         let correction_magnitude = (collision.penetration_depth - slop).max(0.0) / (1.0 / mass_a + 1.0 / mass_b) * correction_percent;
         let correction = collision.contact_normal * correction_magnitude;
@@ -397,6 +597,21 @@ impl PhysicsWorld {
         let mut hits = Vec::new();
         
         for (entity, collider) in &self.colliders {
+            // 三角网格精确测试到三角形
+            if let ColliderShape::TriangleMesh { mesh } = &collider.shape {
+                if let Some(shape) = self.triangle_meshes.get(mesh.id()) {
+                    if let Some((hit, _)) = shape.raycast_world(collider.position, collider.rotation, ray, max_distance) {
+                        hits.push(RaycastHit {
+                            entity: *entity,
+                            point: hit.point,
+                            normal: hit.normal,
+                            distance: hit.distance,
+                        });
+                    }
+                }
+                continue;
+            }
+
             if let Some(bounding_sphere) = &collider.bounding_sphere {
                 if let Some(hit) = ray.intersect_sphere(bounding_sphere) {
                     if hit.distance <= max_distance {
@@ -426,13 +641,83 @@ impl PhysicsWorld {
         self.config.gravity
     }
 
+    /// 设置求解器迭代次数(速度迭代, 位置迭代)
+    pub fn set_solver_iterations(&mut self, velocity: u32, position: u32) {
+        self.config.velocity_iterations = velocity.max(1);
+        self.config.position_iterations = position.max(1);
+    }
+
+    /// 获取求解器迭代次数(速度迭代, 位置迭代)
+    pub fn solver_iterations(&self) -> (u32, u32) {
+        (self.config.velocity_iterations, self.config.position_iterations)
+    }
+
+    /// 设置固定时间步长
+    pub fn set_fixed_timestep(&mut self, timestep: f32) {
+        if timestep > 0.0 {
+            self.config.timestep = timestep;
+            self.fixed_step.set_timestep(timestep);
+        } else {
+            log::warn!("无效的物理时间步长: {}", timestep);
+        }
+    }
+
+    /// 获取固定时间步长
+    pub fn fixed_timestep(&self) -> f32 {
+        self.config.timestep
+    }
+
+    /// 设置每帧最大子步数，卡顿时超出的模拟时间会被丢弃
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.config.max_substeps = max_substeps.max(1);
+        self.fixed_step.set_max_substeps(max_substeps);
+    }
+
+    /// 获取固定步长累加器
+    pub fn fixed_step(&self) -> &FixedTimestep {
+        &self.fixed_step
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &PhysicsConfig {
+        &self.config
+    }
+
+    /// 获取最近一次update的各阶段耗时
+    pub fn timings(&self) -> PhysicsTimings {
+        self.timings
+    }
+
+    /// 供性能监控器使用的统计
+    pub fn performance_stats(&self) -> crate::performance::PhysicsStats {
+        crate::performance::PhysicsStats {
+            rigid_bodies: self.rigid_bodies.len(),
+            colliders: self.colliders.len(),
+            active_bodies: self
+                .rigid_bodies
+                .values()
+                .filter(|rigid_body| rigid_body.is_dynamic() && !rigid_body.is_sleeping)
+                .count(),
+            collision_pairs: self.collision_pairs.len(),
+            simulation_time: self.timings.simulation_time,
+            broad_phase_time: self.timings.broad_phase_time,
+            narrow_phase_time: self.timings.narrow_phase_time,
+            solver_time: self.timings.solver_time,
+        }
+    }
+
     /// 获取统计信息
     pub fn stats(&self) -> PhysicsStats {
         PhysicsStats {
             rigid_body_count: self.rigid_bodies.len(),
             collider_count: self.colliders.len(),
             active_collision_pairs: self.collision_pairs.len(),
+            filtered_collision_pairs: self.filtered_pairs,
             collision_events: self.collision_events.len(),
+            broad_phase_time: self.timings.broad_phase_time,
+            narrow_phase_time: self.timings.narrow_phase_time,
+            solver_time: self.timings.solver_time,
+            simulation_time: self.timings.simulation_time,
         }
     }
 }
@@ -452,5 +737,221 @@ pub struct PhysicsStats {
     pub rigid_body_count: usize,
     pub collider_count: usize,
     pub active_collision_pairs: usize,
+    /// 包围盒相交但被层碰撞矩阵或碰撞组跳过的碰撞对
+    pub filtered_collision_pairs: usize,
     pub collision_events: usize,
+    pub broad_phase_time: Duration,
+    pub narrow_phase_time: Duration,
+    pub solver_time: Duration,
+    pub simulation_time: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::{Builder, WorldExt};
+
+    fn entity() -> Entity {
+        let mut world = specs::World::new();
+        world.create_entity().build()
+    }
+
+    #[test]
+    fn solver_iterations_are_at_least_one() {
+        let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+        physics.set_solver_iterations(0, 5);
+        assert_eq!(physics.solver_iterations(), (1, 5));
+    }
+
+    #[test]
+    fn invalid_timestep_is_ignored() {
+        let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+        physics.set_fixed_timestep(0.02);
+        physics.set_fixed_timestep(0.0);
+        physics.set_fixed_timestep(-1.0);
+        assert_eq!(physics.fixed_timestep(), 0.02);
+    }
+
+    #[test]
+    fn update_runs_requested_steps_and_records_timings() {
+        let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+        let body = entity();
+        physics.add_rigid_body(body, PhysicsRigidBody::dynamic_body());
+
+        physics.update_steps(3).unwrap();
+        assert_eq!(physics.timings().steps, 3);
+        assert!(physics.get_rigid_body(body).unwrap().position.y < 0.0);
+
+        physics.pause();
+        physics.update_steps(2).unwrap();
+        assert_eq!(physics.timings().steps, 3);
+        physics.resume();
+        physics.update_steps(0).unwrap();
+        assert_eq!(physics.timings().steps, 0);
+    }
+
+    #[test]
+    fn update_accumulates_delta_time_up_to_max_substeps() {
+        let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+        physics.set_fixed_timestep(0.25);
+        physics.set_max_substeps(2);
+
+        physics.update(0.625).unwrap();
+        assert_eq!(physics.timings().steps, 2);
+        physics.update(0.0625).unwrap();
+        assert_eq!(physics.timings().steps, 0);
+        // 超出最大子步数的时间被丢弃
+        physics.update(2.0).unwrap();
+        assert_eq!(physics.timings().steps, 2);
+        assert!(physics.fixed_step().last_dropped() > 0.0);
+    }
+
+    #[test]
+    fn collision_matrix_filters_layer_pairs() {
+        const A: u32 = 1;
+        const B: u32 = 2;
+        const C: u32 = 3;
+
+        let mut world = specs::World::new();
+        let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+        physics.collision_matrix_mut().set_layer_mask(A, 1 << C);
+
+        // 三个互相重叠的球体，分别位于A、B、C层
+        let mut spawn = |layer: u32, x: f32| {
+            let entity = world.create_entity().build();
+            let mut collider = Collider::new(ColliderShape::sphere(1.0)).with_collision_layer(layer);
+            collider.update_bounds(Vec3::new(x, 0.0, 0.0), glam::Quat::IDENTITY);
+            physics.add_collider(entity, collider);
+            entity
+        };
+        let (a, b, c) = (spawn(A, 0.0), spawn(B, 0.5), spawn(C, 1.0));
+
+        physics.update_steps(1).unwrap();
+
+        let pairs: Vec<_> = physics.collision_events().iter().map(|event| (event.entity_a, event.entity_b)).collect();
+        let touching = |x, y| pairs.contains(&(x, y)) || pairs.contains(&(y, x));
+        assert!(!touching(a, b));
+        assert!(touching(a, c));
+        assert!(touching(b, c));
+        assert_eq!(physics.stats().filtered_collision_pairs, 1);
+    }
+
+    #[test]
+    fn collision_matrix_is_symmetric() {
+        let mut matrix = CollisionMatrix::none();
+        matrix.set_collides(2, 5, true);
+        assert!(matrix.collides(5, 2));
+        assert_eq!(matrix.layer_mask(2), 1 << 5);
+
+        matrix.set_layer_mask(5, 0);
+        assert!(!matrix.collides(2, 5));
+        // 超出范围的层不碰撞
+        assert!(!CollisionMatrix::new().collides(0, 32));
+    }
+
+    #[test]
+    fn broad_phase_choice_does_not_change_collisions() {
+        let mut world = specs::World::new();
+        let entities: Vec<Entity> = (0..8).map(|_| world.create_entity().build()).collect();
+
+        let mut results = Vec::new();
+        for broad_phase in [BroadPhase::SweepAndPrune, BroadPhase::SpatialHash { cell_size: 1.5 }, BroadPhase::Bvh] {
+            let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+            physics.set_broad_phase(broad_phase);
+            assert_eq!(physics.broad_phase(), broad_phase);
+            for (i, &entity) in entities.iter().enumerate() {
+                let mut collider = Collider::new(ColliderShape::sphere(0.6));
+                collider.update_bounds(Vec3::new((i % 4) as f32, (i / 4) as f32 * 3.0, 0.0), glam::Quat::IDENTITY);
+                physics.add_collider(entity, collider);
+            }
+            physics.update_steps(1).unwrap();
+
+            let mut pairs: Vec<_> = physics.collision_events().iter().map(|event| (event.entity_a, event.entity_b)).collect();
+            pairs.sort();
+            assert!(!pairs.is_empty());
+            results.push(pairs);
+        }
+        assert_eq!(results[0], results[1]);
+        assert_eq!(results[0], results[2]);
+    }
+
+    #[test]
+    fn phase_timings_sum_to_simulation_time() {
+        let mut world = specs::World::new();
+        let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+        // 10x10的球体网格，相邻球体互相重叠
+        for i in 0..100 {
+            let entity = world.create_entity().build();
+            let position = Vec3::new((i % 10) as f32 * 0.8, (i / 10) as f32 * 0.8, 0.0);
+            let mut rigid_body = PhysicsRigidBody::dynamic_body();
+            rigid_body.position = position;
+            physics.add_rigid_body(entity, rigid_body);
+            let mut collider = Collider::new(ColliderShape::sphere(0.5));
+            collider.update_bounds(position, glam::Quat::IDENTITY);
+            physics.add_collider(entity, collider);
+        }
+
+        physics.update_steps(4).unwrap();
+        let timings = physics.timings();
+        assert!(timings.broad_phase_time > Duration::ZERO);
+        assert!(timings.narrow_phase_time > Duration::ZERO);
+        assert!(timings.solver_time > Duration::ZERO);
+
+        // 各阶段在总耗时之内，且占了其中的大部分
+        let phases = timings.broad_phase_time + timings.narrow_phase_time + timings.solver_time;
+        assert!(phases <= timings.simulation_time);
+        assert!(phases.as_secs_f64() >= timings.simulation_time.as_secs_f64() * 0.5, "{:?}", timings);
+
+        let stats = physics.performance_stats();
+        assert_eq!(stats.rigid_bodies, 100);
+        assert_eq!(stats.colliders, 100);
+        assert_eq!(stats.active_bodies, 100);
+        assert_eq!(stats.simulation_time, timings.simulation_time);
+        assert_eq!(stats.solver_time, timings.solver_time);
+    }
+
+    #[test]
+    fn raycast_and_contacts_use_triangle_mesh_collider() {
+        use crate::assets::AssetHandle;
+        use crate::render::{Mesh, MeshVertex};
+
+        // 斜坡 y = x / 2
+        let vertices = [(0.0, 0.0, -1.0), (4.0, 2.0, -1.0), (4.0, 2.0, 1.0), (0.0, 0.0, 1.0)]
+            .map(|(x, y, z)| MeshVertex { position: Vec3::new(x, y, z), ..Default::default() })
+            .to_vec();
+        let mesh = Mesh::from_geometry("ramp", vertices, vec![0, 2, 1, 0, 3, 2]);
+        let handle = AssetHandle::new(1, &std::sync::Arc::new(mesh), "ramp.obj");
+
+        let mut world = specs::World::new();
+        let mut physics = PhysicsWorld::new(PhysicsConfig::default());
+        let ramp = world.create_entity().build();
+        let mut collider = Collider::new(ColliderShape::triangle_mesh(handle));
+        collider.update_bounds(Vec3::ZERO, glam::Quat::IDENTITY);
+        physics.add_collider(ramp, collider);
+        // 三角网格刚体被强制为静态
+        physics.add_rigid_body(ramp, PhysicsRigidBody::dynamic_body());
+        assert_eq!(physics.get_rigid_body(ramp).unwrap().body_type, RigidBodyType::Static);
+
+        let ray = crate::math::Ray::new(Vec3::new(3.0, 10.0, 0.5), Vec3::NEG_Y);
+        let hits = physics.raycast(&ray, 100.0);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entity, ramp);
+        assert!((hits[0].point - Vec3::new(3.0, 1.5, 0.5)).length() < 1e-4);
+
+        // 斜坡上方略微穿入的球体产生接触，法线从斜坡指向球
+        let ball = world.create_entity().build();
+        let mut sphere = Collider::new(ColliderShape::sphere(0.5));
+        sphere.update_bounds(Vec3::new(2.0, 1.4, 0.0), glam::Quat::IDENTITY);
+        physics.add_collider(ball, sphere);
+        physics.update_steps(1).unwrap();
+
+        let event = physics
+            .collision_events()
+            .iter()
+            .find(|event| event.entity_a == ball || event.entity_b == ball)
+            .unwrap();
+        let normal_to_ball = if event.entity_a == ramp { event.contact_normal } else { -event.contact_normal };
+        assert!(normal_to_ball.y > 0.0);
+        assert!(event.penetration_depth > 0.0);
+    }
 }
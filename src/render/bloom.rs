@@ -0,0 +1,439 @@
+//! Bloom辉光效果
+//!
+//! 亮部提取(软阈值) -> 逐级下采样 -> 逐级上采样并模糊累加 -> 按强度叠加到场景。
+//! 自发光材质的emission输出到HDR缓冲中，亮度超过阈值的部分即会产生辉光。
+//! `BloomProcessor` 是CPU参考实现，与bloom.wgsl中的GPU实现保持一致，可用于离屏验证。
+
+use crate::math::Vec3;
+use crate::render::{BloomConfig, RenderTarget};
+use wgpu::util::DeviceExt;
+
+/// Bloom着色器uniform(对应bloom.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct BloomUniforms {
+    texel_size: [f32; 2],
+    threshold: f32,
+    knee: f32,
+    intensity: f32,
+    radius: f32,
+    _padding: [f32; 2],
+}
+
+/// 亮度(Rec.709)
+fn luminance(color: Vec3) -> f32 {
+    color.dot(Vec3::new(0.2126, 0.7152, 0.0722))
+}
+
+/// CPU Bloom处理器
+pub struct BloomProcessor;
+
+impl BloomProcessor {
+    /// 软阈值亮部提取，低于 `threshold - knee` 的颜色完全被剔除
+    pub fn bright_pass(color: Vec3, threshold: f32, knee: f32) -> Vec3 {
+        let brightness = luminance(color);
+        let soft = (brightness - threshold + knee).clamp(0.0, 2.0 * knee);
+        let soft_curve = soft * soft / (4.0 * knee + 1e-5);
+        let contribution = soft_curve.max(brightness - threshold) / brightness.max(1e-5);
+        color * contribution.max(0.0)
+    }
+
+    /// 对线性HDR图像应用Bloom
+    pub fn apply(config: &BloomConfig, image: &mut [Vec3], width: u32, height: u32) {
+        if !config.enabled || width == 0 || height == 0 || image.len() < (width * height) as usize {
+            return;
+        }
+
+        // 亮部提取
+        let bright: Vec<Vec3> = image
+            .iter()
+            .map(|&c| Self::bright_pass(c, config.threshold, config.knee))
+            .collect();
+
+        // 逐级下采样
+        let mut levels = vec![(bright, width, height)];
+        for _ in 0..config.iterations.max(1) {
+            let (source, w, h) = levels.last().unwrap();
+            if *w <= 1 && *h <= 1 {
+                break;
+            }
+            let next = Self::downsample(source, *w, *h);
+            levels.push(next);
+        }
+
+        // 逐级上采样模糊并累加到上一级
+        while levels.len() > 1 {
+            let (small, sw, sh) = levels.pop().unwrap();
+            let blurred = Self::blur(&small, sw, sh, config.radius);
+            let (target, tw, th) = levels.last_mut().unwrap();
+            for y in 0..*th {
+                for x in 0..*tw {
+                    let u = (x as f32 + 0.5) / *tw as f32;
+                    let v = (y as f32 + 0.5) / *th as f32;
+                    target[(y * *tw + x) as usize] += Self::sample_bilinear(&blurred, sw, sh, u, v);
+                }
+            }
+        }
+
+        // 叠加合成
+        let (bloom, _, _) = &levels[0];
+        for (pixel, glow) in image.iter_mut().zip(bloom.iter()) {
+            *pixel += *glow * config.intensity;
+        }
+    }
+
+    /// 2x2盒式下采样
+    fn downsample(source: &[Vec3], width: u32, height: u32) -> (Vec<Vec3>, u32, u32) {
+        let next_width = (width / 2).max(1);
+        let next_height = (height / 2).max(1);
+        let fetch = |x: u32, y: u32| source[(y.min(height - 1) * width + x.min(width - 1)) as usize];
+
+        let mut result = vec![Vec3::ZERO; (next_width * next_height) as usize];
+        for y in 0..next_height {
+            for x in 0..next_width {
+                let (sx, sy) = (x * 2, y * 2);
+                result[(y * next_width + x) as usize] =
+                    (fetch(sx, sy) + fetch(sx + 1, sy) + fetch(sx, sy + 1) + fetch(sx + 1, sy + 1)) * 0.25;
+            }
+        }
+        (result, next_width, next_height)
+    }
+
+    /// 3x3帐篷滤波(与fs_upsample一致)
+    fn blur(source: &[Vec3], width: u32, height: u32, radius: f32) -> Vec<Vec3> {
+        let offset = radius.round().max(1.0) as i32;
+        let fetch = |x: i32, y: i32| {
+            let x = x.clamp(0, width as i32 - 1) as u32;
+            let y = y.clamp(0, height as i32 - 1) as u32;
+            source[(y * width + x) as usize]
+        };
+        const WEIGHTS: [[f32; 3]; 3] = [[1.0, 2.0, 1.0], [2.0, 4.0, 2.0], [1.0, 2.0, 1.0]];
+
+        let mut result = vec![Vec3::ZERO; source.len()];
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let mut sum = Vec3::ZERO;
+                for (j, row) in WEIGHTS.iter().enumerate() {
+                    for (i, weight) in row.iter().enumerate() {
+                        let dx = (i as i32 - 1) * offset;
+                        let dy = (j as i32 - 1) * offset;
+                        sum += fetch(x + dx, y + dy) * *weight;
+                    }
+                }
+                result[(y as u32 * width + x as u32) as usize] = sum / 16.0;
+            }
+        }
+        result
+    }
+
+    fn sample_bilinear(source: &[Vec3], width: u32, height: u32, u: f32, v: f32) -> Vec3 {
+        let x = (u * width as f32 - 0.5).max(0.0);
+        let y = (v * height as f32 - 0.5).max(0.0);
+        let x0 = (x.floor() as u32).min(width - 1);
+        let y0 = (y.floor() as u32).min(height - 1);
+        let x1 = (x0 + 1).min(width - 1);
+        let y1 = (y0 + 1).min(height - 1);
+        let fx = x - x0 as f32;
+        let fy = y - y0 as f32;
+
+        let fetch = |x: u32, y: u32| source[(y * width + x) as usize];
+        let top = fetch(x0, y0).lerp(fetch(x1, y0), fx);
+        let bottom = fetch(x0, y1).lerp(fetch(x1, y1), fx);
+        top.lerp(bottom, fy)
+    }
+}
+
+/// GPU Bloom渲染器
+pub struct BloomRenderer {
+    prefilter_pipeline: wgpu::RenderPipeline,
+    downsample_pipeline: wgpu::RenderPipeline,
+    upsample_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    mip_chain: Vec<RenderTarget>,
+    width: u32,
+    height: u32,
+}
+
+impl BloomRenderer {
+    /// Bloom中间缓冲格式
+    const MIP_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, width: u32, height: u32, iterations: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Bloom着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/bloom.wgsl").into()),
+        });
+
+        let texture_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Bloom绑定组布局"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                texture_entry(3),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Bloom管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |entry_point: &str, format: wgpu::TextureFormat, blend: Option<wgpu::BlendState>| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+
+        // 上采样结果以加法混合累加到上一级
+        let additive = wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent::OVER,
+        };
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Bloom采样器"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let mut renderer = Self {
+            prefilter_pipeline: create_pipeline("fs_prefilter", Self::MIP_FORMAT, None),
+            downsample_pipeline: create_pipeline("fs_downsample", Self::MIP_FORMAT, None),
+            upsample_pipeline: create_pipeline("fs_upsample", Self::MIP_FORMAT, Some(additive)),
+            composite_pipeline: create_pipeline("fs_composite", output_format, None),
+            bind_group_layout,
+            sampler,
+            mip_chain: Vec::new(),
+            width: 0,
+            height: 0,
+        };
+        renderer.resize(device, width, height, iterations);
+        renderer
+    }
+
+    /// 重新创建下采样链
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32, iterations: u32) {
+        self.width = width;
+        self.height = height;
+        self.mip_chain.clear();
+
+        for i in 0..iterations.max(1) {
+            let mip_width = (width >> (i + 1)).max(1);
+            let mip_height = (height >> (i + 1)).max(1);
+            self.mip_chain.push(RenderTarget::new(
+                device,
+                mip_width,
+                mip_height,
+                Self::MIP_FORMAT,
+                Some(&format!("Bloom Mip {}", i)),
+            ));
+            if mip_width == 1 && mip_height == 1 {
+                break;
+            }
+        }
+    }
+
+    /// 将Bloom应用到input并写入output
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        config: &BloomConfig,
+        input: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+    ) {
+        let Some(first) = self.mip_chain.first() else {
+            return;
+        };
+
+        // 亮部提取 + 第一次下采样
+        self.draw(device, encoder, &self.prefilter_pipeline, config, (self.width, self.height), input, input, &first.view, true);
+
+        // 逐级下采样
+        for pair in self.mip_chain.windows(2) {
+            let (source, target) = (&pair[0], &pair[1]);
+            self.draw(device, encoder, &self.downsample_pipeline, config, (source.width, source.height), &source.view, &source.view, &target.view, true);
+        }
+
+        // 逐级上采样，加法混合到上一级
+        for pair in self.mip_chain.windows(2).rev() {
+            let (target, source) = (&pair[0], &pair[1]);
+            self.draw(device, encoder, &self.upsample_pipeline, config, (source.width, source.height), &source.view, &source.view, &target.view, false);
+        }
+
+        // 叠加到场景
+        self.draw(device, encoder, &self.composite_pipeline, config, (self.width, self.height), input, &first.view, output, true);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn draw(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        config: &BloomConfig,
+        source_size: (u32, u32),
+        source: &wgpu::TextureView,
+        bloom: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+        clear: bool,
+    ) {
+        let uniforms = BloomUniforms {
+            texel_size: [1.0 / source_size.0.max(1) as f32, 1.0 / source_size.1.max(1) as f32],
+            threshold: config.threshold,
+            knee: config.knee,
+            intensity: config.intensity,
+            radius: config.radius,
+            _padding: [0.0; 2],
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Bloom Uniform"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Bloom绑定组"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(source) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(bloom) },
+            ],
+        });
+
+        let load = if clear {
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK)
+        } else {
+            wgpu::LoadOp::Load
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Bloom通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations { load, store: wgpu::StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bright_pass_uses_soft_threshold() {
+        // 低于 threshold - knee 的颜色被完全剔除
+        assert_eq!(BloomProcessor::bright_pass(Vec3::splat(0.4), 1.0, 0.5), Vec3::ZERO);
+
+        // 远高于阈值时只保留超出部分
+        let bright = BloomProcessor::bright_pass(Vec3::splat(4.0), 1.0, 0.5);
+        assert!((bright.x - 3.0).abs() < 1e-3);
+
+        // 过渡区间内有部分贡献
+        let soft = BloomProcessor::bright_pass(Vec3::splat(0.9), 1.0, 0.5);
+        assert!(soft.x > 0.0 && soft.x < 0.9);
+    }
+
+    #[test]
+    fn dark_image_is_unchanged() {
+        let config = BloomConfig::default();
+        let mut image = vec![Vec3::splat(0.25); 16 * 16];
+        BloomProcessor::apply(&config, &mut image, 16, 16);
+        assert!(image.iter().all(|&c| c == Vec3::splat(0.25)));
+    }
+
+    #[test]
+    fn bright_pixel_glows_into_neighbours() {
+        let config = BloomConfig::default();
+        let (width, height) = (16, 16);
+        let mut image = vec![Vec3::ZERO; (width * height) as usize];
+        let center = (8 * width + 8) as usize;
+        image[center] = Vec3::splat(50.0);
+
+        BloomProcessor::apply(&config, &mut image, width, height);
+
+        assert!(image[center].x > 50.0);
+        assert!(image[center + 1].x > 0.0);
+        assert!(image[center + 2 * width as usize].x > 0.0);
+        // 离光源越远辉光越弱
+        assert!(image[center + 1].x > image[center + 4].x);
+        assert_eq!(image[0].x, image[0].z);
+    }
+
+    #[test]
+    fn disabled_or_mismatched_input_is_ignored() {
+        let disabled = BloomConfig { enabled: false, ..Default::default() };
+        let mut image = vec![Vec3::splat(10.0); 4];
+        BloomProcessor::apply(&disabled, &mut image, 2, 2);
+        assert!(image.iter().all(|&c| c == Vec3::splat(10.0)));
+
+        // 图像尺寸不足时不处理
+        BloomProcessor::apply(&BloomConfig::default(), &mut image, 4, 4);
+        assert!(image.iter().all(|&c| c == Vec3::splat(10.0)));
+    }
+}
@@ -1,6 +1,7 @@
 //! 相机系统
 
-use glam::{Mat4, Vec3, Vec4, Quat};
+use glam::{Mat4, Vec2, Vec3, Quat};
+use crate::math::{Coordinate, Ray};
 use serde::{Deserialize, Serialize};
 
 /// 相机投影类型
@@ -31,6 +32,99 @@ pub struct Camera {
     pub orthographic_size: f32,
     /// 是否是主相机
     pub is_main: bool,
+    /// 对焦距离(景深)
+    #[serde(default = "default_focus_distance")]
+    pub focus_distance: f32,
+    /// 光圈大小，越大失焦区域越模糊，0表示关闭景深模糊
+    #[serde(default = "default_aperture")]
+    pub aperture: f32,
+    /// 渲染区域(相对窗口的比例)，None表示整个窗口
+    #[serde(default)]
+    pub viewport: Option<Viewport>,
+    /// 多个相机时的渲染顺序，小的先渲染
+    #[serde(default)]
+    pub render_order: i32,
+}
+
+/// 相机视口，坐标为相对渲染目标的比例(0-1)，原点在左上角
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+impl Viewport {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// 整个渲染目标
+    pub fn full() -> Self {
+        Self::new(0.0, 0.0, 1.0, 1.0)
+    }
+
+    /// 左右分屏，index从左往右数
+    pub fn split_horizontal(index: u32, count: u32) -> Self {
+        let width = 1.0 / count.max(1) as f32;
+        Self::new(width * index as f32, 0.0, width, 1.0)
+    }
+
+    /// 上下分屏，index从上往下数
+    pub fn split_vertical(index: u32, count: u32) -> Self {
+        let height = 1.0 / count.max(1) as f32;
+        Self::new(0.0, height * index as f32, 1.0, height)
+    }
+
+    /// 换算为渲染目标上的像素区域，裁剪到目标范围内，面积为0时返回None
+    pub fn to_pixels(&self, target_width: u32, target_height: u32) -> Option<ViewportRect> {
+        let to_pixel = |fraction: f32, size: u32| (fraction.clamp(0.0, 1.0) * size as f32).round() as u32;
+        let left = to_pixel(self.x, target_width);
+        let top = to_pixel(self.y, target_height);
+        let right = to_pixel(self.x + self.width, target_width);
+        let bottom = to_pixel(self.y + self.height, target_height);
+
+        (right > left && bottom > top).then(|| ViewportRect {
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+}
+
+/// 视口在渲染目标上的像素区域，同时用作wgpu的视口和裁剪矩形
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewportRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ViewportRect {
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height.max(1) as f32
+    }
+
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+fn default_focus_distance() -> f32 {
+    10.0
+}
+
+fn default_aperture() -> f32 {
+    0.5
 }
 
 impl Default for Camera {
@@ -45,6 +139,10 @@ impl Default for Camera {
             far_plane: 100.0,
             orthographic_size: 5.0,
             is_main: true,
+            focus_distance: default_focus_distance(),
+            aperture: default_aperture(),
+            viewport: None,
+            render_order: 0,
         }
     }
 }
@@ -74,6 +172,23 @@ impl Camera {
         }
     }
 
+    /// 设置视口
+    pub fn with_viewport(mut self, viewport: Viewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// 设置渲染顺序
+    pub fn with_render_order(mut self, render_order: i32) -> Self {
+        self.render_order = render_order;
+        self
+    }
+
+    /// 相机在渲染目标上的像素区域
+    pub fn viewport_rect(&self, target_width: u32, target_height: u32) -> Option<ViewportRect> {
+        self.viewport.unwrap_or_default().to_pixels(target_width, target_height)
+    }
+
     /// 获取视图矩阵
     pub fn view_matrix(&self) -> Mat4 {
         Mat4::from_rotation_translation(self.rotation, self.position).inverse()
@@ -83,12 +198,12 @@ impl Camera {
     pub fn projection_matrix(&self) -> Mat4 {
         match self.projection_type {
             ProjectionType::Perspective => {
-                Mat4::perspective_rh(self.fovy, self.aspect_ratio, self.near_plane, self.far_plane)
+                Coordinate::perspective(self.fovy, self.aspect_ratio, self.near_plane, self.far_plane)
             }
             ProjectionType::Orthographic => {
                 let height = self.orthographic_size;
                 let width = height * self.aspect_ratio;
-                Mat4::orthographic_rh(
+                Coordinate::orthographic(
                     -width / 2.0,
                     width / 2.0,
                     -height / 2.0,
@@ -105,6 +220,53 @@ impl Camera {
         self.projection_matrix() * self.view_matrix()
     }
 
+    /// 从屏幕坐标(像素，左上角为原点)发出的世界空间射线
+    pub fn screen_point_to_ray(&self, screen_pos: Vec2, screen_size: Vec2) -> Ray {
+        let ndc_x = 2.0 * screen_pos.x / screen_size.x.max(1.0) - 1.0;
+        let ndc_y = 1.0 - 2.0 * screen_pos.y / screen_size.y.max(1.0);
+
+        // wgpu深度范围为[0, 1]，分别反投影近、远平面上的点
+        let inverse = self.view_projection_matrix().inverse();
+        let near = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 0.0));
+        let far = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 1.0));
+        Ray::new(near, far - near)
+    }
+
+    /// 世界坐标投影到渲染目标上的像素坐标(左上角为原点，包含视口偏移)
+    ///
+    /// 点在相机后方或近平面之前时返回None；在视口外但位于相机前方时仍返回坐标，
+    /// 便于HUD把屏幕外目标的指示器钳制到边缘。
+    pub fn world_to_screen(&self, world_pos: Vec3, viewport: &ViewportRect) -> Option<Vec2> {
+        let clip = self.view_projection_matrix() * world_pos.extend(1.0);
+        if clip.w <= f32::EPSILON {
+            return None;
+        }
+        let ndc = clip.truncate() / clip.w;
+        if ndc.z < 0.0 {
+            return None;
+        }
+
+        Some(Vec2::new(
+            viewport.x as f32 + (ndc.x + 1.0) * 0.5 * viewport.width as f32,
+            viewport.y as f32 + (1.0 - ndc.y) * 0.5 * viewport.height as f32,
+        ))
+    }
+
+    /// 像素坐标反投影为世界坐标，depth为沿相机前方向到相机的距离
+    pub fn screen_to_world(&self, screen_pos: Vec2, depth: f32, viewport: &ViewportRect) -> Vec3 {
+        let local = screen_pos - Vec2::new(viewport.x as f32, viewport.y as f32);
+        let ray = self.screen_point_to_ray(local, Vec2::new(viewport.width as f32, viewport.height as f32));
+
+        // 射线上视深等于depth的点，透视与正交投影通用
+        let forward = self.forward();
+        let facing = ray.direction.dot(forward);
+        if facing.abs() <= f32::EPSILON {
+            return ray.origin;
+        }
+        let t = (depth - (ray.origin - self.position).dot(forward)) / facing;
+        ray.origin + ray.direction * t
+    }
+
     /// 向前移动
     pub fn move_forward(&mut self, distance: f32) {
         let forward = self.forward();
@@ -125,17 +287,17 @@ impl Camera {
 
     /// 获取前方向量
     pub fn forward(&self) -> Vec3 {
-        self.rotation * Vec3::NEG_Z
+        self.rotation * Coordinate::FORWARD
     }
 
     /// 获取右方向量
     pub fn right(&self) -> Vec3 {
-        self.rotation * Vec3::X
+        self.rotation * Coordinate::RIGHT
     }
 
     /// 获取上方向量
     pub fn up(&self) -> Vec3 {
-        self.rotation * Vec3::Y
+        self.rotation * Coordinate::UP
     }
 
     /// 设置位置
@@ -148,20 +310,29 @@ impl Camera {
         self.rotation = rotation;
     }
 
+    /// 角度表示的欧拉角(x=俯仰, y=偏航, z=翻滚)
+    pub fn euler_degrees(&self) -> Vec3 {
+        Coordinate::euler_degrees(self.rotation)
+    }
+
+    /// 按角度欧拉角设置旋转
+    pub fn set_euler_degrees(&mut self, degrees: Vec3) {
+        self.rotation = Coordinate::rotation_from_euler_degrees(degrees);
+    }
+
     /// 看向目标点
     pub fn look_at(&mut self, target: Vec3, up: Vec3) {
-        let forward = (target - self.position).normalize();
-        let right = forward.cross(up).normalize();
-        let up = right.cross(forward).normalize();
-        
-        let rotation_matrix = Mat4::from_cols(
-            Vec4::new(right.x, up.x, -forward.x, 0.0),
-            Vec4::new(right.y, up.y, -forward.y, 0.0),
-            Vec4::new(right.z, up.z, -forward.z, 0.0),
-            Vec4::new(0.0, 0.0, 0.0, 1.0),
-        );
-        
-        self.rotation = Quat::from_mat4(&rotation_matrix);
+        self.rotation = Coordinate::rotation_looking_to(target - self.position, up);
+    }
+
+    /// 设置对焦距离，限制在近裁剪面之外
+    pub fn set_focus_distance(&mut self, distance: f32) {
+        self.focus_distance = distance.max(self.near_plane);
+    }
+
+    /// 设置光圈大小
+    pub fn set_aperture(&mut self, aperture: f32) {
+        self.aperture = aperture.max(0.0);
     }
 
     /// 更新长宽比
@@ -200,3 +371,103 @@ impl CameraController {
         // 目前保持简单
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_center_ray_points_forward() {
+        let mut camera = Camera::default();
+        camera.position = Vec3::new(2.0, 3.0, 10.0);
+        camera.look_at(Vec3::new(2.0, 3.0, 0.0), Vec3::Y);
+
+        let ray = camera.screen_point_to_ray(Vec2::new(800.0, 450.0), Vec2::new(1600.0, 900.0));
+        assert!((ray.direction - camera.forward()).length() < 1e-4);
+        // 射线从近平面出发
+        assert!((ray.origin - (camera.position + camera.forward() * camera.near_plane)).length() < 1e-3);
+    }
+
+    #[test]
+    fn screen_corner_ray_projects_back_to_corner() {
+        let camera = Camera::default();
+        let ray = camera.screen_point_to_ray(Vec2::ZERO, Vec2::new(1600.0, 900.0));
+        let clip = camera.view_projection_matrix() * ray.point_at(5.0).extend(1.0);
+        let ndc = clip.truncate() / clip.w;
+        assert!((ndc.x + 1.0).abs() < 1e-3 && (ndc.y - 1.0).abs() < 1e-3, "{:?}", ndc);
+    }
+
+    #[test]
+    fn split_screen_halves_tile_the_target() {
+        let left = Viewport::split_horizontal(0, 2).to_pixels(1280, 720).unwrap();
+        let right = Viewport::split_horizontal(1, 2).to_pixels(1280, 720).unwrap();
+        assert_eq!(left, ViewportRect { x: 0, y: 0, width: 640, height: 720 });
+        assert_eq!(right, ViewportRect { x: 640, y: 0, width: 640, height: 720 });
+
+        // 奇数宽度下两半之间没有缝隙
+        let left = Viewport::split_horizontal(0, 2).to_pixels(1281, 720).unwrap();
+        let right = Viewport::split_horizontal(1, 2).to_pixels(1281, 720).unwrap();
+        assert_eq!(left.x + left.width, right.x);
+        assert_eq!(right.x + right.width, 1281);
+
+        let bottom = Viewport::split_vertical(1, 2).to_pixels(1280, 720).unwrap();
+        assert_eq!(bottom, ViewportRect { x: 0, y: 360, width: 1280, height: 360 });
+        assert!(bottom.contains(0, 360) && !bottom.contains(0, 359));
+    }
+
+    #[test]
+    fn viewport_is_clamped_and_empty_viewports_are_skipped() {
+        let rect = Viewport::new(0.5, -0.5, 1.0, 1.0).to_pixels(100, 100).unwrap();
+        assert_eq!(rect, ViewportRect { x: 50, y: 0, width: 50, height: 50 });
+        assert!(Viewport::new(0.2, 0.2, 0.0, 0.5).to_pixels(100, 100).is_none());
+
+        let camera = Camera::default();
+        assert_eq!(camera.viewport_rect(800, 600), Some(ViewportRect { x: 0, y: 0, width: 800, height: 600 }));
+        assert_eq!(ViewportRect { x: 0, y: 0, width: 640, height: 720 }.aspect_ratio(), 640.0 / 720.0);
+    }
+
+    fn camera_looking_down_negative_z() -> Camera {
+        let mut camera = Camera::perspective(60.0, 800.0 / 600.0, 0.1, 100.0);
+        camera.position = Vec3::new(1.0, 2.0, 10.0);
+        camera.look_at(Vec3::new(1.0, 2.0, 0.0), Vec3::Y);
+        camera
+    }
+
+    #[test]
+    fn point_in_front_projects_into_viewport() {
+        let camera = camera_looking_down_negative_z();
+        let viewport = ViewportRect { x: 100, y: 50, width: 800, height: 600 };
+
+        let center = camera.world_to_screen(Vec3::new(1.0, 2.0, 0.0), &viewport).unwrap();
+        assert!((center - Vec2::new(500.0, 350.0)).length() < 1e-3, "{:?}", center);
+
+        // 右上方的点落在视口右上部分
+        let upper_right = camera.world_to_screen(Vec3::new(2.0, 3.0, 0.0), &viewport).unwrap();
+        assert!(upper_right.x > 500.0 && upper_right.x < 900.0);
+        assert!(upper_right.y < 350.0 && upper_right.y > 50.0);
+    }
+
+    #[test]
+    fn point_behind_camera_is_not_projected() {
+        let camera = camera_looking_down_negative_z();
+        let viewport = ViewportRect { x: 0, y: 0, width: 800, height: 600 };
+        assert!(camera.world_to_screen(Vec3::new(1.0, 2.0, 20.0), &viewport).is_none());
+        assert!(camera.world_to_screen(camera.position, &viewport).is_none());
+    }
+
+    #[test]
+    fn screen_to_world_inverts_world_to_screen() {
+        let viewport = ViewportRect { x: 40, y: 20, width: 800, height: 600 };
+        let mut orthographic = Camera::orthographic(10.0, 800.0 / 600.0, 0.1, 100.0);
+        orthographic.position = Vec3::new(0.0, 0.0, 10.0);
+
+        for camera in [camera_looking_down_negative_z(), orthographic] {
+            for point in [Vec3::new(1.5, 2.5, -3.0), Vec3::new(-1.0, 0.5, 4.0)] {
+                let screen = camera.world_to_screen(point, &viewport).unwrap();
+                let depth = (point - camera.position).dot(camera.forward());
+                let restored = camera.screen_to_world(screen, depth, &viewport);
+                assert!((restored - point).length() < 1e-3, "{:?} -> {:?}", point, restored);
+            }
+        }
+    }
+}
@@ -0,0 +1,494 @@
+//! 调试绘制
+//!
+//! 即时模式的世界空间调试图元(线段、包围盒、球、射线、文字)，每帧重新提交，帧结束时清空。
+//! 引擎由 `DebugDrawRenderer` 在主通道之后以线段列表绘制(MSAA时线段同样抗锯齿)；
+//! 编辑器等外部使用者可遍历 `lines`/`texts` 自行投影绘制。文字不进入GPU通道，由界面层绘制。
+
+use crate::math::{Ray, AABB};
+use crate::render::{RenderTargets, MAIN_DEPTH_FORMAT};
+use glam::{Mat4, Vec3, Vec4};
+use std::ops::Range;
+use wgpu::util::DeviceExt;
+
+/// 调试绘制设置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugDrawConfig {
+    /// 关闭时忽略所有提交
+    pub enabled: bool,
+    /// 新图元默认是否被场景遮挡
+    pub depth_test: bool,
+    /// 球体每个圆环的分段数
+    pub sphere_segments: u32,
+}
+
+impl Default for DebugDrawConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            depth_test: true,
+            sphere_segments: 24,
+        }
+    }
+}
+
+/// 世界空间线段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugLine {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub color: Vec4,
+    /// false时绘制在所有几何体之上
+    pub depth_test: bool,
+}
+
+/// 世界空间锚定的文字
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugText {
+    pub position: Vec3,
+    pub text: String,
+    pub color: Vec4,
+}
+
+/// 调试线段的GPU顶点
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DebugVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl DebugVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<DebugVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// 一帧的线段顶点：先是深度测试的线段，再是覆盖在上层的线段
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DebugVertexBatch {
+    pub vertices: Vec<DebugVertex>,
+    pub depth_tested: Range<u32>,
+    pub overlay: Range<u32>,
+}
+
+/// 调试绘制队列
+#[derive(Debug, Clone, Default)]
+pub struct DebugDraw {
+    config: DebugDrawConfig,
+    lines: Vec<DebugLine>,
+    texts: Vec<DebugText>,
+}
+
+impl DebugDraw {
+    pub fn new(config: DebugDrawConfig) -> Self {
+        Self {
+            config,
+            lines: Vec::new(),
+            texts: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &DebugDrawConfig {
+        &self.config
+    }
+
+    pub fn config_mut(&mut self) -> &mut DebugDrawConfig {
+        &mut self.config
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.config.enabled = enabled;
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 线段
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Vec4) {
+        let depth_test = self.config.depth_test;
+        self.line_with_depth(start, end, color, depth_test);
+    }
+
+    /// 指定是否被场景遮挡的线段
+    pub fn line_with_depth(&mut self, start: Vec3, end: Vec3, color: Vec4, depth_test: bool) {
+        if self.config.enabled {
+            self.lines.push(DebugLine { start, end, color, depth_test });
+        }
+    }
+
+    /// 轴对齐包围盒的12条棱
+    pub fn aabb(&mut self, aabb: &AABB, color: Vec4) {
+        let (min, max) = (aabb.min, aabb.max);
+        let corner = |i: usize| {
+            Vec3::new(
+                if i & 1 == 0 { min.x } else { max.x },
+                if i & 2 == 0 { min.y } else { max.y },
+                if i & 4 == 0 { min.z } else { max.z },
+            )
+        };
+        // 每条棱连接只差一个坐标位的两个角点
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    self.line(corner(i), corner(i | bit), color);
+                }
+            }
+        }
+    }
+
+    /// 球体：三个坐标平面上的圆环
+    pub fn sphere(&mut self, center: Vec3, radius: f32, color: Vec4) {
+        let segments = self.config.sphere_segments.max(3);
+        let axes = [(Vec3::X, Vec3::Y), (Vec3::Y, Vec3::Z), (Vec3::Z, Vec3::X)];
+        for (u, v) in axes {
+            let point = |i: u32| {
+                let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+                center + (u * angle.cos() + v * angle.sin()) * radius
+            };
+            for i in 0..segments {
+                self.line(point(i), point(i + 1), color);
+            }
+        }
+    }
+
+    /// 从射线起点沿方向画length长的线段
+    pub fn ray(&mut self, ray: &Ray, length: f32, color: Vec4) {
+        self.line(ray.origin, ray.origin + ray.direction * length, color);
+    }
+
+    /// 世界空间文字
+    pub fn text(&mut self, position: Vec3, text: impl Into<String>, color: Vec4) {
+        if self.config.enabled {
+            self.texts.push(DebugText { position, text: text.into(), color });
+        }
+    }
+
+    pub fn lines(&self) -> &[DebugLine] {
+        &self.lines
+    }
+
+    pub fn texts(&self) -> &[DebugText] {
+        &self.texts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.texts.is_empty()
+    }
+
+    /// 清空本帧提交的图元
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.texts.clear();
+    }
+
+    /// 帧结束，清空图元
+    pub fn end_frame(&mut self) {
+        self.clear();
+    }
+
+    /// 生成线段列表顶点，每条线段2个顶点
+    pub fn build_vertices(&self) -> DebugVertexBatch {
+        let mut vertices = Vec::with_capacity(self.lines.len() * 2);
+        let mut push = |line: &DebugLine| {
+            let color = line.color.to_array();
+            vertices.push(DebugVertex { position: line.start.to_array(), color });
+            vertices.push(DebugVertex { position: line.end.to_array(), color });
+        };
+
+        self.lines.iter().filter(|line| line.depth_test).for_each(&mut push);
+        let split = (self.lines.iter().filter(|line| line.depth_test).count() * 2) as u32;
+        self.lines.iter().filter(|line| !line.depth_test).for_each(&mut push);
+        let total = vertices.len() as u32;
+
+        DebugVertexBatch {
+            vertices,
+            depth_tested: 0..split,
+            overlay: split..total,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct DebugUniforms {
+    view_projection: [[f32; 4]; 4],
+}
+
+/// 在主颜色缓冲上绘制调试线段
+pub struct DebugDrawRenderer {
+    depth_tested_pipeline: wgpu::RenderPipeline,
+    overlay_pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    vertex_buffer: wgpu::Buffer,
+    /// 顶点缓冲容量(顶点数)，不足时按2倍扩容
+    vertex_capacity: usize,
+}
+
+impl DebugDrawRenderer {
+    const INITIAL_VERTEX_CAPACITY: usize = 1024;
+
+    /// 管线的颜色格式、采样数与 `RenderTargets` 一致
+    pub fn new(device: &wgpu::Device, color_format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("调试绘制着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/debug_draw.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("调试绘制绑定组布局"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("调试绘制管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |label: &str, depth_compare: wgpu::CompareFunction| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[DebugVertex::desc()],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: color_format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::LineList,
+                    ..Default::default()
+                },
+                // 调试线段不写深度，避免遮挡之后绘制的内容
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: MAIN_DEPTH_FORMAT,
+                    depth_write_enabled: false,
+                    depth_compare,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: sample_count.max(1),
+                    ..Default::default()
+                },
+                multiview: None,
+            })
+        };
+        let depth_tested_pipeline = create_pipeline("调试绘制管线", wgpu::CompareFunction::LessEqual);
+        let overlay_pipeline = create_pipeline("调试绘制覆盖管线", wgpu::CompareFunction::Always);
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("调试绘制统一缓冲"),
+            contents: bytemuck::bytes_of(&DebugUniforms { view_projection: Mat4::IDENTITY.to_cols_array_2d() }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("调试绘制绑定组"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() }],
+        });
+
+        Self {
+            depth_tested_pipeline,
+            overlay_pipeline,
+            uniform_buffer,
+            bind_group,
+            vertex_buffer: Self::create_vertex_buffer(device, Self::INITIAL_VERTEX_CAPACITY),
+            vertex_capacity: Self::INITIAL_VERTEX_CAPACITY,
+        }
+    }
+
+    fn create_vertex_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("调试绘制顶点缓冲"),
+            size: (capacity * std::mem::size_of::<DebugVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// 以 `LoadOp::Load` 在主颜色缓冲上绘制本帧的线段，返回绘制的顶点数
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        targets: &RenderTargets,
+        debug_draw: &DebugDraw,
+        view_projection: Mat4,
+    ) -> u32 {
+        let batch = debug_draw.build_vertices();
+        if batch.vertices.is_empty() {
+            return 0;
+        }
+
+        if batch.vertices.len() > self.vertex_capacity {
+            self.vertex_capacity = batch.vertices.len().next_power_of_two();
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.vertex_capacity);
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&batch.vertices));
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&DebugUniforms { view_projection: view_projection.to_cols_array_2d() }),
+        );
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("调试绘制通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: targets.color_view(),
+                resolve_target: targets.color_resolve_target(),
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: targets.depth_view(),
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        if !batch.depth_tested.is_empty() {
+            render_pass.set_pipeline(&self.depth_tested_pipeline);
+            render_pass.draw(batch.depth_tested.clone(), 0..1);
+        }
+        if !batch.overlay.is_empty() {
+            render_pass.set_pipeline(&self.overlay_pipeline);
+            render_pass.draw(batch.overlay.clone(), 0..1);
+        }
+
+        batch.vertices.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    const RED: Vec4 = Vec4::new(1.0, 0.0, 0.0, 1.0);
+    const GREEN: Vec4 = Vec4::new(0.0, 1.0, 0.0, 1.0);
+
+    #[test]
+    fn queued_lines_build_vertices_and_clear_at_frame_end() {
+        let mut debug_draw = DebugDraw::default();
+        debug_draw.line_with_depth(Vec3::ZERO, Vec3::X, RED, false);
+        debug_draw.line(Vec3::Y, Vec3::Z, GREEN);
+        debug_draw.text(Vec3::ONE, "玩家", GREEN);
+
+        let batch = debug_draw.build_vertices();
+        // 深度测试的线段在前，覆盖线段在后
+        assert_eq!(
+            batch.vertices,
+            [
+                DebugVertex { position: [0.0, 1.0, 0.0], color: GREEN.to_array() },
+                DebugVertex { position: [0.0, 0.0, 1.0], color: GREEN.to_array() },
+                DebugVertex { position: [0.0, 0.0, 0.0], color: RED.to_array() },
+                DebugVertex { position: [1.0, 0.0, 0.0], color: RED.to_array() },
+            ]
+        );
+        assert_eq!((batch.depth_tested, batch.overlay), (0..2, 2..4));
+        assert_eq!(debug_draw.texts()[0].text, "玩家");
+
+        debug_draw.end_frame();
+        assert!(debug_draw.is_empty());
+        assert_eq!(debug_draw.build_vertices(), DebugVertexBatch::default());
+    }
+
+    #[test]
+    fn shapes_expand_to_lines() {
+        let mut debug_draw = DebugDraw::new(DebugDrawConfig { sphere_segments: 8, ..Default::default() });
+        debug_draw.aabb(&AABB::new(Vec3::ZERO, Vec3::ONE), RED);
+        assert_eq!(debug_draw.lines().len(), 12);
+        assert!(debug_draw.lines().iter().all(|line| (line.end - line.start).length() == 1.0));
+
+        debug_draw.clear();
+        debug_draw.sphere(Vec3::new(0.0, 5.0, 0.0), 2.0, RED);
+        assert_eq!(debug_draw.lines().len(), 24);
+        assert!(debug_draw
+            .lines()
+            .iter()
+            .all(|line| ((line.start - Vec3::new(0.0, 5.0, 0.0)).length() - 2.0).abs() < 1e-5));
+
+        debug_draw.clear();
+        debug_draw.ray(&Ray::new(Vec3::ZERO, Vec3::new(0.0, 0.0, -2.0)), 3.0, GREEN);
+        assert!(debug_draw.lines()[0].end.abs_diff_eq(Vec3::new(0.0, 0.0, -3.0), 1e-6));
+    }
+
+    #[test]
+    fn disabled_debug_draw_ignores_submissions() {
+        let mut debug_draw = DebugDraw::default();
+        debug_draw.line(Vec3::ZERO, Vec3::X, RED);
+        debug_draw.set_enabled(false);
+        assert!(debug_draw.is_empty());
+
+        debug_draw.line(Vec3::ZERO, Vec3::X, RED);
+        debug_draw.text(Vec3::ZERO, "隐藏", RED);
+        assert!(debug_draw.is_empty());
+    }
+
+    #[test]
+    fn renderer_draws_batch_and_grows_vertex_buffer() {
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过: 没有可用的图形适配器");
+            return;
+        };
+
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let targets = RenderTargets::new(&device, 32, 32, format, 1);
+        let mut renderer = DebugDrawRenderer::new(&device, format, targets.sample_count());
+
+        let mut debug_draw = DebugDraw::default();
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        assert_eq!(renderer.render(&device, &queue, &mut encoder, &targets, &debug_draw, Mat4::IDENTITY), 0);
+
+        // 超过初始容量的线段触发扩容
+        for i in 0..600 {
+            let x = i as f32 / 600.0 * 2.0 - 1.0;
+            debug_draw.line_with_depth(Vec3::new(x, -1.0, 0.5), Vec3::new(x, 1.0, 0.5), RED, i % 2 == 0);
+        }
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let drawn = renderer.render(&device, &queue, &mut encoder, &targets, &debug_draw, Mat4::IDENTITY);
+        queue.submit(Some(encoder.finish()));
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+
+        assert_eq!(drawn, 1200);
+        assert_eq!(renderer.vertex_capacity, 2048);
+    }
+}
@@ -0,0 +1,524 @@
+//! 景深效果
+//!
+//! 由深度缓冲与相机对焦距离/光圈计算弥散圈(CoC) -> 按CoC做水平、垂直两次可分离模糊 -> 按CoC混合清晰与模糊图像。
+//! 模糊采样按样本自身的CoC加权，避免清晰的前景渗入模糊的背景。
+//! `DepthOfFieldProcessor` 是CPU参考实现，与depth_of_field.wgsl中的GPU实现保持一致。
+
+use crate::math::Vec3;
+use crate::render::{Camera, DepthOfFieldConfig, ProjectionType, RenderTarget};
+use wgpu::util::DeviceExt;
+
+/// 单侧模糊采样数(与depth_of_field.wgsl中的SAMPLE_COUNT一致)
+pub const DOF_SAMPLE_COUNT: i32 = 8;
+
+/// 景深着色器uniform(对应depth_of_field.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct DepthOfFieldUniforms {
+    texel_size: [f32; 2],
+    direction: [f32; 2],
+    near_plane: f32,
+    far_plane: f32,
+    focus_distance: f32,
+    focus_range: f32,
+    aperture: f32,
+    max_radius: f32,
+    orthographic: f32,
+    _padding: f32,
+}
+
+/// 景深用到的相机参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthOfFieldCamera {
+    pub near_plane: f32,
+    pub far_plane: f32,
+    pub orthographic: bool,
+}
+
+impl Default for DepthOfFieldCamera {
+    fn default() -> Self {
+        Self::from_camera(&Camera::default())
+    }
+}
+
+impl DepthOfFieldCamera {
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self {
+            near_plane: camera.near_plane,
+            far_plane: camera.far_plane,
+            orthographic: camera.projection_type == ProjectionType::Orthographic,
+        }
+    }
+
+    /// 将[0, 1]深度缓冲值转换为视空间线性深度
+    pub fn linearize_depth(&self, depth: f32) -> f32 {
+        let (near, far) = (self.near_plane, self.far_plane);
+        if self.orthographic {
+            near + depth * (far - near)
+        } else {
+            near * far / (far - depth * (far - near)).max(1e-6)
+        }
+    }
+}
+
+/// CPU景深处理器
+pub struct DepthOfFieldProcessor;
+
+impl DepthOfFieldProcessor {
+    /// 线性深度处的弥散圈大小，范围[0, 1]
+    ///
+    /// 对焦距离前后 `focus_range / 2` 内完全清晰，之外按光圈与离焦程度增长。
+    pub fn circle_of_confusion(config: &DepthOfFieldConfig, linear_depth: f32) -> f32 {
+        let defocus = (linear_depth - config.focus_distance).abs() - config.focus_range * 0.5;
+        if defocus <= 0.0 {
+            return 0.0;
+        }
+        (config.aperture * defocus / linear_depth.max(1e-4)).clamp(0.0, 1.0)
+    }
+
+    /// 弥散圈对应的模糊半径(像素)
+    pub fn blur_radius(config: &DepthOfFieldConfig, coc: f32) -> f32 {
+        coc * config.max_blur_radius * config.blur_strength
+    }
+
+    /// 对线性HDR图像应用景深，`depth` 为与图像同尺寸的深度缓冲值
+    pub fn apply(
+        config: &DepthOfFieldConfig,
+        camera: &DepthOfFieldCamera,
+        image: &mut [Vec3],
+        depth: &[f32],
+        width: u32,
+        height: u32,
+    ) {
+        let pixel_count = (width * height) as usize;
+        if !config.enabled || width == 0 || height == 0 || image.len() < pixel_count || depth.len() < pixel_count {
+            return;
+        }
+
+        let coc: Vec<f32> = depth[..pixel_count]
+            .iter()
+            .map(|&d| Self::circle_of_confusion(config, camera.linearize_depth(d)))
+            .collect();
+
+        let horizontal = Self::blur(config, image, &coc, width, height, (1, 0));
+        let blurred = Self::blur(config, &horizontal, &coc, width, height, (0, 1));
+
+        for ((pixel, blurred), coc) in image.iter_mut().zip(blurred.iter()).zip(coc.iter()) {
+            *pixel = pixel.lerp(*blurred, *coc);
+        }
+    }
+
+    /// 沿单一方向的CoC加权高斯模糊(与fs_blur一致)
+    fn blur(
+        config: &DepthOfFieldConfig,
+        source: &[Vec3],
+        coc: &[f32],
+        width: u32,
+        height: u32,
+        direction: (i32, i32),
+    ) -> Vec<Vec3> {
+        let fetch = |x: i32, y: i32| {
+            let x = x.clamp(0, width as i32 - 1) as u32;
+            let y = y.clamp(0, height as i32 - 1) as u32;
+            let index = (y * width + x) as usize;
+            (source[index], coc[index])
+        };
+
+        let mut result = vec![Vec3::ZERO; (width * height) as usize];
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let (center, center_coc) = fetch(x, y);
+                let step = Self::blur_radius(config, center_coc) / DOF_SAMPLE_COUNT as f32;
+                if step < 0.5 {
+                    result[(y as u32 * width + x as u32) as usize] = center;
+                    continue;
+                }
+
+                let mut sum = Vec3::ZERO;
+                let mut total_weight = 0.0;
+                for i in -DOF_SAMPLE_COUNT..=DOF_SAMPLE_COUNT {
+                    let offset = (i as f32 * step).round() as i32;
+                    let (color, sample_coc) = fetch(x + direction.0 * offset, y + direction.1 * offset);
+                    let t = i as f32 / DOF_SAMPLE_COUNT as f32;
+                    let weight = (-2.0 * t * t).exp() * (sample_coc + 0.05);
+                    sum += color * weight;
+                    total_weight += weight;
+                }
+                result[(y as u32 * width + x as u32) as usize] = sum / total_weight;
+            }
+        }
+        result
+    }
+}
+
+/// GPU景深渲染器
+pub struct DepthOfFieldRenderer {
+    coc_pipeline: wgpu::RenderPipeline,
+    blur_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+    coc_bind_group_layout: wgpu::BindGroupLayout,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    coc_target: RenderTarget,
+    blur_targets: [RenderTarget; 2],
+    width: u32,
+    height: u32,
+}
+
+impl DepthOfFieldRenderer {
+    /// CoC缓冲格式
+    const COC_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R16Float;
+    /// 模糊中间缓冲格式
+    const BLUR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("景深着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/post_processing/depth_of_field.wgsl").into()),
+        });
+
+        let uniform_entry = wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let texture_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let coc_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("景深CoC绑定组布局"),
+            entries: &[
+                uniform_entry,
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("景深绑定组布局"),
+            entries: &[
+                uniform_entry,
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                texture_entry(3),
+                texture_entry(5),
+            ],
+        });
+
+        let create_pipeline = |entry_point: &str, layout: &wgpu::BindGroupLayout, format: wgpu::TextureFormat| {
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("景深管线布局"),
+                bind_group_layouts: &[layout],
+                push_constant_ranges: &[],
+            });
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("景深采样器"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let (coc_target, blur_targets) = Self::create_targets(device, width, height);
+        Self {
+            coc_pipeline: create_pipeline("fs_coc", &coc_bind_group_layout, Self::COC_FORMAT),
+            blur_pipeline: create_pipeline("fs_blur", &bind_group_layout, Self::BLUR_FORMAT),
+            composite_pipeline: create_pipeline("fs_composite", &bind_group_layout, output_format),
+            coc_bind_group_layout,
+            bind_group_layout,
+            sampler,
+            coc_target,
+            blur_targets,
+            width,
+            height,
+        }
+    }
+
+    fn create_targets(device: &wgpu::Device, width: u32, height: u32) -> (RenderTarget, [RenderTarget; 2]) {
+        let (width, height) = (width.max(1), height.max(1));
+        (
+            RenderTarget::new(device, width, height, Self::COC_FORMAT, Some("DoF CoC")),
+            [
+                RenderTarget::new(device, width, height, Self::BLUR_FORMAT, Some("DoF Blur H")),
+                RenderTarget::new(device, width, height, Self::BLUR_FORMAT, Some("DoF Blur V")),
+            ],
+        )
+    }
+
+    /// 重新创建中间缓冲
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        let (coc_target, blur_targets) = Self::create_targets(device, width, height);
+        self.coc_target = coc_target;
+        self.blur_targets = blur_targets;
+    }
+
+    /// 按深度缓冲对input应用景深并写入output
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        config: &DepthOfFieldConfig,
+        camera: &DepthOfFieldCamera,
+        input: &wgpu::TextureView,
+        depth: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+    ) {
+        // 弥散圈
+        let coc_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("景深CoC绑定组"),
+            layout: &self.coc_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.uniform_buffer(device, config, camera, [0.0, 0.0]).as_entire_binding(),
+                },
+                wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(depth) },
+            ],
+        });
+        self.draw(encoder, &self.coc_pipeline, &coc_bind_group, &self.coc_target.view);
+
+        // 水平、垂直可分离模糊
+        let [horizontal, vertical] = &self.blur_targets;
+        self.pass(device, encoder, &self.blur_pipeline, config, camera, [1.0, 0.0], input, input, &horizontal.view);
+        self.pass(device, encoder, &self.blur_pipeline, config, camera, [0.0, 1.0], &horizontal.view, input, &vertical.view);
+
+        // 按CoC混合
+        self.pass(device, encoder, &self.composite_pipeline, config, camera, [0.0, 0.0], input, &vertical.view, output);
+    }
+
+    fn uniform_buffer(
+        &self,
+        device: &wgpu::Device,
+        config: &DepthOfFieldConfig,
+        camera: &DepthOfFieldCamera,
+        direction: [f32; 2],
+    ) -> wgpu::Buffer {
+        let uniforms = DepthOfFieldUniforms {
+            texel_size: [1.0 / self.width.max(1) as f32, 1.0 / self.height.max(1) as f32],
+            direction,
+            near_plane: camera.near_plane,
+            far_plane: camera.far_plane,
+            focus_distance: config.focus_distance,
+            focus_range: config.focus_range,
+            aperture: config.aperture,
+            max_radius: config.max_blur_radius * config.blur_strength,
+            orthographic: if camera.orthographic { 1.0 } else { 0.0 },
+            _padding: 0.0,
+        };
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("DoF Uniform"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn pass(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        config: &DepthOfFieldConfig,
+        camera: &DepthOfFieldCamera,
+        direction: [f32; 2],
+        source: &wgpu::TextureView,
+        blurred: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+    ) {
+        let uniform_buffer = self.uniform_buffer(device, config, camera, direction);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("景深绑定组"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(source) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(&self.coc_target.view) },
+                wgpu::BindGroupEntry { binding: 5, resource: wgpu::BindingResource::TextureView(blurred) },
+            ],
+        });
+        self.draw(encoder, pipeline, &bind_group, target);
+    }
+
+    fn draw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        bind_group: &wgpu::BindGroup,
+        target: &wgpu::TextureView,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("景深通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: u32 = 32;
+    const HEIGHT: u32 = 16;
+
+    fn camera() -> DepthOfFieldCamera {
+        DepthOfFieldCamera { near_plane: 0.1, far_plane: 100.0, orthographic: false }
+    }
+
+    /// linearize_depth的逆变换
+    fn depth_at(camera: &DepthOfFieldCamera, linear_depth: f32) -> f32 {
+        let (near, far) = (camera.near_plane, camera.far_plane);
+        (far - near * far / linear_depth) / (far - near)
+    }
+
+    fn variance(values: impl Iterator<Item = f32>) -> f32 {
+        let values: Vec<f32> = values.collect();
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / values.len() as f32
+    }
+
+    /// 区域内像素亮度的方差
+    fn region_variance(image: &[Vec3], columns: std::ops::Range<u32>) -> f32 {
+        variance((0..HEIGHT).flat_map(|y| columns.clone().map(move |x| (x, y))).map(|(x, y)| image[(y * WIDTH + x) as usize].x))
+    }
+
+    #[test]
+    fn linearize_depth_maps_range_to_clip_planes() {
+        let perspective = camera();
+        assert!((perspective.linearize_depth(0.0) - 0.1).abs() < 1e-5);
+        assert!((perspective.linearize_depth(1.0) - 100.0).abs() < 1e-2);
+        assert!((perspective.linearize_depth(depth_at(&perspective, 10.0)) - 10.0).abs() < 1e-2);
+
+        let orthographic = DepthOfFieldCamera { orthographic: true, ..perspective };
+        assert!((orthographic.linearize_depth(0.5) - 50.05).abs() < 1e-4);
+    }
+
+    #[test]
+    fn circle_of_confusion_grows_outside_focus_range() {
+        let config = DepthOfFieldConfig { enabled: true, focus_distance: 10.0, focus_range: 4.0, aperture: 1.0, ..Default::default() };
+        assert_eq!(DepthOfFieldProcessor::circle_of_confusion(&config, 10.0), 0.0);
+        assert_eq!(DepthOfFieldProcessor::circle_of_confusion(&config, 12.0), 0.0);
+
+        let near = DepthOfFieldProcessor::circle_of_confusion(&config, 20.0);
+        let far = DepthOfFieldProcessor::circle_of_confusion(&config, 80.0);
+        assert!(near > 0.0 && far > near && far <= 1.0);
+
+        let closed = DepthOfFieldConfig { aperture: 0.0, ..config };
+        assert_eq!(DepthOfFieldProcessor::circle_of_confusion(&closed, 80.0), 0.0);
+    }
+
+    #[test]
+    fn focused_pixels_stay_sharp_while_far_pixels_blur() {
+        let camera = camera();
+        let config = DepthOfFieldConfig { enabled: true, focus_distance: 10.0, focus_range: 2.0, aperture: 1.0, ..Default::default() };
+
+        // 棋盘格图像，左半对焦，右半在远处
+        let original: Vec<Vec3> = (0..WIDTH * HEIGHT)
+            .map(|i| if (i % WIDTH + i / WIDTH) % 2 == 0 { Vec3::ONE } else { Vec3::ZERO })
+            .collect();
+        let depth: Vec<f32> = (0..WIDTH * HEIGHT)
+            .map(|i| depth_at(&camera, if i % WIDTH < WIDTH / 2 { 10.0 } else { 90.0 }))
+            .collect();
+
+        let mut image = original.clone();
+        DepthOfFieldProcessor::apply(&config, &camera, &mut image, &depth, WIDTH, HEIGHT);
+
+        let focused = 0..WIDTH / 2;
+        assert_eq!(region_variance(&image, focused.clone()), region_variance(&original, focused));
+
+        let far = WIDTH / 2 + 4..WIDTH;
+        assert!(region_variance(&image, far.clone()) < region_variance(&original, far) * 0.25);
+    }
+
+    #[test]
+    fn disabled_config_leaves_image_unchanged() {
+        let camera = camera();
+        let original: Vec<Vec3> = (0..WIDTH * HEIGHT).map(|i| Vec3::splat((i % 2) as f32)).collect();
+        let depth = vec![depth_at(&camera, 90.0); (WIDTH * HEIGHT) as usize];
+        let mut image = original.clone();
+        DepthOfFieldProcessor::apply(&DepthOfFieldConfig::default(), &camera, &mut image, &depth, WIDTH, HEIGHT);
+        assert_eq!(image, original);
+    }
+
+    #[test]
+    fn config_follows_camera_focus_and_aperture() {
+        let mut camera = Camera::default();
+        camera.set_focus_distance(0.0);
+        assert_eq!(camera.focus_distance, camera.near_plane);
+        camera.set_aperture(-1.0);
+        assert_eq!(camera.aperture, 0.0);
+
+        camera.set_focus_distance(25.0);
+        camera.set_aperture(2.0);
+        let mut config = DepthOfFieldConfig::default();
+        config.apply_camera(&camera);
+        assert_eq!((config.focus_distance, config.aperture), (25.0, 2.0));
+    }
+}
@@ -0,0 +1,259 @@
+//! 绘制列表
+//!
+//! 收集世界中可见的网格渲染器，按材质的渲染队列排序：不透明队列内按(着色器, 材质, 网格)聚合以减少状态切换，
+//! 透明队列内按与相机的距离由远到近排序。覆盖层队列单独成段，在透明物体合成之后绘制。实体ID只用于保证排序稳定。
+
+use crate::ecs::{MeshRenderer, Transform};
+use crate::math::{Mat4, Vec3, Vec4};
+use crate::render::{CullMode, FrontFace, Material, PipelineVariantKey, RenderQueue, ShaderKeywordSet, ShaderVariantKey};
+use specs::{Entity, Join, LendJoin, World, WorldExt};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// 一次网格绘制
+#[derive(Debug, Clone)]
+pub struct DrawItem {
+    pub entity: Entity,
+    pub mesh_name: String,
+    pub material_name: String,
+    pub shader_name: String,
+    /// 材质启用的着色器关键字
+    pub keywords: ShaderKeywordSet,
+    /// 材质实际生效的剔除方式
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    pub render_queue: u32,
+    /// 到相机的距离平方
+    pub distance_squared: f32,
+    /// 世界变换矩阵
+    pub model: Mat4,
+    /// 材质基础颜色，alpha已乘以材质透明度
+    pub color: Vec4,
+}
+
+impl DrawItem {
+    /// 绘制该项使用的管线变体
+    pub fn pipeline_key(&self) -> PipelineVariantKey {
+        PipelineVariantKey::new(ShaderVariantKey::new(self.shader_name.clone(), self.keywords), self.cull_mode, self.front_face)
+    }
+}
+
+/// 按渲染队列排序的绘制列表
+#[derive(Debug, Clone, Default)]
+pub struct DrawList {
+    items: Vec<DrawItem>,
+}
+
+impl DrawList {
+    /// 收集可见的网格渲染器，材质未注册时按默认材质(不透明队列)处理
+    pub fn collect(world: &World, materials: &HashMap<String, Material>, camera_position: Vec3) -> Self {
+        let entities = world.entities();
+        let renderers = world.read_storage::<MeshRenderer>();
+        let transforms = world.read_storage::<Transform>();
+        let default_material = Material::default();
+
+        let items = (&entities, &renderers, transforms.maybe())
+            .join()
+            .filter(|(_, renderer, _)| renderer.visible)
+            .map(|(entity, renderer, transform)| {
+                let material = materials.get(&renderer.material_name).unwrap_or(&default_material);
+                let model = transform.map_or(Mat4::IDENTITY, Transform::current_world_matrix);
+                let position = model.w_axis.truncate();
+                let properties = &material.properties;
+                DrawItem {
+                    entity,
+                    mesh_name: renderer.mesh_name.clone(),
+                    material_name: renderer.material_name.clone(),
+                    shader_name: material.shader_name.clone(),
+                    keywords: ShaderKeywordSet::from_material(material),
+                    cull_mode: material.effective_cull_mode(),
+                    front_face: material.front_face,
+                    render_queue: material.render_queue,
+                    distance_squared: position.distance_squared(camera_position),
+                    model,
+                    color: properties.base_color * Vec4::new(1.0, 1.0, 1.0, properties.alpha),
+                }
+            })
+            .collect();
+        Self::from_items(items)
+    }
+
+    /// 排序给定的绘制项
+    pub fn from_items(mut items: Vec<DrawItem>) -> Self {
+        items.sort_by(Self::compare);
+        Self { items }
+    }
+
+    fn compare(a: &DrawItem, b: &DrawItem) -> Ordering {
+        a.render_queue.cmp(&b.render_queue).then_with(|| {
+            let order = if RenderQueue::is_transparent(a.render_queue) {
+                b.distance_squared.total_cmp(&a.distance_squared)
+            } else {
+                a.shader_name
+                    .cmp(&b.shader_name)
+                    .then_with(|| a.material_name.cmp(&b.material_name))
+                    .then_with(|| a.mesh_name.cmp(&b.mesh_name))
+            };
+            order.then_with(|| a.entity.id().cmp(&b.entity.id()))
+        })
+    }
+
+    /// 按绘制顺序排列的绘制项
+    pub fn items(&self) -> &[DrawItem] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 不透明部分(透明队列之前)
+    pub fn opaque(&self) -> &[DrawItem] {
+        &self.items[..self.transparent_start()]
+    }
+
+    /// 透明部分(透明队列，不含覆盖层)
+    pub fn transparent(&self) -> &[DrawItem] {
+        &self.items[self.transparent_start()..self.overlay_start()]
+    }
+
+    /// 覆盖层部分(覆盖层队列及之后)，在透明物体合成之后绘制
+    pub fn overlay(&self) -> &[DrawItem] {
+        &self.items[self.overlay_start()..]
+    }
+
+    fn transparent_start(&self) -> usize {
+        self.items.partition_point(|item| item.render_queue < RenderQueue::TRANSPARENT)
+    }
+
+    fn overlay_start(&self) -> usize {
+        self.items.partition_point(|item| item.render_queue < RenderQueue::OVERLAY)
+    }
+
+    /// 按可见性标记移除绘制项，`visible` 与 `items()` 一一对应，返回移除的数量
+    pub fn retain_visible(&mut self, visible: &[bool]) -> usize {
+        let before = self.items.len();
+        let mut visible = visible.iter();
+        self.items.retain(|_| visible.next().copied().unwrap_or(true));
+        before - self.items.len()
+    }
+
+    /// 相邻绘制之间的材质切换次数
+    pub fn material_switches(&self) -> usize {
+        self.items
+            .windows(2)
+            .filter(|pair| pair[0].material_name != pair[1].material_name)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::Builder;
+
+    fn item(world: &mut World, shader: &str, render_queue: u32, distance_squared: f32) -> DrawItem {
+        DrawItem {
+            entity: world.create_entity().build(),
+            mesh_name: "cube".to_string(),
+            material_name: shader.to_string(),
+            shader_name: shader.to_string(),
+            keywords: ShaderKeywordSet::new(),
+            cull_mode: CullMode::Back,
+            front_face: FrontFace::Ccw,
+            render_queue,
+            distance_squared,
+            model: Mat4::IDENTITY,
+            color: Vec4::ONE,
+        }
+    }
+
+    #[test]
+    fn opaque_groups_by_shader_and_transparent_sorts_back_to_front() {
+        let mut world = World::new();
+        let items = vec![
+            item(&mut world, "glass", RenderQueue::TRANSPARENT, 1.0),
+            item(&mut world, "pbr", RenderQueue::GEOMETRY, 4.0),
+            item(&mut world, "glass", RenderQueue::TRANSPARENT, 9.0),
+            item(&mut world, "basic", RenderQueue::GEOMETRY, 1.0),
+        ];
+        let list = DrawList::from_items(items);
+
+        let opaque: Vec<_> = list.opaque().iter().map(|item| item.shader_name.as_str()).collect();
+        assert_eq!(opaque, ["basic", "pbr"]);
+        let distances: Vec<_> = list.transparent().iter().map(|item| item.distance_squared).collect();
+        assert_eq!(distances, [9.0, 1.0]);
+    }
+
+    #[test]
+    fn retain_visible_keeps_order() {
+        let mut world = World::new();
+        let items = (0..4).map(|i| item(&mut world, "basic", RenderQueue::GEOMETRY, i as f32)).collect();
+        let mut list = DrawList::from_items(items);
+        let kept: Vec<_> = list.items().iter().map(|item| item.entity).collect();
+
+        assert_eq!(list.retain_visible(&[true, false, true, false]), 2);
+        let remaining: Vec<_> = list.items().iter().map(|item| item.entity).collect();
+        assert_eq!(remaining, [kept[0], kept[2]]);
+    }
+
+    fn spawn(world: &mut World, material_name: &str, position: Vec3, visible: bool) -> Entity {
+        let mut transform = Transform::new();
+        transform.set_position(position);
+        world
+            .create_entity()
+            .with(MeshRenderer { visible, ..MeshRenderer::new("cube", material_name) })
+            .with(transform)
+            .build()
+    }
+
+    #[test]
+    fn collect_orders_by_material_render_queue() {
+        let mut world = World::new();
+        world.register::<MeshRenderer>();
+        world.register::<Transform>();
+        let mut materials = HashMap::new();
+        materials.insert("glass".to_string(), Material::new("glass").with_render_queue(RenderQueue::TRANSPARENT));
+        materials.insert("ui".to_string(), Material::new("ui").with_render_queue(RenderQueue::OVERLAY));
+        materials.insert("sky".to_string(), Material::new("sky").with_render_queue(RenderQueue::BACKGROUND));
+
+        // 创建顺序与队列顺序相反
+        let overlay = spawn(&mut world, "ui", Vec3::ZERO, true);
+        let near_glass = spawn(&mut world, "glass", Vec3::new(0.0, 0.0, 1.0), true);
+        let far_glass = spawn(&mut world, "glass", Vec3::new(0.0, 0.0, 5.0), true);
+        let unknown = spawn(&mut world, "missing", Vec3::ZERO, true);
+        spawn(&mut world, "glass", Vec3::ZERO, false);
+        let sky = spawn(&mut world, "sky", Vec3::ZERO, true);
+
+        let list = DrawList::collect(&world, &materials, Vec3::ZERO);
+        let order: Vec<_> = list.items().iter().map(|item| item.entity).collect();
+        assert_eq!(order, [sky, unknown, far_glass, near_glass, overlay]);
+        assert_eq!(list.items()[1].render_queue, RenderQueue::GEOMETRY);
+        assert_eq!(list.opaque().len(), 2);
+        // 覆盖层不属于透明部分，不写入OIT缓冲
+        let transparent: Vec<_> = list.transparent().iter().map(|item| item.entity).collect();
+        assert_eq!(transparent, [far_glass, near_glass]);
+        let overlays: Vec<_> = list.overlay().iter().map(|item| item.entity).collect();
+        assert_eq!(overlays, [overlay]);
+        assert_eq!(list.items()[2].distance_squared, 25.0);
+    }
+
+    #[test]
+    fn material_switches_counts_adjacent_changes() {
+        let mut world = World::new();
+        let items = vec![
+            item(&mut world, "pbr", RenderQueue::GEOMETRY, 0.0),
+            item(&mut world, "basic", RenderQueue::GEOMETRY, 0.0),
+            item(&mut world, "pbr", RenderQueue::GEOMETRY, 0.0),
+            item(&mut world, "basic", RenderQueue::GEOMETRY, 0.0),
+        ];
+        // 排序后同材质相邻，只切换一次
+        assert_eq!(DrawList::from_items(items).material_switches(), 1);
+        assert!(DrawList::default().is_empty());
+        assert_eq!(DrawList::default().material_switches(), 0);
+    }
+}
@@ -0,0 +1,207 @@
+//! 距离雾
+//!
+//! 雾量由片元到相机的距离决定，在PBR着色器末尾与雾颜色混合(`apply_fog`)。
+//! `Fog::factor` 是与着色器一致的CPU参考实现。
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// 雾量随距离变化的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FogMode {
+    /// 在start到end之间线性增加
+    #[default]
+    Linear,
+    /// 1 - e^(-density * d)
+    Exp,
+    /// 1 - e^(-(density * d)^2)，近处更清晰、远处更快变浓
+    Exp2,
+}
+
+impl FogMode {
+    /// 着色器中的模式编号
+    pub fn shader_index(&self) -> u32 {
+        match self {
+            FogMode::Linear => 0,
+            FogMode::Exp => 1,
+            FogMode::Exp2 => 2,
+        }
+    }
+}
+
+/// 雾设置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fog {
+    pub enabled: bool,
+    pub mode: FogMode,
+    pub color: Vec3,
+    /// 指数雾的密度
+    pub density: f32,
+    /// 线性雾开始的距离
+    pub start: f32,
+    /// 线性雾完全不透明的距离
+    pub end: f32,
+}
+
+impl Default for Fog {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: FogMode::Linear,
+            color: Vec3::new(0.6, 0.65, 0.7),
+            density: 0.05,
+            start: 10.0,
+            end: 100.0,
+        }
+    }
+}
+
+impl Fog {
+    /// 线性雾
+    pub fn linear(color: Vec3, start: f32, end: f32) -> Self {
+        Self {
+            enabled: true,
+            mode: FogMode::Linear,
+            color,
+            start,
+            end,
+            ..Default::default()
+        }
+    }
+
+    /// 指数雾
+    pub fn exponential(color: Vec3, density: f32) -> Self {
+        Self {
+            enabled: true,
+            mode: FogMode::Exp,
+            color,
+            density,
+            ..Default::default()
+        }
+    }
+
+    /// 平方指数雾
+    pub fn exponential_squared(color: Vec3, density: f32) -> Self {
+        Self {
+            mode: FogMode::Exp2,
+            ..Self::exponential(color, density)
+        }
+    }
+
+    /// 距离distance处的雾量，0为无雾，1为完全是雾的颜色
+    pub fn factor(&self, distance: f32) -> f32 {
+        if !self.enabled {
+            return 0.0;
+        }
+
+        let distance = distance.max(0.0);
+        let factor = match self.mode {
+            FogMode::Linear => {
+                let range = self.end - self.start;
+                if range <= f32::EPSILON {
+                    if distance >= self.end { 1.0 } else { 0.0 }
+                } else {
+                    (distance - self.start) / range
+                }
+            }
+            FogMode::Exp => 1.0 - (-self.density * distance).exp(),
+            FogMode::Exp2 => {
+                let scaled = self.density * distance;
+                1.0 - (-scaled * scaled).exp()
+            }
+        };
+        factor.clamp(0.0, 1.0)
+    }
+
+    /// 把雾混合到距离distance处的颜色上
+    pub fn apply(&self, color: Vec3, distance: f32) -> Vec3 {
+        color.lerp(self.color, self.factor(distance))
+    }
+}
+
+/// 雾uniform数据，与PBR着色器中的 `FogUniform` 布局一致
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FogUniform {
+    /// rgb为雾颜色，a为1时启用
+    pub color: [f32; 4],
+    /// x为模式，y为密度，z/w为线性雾的起止距离
+    pub params: [f32; 4],
+}
+
+impl From<&Fog> for FogUniform {
+    fn from(fog: &Fog) -> Self {
+        Self {
+            color: fog.color.extend(if fog.enabled { 1.0 } else { 0.0 }).to_array(),
+            params: [fog.mode.shader_index() as f32, fog.density, fog.start, fog.end],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_fog_spans_start_to_end() {
+        let fog = Fog::linear(Vec3::ONE, 10.0, 50.0);
+        assert_eq!(fog.factor(10.0), 0.0);
+        assert_eq!(fog.factor(50.0), 1.0);
+        assert!((fog.factor(30.0) - 0.5).abs() < 1e-6);
+        // 范围外截断
+        assert_eq!(fog.factor(2.0), 0.0);
+        assert_eq!(fog.factor(500.0), 1.0);
+    }
+
+    #[test]
+    fn exponential_fog_matches_reference_formula() {
+        let distance = 20.0_f32;
+        let exp = Fog::exponential(Vec3::ONE, 0.05);
+        assert!((exp.factor(distance) - (1.0 - (-0.05 * distance).exp())).abs() < 1e-6);
+
+        let exp2 = Fog::exponential_squared(Vec3::ONE, 0.05);
+        let reference = 1.0 - (-(0.05 * distance) * (0.05 * distance)).exp();
+        assert!((exp2.factor(distance) - reference).abs() < 1e-6);
+        // 同样密度下平方指数雾近处更清晰
+        assert!(exp2.factor(5.0) < exp.factor(5.0));
+        assert_eq!(exp2.factor(0.0), 0.0);
+    }
+
+    #[test]
+    fn disabled_or_degenerate_fog() {
+        let mut fog = Fog::linear(Vec3::ONE, 10.0, 10.0);
+        assert_eq!(fog.factor(9.9), 0.0);
+        assert_eq!(fog.factor(10.0), 1.0);
+
+        fog.enabled = false;
+        assert_eq!(fog.factor(1000.0), 0.0);
+        assert_eq!(fog.apply(Vec3::ZERO, 1000.0), Vec3::ZERO);
+    }
+
+    #[test]
+    fn apply_blends_toward_fog_color() {
+        let fog = Fog::linear(Vec3::new(1.0, 0.0, 0.0), 0.0, 10.0);
+        let color = fog.apply(Vec3::new(0.0, 0.0, 1.0), 5.0);
+        assert!((color - Vec3::new(0.5, 0.0, 0.5)).length() < 1e-6);
+    }
+
+    #[test]
+    fn uniform_packs_mode_and_parameters() {
+        let uniform = FogUniform::from(&Fog::exponential_squared(Vec3::new(0.1, 0.2, 0.3), 0.02));
+        assert_eq!(uniform.color, [0.1, 0.2, 0.3, 1.0]);
+        assert_eq!(uniform.params[0], 2.0);
+        assert_eq!(uniform.params[1], 0.02);
+        assert_eq!(std::mem::size_of::<FogUniform>(), 32);
+
+        let disabled = FogUniform::from(&Fog::default());
+        assert_eq!(disabled.color[3], 0.0);
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let fog: Fog = serde_json::from_str(r#"{"enabled": true, "mode": "Exp2"}"#).unwrap();
+        assert_eq!(fog.mode, FogMode::Exp2);
+        assert_eq!(fog.density, Fog::default().density);
+    }
+}
@@ -0,0 +1,282 @@
+//! 多缓冲的每帧资源
+//!
+//! 每帧写入的uniform等资源按帧环(2或3套)轮换：CPU写第N帧时，GPU可能仍在执行第N-1、N-2帧。
+//! 每套资源在提交后挂一个栅栏，轮换回来时若GPU尚未完成则等待，保证不会改写仍被使用的缓冲。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 允许的在途帧数范围
+pub const MIN_FRAMES_IN_FLIGHT: u32 = 2;
+pub const MAX_FRAMES_IN_FLIGHT: u32 = 3;
+
+/// 一次提交的完成栅栏
+#[derive(Debug)]
+pub struct FrameFence {
+    submission: wgpu::SubmissionIndex,
+    signaled: Arc<AtomicBool>,
+}
+
+impl FrameFence {
+    /// 在队列已提交的工作完成时发出信号
+    pub fn new(queue: &wgpu::Queue, submission: wgpu::SubmissionIndex) -> Self {
+        let signaled = Arc::new(AtomicBool::new(false));
+        let flag = signaled.clone();
+        queue.on_submitted_work_done(move || flag.store(true, Ordering::Release));
+        Self { submission, signaled }
+    }
+
+    pub fn is_signaled(&self) -> bool {
+        self.signaled.load(Ordering::Acquire)
+    }
+
+    /// 阻塞直到提交的工作完成
+    pub fn wait(&self, device: &wgpu::Device) {
+        if !self.is_signaled() {
+            device.poll(wgpu::Maintain::WaitForSubmissionIndex(self.submission.clone()));
+            self.signaled.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// 帧环中的一个槽位
+#[derive(Debug)]
+struct FrameSlot<T> {
+    resources: T,
+    fence: Option<FrameFence>,
+}
+
+/// 每帧资源环
+#[derive(Debug)]
+pub struct FrameRing<T> {
+    slots: Vec<FrameSlot<T>>,
+    current: usize,
+    /// 已开始的帧数
+    frame_number: u64,
+    /// 当前槽位已开始、尚未提交
+    recording: bool,
+    /// 因GPU未完成而等待的次数
+    stall_count: u64,
+}
+
+impl<T> FrameRing<T> {
+    /// 创建帧环，帧数限制在[2, 3]
+    pub fn new(frames_in_flight: u32, mut create: impl FnMut(usize) -> T) -> Self {
+        let count = frames_in_flight.clamp(MIN_FRAMES_IN_FLIGHT, MAX_FRAMES_IN_FLIGHT) as usize;
+        let slots = (0..count)
+            .map(|index| FrameSlot { resources: create(index), fence: None })
+            .collect();
+        Self {
+            slots,
+            current: count - 1,
+            frame_number: 0,
+            recording: false,
+            stall_count: 0,
+        }
+    }
+
+    /// 轮换到下一套资源；若它仍被在途帧使用则等待GPU完成
+    pub fn begin_frame(&mut self, device: &wgpu::Device) -> &mut T {
+        if !self.recording {
+            self.current = (self.current + 1) % self.slots.len();
+            self.frame_number += 1;
+            self.recording = true;
+
+            if let Some(fence) = self.slots[self.current].fence.take() {
+                if !fence.is_signaled() {
+                    self.stall_count += 1;
+                    fence.wait(device);
+                }
+            }
+        }
+        &mut self.slots[self.current].resources
+    }
+
+    /// 记录本帧最后一次提交，之后该槽位在GPU完成前不会被再次使用
+    pub fn end_frame(&mut self, queue: &wgpu::Queue, submission: wgpu::SubmissionIndex) {
+        if self.recording {
+            self.slots[self.current].fence = Some(FrameFence::new(queue, submission));
+            self.recording = false;
+        }
+    }
+
+    /// 当前帧的资源
+    pub fn current(&self) -> &T {
+        &self.slots[self.current].resources
+    }
+
+    pub fn current_mut(&mut self) -> &mut T {
+        &mut self.slots[self.current].resources
+    }
+
+    /// 当前槽位索引
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn frames_in_flight(&self) -> u32 {
+        self.slots.len() as u32
+    }
+
+    pub fn frame_number(&self) -> u64 {
+        self.frame_number
+    }
+
+    /// 已提交但GPU尚未完成的帧数
+    pub fn in_flight_count(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.fence.as_ref().map_or(false, |fence| !fence.is_signaled()))
+            .count()
+    }
+
+    /// 槽位是否仍被在途帧使用
+    pub fn is_slot_in_flight(&self, index: usize) -> bool {
+        self.slots
+            .get(index)
+            .and_then(|slot| slot.fence.as_ref())
+            .map_or(false, |fence| !fence.is_signaled())
+    }
+
+    pub fn stall_count(&self) -> u64 {
+        self.stall_count
+    }
+
+    /// 等待所有在途帧完成(如缩放或销毁资源前)
+    pub fn wait_idle(&mut self, device: &wgpu::Device) {
+        for slot in &mut self.slots {
+            if let Some(fence) = slot.fence.take() {
+                fence.wait(device);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().map(|slot| &slot.resources)
+    }
+}
+
+/// 每帧全局uniform
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FrameUniform {
+    /// 渲染目标宽高及其倒数
+    pub resolution: [f32; 4],
+    /// 帧序号(低32位)与环中的槽位索引
+    pub frame: [u32; 4],
+}
+
+/// 一帧独占的GPU资源
+#[derive(Debug)]
+pub struct FrameResources {
+    /// 每帧全局uniform缓冲
+    pub uniform_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl FrameResources {
+    pub fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("帧资源绑定组布局"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT | wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        })
+    }
+
+    pub fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, index: usize) -> Self {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(&format!("帧uniform缓冲{}", index)),
+            size: std::mem::size_of::<FrameUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("帧资源绑定组{}", index)),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+        Self { uniform_buffer, bind_group }
+    }
+
+    pub fn write_uniform(&self, queue: &wgpu::Queue, uniform: &FrameUniform) {
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(uniform));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    #[test]
+    fn frames_in_flight_are_clamped() {
+        let ring = FrameRing::new(1, |index| index);
+        assert_eq!(ring.frames_in_flight(), MIN_FRAMES_IN_FLIGHT);
+        let ring = FrameRing::new(8, |index| index * 10);
+        assert_eq!(ring.frames_in_flight(), MAX_FRAMES_IN_FLIGHT);
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![0, 10, 20]);
+        // 第一帧开始前指向最后一个槽位，begin_frame后从0开始
+        assert_eq!((ring.current_index(), ring.frame_number(), ring.in_flight_count()), (2, 0, 0));
+    }
+
+    #[test]
+    fn submitted_frames_rotate_without_reusing_busy_slots() {
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过帧环测试: 没有可用的适配器");
+            return;
+        };
+        let layout = FrameResources::bind_group_layout(&device);
+        let mut ring = FrameRing::new(3, |index| FrameResources::new(&device, &layout, index));
+
+        let mut visited = Vec::new();
+        for frame in 0..10u32 {
+            let resources = ring.begin_frame(&device);
+            let uniform = FrameUniform { resolution: [64.0, 32.0, 1.0 / 64.0, 1.0 / 32.0], frame: [frame, 0, 0, 0] };
+            resources.write_uniform(&queue, &uniform);
+            let index = ring.current_index();
+            // 正在写入的槽位不能仍被在途帧使用
+            assert!(!ring.is_slot_in_flight(index));
+            // 重复begin_frame不会再次轮换
+            ring.begin_frame(&device);
+            assert_eq!(ring.current_index(), index);
+
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.clear_buffer(&ring.current().uniform_buffer, 0, None);
+            let submission = queue.submit(std::iter::once(encoder.finish()));
+            ring.end_frame(&queue, submission);
+            assert!(ring.in_flight_count() <= 3);
+            visited.push(index);
+        }
+
+        assert_eq!(visited, vec![0, 1, 2, 0, 1, 2, 0, 1, 2, 0]);
+        assert_eq!(ring.frame_number(), 10);
+
+        ring.wait_idle(&device);
+        assert_eq!(ring.in_flight_count(), 0);
+        assert!((0..3).all(|index| !ring.is_slot_in_flight(index)));
+        assert!(!ring.is_slot_in_flight(7));
+    }
+
+    #[test]
+    fn fence_signals_after_wait() {
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过栅栏测试: 没有可用的适配器");
+            return;
+        };
+        let submission = queue.submit(std::iter::empty());
+        let fence = FrameFence::new(&queue, submission);
+        fence.wait(&device);
+        assert!(fence.is_signaled());
+    }
+}
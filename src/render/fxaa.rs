@@ -0,0 +1,270 @@
+//! 快速近似抗锯齿(FXAA)
+//!
+//! 按亮度检测边缘，沿边缘法线方向偏移采样并与子像素混合，单次全屏绘制完成。
+
+use crate::render::{FXAAConfig, FXAAQuality};
+use wgpu::util::DeviceExt;
+
+/// FXAA着色器uniform(对应fxaa.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct FxaaUniforms {
+    texel_size: [f32; 2],
+    quality_preset: u32,
+    _padding: u32,
+}
+
+impl FXAAQuality {
+    /// 着色器中的质量预设编号
+    pub fn preset_index(&self) -> u32 {
+        match self {
+            FXAAQuality::Low => 0,
+            FXAAQuality::Medium => 1,
+            FXAAQuality::High => 2,
+            FXAAQuality::Ultra => 3,
+        }
+    }
+}
+
+/// GPU FXAA渲染器
+pub struct FxaaRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl FxaaRenderer {
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("FXAA着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/post_processing/fxaa.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("FXAA绑定组布局"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("FXAA管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("FXAA管线"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: output_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("FXAA采样器"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self { pipeline, bind_group_layout, sampler }
+    }
+
+    /// 对input做抗锯齿并写入output，两者尺寸相同
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        config: &FXAAConfig,
+        width: u32,
+        height: u32,
+        input: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+    ) {
+        let uniforms = FxaaUniforms {
+            texel_size: [1.0 / width.max(1) as f32, 1.0 / height.max(1) as f32],
+            quality_preset: config.quality_preset.preset_index(),
+            _padding: 0,
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("FXAA Uniform"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("FXAA绑定组"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(input) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+            ],
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("FXAA通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+    const SIZE: u32 = 8;
+
+    fn texture(device: &wgpu::Device, usage: wgpu::TextureUsages) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("FXAA测试纹理"),
+            size: wgpu::Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage,
+            view_formats: &[],
+        })
+    }
+
+    /// 对角线右上方为白色的锯齿边
+    fn staircase() -> Vec<u8> {
+        (0..SIZE * SIZE)
+            .flat_map(|i| if i % SIZE > i / SIZE { [255, 255, 255, 255] } else { [0, 0, 0, 255] })
+            .collect()
+    }
+
+    /// 对图像做一次FXAA并回读每个像素的红色通道
+    fn run_fxaa(device: &wgpu::Device, queue: &wgpu::Queue, pixels: &[u8]) -> Vec<u8> {
+        let input = texture(device, wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST);
+        queue.write_texture(
+            input.as_image_copy(),
+            pixels,
+            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(SIZE * 4), rows_per_image: Some(SIZE) },
+            input.size(),
+        );
+        let output = texture(device, wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        FxaaRenderer::new(device, FORMAT).render(
+            device,
+            &mut encoder,
+            &FXAAConfig::default(),
+            SIZE,
+            SIZE,
+            &input.create_view(&wgpu::TextureViewDescriptor::default()),
+            &output.create_view(&wgpu::TextureViewDescriptor::default()),
+        );
+
+        let bytes_per_row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("FXAA测试回读缓冲"),
+            size: (bytes_per_row * SIZE) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            output.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(bytes_per_row), rows_per_image: Some(SIZE) },
+            },
+            output.size(),
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .map(|(x, y)| data[(y * bytes_per_row + x * 4) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn quality_presets_match_shader_indices() {
+        let presets = [FXAAQuality::Low, FXAAQuality::Medium, FXAAQuality::High, FXAAQuality::Ultra];
+        assert_eq!(presets.map(|preset| preset.preset_index()), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn staircase_edge_is_smoothed_and_flat_areas_are_kept() {
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过FXAA测试: 没有可用的适配器");
+            return;
+        };
+
+        let red = run_fxaa(&device, &queue, &staircase());
+        let pixel = |x: u32, y: u32| red[(y * SIZE + x) as usize];
+        // 远离边缘的像素不变
+        assert_eq!((pixel(SIZE - 1, 0), pixel(0, SIZE - 1)), (255, 0));
+        // 对角线上的锯齿被混合
+        for i in 0..SIZE {
+            assert!(pixel(i, i) > 0 && pixel(i, i) < 255, "({}, {}) = {}", i, i, pixel(i, i));
+        }
+
+        let flat = vec![128u8; (SIZE * SIZE * 4) as usize];
+        assert!(run_fxaa(&device, &queue, &flat).iter().all(|&value| value.abs_diff(128) <= 1));
+    }
+}
@@ -0,0 +1,141 @@
+//! 资源派生的GPU对象缓存
+//!
+//! 按资源ID缓存由资源数据创建的GPU对象(纹理、绑定组等)并记录创建时的资源代数。
+//! 资源热重载后句柄代数递增，下次获取时自动用新数据重建，持有句柄的一方无需感知。
+
+use crate::assets::{AssetHandle, AssetId};
+use std::collections::HashMap;
+
+#[derive(Debug)]
+struct GpuAssetEntry<V> {
+    generation: u64,
+    value: V,
+}
+
+/// 资源ID -> GPU对象
+#[derive(Debug)]
+pub struct GpuAssetCache<V> {
+    entries: HashMap<AssetId, GpuAssetEntry<V>>,
+}
+
+impl<V> Default for GpuAssetCache<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> GpuAssetCache<V> {
+    pub fn new() -> Self {
+        Self { entries: HashMap::new() }
+    }
+
+    /// 获取句柄对应的GPU对象，未创建或资源已重新加载时调用create重建
+    ///
+    /// 资源处于加载中或重新加载失败时沿用已有的对象，从未创建过则返回None。
+    pub fn get_or_create<T>(&mut self, handle: &AssetHandle<T>, create: impl FnOnce(&T) -> V) -> Option<&V> {
+        let generation = handle.generation();
+        let stale = self
+            .entries
+            .get(&handle.id())
+            .map_or(true, |entry| entry.generation != generation);
+
+        if stale && handle.is_valid() {
+            if let Some(resource) = handle.get() {
+                let value = create(&resource);
+                self.entries.insert(handle.id(), GpuAssetEntry { generation, value });
+            }
+        }
+        self.entries.get(&handle.id()).map(|entry| &entry.value)
+    }
+
+    /// 不重建，直接获取缓存的对象
+    pub fn get(&self, id: AssetId) -> Option<&V> {
+        self.entries.get(&id).map(|entry| &entry.value)
+    }
+
+    /// 缓存的对象是否基于旧代数的资源创建
+    pub fn is_stale<T>(&self, handle: &AssetHandle<T>) -> bool {
+        self.entries
+            .get(&handle.id())
+            .map_or(true, |entry| entry.generation != handle.generation())
+    }
+
+    /// 创建对象时的资源代数
+    pub fn generation(&self, id: AssetId) -> Option<u64> {
+        self.entries.get(&id).map(|entry| entry.generation)
+    }
+
+    pub fn remove(&mut self, id: AssetId) -> Option<V> {
+        self.entries.remove(&id).map(|entry| entry.value)
+    }
+
+    /// 只保留满足条件的资源，用于清理已卸载资源的GPU对象
+    pub fn retain(&mut self, mut keep: impl FnMut(AssetId) -> bool) {
+        self.entries.retain(|&id, _| keep(id));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn reloaded_asset_is_rebuilt_once() {
+        let handle = AssetHandle::new(1, &Arc::new(String::from("v1")), "albedo.txt");
+        let mut cache = GpuAssetCache::new();
+        let mut builds = 0;
+
+        let value = cache.get_or_create(&handle, |data| { builds += 1; data.to_uppercase() }).cloned();
+        assert_eq!(value.as_deref(), Some("V1"));
+        cache.get_or_create(&handle, |data| { builds += 1; data.to_uppercase() });
+        assert_eq!(builds, 1);
+
+        handle.slot().set_loaded(Arc::new(String::from("v2")));
+        assert!(cache.is_stale(&handle));
+        let value = cache.get_or_create(&handle, |data| { builds += 1; data.to_uppercase() }).cloned();
+        assert_eq!(value.as_deref(), Some("V2"));
+        assert_eq!(builds, 2);
+        assert_eq!(cache.generation(handle.id()), Some(2));
+    }
+
+    #[test]
+    fn failed_reload_keeps_previous_object() {
+        let handle = AssetHandle::new(2, &Arc::new(1u32), "value.bin");
+        let mut cache = GpuAssetCache::new();
+        cache.get_or_create(&handle, |value| *value * 10);
+
+        handle.slot().set_failed("文件损坏");
+        assert_eq!(cache.get_or_create(&handle, |_| unreachable!()), Some(&10));
+
+        let loading: AssetHandle<u32> = AssetHandle::loading(3, "pending.bin");
+        assert!(cache.get_or_create(&loading, |value| *value).is_none());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn retain_drops_unloaded_assets() {
+        let mut cache = GpuAssetCache::new();
+        for id in 1..=3 {
+            cache.get_or_create(&AssetHandle::new(id, &Arc::new(id), "value.bin"), |value| *value);
+        }
+        cache.retain(|id| id != 2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.remove(3), Some(3));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
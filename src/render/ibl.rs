@@ -0,0 +1,552 @@
+//! 基于图像的光照(IBL)
+//!
+//! 环境立方体贴图在CPU上预过滤为两张立方体贴图：
+//! - 辐照度图：对每个法线方向做余弦加权的半球积分，提供漫反射环境光；
+//! - 镜面预过滤图：每级mip对应一个粗糙度(0到1线性分布)，用GGX重要性采样卷积(假设N=V=R)。
+//!
+//! 结果上传为 `Rgba16Float` 立方体纹理，绑定到PBR着色器的第3组(`HAS_IBL` 关键字)。
+
+use crate::math::Vec3;
+use crate::render::{Texture, TextureDescriptor, TextureFormat, WrapMode};
+use crate::{EngineError, EngineResult};
+
+/// 立方体贴图面上(u, v)处的方向，u、v范围[-1, 1]，v向下
+///
+/// 面顺序为+X、-X、+Y、-Y、+Z、-Z，与wgpu的立方体纹理层顺序一致。
+pub fn cube_face_direction(face: u32, u: f32, v: f32) -> Vec3 {
+    let direction = match face {
+        0 => Vec3::new(1.0, -v, -u),
+        1 => Vec3::new(-1.0, -v, u),
+        2 => Vec3::new(u, 1.0, v),
+        3 => Vec3::new(u, -1.0, -v),
+        4 => Vec3::new(u, -v, 1.0),
+        _ => Vec3::new(-u, -v, -1.0),
+    };
+    direction.normalize()
+}
+
+/// 方向所在的立方体面及面上的(u, v)，`cube_face_direction` 的逆运算
+pub fn direction_to_cube_face(direction: Vec3) -> (u32, f32, f32) {
+    let abs = direction.abs();
+    if abs.x >= abs.y && abs.x >= abs.z {
+        let major = abs.x.max(f32::EPSILON);
+        if direction.x >= 0.0 {
+            (0, -direction.z / major, -direction.y / major)
+        } else {
+            (1, direction.z / major, -direction.y / major)
+        }
+    } else if abs.y >= abs.z {
+        let major = abs.y.max(f32::EPSILON);
+        if direction.y >= 0.0 {
+            (2, direction.x / major, direction.z / major)
+        } else {
+            (3, direction.x / major, -direction.z / major)
+        }
+    } else {
+        let major = abs.z.max(f32::EPSILON);
+        if direction.z >= 0.0 {
+            (4, direction.x / major, -direction.y / major)
+        } else {
+            (5, -direction.x / major, -direction.y / major)
+        }
+    }
+}
+
+/// 预过滤参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IblSettings {
+    /// 辐照度图边长
+    pub irradiance_size: u32,
+    /// 镜面预过滤图第0级的边长，mip级数为log2(边长)+1
+    pub specular_size: u32,
+    /// 每个镜面texel的GGX采样数
+    pub sample_count: u32,
+}
+
+impl Default for IblSettings {
+    fn default() -> Self {
+        Self {
+            irradiance_size: 32,
+            specular_size: 128,
+            sample_count: 64,
+        }
+    }
+}
+
+/// 预过滤后的环境光照
+#[derive(Debug, Clone)]
+pub struct EnvironmentMap {
+    /// 漫反射辐照度(已除以π，乘以反照率即为漫反射环境光)
+    pub irradiance: Texture,
+    /// 镜面预过滤图的各级mip，第i级的粗糙度为 i / (级数 - 1)
+    pub specular_mips: Vec<Texture>,
+}
+
+impl EnvironmentMap {
+    /// 辐照度积分使用的源立方体边长
+    const IRRADIANCE_SOURCE_SIZE: u32 = 16;
+
+    /// 预过滤环境立方体贴图
+    pub fn prefilter(environment: &Texture, settings: &IblSettings) -> EngineResult<Self> {
+        if !environment.descriptor.is_cubemap || environment.descriptor.depth_or_array_layers != 6 {
+            return Err(EngineError::RenderError(format!("IBL需要立方体贴图: {}", environment.name)).into());
+        }
+        let irradiance_size = settings.irradiance_size.max(1);
+        let specular_size = settings.specular_size.max(1);
+
+        let irradiance_source = CpuCube::from_texture(environment, Self::IRRADIANCE_SOURCE_SIZE)?;
+        let irradiance = irradiance_source.convolve_irradiance(irradiance_size);
+
+        let mip_count = Self::specular_mip_count(specular_size);
+        let mut specular_mips = Vec::with_capacity(mip_count as usize);
+        for mip in 0..mip_count {
+            let size = (specular_size >> mip).max(1);
+            let source = CpuCube::from_texture(environment, size)?;
+            let mip_cube = if mip == 0 {
+                source
+            } else {
+                source.prefilter_specular(size, Self::mip_roughness(mip, mip_count), settings.sample_count.max(1))
+            };
+            specular_mips.push(mip_cube.into_texture(format!("{}_specular_{}", environment.name, mip)));
+        }
+
+        Ok(Self {
+            irradiance: irradiance.into_texture(format!("{}_irradiance", environment.name)),
+            specular_mips,
+        })
+    }
+
+    /// 边长为size的镜面图的mip级数
+    pub fn specular_mip_count(size: u32) -> u32 {
+        32 - size.max(1).leading_zeros()
+    }
+
+    /// 第mip级对应的粗糙度
+    pub fn mip_roughness(mip: u32, mip_count: u32) -> f32 {
+        if mip_count <= 1 {
+            0.0
+        } else {
+            mip as f32 / (mip_count - 1) as f32
+        }
+    }
+
+    pub fn mip_count(&self) -> u32 {
+        self.specular_mips.len() as u32
+    }
+
+    /// PBR着色器第3组的绑定布局：辐照度图、镜面预过滤图、采样器
+    pub fn bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        let cube_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::Cube,
+                multisampled: false,
+            },
+            count: None,
+        };
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("IBL绑定组布局"),
+            entries: &[
+                cube_entry(0),
+                cube_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    /// 上传到GPU并创建绑定组
+    pub fn upload(&self, device: &wgpu::Device, queue: &wgpu::Queue, layout: &wgpu::BindGroupLayout) -> GpuEnvironmentMap {
+        let irradiance = Self::upload_cube(device, queue, "IBL辐照度图", std::slice::from_ref(&self.irradiance));
+        let specular = Self::upload_cube(device, queue, "IBL镜面预过滤图", &self.specular_mips);
+        let cube_view = |texture: &wgpu::Texture| {
+            texture.create_view(&wgpu::TextureViewDescriptor {
+                dimension: Some(wgpu::TextureViewDimension::Cube),
+                ..Default::default()
+            })
+        };
+        let irradiance_view = cube_view(&irradiance);
+        let specular_view = cube_view(&specular);
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("IBL采样器"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("IBL绑定组"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&irradiance_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&specular_view) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&sampler) },
+            ],
+        });
+
+        GpuEnvironmentMap {
+            irradiance,
+            irradiance_view,
+            specular,
+            specular_view,
+            sampler,
+            bind_group,
+        }
+    }
+
+    /// 把各级mip(Rgba32Float立方体)上传为一张Rgba16Float立方体纹理
+    pub(crate) fn upload_cube(device: &wgpu::Device, queue: &wgpu::Queue, label: &str, mips: &[Texture]) -> wgpu::Texture {
+        let size = mips[0].descriptor.width;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d { width: size, height: size, depth_or_array_layers: 6 },
+            mip_level_count: mips.len() as u32,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        for (mip_level, mip) in mips.iter().enumerate() {
+            let mip_size = mip.descriptor.width;
+            let data: Vec<u8> = mip
+                .data
+                .chunks_exact(4)
+                .flat_map(|bytes| f32_to_f16_bits(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])).to_le_bytes())
+                .collect();
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: mip_level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &data,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(mip_size * 8),
+                    rows_per_image: Some(mip_size),
+                },
+                wgpu::Extent3d { width: mip_size, height: mip_size, depth_or_array_layers: 6 },
+            );
+        }
+        texture
+    }
+}
+
+/// GPU上的环境光照资源
+pub struct GpuEnvironmentMap {
+    pub irradiance: wgpu::Texture,
+    pub irradiance_view: wgpu::TextureView,
+    pub specular: wgpu::Texture,
+    pub specular_view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    /// 对应 `EnvironmentMap::bind_group_layout`
+    pub bind_group: wgpu::BindGroup,
+}
+
+/// CPU上的RGB立方体，用于积分
+struct CpuCube {
+    size: u32,
+    texels: Vec<Vec3>,
+}
+
+impl CpuCube {
+    fn new(size: u32) -> Self {
+        Self { size, texels: vec![Vec3::ZERO; (size * size * 6) as usize] }
+    }
+
+    /// 把立方体贴图重采样到边长size：缩小时按块平均，放大时取最近texel
+    fn from_texture(texture: &Texture, size: u32) -> EngineResult<Self> {
+        let source_size = texture.descriptor.width.max(1);
+        let mut cube = Self::new(size);
+        let ratio = (source_size / size).max(1);
+
+        for face in 0..6 {
+            for y in 0..size {
+                for x in 0..size {
+                    let (source_x, source_y) = if source_size >= size {
+                        (x * source_size / size, y * source_size / size)
+                    } else {
+                        ((x * source_size / size).min(source_size - 1), (y * source_size / size).min(source_size - 1))
+                    };
+
+                    let mut sum = Vec3::ZERO;
+                    let block = if source_size >= size { ratio } else { 1 };
+                    for dy in 0..block {
+                        for dx in 0..block {
+                            let texel = texture.texel(face, (source_x + dx).min(source_size - 1), (source_y + dy).min(source_size - 1))?;
+                            sum += Vec3::new(texel[0], texel[1], texel[2]);
+                        }
+                    }
+                    let i = cube.index(face, x, y);
+                    cube.texels[i] = sum / (block * block) as f32;
+                }
+            }
+        }
+        Ok(cube)
+    }
+
+    fn index(&self, face: u32, x: u32, y: u32) -> usize {
+        ((face * self.size + y) * self.size + x) as usize
+    }
+
+    fn texel_uv(&self, coordinate: u32) -> f32 {
+        (coordinate as f32 + 0.5) / self.size as f32 * 2.0 - 1.0
+    }
+
+    fn sample(&self, direction: Vec3) -> Vec3 {
+        let (face, u, v) = direction_to_cube_face(direction);
+        let to_texel = |coordinate: f32| (((coordinate + 1.0) * 0.5 * self.size as f32) as u32).min(self.size - 1);
+        self.texels[self.index(face, to_texel(u), to_texel(v))]
+    }
+
+    /// 余弦加权半球积分
+    fn convolve_irradiance(&self, size: u32) -> Self {
+        // 预先计算源texel的方向和立体角
+        let mut samples = Vec::with_capacity(self.texels.len());
+        for face in 0..6 {
+            for y in 0..self.size {
+                for x in 0..self.size {
+                    let (u, v) = (self.texel_uv(x), self.texel_uv(y));
+                    let texel_area = (2.0 / self.size as f32).powi(2);
+                    let solid_angle = texel_area / (1.0 + u * u + v * v).powf(1.5);
+                    samples.push((cube_face_direction(face, u, v), self.texels[self.index(face, x, y)] * solid_angle));
+                }
+            }
+        }
+
+        let mut result = Self::new(size);
+        for face in 0..6 {
+            for y in 0..size {
+                for x in 0..size {
+                    let normal = cube_face_direction(face, result.texel_uv(x), result.texel_uv(y));
+                    let irradiance: Vec3 = samples
+                        .iter()
+                        .map(|&(direction, radiance)| radiance * normal.dot(direction).max(0.0))
+                        .sum();
+                    let index = result.index(face, x, y);
+                    result.texels[index] = irradiance / std::f32::consts::PI;
+                }
+            }
+        }
+        result
+    }
+
+    /// GGX重要性采样的镜面预过滤
+    fn prefilter_specular(&self, size: u32, roughness: f32, sample_count: u32) -> Self {
+        let alpha = roughness * roughness;
+        let mut result = Self::new(size);
+
+        for face in 0..6 {
+            for y in 0..size {
+                for x in 0..size {
+                    let normal = cube_face_direction(face, result.texel_uv(x), result.texel_uv(y));
+                    let up = if normal.z.abs() < 0.999 { Vec3::Z } else { Vec3::X };
+                    let tangent = up.cross(normal).normalize();
+                    let bitangent = normal.cross(tangent);
+
+                    let mut sum = Vec3::ZERO;
+                    let mut weight = 0.0;
+                    for i in 0..sample_count {
+                        let (xi_x, xi_y) = hammersley(i, sample_count);
+                        let phi = 2.0 * std::f32::consts::PI * xi_x;
+                        let cos_theta = ((1.0 - xi_y) / (1.0 + (alpha * alpha - 1.0) * xi_y)).sqrt();
+                        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+                        let half = (tangent * (phi.cos() * sin_theta) + bitangent * (phi.sin() * sin_theta) + normal * cos_theta)
+                            .normalize();
+                        let light = half * (2.0 * normal.dot(half)) - normal;
+
+                        let n_dot_l = normal.dot(light);
+                        if n_dot_l > 0.0 {
+                            sum += self.sample(light) * n_dot_l;
+                            weight += n_dot_l;
+                        }
+                    }
+                    let index = result.index(face, x, y);
+                    result.texels[index] = if weight > 0.0 { sum / weight } else { self.sample(normal) };
+                }
+            }
+        }
+        result
+    }
+
+    fn into_texture(self, name: String) -> Texture {
+        let data = self
+            .texels
+            .iter()
+            .flat_map(|texel| [texel.x, texel.y, texel.z, 1.0])
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let descriptor = TextureDescriptor {
+            width: self.size,
+            height: self.size,
+            format: TextureFormat::Rgba32Float,
+            wrap_u: WrapMode::ClampToEdge,
+            wrap_v: WrapMode::ClampToEdge,
+            generate_mipmaps: false,
+            depth_or_array_layers: 6,
+            is_cubemap: true,
+            ..Default::default()
+        };
+        Texture::new(descriptor, data, name)
+    }
+}
+
+/// Hammersley低差异序列
+fn hammersley(index: u32, count: u32) -> (f32, f32) {
+    (index as f32 / count as f32, index.reverse_bits() as f32 * 2.328_306_4e-10)
+}
+
+/// f32转半精度浮点的位模式(截断舍入，超出范围的值变为无穷大)
+fn f32_to_f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // 无穷大或NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        // 非规格化数
+        if half_exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        return sign | (mantissa >> (14 - half_exponent) as u32) as u16;
+    }
+    sign | ((half_exponent as u16) << 10) | (mantissa >> 13) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    /// 边长size、所有texel为color的Rgba32Float立方体贴图
+    fn uniform_cube(size: u32, color: [f32; 3]) -> Texture {
+        let mut cube = CpuCube::new(size);
+        cube.texels.fill(Vec3::from_array(color));
+        cube.into_texture("uniform".to_string())
+    }
+
+    fn small_settings(specular_size: u32) -> IblSettings {
+        IblSettings { irradiance_size: 4, specular_size, sample_count: 16 }
+    }
+
+    #[test]
+    fn face_directions_round_trip() {
+        for face in 0..6 {
+            for (u, v) in [(0.0, 0.0), (0.5, -0.25), (-0.9, 0.9)] {
+                let (back_face, back_u, back_v) = direction_to_cube_face(cube_face_direction(face, u, v));
+                assert_eq!(back_face, face);
+                assert!((back_u - u).abs() < 1e-5 && (back_v - v).abs() < 1e-5, "面{} ({}, {})", face, u, v);
+            }
+        }
+        assert_eq!(direction_to_cube_face(Vec3::NEG_Y).0, 3);
+    }
+
+    #[test]
+    fn mip_count_and_roughness() {
+        assert_eq!(EnvironmentMap::specular_mip_count(32), 6);
+        assert_eq!(EnvironmentMap::specular_mip_count(128), 8);
+        assert_eq!(EnvironmentMap::specular_mip_count(1), 1);
+        assert_eq!(EnvironmentMap::specular_mip_count(0), 1);
+        assert_eq!(EnvironmentMap::mip_roughness(0, 6), 0.0);
+        assert_eq!(EnvironmentMap::mip_roughness(5, 6), 1.0);
+        assert_eq!(EnvironmentMap::mip_roughness(0, 1), 0.0);
+    }
+
+    #[test]
+    fn uniform_environment_prefilters_to_same_radiance() {
+        let color = [0.5, 1.0, 2.0];
+        let environment = EnvironmentMap::prefilter(&uniform_cube(32, color), &small_settings(32)).unwrap();
+
+        assert_eq!(environment.mip_count(), 6);
+        for (mip, texture) in environment.specular_mips.iter().enumerate() {
+            assert_eq!(texture.descriptor.width, 32 >> mip);
+            assert_eq!(texture.descriptor.depth_or_array_layers, 6);
+            let texel = texture.texel(2, 0, 0).unwrap();
+            assert!((Vec3::new(texel[0], texel[1], texel[2]) - Vec3::from_array(color)).length() < 1e-3, "mip {}", mip);
+        }
+
+        // 均匀辐射亮度的余弦积分除以π后等于原值
+        assert_eq!(environment.irradiance.descriptor.width, 4);
+        for layer in 0..6 {
+            let texel = environment.irradiance.texel(layer, 1, 2).unwrap();
+            assert!((Vec3::new(texel[0], texel[1], texel[2]) - Vec3::from_array(color)).length() < 0.05, "{:?}", texel);
+        }
+    }
+
+    #[test]
+    fn irradiance_follows_bright_face() {
+        // 只有+Y面发光
+        let mut cube = CpuCube::new(8);
+        for y in 0..8 {
+            for x in 0..8 {
+                let index = cube.index(2, x, y);
+                cube.texels[index] = Vec3::ONE;
+            }
+        }
+        let environment = EnvironmentMap::prefilter(&cube.into_texture("sky".to_string()), &small_settings(8)).unwrap();
+        let up = environment.irradiance.sample_cube(Vec3::Y).unwrap()[0];
+        let side = environment.irradiance.sample_cube(Vec3::X).unwrap()[0];
+        let down = environment.irradiance.sample_cube(Vec3::NEG_Y).unwrap()[0];
+        assert!(up > side && side > down, "{} {} {}", up, side, down);
+        assert!(down.abs() < 1e-6);
+    }
+
+    #[test]
+    fn non_cubemap_environment_is_rejected() {
+        assert!(EnvironmentMap::prefilter(&Texture::solid_color(8, 8, [255; 4]), &IblSettings::default()).is_err());
+    }
+
+    #[test]
+    fn half_float_conversion() {
+        assert_eq!(f32_to_f16_bits(1.0), 0x3c00);
+        assert_eq!(f32_to_f16_bits(0.5), 0x3800);
+        assert_eq!(f32_to_f16_bits(-2.0), 0xc000);
+        assert_eq!(f32_to_f16_bits(0.0), 0);
+        assert_eq!(f32_to_f16_bits(65536.0), 0x7c00);
+        assert_eq!(f32_to_f16_bits(f32::NEG_INFINITY), 0xfc00);
+        // 最小的非规格化半精度数
+        assert_eq!(f32_to_f16_bits(2.0f32.powi(-24)), 1);
+        assert_eq!(f32_to_f16_bits(1e-10), 0);
+    }
+
+    #[test]
+    fn upload_creates_cube_textures_with_all_mips() {
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过IBL上传测试: 没有可用的适配器");
+            return;
+        };
+
+        let environment = EnvironmentMap::prefilter(&uniform_cube(32, [1.0; 3]), &small_settings(32)).unwrap();
+        let layout = EnvironmentMap::bind_group_layout(&device);
+        let gpu = environment.upload(&device, &queue, &layout);
+
+        assert_eq!(gpu.specular.depth_or_array_layers(), 6);
+        assert_eq!(gpu.specular.mip_level_count(), 6);
+        assert_eq!(gpu.specular.width(), 32);
+        assert_eq!(gpu.irradiance.depth_or_array_layers(), 6);
+        assert_eq!(gpu.irradiance.mip_level_count(), 1);
+    }
+}
@@ -50,6 +50,63 @@ pub enum TextureSlot {
     Height,
 }
 
+/// 渲染队列，数值小的先绘制
+///
+/// 不透明物体在 `GEOMETRY`，透明物体在 `TRANSPARENT` 及之后；同一区间内可用相邻数值微调顺序。
+pub struct RenderQueue;
+
+impl RenderQueue {
+    pub const BACKGROUND: u32 = 1000;
+    pub const GEOMETRY: u32 = 2000;
+    pub const ALPHA_TEST: u32 = 2450;
+    pub const TRANSPARENT: u32 = 3000;
+    pub const OVERLAY: u32 = 4000;
+
+    /// 该队列是否按透明物体处理(由远到近绘制)
+    pub fn is_transparent(queue: u32) -> bool {
+        (Self::TRANSPARENT..Self::OVERLAY).contains(&queue)
+    }
+}
+
+/// 背面剔除方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum CullMode {
+    Front,
+    #[default]
+    Back,
+    /// 不剔除(双面渲染)
+    None,
+}
+
+impl CullMode {
+    pub fn to_wgpu(self) -> Option<wgpu::Face> {
+        match self {
+            CullMode::Front => Some(wgpu::Face::Front),
+            CullMode::Back => Some(wgpu::Face::Back),
+            CullMode::None => None,
+        }
+    }
+}
+
+/// 正面的顶点环绕方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum FrontFace {
+    /// 逆时针为正面
+    #[default]
+    Ccw,
+    /// 顺时针为正面，用于环绕方向相反的导入网格
+    Cw,
+}
+
+impl FrontFace {
+    pub fn to_wgpu(self) -> wgpu::FrontFace {
+        match self {
+            FrontFace::Ccw => wgpu::FrontFace::Ccw,
+            FrontFace::Cw => wgpu::FrontFace::Cw,
+        }
+    }
+}
+
 /// 材质
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Material {
@@ -57,6 +114,14 @@ pub struct Material {
     pub properties: MaterialProperties,
     pub textures: HashMap<TextureSlot, String>, // 纹理资源路径
     pub shader_name: String,
+    /// 渲染队列，见 `RenderQueue`
+    #[serde(default = "Material::default_render_queue")]
+    pub render_queue: u32,
+    /// 背面剔除，`properties.double_sided` 为true时总是不剔除
+    #[serde(default)]
+    pub cull_mode: CullMode,
+    #[serde(default)]
+    pub front_face: FrontFace,
 }
 
 impl Default for Material {
@@ -66,11 +131,18 @@ impl Default for Material {
             properties: MaterialProperties::default(),
             textures: HashMap::new(),
             shader_name: "标准".to_string(),
+            render_queue: RenderQueue::GEOMETRY,
+            cull_mode: CullMode::Back,
+            front_face: FrontFace::Ccw,
         }
     }
 }
 
 impl Material {
+    fn default_render_queue() -> u32 {
+        RenderQueue::GEOMETRY
+    }
+
     /// 创建新的材质
     pub fn new(name: impl Into<String>) -> Self {
         Self {
@@ -137,9 +209,133 @@ impl Material {
         self.textures.get(&slot)
     }
 
+    /// 设置渲染队列
+    pub fn with_render_queue(mut self, render_queue: u32) -> Self {
+        self.render_queue = render_queue;
+        self
+    }
+
+    /// 是否在透明队列中绘制
+    pub fn is_transparent(&self) -> bool {
+        RenderQueue::is_transparent(self.render_queue)
+    }
+
+    /// 设置背面剔除方式
+    pub fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    /// 设置正面的环绕方向
+    pub fn with_front_face(mut self, front_face: FrontFace) -> Self {
+        self.front_face = front_face;
+        self
+    }
+
+    /// 设置双面渲染(植被、薄片等)
+    pub fn with_double_sided(mut self, double_sided: bool) -> Self {
+        self.properties.double_sided = double_sided;
+        self.cull_mode = if double_sided { CullMode::None } else { CullMode::Back };
+        self
+    }
+
+    /// 是否双面渲染
+    pub fn is_double_sided(&self) -> bool {
+        self.properties.double_sided || self.cull_mode == CullMode::None
+    }
+
+    /// 实际生效的剔除方式
+    pub fn effective_cull_mode(&self) -> CullMode {
+        if self.properties.double_sided {
+            CullMode::None
+        } else {
+            self.cull_mode
+        }
+    }
+
+    /// 渲染管线的图元状态
+    pub fn primitive_state(&self) -> wgpu::PrimitiveState {
+        wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: self.front_face.to_wgpu(),
+            cull_mode: self.effective_cull_mode().to_wgpu(),
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        }
+    }
+
     /// 设置着色器
     pub fn with_shader(mut self, shader_name: impl Into<String>) -> Self {
         self.shader_name = shader_name.into();
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transparent_range_excludes_overlay() {
+        assert!(!RenderQueue::is_transparent(RenderQueue::GEOMETRY));
+        assert!(!RenderQueue::is_transparent(RenderQueue::ALPHA_TEST));
+        assert!(!RenderQueue::is_transparent(RenderQueue::TRANSPARENT - 1));
+        assert!(RenderQueue::is_transparent(RenderQueue::TRANSPARENT));
+        assert!(RenderQueue::is_transparent(RenderQueue::OVERLAY - 1));
+        assert!(!RenderQueue::is_transparent(RenderQueue::OVERLAY));
+
+        assert!(!Material::new("墙").is_transparent());
+        assert!(Material::new("玻璃").with_render_queue(RenderQueue::TRANSPARENT + 10).is_transparent());
+    }
+
+    #[test]
+    fn missing_render_queue_deserializes_as_geometry() {
+        let material = Material::new("旧材质").with_render_queue(RenderQueue::OVERLAY);
+        let mut value = serde_json::to_value(&material).unwrap();
+        value.as_object_mut().unwrap().remove("render_queue");
+
+        let loaded: Material = serde_json::from_value(value).unwrap();
+        assert_eq!(loaded.render_queue, RenderQueue::GEOMETRY);
+    }
+
+    #[test]
+    fn default_material_culls_back_faces() {
+        let primitive = Material::default().primitive_state();
+        assert_eq!(primitive.cull_mode, Some(wgpu::Face::Back));
+        assert_eq!(primitive.front_face, wgpu::FrontFace::Ccw);
+        assert_eq!(primitive.topology, wgpu::PrimitiveTopology::TriangleList);
+    }
+
+    #[test]
+    fn double_sided_material_disables_culling() {
+        let foliage = Material::new("树叶").with_double_sided(true);
+        assert!(foliage.is_double_sided());
+        assert_eq!(foliage.primitive_state().cull_mode, None);
+
+        // 直接设置属性的旧材质同样不剔除
+        let mut legacy = Material::new("旧树叶").with_cull_mode(CullMode::Front);
+        legacy.properties.double_sided = true;
+        assert_eq!(legacy.effective_cull_mode(), CullMode::None);
+        assert!(crate::render::ShaderKeywordSet::from_material(&legacy).contains(crate::render::ShaderKeyword::DoubleSided));
+
+        let solid = foliage.with_double_sided(false);
+        assert_eq!(solid.primitive_state().cull_mode, Some(wgpu::Face::Back));
+    }
+
+    #[test]
+    fn winding_and_cull_mode_map_to_pipeline() {
+        let material = Material::new("导入网格").with_front_face(FrontFace::Cw).with_cull_mode(CullMode::Front);
+        let primitive = material.primitive_state();
+        assert_eq!(primitive.front_face, wgpu::FrontFace::Cw);
+        assert_eq!(primitive.cull_mode, Some(wgpu::Face::Front));
+
+        let mut value = serde_json::to_value(&material).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("cull_mode");
+        object.remove("front_face");
+        let loaded: Material = serde_json::from_value(value).unwrap();
+        assert_eq!((loaded.cull_mode, loaded.front_face), (CullMode::Back, FrontFace::Ccw));
+    }
+}
@@ -1,7 +1,11 @@
 //! 网格系统
 
+use crate::math::{BoundingSphere, AABB};
+use crate::render::Vertex;
 use glam::{Vec2, Vec3};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
 /// 顶点结构
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -10,8 +14,17 @@ pub struct MeshVertex {
     pub normal: Vec3,
     pub tex_coords: Vec2,
     pub color: Vec3,
+    /// 影响该顶点的骨骼索引
+    #[serde(default)]
+    pub joint_indices: [u16; MAX_JOINT_INFLUENCES],
+    /// 对应骨骼的权重，全为0表示未蒙皮
+    #[serde(default)]
+    pub joint_weights: [f32; MAX_JOINT_INFLUENCES],
 }
 
+/// 每个顶点最多受影响的骨骼数
+pub const MAX_JOINT_INFLUENCES: usize = 4;
+
 impl Default for MeshVertex {
     fn default() -> Self {
         Self {
@@ -19,6 +32,67 @@ impl Default for MeshVertex {
             normal: Vec3::Y,
             tex_coords: Vec2::ZERO,
             color: Vec3::ONE,
+            joint_indices: [0; MAX_JOINT_INFLUENCES],
+            joint_weights: [0.0; MAX_JOINT_INFLUENCES],
+        }
+    }
+}
+
+impl MeshVertex {
+    /// 是否带有蒙皮权重
+    pub fn is_skinned(&self) -> bool {
+        self.joint_weights.iter().any(|&weight| weight > 0.0)
+    }
+
+    /// 设置骨骼影响，权重归一化为总和1
+    pub fn set_joints(&mut self, indices: [u16; MAX_JOINT_INFLUENCES], weights: [f32; MAX_JOINT_INFLUENCES]) {
+        let total: f32 = weights.iter().map(|weight| weight.max(0.0)).sum();
+        self.joint_indices = indices;
+        self.joint_weights = if total > 0.0 {
+            weights.map(|weight| weight.max(0.0) / total)
+        } else {
+            [0.0; MAX_JOINT_INFLUENCES]
+        };
+    }
+}
+
+/// 网格局部空间的包围体
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MeshBounds {
+    pub aabb: AABB,
+    pub sphere: BoundingSphere,
+}
+
+impl MeshBounds {
+    /// 由顶点位置计算，包围球以AABB中心为球心、取最远顶点距离为半径
+    pub fn from_vertices(vertices: &[MeshVertex]) -> Self {
+        let Some(first) = vertices.first() else {
+            return Self::default();
+        };
+
+        let mut aabb = AABB::new(first.position, first.position);
+        for vertex in &vertices[1..] {
+            aabb.expand_to_include(vertex.position);
+        }
+
+        let center = aabb.center();
+        let radius_squared = vertices
+            .iter()
+            .map(|vertex| vertex.position.distance_squared(center))
+            .fold(0.0, f32::max);
+
+        Self {
+            aabb,
+            sphere: BoundingSphere::new(center, radius_squared.sqrt()),
+        }
+    }
+}
+
+impl Default for MeshBounds {
+    fn default() -> Self {
+        Self {
+            aabb: AABB::new(Vec3::ZERO, Vec3::ZERO),
+            sphere: BoundingSphere::default(),
         }
     }
 }
@@ -26,42 +100,163 @@ impl Default for MeshVertex {
 /// 网格数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mesh {
+    /// 直接修改顶点位置后需调用 `invalidate_bounds`，或改用 `vertices_mut`
     pub vertices: Vec<MeshVertex>,
     pub indices: Vec<u32>,
     pub name: String,
+    /// 缓存的局部包围体，首次查询或重新计算时生成
+    #[serde(skip)]
+    bounds: OnceLock<MeshBounds>,
 }
 
 impl Mesh {
+    /// 导入时默认的顶点焊接距离
+    pub const DEFAULT_WELD_EPSILON: f32 = 1e-5;
+    /// 导入时默认的平滑角度(60°，弧度)
+    pub const DEFAULT_SMOOTHING_ANGLE: f32 = std::f32::consts::FRAC_PI_3;
+
     /// 创建新的网格
     pub fn new(name: impl Into<String>) -> Self {
+        Self::from_geometry(name, Vec::new(), Vec::new())
+    }
+
+    /// 由顶点和索引创建网格，同时计算包围体
+    pub fn from_geometry(name: impl Into<String>, vertices: Vec<MeshVertex>, indices: Vec<u32>) -> Self {
+        let bounds = OnceLock::from(MeshBounds::from_vertices(&vertices));
         Self {
-            vertices: Vec::new(),
-            indices: Vec::new(),
+            vertices,
+            indices,
             name: name.into(),
+            bounds,
         }
     }
 
-    /// 创建立方体网格
-    pub fn cube() -> Self {
-        let vertices = vec![
-            // 前面
-            MeshVertex { position: Vec3::new(-0.5, -0.5, 0.5), normal: Vec3::Z, tex_coords: Vec2::new(0.0, 1.0), color: Vec3::ONE },
-            MeshVertex { position: Vec3::new(0.5, -0.5, 0.5), normal: Vec3::Z, tex_coords: Vec2::new(1.0, 1.0), color: Vec3::ONE },
-            MeshVertex { position: Vec3::new(0.5, 0.5, 0.5), normal: Vec3::Z, tex_coords: Vec2::new(1.0, 0.0), color: Vec3::ONE },
-            MeshVertex { position: Vec3::new(-0.5, 0.5, 0.5), normal: Vec3::Z, tex_coords: Vec2::new(0.0, 0.0), color: Vec3::ONE },
-            // 其他面...
-        ];
+    /// 局部空间的包围盒和包围球
+    pub fn local_bounds(&self) -> MeshBounds {
+        *self.bounds.get_or_init(|| MeshBounds::from_vertices(&self.vertices))
+    }
 
-        let indices = vec![
-            0, 1, 2, 2, 3, 0, // 前面
-            // 其他面的索引...
+    /// 局部空间的AABB
+    pub fn local_aabb(&self) -> AABB {
+        self.local_bounds().aabb
+    }
+
+    /// 局部空间的包围球
+    pub fn local_bounding_sphere(&self) -> BoundingSphere {
+        self.local_bounds().sphere
+    }
+
+    /// 使缓存的包围体失效，下次查询时重新计算
+    pub fn invalidate_bounds(&mut self) {
+        self.bounds.take();
+    }
+
+    /// 立即重新计算包围体
+    pub fn recalculate_bounds(&mut self) -> MeshBounds {
+        let bounds = MeshBounds::from_vertices(&self.vertices);
+        self.bounds = OnceLock::from(bounds);
+        bounds
+    }
+
+    /// 可修改的顶点列表，包围体随之失效
+    pub fn vertices_mut(&mut self) -> &mut Vec<MeshVertex> {
+        self.invalidate_bounds();
+        &mut self.vertices
+    }
+
+    /// 是否为蒙皮网格(任一顶点带骨骼权重)
+    pub fn is_skinned(&self) -> bool {
+        self.vertices.iter().any(MeshVertex::is_skinned)
+    }
+
+    /// 引用的最大骨骼索引，未蒙皮时为None
+    pub fn max_joint_index(&self) -> Option<u16> {
+        self.vertices
+            .iter()
+            .filter(|vertex| vertex.is_skinned())
+            .flat_map(|vertex| {
+                vertex.joint_indices.iter().zip(&vertex.joint_weights)
+                    .filter(|(_, &weight)| weight > 0.0)
+                    .map(|(&index, _)| index)
+            })
+            .max()
+    }
+
+    /// 按索引展开的三角形顶点位置(每3个一个三角形)，越界索引的三角形被跳过
+    pub fn triangle_positions(&self) -> Vec<Vec3> {
+        self.indices
+            .chunks_exact(3)
+            .filter_map(|triangle| {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|index| self.vertices.get(index as usize));
+                Some([a?.position, b?.position, c?.position])
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// 替换顶点列表并重新计算包围体
+    pub fn set_vertices(&mut self, vertices: Vec<MeshVertex>) {
+        self.vertices = vertices;
+        self.recalculate_bounds();
+    }
+
+    /// 创建立方体网格(边长1，每个面独立顶点以保持硬边法线)
+    pub fn cube() -> Self {
+        // (法线, 面内u轴, 面内v轴)，u × v = 法线，保证逆时针朝外
+        let faces = [
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+            (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
         ];
 
-        Self {
-            vertices,
-            indices,
-            name: "立方体".to_string(),
+        let mut vertices = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+        for (normal, u, v) in faces {
+            let base = vertices.len() as u32;
+            for (du, dv, tex_coords) in [
+                (-0.5, -0.5, Vec2::new(0.0, 1.0)),
+                (0.5, -0.5, Vec2::new(1.0, 1.0)),
+                (0.5, 0.5, Vec2::new(1.0, 0.0)),
+                (-0.5, 0.5, Vec2::new(0.0, 0.0)),
+            ] {
+                vertices.push(MeshVertex {
+                    position: normal * 0.5 + u * du + v * dv,
+                    normal,
+                    tex_coords,
+                    color: Vec3::ONE,
+                    ..Default::default()
+                });
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base + 2, base + 3, base]);
         }
+
+        Self::from_geometry("立方体", vertices, indices)
+    }
+
+    /// 创建XZ平面上朝上的正方形平面网格
+    pub fn plane(size: f32) -> Self {
+        let half = size * 0.5;
+        let vertex = |x: f32, z: f32, tex_coords: Vec2| MeshVertex {
+            position: Vec3::new(x, 0.0, z),
+            normal: Vec3::Y,
+            tex_coords,
+            color: Vec3::ONE,
+            ..Default::default()
+        };
+
+        Self::from_geometry(
+            "平面",
+            vec![
+                vertex(-half, half, Vec2::new(0.0, 1.0)),
+                vertex(half, half, Vec2::new(1.0, 1.0)),
+                vertex(half, -half, Vec2::new(1.0, 0.0)),
+                vertex(-half, -half, Vec2::new(0.0, 0.0)),
+            ],
+            vec![0, 1, 2, 2, 3, 0],
+        )
     }
 
     /// 创建球体网格
@@ -84,6 +279,7 @@ impl Mesh {
                     normal: Vec3::new(x, y, z).normalize(),
                     tex_coords: Vec2::new(j as f32 / segments as f32, i as f32 / segments as f32),
                     color: Vec3::ONE,
+                    ..Default::default()
                 });
             }
         }
@@ -99,11 +295,7 @@ impl Mesh {
             }
         }
 
-        Self {
-            vertices,
-            indices,
-            name: "球体".to_string(),
-        }
+        Self::from_geometry("球体", vertices, indices)
     }
 
     /// 计算法线
@@ -134,4 +326,353 @@ impl Mesh {
             vertex.normal = vertex.normal.normalize();
         }
     }
+
+    /// 焊接重合顶点
+    ///
+    /// 位置距离不超过`position_epsilon`且UV、颜色、骨骼权重相同的顶点合并为一个，保留UV接缝。
+    /// 合并后的顶点沿用第一个顶点的法线，通常随后调用`smooth_normals`重新生成。
+    /// 焊接后退化的三角形会被移除，返回减少的顶点数。
+    pub fn weld_vertices(&mut self, position_epsilon: f32) -> usize {
+        let epsilon = position_epsilon.max(f32::EPSILON);
+        let cell_of = |position: Vec3| {
+            let cell = (position / epsilon).floor();
+            (cell.x as i64, cell.y as i64, cell.z as i64)
+        };
+
+        let mut grid: HashMap<(i64, i64, i64), Vec<u32>> = HashMap::new();
+        let mut welded: Vec<MeshVertex> = Vec::with_capacity(self.vertices.len());
+        let mut remap = Vec::with_capacity(self.vertices.len());
+
+        for vertex in &self.vertices {
+            let (cx, cy, cz) = cell_of(vertex.position);
+            let mut found = None;
+            'search: for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let Some(candidates) = grid.get(&(cx + dx, cy + dy, cz + dz)) else {
+                            continue;
+                        };
+                        for &candidate in candidates {
+                            let other = &welded[candidate as usize];
+                            if other.position.distance(vertex.position) <= epsilon
+                                && other.tex_coords.abs_diff_eq(vertex.tex_coords, 1e-6)
+                                && other.color.abs_diff_eq(vertex.color, 1e-6)
+                                && other.joint_indices == vertex.joint_indices
+                                && other.joint_weights == vertex.joint_weights
+                            {
+                                found = Some(candidate);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+
+            let index = found.unwrap_or_else(|| {
+                let index = welded.len() as u32;
+                welded.push(*vertex);
+                grid.entry((cx, cy, cz)).or_default().push(index);
+                index
+            });
+            remap.push(index);
+        }
+
+        let removed = self.vertices.len() - welded.len();
+        self.set_vertices(welded);
+
+        let mut indices = Vec::with_capacity(self.indices.len());
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| remap[triangle[i] as usize]);
+            if a != b && b != c && a != c {
+                indices.extend_from_slice(&[a, b, c]);
+            }
+        }
+        self.indices = indices;
+
+        removed
+    }
+
+    /// 按折痕角度平滑法线
+    ///
+    /// 每个三角形角点的法线取共享该位置、且面法线夹角小于`angle_threshold`(弧度)的
+    /// 所有三角形的面积加权平均，超过阈值的边保持硬边。
+    /// 同一顶点在不同面上需要不同法线时会被拆分。
+    pub fn smooth_normals(&mut self, angle_threshold: f32) {
+        let cos_threshold = angle_threshold.cos();
+        let triangle_count = self.indices.len() / 3;
+
+        // 面积加权的面法线与单位面法线
+        let mut weighted_normals = Vec::with_capacity(triangle_count);
+        let mut unit_normals = Vec::with_capacity(triangle_count);
+        for triangle in self.indices.chunks_exact(3) {
+            let [v0, v1, v2] = [0, 1, 2].map(|i| self.vertices[triangle[i] as usize].position);
+            let normal = (v1 - v0).cross(v2 - v0);
+            weighted_normals.push(normal);
+            unit_normals.push(normal.normalize_or_zero());
+        }
+
+        // 按位置把三角形分组，不依赖顶点是否已焊接
+        let position_key = |position: Vec3| position.to_array().map(f32::to_bits);
+        let mut faces_at_position: HashMap<[u32; 3], Vec<usize>> = HashMap::new();
+        for (corner, &index) in self.indices.iter().enumerate().take(triangle_count * 3) {
+            let key = position_key(self.vertices[index as usize].position);
+            let faces = faces_at_position.entry(key).or_default();
+            if faces.last() != Some(&(corner / 3)) {
+                faces.push(corner / 3);
+            }
+        }
+
+        let mut vertices = Vec::with_capacity(self.vertices.len());
+        let mut split: HashMap<(u32, [u32; 3]), u32> = HashMap::new();
+        let mut indices = Vec::with_capacity(self.indices.len());
+
+        for (corner, &index) in self.indices.iter().enumerate().take(triangle_count * 3) {
+            let face = corner / 3;
+            let vertex = self.vertices[index as usize];
+            let face_normal = unit_normals[face];
+
+            let mut normal = Vec3::ZERO;
+            for &other in &faces_at_position[&position_key(vertex.position)] {
+                if other == face || face_normal.dot(unit_normals[other]) >= cos_threshold {
+                    normal += weighted_normals[other];
+                }
+            }
+            let normal = if normal.length_squared() > 0.0 {
+                normal.normalize()
+            } else {
+                vertex.normal
+            };
+
+            let split_vertex = MeshVertex { normal, ..vertex };
+            let new_index = *split.entry((index, position_key(normal))).or_insert_with(|| {
+                vertices.push(split_vertex);
+                (vertices.len() - 1) as u32
+            });
+            indices.push(new_index);
+        }
+
+        self.vertices = vertices;
+        self.indices = indices;
+    }
+}
+
+/// 上传到GPU的网格，顶点格式为 `Vertex`
+pub struct GpuMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+    pub vertex_count: u32,
+    /// 局部空间包围盒，用于遮挡剔除的候选测试
+    pub bounds: AABB,
+    /// 局部空间三角形顶点位置，作为遮挡体光栅化到深度预通道
+    pub occluder_triangles: Vec<Vec3>,
+}
+
+impl GpuMesh {
+    /// 上传网格的顶点和索引
+    pub fn upload(device: &wgpu::Device, mesh: &Mesh) -> Self {
+        use wgpu::util::DeviceExt;
+
+        let vertices: Vec<Vertex> = mesh
+            .vertices
+            .iter()
+            .map(|vertex| Vertex {
+                position: vertex.position.to_array(),
+                color: vertex.color.to_array(),
+                tex_coords: vertex.tex_coords.to_array(),
+            })
+            .collect();
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} 顶点缓冲", mesh.name)),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{} 索引缓冲", mesh.name)),
+            contents: bytemuck::cast_slice(&mesh.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: mesh.indices.len() as u32,
+            vertex_count: vertices.len() as u32,
+            bounds: mesh.local_aabb(),
+            occluder_triangles: mesh.triangle_positions(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(position: Vec3) -> MeshVertex {
+        MeshVertex { position, ..Default::default() }
+    }
+
+    /// 两个三角形组成的四边形，每个三角形使用独立顶点
+    fn split_quad() -> Mesh {
+        let corners = [Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 0.0), Vec3::Y];
+        let vertices = [0, 1, 2, 0, 2, 3].map(|i| vertex(corners[i])).to_vec();
+        Mesh::from_geometry("quad", vertices, (0..6).collect())
+    }
+
+    #[test]
+    fn triangle_positions_follow_indices() {
+        let mut mesh = split_quad();
+        mesh.weld_vertices(Mesh::DEFAULT_WELD_EPSILON);
+        assert_eq!(mesh.triangle_positions(), vec![
+            Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 0.0),
+            Vec3::ZERO, Vec3::new(1.0, 1.0, 0.0), Vec3::Y,
+        ]);
+    }
+
+    #[test]
+    fn weld_merges_coincident_vertices() {
+        let mut mesh = split_quad();
+        assert_eq!(mesh.weld_vertices(Mesh::DEFAULT_WELD_EPSILON), 2);
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn weld_keeps_uv_seams_and_drops_degenerate_triangles() {
+        let mut mesh = split_quad();
+        mesh.vertices[3].tex_coords = Vec2::ONE;
+        mesh.weld_vertices(Mesh::DEFAULT_WELD_EPSILON);
+        assert_eq!(mesh.vertices.len(), 5);
+
+        // 距离阈值大于三角形尺寸时所有顶点合并，三角形全部退化
+        let mut mesh = split_quad();
+        mesh.weld_vertices(2.0);
+        assert_eq!(mesh.vertices.len(), 1);
+        assert!(mesh.indices.is_empty());
+    }
+
+    #[test]
+    fn small_threshold_keeps_cube_edges_hard() {
+        let mut mesh = Mesh::cube();
+        mesh.smooth_normals(30f32.to_radians());
+
+        for vertex in &mesh.vertices {
+            let axis_aligned = vertex.normal.abs().max_element();
+            assert!((axis_aligned - 1.0).abs() < 1e-5, "normal {:?} was smoothed", vertex.normal);
+        }
+    }
+
+    #[test]
+    fn full_threshold_smooths_cube_corners() {
+        let mut mesh = Mesh::cube();
+        mesh.smooth_normals(std::f32::consts::PI);
+
+        for vertex in &mesh.vertices {
+            // 每个角的法线都指向角所在的卦限
+            let outward = vertex.normal * vertex.position.signum();
+            assert!(outward.min_element() > 0.0, "normal {:?} at {:?}", vertex.normal, vertex.position);
+            assert!((vertex.normal.length() - 1.0).abs() < 1e-5);
+        }
+        // 每个角在三个面上的UV不同，拆分后仍是24个顶点
+        assert_eq!(mesh.vertices.len(), 24);
+    }
+
+    /// 每个三角形按绕序算出的法线与顶点法线同向
+    fn assert_outward_winding(mesh: &Mesh) {
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[triangle[i] as usize]);
+            let face_normal = (b.position - a.position).cross(c.position - a.position);
+            assert!(face_normal.dot(a.normal) > 0.0, "{:?}", triangle);
+        }
+    }
+
+    #[test]
+    fn cube_has_hard_edged_outward_faces() {
+        let cube = Mesh::cube();
+        assert_eq!(cube.vertices.len(), 24);
+        assert_eq!(cube.indices.len(), 36);
+        for vertex in &cube.vertices {
+            assert_eq!(vertex.position.abs().max_element(), 0.5);
+            assert_eq!(vertex.position.dot(vertex.normal), 0.5);
+        }
+        assert_outward_winding(&cube);
+    }
+
+    #[test]
+    fn plane_faces_up() {
+        let plane = Mesh::plane(4.0);
+        assert_eq!(plane.vertices.len(), 4);
+        assert!(plane.vertices.iter().all(|v| v.position.y == 0.0 && v.position.x.abs() == 2.0 && v.normal == Vec3::Y));
+        assert_outward_winding(&plane);
+    }
+
+    #[test]
+    fn cube_reports_expected_bounds() {
+        let bounds = Mesh::cube().local_bounds();
+        assert_eq!(bounds.aabb.min, Vec3::splat(-0.5));
+        assert_eq!(bounds.aabb.max, Vec3::splat(0.5));
+        assert_eq!(bounds.sphere.center, Vec3::ZERO);
+        assert!((bounds.sphere.radius - 0.75f32.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sphere_bounds_match_radius() {
+        let sphere = Mesh::sphere(2.0, 16);
+        assert!((sphere.local_bounding_sphere().radius - 2.0).abs() < 1e-4);
+        assert!(sphere.local_aabb().max.abs_diff_eq(Vec3::splat(2.0), 1e-4));
+    }
+
+    #[test]
+    fn editing_vertices_invalidates_bounds() {
+        let mut mesh = split_quad();
+        assert_eq!(mesh.local_aabb().max, Vec3::new(1.0, 1.0, 0.0));
+
+        mesh.vertices_mut()[0].position = Vec3::new(-3.0, 0.0, 2.0);
+        let aabb = mesh.local_aabb();
+        assert_eq!(aabb.min, Vec3::new(-3.0, 0.0, 0.0));
+        assert_eq!(aabb.max, Vec3::new(1.0, 1.0, 2.0));
+
+        // 直接修改字段后需手动失效
+        mesh.vertices[1].position = Vec3::new(5.0, 0.0, 0.0);
+        assert_eq!(mesh.local_aabb().max.x, 1.0);
+        mesh.invalidate_bounds();
+        assert_eq!(mesh.local_aabb().max.x, 5.0);
+    }
+
+    #[test]
+    fn empty_mesh_has_default_bounds() {
+        assert_eq!(Mesh::new("空").local_bounds(), MeshBounds::default());
+    }
+
+    #[test]
+    fn joint_weights_are_normalized() {
+        let mut vertex = MeshVertex::default();
+        assert!(!vertex.is_skinned());
+        vertex.set_joints([2, 5, 0, 0], [1.0, 3.0, -1.0, 0.0]);
+        assert_eq!(vertex.joint_weights, [0.25, 0.75, 0.0, 0.0]);
+        assert!(vertex.is_skinned());
+
+        vertex.set_joints([1, 0, 0, 0], [0.0; MAX_JOINT_INFLUENCES]);
+        assert!(!vertex.is_skinned());
+    }
+
+    #[test]
+    fn skinned_mesh_reports_max_joint_and_welds_by_joints() {
+        let mut mesh = split_quad();
+        assert!(!mesh.is_skinned());
+        assert_eq!(mesh.max_joint_index(), None);
+
+        // 第二个三角形的顶点绑定到其他骨骼，不与第一个三角形焊接
+        for vertex in &mut mesh.vertices[3..] {
+            vertex.set_joints([7, 0, 0, 0], [1.0, 0.0, 0.0, 0.0]);
+        }
+        // 权重为0的骨骼索引不计入
+        mesh.vertices[0].set_joints([1, 12, 0, 0], [1.0, 0.0, 0.0, 0.0]);
+        assert!(mesh.is_skinned());
+        assert_eq!(mesh.max_joint_index(), Some(7));
+
+        mesh.weld_vertices(Mesh::DEFAULT_WELD_EPSILON);
+        assert_eq!(mesh.vertices.len(), 6);
+    }
 }
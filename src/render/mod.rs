@@ -1,22 +1,120 @@
 //! 渲染系统模块
 
 pub mod render_system;
+pub mod render_stats;
+pub mod render_targets;
+pub mod frame_resources;
 pub mod shader;
+pub mod shader_variant;
+pub mod shader_include;
 pub mod mesh;
+pub mod skinning;
 pub mod texture;
+pub mod gpu_asset_cache;
+pub mod ibl;
 pub mod material;
+pub mod draw_list;
 pub mod camera;
 pub mod shadows;
+pub mod shadow_atlas;
 pub mod post_processing;
+pub mod skybox;
+pub mod fog;
+pub mod occlusion;
+pub mod render_graph;
+pub mod bloom;
+pub mod depth_of_field;
+pub mod taa;
+pub mod fxaa;
+pub mod oit;
+pub mod thumbnail;
+pub mod sprite;
+pub mod tilemap;
+pub mod debug_draw;
 
 pub use render_system::*;
+pub use render_stats::*;
+pub use render_targets::*;
+pub use frame_resources::*;
 pub use shader::*;
+pub use shader_variant::*;
+pub use shader_include::*;
 pub use mesh::*;
+pub use skinning::*;
 pub use texture::*;
+pub use gpu_asset_cache::*;
+pub use ibl::*;
 pub use material::*;
+pub use draw_list::*;
 pub use camera::*;
 pub use shadows::*;
+pub use shadow_atlas::*;
 pub use post_processing::*;
+pub use skybox::*;
+pub use fog::*;
+pub use occlusion::*;
+pub use render_graph::*;
+pub use bloom::*;
+pub use depth_of_field::*;
+pub use taa::*;
+pub use fxaa::*;
+pub use oit::*;
+pub use thumbnail::*;
+pub use sprite::*;
+pub use tilemap::*;
+pub use debug_draw::*;
 
 // 重新导出组件中的Light相关类型，以便向后兼容
-pub use crate::ecs::{Light, LightType};
\ No newline at end of file
+pub use crate::ecs::{Light, LightType};
+/// 请求无窗口的适配器、设备和队列，优先硬件适配器，没有时退回软件适配器
+pub(crate) async fn request_headless_device(
+    label: &str,
+    required_features: wgpu::Features,
+    required_limits: wgpu::Limits,
+) -> crate::EngineResult<(wgpu::Adapter, wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    });
+
+    let mut adapter = None;
+    for force_fallback_adapter in [false, true] {
+        adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::LowPower,
+                compatible_surface: None,
+                force_fallback_adapter,
+            })
+            .await;
+        if adapter.is_some() {
+            break;
+        }
+    }
+    let adapter = adapter.ok_or_else(|| crate::EngineError::RenderError(format!("未找到可用于{}的适配器", label)))?;
+
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some(label),
+                required_features,
+                required_limits,
+            },
+            None,
+        )
+        .await
+        .map_err(|e| crate::EngineError::RenderError(format!("请求{}失败: {}", label, e)))?;
+    Ok((adapter, device, queue))
+}
+
+/// 测试用的无窗口适配器、设备和队列
+///
+/// `requirements` 为需要的特性和限制，None表示无特性、downlevel默认限制；没有任何适配器或
+/// 适配器不满足要求时返回None，调用的测试应跳过。
+#[cfg(test)]
+pub(crate) fn headless_device(
+    requirements: Option<(wgpu::Features, wgpu::Limits)>,
+) -> Option<(wgpu::Adapter, wgpu::Device, wgpu::Queue)> {
+    let (required_features, required_limits) =
+        requirements.unwrap_or_else(|| (wgpu::Features::empty(), wgpu::Limits::downlevel_defaults()));
+    pollster::block_on(request_headless_device("测试设备", required_features, required_limits)).ok()
+}
@@ -0,0 +1,440 @@
+//! 遮挡剔除系统
+//!
+//! 先将不透明遮挡体的三角形深度光栅化到低分辨率深度缓冲(深度预通道)，
+//! 再构建层级Z缓冲(HiZ)，用包围盒的最近深度与HiZ中的最远深度比较来剔除被遮挡物体。
+
+use crate::math::{Mat4, Vec3, Vec4, AABB};
+
+/// 遮挡剔除配置
+#[derive(Debug, Clone)]
+pub struct OcclusionConfig {
+    pub enabled: bool,
+    /// 深度预通道缓冲宽度
+    pub width: u32,
+    /// 深度预通道缓冲高度
+    pub height: u32,
+}
+
+impl Default for OcclusionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            width: 256,
+            height: 128,
+        }
+    }
+}
+
+/// 遮挡剔除统计
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OcclusionStats {
+    pub occluders: u32,
+    pub occluder_triangles: u32,
+    pub tested: u32,
+    pub culled: u32,
+}
+
+/// 层级Z缓冲，第0层为深度预通道结果，之后每层存储2x2区域的最远深度
+#[derive(Debug, Clone)]
+pub struct HiZBuffer {
+    levels: Vec<Vec<f32>>,
+    sizes: Vec<(u32, u32)>,
+}
+
+impl HiZBuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        Self {
+            levels: vec![vec![1.0; (width * height) as usize]],
+            sizes: vec![(width, height)],
+        }
+    }
+
+    /// 第0层尺寸
+    pub fn size(&self) -> (u32, u32) {
+        self.sizes[0]
+    }
+
+    /// 层级数量
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// 清空为最远深度
+    pub fn clear(&mut self) {
+        self.levels.truncate(1);
+        self.sizes.truncate(1);
+        self.levels[0].fill(1.0);
+    }
+
+    /// 读取指定层级的深度
+    pub fn depth(&self, level: usize, x: u32, y: u32) -> f32 {
+        let (width, height) = self.sizes[level];
+        let x = x.min(width - 1);
+        let y = y.min(height - 1);
+        self.levels[level][(y * width + x) as usize]
+    }
+
+    /// 写入第0层深度(保留最近值)
+    fn write_min(&mut self, x: u32, y: u32, depth: f32) {
+        let width = self.sizes[0].0;
+        let texel = &mut self.levels[0][(y * width + x) as usize];
+        if depth < *texel {
+            *texel = depth;
+        }
+    }
+
+    /// 从第0层构建降采样层级
+    pub fn build_mips(&mut self) {
+        self.levels.truncate(1);
+        self.sizes.truncate(1);
+
+        loop {
+            let level = self.levels.len() - 1;
+            let (width, height) = self.sizes[level];
+            if width == 1 && height == 1 {
+                break;
+            }
+
+            let next_width = (width + 1) / 2;
+            let next_height = (height + 1) / 2;
+            let mut next = vec![0.0; (next_width * next_height) as usize];
+
+            for y in 0..next_height {
+                for x in 0..next_width {
+                    let d00 = self.depth(level, x * 2, y * 2);
+                    let d10 = self.depth(level, x * 2 + 1, y * 2);
+                    let d01 = self.depth(level, x * 2, y * 2 + 1);
+                    let d11 = self.depth(level, x * 2 + 1, y * 2 + 1);
+                    next[(y * next_width + x) as usize] = d00.max(d10).max(d01).max(d11);
+                }
+            }
+
+            self.levels.push(next);
+            self.sizes.push((next_width, next_height));
+        }
+    }
+}
+
+/// 屏幕空间投影结果
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    depth: f32,
+}
+
+/// 遮挡剔除器
+pub struct OcclusionCuller {
+    pub config: OcclusionConfig,
+    view_projection: Mat4,
+    hiz: HiZBuffer,
+    hiz_ready: bool,
+    stats: OcclusionStats,
+}
+
+impl OcclusionCuller {
+    pub fn new(config: OcclusionConfig) -> Self {
+        let hiz = HiZBuffer::new(config.width, config.height);
+        Self {
+            config,
+            view_projection: Mat4::IDENTITY,
+            hiz,
+            hiz_ready: false,
+            stats: OcclusionStats::default(),
+        }
+    }
+
+    /// 开始新一帧的深度预通道
+    pub fn begin_frame(&mut self, view_projection: Mat4) {
+        let (width, height) = self.hiz.size();
+        if width != self.config.width.max(1) || height != self.config.height.max(1) {
+            self.hiz = HiZBuffer::new(self.config.width, self.config.height);
+        } else {
+            self.hiz.clear();
+        }
+        self.view_projection = view_projection;
+        self.hiz_ready = false;
+        self.stats = OcclusionStats::default();
+    }
+
+    /// 将世界空间包围盒作为实心遮挡体写入深度
+    ///
+    /// 包围盒会填满网格的空洞和凹处，只适合墙体等填满包围盒的物体，一般网格使用 `add_occluder_triangles`。
+    pub fn add_occluder(&mut self, bounds: &AABB) {
+        let corners = Self::aabb_corners(bounds);
+        // 立方体12个三角形
+        const BOX_INDICES: [usize; 36] = [
+            0, 1, 3, 0, 3, 2, // -X
+            4, 6, 7, 4, 7, 5, // +X
+            0, 4, 5, 0, 5, 1, // -Y
+            2, 3, 7, 2, 7, 6, // +Y
+            0, 2, 6, 0, 6, 4, // -Z
+            1, 5, 7, 1, 7, 3, // +Z
+        ];
+
+        let mut triangles = [Vec3::ZERO; 36];
+        for (i, &index) in BOX_INDICES.iter().enumerate() {
+            triangles[i] = corners[index];
+        }
+        self.add_occluder_triangles(&triangles);
+    }
+
+    /// 将世界空间三角形列表(每3个顶点一个三角形)写入深度
+    pub fn add_occluder_triangles(&mut self, vertices: &[Vec3]) {
+        self.stats.occluders += 1;
+        for triangle in vertices.chunks_exact(3) {
+            self.rasterize_triangle(triangle[0], triangle[1], triangle[2]);
+        }
+        self.hiz_ready = false;
+    }
+
+    /// 构建HiZ层级，在所有遮挡体写入后调用
+    pub fn build_hiz(&mut self) {
+        self.hiz.build_mips();
+        self.hiz_ready = true;
+    }
+
+    /// 检查世界空间包围盒是否被遮挡
+    pub fn is_occluded(&mut self, bounds: &AABB) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+        if !self.hiz_ready {
+            self.build_hiz();
+        }
+
+        self.stats.tested += 1;
+        let occluded = self.test_aabb(bounds);
+        if occluded {
+            self.stats.culled += 1;
+        }
+        occluded
+    }
+
+    /// 对一组包围盒进行剔除，返回每个物体是否可见
+    pub fn cull(&mut self, bounds: &[AABB]) -> Vec<bool> {
+        bounds.iter().map(|aabb| !self.is_occluded(aabb)).collect()
+    }
+
+    /// 完整执行一次深度预通道 + HiZ剔除
+    pub fn run(&mut self, view_projection: Mat4, occluders: &[AABB], candidates: &[AABB]) -> Vec<bool> {
+        self.begin_frame(view_projection);
+        for occluder in occluders {
+            self.add_occluder(occluder);
+        }
+        self.build_hiz();
+        self.cull(candidates)
+    }
+
+    /// 获取统计信息
+    pub fn stats(&self) -> OcclusionStats {
+        self.stats
+    }
+
+    /// 获取HiZ缓冲
+    pub fn hiz(&self) -> &HiZBuffer {
+        &self.hiz
+    }
+
+    fn test_aabb(&self, bounds: &AABB) -> bool {
+        let (width, height) = self.hiz.size();
+        let mut min_x = f32::MAX;
+        let mut min_y = f32::MAX;
+        let mut max_x = f32::MIN;
+        let mut max_y = f32::MIN;
+        let mut nearest = f32::MAX;
+
+        for corner in Self::aabb_corners(bounds) {
+            // 跨越相机平面的物体保守地视为可见
+            let Some(vertex) = self.project(corner) else {
+                return false;
+            };
+            min_x = min_x.min(vertex.x);
+            min_y = min_y.min(vertex.y);
+            max_x = max_x.max(vertex.x);
+            max_y = max_y.max(vertex.y);
+            nearest = nearest.min(vertex.depth);
+        }
+
+        // 屏幕外的物体交给视锥剔除处理
+        if max_x < 0.0 || max_y < 0.0 || min_x >= width as f32 || min_y >= height as f32 {
+            return false;
+        }
+        if nearest <= 0.0 {
+            return false;
+        }
+
+        let min_x = min_x.max(0.0) as u32;
+        let min_y = min_y.max(0.0) as u32;
+        let max_x = (max_x.min(width as f32 - 1.0)) as u32;
+        let max_y = (max_y.min(height as f32 - 1.0)) as u32;
+
+        // 选择使包围矩形覆盖约2x2纹素的层级
+        let extent = (max_x - min_x + 1).max(max_y - min_y + 1) as f32;
+        let level = (extent.log2().ceil().max(1.0) as usize - 1).min(self.hiz.level_count() - 1);
+
+        let (x0, y0) = (min_x >> level, min_y >> level);
+        let (x1, y1) = (max_x >> level, max_y >> level);
+        let mut farthest: f32 = 0.0;
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                farthest = farthest.max(self.hiz.depth(level, x, y));
+            }
+        }
+
+        nearest > farthest
+    }
+
+    fn project(&self, point: Vec3) -> Option<ScreenVertex> {
+        let clip: Vec4 = self.view_projection * point.extend(1.0);
+        if clip.w <= 1e-5 {
+            return None;
+        }
+
+        let ndc = clip.truncate() / clip.w;
+        let (width, height) = self.hiz.size();
+        Some(ScreenVertex {
+            x: (ndc.x * 0.5 + 0.5) * width as f32,
+            y: (0.5 - ndc.y * 0.5) * height as f32,
+            depth: ndc.z,
+        })
+    }
+
+    fn rasterize_triangle(&mut self, a: Vec3, b: Vec3, c: Vec3) {
+        // 近平面后的三角形不写入深度(保守处理)
+        let (Some(v0), Some(v1), Some(v2)) = (self.project(a), self.project(b), self.project(c)) else {
+            return;
+        };
+
+        let area = (v1.x - v0.x) * (v2.y - v0.y) - (v2.x - v0.x) * (v1.y - v0.y);
+        if area.abs() < f32::EPSILON {
+            return;
+        }
+        self.stats.occluder_triangles += 1;
+
+        let (width, height) = self.hiz.size();
+        let min_x = v0.x.min(v1.x).min(v2.x).floor().max(0.0) as i64;
+        let min_y = v0.y.min(v1.y).min(v2.y).floor().max(0.0) as i64;
+        let max_x = (v0.x.max(v1.x).max(v2.x).ceil() as i64).min(width as i64 - 1);
+        let max_y = (v0.y.max(v1.y).max(v2.y).ceil() as i64).min(height as i64 - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let px = x as f32 + 0.5;
+                let py = y as f32 + 0.5;
+
+                let w0 = ((v1.x - px) * (v2.y - py) - (v2.x - px) * (v1.y - py)) / area;
+                let w1 = ((v2.x - px) * (v0.y - py) - (v0.x - px) * (v2.y - py)) / area;
+                let w2 = 1.0 - w0 - w1;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let depth = w0 * v0.depth + w1 * v1.depth + w2 * v2.depth;
+                if (0.0..=1.0).contains(&depth) {
+                    self.hiz.write_min(x as u32, y as u32, depth);
+                }
+            }
+        }
+    }
+
+    fn aabb_corners(bounds: &AABB) -> [Vec3; 8] {
+        let (min, max) = (bounds.min, bounds.max);
+        [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(min.x, max.y, max.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(max.x, max.y, max.z),
+        ]
+    }
+}
+
+impl Default for OcclusionCuller {
+    fn default() -> Self {
+        Self::new(OcclusionConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view_projection() -> Mat4 {
+        let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y);
+        let projection = Mat4::perspective_rh(60f32.to_radians(), 2.0, 0.1, 100.0);
+        projection * view
+    }
+
+    fn wall() -> AABB {
+        AABB::from_center_size(Vec3::ZERO, Vec3::new(6.0, 6.0, 0.2))
+    }
+
+    #[test]
+    fn hiz_mips_keep_farthest_depth() {
+        let mut hiz = HiZBuffer::new(4, 2);
+        hiz.write_min(0, 0, 0.2);
+        hiz.write_min(1, 0, 0.4);
+        hiz.write_min(0, 1, 0.3);
+        hiz.write_min(1, 1, 0.1);
+        hiz.build_mips();
+
+        assert_eq!(hiz.level_count(), 3);
+        assert_eq!(hiz.depth(1, 0, 0), 0.4);
+        // 未写入的区域保持最远深度
+        assert_eq!(hiz.depth(1, 1, 0), 1.0);
+        assert_eq!(hiz.depth(2, 0, 0), 1.0);
+    }
+
+    #[test]
+    fn box_behind_wall_is_culled() {
+        let mut culler = OcclusionCuller::default();
+        let behind = AABB::from_center_size(Vec3::new(0.0, 0.0, -3.0), Vec3::splat(0.5));
+        let in_front = AABB::from_center_size(Vec3::new(0.0, 0.0, 2.0), Vec3::splat(0.5));
+
+        let visible = culler.run(view_projection(), &[wall()], &[behind, in_front]);
+        assert_eq!(visible, vec![false, true]);
+
+        let stats = culler.stats();
+        assert_eq!(stats.occluders, 1);
+        assert_eq!(stats.tested, 2);
+        assert_eq!(stats.culled, 1);
+    }
+
+    #[test]
+    fn only_covered_pixels_of_triangle_occluders_hide_objects() {
+        // 墙面的左下半三角形，包围盒与整面墙相同
+        let triangle = [Vec3::new(-3.0, -3.0, 0.0), Vec3::new(3.0, -3.0, 0.0), Vec3::new(-3.0, 3.0, 0.0)];
+        let behind_covered = AABB::from_center_size(Vec3::new(-1.5, -1.5, -3.0), Vec3::splat(0.5));
+        let behind_uncovered = AABB::from_center_size(Vec3::new(1.5, 1.5, -3.0), Vec3::splat(0.5));
+
+        let mut culler = OcclusionCuller::default();
+        culler.begin_frame(view_projection());
+        culler.add_occluder_triangles(&triangle);
+        culler.build_hiz();
+        assert_eq!(culler.cull(&[behind_covered, behind_uncovered]), vec![false, true]);
+
+        // 同一三角形的包围盒会错误地遮住空白区域后的物体
+        let bounds = AABB::from_points(&triangle).unwrap();
+        assert_eq!(culler.run(view_projection(), &[bounds], &[behind_uncovered]), vec![false]);
+    }
+
+    #[test]
+    fn objects_crossing_camera_plane_stay_visible() {
+        let mut culler = OcclusionCuller::default();
+        let around_camera = AABB::from_center_size(Vec3::new(0.0, 0.0, 5.0), Vec3::splat(2.0));
+        assert_eq!(culler.run(view_projection(), &[wall()], &[around_camera]), vec![true]);
+    }
+
+    #[test]
+    fn disabled_culler_keeps_everything() {
+        let mut culler = OcclusionCuller::new(OcclusionConfig { enabled: false, ..Default::default() });
+        let behind = AABB::from_center_size(Vec3::new(0.0, 0.0, -3.0), Vec3::splat(0.5));
+        assert_eq!(culler.run(view_projection(), &[wall()], &[behind]), vec![true]);
+        assert_eq!(culler.stats().culled, 0);
+    }
+}
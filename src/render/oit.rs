@@ -0,0 +1,361 @@
+//! 加权混合顺序无关透明(Weighted Blended Order-Independent Transparency)
+//!
+//! 参考 Morgan McGuire 与 Louis Bavoil 的方法：透明物体无需排序，
+//! 累积通道把按深度加权的预乘颜色累加到accum缓冲，把(1 - alpha)的乘积写入reveal缓冲，
+//! 合成通道再用加权平均颜色和总覆盖率叠加到不透明场景上。
+//! 加法和乘法满足交换律，因此结果与绘制顺序无关。
+//! `OitAccumulator` 是单个像素的CPU参考实现，与oit.wgsl保持一致。
+
+use crate::math::{Vec3, Vec4};
+
+/// 单个像素的OIT累积状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OitAccumulator {
+    /// 加权的预乘颜色(rgb)与加权的alpha(a)之和
+    pub accum: Vec4,
+    /// 所有片元(1 - alpha)的乘积，即背景的可见度
+    pub revealage: f32,
+}
+
+impl Default for OitAccumulator {
+    fn default() -> Self {
+        Self {
+            accum: Vec4::ZERO,
+            revealage: 1.0,
+        }
+    }
+}
+
+impl OitAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 深度权重，depth为[0, 1]的非线性深度，越近权重越大
+    pub fn weight(depth: f32, alpha: f32) -> f32 {
+        let d = 1.0 - depth.clamp(0.0, 1.0);
+        alpha * (3e3 * d * d * d).max(1e-2)
+    }
+
+    /// 累积一个透明片元(非预乘颜色)
+    pub fn add(&mut self, color: Vec3, alpha: f32, depth: f32) {
+        let alpha = alpha.clamp(0.0, 1.0);
+        let weight = Self::weight(depth, alpha);
+        self.accum += (color * alpha).extend(alpha) * weight;
+        self.revealage *= 1.0 - alpha;
+    }
+
+    /// 透明物体的总覆盖率
+    pub fn coverage(&self) -> f32 {
+        1.0 - self.revealage
+    }
+
+    /// 合成到不透明背景上
+    pub fn resolve(&self, background: Vec3) -> Vec3 {
+        if self.revealage >= 1.0 {
+            return background;
+        }
+        let average_color = self.accum.truncate() / self.accum.w.max(1e-5);
+        average_color * self.coverage() + background * self.revealage
+    }
+}
+
+/// GPU加权混合OIT渲染器
+pub struct OitRenderer {
+    composite_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    accum_view: wgpu::TextureView,
+    reveal_view: wgpu::TextureView,
+    /// 多重采样的累积缓冲，MSAA时透明物体绘制到这里并解析到accum/reveal
+    msaa_views: Option<(wgpu::TextureView, wgpu::TextureView)>,
+    sample_count: u32,
+    width: u32,
+    height: u32,
+}
+
+impl OitRenderer {
+    /// 累积缓冲格式
+    pub const ACCUM_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+    /// 可见度缓冲格式
+    pub const REVEAL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+    /// `sample_count` 与主深度缓冲一致，累积通道才能进行深度测试
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, width: u32, height: u32, sample_count: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("OIT合成着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/oit.wgsl").into()),
+        });
+
+        let texture_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("OIT合成绑定组布局"),
+            entries: &[texture_entry(0), texture_entry(1)],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("OIT合成管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let composite_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("OIT合成管线"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_composite",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: output_format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sample_count = sample_count.max(1);
+        let (accum_view, reveal_view) = Self::create_targets(device, width, height, 1);
+        let msaa_views = (sample_count > 1).then(|| Self::create_targets(device, width, height, sample_count));
+        let bind_group = Self::create_bind_group(device, &bind_group_layout, &accum_view, &reveal_view);
+
+        Self {
+            composite_pipeline,
+            bind_group_layout,
+            bind_group,
+            accum_view,
+            reveal_view,
+            msaa_views,
+            sample_count,
+            width,
+            height,
+        }
+    }
+
+    /// 透明物体管线需要的采样数
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// 透明物体管线的颜色目标：accum加法混合，reveal乘以(1 - alpha)
+    pub fn accumulation_targets() -> [Option<wgpu::ColorTargetState>; 2] {
+        [
+            Some(wgpu::ColorTargetState {
+                format: Self::ACCUM_FORMAT,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::One,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::One,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            }),
+            Some(wgpu::ColorTargetState {
+                format: Self::REVEAL_FORMAT,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::Zero,
+                        dst_factor: wgpu::BlendFactor::OneMinusSrc,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::Zero,
+                        dst_factor: wgpu::BlendFactor::OneMinusSrc,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                }),
+                write_mask: wgpu::ColorWrites::RED,
+            }),
+        ]
+    }
+
+    /// 开始累积通道，透明物体在返回的通道中绘制(深度只读)
+    pub fn begin_accumulation<'a>(
+        &'a self,
+        encoder: &'a mut wgpu::CommandEncoder,
+        depth_view: Option<&'a wgpu::TextureView>,
+    ) -> wgpu::RenderPass<'a> {
+        let (accum_view, accum_resolve, reveal_view, reveal_resolve) = match &self.msaa_views {
+            Some((accum, reveal)) => (accum, Some(&self.accum_view), reveal, Some(&self.reveal_view)),
+            None => (&self.accum_view, None, &self.reveal_view, None),
+        };
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("OIT累积通道"),
+            color_attachments: &[
+                Some(wgpu::RenderPassColorAttachment {
+                    view: accum_view,
+                    resolve_target: accum_resolve,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+                Some(wgpu::RenderPassColorAttachment {
+                    view: reveal_view,
+                    resolve_target: reveal_resolve,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+            ],
+            depth_stencil_attachment: depth_view.map(|view| wgpu::RenderPassDepthStencilAttachment {
+                view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        })
+    }
+
+    /// 合成通道：把透明结果叠加到target上
+    pub fn composite(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("OIT合成通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(&self.composite_pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// 窗口大小变化时重新创建累积缓冲
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if width == self.width && height == self.height {
+            return;
+        }
+        let (accum_view, reveal_view) = Self::create_targets(device, width, height, 1);
+        self.bind_group = Self::create_bind_group(device, &self.bind_group_layout, &accum_view, &reveal_view);
+        self.accum_view = accum_view;
+        self.reveal_view = reveal_view;
+        self.msaa_views = (self.sample_count > 1).then(|| Self::create_targets(device, width, height, self.sample_count));
+        self.width = width;
+        self.height = height;
+    }
+
+    fn create_targets(device: &wgpu::Device, width: u32, height: u32, sample_count: u32) -> (wgpu::TextureView, wgpu::TextureView) {
+        // 多重采样缓冲只作为附件，解析后的单采样缓冲供合成通道读取
+        let usage = if sample_count > 1 {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+        };
+        let create = |label: &str, format: wgpu::TextureFormat| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width: width.max(1),
+                        height: height.max(1),
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        (
+            create("OIT累积缓冲", Self::ACCUM_FORMAT),
+            create("OIT可见度缓冲", Self::REVEAL_FORMAT),
+        )
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        accum_view: &wgpu::TextureView,
+        reveal_view: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("OIT合成绑定组"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(accum_view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(reveal_view) },
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_pixel_keeps_background() {
+        let background = Vec3::new(0.2, 0.4, 0.6);
+        assert_eq!(OitAccumulator::new().resolve(background), background);
+    }
+
+    #[test]
+    fn single_fragment_matches_alpha_blending() {
+        let mut pixel = OitAccumulator::new();
+        pixel.add(Vec3::X, 0.25, 0.5);
+        let resolved = pixel.resolve(Vec3::Z);
+        assert!((resolved - Vec3::new(0.25, 0.0, 0.75)).length() < 1e-5);
+        assert!((pixel.coverage() - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn result_is_order_independent() {
+        let fragments = [(Vec3::X, 0.5, 0.3), (Vec3::Y, 0.4, 0.6), (Vec3::Z, 0.7, 0.9)];
+        let mut forward = OitAccumulator::new();
+        let mut backward = OitAccumulator::new();
+        for &(color, alpha, depth) in &fragments {
+            forward.add(color, alpha, depth);
+        }
+        for &(color, alpha, depth) in fragments.iter().rev() {
+            backward.add(color, alpha, depth);
+        }
+        let background = Vec3::splat(0.1);
+        assert!((forward.resolve(background) - backward.resolve(background)).length() < 1e-5);
+    }
+
+    #[test]
+    fn nearer_fragments_weigh_more() {
+        assert!(OitAccumulator::weight(0.1, 0.5) > OitAccumulator::weight(0.9, 0.5));
+        assert_eq!(OitAccumulator::weight(0.5, 0.0), 0.0);
+    }
+}
@@ -1,6 +1,7 @@
 //! 后处理效果系统
 
-use crate::math::{Vec2, Vec3, Vec4};
+use crate::math::{Mat4, Vec2, Vec3, Vec4};
+use crate::render::{BloomRenderer, Camera, DepthOfFieldCamera, DepthOfFieldRenderer, TaaCamera, TaaRenderer, TaaResolver, FxaaRenderer};
 use wgpu::*;
 use wgpu::util::DeviceExt;
 use std::collections::HashMap;
@@ -20,6 +21,73 @@ pub enum PostProcessingEffect {
     ChromaticAberration, // 色差
     FilmGrain,          // 胶片颗粒
     LensFlare,          // 镜头光晕
+    TAA,                // 时间性抗锯齿
+}
+
+/// 抗锯齿方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AntiAliasing {
+    None,
+    /// 硬件多重采样，参数为采样数
+    Msaa(u32),
+    /// 快速近似抗锯齿(后处理)
+    Fxaa,
+    /// 时间性抗锯齿(后处理，按深度重投影历史帧)
+    Taa,
+}
+
+impl Default for AntiAliasing {
+    fn default() -> Self {
+        AntiAliasing::Msaa(4)
+    }
+}
+
+impl AntiAliasing {
+    /// 由旧配置的MSAA采样数转换
+    pub fn from_msaa_samples(samples: u32) -> Self {
+        if samples <= 1 {
+            AntiAliasing::None
+        } else {
+            AntiAliasing::Msaa(samples)
+        }
+    }
+
+    /// 反序列化时兼容旧的 `msaa_samples` 整数值
+    pub fn deserialize_compat<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Compat {
+            Samples(u32),
+            Mode(AntiAliasing),
+        }
+
+        Ok(match <Compat as serde::Deserialize>::deserialize(deserializer)? {
+            Compat::Samples(samples) => AntiAliasing::from_msaa_samples(samples),
+            Compat::Mode(mode) => mode,
+        })
+    }
+
+    /// 主通道管线的采样数
+    pub fn sample_count(&self) -> u32 {
+        match self {
+            AntiAliasing::Msaa(samples) => (*samples).max(1),
+            _ => 1,
+        }
+    }
+
+    /// 对应的后处理效果
+    pub fn post_effect(&self) -> Option<PostProcessingEffect> {
+        match self {
+            AntiAliasing::Fxaa => Some(PostProcessingEffect::FXAA),
+            AntiAliasing::Taa => Some(PostProcessingEffect::TAA),
+            _ => None,
+        }
+    }
+
+    /// 是否需要主通道输出运动向量
+    pub fn requires_motion_vectors(&self) -> bool {
+        matches!(self, AntiAliasing::Taa)
+    }
 }
 
 /// 后处理配置
@@ -30,6 +98,7 @@ pub struct PostProcessingConfig {
     pub tone_mapping: ToneMappingConfig,
     pub color_grading: ColorGradingConfig,
     pub fxaa: FXAAConfig,
+    pub taa: TAAConfig,
     pub ssao: SSAOConfig,
     pub depth_of_field: DepthOfFieldConfig,
     pub motion_blur: MotionBlurConfig,
@@ -50,6 +119,7 @@ impl Default for PostProcessingConfig {
             tone_mapping: ToneMappingConfig::default(),
             color_grading: ColorGradingConfig::default(),
             fxaa: FXAAConfig::default(),
+            taa: TAAConfig::default(),
             ssao: SSAOConfig::default(),
             depth_of_field: DepthOfFieldConfig::default(),
             motion_blur: MotionBlurConfig::default(),
@@ -60,11 +130,26 @@ impl Default for PostProcessingConfig {
     }
 }
 
+impl PostProcessingConfig {
+    /// 按抗锯齿方式启用FXAA/TAA后处理效果，MSAA或无抗锯齿时两者都会移除
+    pub fn set_anti_aliasing(&mut self, anti_aliasing: AntiAliasing) {
+        self.enabled_effects
+            .retain(|&e| e != PostProcessingEffect::FXAA && e != PostProcessingEffect::TAA);
+        self.fxaa.enabled = anti_aliasing == AntiAliasing::Fxaa;
+        self.taa.enabled = anti_aliasing == AntiAliasing::Taa;
+
+        if let Some(effect) = anti_aliasing.post_effect() {
+            self.enabled_effects.push(effect);
+        }
+    }
+}
+
 /// Bloom配置
 #[derive(Debug, Clone)]
 pub struct BloomConfig {
     pub enabled: bool,
     pub threshold: f32,      // 亮度阈值
+    pub knee: f32,           // 软阈值过渡宽度
     pub intensity: f32,      // 强度
     pub iterations: u32,     // 迭代次数
     pub radius: f32,         // 半径
@@ -75,6 +160,7 @@ impl Default for BloomConfig {
         Self {
             enabled: true,
             threshold: 1.0,
+            knee: 0.5,
             intensity: 0.8,
             iterations: 5,
             radius: 1.0,
@@ -168,6 +254,26 @@ impl Default for FXAAConfig {
     }
 }
 
+/// TAA配置
+#[derive(Debug, Clone)]
+pub struct TAAConfig {
+    pub enabled: bool,
+    /// 历史帧混合权重
+    pub feedback: f32,
+    /// 投影抖动幅度(像素)
+    pub jitter_scale: f32,
+}
+
+impl Default for TAAConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            feedback: 0.9,
+            jitter_scale: 1.0,
+        }
+    }
+}
+
 /// SSAO配置
 #[derive(Debug, Clone)]
 pub struct SSAOConfig {
@@ -197,7 +303,12 @@ impl Default for SSAOConfig {
 pub struct DepthOfFieldConfig {
     pub enabled: bool,
     pub focus_distance: f32,
+    /// 对焦距离前后完全清晰的范围
     pub focus_range: f32,
+    /// 光圈大小，决定弥散圈随离焦增长的速度
+    pub aperture: f32,
+    /// 弥散圈为1时的模糊半径(像素)
+    pub max_blur_radius: f32,
     pub blur_strength: f32,
     pub bokeh_shape: BokehShape,
 }
@@ -215,12 +326,22 @@ impl Default for DepthOfFieldConfig {
             enabled: false,
             focus_distance: 10.0,
             focus_range: 5.0,
+            aperture: 0.5,
+            max_blur_radius: 12.0,
             blur_strength: 1.0,
             bokeh_shape: BokehShape::Circle,
         }
     }
 }
 
+impl DepthOfFieldConfig {
+    /// 从相机同步对焦距离与光圈
+    pub fn apply_camera(&mut self, camera: &Camera) {
+        self.focus_distance = camera.focus_distance;
+        self.aperture = camera.aperture;
+    }
+}
+
 /// 运动模糊配置
 #[derive(Debug, Clone)]
 pub struct MotionBlurConfig {
@@ -345,12 +466,29 @@ pub struct PostProcessingRenderer {
     
     // 全屏四边形顶点缓冲区
     fullscreen_quad_buffer: Buffer,
+
+    // Bloom渲染器
+    bloom_renderer: BloomRenderer,
+
+    // 景深渲染器及其使用的相机参数
+    dof_renderer: DepthOfFieldRenderer,
+    dof_camera: DepthOfFieldCamera,
+
+    // FXAA渲染器
+    fxaa_renderer: FxaaRenderer,
+
+    // TAA渲染器及前后两帧的相机矩阵
+    taa_renderer: TaaRenderer,
+    taa_camera: TaaCamera,
     
     screen_width: u32,
     screen_height: u32,
 }
 
 impl PostProcessingRenderer {
+    /// 场景深度缓冲格式
+    pub const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
     pub fn new(device: &Device, screen_width: u32, screen_height: u32, config: PostProcessingConfig) -> Self {
         // 创建采样器
         let linear_sampler = device.create_sampler(&SamplerDescriptor {
@@ -389,6 +527,18 @@ impl PostProcessingRenderer {
             usage: BufferUsages::VERTEX,
         });
 
+        let bloom_renderer = BloomRenderer::new(
+            device,
+            TextureFormat::Rgba8UnormSrgb,
+            screen_width,
+            screen_height,
+            config.bloom.iterations,
+        );
+
+        let dof_renderer = DepthOfFieldRenderer::new(device, TextureFormat::Rgba8UnormSrgb, screen_width, screen_height);
+        let fxaa_renderer = FxaaRenderer::new(device, TextureFormat::Rgba8UnormSrgb);
+        let taa_renderer = TaaRenderer::new(device, TextureFormat::Rgba8UnormSrgb, screen_width, screen_height);
+
         let mut renderer = Self {
             config,
             render_targets: HashMap::new(),
@@ -398,6 +548,12 @@ impl PostProcessingRenderer {
             uniform_buffers: HashMap::new(),
             bind_group_layouts: HashMap::new(),
             fullscreen_quad_buffer,
+            bloom_renderer,
+            dof_renderer,
+            dof_camera: DepthOfFieldCamera::default(),
+            fxaa_renderer,
+            taa_renderer,
+            taa_camera: TaaCamera::default(),
             screen_width,
             screen_height,
         };
@@ -444,18 +600,11 @@ impl PostProcessingRenderer {
             );
         }
 
-        // 景深缓冲区
-        if self.config.depth_of_field.enabled {
-            self.render_targets.insert(
-                "dof_coc".to_string(),
-                RenderTarget::new(device, self.screen_width, self.screen_height, TextureFormat::R16Float, Some("DoF CoC"))
-            );
-            
-            self.render_targets.insert(
-                "dof_blur".to_string(),
-                RenderTarget::new(device, self.screen_width, self.screen_height, TextureFormat::Rgba16Float, Some("DoF Blur"))
-            );
-        }
+        // 场景深度缓冲区，主通道写入，景深等效果采样
+        self.render_targets.insert(
+            "depth".to_string(),
+            RenderTarget::new(device, self.screen_width, self.screen_height, Self::DEPTH_FORMAT, Some("Scene Depth"))
+        );
 
         // 临时缓冲区
         self.render_targets.insert(
@@ -506,7 +655,7 @@ impl PostProcessingRenderer {
             match effect {
                 PostProcessingEffect::Bloom => {
                     if self.config.bloom.enabled {
-                        self.apply_bloom(encoder, current_input, temp_texture);
+                        self.apply_bloom(device, encoder, current_input, temp_texture);
                         current_input = temp_texture;
                     }
                 }
@@ -518,7 +667,13 @@ impl PostProcessingRenderer {
                 }
                 PostProcessingEffect::FXAA => {
                     if self.config.fxaa.enabled {
-                        self.apply_fxaa(encoder, current_input, temp_texture);
+                        self.apply_fxaa(device, encoder, current_input, temp_texture);
+                        current_input = temp_texture;
+                    }
+                }
+                PostProcessingEffect::TAA => {
+                    if self.config.taa.enabled {
+                        self.apply_taa(device, encoder, current_input, temp_texture);
                         current_input = temp_texture;
                     }
                 }
@@ -528,6 +683,12 @@ impl PostProcessingRenderer {
                         current_input = temp_texture;
                     }
                 }
+                PostProcessingEffect::DepthOfField => {
+                    if self.config.depth_of_field.enabled {
+                        self.apply_depth_of_field(device, encoder, current_input, temp_texture);
+                        current_input = temp_texture;
+                    }
+                }
                 PostProcessingEffect::Vignette => {
                     if self.config.vignette.enabled {
                         self.apply_vignette(encoder, current_input, temp_texture);
@@ -547,16 +708,16 @@ impl PostProcessingRenderer {
     }
 
     /// 应用Bloom效果
-    fn apply_bloom(&self, encoder: &mut CommandEncoder, input: &TextureView, output: &TextureView) {
-        // 1. 提取亮度
-        // 2. 下采样
-        // 3. 模糊
-        // 4. 上采样并混合
-        
-        // 这里是简化的实现框架
-        // 实际实现需要多个渲染通道
-        
-        // TODO: 实现完整的Bloom算法
+    fn apply_bloom(&self, device: &Device, encoder: &mut CommandEncoder, input: &TextureView, output: &TextureView) {
+        // 1. 提取亮度 2. 下采样 3. 上采样模糊 4. 叠加合成
+        self.bloom_renderer.render(device, encoder, &self.config.bloom, input, output);
+    }
+
+    /// 应用景深
+    fn apply_depth_of_field(&self, device: &Device, encoder: &mut CommandEncoder, input: &TextureView, output: &TextureView) {
+        // 1. 由深度计算弥散圈 2. 水平模糊 3. 垂直模糊 4. 按弥散圈混合
+        let depth = &self.render_targets["depth"].view;
+        self.dof_renderer.render(device, encoder, &self.config.depth_of_field, &self.dof_camera, input, depth, output);
     }
 
     /// 应用色调映射
@@ -565,8 +726,15 @@ impl PostProcessingRenderer {
     }
 
     /// 应用FXAA
-    fn apply_fxaa(&self, encoder: &mut CommandEncoder, input: &TextureView, output: &TextureView) {
-        // TODO: 实现FXAA算法
+    fn apply_fxaa(&self, device: &Device, encoder: &mut CommandEncoder, input: &TextureView, output: &TextureView) {
+        self.fxaa_renderer.render(device, encoder, &self.config.fxaa, self.screen_width, self.screen_height, input, output);
+    }
+
+    /// 应用TAA
+    fn apply_taa(&self, device: &Device, encoder: &mut CommandEncoder, input: &TextureView, output: &TextureView) {
+        // 1. 由深度与前后两帧视图投影矩阵重投影历史帧 2. 邻域颜色钳制 3. 按feedback混合并写回历史
+        let depth = &self.render_targets["depth"].view;
+        self.taa_renderer.render(device, encoder, &self.config.taa, &self.taa_camera, input, depth, output);
     }
 
     /// 应用色彩分级
@@ -589,6 +757,7 @@ impl PostProcessingRenderer {
         self.config = new_config;
         
         // 重新创建必要的资源
+        self.bloom_renderer.resize(device, self.screen_width, self.screen_height, self.config.bloom.iterations);
         self.create_render_targets(device);
         self.create_pipelines(device);
     }
@@ -601,6 +770,36 @@ impl PostProcessingRenderer {
         // 重新创建渲染目标
         self.render_targets.clear();
         self.create_render_targets(device);
+        self.bloom_renderer.resize(device, new_width, new_height, self.config.bloom.iterations);
+        self.dof_renderer.resize(device, new_width, new_height);
+        self.taa_renderer.resize(device, new_width, new_height);
+    }
+
+    /// 场景深度缓冲，主通道应将其作为深度附件以供景深采样
+    pub fn depth_target(&self) -> &RenderTarget {
+        &self.render_targets["depth"]
+    }
+
+    /// 场景深度缓冲视图
+    pub fn depth_view(&self) -> &TextureView {
+        &self.depth_target().view
+    }
+
+    /// 同步相机的裁剪面、对焦距离与光圈，用于景深
+    pub fn set_camera(&mut self, camera: &Camera) {
+        self.dof_camera = DepthOfFieldCamera::from_camera(camera);
+        self.config.depth_of_field.apply_camera(camera);
+        self.taa_camera.advance(camera);
+    }
+
+    /// 启用TAA时主通道应使用的抖动投影矩阵，需在 `set_camera` 之后调用
+    pub fn jittered_projection(&self, camera: &Camera) -> Mat4 {
+        let projection = camera.projection_matrix();
+        if !self.config.taa.enabled {
+            return projection;
+        }
+        let jitter = self.taa_camera.jitter(&self.config.taa, self.screen_width, self.screen_height);
+        TaaResolver::jitter_projection(projection, jitter)
     }
 
     /// 获取效果是否启用
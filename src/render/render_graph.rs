@@ -0,0 +1,476 @@
+//! 渲染图
+//!
+//! 渲染通道声明自己读写的纹理，渲染图据此进行拓扑排序确定执行顺序，
+//! 并为生命周期不重叠且描述相同的临时渲染目标分配同一个物理纹理(别名复用)。
+
+use crate::{EngineResult, EngineError};
+use crate::render::AntiAliasing;
+use std::collections::{BTreeSet, HashMap};
+
+/// 渲染图纹理描述
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderTextureDesc {
+    pub width: u32,
+    pub height: u32,
+    pub format: wgpu::TextureFormat,
+}
+
+impl RenderTextureDesc {
+    pub fn new(width: u32, height: u32, format: wgpu::TextureFormat) -> Self {
+        Self { width, height, format }
+    }
+}
+
+/// 渲染图中的纹理资源
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderGraphResource {
+    /// 由渲染图分配和复用的临时纹理
+    Transient(RenderTextureDesc),
+    /// 外部导入的纹理(如交换链)，不参与别名复用
+    Imported,
+}
+
+/// 渲染通道声明
+#[derive(Debug, Clone)]
+pub struct RenderGraphPass {
+    pub name: String,
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+    pub enabled: bool,
+}
+
+impl RenderGraphPass {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            reads: Vec::new(),
+            writes: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// 声明读取的纹理
+    pub fn read(mut self, texture: impl Into<String>) -> Self {
+        self.reads.push(texture.into());
+        self
+    }
+
+    /// 声明写入的纹理
+    pub fn write(mut self, texture: impl Into<String>) -> Self {
+        self.writes.push(texture.into());
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// 渲染图
+#[derive(Debug, Clone, Default)]
+pub struct RenderGraph {
+    resources: HashMap<String, RenderGraphResource>,
+    passes: Vec<RenderGraphPass>,
+}
+
+impl RenderGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 默认管线：阴影 -> 主通道 -> 透明累积 -> 透明合成 -> 后处理 -> 交换链
+    pub fn default_pipeline(width: u32, height: u32) -> Self {
+        let mut graph = Self::new();
+        graph.add_texture("shadow_map", RenderTextureDesc::new(2048, 2048, wgpu::TextureFormat::Depth32Float));
+        graph.add_texture("scene_color", RenderTextureDesc::new(width, height, wgpu::TextureFormat::Rgba16Float));
+        graph.add_texture("scene_depth", RenderTextureDesc::new(width, height, wgpu::TextureFormat::Depth32Float));
+        graph.add_texture("oit_accum", RenderTextureDesc::new(width, height, wgpu::TextureFormat::Rgba16Float));
+        graph.add_texture("oit_reveal", RenderTextureDesc::new(width, height, wgpu::TextureFormat::R8Unorm));
+        graph.import_texture("backbuffer");
+
+        graph.add_pass(RenderGraphPass::new("shadow").write("shadow_map"));
+        graph.add_pass(
+            RenderGraphPass::new("main")
+                .read("shadow_map")
+                .write("scene_color")
+                .write("scene_depth"),
+        );
+        graph.add_pass(
+            RenderGraphPass::new("transparent")
+                .read("scene_depth")
+                .write("oit_accum")
+                .write("oit_reveal"),
+        );
+        graph.add_pass(
+            RenderGraphPass::new("oit_composite")
+                .read("oit_accum")
+                .read("oit_reveal")
+                .write("scene_color"),
+        );
+        // 覆盖层画在合成后的场景颜色上
+        graph.add_pass(RenderGraphPass::new("overlay").read("scene_color").write("scene_color"));
+        graph.add_pass(RenderGraphPass::new("post").read("scene_color").write("backbuffer"));
+        graph
+    }
+
+    /// 在默认管线的后处理通道之前插入FXAA/TAA通道
+    ///
+    /// 抗锯齿通道读取合成后的场景颜色并写入中间纹理，后处理改为把该纹理复制到交换链。
+    pub fn insert_anti_aliasing_pass(&mut self, anti_aliasing: AntiAliasing, width: u32, height: u32) {
+        let pass_name = match anti_aliasing {
+            AntiAliasing::Fxaa => "fxaa",
+            AntiAliasing::Taa => "taa",
+            _ => return,
+        };
+
+        self.add_texture("aa_color", RenderTextureDesc::new(width, height, wgpu::TextureFormat::Rgba8Unorm));
+        if let Some(post) = self.passes.iter_mut().find(|pass| pass.name == "post") {
+            for texture in post.reads.iter_mut().filter(|texture| *texture == "scene_color") {
+                *texture = "aa_color".to_string();
+            }
+        }
+
+        let mut pass = RenderGraphPass::new(pass_name).read("scene_color").write("aa_color");
+        if anti_aliasing == AntiAliasing::Taa {
+            // 由深度重投影历史帧，历史缓冲跨帧保留
+            self.import_texture("taa_history");
+            pass = pass.read("scene_depth").read("taa_history").write("taa_history");
+        }
+        self.add_pass(pass);
+    }
+
+    /// 声明临时纹理
+    pub fn add_texture(&mut self, name: impl Into<String>, desc: RenderTextureDesc) {
+        self.resources.insert(name.into(), RenderGraphResource::Transient(desc));
+    }
+
+    /// 声明外部导入的纹理
+    pub fn import_texture(&mut self, name: impl Into<String>) {
+        self.resources.insert(name.into(), RenderGraphResource::Imported);
+    }
+
+    /// 添加渲染通道
+    pub fn add_pass(&mut self, pass: RenderGraphPass) {
+        self.passes.push(pass);
+    }
+
+    /// 移除渲染通道
+    pub fn remove_pass(&mut self, name: &str) -> bool {
+        let count = self.passes.len();
+        self.passes.retain(|pass| pass.name != name);
+        self.passes.len() != count
+    }
+
+    /// 启用/禁用渲染通道
+    pub fn set_pass_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        match self.passes.iter_mut().find(|pass| pass.name == name) {
+            Some(pass) => {
+                pass.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 获取渲染通道
+    pub fn pass(&self, name: &str) -> Option<&RenderGraphPass> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+
+    /// 获取所有渲染通道(声明顺序)
+    pub fn passes(&self) -> &[RenderGraphPass] {
+        &self.passes
+    }
+
+    /// 编译渲染图：检查资源、拓扑排序并分配物理纹理
+    pub fn compile(&self) -> EngineResult<CompiledRenderGraph> {
+        let passes: Vec<&RenderGraphPass> = self.passes.iter().filter(|pass| pass.enabled).collect();
+
+        for (i, pass) in passes.iter().enumerate() {
+            if passes[..i].iter().any(|other| other.name == pass.name) {
+                return Err(EngineError::RenderError(format!("渲染通道重复: {}", pass.name)).into());
+            }
+            for texture in pass.reads.iter().chain(pass.writes.iter()) {
+                if !self.resources.contains_key(texture) {
+                    return Err(EngineError::RenderError(
+                        format!("渲染通道 {} 引用了未声明的纹理: {}", pass.name, texture)
+                    ).into());
+                }
+            }
+        }
+
+        // 每个纹理的写入者和读者(按声明顺序)
+        let mut writers: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut readers: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, pass) in passes.iter().enumerate() {
+            for texture in &pass.writes {
+                writers.entry(texture.as_str()).or_default().push(index);
+            }
+            for texture in &pass.reads {
+                readers.entry(texture.as_str()).or_default().push(index);
+            }
+        }
+
+        // 建立依赖边：读者依赖先于它声明的写入者，写入者依赖之前的写入者，
+        // 以及读取旧内容的读者(读后写)，避免覆盖或别名复用仍在被读取的纹理
+        let mut edges: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); passes.len()];
+        let mut in_degree = vec![0usize; passes.len()];
+        for (index, pass) in passes.iter().enumerate() {
+            let mut dependencies = BTreeSet::new();
+            for texture in &pass.reads {
+                let texture_writers = writers.get(texture.as_str()).map(Vec::as_slice).unwrap_or(&[]);
+                if texture_writers.is_empty() {
+                    if self.resources.get(texture) != Some(&RenderGraphResource::Imported) {
+                        return Err(EngineError::RenderError(
+                            format!("渲染通道 {} 读取的纹理 {} 没有写入者", pass.name, texture)
+                        ).into());
+                    }
+                    continue;
+                }
+                let earlier: Vec<usize> = texture_writers.iter().copied().filter(|&w| w < index).collect();
+                if earlier.is_empty() {
+                    dependencies.extend(texture_writers.iter().copied().filter(|&w| w != index));
+                } else {
+                    dependencies.extend(earlier);
+                }
+            }
+            for texture in &pass.writes {
+                let texture_writers = &writers[texture.as_str()];
+                if let Some(&previous) = texture_writers.iter().rev().find(|&&w| w < index) {
+                    dependencies.insert(previous);
+                }
+                // 只有读取更早写入者结果的读者才需在此之前执行，否则读者本身依赖本通道
+                let texture_readers = readers.get(texture.as_str()).map(Vec::as_slice).unwrap_or(&[]);
+                dependencies.extend(
+                    texture_readers
+                        .iter()
+                        .copied()
+                        .filter(|&r| r < index && texture_writers.iter().any(|&w| w < r)),
+                );
+            }
+
+            for dependency in dependencies {
+                if edges[dependency].insert(index) {
+                    in_degree[index] += 1;
+                }
+            }
+        }
+
+        // Kahn拓扑排序，同层按声明顺序保持稳定
+        let mut ready: BTreeSet<usize> = (0..passes.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(passes.len());
+        while let Some(index) = ready.pop_first() {
+            order.push(index);
+            for &next in &edges[index] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.insert(next);
+                }
+            }
+        }
+        if order.len() != passes.len() {
+            return Err(EngineError::RenderError("渲染图存在循环依赖".to_string()).into());
+        }
+
+        let (physical_targets, assignments) = self.allocate_targets(&passes, &order);
+
+        Ok(CompiledRenderGraph {
+            passes: order.iter().map(|&i| passes[i].clone()).collect(),
+            physical_targets,
+            assignments,
+        })
+    }
+
+    /// 为临时纹理分配物理纹理，生命周期不重叠且描述相同的纹理共享同一物理纹理
+    fn allocate_targets(&self, passes: &[&RenderGraphPass], order: &[usize]) -> (Vec<RenderTextureDesc>, HashMap<String, usize>) {
+        // 计算每个临时纹理在执行顺序中的首次和末次使用位置
+        let mut lifetimes: HashMap<&str, (usize, usize)> = HashMap::new();
+        for (position, &index) in order.iter().enumerate() {
+            let pass = passes[index];
+            for texture in pass.reads.iter().chain(pass.writes.iter()) {
+                if let Some(RenderGraphResource::Transient(_)) = self.resources.get(texture) {
+                    let lifetime = lifetimes.entry(texture.as_str()).or_insert((position, position));
+                    lifetime.0 = lifetime.0.min(position);
+                    lifetime.1 = lifetime.1.max(position);
+                }
+            }
+        }
+
+        let mut sorted: Vec<(&str, (usize, usize))> = lifetimes.into_iter().collect();
+        sorted.sort_by(|a, b| a.1.0.cmp(&b.1.0).then_with(|| a.0.cmp(b.0)));
+
+        let mut physical_targets: Vec<RenderTextureDesc> = Vec::new();
+        let mut slot_last_use: Vec<usize> = Vec::new();
+        let mut assignments = HashMap::new();
+
+        for (name, (first, last)) in sorted {
+            let Some(RenderGraphResource::Transient(desc)) = self.resources.get(name) else {
+                continue;
+            };
+            let reusable = (0..physical_targets.len())
+                .find(|&slot| physical_targets[slot] == *desc && slot_last_use[slot] < first);
+
+            let slot = match reusable {
+                Some(slot) => {
+                    slot_last_use[slot] = last;
+                    slot
+                }
+                None => {
+                    physical_targets.push(*desc);
+                    slot_last_use.push(last);
+                    physical_targets.len() - 1
+                }
+            };
+            assignments.insert(name.to_string(), slot);
+        }
+
+        (physical_targets, assignments)
+    }
+}
+
+/// 编译后的渲染图
+#[derive(Debug, Clone, Default)]
+pub struct CompiledRenderGraph {
+    passes: Vec<RenderGraphPass>,
+    physical_targets: Vec<RenderTextureDesc>,
+    assignments: HashMap<String, usize>,
+}
+
+impl CompiledRenderGraph {
+    /// 按执行顺序排列的渲染通道
+    pub fn passes(&self) -> &[RenderGraphPass] {
+        &self.passes
+    }
+
+    /// 按执行顺序排列的渲染通道名称
+    pub fn execution_order(&self) -> Vec<&str> {
+        self.passes.iter().map(|pass| pass.name.as_str()).collect()
+    }
+
+    /// 渲染通道在执行顺序中的位置
+    pub fn position_of(&self, pass_name: &str) -> Option<usize> {
+        self.passes.iter().position(|pass| pass.name == pass_name)
+    }
+
+    /// 需要创建的物理纹理
+    pub fn physical_targets(&self) -> &[RenderTextureDesc] {
+        &self.physical_targets
+    }
+
+    /// 临时纹理对应的物理纹理索引
+    pub fn physical_target_of(&self, texture: &str) -> Option<usize> {
+        self.assignments.get(texture).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desc() -> RenderTextureDesc {
+        RenderTextureDesc::new(64, 64, wgpu::TextureFormat::Rgba16Float)
+    }
+
+    #[test]
+    fn default_pipeline_orders_passes() {
+        let compiled = RenderGraph::default_pipeline(800, 600).compile().unwrap();
+        assert_eq!(compiled.execution_order(), vec!["shadow", "main", "transparent", "oit_composite", "overlay", "post"]);
+    }
+
+    #[test]
+    fn anti_aliasing_pass_runs_between_composite_and_post() {
+        for (anti_aliasing, name) in [(AntiAliasing::Fxaa, "fxaa"), (AntiAliasing::Taa, "taa")] {
+            let mut graph = RenderGraph::default_pipeline(800, 600);
+            graph.insert_anti_aliasing_pass(anti_aliasing, 800, 600);
+            let compiled = graph.compile().unwrap();
+            assert!(compiled.position_of("overlay").unwrap() < compiled.position_of(name).unwrap());
+            assert!(compiled.position_of(name).unwrap() < compiled.position_of("post").unwrap());
+        }
+
+        let mut graph = RenderGraph::default_pipeline(800, 600);
+        graph.insert_anti_aliasing_pass(AntiAliasing::Msaa(4), 800, 600);
+        assert_eq!(graph.compile().unwrap().execution_order(), vec!["shadow", "main", "transparent", "oit_composite", "overlay", "post"]);
+    }
+
+    #[test]
+    fn reader_declared_before_writer_runs_after_it() {
+        let mut graph = RenderGraph::new();
+        graph.add_texture("a", desc());
+        graph.import_texture("out");
+        graph.add_pass(RenderGraphPass::new("consume").read("a").write("out"));
+        graph.add_pass(RenderGraphPass::new("produce").write("a"));
+        let compiled = graph.compile().unwrap();
+        assert_eq!(compiled.execution_order(), vec!["produce", "consume"]);
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let mut graph = RenderGraph::new();
+        graph.add_texture("a", desc());
+        graph.add_texture("b", desc());
+        graph.add_pass(RenderGraphPass::new("first").read("b").write("a"));
+        graph.add_pass(RenderGraphPass::new("second").read("a").write("b"));
+        assert!(graph.compile().is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_texture_and_duplicate_pass() {
+        let mut graph = RenderGraph::new();
+        graph.add_pass(RenderGraphPass::new("pass").write("missing"));
+        assert!(graph.compile().is_err());
+
+        let mut graph = RenderGraph::new();
+        graph.add_texture("a", desc());
+        graph.add_pass(RenderGraphPass::new("pass").write("a"));
+        graph.add_pass(RenderGraphPass::new("pass").write("a"));
+        assert!(graph.compile().is_err());
+    }
+
+    #[test]
+    fn writer_waits_for_earlier_readers() {
+        // copy读取第一次写入的a，但还要等之后声明的make_c；overwrite再次写入a，必须排在copy之后
+        let mut graph = RenderGraph::new();
+        graph.add_texture("a", desc());
+        graph.add_texture("b", desc());
+        graph.add_texture("c", desc());
+        graph.import_texture("out");
+        graph.add_pass(RenderGraphPass::new("fill").write("a"));
+        graph.add_pass(RenderGraphPass::new("copy").read("a").read("c").write("b"));
+        graph.add_pass(RenderGraphPass::new("overwrite").write("a"));
+        graph.add_pass(RenderGraphPass::new("make_c").write("c"));
+        graph.add_pass(RenderGraphPass::new("present").read("a").read("b").write("out"));
+        let compiled = graph.compile().unwrap();
+        assert!(compiled.position_of("copy").unwrap() < compiled.position_of("overwrite").unwrap());
+        assert!(compiled.position_of("overwrite").unwrap() < compiled.position_of("present").unwrap());
+    }
+
+    #[test]
+    fn aliases_only_non_overlapping_textures() {
+        let mut graph = RenderGraph::new();
+        graph.add_texture("a", desc());
+        graph.add_texture("b", desc());
+        graph.add_texture("c", desc());
+        graph.import_texture("out");
+        graph.add_pass(RenderGraphPass::new("p1").write("a"));
+        graph.add_pass(RenderGraphPass::new("p2").read("a").write("b"));
+        graph.add_pass(RenderGraphPass::new("p3").read("b").write("c"));
+        graph.add_pass(RenderGraphPass::new("p4").read("c").write("out"));
+        let compiled = graph.compile().unwrap();
+
+        assert_eq!(compiled.physical_targets().len(), 2);
+        assert_eq!(compiled.physical_target_of("a"), compiled.physical_target_of("c"));
+        assert_ne!(compiled.physical_target_of("a"), compiled.physical_target_of("b"));
+        assert_eq!(compiled.physical_target_of("out"), None);
+    }
+
+    #[test]
+    fn disabled_passes_are_skipped() {
+        let mut graph = RenderGraph::default_pipeline(800, 600);
+        assert!(graph.set_pass_enabled("shadow", false));
+        // main读取的shadow_map失去写入者
+        assert!(graph.compile().is_err());
+        assert!(graph.remove_pass("shadow"));
+        assert!(!graph.set_pass_enabled("shadow", true));
+    }
+}
@@ -0,0 +1,153 @@
+//! 渲染统计记录
+//!
+//! 渲染系统在提交绘制命令的同时调用 `RenderStatsRecorder`，统计本帧真实的绘制调用、三角形、顶点，
+//! 以及着色器/纹理/渲染目标的切换次数。切换只在绑定对象与上一次不同时计数。
+//! 网格绘制在实际调用 `set_pipeline` / `set_bind_group` 处按对象ID计数，复制到交换链等全屏通道不计入。
+
+use crate::performance::RenderStats;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// 每帧的渲染统计记录器
+#[derive(Debug, Clone, Default)]
+pub struct RenderStatsRecorder {
+    stats: RenderStats,
+    current_shader: Option<u64>,
+    /// 各绑定组槽位当前绑定的对象
+    current_bind_groups: HashMap<u32, u64>,
+    current_target: Option<u64>,
+}
+
+impl RenderStatsRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始新的一帧，清空计数与绑定状态
+    pub fn begin_frame(&mut self) {
+        *self = Self::default();
+    }
+
+    fn key(id: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 开始渲染到某个目标(每个渲染通道调用一次)
+    pub fn set_render_target(&mut self, target: impl Hash) {
+        if Self::switch(&mut self.current_target, Self::key(target)) {
+            self.stats.render_targets_switches += 1;
+        }
+        // 新的渲染通道需要重新绑定管线和资源
+        self.current_shader = None;
+        self.current_bind_groups.clear();
+    }
+
+    /// 绑定着色器/管线
+    pub fn set_shader(&mut self, shader: impl Hash) {
+        if Self::switch(&mut self.current_shader, Self::key(shader)) {
+            self.stats.shader_switches += 1;
+        }
+    }
+
+    /// 绑定纹理(或包含纹理的绑定组)，等同于绑定到0号槽位
+    pub fn set_textures(&mut self, textures: impl Hash) {
+        self.set_bind_group(0, textures);
+    }
+
+    /// 在某个槽位绑定绑定组，与该槽位上一次绑定的对象不同时计为一次切换
+    pub fn set_bind_group(&mut self, index: u32, bind_group: impl Hash) {
+        let key = Self::key(bind_group);
+        if self.current_bind_groups.insert(index, key) != Some(key) {
+            self.stats.texture_switches += 1;
+        }
+    }
+
+    fn switch(current: &mut Option<u64>, key: u64) -> bool {
+        let changed = *current != Some(key);
+        *current = Some(key);
+        changed
+    }
+
+    /// 非索引三角形列表绘制
+    pub fn draw(&mut self, vertex_count: u32, instance_count: u32) {
+        self.stats.draw_calls += 1;
+        self.stats.vertices += vertex_count * instance_count;
+        self.stats.triangles += vertex_count / 3 * instance_count;
+    }
+
+    /// 索引三角形列表绘制，`vertex_count` 为被引用的顶点数
+    pub fn draw_indexed(&mut self, index_count: u32, vertex_count: u32, instance_count: u32) {
+        self.stats.draw_calls += 1;
+        self.stats.vertices += vertex_count * instance_count;
+        self.stats.triangles += index_count / 3 * instance_count;
+    }
+
+    /// 三角形带绘制(精灵四边形等)
+    pub fn draw_strip(&mut self, vertex_count: u32, instance_count: u32) {
+        self.stats.draw_calls += 1;
+        self.stats.vertices += vertex_count * instance_count;
+        self.stats.triangles += vertex_count.saturating_sub(2) * instance_count;
+    }
+
+    /// 遮挡剔除跳过的绘制
+    pub fn occlusion_culled(&mut self, count: u32) {
+        self.stats.occlusion_culled += count;
+    }
+
+    /// 合并其他来源的统计(例如阴影渲染器)
+    pub fn stats_mut(&mut self) -> &mut RenderStats {
+        &mut self.stats
+    }
+
+    pub fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_indexed_draws() {
+        let mut recorder = RenderStatsRecorder::new();
+        recorder.draw_indexed(36, 24, 1);
+        recorder.draw_indexed(6, 4, 2);
+        let stats = recorder.stats();
+        assert_eq!(stats.draw_calls, 2);
+        assert_eq!(stats.triangles, 12 + 4);
+        assert_eq!(stats.vertices, 24 + 8);
+    }
+
+    #[test]
+    fn switches_only_count_changes() {
+        let mut recorder = RenderStatsRecorder::new();
+        recorder.set_render_target("主颜色缓冲");
+        recorder.set_shader("basic");
+        recorder.set_shader("basic");
+        recorder.set_textures("石头");
+        recorder.set_textures("木头");
+        // 不同槽位各自记录
+        recorder.set_bind_group(1, "相机雾");
+        recorder.set_bind_group(1, "相机雾");
+        recorder.set_render_target("主颜色缓冲");
+        // 新通道需要重新绑定着色器
+        recorder.set_shader("basic");
+
+        let stats = recorder.stats();
+        assert_eq!(stats.render_targets_switches, 1);
+        assert_eq!(stats.shader_switches, 2);
+        assert_eq!(stats.texture_switches, 3);
+    }
+
+    #[test]
+    fn begin_frame_resets_counts() {
+        let mut recorder = RenderStatsRecorder::new();
+        recorder.draw(3, 1);
+        recorder.begin_frame();
+        assert_eq!(recorder.stats().draw_calls, 0);
+    }
+}
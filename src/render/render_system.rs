@@ -3,9 +3,14 @@
 use crate::{EngineResult, EngineError, RenderConfig};
 use crate::ecs::ECSWorld;
 use crate::scene::Scene;
+use crate::render::{Skybox, SkyboxRenderer, OcclusionCuller, OcclusionConfig, RenderGraph, CompiledRenderGraph, AntiAliasing, PostProcessingConfig, OitRenderer, Texture, ThumbnailRenderer, ThumbnailCache, ThumbnailSource, RenderStatsRecorder, ViewportRect, RenderTargets, CustomRenderPass, CustomPassContext, TargetBlitter, MAIN_DEPTH_FORMAT, FrameRing, FrameResources, FrameUniform, DrawList, DrawItem, GpuMesh, Mesh, Material, JointPalette, DebugDraw, DebugDrawRenderer, Fog, FogUniform, GpuAssetCache, FxaaRenderer, TaaRenderer, TaaCamera, TaaResolver, RenderTarget, PipelineVariantCache, PipelineVariantKey, ShaderVariantCache, request_headless_device};
+use crate::performance::RenderStats;
+use crate::assets::AssetHandle;
+use crate::math::AABB;
 
 use wgpu::util::DeviceExt;
 use winit::window::Window;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// 顶点数据结构
@@ -43,19 +48,233 @@ impl Vertex {
     }
 }
 
+/// 每次网格绘制的uniform(对应basic.wgsl中的DrawUniform)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct DrawUniform {
+    model_view_projection: [[f32; 4]; 4],
+    model: [[f32; 4]; 4],
+    color: [f32; 4],
+}
+
+/// 每个相机的雾参数(对应basic.wgsl中的CameraFog)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct CameraFogUniform {
+    /// xyz为相机世界位置
+    camera_position: [f32; 4],
+    fog: FogUniform,
+}
+
+impl CameraFogUniform {
+    /// camera为None时不启用雾
+    fn new(camera: Option<&CameraView>) -> Self {
+        match camera {
+            Some(camera) => Self {
+                camera_position: camera.position.extend(1.0).to_array(),
+                fog: FogUniform::from(&camera.fog),
+            },
+            None => Self {
+                camera_position: [0.0, 0.0, 0.0, 1.0],
+                fog: FogUniform::from(&Fog::default()),
+            },
+        }
+    }
+}
+
 /// 渲染系统
 pub struct RenderSystem {
-    surface: wgpu::Surface<'static>,
+    /// 无窗口(离屏)渲染时为None
+    surface: Option<wgpu::Surface<'static>>,
     device: wgpu::Device,
     queue: wgpu::Queue,
+    /// 交换链配置，离屏渲染时只使用其中的尺寸和格式
     config: wgpu::SurfaceConfiguration,
     size: winit::dpi::PhysicalSize<u32>,
-    window: Arc<Window>,
-    render_pipeline: wgpu::RenderPipeline,
-    vertex_buffer: wgpu::Buffer,
-    index_buffer: wgpu::Buffer,
-    num_indices: u32,
+    window: Option<Arc<Window>>,
+    /// 网格绘制管线，按材质的着色器变体、剔除方式和环绕方向缓存
+    pipelines: DrawPipelines,
+    /// 绘制uniform的绑定组布局(动态偏移)
+    draw_bind_group_layout: wgpu::BindGroupLayout,
+    fog_bind_group_layout: wgpu::BindGroupLayout,
+    /// 绘制uniform在缓冲中的间隔，满足动态偏移对齐
+    draw_uniform_stride: wgpu::BufferAddress,
+    /// 已上传的网格(名称 -> GPU网格)，按 MeshRenderer::mesh_name 查找
+    meshes: HashMap<String, GpuMesh>,
     clear_color: wgpu::Color,
+    skybox_renderer: SkyboxRenderer,
+    occlusion_culler: OcclusionCuller,
+    render_graph: RenderGraph,
+    compiled_graph: CompiledRenderGraph,
+    anti_aliasing: AntiAliasing,
+    /// 主通道采样数(MSAA时大于1)
+    sample_count: u32,
+    /// 主通道的颜色、解析与深度缓冲
+    targets: RenderTargets,
+    /// 把场景颜色复制到交换链
+    blitter: TargetBlitter,
+    /// FXAA/TAA的输出，后处理从这里复制到交换链；未启用这两种抗锯齿时为None
+    anti_aliasing_target: Option<RenderTarget>,
+    fxaa_renderer: Option<FxaaRenderer>,
+    taa_renderer: Option<TaaRenderer>,
+    /// 第一个相机前后两帧的视图投影矩阵(未抖动)
+    taa_camera: TaaCamera,
+    /// 主通道与后处理之间执行的自定义通道
+    custom_passes: Vec<Box<dyn CustomRenderPass>>,
+    /// 每帧资源环，轮换时等待仍在GPU上执行的帧
+    frames: FrameRing<FrameResources>,
+    frame_bind_group_layout: wgpu::BindGroupLayout,
+    post_processing: PostProcessingConfig,
+    /// 透明物体的加权混合OIT
+    oit_renderer: OitRenderer,
+    /// Surface支持的呈现模式
+    supported_present_modes: Vec<wgpu::PresentMode>,
+    /// 本帧实际提交的绘制统计
+    render_stats: RenderStatsRecorder,
+    /// 缩略图渲染器，首次使用时创建
+    thumbnail_renderer: Option<ThumbnailRenderer>,
+    thumbnail_cache: ThumbnailCache,
+    /// 本帧各相机的视口(按渲染顺序)
+    camera_viewports: Vec<ViewportRect>,
+    /// 已注册的材质(名称 -> 材质)，决定网格的渲染队列
+    materials: HashMap<String, Material>,
+    /// 本帧按渲染队列排序的网格绘制
+    draw_list: DrawList,
+    /// 蒙皮实例的骨骼矩阵调色板(实例名 -> 调色板)
+    skin_palettes: HashMap<String, JointPalette>,
+    /// 由资源创建的绑定组，资源重新加载后自动重建
+    asset_bind_groups: GpuAssetCache<wgpu::BindGroup>,
+    /// 本帧提交的调试图元，渲染后清空
+    debug_draw: DebugDraw,
+    debug_draw_renderer: DebugDrawRenderer,
+}
+
+/// 一个相机在透明或覆盖层通道中的绘制
+struct CameraDraws<'a> {
+    viewport: Option<ViewportRect>,
+    fog_bind_group: wgpu::BindGroup,
+    bind_group: wgpu::BindGroup,
+    items: Vec<&'a DrawItem>,
+    pipelines: Vec<Arc<wgpu::RenderPipeline>>,
+}
+
+/// 网格绘制所在的通道
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DrawPass {
+    Opaque,
+    /// 写入OIT累积缓冲
+    Transparent,
+    /// OIT合成之后直接绘制到场景颜色缓冲，不做深度测试
+    Overlay,
+}
+
+/// 网格绘制管线，按(着色器变体, 剔除方式, 环绕方向)在首次使用时编译
+///
+/// 网格绘制目前都使用基础着色器源码，材质的着色器名和关键字只用于区分变体。
+struct DrawPipelines {
+    layout: wgpu::PipelineLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    variants: ShaderVariantCache,
+    opaque: PipelineVariantCache,
+    transparent: PipelineVariantCache,
+    overlay: PipelineVariantCache,
+}
+
+impl DrawPipelines {
+    const SOURCE: &'static str = include_str!("shaders/basic.wgsl");
+
+    fn new(layout: wgpu::PipelineLayout, format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        Self {
+            layout,
+            format,
+            sample_count,
+            variants: ShaderVariantCache::new(),
+            opaque: PipelineVariantCache::new(),
+            transparent: PipelineVariantCache::new(),
+            overlay: PipelineVariantCache::new(),
+        }
+    }
+
+    /// 绘制项在某个通道使用的管线
+    fn get(&mut self, device: &wgpu::Device, item: &DrawItem, pass: DrawPass) -> EngineResult<Arc<wgpu::RenderPipeline>> {
+        let key = item.pipeline_key();
+        let variant = self.variants.get_or_preprocess(key.variant, Self::SOURCE)?;
+        let (layout, format, sample_count) = (&self.layout, self.format, self.sample_count);
+        let cache = match pass {
+            DrawPass::Opaque => &mut self.opaque,
+            DrawPass::Transparent => &mut self.transparent,
+            DrawPass::Overlay => &mut self.overlay,
+        };
+        Ok(cache.get_or_create(device, &variant, key.cull_mode, key.front_face, |device, module, key| {
+            Self::create(device, layout, module, key, format, sample_count, pass)
+        }))
+    }
+
+    fn create(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        module: &wgpu::ShaderModule,
+        key: &PipelineVariantKey,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        pass: DrawPass,
+    ) -> wgpu::RenderPipeline {
+        let label = key.variant.keywords.label(&key.variant.shader_name);
+        let color_targets = [Some(wgpu::ColorTargetState {
+            format,
+            blend: Some(wgpu::BlendState::REPLACE),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let accumulation_targets = OitRenderer::accumulation_targets();
+        let depth_stencil = |depth_write_enabled| wgpu::DepthStencilState {
+            format: MAIN_DEPTH_FORMAT,
+            depth_write_enabled,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        };
+        // 透明物体：深度只读，按OIT累积混合，无需排序；覆盖层画在合成后的单采样场景颜色上
+        let (entry_point, targets, depth_stencil, sample_count): (_, &[_], _, _) = match pass {
+            DrawPass::Opaque => ("fs_main", &color_targets, Some(depth_stencil(true)), sample_count),
+            DrawPass::Transparent => ("fs_accumulate", &accumulation_targets, Some(depth_stencil(false)), sample_count),
+            DrawPass::Overlay => ("fs_main", &color_targets, None, 1),
+        };
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&label),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module,
+                entry_point,
+                targets,
+            }),
+            primitive: key.primitive_state(),
+            depth_stencil,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        })
+    }
+}
+
+/// 单个相机本帧的渲染参数
+#[derive(Debug, Clone)]
+pub struct CameraView {
+    pub view_projection: glam::Mat4,
+    /// 相机世界位置
+    pub position: glam::Vec3,
+    pub skybox: Skybox,
+    pub fog: Fog,
+    /// 视口与裁剪矩形
+    pub viewport: ViewportRect,
 }
 
 impl RenderSystem {
@@ -129,76 +348,118 @@ impl RenderSystem {
 
         surface.configure(&device, &config);
 
-        // 创建着色器
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("基础着色器"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/basic.wgsl").into()),
+        let supported_present_modes = surface_caps.present_modes;
+        Self::with_device(&adapter, device, queue, config, supported_present_modes, render_config, Some((surface, window)))
+    }
+
+    /// 创建不需要窗口的离屏渲染系统，用于测试和无窗口工具
+    ///
+    /// 场景渲染到 `render_targets()` 的场景颜色缓冲(启用FXAA/TAA时为抗锯齿输出)，不复制到交换链。
+    pub async fn new_headless(width: u32, height: u32, render_config: &RenderConfig) -> EngineResult<Self> {
+        let (adapter, device, queue) =
+            request_headless_device("离屏渲染设备", wgpu::Features::empty(), wgpu::Limits::downlevel_defaults()).await?;
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        Self::with_device(&adapter, device, queue, config, vec![wgpu::PresentMode::Fifo], render_config, None)
+    }
+
+    /// 在已创建的设备上创建渲染资源，surface为None时离屏渲染
+    fn with_device(
+        adapter: &wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        config: wgpu::SurfaceConfiguration,
+        supported_present_modes: Vec<wgpu::PresentMode>,
+        render_config: &RenderConfig,
+        surface: Option<(wgpu::Surface<'static>, Arc<Window>)>,
+    ) -> EngineResult<Self> {
+        let size = winit::dpi::PhysicalSize::new(config.width, config.height);
+        let (surface, window) = surface.unzip();
+
+        // 抗锯齿：MSAA使用多重采样管线，FXAA/TAA作为后处理通道
+        let mut anti_aliasing = render_config.anti_aliasing;
+        let format_features = adapter.get_texture_format_features(config.format);
+        if !format_features.flags.sample_count_supported(anti_aliasing.sample_count()) {
+            log::warn!("不支持{}倍MSAA，改为FXAA", anti_aliasing.sample_count());
+            anti_aliasing = AntiAliasing::Fxaa;
+        }
+        let sample_count = anti_aliasing.sample_count();
+        let targets = RenderTargets::new(&device, config.width, config.height, config.format, sample_count);
+        let blitter = TargetBlitter::new(&device, config.format);
+        let anti_aliasing_target = Self::create_anti_aliasing_target(&device, anti_aliasing, config.format, config.width, config.height);
+        let fxaa_renderer = (anti_aliasing == AntiAliasing::Fxaa).then(|| FxaaRenderer::new(&device, config.format));
+        let taa_renderer = (anti_aliasing == AntiAliasing::Taa)
+            .then(|| TaaRenderer::new(&device, config.format, config.width.max(1), config.height.max(1)));
+        let mut post_processing = PostProcessingConfig::default();
+        post_processing.set_anti_aliasing(anti_aliasing);
+
+        // 每次绘制的变换和颜色通过动态偏移绑定
+        let draw_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("绘制绑定组布局"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: wgpu::BufferSize::new(std::mem::size_of::<DrawUniform>() as u64),
+                },
+                count: None,
+            }],
+        });
+        // 每个相机的位置和雾参数
+        let fog_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("雾绑定组布局"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: wgpu::BufferSize::new(std::mem::size_of::<CameraFogUniform>() as u64),
+                },
+                count: None,
+            }],
         });
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
+        let draw_uniform_stride = (std::mem::size_of::<DrawUniform>() as wgpu::BufferAddress).div_ceil(alignment) * alignment;
 
-        // 创建渲染管线
+        // 网格绘制管线在首次绘制某个材质变体时创建
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("渲染管线布局"),
-                bind_group_layouts: &[],
+                bind_group_layouts: &[&draw_bind_group_layout, &fog_bind_group_layout],
                 push_constant_ranges: &[],
             });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("渲染管线"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[Vertex::desc()],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-        });
+        let pipelines = DrawPipelines::new(render_pipeline_layout, config.format, sample_count);
 
-        // 创建测试三角形
-        let vertices = &[
-            Vertex { position: [0.0, 0.5, 0.0], color: [1.0, 0.0, 0.0], tex_coords: [0.5, 0.0] },
-            Vertex { position: [-0.5, -0.5, 0.0], color: [0.0, 1.0, 0.0], tex_coords: [0.0, 1.0] },
-            Vertex { position: [0.5, -0.5, 0.0], color: [0.0, 0.0, 1.0], tex_coords: [1.0, 1.0] },
-        ];
-        let indices = &[0u16, 1, 2];
-
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("顶点缓冲"),
-            contents: bytemuck::cast_slice(vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
+        // 内置网格，与缩略图渲染器的名称一致
+        let mut meshes = HashMap::new();
+        meshes.insert("cube".to_string(), GpuMesh::upload(&device, &Mesh::cube()));
+        meshes.insert("sphere".to_string(), GpuMesh::upload(&device, &Mesh::sphere(0.5, 24)));
+        meshes.insert("plane".to_string(), GpuMesh::upload(&device, &Mesh::plane(1.0)));
 
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("索引缓冲"),
-            contents: bytemuck::cast_slice(indices),
-            usage: wgpu::BufferUsages::INDEX,
-        });
+        let skybox_renderer = SkyboxRenderer::new(&device, config.format, Some(MAIN_DEPTH_FORMAT), sample_count);
+        let oit_renderer = OitRenderer::new(&device, config.format, config.width, config.height, sample_count);
+        let debug_draw_renderer = DebugDrawRenderer::new(&device, config.format, sample_count);
 
-        let num_indices = indices.len() as u32;
+        let mut render_graph = RenderGraph::default_pipeline(config.width, config.height);
+        render_graph.insert_anti_aliasing_pass(anti_aliasing, config.width, config.height);
+        let compiled_graph = render_graph.compile()?;
+
+        let frame_bind_group_layout = FrameResources::bind_group_layout(&device);
+        let frames = FrameRing::new(render_config.frames_in_flight, |index| {
+            FrameResources::new(&device, &frame_bind_group_layout, index)
+        });
 
         Ok(Self {
             surface,
@@ -207,77 +468,865 @@ impl RenderSystem {
             config,
             size,
             window,
-            render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            num_indices,
+            pipelines,
+            draw_bind_group_layout,
+            fog_bind_group_layout,
+            draw_uniform_stride,
+            meshes,
             clear_color: wgpu::Color {
                 r: 0.1,
                 g: 0.2,
                 b: 0.3,
                 a: 1.0,
             },
+            skybox_renderer,
+            occlusion_culler: OcclusionCuller::new(OcclusionConfig::default()),
+            render_graph,
+            compiled_graph,
+            anti_aliasing,
+            sample_count,
+            targets,
+            blitter,
+            anti_aliasing_target,
+            fxaa_renderer,
+            taa_renderer,
+            taa_camera: TaaCamera::default(),
+            custom_passes: Vec::new(),
+            frames,
+            frame_bind_group_layout,
+            post_processing,
+            oit_renderer,
+            supported_present_modes,
+            render_stats: RenderStatsRecorder::new(),
+            thumbnail_renderer: None,
+            thumbnail_cache: ThumbnailCache::new(),
+            camera_viewports: Vec::new(),
+            materials: HashMap::new(),
+            draw_list: DrawList::default(),
+            skin_palettes: HashMap::new(),
+            asset_bind_groups: GpuAssetCache::new(),
+            debug_draw: DebugDraw::default(),
+            debug_draw_renderer,
         })
     }
 
+    /// 每帧资源环
+    pub fn frame_ring(&self) -> &FrameRing<FrameResources> {
+        &self.frames
+    }
+
+    /// 当前帧的每帧资源
+    pub fn frame_resources(&self) -> &FrameResources {
+        self.frames.current()
+    }
+
+    /// 每帧资源绑定组的布局，自定义管线据此绑定 `FrameResources::bind_group`
+    pub fn frame_bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.frame_bind_group_layout
+    }
+
+    pub fn frames_in_flight(&self) -> u32 {
+        self.frames.frames_in_flight()
+    }
+
+    /// 主通道的颜色、解析与深度缓冲
+    pub fn render_targets(&self) -> &RenderTargets {
+        &self.targets
+    }
+
+    /// 主通道绘制的颜色缓冲(MSAA时为多重采样缓冲)
+    pub fn color_target(&self) -> &wgpu::TextureView {
+        self.targets.color_view()
+    }
+
+    /// 主通道的深度缓冲
+    pub fn depth_target(&self) -> &wgpu::TextureView {
+        self.targets.depth_view()
+    }
+
+    /// 解析后的单采样场景颜色，后处理和自定义通道可作为纹理采样
+    pub fn resolve_target(&self) -> &wgpu::TextureView {
+        self.targets.resolve_view()
+    }
+
+    /// 添加在主通道之后、后处理之前执行的自定义通道，按添加顺序执行
+    pub fn add_custom_pass(&mut self, pass: Box<dyn CustomRenderPass>) {
+        self.custom_passes.push(pass);
+    }
+
+    /// 按名称移除自定义通道
+    pub fn remove_custom_pass(&mut self, name: &str) -> bool {
+        let count = self.custom_passes.len();
+        self.custom_passes.retain(|pass| pass.name() != name);
+        self.custom_passes.len() != count
+    }
+
+    /// 已添加的自定义通道名称
+    pub fn custom_pass_names(&self) -> Vec<&str> {
+        self.custom_passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// 当前抗锯齿方式
+    pub fn anti_aliasing(&self) -> AntiAliasing {
+        self.anti_aliasing
+    }
+
+    /// 主通道管线采样数
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// 后处理配置
+    pub fn post_processing(&self) -> &PostProcessingConfig {
+        &self.post_processing
+    }
+
+    /// 获取透明物体渲染器
+    pub fn oit_renderer(&self) -> &OitRenderer {
+        &self.oit_renderer
+    }
+
+    /// 运行时切换呈现模式(垂直同步)，不支持的模式回退到Fifo
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) -> wgpu::PresentMode {
+        let resolved = Self::resolve_present_mode(present_mode, &self.supported_present_modes);
+        if resolved != present_mode {
+            log::warn!("不支持呈现模式{:?}，回退到{:?}", present_mode, resolved);
+        }
+        if resolved != self.config.present_mode {
+            self.config.present_mode = resolved;
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+        }
+        resolved
+    }
+
+    /// 在支持的呈现模式中选择，Fifo为所有平台都保证支持的模式
+    pub fn resolve_present_mode(requested: wgpu::PresentMode, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+        match requested {
+            wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync => requested,
+            _ if supported.contains(&requested) => requested,
+            _ => wgpu::PresentMode::Fifo,
+        }
+    }
+
+    /// 开启/关闭垂直同步
+    pub fn set_vsync(&mut self, vsync: bool) -> wgpu::PresentMode {
+        self.set_present_mode(if vsync { wgpu::PresentMode::Fifo } else { wgpu::PresentMode::Immediate })
+    }
+
+    /// 当前呈现模式
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.config.present_mode
+    }
+
+    /// Surface支持的呈现模式
+    pub fn supported_present_modes(&self) -> &[wgpu::PresentMode] {
+        &self.supported_present_modes
+    }
+
     /// 调整渲染大小
     pub fn resize(&mut self, new_width: u32, new_height: u32) -> EngineResult<()> {
         if new_width > 0 && new_height > 0 {
             self.size = winit::dpi::PhysicalSize::new(new_width, new_height);
             self.config.width = new_width;
             self.config.height = new_height;
-            self.surface.configure(&self.device, &self.config);
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+            self.targets = RenderTargets::new(&self.device, new_width, new_height, self.config.format, self.sample_count);
+            self.oit_renderer.resize(&self.device, new_width, new_height);
+            self.anti_aliasing_target =
+                Self::create_anti_aliasing_target(&self.device, self.anti_aliasing, self.config.format, new_width, new_height);
+            if let Some(taa_renderer) = &mut self.taa_renderer {
+                taa_renderer.resize(&self.device, new_width, new_height);
+            }
         }
         Ok(())
     }
 
+    /// FXAA/TAA的输出缓冲，其他抗锯齿方式不需要
+    fn create_anti_aliasing_target(
+        device: &wgpu::Device,
+        anti_aliasing: AntiAliasing,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Option<RenderTarget> {
+        anti_aliasing
+            .post_effect()
+            .map(|_| RenderTarget::new(device, width.max(1), height.max(1), format, Some("抗锯齿输出")))
+    }
+
     /// 开始一帧渲染
     pub fn begin_frame(&mut self) -> EngineResult<()> {
+        self.render_stats.begin_frame();
         Ok(())
     }
 
+    /// 本帧的绘制调用次数
+    pub fn draw_calls(&self) -> u32 {
+        self.render_stats.stats().draw_calls
+    }
+
+    /// 本帧的渲染统计(绘制调用、三角形、顶点与状态切换)
+    pub fn render_stats(&self) -> &RenderStats {
+        self.render_stats.stats()
+    }
+
     /// 渲染场景
+    ///
+    /// 主相机和设置了视口的相机按 `render_order` 依次渲染到各自的视口，每个相机单独提交，
+    /// 保证天空盒等uniform互不覆盖。整个目标只清屏一次，颜色取第一个相机的天空盒。
     pub fn render_scene(&mut self, scene: &Scene, ecs_world: &ECSWorld) -> EngineResult<()> {
-        let output = self.surface
-            .get_current_texture()
-            .map_err(|e| EngineError::RenderError(format!("获取表面纹理失败: {}", e)))?;
+        let mut camera_views = Self::collect_camera_views(ecs_world, self.config.width, self.config.height);
+        self.camera_viewports = camera_views.iter().map(|camera_view| camera_view.viewport).collect();
+        let camera_position = camera_views.first().map_or(glam::Vec3::ZERO, |camera_view| camera_view.position);
+        self.draw_list = DrawList::collect(ecs_world.world(), &self.materials, camera_position);
+        // 各视口的可见集合不同，只在单相机时做遮挡剔除
+        if let [camera_view] = camera_views.as_slice() {
+            let culled = self.cull_draw_list(camera_view.view_projection);
+            self.render_stats.occlusion_culled(culled as u32);
+        }
+        // TAA：记录第一个相机未抖动的矩阵用于重投影，再给所有相机加上本帧的亚像素抖动
+        if self.taa_renderer.is_some() {
+            if let Some(camera_view) = camera_views.first() {
+                self.taa_camera.advance_view_projection(camera_view.view_projection);
+            }
+            let jitter = self.taa_camera.jitter(&self.post_processing.taa, self.config.width, self.config.height);
+            for camera_view in &mut camera_views {
+                camera_view.view_projection = TaaResolver::jitter_projection(camera_view.view_projection, jitter);
+            }
+        }
 
+        // 轮换到下一套每帧资源，GPU仍在使用时在此等待
+        self.frames.begin_frame(&self.device);
+        let (width, height) = (self.config.width as f32, self.config.height as f32);
+        self.frames.current().write_uniform(&self.queue, &FrameUniform {
+            resolution: [width, height, 1.0 / width, 1.0 / height],
+            frame: [self.frames.frame_number() as u32, self.frames.current_index() as u32, 0, 0],
+        });
+
+        // 离屏渲染时没有交换链，结果留在场景颜色缓冲中
+        let output = match &self.surface {
+            Some(surface) => Some(
+                surface
+                    .get_current_texture()
+                    .map_err(|e| EngineError::RenderError(format!("获取表面纹理失败: {}", e)))?,
+            ),
+            None => None,
+        };
         let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+            .as_ref()
+            .map(|output| output.texture.create_view(&wgpu::TextureViewDescriptor::default()));
 
-        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        let mut encoder = self.create_encoder();
+        let mut oit_accumulated = false;
+        let mut anti_aliased = false;
+        // 按渲染图编译出的顺序执行各通道，名称与自定义通道相同的图通道由该自定义通道执行
+        let pass_names: Vec<String> = self.compiled_graph.passes().iter().map(|pass| pass.name.clone()).collect();
+        for pass_name in &pass_names {
+            match pass_name.as_str() {
+                "main" => {
+                    // 相机通道各自提交，先提交此前记录的命令以保持顺序
+                    let pending = std::mem::replace(&mut encoder, self.create_encoder());
+                    self.queue.submit(std::iter::once(pending.finish()));
+                    self.render_main_pass(&camera_views, &mut encoder)?;
+                    // 未加入渲染图的自定义通道在主通道之后、透明合成之前
+                    let graph_passes = &pass_names;
+                    self.run_custom_passes(&mut encoder, |name| !graph_passes.iter().any(|pass| pass == name));
+                }
+                "transparent" => oit_accumulated = self.render_transparent_pass(&camera_views, &mut encoder)?,
+                "oit_composite" => {
+                    if oit_accumulated {
+                        self.render_stats.set_render_target("主颜色缓冲");
+                        self.oit_renderer.composite(&mut encoder, self.targets.resolve_view());
+                        self.render_stats.set_shader("OIT合成");
+                        self.render_stats.set_textures("OIT累积缓冲");
+                        self.render_stats.draw(3, 1);
+                    }
+                }
+                "overlay" => self.render_overlay_pass(&camera_views, &mut encoder)?,
+                "fxaa" | "taa" => anti_aliased = self.render_anti_aliasing_pass(&mut encoder),
+                // 复制到交换链不属于场景绘制，不计入统计
+                "post" => {
+                    if let Some(view) = &view {
+                        let source = match &self.anti_aliasing_target {
+                            Some(target) if anti_aliased => &target.view,
+                            _ => self.targets.resolve_view(),
+                        };
+                        self.blitter.blit(&self.device, &mut encoder, source, view);
+                    }
+                }
+                name => self.run_custom_passes(&mut encoder, |pass| pass == name),
+            }
+        }
+
+        let submission = self.queue.submit(std::iter::once(encoder.finish()));
+        self.frames.end_frame(&self.queue, submission);
+        self.debug_draw.end_frame();
+        if let Some(output) = output {
+            output.present();
+        }
+
+        Ok(())
+    }
+
+    fn create_encoder(&self) -> wgpu::CommandEncoder {
+        self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("渲染编码器"),
+        })
+    }
+
+    /// 主通道：各相机的不透明绘制，调试线段按第一个相机绘制在其上
+    fn render_main_pass(&mut self, camera_views: &[CameraView], encoder: &mut wgpu::CommandEncoder) -> EngineResult<()> {
+        if camera_views.is_empty() {
+            self.render_camera_pass(None, true)?;
+        }
+        for (i, camera_view) in camera_views.iter().enumerate() {
+            self.render_camera_pass(Some(camera_view), i == 0)?;
+        }
+
+        if let Some(camera_view) = camera_views.first() {
+            self.render_stats.set_render_target("主颜色缓冲");
+            let vertex_count = self.debug_draw_renderer.render(
+                &self.device,
+                &self.queue,
+                encoder,
+                &self.targets,
+                &self.debug_draw,
+                camera_view.view_projection,
+            );
+            if vertex_count > 0 {
+                self.render_stats.set_shader("调试绘制");
+                self.render_stats.set_textures("");
+                self.render_stats.draw(vertex_count, 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// FXAA/TAA：读取合成后的场景颜色写入抗锯齿输出，返回是否执行
+    fn render_anti_aliasing_pass(&mut self, encoder: &mut wgpu::CommandEncoder) -> bool {
+        let Some(target) = &self.anti_aliasing_target else {
+            return false;
+        };
+        let (width, height) = self.targets.size();
+        self.render_stats.set_render_target("抗锯齿输出");
+        self.render_stats.set_textures("场景颜色缓冲");
+        if let Some(fxaa_renderer) = &self.fxaa_renderer {
+            fxaa_renderer.render(&self.device, encoder, &self.post_processing.fxaa, width, height, self.targets.resolve_view(), &target.view);
+            self.render_stats.set_shader("FXAA");
+            self.render_stats.draw(3, 1);
+        } else if let Some(taa_renderer) = &self.taa_renderer {
+            taa_renderer.render(
+                &self.device,
+                encoder,
+                &self.post_processing.taa,
+                &self.taa_camera,
+                self.targets.resolve_view(),
+                self.targets.depth_view(),
+                &target.view,
+            );
+            // 解析到历史缓冲，再复制到输出
+            self.render_stats.set_shader("TAA");
+            self.render_stats.draw(3, 1);
+            self.render_stats.draw(3, 1);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// 执行名称满足filter的自定义通道
+    fn run_custom_passes(&mut self, encoder: &mut wgpu::CommandEncoder, filter: impl Fn(&str) -> bool) {
+        let context = CustomPassContext {
+            device: &self.device,
+            queue: &self.queue,
+            targets: &self.targets,
+            frame: self.frames.current(),
+        };
+        for pass in self.custom_passes.iter_mut().filter(|pass| filter(pass.name())) {
+            self.render_stats.set_render_target(pass.name());
+            pass.execute(&context, encoder);
+        }
+    }
+
+    /// 透明物体无需排序：按各相机累积到OIT缓冲，返回是否有透明绘制(没有时跳过合成)
+    fn render_transparent_pass(&mut self, camera_views: &[CameraView], encoder: &mut wgpu::CommandEncoder) -> EngineResult<bool> {
+        let mut draws = Vec::new();
+        for (viewport, fog_bind_group, (bind_group, items)) in self.camera_draws(camera_views, self.draw_list.transparent()) {
+            let pipelines = Self::item_pipelines(&mut self.pipelines, &self.device, &items, DrawPass::Transparent)?;
+            draws.push(CameraDraws { viewport, fog_bind_group, bind_group, items, pipelines });
+        }
+        if draws.is_empty() {
+            return Ok(false);
+        }
+
+        self.render_stats.set_render_target("OIT累积缓冲");
+        let mut render_pass = self.oit_renderer.begin_accumulation(encoder, Some(self.targets.depth_view()));
+        Self::submit_camera_draws(&mut render_pass, &mut self.render_stats, &self.meshes, &draws, self.draw_uniform_stride);
+        Ok(true)
+    }
+
+    /// 覆盖层：OIT合成之后按渲染队列顺序画在场景颜色上，不做深度测试
+    fn render_overlay_pass(&mut self, camera_views: &[CameraView], encoder: &mut wgpu::CommandEncoder) -> EngineResult<()> {
+        let mut draws = Vec::new();
+        for (viewport, fog_bind_group, (bind_group, items)) in self.camera_draws(camera_views, self.draw_list.overlay()) {
+            let pipelines = Self::item_pipelines(&mut self.pipelines, &self.device, &items, DrawPass::Overlay)?;
+            draws.push(CameraDraws { viewport, fog_bind_group, bind_group, items, pipelines });
+        }
+        if draws.is_empty() {
+            return Ok(());
+        }
+
+        self.render_stats.set_render_target("主颜色缓冲");
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("覆盖层通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: self.targets.resolve_view(),
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        Self::submit_camera_draws(&mut render_pass, &mut self.render_stats, &self.meshes, &draws, self.draw_uniform_stride);
+        Ok(())
+    }
+
+    /// 各相机要画的绘制项及其uniform与雾绑定组，没有相机时不设视口、不启用雾
+    #[allow(clippy::type_complexity)]
+    fn camera_draws<'a>(
+        &self,
+        camera_views: &[CameraView],
+        items: &'a [DrawItem],
+    ) -> Vec<(Option<ViewportRect>, wgpu::BindGroup, (wgpu::BindGroup, Vec<&'a DrawItem>))> {
+        if camera_views.is_empty() {
+            self.draw_bind_group(glam::Mat4::IDENTITY, items)
+                .map(|draws| (None, self.fog_bind_group(None), draws))
+                .into_iter()
+                .collect()
+        } else {
+            camera_views
+                .iter()
+                .filter_map(|camera_view| {
+                    self.draw_bind_group(camera_view.view_projection, items)
+                        .map(|draws| (Some(camera_view.viewport), self.fog_bind_group(Some(camera_view)), draws))
+                })
+                .collect()
+        }
+    }
+
+    /// 依次设置各相机的视口和雾绑定组并提交其绘制项
+    fn submit_camera_draws<'a>(
+        render_pass: &mut wgpu::RenderPass<'a>,
+        render_stats: &mut RenderStatsRecorder,
+        meshes: &'a HashMap<String, GpuMesh>,
+        draws: &'a [CameraDraws],
+        stride: wgpu::BufferAddress,
+    ) {
+        for draw in draws {
+            if let Some(rect) = draw.viewport {
+                render_pass.set_viewport(rect.x as f32, rect.y as f32, rect.width as f32, rect.height as f32, 0.0, 1.0);
+                render_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+            }
+            render_pass.set_bind_group(1, &draw.fog_bind_group, &[]);
+            render_stats.set_bind_group(1, draw.fog_bind_group.global_id());
+            Self::submit_draws(render_pass, render_stats, meshes, &draw.bind_group, &draw.items, &draw.pipelines, stride);
+        }
+    }
+
+    /// 渲染一个相机的不透明通道，camera为None时不设视口、不画天空盒
+    fn render_camera_pass(&mut self, camera: Option<&CameraView>, clear: bool) -> EngineResult<()> {
+        let draw_skybox = match camera {
+            Some(camera) if camera.skybox.needs_draw() => {
+                self.skybox_renderer.update(&self.device, &self.queue, &camera.skybox, camera.view_projection);
+                true
+            }
+            _ => false,
+        };
+        let load = if clear {
+            let clear_color = match camera {
+                Some(camera) => {
+                    let color = camera.skybox.clear_color();
+                    wgpu::Color { r: color.x as f64, g: color.y as f64, b: color.z as f64, a: color.w as f64 }
+                }
+                None => self.clear_color,
+            };
+            wgpu::LoadOp::Clear(clear_color)
+        } else {
+            wgpu::LoadOp::Load
+        };
+
+        let view_projection = camera.map_or(glam::Mat4::IDENTITY, |camera| camera.view_projection);
+        let opaque = self.draw_bind_group(view_projection, self.draw_list.opaque());
+        let pipelines = match &opaque {
+            Some((_, items)) => Self::item_pipelines(&mut self.pipelines, &self.device, items, DrawPass::Opaque)?,
+            None => Vec::new(),
+        };
+        let fog_bind_group = self.fog_bind_group(camera);
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("相机渲染编码器"),
         });
 
         {
+            self.render_stats.set_render_target("主颜色缓冲");
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("渲染通道"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    // MSAA时渲染到多重采样缓冲并解析到场景颜色缓冲
+                    view: self.targets.color_view(),
+                    resolve_target: self.targets.color_resolve_target(),
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(self.clear_color),
+                        load,
                         store: wgpu::StoreOp::Store,
                     },
                 })],
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: self.targets.depth_view(),
+                    depth_ops: Some(wgpu::Operations {
+                        load: if clear { wgpu::LoadOp::Clear(1.0) } else { wgpu::LoadOp::Load },
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
                 occlusion_query_set: None,
                 timestamp_writes: None,
             });
 
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+            if let Some(camera) = camera {
+                let rect = camera.viewport;
+                render_pass.set_viewport(rect.x as f32, rect.y as f32, rect.width as f32, rect.height as f32, 0.0, 1.0);
+                render_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+            }
+
+            // 天空盒在不透明几何体之前绘制
+            if draw_skybox {
+                self.skybox_renderer.draw(&mut render_pass);
+                self.render_stats.set_shader("天空盒");
+                self.render_stats.set_textures("天空盒");
+                self.render_stats.draw(3, 1);
+            }
+
+            // 不透明绘制按绘制列表的顺序提交
+            if let Some((bind_group, items)) = &opaque {
+                render_pass.set_bind_group(1, &fog_bind_group, &[]);
+                self.render_stats.set_bind_group(1, fog_bind_group.global_id());
+                Self::submit_draws(&mut render_pass, &mut self.render_stats, &self.meshes, bind_group, items, &pipelines, self.draw_uniform_stride);
+            }
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        Ok(())
+    }
 
+    /// 依次取得绘制项的管线，首次出现的变体在此编译
+    fn item_pipelines(
+        pipelines: &mut DrawPipelines,
+        device: &wgpu::Device,
+        items: &[&DrawItem],
+        pass: DrawPass,
+    ) -> EngineResult<Vec<Arc<wgpu::RenderPipeline>>> {
+        items.iter().map(|item| pipelines.get(device, item, pass)).collect()
+    }
+
+    /// 为绘制项写入uniform并创建绑定组，返回绑定组和有网格可画的绘制项
+    fn draw_bind_group<'a>(&self, view_projection: glam::Mat4, items: &'a [DrawItem]) -> Option<(wgpu::BindGroup, Vec<&'a DrawItem>)> {
+        let items: Vec<&DrawItem> = items
+            .iter()
+            .filter(|item| self.meshes.get(&item.mesh_name).is_some_and(|mesh| mesh.index_count > 0))
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+
+        let stride = self.draw_uniform_stride as usize;
+        let mut contents = vec![0u8; stride * items.len()];
+        for (i, item) in items.iter().enumerate() {
+            let uniform = DrawUniform {
+                model_view_projection: (view_projection * item.model).to_cols_array_2d(),
+                model: item.model.to_cols_array_2d(),
+                color: item.color.to_array(),
+            };
+            contents[i * stride..i * stride + std::mem::size_of::<DrawUniform>()]
+                .copy_from_slice(bytemuck::bytes_of(&uniform));
+        }
+        let buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("绘制Uniform"),
+            contents: &contents,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("绘制绑定组"),
+            layout: &self.draw_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &buffer,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(std::mem::size_of::<DrawUniform>() as u64),
+                }),
+            }],
+        });
+        Some((bind_group, items))
+    }
+
+    /// 上传相机的位置和雾参数，camera为None时不启用雾
+    fn fog_bind_group(&self, camera: Option<&CameraView>) -> wgpu::BindGroup {
+        let buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("相机雾Uniform"),
+            contents: bytemuck::bytes_of(&CameraFogUniform::new(camera)),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("相机雾绑定组"),
+            layout: &self.fog_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() }],
+        })
+    }
+
+    /// 按顺序提交绘制项，第i项使用uniform缓冲中的第i个槽位和第i个管线，相邻绘制项管线相同时不重复绑定
+    fn submit_draws<'a>(
+        render_pass: &mut wgpu::RenderPass<'a>,
+        render_stats: &mut RenderStatsRecorder,
+        meshes: &'a HashMap<String, GpuMesh>,
+        bind_group: &'a wgpu::BindGroup,
+        items: &[&DrawItem],
+        pipelines: &'a [Arc<wgpu::RenderPipeline>],
+        stride: wgpu::BufferAddress,
+    ) {
+        let mut bound_pipeline: Option<&Arc<wgpu::RenderPipeline>> = None;
+        for (i, (item, pipeline)) in items.iter().zip(pipelines).enumerate() {
+            if !bound_pipeline.is_some_and(|bound| Arc::ptr_eq(bound, pipeline)) {
+                render_pass.set_pipeline(pipeline);
+                render_stats.set_shader(pipeline.global_id());
+                bound_pipeline = Some(pipeline);
+            }
+            let mesh = &meshes[&item.mesh_name];
+            // 动态偏移不同但绑定组相同，不计为切换
+            render_pass.set_bind_group(0, bind_group, &[(i as wgpu::BufferAddress * stride) as wgpu::DynamicOffset]);
+            render_stats.set_bind_group(0, bind_group.global_id());
+            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+            render_stats.draw_indexed(mesh.index_count, mesh.vertex_count, 1);
+        }
+    }
+
+    /// 上传网格，mesh_name 相同的 MeshRenderer 使用该网格
+    pub fn register_mesh(&mut self, name: impl Into<String>, mesh: &Mesh) {
+        self.meshes.insert(name.into(), GpuMesh::upload(&self.device, mesh));
+    }
+
+    /// 释放已上传的网格
+    pub fn remove_mesh(&mut self, name: &str) -> bool {
+        self.meshes.remove(name).is_some()
+    }
+
+    /// 渲染场景或预制件的size×size预览图，结果按内容和尺寸缓存
+    pub fn render_thumbnail(&mut self, source: ThumbnailSource, size: u32) -> EngineResult<AssetHandle<Texture>> {
+        let device = &self.device;
+        let queue = &self.queue;
+        let renderer = self.thumbnail_renderer.get_or_insert_with(|| ThumbnailRenderer::new(device));
+        self.thumbnail_cache
+            .get_or_render(source.cache_key(size), || renderer.render(device, queue, &source, size))
+    }
+
+    /// 缩略图渲染器(注册自定义网格)
+    pub fn thumbnail_renderer_mut(&mut self) -> &mut ThumbnailRenderer {
+        let device = &self.device;
+        self.thumbnail_renderer.get_or_insert_with(|| ThumbnailRenderer::new(device))
+    }
+
+    /// 缩略图缓存(内容变化后使缓存失效)
+    pub fn thumbnail_cache_mut(&mut self) -> &mut ThumbnailCache {
+        &mut self.thumbnail_cache
+    }
+
+    /// 启用/禁用遮挡剔除
+    pub fn set_occlusion_culling(&mut self, enabled: bool) {
+        self.occlusion_culler.config.enabled = enabled;
+    }
+
+    /// 深度预通道 + HiZ遮挡剔除，在主通道之前调用，返回每个候选物体是否可见
+    ///
+    /// occluders按实心包围盒写入深度，只适合墙体等填满包围盒的物体。
+    pub fn cull_occluded(&mut self, view_projection: glam::Mat4, occluders: &[AABB], candidates: &[AABB]) -> Vec<bool> {
+        if !self.occlusion_culler.config.enabled {
+            return vec![true; candidates.len()];
+        }
+        self.occlusion_culler.run(view_projection, occluders, candidates)
+    }
+
+    /// 以不透明绘制的网格三角形为遮挡体剔除本帧绘制列表，返回剔除的数量
+    ///
+    /// 候选物体用世界包围盒测试；未注册网格的绘制项没有包围盒，保守地视为可见。
+    fn cull_draw_list(&mut self, view_projection: glam::Mat4) -> usize {
+        if !self.occlusion_culler.config.enabled || self.draw_list.is_empty() {
+            return 0;
+        }
+        let bounds: Vec<Option<AABB>> = self
+            .draw_list
+            .items()
+            .iter()
+            .map(|item| self.meshes.get(&item.mesh_name).map(|mesh| mesh.bounds.transform(&item.model)))
+            .collect();
+        // 包围盒会覆盖网格的空洞和凹处，遮挡体必须按实际三角形光栅化
+        let occluders: Vec<Vec<glam::Vec3>> = self
+            .draw_list
+            .opaque()
+            .iter()
+            .filter_map(|item| self.meshes.get(&item.mesh_name).map(|mesh| (item, mesh)))
+            .map(|(item, mesh)| mesh.occluder_triangles.iter().map(|&position| item.model.transform_point3(position)).collect())
+            .collect();
+        let candidates: Vec<AABB> = bounds.iter().flatten().copied().collect();
+
+        self.occlusion_culler.begin_frame(view_projection);
+        for triangles in &occluders {
+            self.occlusion_culler.add_occluder_triangles(triangles);
+        }
+        self.occlusion_culler.build_hiz();
+        let mut visible = self.occlusion_culler.cull(&candidates).into_iter();
+        let flags: Vec<bool> = bounds
+            .iter()
+            .map(|bounds| bounds.is_none() || visible.next().unwrap_or(true))
+            .collect();
+        self.draw_list.retain_visible(&flags)
+    }
+
+    /// 获取遮挡剔除器
+    pub fn occlusion_culler(&self) -> &OcclusionCuller {
+        &self.occlusion_culler
+    }
+
+    /// 获取遮挡剔除器的可变引用
+    pub fn occlusion_culler_mut(&mut self) -> &mut OcclusionCuller {
+        &mut self.occlusion_culler
+    }
+
+    /// 获取渲染图
+    pub fn render_graph(&self) -> &RenderGraph {
+        &self.render_graph
+    }
+
+    /// 替换渲染图，编译失败时保留原渲染图
+    pub fn set_render_graph(&mut self, graph: RenderGraph) -> EngineResult<()> {
+        self.compiled_graph = graph.compile()?;
+        self.render_graph = graph;
         Ok(())
     }
 
+    /// 修改渲染图后重新编译
+    pub fn rebuild_render_graph(&mut self, modify: impl FnOnce(&mut RenderGraph)) -> EngineResult<()> {
+        let mut graph = self.render_graph.clone();
+        modify(&mut graph);
+        self.set_render_graph(graph)
+    }
+
+    /// 获取编译后的渲染图(通道执行顺序与物理纹理分配)
+    pub fn compiled_render_graph(&self) -> &CompiledRenderGraph {
+        &self.compiled_graph
+    }
+
+    /// 本帧参与渲染的相机：主相机和设置了视口的相机，按渲染顺序排列
+    ///
+    /// 投影的长宽比取视口的长宽比；视口面积为0的相机被跳过。
+    pub fn collect_camera_views(ecs_world: &ECSWorld, target_width: u32, target_height: u32) -> Vec<CameraView> {
+        use specs::{Join, WorldExt};
+
+        let cameras = ecs_world.world().read_storage::<crate::ecs::Camera>();
+        let mut active: Vec<&crate::ecs::Camera> = cameras
+            .join()
+            .filter(|camera| camera.camera.is_main || camera.camera.viewport.is_some())
+            .collect();
+        active.sort_by_key(|camera| camera.camera.render_order);
+
+        active
+            .into_iter()
+            .filter_map(|camera| {
+                let viewport = camera.camera.viewport_rect(target_width, target_height)?;
+                let mut render_camera = camera.camera.clone();
+                render_camera.update_aspect_ratio(viewport.aspect_ratio());
+                Some(CameraView {
+                    view_projection: render_camera.view_projection_matrix(),
+                    position: render_camera.position,
+                    skybox: camera.skybox.clone(),
+                    fog: camera.fog,
+                    viewport,
+                })
+            })
+            .collect()
+    }
+
+    /// 注册材质，网格渲染器按名称引用
+    pub fn register_material(&mut self, material: Material) {
+        self.materials.insert(material.name.clone(), material);
+    }
+
+    pub fn material(&self, name: &str) -> Option<&Material> {
+        self.materials.get(name)
+    }
+
+    /// 本帧按渲染队列排序的网格绘制
+    pub fn draw_list(&self) -> &DrawList {
+        &self.draw_list
+    }
+
+    /// 上传蒙皮实例本帧的骨骼矩阵，首次上传时创建调色板
+    pub fn upload_skin_palette(&mut self, instance: &str, skinning_matrices: &[glam::Mat4]) -> &JointPalette {
+        let device = &self.device;
+        let palette = self
+            .skin_palettes
+            .entry(instance.to_string())
+            .or_insert_with(|| JointPalette::new(device, instance));
+        palette.update(&self.queue, skinning_matrices);
+        palette
+    }
+
+    pub fn skin_palette(&self, instance: &str) -> Option<&JointPalette> {
+        self.skin_palettes.get(instance)
+    }
+
+    /// 移除不再绘制的蒙皮实例的调色板
+    pub fn remove_skin_palette(&mut self, instance: &str) -> bool {
+        self.skin_palettes.remove(instance).is_some()
+    }
+
+    /// 资源对应的绑定组，首次使用或资源重新加载后调用create创建
+    pub fn asset_bind_group<T>(
+        &mut self,
+        handle: &AssetHandle<T>,
+        create: impl FnOnce(&wgpu::Device, &wgpu::Queue, &T) -> wgpu::BindGroup,
+    ) -> Option<&wgpu::BindGroup> {
+        let (device, queue) = (&self.device, &self.queue);
+        self.asset_bind_groups.get_or_create(handle, |resource| create(device, queue, resource))
+    }
+
+    /// 释放资源的绑定组(资源卸载时调用)
+    pub fn remove_asset_bind_group(&mut self, id: crate::assets::AssetId) -> bool {
+        self.asset_bind_groups.remove(id).is_some()
+    }
+
+    /// 调试绘制队列，在下一次 `render_scene` 中绘制
+    pub fn debug_draw(&self) -> &DebugDraw {
+        &self.debug_draw
+    }
+
+    pub fn debug_draw_mut(&mut self) -> &mut DebugDraw {
+        &mut self.debug_draw
+    }
+
+    /// 本帧各相机的视口(按渲染顺序)
+    pub fn camera_viewports(&self) -> &[ViewportRect] {
+        &self.camera_viewports
+    }
+
     /// 结束一帧渲染
     pub fn end_frame(&mut self) -> EngineResult<()> {
         Ok(())
@@ -288,3 +1337,275 @@ impl RenderSystem {
         self.clear_color = wgpu::Color { r: r as f64, g: g as f64, b: b as f64, a: a as f64 };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{MeshRenderer, Transform};
+    use crate::render::{headless_device, CullMode, FrontFace, RenderQueue};
+    use specs::Builder;
+
+    #[test]
+    fn unsupported_present_mode_falls_back_to_fifo() {
+        let supported = [wgpu::PresentMode::Fifo, wgpu::PresentMode::Mailbox];
+
+        assert_eq!(RenderSystem::resolve_present_mode(wgpu::PresentMode::Mailbox, &supported), wgpu::PresentMode::Mailbox);
+        assert_eq!(RenderSystem::resolve_present_mode(wgpu::PresentMode::Immediate, &supported), wgpu::PresentMode::Fifo);
+        assert_eq!(RenderSystem::resolve_present_mode(wgpu::PresentMode::FifoRelaxed, &[]), wgpu::PresentMode::Fifo);
+    }
+
+    #[test]
+    fn auto_present_modes_are_resolved_by_wgpu() {
+        assert_eq!(RenderSystem::resolve_present_mode(wgpu::PresentMode::AutoNoVsync, &[]), wgpu::PresentMode::AutoNoVsync);
+        assert_eq!(RenderSystem::resolve_present_mode(wgpu::PresentMode::AutoVsync, &[]), wgpu::PresentMode::AutoVsync);
+    }
+
+    fn spawn_camera(world: &mut ECSWorld, camera: crate::render::Camera) {
+        world
+            .create_entity()
+            .with(crate::ecs::Camera { camera, ..Default::default() })
+            .build();
+    }
+
+    #[test]
+    fn split_screen_cameras_render_into_their_halves_in_order() {
+        use crate::render::{Camera, Viewport};
+
+        let mut world = ECSWorld::new().unwrap();
+        let mut right = Camera::default().with_viewport(Viewport::split_horizontal(1, 2)).with_render_order(2);
+        right.is_main = false;
+        let left = Camera::default().with_viewport(Viewport::split_horizontal(0, 2)).with_render_order(1);
+        spawn_camera(&mut world, right.clone());
+        spawn_camera(&mut world, left);
+        // 既非主相机也没有视口的相机不参与渲染
+        let mut inactive = Camera::default();
+        inactive.is_main = false;
+        spawn_camera(&mut world, inactive);
+
+        let views = RenderSystem::collect_camera_views(&world, 1280, 720);
+        let viewports: Vec<ViewportRect> = views.iter().map(|view| view.viewport).collect();
+        assert_eq!(viewports, vec![
+            ViewportRect { x: 0, y: 0, width: 640, height: 720 },
+            ViewportRect { x: 640, y: 0, width: 640, height: 720 },
+        ]);
+
+        // 投影使用视口的长宽比
+        let mut expected = right;
+        expected.update_aspect_ratio(640.0 / 720.0);
+        assert!(views[1].view_projection.abs_diff_eq(expected.view_projection_matrix(), 1e-5));
+    }
+
+    #[test]
+    fn camera_fog_uniform_matches_shader_layout() {
+        assert_eq!(std::mem::size_of::<DrawUniform>(), 144);
+        assert_eq!(std::mem::size_of::<CameraFogUniform>(), 48);
+        assert_eq!(std::mem::offset_of!(CameraFogUniform, fog), 16);
+
+        let camera = CameraView {
+            view_projection: glam::Mat4::IDENTITY,
+            position: glam::Vec3::new(1.0, 2.0, 3.0),
+            skybox: Skybox::default(),
+            fog: Fog::linear(glam::Vec3::ONE, 5.0, 20.0),
+            viewport: ViewportRect { x: 0, y: 0, width: 1, height: 1 },
+        };
+        let uniform = CameraFogUniform::new(Some(&camera));
+        assert_eq!(uniform.camera_position, [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(uniform.fog, FogUniform::from(&camera.fog));
+        // 没有相机时不启用雾
+        assert_eq!(CameraFogUniform::new(None).fog.color[3], 0.0);
+    }
+
+    #[test]
+    fn basic_shader_with_fog_builds_pipeline_layout() {
+        let Some((_, device, _queue)) = headless_device(None) else {
+            eprintln!("跳过基础着色器测试: 没有可用的适配器");
+            return;
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/basic.wgsl").into()),
+        });
+        let uniform_layout = |visibility, has_dynamic_offset, size: usize| {
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: None,
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset,
+                        min_binding_size: wgpu::BufferSize::new(size as u64),
+                    },
+                    count: None,
+                }],
+            })
+        };
+        let draw_layout = uniform_layout(wgpu::ShaderStages::VERTEX, true, std::mem::size_of::<DrawUniform>());
+        let fog_layout = uniform_layout(wgpu::ShaderStages::FRAGMENT, false, std::mem::size_of::<CameraFogUniform>());
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&draw_layout, &fog_layout],
+            push_constant_ranges: &[],
+        });
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&layout),
+            vertex: wgpu::VertexState { module: &shader, entry_point: "vs_main", buffers: &[Vertex::desc()] },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::TextureFormat::Rgba8Unorm.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{:?}", error);
+    }
+
+    #[test]
+    fn zero_area_viewports_are_skipped() {
+        use crate::render::{Camera, Viewport};
+
+        let mut world = ECSWorld::new().unwrap();
+        spawn_camera(&mut world, Camera::default().with_viewport(Viewport::new(0.0, 0.0, 0.0, 1.0)));
+        assert!(RenderSystem::collect_camera_views(&world, 1280, 720).is_empty());
+    }
+
+    /// 无抗锯齿的离屏渲染系统，没有可用的图形适配器时返回None
+    fn headless_render_system() -> Option<RenderSystem> {
+        let config = RenderConfig { anti_aliasing: AntiAliasing::None, ..Default::default() };
+        match pollster::block_on(RenderSystem::new_headless(64, 64, &config)) {
+            Ok(render_system) => Some(render_system),
+            Err(error) => {
+                eprintln!("跳过: {}", error);
+                None
+            }
+        }
+    }
+
+    /// 渲染一帧，读回场景颜色缓冲的中心像素
+    fn render_center_pixel(render_system: &mut RenderSystem, world: &ECSWorld) -> [u8; 4] {
+        render_system.begin_frame().unwrap();
+        render_system.render_scene(&Scene::new("测试场景"), world).unwrap();
+
+        let (width, height) = render_system.targets.size();
+        let readback = render_system.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("场景颜色回读"),
+            size: (width * height * 4) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = render_system.create_encoder();
+        encoder.copy_texture_to_buffer(
+            render_system.targets.resolve_texture().as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * 4),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        render_system.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        render_system.device.poll(wgpu::Maintain::Wait);
+        let pixels = slice.get_mapped_range();
+        let center = ((height / 2 * width + width / 2) * 4) as usize;
+        [pixels[center], pixels[center + 1], pixels[center + 2], pixels[center + 3]]
+    }
+
+    #[test]
+    fn mesh_culling_follows_material() {
+        let Some(mut render_system) = headless_render_system() else {
+            return;
+        };
+        // 没有相机时模型矩阵直接变换到裁剪空间：平面绕X轴转到XY平面，正面(+Y)朝向屏幕外
+        let mut world = ECSWorld::new().unwrap();
+        let facing_away = Transform::new()
+            .with_position(glam::Vec3::new(0.0, 0.0, 0.5))
+            .with_rotation(glam::Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2));
+        world.create_entity().with(facing_away).with(MeshRenderer::new("plane", "平面材质")).build();
+
+        let mut draw_with = |material: Material| {
+            render_system.register_material(material);
+            render_center_pixel(&mut render_system, &world)[0]
+        };
+        let material = || Material::new("平面材质");
+        assert!(draw_with(material()) < 255, "默认材质剔除背面");
+        assert_eq!(draw_with(material().with_double_sided(true)), 255);
+        assert_eq!(draw_with(material().with_cull_mode(CullMode::Front)), 255);
+        // 顺时针为正面时同一个平面朝向屏幕，不再被剔除
+        assert_eq!(draw_with(material().with_front_face(FrontFace::Cw)), 255);
+        assert!(draw_with(material().with_front_face(FrontFace::Cw).with_cull_mode(CullMode::Front)) < 255);
+        assert_eq!(render_system.pipelines.opaque.len(), 5);
+    }
+
+    #[test]
+    fn render_stats_count_bound_pipelines_and_bind_groups() {
+        let Some(mut render_system) = headless_render_system() else {
+            return;
+        };
+        let mut world = ECSWorld::new().unwrap();
+        world.create_entity().with(MeshRenderer::new("cube", "石头")).build();
+        world.create_entity().with(MeshRenderer::new("plane", "草地")).build();
+        render_system.register_material(Material::new("石头"));
+        render_system.register_material(Material::new("草地").with_double_sided(true));
+
+        render_center_pixel(&mut render_system, &world);
+        let stats = render_system.render_stats().clone();
+        // 立方体36索引/24顶点，平面6索引/4顶点
+        assert_eq!(stats.draw_calls, 2);
+        assert_eq!(stats.triangles, 14);
+        assert_eq!(stats.vertices, 28);
+        assert_eq!(stats.render_targets_switches, 1);
+        // 剔除方式不同的两种材质各绑定一次管线
+        assert_eq!(stats.shader_switches, 2);
+        // 相机雾绑定组和绘制uniform绑定组各一次，逐项只改变动态偏移
+        assert_eq!(stats.texture_switches, 2);
+
+        // 两种材质使用同一管线时只绑定一次
+        render_system.register_material(Material::new("草地"));
+        render_center_pixel(&mut render_system, &world);
+        let stats = render_system.render_stats();
+        assert_eq!(stats.draw_calls, 2);
+        assert_eq!(stats.shader_switches, 1);
+        assert_eq!(stats.texture_switches, 2);
+    }
+
+    #[test]
+    fn overlay_draws_after_transparent_composite_without_depth_test() {
+        let Some(mut render_system) = headless_render_system() else {
+            return;
+        };
+        // 两个平面都朝向屏幕：红色不透明平面在前，绿色覆盖层在后
+        let mut world = ECSWorld::new().unwrap();
+        let facing_screen = |z: f32| {
+            Transform::new()
+                .with_position(glam::Vec3::new(0.0, 0.0, z))
+                .with_rotation(glam::Quat::from_rotation_x(std::f32::consts::FRAC_PI_2))
+        };
+        world.create_entity().with(facing_screen(0.2)).with(MeshRenderer::new("plane", "红")).build();
+        world.create_entity().with(facing_screen(0.8)).with(MeshRenderer::new("plane", "界面")).build();
+        render_system.register_material(Material::new("红").with_base_color(glam::Vec4::new(1.0, 0.0, 0.0, 1.0)));
+        render_system.register_material(
+            Material::new("界面")
+                .with_base_color(glam::Vec4::new(0.0, 1.0, 0.0, 1.0))
+                .with_render_queue(RenderQueue::OVERLAY),
+        );
+
+        let [red, green, ..] = render_center_pixel(&mut render_system, &world);
+        assert_eq!((red, green), (0, 255));
+        assert_eq!(render_system.draw_list().overlay().len(), 1);
+        assert!(render_system.draw_list().transparent().is_empty());
+        assert_eq!(render_system.render_stats().draw_calls, 2);
+    }
+}
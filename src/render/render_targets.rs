@@ -0,0 +1,422 @@
+//! 主渲染目标与自定义渲染通道
+//!
+//! 主通道先渲染到 `RenderTargets` 持有的离屏缓冲：MSAA时绘制到多重采样颜色缓冲并解析到单采样的
+//! 解析缓冲，否则直接绘制到解析缓冲；深度缓冲与颜色缓冲采样数相同。
+//! 主通道结束后依次执行注册的 `CustomRenderPass`，再进行透明合成等后处理，最后复制到交换链。
+
+use crate::render::FrameResources;
+use std::fmt;
+
+/// 主深度缓冲格式
+pub const MAIN_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// 主通道的颜色、解析和深度缓冲
+pub struct RenderTargets {
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    /// MSAA颜色缓冲，采样数为1时为None
+    msaa_view: Option<wgpu::TextureView>,
+    resolve_texture: wgpu::Texture,
+    resolve_view: wgpu::TextureView,
+    depth_texture: wgpu::Texture,
+    depth_view: wgpu::TextureView,
+}
+
+impl RenderTargets {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32, format: wgpu::TextureFormat, sample_count: u32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        let sample_count = sample_count.max(1);
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+
+        let msaa_view = (sample_count > 1).then(|| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some("MSAA颜色缓冲"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        });
+
+        let resolve_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("场景颜色缓冲"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let resolve_view = resolve_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // 单采样深度供TAA等通道读取；多重采样深度只作为附件，与MSAA颜色缓冲保持同类存储，
+        // 否则GL后端的帧缓冲不完整，解析缓冲得不到任何内容
+        let depth_usage = if sample_count > 1 {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+        };
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("场景深度缓冲"),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: MAIN_DEPTH_FORMAT,
+            usage: depth_usage,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            width,
+            height,
+            format,
+            sample_count,
+            msaa_view,
+            resolve_texture,
+            resolve_view,
+            depth_texture,
+            depth_view,
+        }
+    }
+
+    /// 主通道绘制的颜色缓冲(MSAA时为多重采样缓冲，否则与解析缓冲相同)
+    pub fn color_view(&self) -> &wgpu::TextureView {
+        self.msaa_view.as_ref().unwrap_or(&self.resolve_view)
+    }
+
+    /// 颜色附件需要的解析目标，MSAA时为解析缓冲，否则为None
+    pub fn color_resolve_target(&self) -> Option<&wgpu::TextureView> {
+        self.msaa_view.as_ref().map(|_| &self.resolve_view)
+    }
+
+    /// 单采样的场景颜色，可作为纹理采样
+    pub fn resolve_view(&self) -> &wgpu::TextureView {
+        &self.resolve_view
+    }
+
+    pub fn resolve_texture(&self) -> &wgpu::Texture {
+        &self.resolve_texture
+    }
+
+    /// 深度缓冲，采样数与颜色缓冲相同，只有单采样时可以作为纹理绑定
+    pub fn depth_view(&self) -> &wgpu::TextureView {
+        &self.depth_view
+    }
+
+    pub fn depth_texture(&self) -> &wgpu::Texture {
+        &self.depth_texture
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+}
+
+/// 自定义通道执行时可用的资源
+pub struct CustomPassContext<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    pub targets: &'a RenderTargets,
+    /// 本帧独占的每帧资源
+    pub frame: &'a FrameResources,
+}
+
+/// 在主通道与后处理之间执行的自定义渲染通道
+///
+/// 通道应以 `LoadOp::Load` 读写 `targets` 中的缓冲；管线的采样数和深度格式需与
+/// `RenderTargets::sample_count`、`MAIN_DEPTH_FORMAT` 一致。
+pub trait CustomRenderPass {
+    /// 通道名称，用于移除和统计
+    fn name(&self) -> &str;
+
+    /// 向encoder记录渲染命令
+    fn execute(&mut self, context: &CustomPassContext, encoder: &mut wgpu::CommandEncoder);
+}
+
+impl fmt::Debug for dyn CustomRenderPass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomRenderPass").field("name", &self.name()).finish()
+    }
+}
+
+/// 把场景颜色复制到交换链(格式相同、尺寸相同的全屏绘制)
+pub struct TargetBlitter {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl TargetBlitter {
+    pub fn new(device: &wgpu::Device, target_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("复制着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/blit.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("复制绑定组布局"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("复制管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("复制管线"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: target_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("复制采样器"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self { pipeline, bind_group_layout, sampler }
+    }
+
+    /// 把source绘制到target
+    pub fn blit(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("复制绑定组"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(source) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+            ],
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("复制到交换链"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    /// 适配器对测试颜色格式和深度格式都支持的MSAA采样数
+    fn msaa_sample_counts(adapter: &wgpu::Adapter) -> Vec<u32> {
+        [2, 4, 8]
+            .into_iter()
+            .filter(|&count| {
+                [FORMAT, MAIN_DEPTH_FORMAT]
+                    .iter()
+                    .all(|&format| adapter.get_texture_format_features(format).flags.sample_count_supported(count))
+            })
+            .collect()
+    }
+
+    /// 记录收到的目标尺寸，并把场景颜色填成纯色
+    struct FillPass {
+        color: wgpu::Color,
+        seen: Vec<((u32, u32), u32)>,
+    }
+
+    impl CustomRenderPass for FillPass {
+        fn name(&self) -> &str {
+            "fill"
+        }
+
+        fn execute(&mut self, context: &CustomPassContext, encoder: &mut wgpu::CommandEncoder) {
+            let targets = context.targets;
+            self.seen.push((targets.size(), targets.sample_count()));
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("填充通道"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: targets.color_view(),
+                    resolve_target: targets.color_resolve_target(),
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(self.color), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: targets.depth_view(),
+                    depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+        }
+    }
+
+    /// 把source复制到新的输出纹理并回读左上角像素
+    fn blit_and_read_pixel(device: &wgpu::Device, queue: &wgpu::Queue, mut encoder: wgpu::CommandEncoder, source: &wgpu::TextureView, width: u32, height: u32) -> [u8; 4] {
+        let output = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("测试输出"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let output_view = output.create_view(&wgpu::TextureViewDescriptor::default());
+        TargetBlitter::new(device, FORMAT).blit(device, &mut encoder, source, &output_view);
+
+        let bytes_per_row = (width * 4).max(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("测试回读缓冲"),
+            size: (bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            output.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(bytes_per_row), rows_per_image: Some(height) },
+            },
+            output.size(),
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        [data[0], data[1], data[2], data[3]]
+    }
+
+    #[test]
+    fn targets_match_requested_size_and_samples() {
+        let Some((adapter, device, _queue)) = headless_device(None) else {
+            eprintln!("跳过渲染目标测试: 没有可用的适配器");
+            return;
+        };
+
+        let single = RenderTargets::new(&device, 32, 16, FORMAT, 0);
+        assert_eq!(single.sample_count(), 1);
+        assert!(single.color_resolve_target().is_none());
+        assert_eq!((single.depth_texture().width(), single.depth_texture().height()), (32, 16));
+        assert!(single.depth_texture().usage().contains(wgpu::TextureUsages::TEXTURE_BINDING));
+
+        if !msaa_sample_counts(&adapter).contains(&4) {
+            eprintln!("跳过MSAA渲染目标测试: 适配器不支持4倍MSAA");
+            return;
+        }
+        let msaa = RenderTargets::new(&device, 0, 48, FORMAT, 4);
+        assert_eq!(msaa.size(), (1, 48));
+        assert_eq!((msaa.sample_count(), msaa.format()), (4, FORMAT));
+        assert!(msaa.color_resolve_target().is_some());
+        assert_eq!(msaa.resolve_texture().sample_count(), 1);
+        assert_eq!(msaa.depth_texture().sample_count(), 4);
+        assert_eq!(msaa.depth_texture().format(), MAIN_DEPTH_FORMAT);
+        assert!(!msaa.depth_texture().usage().contains(wgpu::TextureUsages::TEXTURE_BINDING));
+        assert_eq!((msaa.resolve_texture().width(), msaa.resolve_texture().height()), (1, 48));
+    }
+
+    #[test]
+    fn custom_pass_output_reaches_final_image() {
+        let Some((adapter, device, queue)) = headless_device(None) else {
+            eprintln!("跳过自定义通道测试: 没有可用的适配器");
+            return;
+        };
+        let layout = FrameResources::bind_group_layout(&device);
+        let frame = FrameResources::new(&device, &layout, 0);
+
+        // 适配器不支持4倍MSAA时只测试单采样
+        let supported_counts = msaa_sample_counts(&adapter);
+        for sample_count in [1, 4].into_iter().filter(|&count| count == 1 || supported_counts.contains(&count)) {
+            let targets = RenderTargets::new(&device, 64, 32, FORMAT, sample_count);
+            let mut pass = FillPass { color: wgpu::Color::RED, seen: Vec::new() };
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            let context = CustomPassContext { device: &device, queue: &queue, targets: &targets, frame: &frame };
+            pass.execute(&context, &mut encoder);
+
+            assert_eq!(pass.seen, vec![((64, 32), sample_count)]);
+            let pixel = blit_and_read_pixel(&device, &queue, encoder, targets.resolve_view(), 64, 32);
+            assert_eq!(pixel, [255, 0, 0, 255], "采样数 {}", sample_count);
+        }
+    }
+
+    #[test]
+    fn custom_pass_debug_shows_name() {
+        let pass: Box<dyn CustomRenderPass> = Box::new(FillPass { color: wgpu::Color::BLACK, seen: Vec::new() });
+        assert_eq!(format!("{:?}", pass), "CustomRenderPass { name: \"fill\" }");
+    }
+}
@@ -1,8 +1,11 @@
 //! 着色器系统
 
+use crate::assets::AssetVfs;
+use crate::render::{Material, ShaderKeywordSet, ShaderVariant, ShaderVariantCache, ShaderVariantKey, ShaderIncludeResolver, ShaderLineMap};
 use crate::{EngineResult, EngineError};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// 着色器类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -121,6 +124,12 @@ impl Shader {
 /// 着色器管理器
 pub struct ShaderManager {
     shaders: HashMap<String, Shader>,
+    /// 按关键字预处理后的变体
+    variants: ShaderVariantCache,
+    /// 解析 `#include`
+    includes: ShaderIncludeResolver,
+    /// 展开包含后的源码及行号映射(着色器名 -> 展开结果)，未使用包含的着色器不在其中
+    expanded: HashMap<String, (String, ShaderLineMap)>,
 }
 
 impl Default for ShaderManager {
@@ -134,6 +143,9 @@ impl ShaderManager {
     pub fn new() -> Self {
         let mut manager = Self {
             shaders: HashMap::new(),
+            variants: ShaderVariantCache::new(),
+            includes: ShaderIncludeResolver::default(),
+            expanded: HashMap::new(),
         };
 
         // 添加内置着色器
@@ -156,6 +168,10 @@ struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
     @location(2) tex_coords: vec2<f32>,
+#ifdef SKINNED
+    @location(4) joint_indices: vec4<u32>,
+    @location(5) joint_weights: vec4<f32>,
+#endif
 }
 
 struct VertexOutput {
@@ -177,19 +193,144 @@ struct ModelUniform {
     normal_matrix: mat3x3<f32>,
 }
 
+struct MaterialUniform {
+    base_color: vec4<f32>,
+    // xyz为自发光颜色
+    emission: vec4<f32>,
+    // x为金属度，y为粗糙度
+    params: vec4<f32>,
+}
+
 @group(0) @binding(0)
 var<uniform> camera: CameraUniform;
 
+struct FogUniform {
+    // rgb为雾颜色，a为1时启用
+    color: vec4<f32>,
+    // x为模式(0线性，1指数，2平方指数)，y为密度，z/w为线性雾起止距离
+    params: vec4<f32>,
+}
+
+@group(0) @binding(1)
+var<uniform> fog: FogUniform;
+
+// 按片元到相机的距离混合雾颜色，与Fog::factor一致
+fn apply_fog(color: vec3<f32>, distance: f32) -> vec3<f32> {
+    if (fog.color.a < 0.5) {
+        return color;
+    }
+    let mode = u32(fog.params.x);
+    var factor = 0.0;
+    if (mode == 0u) {
+        let range = fog.params.w - fog.params.z;
+        factor = select(step(fog.params.w, distance), (distance - fog.params.z) / range, range > 1e-6);
+    } else if (mode == 1u) {
+        factor = 1.0 - exp(-fog.params.y * distance);
+    } else {
+        let scaled = fog.params.y * distance;
+        factor = 1.0 - exp(-scaled * scaled);
+    }
+    return mix(color, fog.color.rgb, clamp(factor, 0.0, 1.0));
+}
+
 @group(1) @binding(0)
 var<uniform> model: ModelUniform;
 
+#ifdef SKINNED
+struct JointPalette {
+    joints: array<mat4x4<f32>, 128>,
+}
+
+@group(1) @binding(1)
+var<uniform> joint_palette: JointPalette;
+
+// 线性混合蒙皮：按权重混合骨骼矩阵
+fn skin_matrix(indices: vec4<u32>, weights: vec4<f32>) -> mat4x4<f32> {
+    return joint_palette.joints[indices.x] * weights.x
+        + joint_palette.joints[indices.y] * weights.y
+        + joint_palette.joints[indices.z] * weights.z
+        + joint_palette.joints[indices.w] * weights.w;
+}
+#endif
+
+@group(2) @binding(0)
+var<uniform> material: MaterialUniform;
+
+@group(2) @binding(1)
+var material_sampler: sampler;
+
+#ifdef HAS_BASE_COLOR_MAP
+@group(2) @binding(2)
+var base_color_map: texture_2d<f32>;
+#endif
+
+#ifdef HAS_IBL
+@group(3) @binding(0)
+var irradiance_map: texture_cube<f32>;
+
+@group(3) @binding(1)
+var specular_map: texture_cube<f32>;
+
+@group(3) @binding(2)
+var environment_sampler: sampler;
+
+fn fresnel_schlick_roughness(cos_theta: f32, f0: vec3<f32>, roughness: f32) -> vec3<f32> {
+    return f0 + (max(vec3<f32>(1.0 - roughness), f0) - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+// 环境光：漫反射取辐照度图，镜面按粗糙度选取预过滤图的mip级
+fn ambient_ibl(normal: vec3<f32>, view_dir: vec3<f32>, albedo: vec3<f32>) -> vec3<f32> {
+    let metallic = material.params.x;
+    let roughness = material.params.y;
+    let f0 = mix(vec3<f32>(0.04), albedo, metallic);
+    let n_dot_v = max(dot(normal, view_dir), 0.0);
+    let fresnel = fresnel_schlick_roughness(n_dot_v, f0, roughness);
+    let kd = (vec3<f32>(1.0) - fresnel) * (1.0 - metallic);
+
+    let irradiance = textureSample(irradiance_map, environment_sampler, normal).rgb;
+    let reflected = reflect(-view_dir, normal);
+    let max_lod = f32(textureNumLevels(specular_map) - 1u);
+    let prefiltered = textureSampleLevel(specular_map, environment_sampler, reflected, roughness * max_lod).rgb;
+    return kd * irradiance * albedo + prefiltered * fresnel;
+}
+#endif
+
+#ifdef HAS_NORMAL_MAP
+@group(2) @binding(3)
+var normal_map: texture_2d<f32>;
+
+// 无切线数据，由屏幕空间导数构建切线空间
+fn perturb_normal(normal: vec3<f32>, world_position: vec3<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let dp1 = dpdx(world_position);
+    let dp2 = dpdy(world_position);
+    let duv1 = dpdx(uv);
+    let duv2 = dpdy(uv);
+    let dp2perp = cross(dp2, normal);
+    let dp1perp = cross(normal, dp1);
+    let tangent = dp2perp * duv1.x + dp1perp * duv2.x;
+    let bitangent = dp2perp * duv1.y + dp1perp * duv2.y;
+    let inv_max = inverseSqrt(max(dot(tangent, tangent), dot(bitangent, bitangent)));
+    let tbn = mat3x3<f32>(tangent * inv_max, bitangent * inv_max, normal);
+    let sampled = textureSample(normal_map, material_sampler, uv).xyz * 2.0 - 1.0;
+    return normalize(tbn * sampled);
+}
+#endif
+
 @vertex
 fn vs_main(input: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     
-    let world_position = model.model * vec4<f32>(input.position, 1.0);
+    var local_position = vec4<f32>(input.position, 1.0);
+    var local_normal = input.normal;
+#ifdef SKINNED
+    let skin = skin_matrix(input.joint_indices, input.joint_weights);
+    local_position = skin * local_position;
+    local_normal = (skin * vec4<f32>(local_normal, 0.0)).xyz;
+#endif
+    
+    let world_position = model.model * local_position;
     out.world_position = world_position.xyz;
-    out.world_normal = normalize(model.normal_matrix * input.normal);
+    out.world_normal = normalize(model.normal_matrix * local_normal);
     out.tex_coords = input.tex_coords;
     out.clip_position = camera.view_proj * world_position;
     
@@ -198,14 +339,32 @@ fn vs_main(input: VertexInput) -> VertexOutput {
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    let base_color = vec3<f32>(0.8, 0.8, 0.8);
+    var base_color = material.base_color;
+#ifdef HAS_BASE_COLOR_MAP
+    base_color *= textureSample(base_color_map, material_sampler, input.tex_coords);
+#endif
     let light_dir = normalize(vec3<f32>(1.0, 1.0, 1.0));
-    let normal = normalize(input.world_normal);
+    var normal = normalize(input.world_normal);
+#ifdef HAS_NORMAL_MAP
+    normal = perturb_normal(normal, input.world_position, input.tex_coords);
+#endif
     
     let diffuse = max(dot(normal, light_dir), 0.0);
-    let color = base_color * diffuse;
+    var color = base_color.rgb * diffuse;
+#ifdef HAS_IBL
+    let view_dir = normalize(camera.position - input.world_position);
+    color += ambient_ibl(normal, view_dir, base_color.rgb);
+#endif
+#ifdef HAS_EMISSION
+    color += material.emission.xyz;
+#endif
+    color = apply_fog(color, distance(camera.position, input.world_position));
     
+#ifdef ALPHA_BLEND
+    return vec4<f32>(color, base_color.a);
+#else
     return vec4<f32>(color, 1.0);
+#endif
 }
         "#;
 
@@ -215,9 +374,19 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         self.shaders.insert("pbr".to_string(), pbr_shader);
     }
 
-    /// 添加着色器
-    pub fn add_shader(&mut self, shader: Shader) {
-        self.shaders.insert(shader.name.clone(), shader);
+    /// 设置 `#include` 的资源搜索路径
+    pub fn set_include_paths(&mut self, vfs: AssetVfs) {
+        self.includes = ShaderIncludeResolver::new(vfs);
+    }
+
+    /// 添加 `#include` 的搜索根目录
+    pub fn add_include_path(&mut self, root: impl Into<std::path::PathBuf>, priority: i32) {
+        self.includes.vfs_mut().add_search_path(root, priority);
+    }
+
+    /// 添加着色器，源码中的 `#include` 按资源搜索路径展开
+    pub fn add_shader(&mut self, shader: Shader) -> EngineResult<()> {
+        self.insert_shader(shader, None)
     }
 
     /// 获取着色器
@@ -225,20 +394,177 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         self.shaders.get(name)
     }
 
-    /// 从文件加载着色器
+    /// 从文件加载着色器，`#include` 先相对该文件所在目录查找
     pub fn load_shader_from_file(&mut self, name: impl Into<String>, path: impl AsRef<std::path::Path>) -> EngineResult<()> {
-        let name = name.into();
-        let source = std::fs::read_to_string(path.as_ref())
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
             .map_err(|e| EngineError::AssetError(format!("加载着色器文件失败: {}", e)))?;
 
-        let shader = Shader::new(name.clone()).with_wgsl_source(source);
-        self.shaders.insert(name, shader);
-        
+        let shader = Shader::new(name).with_wgsl_source(source);
+        self.insert_shader(shader, Some(path))
+    }
+
+    fn insert_shader(&mut self, shader: Shader, path: Option<&std::path::Path>) -> EngineResult<()> {
+        if shader.source.lines().any(|line| line.trim_start().starts_with("#include")) {
+            let origin = path.map_or_else(|| shader.name.clone(), |path| path.display().to_string());
+            let expanded = self.includes.expand(&shader.source, &origin, path.and_then(|path| path.parent()))?;
+            self.expanded.insert(shader.name.clone(), (expanded.source, expanded.line_map));
+        } else {
+            self.expanded.remove(&shader.name);
+        }
+
+        self.variants.invalidate_shader(&shader.name);
+        self.shaders.insert(shader.name.clone(), shader);
         Ok(())
     }
 
+    /// 展开包含后的行号映射，编译错误据此定位到原文件
+    pub fn line_map(&self, shader_name: &str) -> Option<ShaderLineMap> {
+        if let Some((_, line_map)) = self.expanded.get(shader_name) {
+            return Some(line_map.clone());
+        }
+        self.shaders
+            .get(shader_name)
+            .map(|shader| ShaderLineMap::identity(&shader.name, &shader.source))
+    }
+
+    /// 编译着色器变体，校验失败时错误信息中的行号映射回原文件
+    pub fn compile_variant(
+        &mut self,
+        device: &wgpu::Device,
+        shader_name: &str,
+        keywords: ShaderKeywordSet,
+    ) -> EngineResult<wgpu::ShaderModule> {
+        let variant = self.variant(shader_name, keywords)?;
+        let label = variant.label();
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(&label),
+            source: wgpu::ShaderSource::Wgsl(variant.source.as_str().into()),
+        });
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            let message = error.to_string();
+            let message = match self.line_map(shader_name) {
+                Some(line_map) => line_map.remap_message(&message),
+                None => message,
+            };
+            return Err(EngineError::RenderError(format!("编译着色器 {} 失败: {}", label, message)).into());
+        }
+        Ok(module)
+    }
+
+    /// 获取着色器的关键字变体，首次使用时预处理并缓存
+    pub fn variant(&mut self, shader_name: &str, keywords: ShaderKeywordSet) -> EngineResult<Arc<ShaderVariant>> {
+        let shader = self.shaders.get(shader_name)
+            .ok_or_else(|| EngineError::RenderError(format!("着色器不存在: {}", shader_name)))?;
+        let source = self.expanded.get(shader_name).map_or(shader.source.as_str(), |(source, _)| source.as_str());
+        self.variants.get_or_preprocess(ShaderVariantKey::new(shader_name, keywords), source)
+    }
+
+    /// 获取材质启用特性对应的着色器变体
+    pub fn material_variant(&mut self, material: &Material) -> EngineResult<Arc<ShaderVariant>> {
+        self.variant(&material.shader_name, ShaderKeywordSet::from_material(material))
+    }
+
+    /// 已缓存的变体数量
+    pub fn variant_count(&self) -> usize {
+        self.variants.len()
+    }
+
     /// 获取所有着色器名称
     pub fn shader_names(&self) -> Vec<&String> {
         self.shaders.keys().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+    use crate::render::TextureSlot;
+
+    #[test]
+    fn material_variants_are_cached_by_feature_set() {
+        let mut manager = ShaderManager::new();
+        let plain = manager.material_variant(&Material::pbr("plain")).unwrap();
+        let same = manager.material_variant(&Material::pbr("other")).unwrap();
+        let normal = manager
+            .material_variant(&Material::pbr("normal").with_texture(TextureSlot::Normal, "textures/brick_n.png"))
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&plain, &same));
+        assert!(!Arc::ptr_eq(&plain, &normal));
+        assert!(!plain.source.contains("normal_map"));
+        assert!(normal.source.contains("perturb_normal"));
+        assert_eq!(manager.variant_count(), 2);
+
+        // 替换着色器源码后变体失效
+        manager.add_shader(Shader::new("pbr").with_wgsl_source("fn main() {}")).unwrap();
+        assert_eq!(manager.variant_count(), 0);
+        assert!(manager.variant("missing", ShaderKeywordSet::new()).is_err());
+    }
+
+    fn include_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sanji_shader_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const MAIN_WGSL: &str = "#include \"lighting.wgsl\"\n\n@fragment\nfn fs_main() -> @location(0) vec4<f32> {\n    return vec4<f32>(lambert(), 0.0, 0.0, 1.0);\n}\n";
+
+    #[test]
+    fn included_helpers_are_part_of_variants() {
+        let dir = include_dir("variant");
+        std::fs::write(dir.join("lighting.wgsl"), "fn lambert() -> f32 { return 0.5; }\n").unwrap();
+        let mut manager = ShaderManager::new();
+        manager.add_include_path(&dir, 0);
+        manager.add_shader(Shader::new("lit").with_wgsl_source(MAIN_WGSL)).unwrap();
+
+        let variant = manager.variant("lit", ShaderKeywordSet::new()).unwrap();
+        assert!(variant.source.starts_with("fn lambert()"));
+        assert!(!variant.source.contains("#include"));
+        let line_map = manager.line_map("lit").unwrap();
+        assert!(line_map.location(1).unwrap().file.ends_with("lighting.wgsl"));
+        assert_eq!(line_map.location(2).unwrap().file, "lit");
+
+        // 未使用包含的着色器按自身行号映射
+        assert_eq!(manager.line_map("pbr").unwrap().location(1).unwrap().file, "pbr");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_include_is_reported_when_adding_shader() {
+        let mut manager = ShaderManager::new();
+        let error = manager
+            .add_shader(Shader::new("lit").with_wgsl_source(MAIN_WGSL))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("lighting.wgsl"), "{}", error);
+        assert!(manager.get_shader("lit").is_none());
+    }
+
+    #[test]
+    fn shader_with_include_compiles() {
+        let Some((_, device, _queue)) = headless_device(None) else {
+            eprintln!("跳过: 没有可用的图形适配器");
+            return;
+        };
+        let dir = include_dir("compile");
+        std::fs::write(dir.join("lighting.wgsl"), "fn lambert() -> f32 { return 0.5; }\n").unwrap();
+        std::fs::write(dir.join("main.wgsl"), MAIN_WGSL).unwrap();
+        let mut manager = ShaderManager::new();
+        manager.load_shader_from_file("lit", dir.join("main.wgsl")).unwrap();
+        assert!(manager.compile_variant(&device, "lit", ShaderKeywordSet::new()).is_ok());
+
+        // 被包含文件中的错误定位到该文件
+        std::fs::write(dir.join("lighting.wgsl"), "fn lambert() -> f32 { return undefined_value; }\n").unwrap();
+        manager.load_shader_from_file("lit", dir.join("main.wgsl")).unwrap();
+        let error = manager.compile_variant(&device, "lit", ShaderKeywordSet::new()).unwrap_err().to_string();
+        assert!(error.contains("lighting.wgsl"), "{}", error);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
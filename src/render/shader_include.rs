@@ -0,0 +1,304 @@
+//! WGSL `#include` 预处理
+//!
+//! `#include "lighting.wgsl"` 先相对包含它的文件所在目录查找，再按资源搜索路径查找，
+//! 展开后整段替换该行。每个文件在一次展开中只包含一次，循环包含报错。
+//! 展开时记录每个输出行来自哪个文件的哪一行，编译错误据此映射回原文件。
+
+use crate::assets::AssetVfs;
+use crate::{EngineError, EngineResult};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 输出行对应的源位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderSourceLocation {
+    pub file: String,
+    /// 从1开始的行号
+    pub line: u32,
+}
+
+impl fmt::Display for ShaderSourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// 展开后源码的行号映射
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShaderLineMap {
+    lines: Vec<ShaderSourceLocation>,
+}
+
+impl ShaderLineMap {
+    /// 未经展开的源码：每行映射到自身
+    pub fn identity(file: &str, source: &str) -> Self {
+        let lines = (1..=source.lines().count() as u32)
+            .map(|line| ShaderSourceLocation { file: file.to_string(), line })
+            .collect();
+        Self { lines }
+    }
+
+    /// 展开后第line行(从1开始)的源位置
+    pub fn location(&self, line: u32) -> Option<&ShaderSourceLocation> {
+        line.checked_sub(1).and_then(|index| self.lines.get(index as usize))
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// 把编译器消息中的 `wgsl:行:列` 改写为原文件位置
+    pub fn remap_message(&self, message: &str) -> String {
+        const MARKER: &str = "wgsl:";
+        let mut output = String::with_capacity(message.len());
+        let mut rest = message;
+
+        while let Some(start) = rest.find(MARKER) {
+            let after = &rest[start + MARKER.len()..];
+            let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+            let location = after[..digits].parse::<u32>().ok().and_then(|line| self.location(line));
+
+            match location {
+                Some(location) => {
+                    output.push_str(&rest[..start]);
+                    output.push_str(&format!("{}:{}", location.file, location.line));
+                }
+                None => output.push_str(&rest[..start + MARKER.len() + digits]),
+            }
+            rest = &after[digits..];
+        }
+        output.push_str(rest);
+        output
+    }
+}
+
+/// 展开后的着色器
+#[derive(Debug, Clone, Default)]
+pub struct ExpandedShader {
+    pub source: String,
+    pub line_map: ShaderLineMap,
+    /// 被包含的文件(按首次包含顺序)
+    pub includes: Vec<PathBuf>,
+}
+
+/// `#include` 解析器
+#[derive(Debug, Clone, Default)]
+pub struct ShaderIncludeResolver {
+    vfs: AssetVfs,
+}
+
+impl ShaderIncludeResolver {
+    pub fn new(vfs: AssetVfs) -> Self {
+        Self { vfs }
+    }
+
+    pub fn vfs(&self) -> &AssetVfs {
+        &self.vfs
+    }
+
+    pub fn vfs_mut(&mut self) -> &mut AssetVfs {
+        &mut self.vfs
+    }
+
+    /// 展开源码中的包含，origin为错误信息中使用的文件名，directory为相对包含的查找目录
+    pub fn expand(&self, source: &str, origin: &str, directory: Option<&Path>) -> EngineResult<ExpandedShader> {
+        let mut expanded = ExpandedShader::default();
+        let mut stack = Vec::new();
+        self.expand_into(source, origin, directory, &mut stack, &mut expanded)?;
+        Ok(expanded)
+    }
+
+    /// 读取并展开着色器文件
+    pub fn expand_file(&self, path: &Path) -> EngineResult<ExpandedShader> {
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| EngineError::AssetError(format!("加载着色器文件失败: {}: {}", path.display(), e)))?;
+        let mut expanded = ExpandedShader::default();
+        let mut stack = vec![Self::canonical(path)];
+        self.expand_into(&source, &path.display().to_string(), path.parent(), &mut stack, &mut expanded)?;
+        Ok(expanded)
+    }
+
+    fn expand_into(
+        &self,
+        source: &str,
+        origin: &str,
+        directory: Option<&Path>,
+        stack: &mut Vec<PathBuf>,
+        expanded: &mut ExpandedShader,
+    ) -> EngineResult<()> {
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let Some(target) = Self::parse_include(line) else {
+                expanded.source.push_str(line);
+                expanded.source.push('\n');
+                expanded.line_map.lines.push(ShaderSourceLocation { file: origin.to_string(), line: line_number });
+                continue;
+            };
+
+            let target = target.map_err(|message| {
+                EngineError::RenderError(format!("{}:{}: {}", origin, line_number, message))
+            })?;
+            let path = self.resolve(target, directory).ok_or_else(|| {
+                EngineError::AssetError(format!("{}:{}: 无法解析包含文件 \"{}\"", origin, line_number, target))
+            })?;
+            let canonical = Self::canonical(&path);
+
+            if let Some(start) = stack.iter().position(|included| *included == canonical) {
+                let chain: Vec<String> = stack[start..]
+                    .iter()
+                    .chain(std::iter::once(&canonical))
+                    .map(|path| path.display().to_string())
+                    .collect();
+                return Err(EngineError::RenderError(format!(
+                    "{}:{}: 循环包含: {}",
+                    origin,
+                    line_number,
+                    chain.join(" -> ")
+                ))
+                .into());
+            }
+
+            // 已包含过的文件不再重复展开，保留空行以维持行号
+            if expanded.includes.contains(&canonical) {
+                expanded.source.push('\n');
+                expanded.line_map.lines.push(ShaderSourceLocation { file: origin.to_string(), line: line_number });
+                continue;
+            }
+
+            let included_source = std::fs::read_to_string(&path)
+                .map_err(|e| EngineError::AssetError(format!("{}:{}: 读取包含文件 {} 失败: {}", origin, line_number, path.display(), e)))?;
+            expanded.includes.push(canonical.clone());
+            stack.push(canonical);
+            self.expand_into(&included_source, &path.display().to_string(), path.parent(), stack, expanded)?;
+            stack.pop();
+        }
+        Ok(())
+    }
+
+    /// 解析 `#include "x"` 或 `#include <x>`，非包含行返回None
+    fn parse_include(line: &str) -> Option<Result<&str, &'static str>> {
+        let argument = line.trim().strip_prefix("#include")?.trim();
+        let target = argument
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .or_else(|| argument.strip_prefix('<').and_then(|rest| rest.strip_suffix('>')));
+        Some(match target {
+            Some(target) if !target.is_empty() => Ok(target),
+            _ => Err("#include 需要带引号的路径"),
+        })
+    }
+
+    fn resolve(&self, target: &str, directory: Option<&Path>) -> Option<PathBuf> {
+        if !AssetVfs::is_virtual(target) {
+            if let Some(candidate) = directory.map(|directory| directory.join(target)) {
+                if candidate.exists() {
+                    return Some(candidate);
+                }
+            }
+        }
+        self.vfs.resolve(target).filter(|path| path.exists())
+    }
+
+    fn canonical(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sanji_shader_include_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn relative_includes_expand_with_line_map() {
+        let dir = temp_dir("relative");
+        std::fs::write(dir.join("common.wgsl"), "const PI: f32 = 3.14159;\n").unwrap();
+        std::fs::write(dir.join("lighting.wgsl"), "#include \"common.wgsl\"\nfn lambert() -> f32 { return 1.0 / PI; }\n").unwrap();
+        std::fs::write(dir.join("main.wgsl"), "#include \"lighting.wgsl\"\n#include \"common.wgsl\"\nfn main() {}\n").unwrap();
+
+        let expanded = ShaderIncludeResolver::default().expand_file(&dir.join("main.wgsl")).unwrap();
+        let lines: Vec<_> = expanded.source.lines().collect();
+        // common.wgsl只展开一次，第二次包含保留为空行
+        assert_eq!(lines, ["const PI: f32 = 3.14159;", "fn lambert() -> f32 { return 1.0 / PI; }", "", "fn main() {}"]);
+        assert_eq!(expanded.includes.len(), 2);
+
+        let file_of = |line| expanded.line_map.location(line).unwrap().file.clone();
+        assert!(file_of(1).ends_with("common.wgsl"));
+        assert!(file_of(2).ends_with("lighting.wgsl"));
+        assert_eq!(expanded.line_map.location(2).unwrap().line, 2);
+        assert!(file_of(3).ends_with("main.wgsl"));
+        assert_eq!(expanded.line_map.location(4).unwrap().line, 3);
+        assert!(expanded.line_map.location(0).is_none());
+        assert!(expanded.line_map.location(5).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn search_paths_resolve_includes_without_directory() {
+        let dir = temp_dir("search");
+        std::fs::write(dir.join("brdf.wgsl"), "fn brdf() {}\n").unwrap();
+        let mut vfs = AssetVfs::new();
+        vfs.add_search_path(&dir, 0);
+
+        let expanded = ShaderIncludeResolver::new(vfs).expand("#include <brdf.wgsl>\nfn main() {}\n", "pbr", None).unwrap();
+        assert_eq!(expanded.source, "fn brdf() {}\nfn main() {}\n");
+        assert_eq!(expanded.line_map.location(2), Some(&ShaderSourceLocation { file: "pbr".to_string(), line: 2 }));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_include_names_the_unresolved_path() {
+        let error = ShaderIncludeResolver::default()
+            .expand("fn a() {}\n#include \"missing/helper.wgsl\"\n", "main.wgsl", None)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("main.wgsl:2"), "{}", error);
+        assert!(error.contains("missing/helper.wgsl"), "{}", error);
+    }
+
+    #[test]
+    fn cyclic_includes_are_rejected() {
+        let dir = temp_dir("cycle");
+        std::fs::write(dir.join("a.wgsl"), "#include \"b.wgsl\"\n").unwrap();
+        std::fs::write(dir.join("b.wgsl"), "#include \"a.wgsl\"\n").unwrap();
+
+        let error = ShaderIncludeResolver::default().expand_file(&dir.join("a.wgsl")).unwrap_err().to_string();
+        assert!(error.contains("循环包含"), "{}", error);
+        assert!(error.contains("a.wgsl -> "), "{}", error);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn malformed_include_is_an_error() {
+        for source in ["#include", "#include lighting.wgsl", "#include \"\""] {
+            let error = ShaderIncludeResolver::default().expand(source, "main", None).unwrap_err().to_string();
+            assert!(error.contains("main:1"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn remap_message_rewrites_known_lines_only() {
+        let mut line_map = ShaderLineMap::identity("main.wgsl", "a\nb\n");
+        line_map.lines[1] = ShaderSourceLocation { file: "lighting.wgsl".to_string(), line: 7 };
+
+        assert_eq!(line_map.len(), 2);
+        assert_eq!(
+            line_map.remap_message("error at wgsl:2:5 and wgsl:1:1"),
+            "error at lighting.wgsl:7:5 and main.wgsl:1:1"
+        );
+        assert_eq!(line_map.remap_message("wgsl:9:1 wgsl:x"), "wgsl:9:1 wgsl:x");
+    }
+}
@@ -0,0 +1,424 @@
+//! 着色器变体
+//!
+//! 材质启用的特性(法线贴图、自发光等)映射为关键字集合，着色器源码中用 `#ifdef KEYWORD` / `#ifndef` /
+//! `#else` / `#endif` 包裹特性相关代码。每个(着色器, 关键字集合)组合在首次使用时预处理并编译，之后从缓存取用，
+//! 特性相同的材质共享同一变体。
+
+use crate::render::{CullMode, FrontFace, Material, TextureSlot};
+use crate::{EngineError, EngineResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 着色器关键字
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShaderKeyword {
+    BaseColorMap,
+    NormalMap,
+    MetallicRoughnessMap,
+    OcclusionMap,
+    Emission,
+    AlphaBlend,
+    DoubleSided,
+    /// 场景提供了预过滤的环境光照
+    ImageBasedLighting,
+    /// 顶点带骨骼索引与权重，由骨骼矩阵调色板蒙皮
+    Skinning,
+}
+
+impl ShaderKeyword {
+    pub const ALL: [ShaderKeyword; 9] = [
+        ShaderKeyword::BaseColorMap,
+        ShaderKeyword::NormalMap,
+        ShaderKeyword::MetallicRoughnessMap,
+        ShaderKeyword::OcclusionMap,
+        ShaderKeyword::Emission,
+        ShaderKeyword::AlphaBlend,
+        ShaderKeyword::DoubleSided,
+        ShaderKeyword::ImageBasedLighting,
+        ShaderKeyword::Skinning,
+    ];
+
+    /// 源码中使用的宏名称
+    pub fn define(&self) -> &'static str {
+        match self {
+            ShaderKeyword::BaseColorMap => "HAS_BASE_COLOR_MAP",
+            ShaderKeyword::NormalMap => "HAS_NORMAL_MAP",
+            ShaderKeyword::MetallicRoughnessMap => "HAS_METALLIC_ROUGHNESS_MAP",
+            ShaderKeyword::OcclusionMap => "HAS_OCCLUSION_MAP",
+            ShaderKeyword::Emission => "HAS_EMISSION",
+            ShaderKeyword::AlphaBlend => "ALPHA_BLEND",
+            ShaderKeyword::DoubleSided => "DOUBLE_SIDED",
+            ShaderKeyword::ImageBasedLighting => "HAS_IBL",
+            ShaderKeyword::Skinning => "SKINNED",
+        }
+    }
+
+    pub fn from_define(define: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|keyword| keyword.define() == define)
+    }
+
+    fn bit(&self) -> u32 {
+        1 << (*self as u32)
+    }
+}
+
+/// 关键字集合
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ShaderKeywordSet(u32);
+
+impl ShaderKeywordSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 由材质启用的特性确定关键字
+    pub fn from_material(material: &Material) -> Self {
+        let mut keywords = Self::new();
+        let has_texture = |slot| material.get_texture(slot).map_or(false, |path| !path.is_empty());
+
+        keywords.set(ShaderKeyword::BaseColorMap, has_texture(TextureSlot::BaseColor));
+        keywords.set(ShaderKeyword::NormalMap, has_texture(TextureSlot::Normal));
+        keywords.set(
+            ShaderKeyword::MetallicRoughnessMap,
+            has_texture(TextureSlot::Metallic) || has_texture(TextureSlot::Roughness),
+        );
+        keywords.set(ShaderKeyword::OcclusionMap, has_texture(TextureSlot::Occlusion));
+        keywords.set(
+            ShaderKeyword::Emission,
+            has_texture(TextureSlot::Emission) || material.properties.emission.max_element() > 0.0,
+        );
+        keywords.set(
+            ShaderKeyword::AlphaBlend,
+            material.properties.alpha < 1.0 || material.properties.base_color.w < 1.0,
+        );
+        keywords.set(ShaderKeyword::DoubleSided, material.is_double_sided());
+        keywords
+    }
+
+    pub fn with(mut self, keyword: ShaderKeyword) -> Self {
+        self.insert(keyword);
+        self
+    }
+
+    pub fn insert(&mut self, keyword: ShaderKeyword) {
+        self.0 |= keyword.bit();
+    }
+
+    pub fn remove(&mut self, keyword: ShaderKeyword) {
+        self.0 &= !keyword.bit();
+    }
+
+    pub fn set(&mut self, keyword: ShaderKeyword, enabled: bool) {
+        if enabled {
+            self.insert(keyword);
+        } else {
+            self.remove(keyword);
+        }
+    }
+
+    pub fn contains(&self, keyword: ShaderKeyword) -> bool {
+        self.0 & keyword.bit() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ShaderKeyword> {
+        let keywords = *self;
+        ShaderKeyword::ALL.into_iter().filter(move |keyword| keywords.contains(*keyword))
+    }
+
+    /// 已定义的宏名称
+    pub fn defines(&self) -> Vec<&'static str> {
+        self.iter().map(|keyword| keyword.define()).collect()
+    }
+
+    /// 用于调试标签的描述，例如 "pbr[HAS_NORMAL_MAP|HAS_EMISSION]"
+    pub fn label(&self, shader_name: &str) -> String {
+        format!("{}[{}]", shader_name, self.defines().join("|"))
+    }
+}
+
+/// 按关键字预处理WGSL源码，支持嵌套的 `#ifdef` / `#ifndef` / `#else` / `#endif`
+///
+/// 指令行和被排除的行输出为空行，使编译错误的行号与源码一致。
+pub fn preprocess_wgsl(source: &str, keywords: ShaderKeywordSet) -> EngineResult<String> {
+    let is_defined = |name: &str| ShaderKeyword::from_define(name).map_or(false, |keyword| keywords.contains(keyword));
+
+    // 每层记录外层是否输出
+    let mut stack: Vec<bool> = Vec::new();
+    let mut active = true;
+    let mut output = String::with_capacity(source.len());
+
+    for (line_number, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        let directive_error = |message: &str| {
+            EngineError::RenderError(format!("着色器预处理第{}行: {}", line_number + 1, message))
+        };
+
+        if let Some(name) = trimmed.strip_prefix("#ifdef") {
+            stack.push(active);
+            active = active && is_defined(name.trim());
+        } else if let Some(name) = trimmed.strip_prefix("#ifndef") {
+            stack.push(active);
+            active = active && !is_defined(name.trim());
+        } else if trimmed.starts_with("#else") {
+            let parent_active = stack.last().copied().ok_or_else(|| directive_error("#else 缺少对应的 #ifdef"))?;
+            active = parent_active && !active;
+        } else if trimmed.starts_with("#endif") {
+            let parent_active = stack.pop().ok_or_else(|| directive_error("#endif 缺少对应的 #ifdef"))?;
+            active = parent_active;
+        } else if active {
+            output.push_str(line);
+            output.push('\n');
+            continue;
+        }
+        output.push('\n');
+    }
+
+    if !stack.is_empty() {
+        return Err(EngineError::RenderError("着色器预处理: #ifdef 缺少 #endif".to_string()).into());
+    }
+    Ok(output)
+}
+
+/// 变体标识
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderVariantKey {
+    pub shader_name: String,
+    pub keywords: ShaderKeywordSet,
+}
+
+impl ShaderVariantKey {
+    pub fn new(shader_name: impl Into<String>, keywords: ShaderKeywordSet) -> Self {
+        Self {
+            shader_name: shader_name.into(),
+            keywords,
+        }
+    }
+
+    pub fn for_material(material: &Material) -> Self {
+        Self::new(material.shader_name.clone(), ShaderKeywordSet::from_material(material))
+    }
+}
+
+/// 预处理后的着色器变体
+#[derive(Debug, Clone)]
+pub struct ShaderVariant {
+    pub key: ShaderVariantKey,
+    pub source: String,
+}
+
+impl ShaderVariant {
+    pub fn label(&self) -> String {
+        self.key.keywords.label(&self.key.shader_name)
+    }
+}
+
+/// 着色器变体缓存(预处理后的源码)
+#[derive(Debug, Default)]
+pub struct ShaderVariantCache {
+    variants: HashMap<ShaderVariantKey, Arc<ShaderVariant>>,
+}
+
+impl ShaderVariantCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取得变体，未缓存时由 `source` 预处理生成
+    pub fn get_or_preprocess(&mut self, key: ShaderVariantKey, source: &str) -> EngineResult<Arc<ShaderVariant>> {
+        if let Some(variant) = self.variants.get(&key) {
+            return Ok(Arc::clone(variant));
+        }
+
+        let variant = Arc::new(ShaderVariant {
+            source: preprocess_wgsl(source, key.keywords)?,
+            key: key.clone(),
+        });
+        self.variants.insert(key, Arc::clone(&variant));
+        Ok(variant)
+    }
+
+    pub fn get(&self, key: &ShaderVariantKey) -> Option<Arc<ShaderVariant>> {
+        self.variants.get(key).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.variants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    /// 移除某个着色器的全部变体(源码变化后调用)
+    pub fn invalidate_shader(&mut self, shader_name: &str) {
+        self.variants.retain(|key, _| key.shader_name != shader_name);
+    }
+
+    pub fn clear(&mut self) {
+        self.variants.clear();
+    }
+}
+
+/// 管线变体标识：着色器变体加上图元的剔除方式与环绕方向
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineVariantKey {
+    pub variant: ShaderVariantKey,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+}
+
+impl PipelineVariantKey {
+    pub fn new(variant: ShaderVariantKey, cull_mode: CullMode, front_face: FrontFace) -> Self {
+        Self {
+            variant,
+            cull_mode,
+            front_face,
+        }
+    }
+
+    /// 材质使用的管线，双面材质不剔除
+    pub fn for_material(material: &Material) -> Self {
+        Self::new(ShaderVariantKey::for_material(material), material.effective_cull_mode(), material.front_face)
+    }
+
+    /// 管线的图元状态
+    pub fn primitive_state(&self) -> wgpu::PrimitiveState {
+        wgpu::PrimitiveState {
+            front_face: self.front_face.to_wgpu(),
+            cull_mode: self.cull_mode.to_wgpu(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 按变体缓存的渲染管线，首次使用某个变体时才编译
+///
+/// 剔除方式或环绕方向不同的管线共享同一个着色器模块。
+#[derive(Default)]
+pub struct PipelineVariantCache {
+    modules: HashMap<ShaderVariantKey, wgpu::ShaderModule>,
+    pipelines: HashMap<PipelineVariantKey, Arc<wgpu::RenderPipeline>>,
+}
+
+impl PipelineVariantCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取得变体对应的管线，未缓存时编译着色器并调用 `create` 创建管线
+    pub fn get_or_create(
+        &mut self,
+        device: &wgpu::Device,
+        variant: &ShaderVariant,
+        cull_mode: CullMode,
+        front_face: FrontFace,
+        create: impl FnOnce(&wgpu::Device, &wgpu::ShaderModule, &PipelineVariantKey) -> wgpu::RenderPipeline,
+    ) -> Arc<wgpu::RenderPipeline> {
+        let key = PipelineVariantKey::new(variant.key.clone(), cull_mode, front_face);
+        if let Some(pipeline) = self.pipelines.get(&key) {
+            return Arc::clone(pipeline);
+        }
+
+        let module = self.modules.entry(variant.key.clone()).or_insert_with(|| {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(&variant.label()),
+                source: wgpu::ShaderSource::Wgsl(variant.source.as_str().into()),
+            })
+        });
+        let pipeline = Arc::new(create(device, module, &key));
+        self.pipelines.insert(key, Arc::clone(&pipeline));
+        pipeline
+    }
+
+    pub fn get(&self, key: &PipelineVariantKey) -> Option<Arc<wgpu::RenderPipeline>> {
+        self.pipelines.get(key).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pipelines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pipelines.is_empty()
+    }
+
+    pub fn invalidate_shader(&mut self, shader_name: &str) {
+        self.modules.retain(|key, _| key.shader_name != shader_name);
+        self.pipelines.retain(|key, _| key.variant.shader_name != shader_name);
+    }
+
+    pub fn clear(&mut self) {
+        self.modules.clear();
+        self.pipelines.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "a\n#ifdef HAS_NORMAL_MAP\nb\n#ifndef HAS_EMISSION\nc\n#else\nd\n#endif\n#else\ne\n#endif\nf";
+
+    fn kept_lines(source: &str) -> Vec<&str> {
+        source.lines().filter(|line| !line.is_empty()).collect()
+    }
+
+    #[test]
+    fn preprocess_selects_nested_branches() {
+        let none = preprocess_wgsl(SOURCE, ShaderKeywordSet::new()).unwrap();
+        assert_eq!(kept_lines(&none), vec!["a", "e", "f"]);
+        // 被排除的行保留为空行，行号不变
+        assert_eq!(none.lines().count(), SOURCE.lines().count());
+
+        let normal = ShaderKeywordSet::new().with(ShaderKeyword::NormalMap);
+        assert_eq!(kept_lines(&preprocess_wgsl(SOURCE, normal).unwrap()), vec!["a", "b", "c", "f"]);
+
+        let both = normal.with(ShaderKeyword::Emission);
+        assert_eq!(kept_lines(&preprocess_wgsl(SOURCE, both).unwrap()), vec!["a", "b", "d", "f"]);
+    }
+
+    #[test]
+    fn preprocess_rejects_unbalanced_directives() {
+        assert!(preprocess_wgsl("#ifdef HAS_IBL\na", ShaderKeywordSet::new()).is_err());
+        assert!(preprocess_wgsl("a\n#endif", ShaderKeywordSet::new()).is_err());
+        assert!(preprocess_wgsl("#else", ShaderKeywordSet::new()).is_err());
+    }
+
+    #[test]
+    fn keyword_set_follows_material_features() {
+        let plain = Material::pbr("plain");
+        assert!(ShaderKeywordSet::from_material(&plain).is_empty());
+
+        let mapped = Material::pbr("mapped")
+            .with_texture(TextureSlot::Normal, "textures/brick_n.png")
+            .with_texture(TextureSlot::Roughness, "textures/brick_r.png");
+        let keywords = ShaderKeywordSet::from_material(&mapped);
+        assert_eq!(keywords.defines(), vec!["HAS_NORMAL_MAP", "HAS_METALLIC_ROUGHNESS_MAP"]);
+        assert_eq!(keywords.label("pbr"), "pbr[HAS_NORMAL_MAP|HAS_METALLIC_ROUGHNESS_MAP]");
+    }
+
+    #[test]
+    fn materials_differing_in_normal_map_use_different_variants() {
+        let mut cache = ShaderVariantCache::new();
+        let plain_a = Material::pbr("plain_a");
+        let plain_b = Material::pbr("plain_b");
+        let normal = Material::pbr("normal").with_texture(TextureSlot::Normal, "textures/brick_n.png");
+
+        let a = cache.get_or_preprocess(ShaderVariantKey::for_material(&plain_a), SOURCE).unwrap();
+        let b = cache.get_or_preprocess(ShaderVariantKey::for_material(&plain_b), SOURCE).unwrap();
+        let n = cache.get_or_preprocess(ShaderVariantKey::for_material(&normal), SOURCE).unwrap();
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &n));
+        assert_ne!(a.source, n.source);
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate_shader("pbr");
+        assert!(cache.is_empty());
+    }
+}
@@ -9,19 +9,93 @@ struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec3<f32>,
     @location(1) tex_coords: vec2<f32>,
+    @location(2) alpha: f32,
+    @location(3) world_position: vec3<f32>,
+}
+
+// 每次绘制的uniform，对应render_system中的DrawUniform
+struct DrawUniform {
+    model_view_projection: mat4x4<f32>,
+    model: mat4x4<f32>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> draw: DrawUniform;
+
+// 与fog.rs中的FogUniform布局一致
+struct FogUniform {
+    color: vec4<f32>,
+    params: vec4<f32>,
+}
+
+// 每个相机的雾参数，对应render_system中的CameraFogUniform
+struct CameraFog {
+    camera_position: vec4<f32>,
+    fog: FogUniform,
+}
+
+@group(1) @binding(0)
+var<uniform> camera_fog: CameraFog;
+
+// 与Fog::factor保持一致
+fn apply_fog(color: vec3<f32>, world_position: vec3<f32>) -> vec3<f32> {
+    let fog = camera_fog.fog;
+    if (fog.color.a < 0.5) {
+        return color;
+    }
+    let distance = length(world_position - camera_fog.camera_position.xyz);
+    let mode = u32(fog.params.x);
+    var factor = 0.0;
+    if (mode == 0u) {
+        let range = fog.params.w - fog.params.z;
+        factor = select(step(fog.params.w, distance), (distance - fog.params.z) / range, range > 1e-6);
+    } else if (mode == 1u) {
+        factor = 1.0 - exp(-fog.params.y * distance);
+    } else {
+        let scaled = fog.params.y * distance;
+        factor = 1.0 - exp(-scaled * scaled);
+    }
+    return mix(color, fog.color.rgb, clamp(factor, 0.0, 1.0));
 }
 
 @vertex
 fn vs_main(model: VertexInput) -> VertexOutput {
     var out: VertexOutput;
-    out.color = model.color;
+    out.color = model.color * draw.color.rgb;
+    out.alpha = draw.color.a;
     out.tex_coords = model.tex_coords;
-    out.clip_position = vec4<f32>(model.position, 1.0);
+    out.world_position = (draw.model * vec4<f32>(model.position, 1.0)).xyz;
+    out.clip_position = draw.model_view_projection * vec4<f32>(model.position, 1.0);
     return out;
 }
 
 // 基础片段着色器
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color, 1.0);
+    return vec4<f32>(apply_fog(in.color, in.world_position), 1.0);
+}
+
+// 加权混合OIT累积输出，混合状态见 OitRenderer::accumulation_targets
+struct OitOutput {
+    @location(0) accum: vec4<f32>,
+    @location(1) reveal: vec4<f32>,
+}
+
+// 与OitAccumulator::weight保持一致
+fn oit_weight(depth: f32, alpha: f32) -> f32 {
+    let d = 1.0 - clamp(depth, 0.0, 1.0);
+    return alpha * max(1e-2, 3e3 * d * d * d);
+}
+
+// 透明片段着色器，写入OIT累积缓冲
+@fragment
+fn fs_accumulate(in: VertexOutput) -> OitOutput {
+    let alpha = clamp(in.alpha, 0.0, 1.0);
+    let weight = oit_weight(in.clip_position.z, alpha);
+    var out: OitOutput;
+    let color = apply_fog(in.color, in.world_position);
+    out.accum = vec4<f32>(color * alpha, alpha) * weight;
+    out.reveal = vec4<f32>(alpha);
+    return out;
 }
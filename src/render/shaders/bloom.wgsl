@@ -0,0 +1,96 @@
+// Bloom着色器：亮部提取 -> 逐级下采样 -> 逐级上采样模糊 -> 叠加合成
+
+struct BloomUniforms {
+    // 源纹理的纹素大小
+    texel_size: vec2<f32>,
+    threshold: f32,
+    knee: f32,
+    intensity: f32,
+    radius: f32,
+    _padding: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: BloomUniforms;
+
+@group(0) @binding(1)
+var source_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var linear_sampler: sampler;
+
+@group(0) @binding(3)
+var bloom_texture: texture_2d<f32>;
+
+// 全屏三角形，不需要顶点缓冲
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>(x * 0.5 + 0.5, 0.5 - y * 0.5);
+    return out;
+}
+
+fn luminance(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+// 与BloomProcessor::bright_pass保持一致的软阈值
+fn bright_pass(color: vec3<f32>) -> vec3<f32> {
+    let brightness = luminance(color);
+    let soft = clamp(brightness - uniforms.threshold + uniforms.knee, 0.0, 2.0 * uniforms.knee);
+    let soft_curve = soft * soft / (4.0 * uniforms.knee + 1e-5);
+    let contribution = max(soft_curve, brightness - uniforms.threshold) / max(brightness, 1e-5);
+    return color * max(contribution, 0.0);
+}
+
+// 13点下采样滤波的简化版：4个双线性采样覆盖4x4纹素
+fn downsample(uv: vec2<f32>) -> vec3<f32> {
+    let offset = uniforms.texel_size;
+    var color = textureSample(source_texture, linear_sampler, uv + vec2<f32>(-offset.x, -offset.y)).rgb;
+    color += textureSample(source_texture, linear_sampler, uv + vec2<f32>(offset.x, -offset.y)).rgb;
+    color += textureSample(source_texture, linear_sampler, uv + vec2<f32>(-offset.x, offset.y)).rgb;
+    color += textureSample(source_texture, linear_sampler, uv + vec2<f32>(offset.x, offset.y)).rgb;
+    return color * 0.25;
+}
+
+@fragment
+fn fs_prefilter(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(bright_pass(downsample(in.uv)), 1.0);
+}
+
+@fragment
+fn fs_downsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(downsample(in.uv), 1.0);
+}
+
+// 3x3帐篷滤波上采样，结果以加法混合写入上一级
+@fragment
+fn fs_upsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    let offset = uniforms.texel_size * uniforms.radius;
+    var color = textureSample(source_texture, linear_sampler, in.uv).rgb * 4.0;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(-offset.x, 0.0)).rgb * 2.0;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(offset.x, 0.0)).rgb * 2.0;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(0.0, -offset.y)).rgb * 2.0;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(0.0, offset.y)).rgb * 2.0;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(-offset.x, -offset.y)).rgb;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(offset.x, -offset.y)).rgb;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(-offset.x, offset.y)).rgb;
+    color += textureSample(source_texture, linear_sampler, in.uv + vec2<f32>(offset.x, offset.y)).rgb;
+    return vec4<f32>(color / 16.0, 1.0);
+}
+
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let scene = textureSample(source_texture, linear_sampler, in.uv);
+    let bloom = textureSample(bloom_texture, linear_sampler, in.uv).rgb;
+    return vec4<f32>(scene.rgb + bloom * uniforms.intensity, scene.a);
+}
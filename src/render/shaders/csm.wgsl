@@ -37,8 +37,8 @@ struct LightUniforms {
     intensity: f32,
     light_type: u32,
     range: f32,
-    spot_angle: f32,
-    _padding3: f32,
+    spot_angle: f32,       // 外锥半角
+    spot_inner_angle: f32, // 内锥半角
 };
 
 struct CSMUniforms {
@@ -245,6 +245,17 @@ fn calculate_lighting(
     return ambient + diffuse + specular;
 }
 
+// 聚光灯锥体衰减：内锥以内为1，外锥以外为0，之间平滑过渡
+fn spot_cone_falloff(light_dir: vec3<f32>) -> f32 {
+    if (light.light_type != 2u) {
+        return 1.0;
+    }
+    let cos_angle = dot(-light_dir, normalize(light.direction));
+    let cos_outer = cos(light.spot_angle);
+    let cos_inner = max(cos(min(light.spot_inner_angle, light.spot_angle)), cos_outer + 1e-4);
+    return smoothstep(cos_outer, cos_inner, cos_angle);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // 采样基础颜色纹理
@@ -269,7 +280,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         normalize(in.world_normal),
         view_dir,
         light_dir,
-        light.color * light.intensity,
+        light.color * light.intensity * spot_cone_falloff(light_dir),
         base_color.rgb
     );
     
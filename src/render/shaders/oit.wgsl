@@ -0,0 +1,43 @@
+// 加权混合顺序无关透明(Weighted Blended OIT)合成着色器
+//
+// 透明物体的片元着色器需输出到两个目标：
+//   @location(0) accum  = vec4(color.rgb * alpha, alpha) * oit_weight(depth, alpha)
+//   @location(1) reveal = alpha
+// 对应的混合状态见 OitRenderer::accumulation_targets。
+
+@group(0) @binding(0)
+var accum_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var reveal_texture: texture_2d<f32>;
+
+// 全屏三角形，不需要顶点缓冲
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+// 与OitAccumulator::weight保持一致的深度权重，depth为[0, 1]的非线性深度
+fn oit_weight(depth: f32, alpha: f32) -> f32 {
+    let d = 1.0 - depth;
+    return alpha * max(1e-2, 3e3 * d * d * d);
+}
+
+// 以预乘透明度输出，混合状态为 One / OneMinusSrcAlpha
+@fragment
+fn fs_composite(@builtin(position) frag_coord: vec4<f32>) -> @location(0) vec4<f32> {
+    let coords = vec2<i32>(frag_coord.xy);
+    let revealage = textureLoad(reveal_texture, coords, 0).r;
+
+    // 没有透明物体覆盖的像素直接丢弃
+    if revealage >= 1.0 {
+        discard;
+    }
+
+    let accum = textureLoad(accum_texture, coords, 0);
+    let average_color = accum.rgb / max(accum.a, 1e-5);
+    let alpha = 1.0 - revealage;
+    return vec4<f32>(average_color * alpha, alpha);
+}
@@ -0,0 +1,65 @@
+// GPU粒子模拟，与 ParticleEmitter::update_particles 的CPU路径保持一致(不含生命周期曲线)
+
+struct Particle {
+    position: vec3<f32>,
+    lifetime: f32,
+    velocity: vec3<f32>,
+    max_lifetime: f32,
+    color: vec4<f32>,
+    size: f32,
+    _padding: array<f32, 3>,
+};
+
+struct SimulationParams {
+    gravity: vec3<f32>,
+    delta_time: f32,
+    start_color: vec4<f32>,
+    end_color: vec4<f32>,
+    particle_count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+};
+
+// 与 wgpu 间接绘制参数布局一致，instance_count 为本帧存活粒子数
+struct DrawArgs {
+    vertex_count: u32,
+    instance_count: atomic<u32>,
+    first_vertex: u32,
+    first_instance: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: SimulationParams;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> alive_indices: array<u32>;
+@group(0) @binding(3) var<storage, read_write> draw_args: DrawArgs;
+
+@compute @workgroup_size(64)
+fn cs_simulate(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= params.particle_count) {
+        return;
+    }
+
+    var particle = particles[index];
+    if (particle.lifetime <= 0.0) {
+        return;
+    }
+
+    particle.lifetime -= params.delta_time;
+    if (particle.lifetime <= 0.0) {
+        particle.lifetime = 0.0;
+        particles[index] = particle;
+        return;
+    }
+
+    let lifetime_ratio = 1.0 - particle.lifetime / particle.max_lifetime;
+    particle.velocity += params.gravity * params.delta_time;
+    particle.position += particle.velocity * params.delta_time;
+    particle.color = mix(params.start_color, params.end_color, lifetime_ratio);
+    particles[index] = particle;
+
+    // 存活粒子写入紧凑索引表，渲染时按实例读取
+    let slot = atomicAdd(&draw_args.instance_count, 1u);
+    alive_indices[slot] = index;
+}
@@ -0,0 +1,116 @@
+// 景深着色器：深度 -> 弥散圈(CoC) -> 水平/垂直可分离模糊 -> 按CoC混合
+
+struct DepthOfFieldUniforms {
+    texel_size: vec2<f32>,
+    // 模糊方向，(1, 0)为水平，(0, 1)为垂直
+    direction: vec2<f32>,
+    near_plane: f32,
+    far_plane: f32,
+    focus_distance: f32,
+    focus_range: f32,
+    aperture: f32,
+    // 最大模糊半径(像素)，已乘以模糊强度
+    max_radius: f32,
+    orthographic: f32,
+    _padding: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// 单侧采样数，与DOF_SAMPLE_COUNT一致
+const SAMPLE_COUNT: i32 = 8;
+
+@group(0) @binding(0)
+var<uniform> uniforms: DepthOfFieldUniforms;
+
+@group(0) @binding(1)
+var source_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var linear_sampler: sampler;
+
+@group(0) @binding(3)
+var coc_texture: texture_2d<f32>;
+
+@group(0) @binding(4)
+var depth_texture: texture_depth_2d;
+
+@group(0) @binding(5)
+var blurred_texture: texture_2d<f32>;
+
+// 全屏三角形，不需要顶点缓冲
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>(x * 0.5 + 0.5, 0.5 - y * 0.5);
+    return out;
+}
+
+// 与DepthOfFieldCamera::linearize_depth保持一致
+fn linearize_depth(depth: f32) -> f32 {
+    let near = uniforms.near_plane;
+    let far = uniforms.far_plane;
+    if (uniforms.orthographic > 0.5) {
+        return near + depth * (far - near);
+    }
+    return near * far / max(far - depth * (far - near), 1e-6);
+}
+
+// 与DepthOfFieldProcessor::circle_of_confusion保持一致
+fn circle_of_confusion(linear_depth: f32) -> f32 {
+    let defocus = abs(linear_depth - uniforms.focus_distance) - uniforms.focus_range * 0.5;
+    if (defocus <= 0.0) {
+        return 0.0;
+    }
+    return clamp(uniforms.aperture * defocus / max(linear_depth, 1e-4), 0.0, 1.0);
+}
+
+@fragment
+fn fs_coc(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = vec2<i32>(textureDimensions(depth_texture));
+    let coord = clamp(vec2<i32>(in.clip_position.xy), vec2<i32>(0), size - vec2<i32>(1));
+    let depth = textureLoad(depth_texture, coord, 0);
+    return vec4<f32>(circle_of_confusion(linearize_depth(depth)), 0.0, 0.0, 1.0);
+}
+
+// 沿uniforms.direction的CoC加权高斯模糊
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    let center = textureSampleLevel(source_texture, linear_sampler, in.uv, 0.0);
+    let center_coc = textureSampleLevel(coc_texture, linear_sampler, in.uv, 0.0).r;
+    let step = center_coc * uniforms.max_radius / f32(SAMPLE_COUNT);
+    if (step < 0.5) {
+        return center;
+    }
+
+    var sum = vec3<f32>(0.0);
+    var total_weight = 0.0;
+    for (var i = -SAMPLE_COUNT; i <= SAMPLE_COUNT; i++) {
+        let offset = round(f32(i) * step) * uniforms.direction * uniforms.texel_size;
+        let sample_uv = in.uv + offset;
+        let color = textureSampleLevel(source_texture, linear_sampler, sample_uv, 0.0).rgb;
+        let sample_coc = textureSampleLevel(coc_texture, linear_sampler, sample_uv, 0.0).r;
+        let t = f32(i) / f32(SAMPLE_COUNT);
+        // 清晰的样本权重低，避免前景渗入失焦区域
+        let weight = exp(-2.0 * t * t) * (sample_coc + 0.05);
+        sum += color * weight;
+        total_weight += weight;
+    }
+
+    return vec4<f32>(sum / total_weight, center.a);
+}
+
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let sharp = textureSample(source_texture, linear_sampler, in.uv);
+    let blurred = textureSample(blurred_texture, linear_sampler, in.uv).rgb;
+    let coc = textureSample(coc_texture, linear_sampler, in.uv).r;
+    return vec4<f32>(mix(sharp.rgb, blurred, coc), sharp.a);
+}
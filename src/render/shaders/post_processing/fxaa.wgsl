@@ -1,10 +1,5 @@
 // FXAA (Fast Approximate Anti-Aliasing) 着色器
 
-struct VertexInput {
-    @location(0) position: vec2<f32>,
-    @location(1) uv: vec2<f32>,
-};
-
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) uv: vec2<f32>,
@@ -25,11 +20,15 @@ var input_texture: texture_2d<f32>;
 @group(0) @binding(2)
 var input_sampler: sampler;
 
+// 全屏三角形，不需要顶点缓冲
 @vertex
-fn vs_main(vertex: VertexInput) -> VertexOutput {
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+
     var out: VertexOutput;
-    out.clip_position = vec4<f32>(vertex.position, 0.0, 1.0);
-    out.uv = vertex.uv;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>(x * 0.5 + 0.5, 0.5 - y * 0.5);
     return out;
 }
 
@@ -83,11 +82,11 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let FXAA_SUBPIX_CAP = 0.75;
     
     // 采样中心像素和周围像素
-    let rgbM = textureSample(input_texture, input_sampler, uv).rgb;
-    let rgbNW = textureSample(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, -texel_size.y)).rgb;
-    let rgbNE = textureSample(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, -texel_size.y)).rgb;
-    let rgbSW = textureSample(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, texel_size.y)).rgb;
-    let rgbSE = textureSample(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, texel_size.y)).rgb;
+    let rgbM = textureSampleLevel(input_texture, input_sampler, uv, 0.0).rgb;
+    let rgbNW = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, -texel_size.y), 0.0).rgb;
+    let rgbNE = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, -texel_size.y), 0.0).rgb;
+    let rgbSW = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, texel_size.y), 0.0).rgb;
+    let rgbSE = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, texel_size.y), 0.0).rgb;
     
     // 转换为亮度
     let lumaM = rgb_to_luma(rgbM);
@@ -107,10 +106,10 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     }
     
     // 采样更多邻居像素
-    let rgbN = textureSample(input_texture, input_sampler, uv + vec2<f32>(0.0, -texel_size.y)).rgb;
-    let rgbS = textureSample(input_texture, input_sampler, uv + vec2<f32>(0.0, texel_size.y)).rgb;
-    let rgbW = textureSample(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, 0.0)).rgb;
-    let rgbE = textureSample(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, 0.0)).rgb;
+    let rgbN = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(0.0, -texel_size.y), 0.0).rgb;
+    let rgbS = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(0.0, texel_size.y), 0.0).rgb;
+    let rgbW = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, 0.0), 0.0).rgb;
+    let rgbE = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, 0.0), 0.0).rgb;
     
     let lumaN = rgb_to_luma(rgbN);
     let lumaS = rgb_to_luma(rgbS);
@@ -127,7 +126,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // 计算边缘方向
     let edgeVert = abs((lumaNW + lumaN + lumaNE) - (lumaSW + lumaS + lumaSE));
     let edgeHorz = abs((lumaNW + lumaW + lumaSW) - (lumaNE + lumaE + lumaSE));
-    let horzSpan = edgeHorz >= edgeVert;
+    let horzSpan = edgeVert >= edgeHorz;
     
     // 选择步长方向
     var lengthSign: f32;
@@ -166,8 +165,8 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     }
     
     // 采样边缘点
-    let luma1 = rgb_to_luma(textureSample(input_texture, input_sampler, uv1).rgb);
-    let luma2 = rgb_to_luma(textureSample(input_texture, input_sampler, uv2).rgb);
+    let luma1 = rgb_to_luma(textureSampleLevel(input_texture, input_sampler, uv1, 0.0).rgb);
+    let luma2 = rgb_to_luma(textureSampleLevel(input_texture, input_sampler, uv2, 0.0).rgb);
     
     // 计算边缘长度
     let reached1 = abs(luma1 - lumaM) >= gradientScaled;
@@ -188,7 +187,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let distanceMin = min(distance1, distance2);
     let pixelOffset = 0.5 - distanceMin / (distance1 + distance2);
     let pixelOffsetGood = pixelOffset > 0.0;
-    let pixelOffsetSubpix = max(pixelOffsetGood ? pixelOffset : 0.0, blendL_capped);
+    let pixelOffsetSubpix = max(select(0.0, pixelOffset, pixelOffsetGood), blendL_capped);
     
     // 计算最终UV坐标
     var finalUV = uv;
@@ -199,7 +198,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     }
     
     // 返回最终颜色
-    let finalColor = textureSample(input_texture, input_sampler, finalUV).rgb;
+    let finalColor = textureSampleLevel(input_texture, input_sampler, finalUV, 0.0).rgb;
     return vec4<f32>(finalColor, 1.0);
 }
 
@@ -210,11 +209,11 @@ fn fs_fxaa_simple(in: VertexOutput) -> @location(0) vec4<f32> {
     let uv = in.uv;
     
     // 采样5个点
-    let rgbM = textureSample(input_texture, input_sampler, uv).rgb;
-    let rgbN = textureSample(input_texture, input_sampler, uv + vec2<f32>(0.0, -texel_size.y)).rgb;
-    let rgbS = textureSample(input_texture, input_sampler, uv + vec2<f32>(0.0, texel_size.y)).rgb;
-    let rgbW = textureSample(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, 0.0)).rgb;
-    let rgbE = textureSample(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, 0.0)).rgb;
+    let rgbM = textureSampleLevel(input_texture, input_sampler, uv, 0.0).rgb;
+    let rgbN = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(0.0, -texel_size.y), 0.0).rgb;
+    let rgbS = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(0.0, texel_size.y), 0.0).rgb;
+    let rgbW = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(-texel_size.x, 0.0), 0.0).rgb;
+    let rgbE = textureSampleLevel(input_texture, input_sampler, uv + vec2<f32>(texel_size.x, 0.0), 0.0).rgb;
     
     // 计算亮度
     let lumaM = rgb_to_luma(rgbM);
@@ -242,14 +241,14 @@ fn fs_fxaa_simple(in: VertexOutput) -> @location(0) vec4<f32> {
     
     if (edgeH > edgeV) {
         // 水平边缘，垂直模糊
-        offset.y = texel_size.y * (lumaN > lumaS ? -1.0 : 1.0) * blend_factor;
+        offset.y = texel_size.y * select(1.0, -1.0, lumaN > lumaS) * blend_factor;
     } else {
         // 垂直边缘，水平模糊
-        offset.x = texel_size.x * (lumaW > lumaE ? -1.0 : 1.0) * blend_factor;
+        offset.x = texel_size.x * select(1.0, -1.0, lumaW > lumaE) * blend_factor;
     }
     
     // 混合颜色
-    let blended_color = textureSample(input_texture, input_sampler, uv + offset).rgb;
+    let blended_color = textureSampleLevel(input_texture, input_sampler, uv + offset, 0.0).rgb;
     let final_color = mix(rgbM, blended_color, 0.5);
     
     return vec4<f32>(final_color, 1.0);
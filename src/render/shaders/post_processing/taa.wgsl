@@ -0,0 +1,89 @@
+// TAA着色器：深度重投影 -> 邻域钳制历史颜色 -> 按feedback混合
+
+struct TaaUniforms {
+    // 当前帧(未抖动)视图投影矩阵的逆
+    inverse_view_projection: mat4x4<f32>,
+    previous_view_projection: mat4x4<f32>,
+    texel_size: vec2<f32>,
+    // 历史帧混合权重
+    feedback: f32,
+    history_valid: f32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: TaaUniforms;
+
+@group(0) @binding(1)
+var current_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var linear_sampler: sampler;
+
+@group(0) @binding(3)
+var history_texture: texture_2d<f32>;
+
+// 深度按非过滤浮点纹理绑定，GL后端不支持从深度纹理textureLoad
+@group(0) @binding(4)
+var depth_texture: texture_2d<f32>;
+
+// 全屏三角形，不需要顶点缓冲
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>(x * 0.5 + 0.5, 0.5 - y * 0.5);
+    return out;
+}
+
+// 与TaaResolver::reproject保持一致
+fn reproject(uv: vec2<f32>, depth: f32) -> vec2<f32> {
+    let ndc = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, depth, 1.0);
+    let world = uniforms.inverse_view_projection * ndc;
+    let previous = uniforms.previous_view_projection * (world / world.w);
+    let previous_ndc = previous.xy / previous.w;
+    return vec2<f32>(previous_ndc.x * 0.5 + 0.5, 0.5 - previous_ndc.y * 0.5);
+}
+
+@fragment
+fn fs_resolve(in: VertexOutput) -> @location(0) vec4<f32> {
+    let current = textureSampleLevel(current_texture, linear_sampler, in.uv, 0.0);
+
+    // 当前帧3x3邻域的颜色范围
+    var neighborhood_min = current.rgb;
+    var neighborhood_max = current.rgb;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * uniforms.texel_size;
+            let color = textureSampleLevel(current_texture, linear_sampler, in.uv + offset, 0.0).rgb;
+            neighborhood_min = min(neighborhood_min, color);
+            neighborhood_max = max(neighborhood_max, color);
+        }
+    }
+
+    let size = vec2<i32>(textureDimensions(depth_texture));
+    let coord = clamp(vec2<i32>(in.clip_position.xy), vec2<i32>(0), size - vec2<i32>(1));
+    let depth = textureLoad(depth_texture, coord, 0).r;
+    let history_uv = reproject(in.uv, depth);
+    let history = textureSampleLevel(history_texture, linear_sampler, history_uv, 0.0).rgb;
+
+    // 与TaaResolver::resolve保持一致
+    let on_screen = all(history_uv >= vec2<f32>(0.0)) && all(history_uv <= vec2<f32>(1.0));
+    if (uniforms.history_valid < 0.5 || !on_screen) {
+        return current;
+    }
+    let clamped = clamp(history, neighborhood_min, neighborhood_max);
+    return vec4<f32>(mix(current.rgb, clamped, clamp(uniforms.feedback, 0.0, 1.0)), current.a);
+}
+
+@fragment
+fn fs_copy(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSampleLevel(current_texture, linear_sampler, in.uv, 0.0);
+}
@@ -16,6 +16,7 @@ struct ShadowUniforms {
     cascade_count: u32,
     _padding: u32,
     cascade_distances: array<f32, 4>,
+    atlas_rect: vec4<f32>, // 图集区域：xy缩放，zw偏移
 };
 
 @group(0) @binding(0)
@@ -35,8 +35,8 @@ struct LightUniforms {
     intensity: f32,
     light_type: u32, // 0=directional, 1=point, 2=spot
     range: f32,
-    spot_angle: f32,
-    _padding3: f32,
+    spot_angle: f32,       // 外锥半角
+    spot_inner_angle: f32, // 内锥半角
 };
 
 struct ShadowUniforms {
@@ -47,6 +47,7 @@ struct ShadowUniforms {
     cascade_count: u32,
     _padding: u32,
     cascade_distances: array<f32, 4>,
+    atlas_rect: vec4<f32>, // 图集区域：xy缩放，zw偏移
 };
 
 @group(0) @binding(0)
@@ -93,16 +94,34 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
     return out;
 }
 
-// PCF（百分比滤波）阴影计算
+// 阴影图集的纹素大小
+fn shadow_texel_size() -> vec2<f32> {
+    return 1.0 / vec2<f32>(textureDimensions(shadow_map));
+}
+
+// 光源局部阴影坐标[0,1]映射到图集中该光源的区域
+fn to_atlas_uv(local_uv: vec2<f32>) -> vec2<f32> {
+    return local_uv * shadow_uniforms.atlas_rect.xy + shadow_uniforms.atlas_rect.zw;
+}
+
+// 把采样坐标限制在光源区域内，避免滤波采到相邻光源的阴影
+fn clamp_to_atlas_rect(uv: vec2<f32>) -> vec2<f32> {
+    let half_texel = shadow_texel_size() * 0.5;
+    let rect_min = shadow_uniforms.atlas_rect.zw + half_texel;
+    let rect_max = shadow_uniforms.atlas_rect.zw + shadow_uniforms.atlas_rect.xy - half_texel;
+    return clamp(uv, rect_min, rect_max);
+}
+
+// PCF（百分比滤波）阴影计算，shadow_coord.xy为图集坐标
 fn calculate_shadow_pcf(shadow_coord: vec3<f32>, bias: f32) -> f32 {
-    let texel_size = 1.0 / 2048.0; // 假设2048x2048阴影贴图
+    let texel_size = shadow_texel_size();
     var shadow = 0.0;
     
     // 3x3 PCF采样
     for (var x = -1; x <= 1; x++) {
         for (var y = -1; y <= 1; y++) {
             let offset = vec2<f32>(f32(x), f32(y)) * texel_size;
-            let sample_coord = shadow_coord.xy + offset;
+            let sample_coord = clamp_to_atlas_rect(shadow_coord.xy + offset);
             shadow += textureSampleCompare(shadow_map, shadow_sampler, sample_coord, shadow_coord.z - bias);
         }
     }
@@ -136,13 +155,12 @@ fn calculate_shadow_poisson(shadow_coord: vec3<f32>, bias: f32) -> f32 {
         vec2<f32>(0.14383161, -0.14100790)
     );
     
-    let texel_size = 1.0 / 2048.0;
-    let disk_radius = 2.0 * texel_size;
+    let disk_radius = 2.0 * shadow_texel_size();
     
     var shadow = 0.0;
     for (var i = 0; i < 16; i++) {
         let offset = poisson_disk[i] * disk_radius;
-        let sample_coord = shadow_coord.xy + offset;
+        let sample_coord = clamp_to_atlas_rect(shadow_coord.xy + offset);
         shadow += textureSampleCompare(shadow_map, shadow_sampler, sample_coord, shadow_coord.z - bias);
     }
     
@@ -169,6 +187,9 @@ fn calculate_shadow(light_space_pos: vec4<f32>, world_normal: vec3<f32>, light_d
     let n_dot_l = dot(world_normal, light_dir);
     let bias = shadow_uniforms.shadow_bias + shadow_uniforms.normal_bias * sqrt(1.0 - n_dot_l * n_dot_l);
     
+    // 偏移到光源在阴影图集中的区域
+    shadow_coord = vec3<f32>(to_atlas_uv(shadow_coord.xy), shadow_coord.z);
+    
     // 使用PCF进行软阴影计算
     return calculate_shadow_pcf(shadow_coord, bias);
 }
@@ -196,6 +217,17 @@ fn calculate_lighting(
     return ambient + diffuse + specular;
 }
 
+// 聚光灯锥体衰减：内锥以内为1，外锥以外为0，之间平滑过渡
+fn spot_cone_falloff(light_dir: vec3<f32>) -> f32 {
+    if (light.light_type != 2u) {
+        return 1.0;
+    }
+    let cos_angle = dot(-light_dir, normalize(light.direction));
+    let cos_outer = cos(light.spot_angle);
+    let cos_inner = max(cos(min(light.spot_inner_angle, light.spot_angle)), cos_outer + 1e-4);
+    return smoothstep(cos_outer, cos_inner, cos_angle);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // 采样基础颜色纹理
@@ -220,7 +252,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         normalize(in.world_normal),
         view_dir,
         light_dir,
-        light.color * light.intensity,
+        light.color * light.intensity * spot_cone_falloff(light_dir),
         base_color.rgb
     );
     
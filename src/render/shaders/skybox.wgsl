@@ -0,0 +1,53 @@
+// 天空盒着色器
+
+struct SkyboxUniforms {
+    inv_view_projection: mat4x4<f32>,
+    top_color: vec4<f32>,
+    bottom_color: vec4<f32>,
+    // x为1时采样立方体贴图
+    params: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: SkyboxUniforms;
+
+@group(0) @binding(1)
+var cubemap_texture: texture_cube<f32>;
+
+@group(0) @binding(2)
+var cubemap_sampler: sampler;
+
+// 全屏三角形，不需要顶点缓冲
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 1.0, 1.0);
+    out.ndc = vec2<f32>(x, y);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // 从NDC重建世界空间视线方向
+    let near = uniforms.inv_view_projection * vec4<f32>(in.ndc, 0.0, 1.0);
+    let far = uniforms.inv_view_projection * vec4<f32>(in.ndc, 1.0, 1.0);
+    let direction = normalize(far.xyz / far.w - near.xyz / near.w);
+
+    // 立方体贴图的面朝向与wgpu一致，直接按世界方向采样
+    let cubemap_color = textureSampleLevel(cubemap_texture, cubemap_sampler, direction, 0.0);
+    if (uniforms.params.x > 0.5) {
+        return vec4<f32>(cubemap_color.rgb, 1.0);
+    }
+
+    // 与Skybox::gradient_factor保持一致
+    let t = clamp(direction.y * 0.5 + 0.5, 0.0, 1.0);
+    return mix(uniforms.bottom_color, uniforms.top_color, t);
+}
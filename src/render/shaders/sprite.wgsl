@@ -0,0 +1,52 @@
+// 精灵着色器：每个实例为一个四边形(4个顶点的三角形带)
+
+struct InstanceInput {
+    // xyz为四边形左下角，w未使用
+    @location(0) origin: vec4<f32>,
+    @location(1) axis_x: vec2<f32>,
+    @location(2) axis_y: vec2<f32>,
+    @location(3) uv_min: vec2<f32>,
+    @location(4) uv_max: vec2<f32>,
+    @location(5) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> view_projection: mat4x4<f32>;
+
+@group(1) @binding(0)
+var sprite_texture: texture_2d<f32>;
+
+@group(1) @binding(1)
+var sprite_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, instance: InstanceInput) -> VertexOutput {
+    // 0:左下 1:右下 2:左上 3:右上
+    let corner = vec2<f32>(f32(vertex_index & 1u), f32(vertex_index >> 1u));
+    let position = instance.origin.xy + instance.axis_x * corner.x + instance.axis_y * corner.y;
+
+    var out: VertexOutput;
+    out.clip_position = view_projection * vec4<f32>(position, instance.origin.z, 1.0);
+    // 纹理V轴向下，四边形底边对应uv_max.y
+    out.uv = vec2<f32>(
+        mix(instance.uv_min.x, instance.uv_max.x, corner.x),
+        mix(instance.uv_max.y, instance.uv_min.y, corner.y),
+    );
+    out.color = instance.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(sprite_texture, sprite_sampler, in.uv) * in.color;
+    if (color.a <= 0.0) {
+        discard;
+    }
+    return color;
+}
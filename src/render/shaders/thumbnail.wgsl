@@ -0,0 +1,37 @@
+// 资源缩略图：顶点已在CPU变换到世界空间，固定方向光 + 环境光
+
+struct ThumbnailUniforms {
+    view_projection: mat4x4<f32>,
+    light_direction: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> uniforms: ThumbnailUniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) color: vec3<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = uniforms.view_projection * vec4<f32>(in.position, 1.0);
+    out.normal = in.normal;
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(in.normal);
+    let diffuse = max(dot(normal, -uniforms.light_direction.xyz), 0.0);
+    let lighting = 0.25 + 0.75 * diffuse;
+    return vec4<f32>(in.color * lighting, 1.0);
+}
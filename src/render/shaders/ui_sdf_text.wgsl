@@ -0,0 +1,69 @@
+// UI距离场文本着色器
+
+struct SdfUniforms {
+    projection: mat4x4<f32>,
+    outline_color: vec4<f32>,
+    glow_color: vec4<f32>,
+    threshold: f32,
+    smoothing: f32,
+    outline_width: f32,
+    glow_width: f32,
+};
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: SdfUniforms;
+
+@group(0) @binding(1)
+var sdf_texture: texture_2d<f32>;
+
+@group(0) @binding(2)
+var sdf_sampler: sampler;
+
+@vertex
+fn vs_main(vertex: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = uniforms.projection * vec4<f32>(vertex.position, 1.0);
+    out.uv = vertex.uv;
+    out.color = vertex.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let distance = textureSample(sdf_texture, sdf_sampler, in.uv).r;
+    let smoothing = uniforms.smoothing;
+
+    // 字形主体：在阈值处做平滑过渡
+    let fill = smoothstep(uniforms.threshold - smoothing, uniforms.threshold + smoothing, distance);
+    var color = vec4<f32>(in.color.rgb, in.color.a * fill);
+
+    // 描边：阈值向外扩展outline_width
+    if (uniforms.outline_width > 0.0) {
+        let outline_edge = uniforms.threshold - uniforms.outline_width;
+        let outline = smoothstep(outline_edge - smoothing, outline_edge + smoothing, distance);
+        let outline_color = vec4<f32>(uniforms.outline_color.rgb, uniforms.outline_color.a * outline);
+        color = mix(outline_color, color, fill);
+    }
+
+    // 发光：在描边之外叠加柔和的衰减
+    if (uniforms.glow_width > 0.0) {
+        let glow_edge = uniforms.threshold - uniforms.outline_width;
+        let glow = smoothstep(glow_edge - uniforms.glow_width, glow_edge, distance);
+        let glow_color = vec4<f32>(uniforms.glow_color.rgb, uniforms.glow_color.a * glow);
+        color = mix(glow_color, color, color.a);
+    }
+
+    return color;
+}
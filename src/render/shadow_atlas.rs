@@ -0,0 +1,340 @@
+//! 阴影图集
+//!
+//! 聚光灯和点光源的阴影贴图打包到同一张深度纹理中，每个光源占用一个正方形区域，
+//! 渲染时通过视口限定绘制范围，采样时把光源空间坐标偏移到对应区域，
+//! 这样所有光源共用一个渲染目标和一次纹理绑定。
+//! 区域按伙伴算法分配：边长为2的幂，释放时与相邻的伙伴块合并。
+
+use crate::math::{Mat4, Vec4};
+use std::collections::{BTreeMap, HashMap};
+
+/// 图集中的正方形区域(像素)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShadowAtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,
+}
+
+impl ShadowAtlasRect {
+    /// 采样用的缩放与偏移：xy为缩放，zw为偏移，atlas_uv = local_uv * xy + zw
+    pub fn uv_scale_offset(&self, atlas_size: u32) -> Vec4 {
+        let atlas_size = atlas_size.max(1) as f32;
+        let scale = self.size as f32 / atlas_size;
+        Vec4::new(scale, scale, self.x as f32 / atlas_size, self.y as f32 / atlas_size)
+    }
+
+    /// 是否与另一个区域重叠
+    pub fn overlaps(&self, other: &ShadowAtlasRect) -> bool {
+        self.x < other.x + other.size
+            && other.x < self.x + self.size
+            && self.y < other.y + other.size
+            && other.y < self.y + self.size
+    }
+}
+
+/// 图集区域分配器(伙伴算法)
+#[derive(Debug, Clone)]
+pub struct ShadowAtlasAllocator {
+    atlas_size: u32,
+    min_size: u32,
+    free_blocks: BTreeMap<u32, Vec<(u32, u32)>>,
+    allocations: HashMap<u32, ShadowAtlasRect>,
+}
+
+impl ShadowAtlasAllocator {
+    /// atlas_size和min_size会向上取整为2的幂
+    pub fn new(atlas_size: u32, min_size: u32) -> Self {
+        let atlas_size = atlas_size.max(1).next_power_of_two();
+        let min_size = min_size.max(1).next_power_of_two().min(atlas_size);
+        let mut allocator = Self {
+            atlas_size,
+            min_size,
+            free_blocks: BTreeMap::new(),
+            allocations: HashMap::new(),
+        };
+        allocator.clear();
+        allocator
+    }
+
+    pub fn atlas_size(&self) -> u32 {
+        self.atlas_size
+    }
+
+    pub fn min_size(&self) -> u32 {
+        self.min_size
+    }
+
+    /// 为光源分配区域，已分配且尺寸相同时直接返回原区域
+    pub fn allocate(&mut self, light_id: u32, requested_size: u32) -> Option<ShadowAtlasRect> {
+        let size = requested_size
+            .max(self.min_size)
+            .next_power_of_two()
+            .min(self.atlas_size);
+
+        if let Some(rect) = self.allocations.get(&light_id).copied() {
+            if rect.size == size {
+                return Some(rect);
+            }
+            self.release(light_id);
+        }
+
+        // 取能容纳的最小空闲块，再逐级对半拆分
+        let block_size = *self.free_blocks.range(size..).find(|(_, blocks)| !blocks.is_empty())?.0;
+        let (x, y) = self.free_blocks.get_mut(&block_size)?.pop()?;
+
+        let mut current = block_size;
+        while current > size {
+            current /= 2;
+            let buddies = self.free_blocks.entry(current).or_default();
+            buddies.push((x + current, y));
+            buddies.push((x, y + current));
+            buddies.push((x + current, y + current));
+        }
+
+        let rect = ShadowAtlasRect { x, y, size };
+        self.allocations.insert(light_id, rect);
+        Some(rect)
+    }
+
+    /// 释放光源占用的区域
+    pub fn release(&mut self, light_id: u32) -> Option<ShadowAtlasRect> {
+        let rect = self.allocations.remove(&light_id)?;
+        self.free_block(rect.x, rect.y, rect.size);
+        Some(rect)
+    }
+
+    /// 光源当前的区域
+    pub fn get(&self, light_id: u32) -> Option<ShadowAtlasRect> {
+        self.allocations.get(&light_id).copied()
+    }
+
+    /// 已分配的区域数量
+    pub fn allocation_count(&self) -> usize {
+        self.allocations.len()
+    }
+
+    /// 已使用的面积比例
+    pub fn usage(&self) -> f32 {
+        let used: u64 = self.allocations.values().map(|rect| rect.size as u64 * rect.size as u64).sum();
+        used as f32 / (self.atlas_size as u64 * self.atlas_size as u64) as f32
+    }
+
+    /// 释放所有区域
+    pub fn clear(&mut self) {
+        self.allocations.clear();
+        self.free_blocks.clear();
+        self.free_blocks.insert(self.atlas_size, vec![(0, 0)]);
+    }
+
+    /// 归还块，四个伙伴块都空闲时合并为上一级
+    fn free_block(&mut self, x: u32, y: u32, size: u32) {
+        let (mut x, mut y, mut size) = (x, y, size);
+        while size < self.atlas_size {
+            let parent_size = size * 2;
+            let (parent_x, parent_y) = (x - x % parent_size, y - y % parent_size);
+            let buddies = [
+                (parent_x, parent_y),
+                (parent_x + size, parent_y),
+                (parent_x, parent_y + size),
+                (parent_x + size, parent_y + size),
+            ];
+
+            let blocks = self.free_blocks.entry(size).or_default();
+            let all_free = buddies
+                .iter()
+                .filter(|&&buddy| buddy != (x, y))
+                .all(|buddy| blocks.contains(buddy));
+            if !all_free {
+                break;
+            }
+
+            blocks.retain(|block| !buddies.contains(block));
+            x = parent_x;
+            y = parent_y;
+            size = parent_size;
+        }
+        self.free_blocks.entry(size).or_default().push((x, y));
+    }
+}
+
+/// 图集中单个光源的阴影数据
+#[derive(Debug, Clone, Copy)]
+pub struct ShadowAtlasEntry {
+    pub rect: ShadowAtlasRect,
+    pub light_view_matrix: Mat4,
+    pub light_projection_matrix: Mat4,
+}
+
+impl ShadowAtlasEntry {
+    /// 光源空间变换矩阵
+    pub fn light_space_matrix(&self) -> Mat4 {
+        self.light_projection_matrix * self.light_view_matrix
+    }
+}
+
+/// GPU阴影图集
+pub struct ShadowAtlas {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    allocator: ShadowAtlasAllocator,
+    entries: HashMap<u32, ShadowAtlasEntry>,
+}
+
+impl ShadowAtlas {
+    /// 深度格式
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+    /// 图集最大边长
+    pub const MAX_SIZE: u32 = 8192;
+    /// 单个光源的最小区域边长
+    pub const MIN_RECT_SIZE: u32 = 128;
+
+    pub fn new(device: &wgpu::Device, size: u32) -> Self {
+        let allocator = ShadowAtlasAllocator::new(size.min(Self::MAX_SIZE), Self::MIN_RECT_SIZE);
+        let size = allocator.atlas_size();
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Atlas Texture"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Atlas Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        Self {
+            texture,
+            view,
+            sampler,
+            allocator,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// 图集边长
+    pub fn size(&self) -> u32 {
+        self.allocator.atlas_size()
+    }
+
+    /// 为光源分配区域，空间不足时逐级减半尝试，仍失败返回None
+    pub fn allocate(&mut self, light_id: u32, requested_size: u32) -> Option<ShadowAtlasRect> {
+        let mut size = requested_size.max(self.allocator.min_size());
+        loop {
+            if let Some(rect) = self.allocator.allocate(light_id, size) {
+                return Some(rect);
+            }
+            if size <= self.allocator.min_size() {
+                return None;
+            }
+            size /= 2;
+        }
+    }
+
+    /// 释放光源的区域
+    pub fn release(&mut self, light_id: u32) {
+        self.allocator.release(light_id);
+        self.entries.remove(&light_id);
+    }
+
+    /// 记录光源本帧的矩阵
+    pub fn set_entry(&mut self, light_id: u32, entry: ShadowAtlasEntry) {
+        self.entries.insert(light_id, entry);
+    }
+
+    /// 光源的阴影数据
+    pub fn entry(&self, light_id: u32) -> Option<&ShadowAtlasEntry> {
+        self.entries.get(&light_id)
+    }
+
+    /// 所有光源的阴影数据
+    pub fn entries(&self) -> impl Iterator<Item = (&u32, &ShadowAtlasEntry)> {
+        self.entries.iter()
+    }
+
+    pub fn allocator(&self) -> &ShadowAtlasAllocator {
+        &self.allocator
+    }
+
+    /// 释放所有区域
+    pub fn clear(&mut self) {
+        self.allocator.clear();
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocations_fill_atlas_without_overlap() {
+        let mut allocator = ShadowAtlasAllocator::new(1024, 64);
+        let rects: Vec<_> = (0..16).map(|id| allocator.allocate(id, 256).unwrap()).collect();
+
+        for (i, a) in rects.iter().enumerate() {
+            assert!(a.x + a.size <= 1024 && a.y + a.size <= 1024);
+            for b in &rects[i + 1..] {
+                assert!(!a.overlaps(b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+        assert_eq!(allocator.usage(), 1.0);
+        assert!(allocator.allocate(16, 64).is_none());
+    }
+
+    #[test]
+    fn released_blocks_merge_back() {
+        let mut allocator = ShadowAtlasAllocator::new(1024, 64);
+        for id in 0..16 {
+            allocator.allocate(id, 256).unwrap();
+        }
+        for id in 0..16 {
+            allocator.release(id).unwrap();
+        }
+
+        assert_eq!(allocator.allocation_count(), 0);
+        assert_eq!(allocator.allocate(99, 1024), Some(ShadowAtlasRect { x: 0, y: 0, size: 1024 }));
+    }
+
+    #[test]
+    fn sizes_are_rounded_and_reallocated_on_change() {
+        let mut allocator = ShadowAtlasAllocator::new(1000, 50);
+        assert_eq!(allocator.atlas_size(), 1024);
+        assert_eq!(allocator.min_size(), 64);
+
+        assert_eq!(allocator.allocate(1, 300).unwrap().size, 512);
+        assert_eq!(allocator.allocate(2, 10).unwrap().size, 64);
+        assert_eq!(allocator.allocate(3, 4096).map(|rect| rect.size), None);
+
+        // 尺寸不变时保持原区域
+        let rect = allocator.get(1).unwrap();
+        assert_eq!(allocator.allocate(1, 512), Some(rect));
+        assert_eq!(allocator.allocate(1, 128).unwrap().size, 128);
+        assert_eq!(allocator.allocation_count(), 2);
+    }
+
+    #[test]
+    fn uv_scale_offset_maps_into_rect() {
+        let rect = ShadowAtlasRect { x: 512, y: 256, size: 256 };
+        assert_eq!(rect.uv_scale_offset(1024), Vec4::new(0.25, 0.25, 0.5, 0.25));
+    }
+}
@@ -1,10 +1,9 @@
 //! 阴影渲染系统
 
-use crate::math::{Vec2, Vec3, Vec4, Mat4, Quat};
-use crate::render::{Camera, Light, LightType, Mesh, Material};
+use crate::math::{Coordinate, Vec2, Vec3, Vec4, Mat4, Quat, AABB};
+use crate::render::{Camera, Light, LightType, Mesh, Material, ShadowAtlas, ShadowAtlasEntry, ShadowAtlasRect};
 use crate::ecs::Transform;
 use wgpu::*;
-use std::collections::HashMap;
 
 /// 阴影映射类型
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -44,9 +43,14 @@ pub struct ShadowConfig {
     pub quality: ShadowQuality,
     pub bias: f32,              // 阴影偏移，防止阴影粉刺
     pub normal_bias: f32,       // 法线偏移
+    pub min_distance: f32,      // 阴影起始距离，小于相机近平面时取近平面
     pub max_distance: f32,      // 最大阴影距离
     pub cascade_count: u32,     // 级联数量（用于CSM）
     pub cascade_splits: Vec<f32>, // 级联分割距离
+    /// 级联正交范围自动贴合视锥切片与场景包围盒的交集
+    pub auto_fit_cascades: bool,
+    /// 稳定级联：固定级联尺寸并把原点对齐到阴影贴图纹素，避免相机移动时阴影边缘闪烁
+    pub stabilize_cascades: bool,
     pub soft_shadow_radius: f32, // 软阴影半径
     pub pcf_samples: u32,       // PCF采样数量
 }
@@ -59,9 +63,12 @@ impl Default for ShadowConfig {
             quality: ShadowQuality::Medium,
             bias: 0.005,
             normal_bias: 0.02,
+            min_distance: 0.0,
             max_distance: 100.0,
             cascade_count: 4,
             cascade_splits: vec![0.1, 0.3, 0.6, 1.0],
+            auto_fit_cascades: true,
+            stabilize_cascades: true,
             soft_shadow_radius: 2.0,
             pcf_samples: 16,
         }
@@ -147,74 +154,81 @@ impl ShadowMap {
 
     /// 更新光源矩阵
     pub fn update_light_matrices(&mut self, light: &Light, transform: &Transform, scene_bounds: &crate::math::bounds::AABB) {
+        let (view, projection) = Self::light_matrices(light, transform, scene_bounds);
+        self.light_view_matrix = view;
+        self.light_projection_matrix = projection;
+    }
+
+    /// 计算光源的(视图矩阵, 投影矩阵)
+    pub fn light_matrices(light: &Light, transform: &Transform, scene_bounds: &crate::math::bounds::AABB) -> (Mat4, Mat4) {
         match light.light_type {
-            LightType::Directional => {
-                self.update_directional_light_matrices(light, transform, scene_bounds);
-            }
-            LightType::Point => {
-                self.update_point_light_matrices(light, transform);
-            }
-            LightType::Spot => {
-                self.update_spot_light_matrices(light, transform);
-            }
+            LightType::Directional => Self::directional_light_matrices(transform, scene_bounds),
+            LightType::Point => Self::point_light_matrices(light, transform),
+            LightType::Spot => Self::spot_light_matrices(light, transform),
         }
     }
 
-    /// 更新方向光矩阵
-    fn update_directional_light_matrices(&mut self, light: &Light, transform: &Transform, scene_bounds: &crate::math::bounds::AABB) {
+    /// 方向光矩阵
+    fn directional_light_matrices(transform: &Transform, scene_bounds: &crate::math::bounds::AABB) -> (Mat4, Mat4) {
         let light_direction = transform.forward().normalize();
         let light_position = scene_bounds.center() - light_direction * scene_bounds.size().length();
 
         // 构建光源视图矩阵
-        self.light_view_matrix = Mat4::look_at_rh(
+        let view = Coordinate::look_at(
             light_position,
             light_position + light_direction,
-            Vec3::Y,
+            Coordinate::UP,
         );
 
         // 计算正交投影矩阵
         let size = scene_bounds.size().length() * 0.5;
-        self.light_projection_matrix = Mat4::orthographic_rh(
+        let projection = Coordinate::orthographic(
             -size, size,
             -size, size,
             -size * 2.0, size * 2.0,
         );
+
+        (view, projection)
     }
 
-    /// 更新点光源矩阵
-    fn update_point_light_matrices(&mut self, light: &Light, transform: &Transform) {
+    /// 点光源矩阵
+    fn point_light_matrices(light: &Light, transform: &Transform) -> (Mat4, Mat4) {
         // 点光源需要6个面的阴影贴图（立方体贴图）
         // 这里简化为单一方向
-        self.light_view_matrix = Mat4::look_at_rh(
+        let view = Coordinate::look_at(
             transform.position,
             transform.position + Vec3::new(0.0, 0.0, 1.0),
-            Vec3::Y,
+            Coordinate::UP,
         );
 
-        self.light_projection_matrix = Mat4::perspective_rh(
+        let projection = Coordinate::perspective(
             90.0_f32.to_radians(),
             1.0,
             0.1,
             light.range,
         );
+
+        (view, projection)
     }
 
-    /// 更新聚光灯矩阵
-    fn update_spot_light_matrices(&mut self, light: &Light, transform: &Transform) {
+    /// 聚光灯矩阵
+    fn spot_light_matrices(light: &Light, transform: &Transform) -> (Mat4, Mat4) {
         let light_direction = transform.forward().normalize();
-        
-        self.light_view_matrix = Mat4::look_at_rh(
+
+        let view = Coordinate::look_at(
             transform.position,
             transform.position + light_direction,
-            Vec3::Y,
+            Coordinate::UP,
         );
 
-        self.light_projection_matrix = Mat4::perspective_rh(
+        let projection = Coordinate::perspective(
             light.spot_angle * 2.0,
             1.0,
             0.1,
             light.range,
         );
+
+        (view, projection)
     }
 
     /// 获取光源空间变换矩阵
@@ -249,45 +263,109 @@ impl CascadedShadowMap {
     pub fn update_cascades(
         &mut self,
         camera: &Camera,
-        light: &Light,
+        _light: &Light,
         light_transform: &Transform,
         config: &ShadowConfig,
-        scene_bounds: &crate::math::bounds::AABB,
+        scene_bounds: &AABB,
     ) {
-        let camera_near = camera.near_plane;
-        let camera_far = config.max_distance.min(camera.far_plane);
+        let shadow_near = camera.near_plane.max(config.min_distance);
+        let shadow_far = config.max_distance.min(camera.far_plane).max(shadow_near);
         
         // 计算级联分割距离
         for (i, split) in config.cascade_splits.iter().enumerate() {
             if i < self.cascade_distances.len() {
-                self.cascade_distances[i] = camera_near + (camera_far - camera_near) * split;
+                self.cascade_distances[i] = shadow_near + (shadow_far - shadow_near) * split;
             }
         }
 
-        // 预先计算所有级联的数据，避免借用冲突
-        let mut cascade_data = Vec::new();
+        let camera_corners = Self::calculate_frustum_corners_static(camera);
+        // 光源视图只含旋转，光源空间坐标不随相机平移漂移，纹素对齐才有意义
+        let light_direction = light_transform.forward().normalize();
+        let light_view = Coordinate::look_to(Vec3::ZERO, light_direction, Coordinate::UP);
+        let scene_bounds = Self::valid_bounds(scene_bounds);
+
         for i in 0..config.cascade_splits.len().min(self.cascades.len()) {
-            let near = if i == 0 { camera_near } else { self.cascade_distances[i - 1] };
+            let near = if i == 0 { shadow_near } else { self.cascade_distances[i - 1] };
             let far = self.cascade_distances[i];
+            let corners = Self::slice_frustum_corners(&camera_corners, camera.near_plane, camera.far_plane, near, far);
+
+            let (view, projection) = if config.auto_fit_cascades {
+                let projection = Self::fit_cascade_projection(
+                    &light_view,
+                    &corners,
+                    scene_bounds,
+                    self.cascades[i].resolution,
+                    config.stabilize_cascades,
+                );
+                (light_view, projection)
+            } else {
+                ShadowMap::directional_light_matrices(light_transform, &Self::calculate_frustum_bounds_static(&corners))
+            };
 
-            // 计算该级联的视锥体
-            let frustum_corners = Self::calculate_frustum_corners_static(camera, near, far);
-            
-            // 计算包围盒
-            let frustum_bounds = Self::calculate_frustum_bounds_static(&frustum_corners);
-            
-            cascade_data.push((i, frustum_bounds));
+            self.cascades[i].light_view_matrix = view;
+            self.cascades[i].light_projection_matrix = projection;
+            self.cascade_matrices[i] = self.cascades[i].get_light_space_matrix();
         }
+    }
 
-        // 更新级联矩阵
-        for (i, frustum_bounds) in cascade_data {
-            self.cascades[i].update_directional_light_matrices(light, light_transform, &frustum_bounds);
-            self.cascade_matrices[i] = self.cascades[i].get_light_space_matrix();
+    /// 计算级联在光源视图空间中的正交投影
+    ///
+    /// 贴合模式取切片角点在光源空间的包围盒并与场景包围盒求交；稳定模式改用切片外接球直径作为边长
+    /// (相机旋转和平移时不变)，并把左下角对齐到纹素网格。深度范围扩展到整个场景，保证切片外的遮挡物也能投影。
+    pub fn fit_cascade_projection(
+        light_view: &Mat4,
+        corners: &[Vec3; 8],
+        scene_bounds: Option<&AABB>,
+        resolution: u32,
+        stabilize: bool,
+    ) -> Mat4 {
+        let light_corners = corners.map(|corner| light_view.transform_point3(corner));
+        let slice_bounds = Self::calculate_frustum_bounds_static(&light_corners);
+        let scene_light = scene_bounds.map(|bounds| bounds.transform(light_view));
+
+        let (mut min, mut max) = (slice_bounds.min, slice_bounds.max);
+        if stabilize {
+            let center = light_corners.iter().copied().sum::<Vec3>() / 8.0;
+            let radius = light_corners.iter().map(|corner| corner.distance(center)).fold(0.0, f32::max);
+            // 半径向上取整，消除浮点误差带来的尺寸抖动
+            let mut size = (radius * 16.0).ceil() / 16.0 * 2.0;
+            if let Some(scene) = &scene_light {
+                let scene_size = scene.size();
+                size = size.min(scene_size.x.max(scene_size.y));
+            }
+            let size = size.max(f32::EPSILON);
+            let texel_size = size / resolution.max(1) as f32;
+            let origin = ShadowUtils::snap_to_texel(center.truncate() - Vec2::splat(size * 0.5), texel_size);
+            min = origin.extend(min.z);
+            max = (origin + Vec2::splat(size)).extend(max.z);
+        } else if let Some(scene) = &scene_light {
+            let fitted_min = min.max(scene.min);
+            let fitted_max = max.min(scene.max);
+            // 切片与场景不相交时保留切片范围
+            if fitted_min.x < fitted_max.x && fitted_min.y < fitted_max.y {
+                min = fitted_min.truncate().extend(min.z);
+                max = fitted_max.truncate().extend(max.z);
+            }
+        }
+
+        let (mut min_z, mut max_z) = (min.z, max.z);
+        if let Some(scene) = &scene_light {
+            min_z = min_z.min(scene.min.z);
+            max_z = max_z.max(scene.max.z);
         }
+
+        // 视图空间朝-Z观察，z越大离光源越近
+        Coordinate::orthographic(min.x, max.x, min.y, max.y, -max_z, -min_z)
+    }
+
+    /// 场景包围盒为空(默认值)时返回None
+    fn valid_bounds(bounds: &AABB) -> Option<&AABB> {
+        let size = bounds.size();
+        (size.x >= 0.0 && size.y >= 0.0 && size.z >= 0.0 && size.is_finite()).then_some(bounds)
     }
 
-    /// 计算视锥体角点（静态版本）
-    fn calculate_frustum_corners_static(camera: &Camera, near: f32, far: f32) -> [Vec3; 8] {
+    /// 相机近、远平面上的视锥体角点，前4个在近平面
+    fn calculate_frustum_corners_static(camera: &Camera) -> [Vec3; 8] {
         let inv_view_proj = (camera.projection_matrix() * camera.view_matrix()).inverse();
         
         // NDC空间中的视锥体角点
@@ -315,12 +393,22 @@ impl CascadedShadowMap {
         world_corners
     }
 
-    /// 计算视锥体包围盒
-    fn calculate_frustum_bounds(&self, corners: &[Vec3; 8]) -> crate::math::bounds::AABB {
-        Self::calculate_frustum_bounds_static(corners)
+    /// 沿视锥棱线截取[near, far]距离的切片角点(视图深度沿棱线线性变化)
+    fn slice_frustum_corners(camera_corners: &[Vec3; 8], camera_near: f32, camera_far: f32, near: f32, far: f32) -> [Vec3; 8] {
+        let depth = (camera_far - camera_near).max(f32::EPSILON);
+        let near_t = (near - camera_near) / depth;
+        let far_t = (far - camera_near) / depth;
+
+        let mut corners = [Vec3::ZERO; 8];
+        for i in 0..4 {
+            let (near_corner, far_corner) = (camera_corners[i], camera_corners[i + 4]);
+            corners[i] = near_corner.lerp(far_corner, near_t);
+            corners[i + 4] = near_corner.lerp(far_corner, far_t);
+        }
+        corners
     }
 
-    fn calculate_frustum_bounds_static(corners: &[Vec3; 8]) -> crate::math::bounds::AABB {
+    fn calculate_frustum_bounds_static(corners: &[Vec3; 8]) -> AABB {
         let mut min = corners[0];
         let mut max = corners[0];
 
@@ -329,18 +417,20 @@ impl CascadedShadowMap {
             max = max.max(corner);
         }
 
-        crate::math::bounds::AABB::new(min, max)
+        AABB::new(min, max)
     }
 }
 
 /// 阴影渲染器
 pub struct ShadowRenderer {
     pub config: ShadowConfig,
-    shadow_maps: HashMap<u32, ShadowMap>, // 光源ID -> 阴影贴图
+    shadow_atlas: ShadowAtlas, // 光源阴影贴图图集
     cascaded_shadow_map: Option<CascadedShadowMap>,
     shadow_pass_pipeline: Option<RenderPipeline>,
     bind_group_layout: BindGroupLayout,
     uniform_buffer: Buffer,
+    atlas_cleared: bool,        // 本帧是否已清除图集
+    frame_shadow_passes: u32,   // 本帧渲染到图集的光源数
 }
 
 impl ShadowRenderer {
@@ -352,7 +442,7 @@ impl ShadowRenderer {
                 // 光源空间变换矩阵
                 BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: ShaderStages::VERTEX,
+                    visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
                     ty: BindingType::Buffer {
                         ty: BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -360,7 +450,7 @@ impl ShadowRenderer {
                     },
                     count: None,
                 },
-                // 阴影贴图
+                // 阴影图集
                 BindGroupLayoutEntry {
                     binding: 1,
                     visibility: ShaderStages::FRAGMENT,
@@ -400,25 +490,56 @@ impl ShadowRenderer {
             None
         };
 
+        let shadow_atlas = ShadowAtlas::new(device, Self::atlas_size(&config));
+
         Self {
             config,
-            shadow_maps: HashMap::new(),
+            shadow_atlas,
             cascaded_shadow_map,
             shadow_pass_pipeline: None,
             bind_group_layout,
             uniform_buffer,
+            atlas_cleared: false,
+            frame_shadow_passes: 0,
         }
     }
 
-    /// 为光源创建阴影贴图
-    pub fn create_shadow_map_for_light(&mut self, device: &Device, light_id: u32) {
-        if !self.shadow_maps.contains_key(&light_id) {
-            let shadow_map = ShadowMap::new(device, self.config.quality.resolution());
-            self.shadow_maps.insert(light_id, shadow_map);
+    /// 图集边长：可容纳16个默认分辨率的光源
+    fn atlas_size(config: &ShadowConfig) -> u32 {
+        (config.quality.resolution() * 4).min(ShadowAtlas::MAX_SIZE)
+    }
+
+    /// 光源在图集中请求的区域边长，点光源和聚光灯使用一半分辨率
+    fn light_rect_size(&self, light: &Light) -> u32 {
+        let resolution = self.config.quality.resolution();
+        match light.light_type {
+            LightType::Directional => resolution,
+            LightType::Point | LightType::Spot => resolution / 2,
         }
     }
 
-    /// 渲染阴影贴图
+    /// 为光源在图集中分配阴影区域
+    pub fn create_shadow_map_for_light(&mut self, light_id: u32, light: &Light) -> Option<ShadowAtlasRect> {
+        let size = self.light_rect_size(light);
+        let rect = self.shadow_atlas.allocate(light_id, size);
+        if rect.is_none() {
+            log::warn!("阴影图集空间不足，光源 {} 不投射阴影", light_id);
+        }
+        rect
+    }
+
+    /// 释放光源的阴影区域
+    pub fn release_shadow_map(&mut self, light_id: u32) {
+        self.shadow_atlas.release(light_id);
+    }
+
+    /// 开始新的一帧，首个阴影通道会清除整个图集
+    pub fn begin_frame(&mut self) {
+        self.atlas_cleared = false;
+        self.frame_shadow_passes = 0;
+    }
+
+    /// 渲染阴影贴图到光源的图集区域
     pub fn render_shadow_map(
         &mut self,
         device: &Device,
@@ -434,22 +555,41 @@ impl ShadowRenderer {
             return;
         }
 
-        // 获取或创建阴影贴图
-        if !self.shadow_maps.contains_key(&light_id) {
-            self.create_shadow_map_for_light(device, light_id);
-        }
+        // 获取或分配图集区域
+        let rect = match self.shadow_atlas.allocator().get(light_id) {
+            Some(rect) => rect,
+            None => match self.create_shadow_map_for_light(light_id, light) {
+                Some(rect) => rect,
+                None => return,
+            },
+        };
+
+        let (light_view_matrix, light_projection_matrix) =
+            ShadowMap::light_matrices(light, light_transform, scene_bounds);
+        let entry = ShadowAtlasEntry {
+            rect,
+            light_view_matrix,
+            light_projection_matrix,
+        };
+        self.shadow_atlas.set_entry(light_id, entry);
 
-        let shadow_map = self.shadow_maps.get_mut(&light_id).unwrap();
-        shadow_map.update_light_matrices(light, light_transform, scene_bounds);
+        // 每帧只清除一次图集，之后的光源保留其他区域的内容
+        let load = if self.atlas_cleared {
+            LoadOp::Load
+        } else {
+            self.atlas_cleared = true;
+            LoadOp::Clear(1.0)
+        };
+        self.frame_shadow_passes += 1;
 
         // 创建渲染通道
         let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
-            label: Some("Shadow Map Pass"),
+            label: Some("Shadow Atlas Pass"),
             color_attachments: &[],
             depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
-                view: &shadow_map.depth_view,
+                view: &self.shadow_atlas.view,
                 depth_ops: Some(Operations {
-                    load: LoadOp::Clear(1.0),
+                    load,
                     store: StoreOp::Store,
                 }),
                 stencil_ops: None,
@@ -458,15 +598,27 @@ impl ShadowRenderer {
             timestamp_writes: None,
         });
 
+        // 限定绘制范围为光源的图集区域
+        render_pass.set_viewport(
+            rect.x as f32,
+            rect.y as f32,
+            rect.size as f32,
+            rect.size as f32,
+            0.0,
+            1.0,
+        );
+        render_pass.set_scissor_rect(rect.x, rect.y, rect.size, rect.size);
+
         // 更新uniform数据
         let uniforms = ShadowUniforms {
-            light_space_matrix: shadow_map.get_light_space_matrix().to_cols_array_2d(),
+            light_space_matrix: entry.light_space_matrix().to_cols_array_2d(),
             light_position: light_transform.position.extend(1.0).to_array(),
             shadow_bias: self.config.bias,
             normal_bias: self.config.normal_bias,
             cascade_count: self.config.cascade_count,
             _padding: 0,
             cascade_distances: [0.0; 4], // 暂时填充，实际使用时会更新
+            atlas_rect: rect.uv_scale_offset(self.shadow_atlas.size()).to_array(),
         };
 
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
@@ -528,6 +680,7 @@ impl ShadowRenderer {
                     csm.cascade_distances.get(2).copied().unwrap_or(0.0),
                     csm.cascade_distances.get(3).copied().unwrap_or(0.0),
                 ],
+                atlas_rect: ShadowUniforms::FULL_ATLAS_RECT, // 级联使用独立纹理
             };
 
             queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
@@ -539,9 +692,14 @@ impl ShadowRenderer {
         }
     }
 
-    /// 获取阴影贴图
-    pub fn get_shadow_map(&self, light_id: u32) -> Option<&ShadowMap> {
-        self.shadow_maps.get(&light_id)
+    /// 获取光源在图集中的阴影数据
+    pub fn get_shadow_entry(&self, light_id: u32) -> Option<&ShadowAtlasEntry> {
+        self.shadow_atlas.entry(light_id)
+    }
+
+    /// 获取阴影图集
+    pub fn shadow_atlas(&self) -> &ShadowAtlas {
+        &self.shadow_atlas
     }
 
     /// 获取级联阴影贴图
@@ -549,6 +707,22 @@ impl ShadowRenderer {
         self.cascaded_shadow_map.as_ref()
     }
 
+    /// 记录本帧的阴影渲染统计
+    ///
+    /// 所有光源共用图集，无论渲染多少光源都只切换一次渲染目标、绑定一次阴影纹理。
+    pub fn record_stats(&self, stats: &mut crate::performance::RenderStats) {
+        if self.frame_shadow_passes == 0 {
+            return;
+        }
+        stats.render_targets_switches += 1;
+        stats.texture_switches += 1;
+    }
+
+    /// 本帧渲染到图集的光源数
+    pub fn frame_shadow_passes(&self) -> u32 {
+        self.frame_shadow_passes
+    }
+
     /// 更新配置
     pub fn update_config(&mut self, device: &Device, new_config: ShadowConfig) {
         let resolution_changed = self.config.quality != new_config.quality;
@@ -559,8 +733,8 @@ impl ShadowRenderer {
 
         // 重新创建资源（如果需要）
         if resolution_changed {
-            // 重新创建所有阴影贴图
-            self.shadow_maps.clear();
+            // 重新创建图集，光源在下次渲染时重新分配区域
+            self.shadow_atlas = ShadowAtlas::new(device, Self::atlas_size(&self.config));
         }
 
         if cascade_changed && self.config.map_type == ShadowMapType::CSM {
@@ -574,7 +748,7 @@ impl ShadowRenderer {
 
     /// 清理资源
     pub fn cleanup(&mut self) {
-        self.shadow_maps.clear();
+        self.shadow_atlas.clear();
         self.cascaded_shadow_map = None;
     }
 }
@@ -591,12 +765,56 @@ pub struct ShadowUniforms {
     pub cascade_count: u32,
     pub _padding: u32,
     pub cascade_distances: [f32; 4],
+    pub atlas_rect: [f32; 4],               // 图集区域：xy缩放，zw偏移
+}
+
+impl ShadowUniforms {
+    /// 占满整张纹理的区域
+    pub const FULL_ATLAS_RECT: [f32; 4] = [1.0, 1.0, 0.0, 0.0];
 }
 
 // Manual implementation of bytemuck traits for ShadowUniforms
 unsafe impl bytemuck::Pod for ShadowUniforms {}
 unsafe impl bytemuck::Zeroable for ShadowUniforms {}
 
+/// 光源uniform数据，与csm.wgsl和shadow_receive.wgsl中的 `LightUniforms` 布局一致
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightUniforms {
+    pub position: [f32; 3],
+    pub _padding1: f32,
+    pub direction: [f32; 3],
+    pub _padding2: f32,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    /// 0=方向光，1=点光源，2=聚光灯
+    pub light_type: u32,
+    pub range: f32,
+    pub spot_angle: f32,       // 外锥半角
+    pub spot_inner_angle: f32, // 内锥半角
+}
+
+impl LightUniforms {
+    pub fn new(light: &Light, position: Vec3, direction: Vec3) -> Self {
+        Self {
+            position: position.to_array(),
+            _padding1: 0.0,
+            direction: direction.normalize_or_zero().to_array(),
+            _padding2: 0.0,
+            color: light.color.to_array(),
+            intensity: light.intensity,
+            light_type: match light.light_type {
+                LightType::Directional => 0,
+                LightType::Point => 1,
+                LightType::Spot => 2,
+            },
+            range: light.range,
+            spot_angle: light.spot_angle,
+            spot_inner_angle: light.spot_inner_angle,
+        }
+    }
+}
+
 /// 阴影计算工具
 pub struct ShadowUtils;
 
@@ -662,6 +880,14 @@ impl ShadowUtils {
             .collect()
     }
 
+    /// 把光源空间坐标向下对齐到阴影贴图纹素网格
+    pub fn snap_to_texel(value: Vec2, texel_size: f32) -> Vec2 {
+        if texel_size <= 0.0 {
+            return value;
+        }
+        (value / texel_size).floor() * texel_size
+    }
+
     /// 世界坐标转换到光源空间
     pub fn world_to_light_space(world_pos: Vec3, light_space_matrix: &Mat4) -> Vec4 {
         *light_space_matrix * world_pos.extend(1.0)
@@ -684,3 +910,166 @@ impl ShadowUtils {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按WGSL uniform布局规则计算结构体各字段的(名称, 偏移)和总大小，只支持f32/u32/vec3<f32>/vec4<f32>
+    fn wgsl_struct_layout(source: &str, name: &str) -> (Vec<(String, usize)>, usize) {
+        let start = source.find(&format!("struct {} {{", name)).unwrap();
+        let body = &source[start..];
+        let body = &body[body.find('{').unwrap() + 1..body.find('}').unwrap()];
+
+        let mut fields = Vec::new();
+        let (mut offset, mut struct_align) = (0usize, 4);
+        for line in body.lines().map(|line| line.split("//").next().unwrap().trim()).filter(|line| !line.is_empty()) {
+            let (field, ty) = line.trim_end_matches(',').split_once(':').unwrap();
+            let (size, align) = match ty.trim() {
+                "f32" | "u32" => (4, 4),
+                "vec3<f32>" => (12, 16),
+                "vec4<f32>" => (16, 16),
+                other => panic!("不支持的类型: {}", other),
+            };
+            offset = offset.next_multiple_of(align);
+            fields.push((field.trim().to_string(), offset));
+            offset += size;
+            struct_align = struct_align.max(align);
+        }
+        (fields, offset.next_multiple_of(struct_align))
+    }
+
+    #[test]
+    fn light_uniforms_match_shader_layout() {
+        use std::mem::{offset_of, size_of};
+
+        let rust_fields = vec![
+            ("position".to_string(), offset_of!(LightUniforms, position)),
+            ("_padding1".to_string(), offset_of!(LightUniforms, _padding1)),
+            ("direction".to_string(), offset_of!(LightUniforms, direction)),
+            ("_padding2".to_string(), offset_of!(LightUniforms, _padding2)),
+            ("color".to_string(), offset_of!(LightUniforms, color)),
+            ("intensity".to_string(), offset_of!(LightUniforms, intensity)),
+            ("light_type".to_string(), offset_of!(LightUniforms, light_type)),
+            ("range".to_string(), offset_of!(LightUniforms, range)),
+            ("spot_angle".to_string(), offset_of!(LightUniforms, spot_angle)),
+            ("spot_inner_angle".to_string(), offset_of!(LightUniforms, spot_inner_angle)),
+        ];
+        assert_eq!(size_of::<LightUniforms>(), 64);
+        assert_eq!(offset_of!(LightUniforms, spot_inner_angle), 60);
+
+        for source in [include_str!("shaders/csm.wgsl"), include_str!("shaders/shadow_receive.wgsl")] {
+            let (fields, size) = wgsl_struct_layout(source, "LightUniforms");
+            assert_eq!(fields, rust_fields);
+            assert_eq!(size, size_of::<LightUniforms>());
+        }
+    }
+
+    #[test]
+    fn light_uniforms_pack_spot_cone() {
+        let light = Light { light_type: LightType::Spot, ..Default::default() }
+            .with_spot_angles(30.0_f32.to_radians(), 40.0_f32.to_radians());
+        let uniforms = LightUniforms::new(&light, Vec3::Y, Vec3::NEG_Y * 2.0);
+
+        let bytes = bytemuck::bytes_of(&uniforms);
+        let read_f32 = |offset: usize| f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        assert_eq!(u32::from_le_bytes(bytes[48..52].try_into().unwrap()), 2);
+        assert_eq!(read_f32(56), light.spot_angle);
+        assert_eq!(read_f32(60), light.spot_inner_angle);
+        assert_eq!(uniforms.direction, [0.0, -1.0, 0.0]);
+    }
+
+    /// 正交投影的左、右、下、上边界
+    fn ortho_extents(projection: &Mat4) -> (f32, f32, f32, f32) {
+        let (sx, sy) = (projection.x_axis.x, projection.y_axis.y);
+        let (tx, ty) = (projection.w_axis.x, projection.w_axis.y);
+        (-(tx + 1.0) / sx, (1.0 - tx) / sx, -(ty + 1.0) / sy, (1.0 - ty) / sy)
+    }
+
+    /// 沿-Z延伸的视锥切片，整体平移offset
+    fn slice(offset: Vec3) -> [Vec3; 8] {
+        let quad = |half: f32, z: f32| {
+            [
+                Vec3::new(-half, -half, z),
+                Vec3::new(half, -half, z),
+                Vec3::new(half, half, z),
+                Vec3::new(-half, half, z),
+            ]
+        };
+        let (near, far) = (quad(1.0, -1.0), quad(8.0, -20.0));
+        let mut corners = [Vec3::ZERO; 8];
+        corners[..4].copy_from_slice(&near);
+        corners[4..].copy_from_slice(&far);
+        corners.map(|corner| corner + offset)
+    }
+
+    fn light_view() -> Mat4 {
+        Coordinate::look_to(Vec3::ZERO, Vec3::new(0.3, -1.0, 0.2).normalize(), Coordinate::UP)
+    }
+
+    #[test]
+    fn stabilized_cascade_origin_snaps_to_texels() {
+        let resolution = 1024;
+        let mut sizes = Vec::new();
+        let mut lefts = Vec::new();
+
+        for step in 0..8 {
+            let offset = Vec3::new(0.013, 0.0, -0.007) * step as f32;
+            let projection = CascadedShadowMap::fit_cascade_projection(&light_view(), &slice(offset), None, resolution, true);
+            let (left, right, bottom, top) = ortho_extents(&projection);
+
+            let size = right - left;
+            let texel_size = size / resolution as f32;
+            for origin in [left, bottom] {
+                let texels = origin / texel_size;
+                assert!((texels - texels.round()).abs() < 1e-2, "origin {} is not texel aligned", origin);
+            }
+            assert!((top - bottom - size).abs() < 1e-3);
+            sizes.push(size);
+            lefts.push(left);
+        }
+
+        // 相机平移时尺寸不变，原点只按整数个纹素移动
+        assert!(sizes.windows(2).all(|pair| (pair[0] - pair[1]).abs() < 1e-4));
+        let texel_size = sizes[0] / resolution as f32;
+        for pair in lefts.windows(2) {
+            let moved = (pair[1] - pair[0]) / texel_size;
+            assert!((moved - moved.round()).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn auto_fit_clamps_cascade_to_scene_bounds() {
+        let view = light_view();
+        // 位于切片中部的小场景
+        let scene = AABB::new(Vec3::new(-1.0, -1.0, -11.0), Vec3::new(1.0, 1.0, -9.0));
+        let projection = CascadedShadowMap::fit_cascade_projection(&view, &slice(Vec3::ZERO), Some(&scene), 1024, false);
+        let (left, right, bottom, top) = ortho_extents(&projection);
+
+        let scene_light = scene.transform(&view);
+        assert!((left - scene_light.min.x).abs() < 1e-4);
+        assert!((right - scene_light.max.x).abs() < 1e-4);
+        assert!((bottom - scene_light.min.y).abs() < 1e-4);
+        assert!((top - scene_light.max.y).abs() < 1e-4);
+
+        // 场景在切片之外时保留切片范围
+        let far_away = AABB::new(Vec3::splat(500.0), Vec3::splat(501.0));
+        let unfitted = CascadedShadowMap::fit_cascade_projection(&view, &slice(Vec3::ZERO), None, 1024, false);
+        let disjoint = CascadedShadowMap::fit_cascade_projection(&view, &slice(Vec3::ZERO), Some(&far_away), 1024, false);
+        let (unfitted, disjoint) = (ortho_extents(&unfitted), ortho_extents(&disjoint));
+        assert!((unfitted.0 - disjoint.0).abs() < 1e-4 && (unfitted.1 - disjoint.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn empty_scene_bounds_are_ignored() {
+        assert!(CascadedShadowMap::valid_bounds(&AABB::default()).is_none());
+        let bounds = AABB::new(Vec3::ZERO, Vec3::ONE);
+        assert!(CascadedShadowMap::valid_bounds(&bounds).is_some());
+    }
+
+    #[test]
+    fn snap_to_texel_rounds_down() {
+        assert_eq!(ShadowUtils::snap_to_texel(Vec2::new(1.26, -0.3), 0.25), Vec2::new(1.25, -0.5));
+        assert_eq!(ShadowUtils::snap_to_texel(Vec2::new(1.26, 2.0), 0.0), Vec2::new(1.26, 2.0));
+    }
+}
@@ -0,0 +1,289 @@
+//! 骨骼蒙皮
+//!
+//! 蒙皮网格的顶点带最多4个骨骼索引与权重(`MeshVertex::joint_indices`/`joint_weights`)，
+//! 骨骼矩阵调色板(`Skeleton::compute_skinning_matrices` 的结果)以uniform缓冲上传，
+//! 绑定到PBR着色器第1组的1号绑定(`SKINNED` 关键字)。
+//! `skin_vertex`/`skin_mesh` 是与着色器一致的CPU参考实现。
+
+use crate::render::{Mesh, MeshVertex, MAX_JOINT_INFLUENCES};
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+/// 调色板最多容纳的骨骼数，与着色器中的数组长度一致
+pub const MAX_SKIN_JOINTS: usize = 128;
+
+/// 蒙皮网格的GPU顶点格式
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SkinnedVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub tex_coords: [f32; 2],
+    pub color: [f32; 3],
+    pub joint_indices: [u16; MAX_JOINT_INFLUENCES],
+    pub joint_weights: [f32; MAX_JOINT_INFLUENCES],
+}
+
+impl SkinnedVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+        0 => Float32x3,
+        1 => Float32x3,
+        2 => Float32x2,
+        3 => Float32x3,
+        4 => Uint16x4,
+        5 => Float32x4,
+    ];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SkinnedVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+impl From<&MeshVertex> for SkinnedVertex {
+    fn from(vertex: &MeshVertex) -> Self {
+        Self {
+            position: vertex.position.to_array(),
+            normal: vertex.normal.to_array(),
+            tex_coords: vertex.tex_coords.to_array(),
+            color: vertex.color.to_array(),
+            joint_indices: vertex.joint_indices,
+            joint_weights: vertex.joint_weights,
+        }
+    }
+}
+
+/// 按顶点权重混合骨骼矩阵，越界的骨骼索引视为单位矩阵
+pub fn skin_matrix(vertex: &MeshVertex, palette: &[Mat4]) -> Mat4 {
+    let mut matrix = Mat4::ZERO;
+    for (&index, &weight) in vertex.joint_indices.iter().zip(&vertex.joint_weights) {
+        if weight > 0.0 {
+            let joint = palette.get(index as usize).copied().unwrap_or(Mat4::IDENTITY);
+            matrix += joint * weight;
+        }
+    }
+    matrix
+}
+
+/// CPU线性混合蒙皮，未蒙皮的顶点保持不变
+pub fn skin_vertex(vertex: &MeshVertex, palette: &[Mat4]) -> MeshVertex {
+    if !vertex.is_skinned() {
+        return *vertex;
+    }
+
+    let matrix = skin_matrix(vertex, palette);
+    let normal = matrix.transform_vector3(vertex.normal).normalize_or_zero();
+    MeshVertex {
+        position: matrix.transform_point3(vertex.position),
+        normal: if normal == Vec3::ZERO { vertex.normal } else { normal },
+        ..*vertex
+    }
+}
+
+/// 对整个网格做CPU蒙皮，返回变形后的网格
+pub fn skin_mesh(mesh: &Mesh, palette: &[Mat4]) -> Mesh {
+    let vertices = mesh.vertices.iter().map(|vertex| skin_vertex(vertex, palette)).collect();
+    Mesh::from_geometry(mesh.name.clone(), vertices, mesh.indices.clone())
+}
+
+/// GPU上的骨骼矩阵调色板
+///
+/// 与模型uniform同属第1组，蒙皮管线的第1组布局由模型条目与 `layout_entry` 组成。
+#[derive(Debug)]
+pub struct JointPalette {
+    buffer: wgpu::Buffer,
+    joint_count: usize,
+}
+
+impl JointPalette {
+    /// 在第1组中的绑定号
+    pub const BINDING: u32 = 1;
+    /// 调色板缓冲大小(字节)
+    pub const BUFFER_SIZE: wgpu::BufferAddress = (MAX_SKIN_JOINTS * std::mem::size_of::<Mat4>()) as wgpu::BufferAddress;
+
+    pub fn layout_entry() -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding: Self::BINDING,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: wgpu::BufferSize::new(Self::BUFFER_SIZE),
+            },
+            count: None,
+        }
+    }
+
+    /// 创建调色板，初始全部为单位矩阵(绑定姿势)
+    pub fn new(device: &wgpu::Device, label: &str) -> Self {
+        let identity = vec![Mat4::IDENTITY.to_cols_array(); MAX_SKIN_JOINTS];
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{}骨骼调色板", label)),
+            contents: bytemuck::cast_slice(&identity),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self { buffer, joint_count: 0 }
+    }
+
+    /// 写入本帧的骨骼矩阵，超出 `MAX_SKIN_JOINTS` 的部分被忽略
+    pub fn update(&mut self, queue: &wgpu::Queue, matrices: &[Mat4]) {
+        if matrices.len() > MAX_SKIN_JOINTS {
+            log::warn!("骨骼数 {} 超过调色板上限 {}，多余骨骼被忽略", matrices.len(), MAX_SKIN_JOINTS);
+        }
+        let count = matrices.len().min(MAX_SKIN_JOINTS);
+        if count > 0 {
+            let data: Vec<[f32; 16]> = matrices[..count].iter().map(Mat4::to_cols_array).collect();
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&data));
+        }
+        self.joint_count = count;
+    }
+
+    /// 第1组绑定组中的调色板条目
+    pub fn bind_group_entry(&self) -> wgpu::BindGroupEntry<'_> {
+        wgpu::BindGroupEntry {
+            binding: Self::BINDING,
+            resource: self.buffer.as_entire_binding(),
+        }
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// 最近一次写入的骨骼数
+    pub fn joint_count(&self) -> usize {
+        self.joint_count
+    }
+}
+
+/// 上传到GPU的蒙皮网格
+#[derive(Debug)]
+pub struct GpuSkinnedMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+}
+
+impl GpuSkinnedMesh {
+    pub fn upload(device: &wgpu::Device, mesh: &Mesh) -> Self {
+        let vertices: Vec<SkinnedVertex> = mesh.vertices.iter().map(SkinnedVertex::from).collect();
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{}蒙皮顶点缓冲", mesh.name)),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{}蒙皮索引缓冲", mesh.name)),
+            contents: bytemuck::cast_slice(&mesh.indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: mesh.indices.len() as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::skeleton::{SkeletalPose, Skeleton, Transform};
+    use glam::Quat;
+
+    /// 根骨骼在原点，子骨骼的绑定姿势位于(0, 1, 0)
+    fn two_bone_skeleton() -> Skeleton {
+        let mut skeleton = Skeleton::new();
+        let root = skeleton.add_bone("root", None);
+        let arm = skeleton.add_bone("arm", Some(root));
+        skeleton.set_bind_pose(arm, Transform { translation: Vec3::Y, ..Default::default() });
+        skeleton
+    }
+
+    fn skinned(position: Vec3, indices: [u16; MAX_JOINT_INFLUENCES], weights: [f32; MAX_JOINT_INFLUENCES]) -> MeshVertex {
+        let mut vertex = MeshVertex { position, normal: Vec3::X, ..Default::default() };
+        vertex.set_joints(indices, weights);
+        vertex
+    }
+
+    /// 子骨骼绕Z轴转90°的调色板
+    fn bent_palette(skeleton: &Skeleton) -> Vec<Mat4> {
+        let mut pose = SkeletalPose::new(skeleton.bone_count());
+        pose.set_bone_transform(
+            1,
+            Transform {
+                translation: Vec3::Y,
+                rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+                ..Default::default()
+            },
+        );
+        skeleton.compute_pose(&pose)
+    }
+
+    #[test]
+    fn moving_bone_deforms_weighted_vertices() {
+        let skeleton = two_bone_skeleton();
+        let palette = bent_palette(&skeleton);
+
+        let tip = skin_vertex(&skinned(Vec3::new(0.0, 2.0, 0.0), [1, 0, 0, 0], [1.0, 0.0, 0.0, 0.0]), &palette);
+        assert!(tip.position.abs_diff_eq(Vec3::new(-1.0, 1.0, 0.0), 1e-5), "{:?}", tip.position);
+        assert!(tip.normal.abs_diff_eq(Vec3::Y, 1e-5));
+
+        // 两根骨骼各一半权重时线性混合
+        let elbow = skin_vertex(&skinned(Vec3::new(0.0, 2.0, 0.0), [0, 1, 0, 0], [1.0, 1.0, 0.0, 0.0]), &palette);
+        assert!(elbow.position.abs_diff_eq(Vec3::new(-0.5, 1.5, 0.0), 1e-5), "{:?}", elbow.position);
+
+        // 只受根骨骼影响的顶点不动
+        let base = skin_vertex(&skinned(Vec3::new(0.5, 0.5, 0.0), [0, 0, 0, 0], [1.0, 0.0, 0.0, 0.0]), &palette);
+        assert!(base.position.abs_diff_eq(Vec3::new(0.5, 0.5, 0.0), 1e-5));
+    }
+
+    #[test]
+    fn bind_pose_palette_leaves_mesh_unchanged() {
+        let skeleton = two_bone_skeleton();
+        let mut pose = SkeletalPose::new(skeleton.bone_count());
+        pose.set_bone_transform(1, skeleton.bones[1].bind_pose);
+        let palette = skeleton.compute_pose(&pose);
+
+        let vertices = vec![
+            skinned(Vec3::new(0.0, 2.0, 0.0), [1, 0, 0, 0], [1.0, 0.0, 0.0, 0.0]),
+            skinned(Vec3::new(1.0, 1.0, 0.0), [0, 1, 0, 0], [0.3, 0.7, 0.0, 0.0]),
+            MeshVertex { position: Vec3::Z, ..Default::default() },
+        ];
+        let mesh = Mesh::from_geometry("arm", vertices, vec![0, 1, 2]);
+        let skinned_mesh = skin_mesh(&mesh, &palette);
+        assert_eq!(skinned_mesh.indices, mesh.indices);
+        for (before, after) in mesh.vertices.iter().zip(&skinned_mesh.vertices) {
+            assert!(before.position.abs_diff_eq(after.position, 1e-5));
+        }
+    }
+
+    #[test]
+    fn unskinned_vertices_and_missing_joints_fall_back() {
+        let palette = [Mat4::from_translation(Vec3::X)];
+        let plain = MeshVertex { position: Vec3::Y, ..Default::default() };
+        assert_eq!(skin_vertex(&plain, &palette).position, Vec3::Y);
+
+        // 越界的骨骼索引按单位矩阵处理
+        let vertex = skinned(Vec3::Y, [0, 9, 0, 0], [1.0, 1.0, 0.0, 0.0]);
+        assert!(skin_vertex(&vertex, &palette).position.abs_diff_eq(Vec3::new(0.5, 1.0, 0.0), 1e-6));
+    }
+
+    #[test]
+    fn skinned_vertex_layout_matches_struct() {
+        let layout = SkinnedVertex::desc();
+        assert_eq!(layout.array_stride, std::mem::size_of::<SkinnedVertex>() as wgpu::BufferAddress);
+        let offsets: Vec<_> = layout.attributes.iter().map(|attribute| attribute.offset).collect();
+        assert_eq!(offsets, [0, 12, 24, 32, 44, 52]);
+
+        let vertex = skinned(Vec3::ONE, [3, 1, 0, 0], [3.0, 1.0, 0.0, 0.0]);
+        let gpu = SkinnedVertex::from(&vertex);
+        assert_eq!(gpu.joint_indices, [3, 1, 0, 0]);
+        assert_eq!(gpu.joint_weights, [0.75, 0.25, 0.0, 0.0]);
+    }
+}
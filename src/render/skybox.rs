@@ -0,0 +1,452 @@
+//! 天空盒系统
+
+use crate::render::{CubemapSource, EnvironmentMap, Texture};
+use glam::{Mat4, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 天空盒类型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Skybox {
+    /// 纯色背景
+    SolidColor(Vec4),
+    /// 垂直渐变(顶部 -> 底部)
+    Gradient {
+        top: Vec4,
+        bottom: Vec4,
+    },
+    /// 立方体贴图(等距柱状投影全景图路径，加载时转换为立方体)
+    Cubemap(String),
+}
+
+impl Default for Skybox {
+    fn default() -> Self {
+        Skybox::SolidColor(Vec4::new(0.1, 0.2, 0.3, 1.0))
+    }
+}
+
+impl Skybox {
+    /// 创建纯色天空盒
+    pub fn solid(color: Vec4) -> Self {
+        Skybox::SolidColor(color)
+    }
+
+    /// 创建渐变天空盒
+    pub fn gradient(top: Vec4, bottom: Vec4) -> Self {
+        Skybox::Gradient { top, bottom }
+    }
+
+    /// 创建立方体贴图天空盒
+    pub fn cubemap(texture_path: impl Into<String>) -> Self {
+        Skybox::Cubemap(texture_path.into())
+    }
+
+    /// 获取清屏颜色
+    pub fn clear_color(&self) -> Vec4 {
+        match self {
+            Skybox::SolidColor(color) => *color,
+            Skybox::Gradient { top, bottom } => (*top + *bottom) * 0.5,
+            Skybox::Cubemap(_) => Vec4::new(0.0, 0.0, 0.0, 1.0),
+        }
+    }
+
+    /// 是否需要额外的天空盒绘制
+    pub fn needs_draw(&self) -> bool {
+        !matches!(self, Skybox::SolidColor(_))
+    }
+
+    /// 立方体贴图天空盒的加载来源
+    pub fn cubemap_source(&self) -> Option<CubemapSource> {
+        match self {
+            Skybox::Cubemap(path) => Some(CubemapSource::Equirect {
+                path: PathBuf::from(path),
+                face_size: SKYBOX_FACE_SIZE,
+            }),
+            _ => None,
+        }
+    }
+
+    /// 按视线方向采样天空颜色(与skybox.wgsl保持一致的CPU参考实现)
+    pub fn sample(&self, direction: Vec3) -> Vec4 {
+        match self {
+            Skybox::SolidColor(color) => *color,
+            Skybox::Gradient { top, bottom } => {
+                let t = Self::gradient_factor(direction);
+                bottom.lerp(*top, t)
+            }
+            // 立方体贴图需要GPU纹理，CPU端返回清屏颜色
+            Skybox::Cubemap(_) => self.clear_color(),
+        }
+    }
+
+    /// 计算渐变插值因子：向下为0，向上为1
+    pub fn gradient_factor(direction: Vec3) -> f32 {
+        let dir = direction.normalize_or_zero();
+        (dir.y * 0.5 + 0.5).clamp(0.0, 1.0)
+    }
+}
+
+/// 全景图转换为立方体贴图时每个面的边长
+pub const SKYBOX_FACE_SIZE: u32 = 512;
+
+/// 天空盒uniform数据
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SkyboxUniforms {
+    pub inv_view_projection: [[f32; 4]; 4],
+    pub top_color: [f32; 4],
+    pub bottom_color: [f32; 4],
+    /// x为1时沿视线方向采样立方体贴图
+    pub params: [f32; 4],
+}
+
+impl SkyboxUniforms {
+    pub fn new(skybox: &Skybox, view_projection: Mat4) -> Self {
+        let (top, bottom) = match skybox {
+            Skybox::Gradient { top, bottom } => (*top, *bottom),
+            other => {
+                let color = other.clear_color();
+                (color, color)
+            }
+        };
+
+        let use_cubemap = matches!(skybox, Skybox::Cubemap(_));
+        Self {
+            inv_view_projection: view_projection.inverse().to_cols_array_2d(),
+            top_color: top.to_array(),
+            bottom_color: bottom.to_array(),
+            params: [if use_cubemap { 1.0 } else { 0.0 }, 0.0, 0.0, 0.0],
+        }
+    }
+}
+
+/// 天空盒渲染器
+pub struct SkyboxRenderer {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+    /// 当前绑定的立方体贴图路径，加载失败时也记录以免每帧重试
+    cubemap_path: Option<String>,
+}
+
+impl SkyboxRenderer {
+    /// 创建天空盒渲染器，depth_format为目标通道的深度格式(天空盒不读写深度)
+    pub fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        depth_format: Option<wgpu::TextureFormat>,
+        sample_count: u32,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("天空盒着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/skybox.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("天空盒绑定组布局"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("天空盒采样器"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("天空盒Uniform缓冲"),
+            size: std::mem::size_of::<SkyboxUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // 未使用立方体贴图时绑定1x1的占位立方体
+        let placeholder = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("天空盒占位立方体贴图"),
+            size: wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 6 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let bind_group = Self::create_bind_group(device, &bind_group_layout, &uniform_buffer, &placeholder, &sampler);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("天空盒管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("天空盒管线"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: color_format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: depth_format.map(|format| wgpu::DepthStencilState {
+                format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            uniform_buffer,
+            bind_group_layout,
+            bind_group,
+            sampler,
+            cubemap_path: None,
+        }
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        uniform_buffer: &wgpu::Buffer,
+        cubemap: &wgpu::Texture,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        let cubemap_view = cubemap.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        });
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("天空盒绑定组"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&cubemap_view) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(sampler) },
+            ],
+        })
+    }
+
+    /// 更新天空盒参数，立方体贴图路径变化时加载并上传新的贴图
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, skybox: &Skybox, view_projection: Mat4) {
+        if let (Skybox::Cubemap(path), Some(source)) = (skybox, skybox.cubemap_source()) {
+            if self.cubemap_path.as_deref() != Some(path.as_str()) {
+                self.cubemap_path = Some(path.clone());
+                match Texture::load_cubemap(&source) {
+                    Ok(cubemap) => {
+                        let texture = EnvironmentMap::upload_cube(device, queue, "天空盒立方体贴图", std::slice::from_ref(&cubemap));
+                        self.bind_group = Self::create_bind_group(device, &self.bind_group_layout, &self.uniform_buffer, &texture, &self.sampler);
+                    }
+                    Err(e) => log::warn!("加载天空盒立方体贴图失败 {}: {}", path, e),
+                }
+            }
+        }
+
+        let uniforms = SkyboxUniforms::new(skybox, view_projection);
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    /// 绘制天空盒(全屏三角形)，应在不透明几何体之前调用
+    pub fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+    const SIZE: u32 = 8;
+
+    /// 沿-Z看向前方、90度视角的相机
+    fn forward_view_projection() -> Mat4 {
+        let view = Mat4::look_at_rh(Vec3::ZERO, Vec3::NEG_Z, Vec3::Y);
+        Mat4::perspective_rh(90f32.to_radians(), 1.0, 0.1, 100.0) * view
+    }
+
+    /// 把天空盒画到离屏纹理并回读每个像素
+    fn render_offscreen(device: &wgpu::Device, queue: &wgpu::Queue, skybox: &Skybox) -> Vec<[u8; 4]> {
+        let mut renderer = SkyboxRenderer::new(device, FORMAT, None, 1);
+        renderer.update(device, queue, skybox, forward_view_projection());
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("天空盒测试目标"),
+            size: wgpu::Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("天空盒测试通道"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            renderer.draw(&mut render_pass);
+        }
+
+        let bytes_per_row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("天空盒测试回读缓冲"),
+            size: (bytes_per_row * SIZE) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(bytes_per_row), rows_per_image: Some(SIZE) },
+            },
+            target.size(),
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let offset = (y * bytes_per_row + x * 4) as usize;
+                [data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn gradient_skybox_renders_top_to_bottom() {
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过天空盒渲染测试: 没有可用的适配器");
+            return;
+        };
+
+        let skybox = Skybox::gradient(Vec4::new(1.0, 0.0, 0.0, 1.0), Vec4::new(0.0, 0.0, 1.0, 1.0));
+        let pixels = render_offscreen(&device, &queue, &skybox);
+        let pixel = |x: u32, y: u32| pixels[(y * SIZE + x) as usize];
+
+        // 上方偏向顶部颜色，下方偏向底部颜色，中间一列从上到下单调变化
+        let (top, bottom) = (pixel(SIZE / 2, 0), pixel(SIZE / 2, SIZE - 1));
+        assert!(top[0] > top[2] && bottom[2] > bottom[0], "top = {:?}, bottom = {:?}", top, bottom);
+        for y in 1..SIZE {
+            assert!(pixel(SIZE / 2, y)[0] <= pixel(SIZE / 2, y - 1)[0]);
+        }
+        // 同一行左右对称
+        assert!(pixel(0, 0)[0].abs_diff(pixel(SIZE - 1, 0)[0]) <= 1);
+    }
+
+    #[test]
+    fn cubemap_skybox_samples_loaded_panorama() {
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过天空盒渲染测试: 没有可用的适配器");
+            return;
+        };
+
+        // 上半为红色、下半为蓝色的全景图
+        let path = std::env::temp_dir().join(format!("sanji_skybox_{}.png", std::process::id()));
+        let panorama = image::RgbaImage::from_fn(16, 8, |_, y| if y < 4 { image::Rgba([255, 0, 0, 255]) } else { image::Rgba([0, 0, 255, 255]) });
+        panorama.save(&path).unwrap();
+
+        let pixels = render_offscreen(&device, &queue, &Skybox::cubemap(path.to_string_lossy()));
+        std::fs::remove_file(&path).ok();
+
+        let (top, bottom) = (pixels[(SIZE / 2) as usize], pixels[((SIZE - 1) * SIZE + SIZE / 2) as usize]);
+        assert!(top[0] > 200 && top[2] < 50, "top = {:?}", top);
+        assert!(bottom[2] > 200 && bottom[0] < 50, "bottom = {:?}", bottom);
+    }
+
+    #[test]
+    fn gradient_factor_spans_down_to_up() {
+        assert_eq!(Skybox::gradient_factor(Vec3::NEG_Y), 0.0);
+        assert_eq!(Skybox::gradient_factor(Vec3::Y), 1.0);
+        assert_eq!(Skybox::gradient_factor(Vec3::X), 0.5);
+    }
+
+    #[test]
+    fn only_cubemap_samples_texture() {
+        let cubemap = Skybox::cubemap("sky/panorama.hdr");
+        assert_eq!(SkyboxUniforms::new(&cubemap, Mat4::IDENTITY).params[0], 1.0);
+        let gradient = Skybox::gradient(Vec4::ONE, Vec4::ZERO);
+        assert_eq!(SkyboxUniforms::new(&gradient, Mat4::IDENTITY).params[0], 0.0);
+    }
+
+    #[test]
+    fn cubemap_source_converts_panorama() {
+        let source = Skybox::cubemap("sky/panorama.hdr").cubemap_source();
+        assert_eq!(
+            source,
+            Some(CubemapSource::Equirect { path: PathBuf::from("sky/panorama.hdr"), face_size: SKYBOX_FACE_SIZE })
+        );
+        assert_eq!(Skybox::default().cubemap_source(), None);
+    }
+}
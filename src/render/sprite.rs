@@ -0,0 +1,469 @@
+//! 2D精灵渲染
+//!
+//! `SpriteBatcher` 收集带有 `SpriteRenderer` 的实体，按(排序顺序, 纹理)排序后把相邻的同纹理精灵合并为一个批次，
+//! 每个批次对应一次实例化绘制调用。`SpriteRenderer2D` 在正交相机下用实例缓冲绘制这些批次。
+
+use crate::ecs::{SpriteRenderer, Transform};
+use crate::math::{Coordinate, Mat4, Vec2};
+use specs::{Join, World, WorldExt};
+use std::collections::HashMap;
+use wgpu::util::DeviceExt;
+
+/// 精灵实例数据(对应sprite.wgsl中的InstanceInput)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SpriteInstance {
+    /// 轴心点换算后四边形左下角的世界坐标，z用于深度
+    pub origin: [f32; 3],
+    pub _padding: f32,
+    /// 四边形的X边(已包含尺寸、缩放与旋转)
+    pub axis_x: [f32; 2],
+    /// 四边形的Y边
+    pub axis_y: [f32; 2],
+    /// 左上角UV(已应用翻转)
+    pub uv_min: [f32; 2],
+    /// 右下角UV(已应用翻转)
+    pub uv_max: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl SpriteInstance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+        0 => Float32x4,
+        1 => Float32x2,
+        2 => Float32x2,
+        3 => Float32x2,
+        4 => Float32x2,
+        5 => Float32x4,
+    ];
+
+    pub fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+
+    /// 由精灵组件与变换计算实例
+    pub fn from_sprite(sprite: &SpriteRenderer, transform: &Transform) -> Self {
+        let scale = transform.scale.truncate() * sprite.size;
+        let right = (transform.rotation * Coordinate::RIGHT).truncate().normalize_or_zero();
+        let up = (transform.rotation * Coordinate::UP).truncate().normalize_or_zero();
+        let axis_x = right * scale.x;
+        let axis_y = up * scale.y;
+        let origin = transform.position.truncate() - axis_x * sprite.pivot.x - axis_y * sprite.pivot.y;
+        let (uv_min, uv_max) = sprite.flipped_uv_rect();
+
+        Self {
+            origin: origin.extend(transform.position.z).to_array(),
+            _padding: 0.0,
+            axis_x: axis_x.to_array(),
+            axis_y: axis_y.to_array(),
+            uv_min: uv_min.to_array(),
+            uv_max: uv_max.to_array(),
+            color: sprite.color.to_array(),
+        }
+    }
+}
+
+/// 共享同一纹理、连续绘制的一组精灵
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteBatch {
+    pub texture_name: String,
+    /// 在实例数组中的起始位置
+    pub first_instance: u32,
+    pub instance_count: u32,
+}
+
+/// 一帧要绘制的精灵
+#[derive(Debug, Clone, Default)]
+pub struct SpriteFrame {
+    pub instances: Vec<SpriteInstance>,
+    pub batches: Vec<SpriteBatch>,
+}
+
+impl SpriteFrame {
+    /// 绘制调用次数(每个批次一次)
+    pub fn draw_calls(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+/// 精灵合批器
+pub struct SpriteBatcher;
+
+impl SpriteBatcher {
+    /// 收集世界中可见的精灵并合批
+    pub fn collect(world: &World) -> SpriteFrame {
+        let entities = world.entities();
+        let transforms = world.read_storage::<Transform>();
+        let sprites = world.read_storage::<SpriteRenderer>();
+
+        let items: Vec<_> = (&entities, &sprites, &transforms)
+            .join()
+            .filter(|(_, sprite, _)| sprite.visible)
+            .map(|(entity, sprite, transform)| (entity.id(), sprite, transform))
+            .collect();
+        Self::batch(items)
+    }
+
+    /// 按(排序顺序, 纹理, id)排序后合并相邻的同纹理精灵
+    ///
+    /// id只用于保证排序稳定，同一排序顺序内的绘制先后不做保证。
+    pub fn batch<'a>(mut items: Vec<(u32, &'a SpriteRenderer, &'a Transform)>) -> SpriteFrame {
+        items.sort_by(|(a_id, a, _), (b_id, b, _)| {
+            a.sorting_order
+                .cmp(&b.sorting_order)
+                .then_with(|| a.texture_name.cmp(&b.texture_name))
+                .then_with(|| a_id.cmp(b_id))
+        });
+
+        let mut frame = SpriteFrame::default();
+        for (_, sprite, transform) in items {
+            let index = frame.instances.len() as u32;
+            frame.instances.push(SpriteInstance::from_sprite(sprite, transform));
+
+            match frame.batches.last_mut() {
+                Some(batch) if batch.texture_name == sprite.texture_name => batch.instance_count += 1,
+                _ => frame.batches.push(SpriteBatch {
+                    texture_name: sprite.texture_name.clone(),
+                    first_instance: index,
+                    instance_count: 1,
+                }),
+            }
+        }
+        frame
+    }
+}
+
+/// 2D正交相机参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpriteCamera {
+    /// 视口中心的世界坐标
+    pub position: Vec2,
+    /// 视口高度(世界单位)
+    pub height: f32,
+    pub aspect_ratio: f32,
+}
+
+impl Default for SpriteCamera {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            height: 10.0,
+            aspect_ratio: 16.0 / 9.0,
+        }
+    }
+}
+
+impl SpriteCamera {
+    /// 精灵深度(z)范围
+    const DEPTH_RANGE: f32 = 1000.0;
+
+    pub fn view_projection_matrix(&self) -> Mat4 {
+        let half_height = self.height * 0.5;
+        let half_width = half_height * self.aspect_ratio;
+        let projection = Coordinate::orthographic(
+            -half_width,
+            half_width,
+            -half_height,
+            half_height,
+            -Self::DEPTH_RANGE,
+            Self::DEPTH_RANGE,
+        );
+        let view = Mat4::from_translation(-self.position.extend(0.0));
+        projection * view
+    }
+
+    /// 可见区域的世界坐标范围(最小点, 最大点)
+    pub fn view_rect(&self) -> (Vec2, Vec2) {
+        let half_extent = Vec2::new(self.height * self.aspect_ratio, self.height) * 0.5;
+        (self.position - half_extent, self.position + half_extent)
+    }
+
+    /// 屏幕坐标(像素，左上角为原点)转世界坐标
+    pub fn screen_to_world(&self, screen_pos: Vec2, screen_size: Vec2) -> Vec2 {
+        let ndc = Vec2::new(
+            2.0 * screen_pos.x / screen_size.x.max(1.0) - 1.0,
+            1.0 - 2.0 * screen_pos.y / screen_size.y.max(1.0),
+        );
+        let world = self.view_projection_matrix().inverse().project_point3(ndc.extend(0.0));
+        world.truncate()
+    }
+}
+
+/// GPU精灵渲染器
+pub struct SpriteRenderer2D {
+    pipeline: wgpu::RenderPipeline,
+    camera_bind_group_layout: wgpu::BindGroupLayout,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// 找不到纹理时使用的白色纹理
+    white_view: wgpu::TextureView,
+}
+
+impl SpriteRenderer2D {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, output_format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("精灵着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/sprite.wgsl").into()),
+        });
+
+        let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("精灵相机绑定组布局"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("精灵纹理绑定组布局"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("精灵管线布局"),
+            bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("精灵管线"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[SpriteInstance::layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: output_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("精灵采样器"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let white = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some("精灵白色纹理"),
+                size: wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &[255, 255, 255, 255],
+        );
+
+        Self {
+            pipeline,
+            camera_bind_group_layout,
+            texture_bind_group_layout,
+            sampler,
+            white_view: white.create_view(&wgpu::TextureViewDescriptor::default()),
+        }
+    }
+
+    /// 绘制一帧精灵，`textures` 按纹理名称提供纹理视图，缺失的纹理以白色代替
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        camera: &SpriteCamera,
+        frame: &SpriteFrame,
+        textures: &HashMap<String, wgpu::TextureView>,
+    ) {
+        if frame.is_empty() {
+            return;
+        }
+
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("精灵相机"),
+            contents: bytemuck::cast_slice(&camera.view_projection_matrix().to_cols_array()),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("精灵相机绑定组"),
+            layout: &self.camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: camera_buffer.as_entire_binding() }],
+        });
+
+        let instance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("精灵实例"),
+            contents: bytemuck::cast_slice(&frame.instances),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let texture_bind_groups: Vec<wgpu::BindGroup> = frame
+            .batches
+            .iter()
+            .map(|batch| {
+                let view = textures.get(&batch.texture_name).unwrap_or(&self.white_view);
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("精灵纹理绑定组"),
+                    layout: &self.texture_bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(view) },
+                        wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                    ],
+                })
+            })
+            .collect();
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("精灵通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, instance_buffer.slice(..));
+
+        for (batch, bind_group) in frame.batches.iter().zip(texture_bind_groups.iter()) {
+            render_pass.set_bind_group(1, bind_group, &[]);
+            render_pass.draw(0..4, batch.first_instance..batch.first_instance + batch.instance_count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::ECSWorld;
+    use glam::{Quat, Vec3, Vec4};
+    use specs::Builder;
+
+    fn transform_at(position: Vec3) -> Transform {
+        let mut transform = Transform::new();
+        transform.set_position(position);
+        transform
+    }
+
+    #[test]
+    fn sprites_sharing_a_texture_batch_into_one_draw_call() {
+        let mut world = ECSWorld::new().unwrap();
+        for i in 0..5 {
+            world
+                .create_entity()
+                .with(transform_at(Vec3::new(i as f32, 0.0, 0.0)))
+                .with(SpriteRenderer::new("characters").with_flip(i % 2 == 0, false))
+                .build();
+        }
+        world
+            .create_entity()
+            .with(transform_at(Vec3::ZERO))
+            .with(SpriteRenderer { visible: false, ..SpriteRenderer::new("characters") })
+            .build();
+
+        let frame = SpriteBatcher::collect(world.world());
+        assert_eq!(frame.instances.len(), 5);
+        assert_eq!(frame.draw_calls(), 1);
+        assert_eq!(frame.batches[0], SpriteBatch { texture_name: "characters".to_string(), first_instance: 0, instance_count: 5 });
+    }
+
+    #[test]
+    fn sorting_order_splits_batches() {
+        let transform = Transform::new();
+        let background = SpriteRenderer::new("tiles").with_sorting_order(-1);
+        let hero = SpriteRenderer::new("characters").with_sorting_order(0);
+        let tree = SpriteRenderer::new("tiles").with_sorting_order(1);
+
+        let frame = SpriteBatcher::batch(vec![(0, &tree, &transform), (1, &hero, &transform), (2, &background, &transform), (3, &background, &transform)]);
+        let batches: Vec<_> = frame.batches.iter().map(|batch| (batch.texture_name.as_str(), batch.instance_count)).collect();
+        assert_eq!(batches, vec![("tiles", 2), ("characters", 1), ("tiles", 1)]);
+        assert_eq!(frame.batches[2].first_instance, 3);
+    }
+
+    #[test]
+    fn instance_applies_pivot_scale_rotation_and_flip() {
+        let mut transform = transform_at(Vec3::new(10.0, 5.0, 2.0));
+        transform.set_scale(Vec3::new(2.0, 1.0, 1.0));
+        let sprite = SpriteRenderer::new("hero")
+            .with_size(Vec2::new(1.0, 3.0))
+            .with_color(Vec4::new(1.0, 0.5, 0.25, 1.0))
+            .with_flip(false, true);
+
+        let instance = SpriteInstance::from_sprite(&sprite, &transform);
+        assert_eq!(instance.origin, [9.0, 3.5, 2.0]);
+        assert_eq!(instance.axis_x, [2.0, 0.0]);
+        assert_eq!(instance.axis_y, [0.0, 3.0]);
+        assert_eq!((instance.uv_min, instance.uv_max), ([0.0, 1.0], [1.0, 0.0]));
+        assert_eq!(instance.color, [1.0, 0.5, 0.25, 1.0]);
+
+        // 绕Z轴旋转90°后X边指向+Y
+        transform.set_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let rotated = SpriteInstance::from_sprite(&sprite, &transform);
+        assert!((Vec2::from_array(rotated.axis_x) - Vec2::new(0.0, 2.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn camera_maps_screen_corners_to_view_rect() {
+        let camera = SpriteCamera { position: Vec2::new(3.0, -1.0), height: 8.0, aspect_ratio: 2.0 };
+        let (min, max) = camera.view_rect();
+        assert_eq!((min, max), (Vec2::new(-5.0, -5.0), Vec2::new(11.0, 3.0)));
+
+        let screen = Vec2::new(800.0, 400.0);
+        assert!((camera.screen_to_world(Vec2::new(0.0, 400.0), screen) - min).length() < 1e-4);
+        assert!((camera.screen_to_world(Vec2::new(800.0, 0.0), screen) - max).length() < 1e-4);
+        assert!((camera.screen_to_world(screen * 0.5, screen) - camera.position).length() < 1e-4);
+    }
+}
@@ -0,0 +1,514 @@
+//! 时间性抗锯齿(TAA)
+//!
+//! 主通道按Halton序列对投影做亚像素抖动 -> 由深度与前后两帧视图投影矩阵把像素重投影到历史帧 ->
+//! 以当前帧3x3邻域的颜色范围钳制历史颜色 -> 按 `feedback` 混合当前帧与历史帧并写回历史缓冲。
+//! `TaaResolver` 是CPU参考实现，与taa.wgsl中的GPU实现保持一致。
+
+use crate::math::{Mat4, Vec2, Vec3, Vec4};
+use crate::render::{Camera, RenderTarget, TAAConfig};
+use std::cell::Cell;
+use wgpu::util::DeviceExt;
+
+/// 抖动序列长度
+pub const TAA_JITTER_SEQUENCE_LENGTH: u32 = 8;
+
+/// TAA着色器uniform(对应taa.wgsl)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct TaaUniforms {
+    inverse_view_projection: [[f32; 4]; 4],
+    previous_view_projection: [[f32; 4]; 4],
+    texel_size: [f32; 2],
+    feedback: f32,
+    history_valid: f32,
+}
+
+/// TAA用到的相机矩阵(未抖动)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaaCamera {
+    pub view_projection: Mat4,
+    pub previous_view_projection: Mat4,
+    /// 已同步的帧数，用于选择抖动偏移
+    pub frame_index: u32,
+}
+
+impl Default for TaaCamera {
+    fn default() -> Self {
+        Self {
+            view_projection: Mat4::IDENTITY,
+            previous_view_projection: Mat4::IDENTITY,
+            frame_index: 0,
+        }
+    }
+}
+
+impl TaaCamera {
+    /// 同步新一帧的相机，上一帧的矩阵成为历史矩阵
+    pub fn advance(&mut self, camera: &Camera) {
+        self.advance_view_projection(camera.view_projection_matrix());
+    }
+
+    /// 以视图投影矩阵同步新一帧
+    pub fn advance_view_projection(&mut self, view_projection: Mat4) {
+        self.previous_view_projection = if self.frame_index == 0 { view_projection } else { self.view_projection };
+        self.view_projection = view_projection;
+        self.frame_index = self.frame_index.wrapping_add(1);
+    }
+
+    /// 当前帧的投影抖动(NDC)
+    pub fn jitter(&self, config: &TAAConfig, width: u32, height: u32) -> Vec2 {
+        TaaResolver::jitter_offset(self.frame_index, width, height, config.jitter_scale)
+    }
+}
+
+/// CPU TAA解析器
+pub struct TaaResolver;
+
+impl TaaResolver {
+    /// Halton低差异序列，`index` 从1开始
+    pub fn halton(mut index: u32, base: u32) -> f32 {
+        let mut fraction = 1.0;
+        let mut result = 0.0;
+        while index > 0 {
+            fraction /= base as f32;
+            result += fraction * (index % base) as f32;
+            index /= base;
+        }
+        result
+    }
+
+    /// 第 `frame_index` 帧的亚像素抖动，单位为NDC
+    pub fn jitter_offset(frame_index: u32, width: u32, height: u32, scale: f32) -> Vec2 {
+        let index = frame_index % TAA_JITTER_SEQUENCE_LENGTH + 1;
+        let pixel = Vec2::new(Self::halton(index, 2) - 0.5, Self::halton(index, 3) - 0.5) * scale;
+        Vec2::new(pixel.x * 2.0 / width.max(1) as f32, pixel.y * 2.0 / height.max(1) as f32)
+    }
+
+    /// 给投影矩阵加上NDC抖动
+    pub fn jitter_projection(projection: Mat4, jitter: Vec2) -> Mat4 {
+        Mat4::from_translation(Vec3::new(jitter.x, jitter.y, 0.0)) * projection
+    }
+
+    /// 把当前帧的屏幕uv(左上角为原点)与深度重投影到上一帧的uv
+    pub fn reproject(uv: Vec2, depth: f32, inverse_view_projection: Mat4, previous_view_projection: Mat4) -> Vec2 {
+        let ndc = Vec4::new(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, depth, 1.0);
+        let world = inverse_view_projection * ndc;
+        let world = world / world.w;
+        let previous = previous_view_projection * world;
+        let previous = previous.truncate() / previous.w;
+        Vec2::new(previous.x * 0.5 + 0.5, 0.5 - previous.y * 0.5)
+    }
+
+    /// 把历史颜色钳制到邻域范围后与当前颜色混合
+    ///
+    /// 重投影落在屏幕外或历史无效时直接使用当前颜色。
+    pub fn resolve(
+        current: Vec3,
+        history: Vec3,
+        neighborhood_min: Vec3,
+        neighborhood_max: Vec3,
+        history_uv: Vec2,
+        feedback: f32,
+        history_valid: bool,
+    ) -> Vec3 {
+        let on_screen = (0.0..=1.0).contains(&history_uv.x) && (0.0..=1.0).contains(&history_uv.y);
+        if !history_valid || !on_screen {
+            return current;
+        }
+        let history = history.clamp(neighborhood_min, neighborhood_max);
+        current.lerp(history, feedback.clamp(0.0, 1.0))
+    }
+}
+
+/// GPU TAA渲染器
+///
+/// 历史缓冲两张交替使用：解析结果写入一张并作为下一帧的历史，再复制到输出。
+pub struct TaaRenderer {
+    resolve_pipeline: wgpu::RenderPipeline,
+    copy_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    history: [RenderTarget; 2],
+    /// 本帧写入的历史缓冲索引
+    write_index: Cell<usize>,
+    history_valid: Cell<bool>,
+    width: u32,
+    height: u32,
+}
+
+impl TaaRenderer {
+    pub fn new(device: &wgpu::Device, output_format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("TAA着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/post_processing/taa.wgsl").into()),
+        });
+
+        let texture_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("TAA绑定组布局"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                texture_entry(3),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("TAA管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let create_pipeline = |entry_point: &str| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: output_format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            })
+        };
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("TAA采样器"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            resolve_pipeline: create_pipeline("fs_resolve"),
+            copy_pipeline: create_pipeline("fs_copy"),
+            bind_group_layout,
+            sampler,
+            history: Self::create_history(device, output_format, width, height),
+            write_index: Cell::new(0),
+            history_valid: Cell::new(false),
+            width,
+            height,
+        }
+    }
+
+    fn create_history(device: &wgpu::Device, format: wgpu::TextureFormat, width: u32, height: u32) -> [RenderTarget; 2] {
+        let (width, height) = (width.max(1), height.max(1));
+        [
+            RenderTarget::new(device, width, height, format, Some("TAA History A")),
+            RenderTarget::new(device, width, height, format, Some("TAA History B")),
+        ]
+    }
+
+    /// 重新创建历史缓冲，旧历史作废
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        let format = self.history[0].format;
+        self.history = Self::create_history(device, format, width, height);
+        self.invalidate_history();
+    }
+
+    /// 丢弃历史(相机切换、画面跳变时调用)
+    pub fn invalidate_history(&self) {
+        self.history_valid.set(false);
+    }
+
+    /// 解析input并写入output，同时更新历史缓冲
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        config: &TAAConfig,
+        camera: &TaaCamera,
+        input: &wgpu::TextureView,
+        depth: &wgpu::TextureView,
+        output: &wgpu::TextureView,
+    ) {
+        let write = self.write_index.get();
+        let read = 1 - write;
+
+        let uniforms = TaaUniforms {
+            inverse_view_projection: camera.view_projection.inverse().to_cols_array_2d(),
+            previous_view_projection: camera.previous_view_projection.to_cols_array_2d(),
+            texel_size: [1.0 / self.width.max(1) as f32, 1.0 / self.height.max(1) as f32],
+            feedback: config.feedback,
+            history_valid: if self.history_valid.get() { 1.0 } else { 0.0 },
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("TAA Uniform"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        // 重投影、钳制并混合到本帧的历史缓冲
+        let resolve_bind_group = self.bind_group(device, &uniform_buffer, input, &self.history[read].view, depth);
+        self.draw(encoder, &self.resolve_pipeline, &resolve_bind_group, &self.history[write].view);
+
+        // 复制到输出
+        let copy_bind_group = self.bind_group(device, &uniform_buffer, &self.history[write].view, &self.history[read].view, depth);
+        self.draw(encoder, &self.copy_pipeline, &copy_bind_group, output);
+
+        self.write_index.set(read);
+        self.history_valid.set(true);
+    }
+
+    fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        current: &wgpu::TextureView,
+        history: &wgpu::TextureView,
+        depth: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("TAA绑定组"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(current) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(history) },
+                wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(depth) },
+            ],
+        })
+    }
+
+    fn draw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        bind_group: &wgpu::BindGroup,
+        target: &wgpu::TextureView,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("TAA通道"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    #[test]
+    fn halton_matches_known_values() {
+        assert_eq!(TaaResolver::halton(1, 2), 0.5);
+        assert_eq!(TaaResolver::halton(2, 2), 0.25);
+        assert_eq!(TaaResolver::halton(3, 2), 0.75);
+        assert!((TaaResolver::halton(1, 3) - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn jitter_stays_within_half_pixel() {
+        for frame in 0..TAA_JITTER_SEQUENCE_LENGTH * 2 {
+            let jitter = TaaResolver::jitter_offset(frame, 100, 50, 1.0);
+            assert!(jitter.x.abs() <= 1.0 / 100.0);
+            assert!(jitter.y.abs() <= 1.0 / 50.0);
+        }
+    }
+
+    #[test]
+    fn reproject_is_identity_for_static_camera() {
+        let camera = Camera::default();
+        let view_projection = camera.view_projection_matrix();
+        let uv = TaaResolver::reproject(Vec2::new(0.25, 0.75), 0.5, view_projection.inverse(), view_projection);
+        assert!((uv - Vec2::new(0.25, 0.75)).length() < 1e-4);
+    }
+
+    #[test]
+    fn reproject_follows_camera_motion() {
+        let mut camera = Camera::default();
+        let previous = camera.view_projection_matrix();
+        camera.move_right(0.5);
+        let current = camera.view_projection_matrix();
+        // 相机右移，静止物体在上一帧中位于更右侧
+        let uv = TaaResolver::reproject(Vec2::new(0.5, 0.5), 0.5, current.inverse(), previous);
+        assert!(uv.x > 0.5);
+    }
+
+    #[test]
+    fn resolve_blends_and_clamps_history() {
+        let current = Vec3::splat(0.5);
+        let uv = Vec2::splat(0.5);
+        let blended = TaaResolver::resolve(current, Vec3::splat(0.6), Vec3::ZERO, Vec3::ONE, uv, 0.9, true);
+        assert!((blended - Vec3::splat(0.59)).length() < 1e-5);
+
+        let clamped = TaaResolver::resolve(current, Vec3::splat(5.0), Vec3::splat(0.4), Vec3::splat(0.6), uv, 1.0, true);
+        assert_eq!(clamped, Vec3::splat(0.6));
+    }
+
+    #[test]
+    fn resolve_rejects_invalid_history() {
+        let current = Vec3::splat(0.5);
+        let history = Vec3::ONE;
+        let (min, max) = (Vec3::ZERO, Vec3::ONE);
+        assert_eq!(TaaResolver::resolve(current, history, min, max, Vec2::splat(0.5), 0.9, false), current);
+        assert_eq!(TaaResolver::resolve(current, history, min, max, Vec2::new(1.5, 0.5), 0.9, true), current);
+    }
+
+    #[test]
+    fn camera_advance_keeps_previous_matrix() {
+        let mut camera = Camera::default();
+        let mut taa_camera = TaaCamera::default();
+        taa_camera.advance(&camera);
+        assert_eq!(taa_camera.previous_view_projection, taa_camera.view_projection);
+
+        let first = taa_camera.view_projection;
+        camera.move_forward(1.0);
+        taa_camera.advance(&camera);
+        assert_eq!(taa_camera.previous_view_projection, first);
+        assert_ne!(taa_camera.view_projection, first);
+    }
+
+    #[test]
+    fn gpu_resolve_of_static_image_keeps_colors() {
+        const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+        const SIZE: u32 = 4;
+        let Some((_, device, queue)) = headless_device(None) else {
+            eprintln!("跳过TAA测试: 没有可用的适配器");
+            return;
+        };
+
+        let texture = |format: wgpu::TextureFormat, usage: wgpu::TextureUsages| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("TAA测试纹理"),
+                size: wgpu::Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+        };
+        let input = texture(FORMAT, wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST);
+        let pixel = [200u8, 100, 50, 255];
+        queue.write_texture(
+            input.as_image_copy(),
+            &pixel.repeat((SIZE * SIZE) as usize),
+            wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(SIZE * 4), rows_per_image: Some(SIZE) },
+            input.size(),
+        );
+        let depth = texture(
+            crate::render::MAIN_DEPTH_FORMAT,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        );
+        let output = texture(FORMAT, wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC);
+        let view = |texture: &wgpu::Texture| texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let renderer = TaaRenderer::new(&device, FORMAT, SIZE, SIZE);
+        let mut camera = TaaCamera::default();
+        let config = TAAConfig::default();
+        let bytes_per_row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("TAA测试回读缓冲"),
+            size: (bytes_per_row * SIZE) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // 第一帧没有历史，第二帧混合历史；静止画面两帧都应保持原色
+        for _ in 0..2 {
+            camera.advance(&Camera::default());
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("清空深度"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &view(&depth),
+                    depth_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Clear(0.5), store: wgpu::StoreOp::Store }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            renderer.render(&device, &mut encoder, &config, &camera, &view(&input), &view(&depth), &view(&output));
+            encoder.copy_texture_to_buffer(
+                output.as_image_copy(),
+                wgpu::ImageCopyBuffer {
+                    buffer: &buffer,
+                    layout: wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(bytes_per_row), rows_per_image: Some(SIZE) },
+                },
+                output.size(),
+            );
+            queue.submit(std::iter::once(encoder.finish()));
+
+            let slice = buffer.slice(..);
+            slice.map_async(wgpu::MapMode::Read, |_| {});
+            device.poll(wgpu::Maintain::Wait);
+            {
+                let data = slice.get_mapped_range();
+                for (actual, expected) in data[..4].iter().zip(pixel) {
+                    assert!(actual.abs_diff(expected) <= 1, "{:?}", &data[..4]);
+                }
+            }
+            buffer.unmap();
+        }
+    }
+}
@@ -1,8 +1,10 @@
 //! 纹理系统
 
 use crate::{EngineResult, EngineError};
+use crate::math::Vec3;
+use crate::render::{cube_face_direction, direction_to_cube_face};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// 纹理格式
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -31,6 +33,126 @@ pub enum WrapMode {
     ClampToBorder,
 }
 
+impl FilterMode {
+    pub fn to_wgpu(self) -> wgpu::FilterMode {
+        match self {
+            FilterMode::Linear => wgpu::FilterMode::Linear,
+            FilterMode::Nearest => wgpu::FilterMode::Nearest,
+        }
+    }
+}
+
+impl WrapMode {
+    pub fn to_wgpu(self) -> wgpu::AddressMode {
+        match self {
+            WrapMode::Repeat => wgpu::AddressMode::Repeat,
+            WrapMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+            WrapMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+            WrapMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
+        }
+    }
+}
+
+/// 纹理采样设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextureSamplerConfig {
+    /// 放大、缩小与mip之间的过滤方式，像素风格使用Nearest
+    pub filter: FilterMode,
+    pub wrap: WrapMode,
+    /// 各向异性过滤级别，1表示关闭；仅Linear过滤时生效
+    pub anisotropy: u8,
+}
+
+impl Default for TextureSamplerConfig {
+    fn default() -> Self {
+        Self {
+            filter: FilterMode::Linear,
+            wrap: WrapMode::Repeat,
+            anisotropy: 1,
+        }
+    }
+}
+
+impl TextureSamplerConfig {
+    /// wgpu允许的最大各向异性级别
+    pub const MAX_ANISOTROPY: u16 = 16;
+
+    /// 像素风格：最近邻过滤，边缘钳制
+    pub fn pixel_art() -> Self {
+        Self {
+            filter: FilterMode::Nearest,
+            wrap: WrapMode::ClampToEdge,
+            anisotropy: 1,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_anisotropy(mut self, anisotropy: u8) -> Self {
+        self.anisotropy = anisotropy;
+        self
+    }
+
+    /// 沿用描述符中的放大过滤与U方向包装模式
+    pub fn from_descriptor(descriptor: &TextureDescriptor) -> Self {
+        Self {
+            filter: descriptor.mag_filter,
+            wrap: descriptor.wrap_u,
+            anisotropy: 1,
+        }
+    }
+
+    /// 设备支持的最大各向异性级别，不支持各向异性过滤时为1
+    pub fn device_max_anisotropy(adapter: &wgpu::Adapter) -> u16 {
+        let supported = adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::ANISOTROPIC_FILTERING);
+        if supported {
+            Self::MAX_ANISOTROPY
+        } else {
+            1
+        }
+    }
+
+    /// 实际使用的各向异性级别：钳制到[1, max_anisotropy]，Nearest过滤时为1(wgpu要求各向异性时全部为Linear)
+    pub fn effective_anisotropy(&self, max_anisotropy: u16) -> u16 {
+        match self.filter {
+            FilterMode::Nearest => 1,
+            FilterMode::Linear => (self.anisotropy as u16).clamp(1, max_anisotropy.clamp(1, Self::MAX_ANISOTROPY)),
+        }
+    }
+
+    pub fn sampler_descriptor<'a>(&self, label: Option<&'a str>, max_anisotropy: u16) -> wgpu::SamplerDescriptor<'a> {
+        let filter = self.filter.to_wgpu();
+        let address_mode = self.wrap.to_wgpu();
+        wgpu::SamplerDescriptor {
+            label,
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_filter: filter,
+            anisotropy_clamp: self.effective_anisotropy(max_anisotropy),
+            border_color: (self.wrap == WrapMode::ClampToBorder).then_some(wgpu::SamplerBorderColor::TransparentBlack),
+            ..Default::default()
+        }
+    }
+
+    pub fn create_sampler(&self, device: &wgpu::Device, label: Option<&str>, max_anisotropy: u16) -> wgpu::Sampler {
+        device.create_sampler(&self.sampler_descriptor(label, max_anisotropy))
+    }
+}
+
 /// 纹理描述符
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextureDescriptor {
@@ -42,6 +164,18 @@ pub struct TextureDescriptor {
     pub wrap_u: WrapMode,
     pub wrap_v: WrapMode,
     pub generate_mipmaps: bool,
+    /// 层数，立方体贴图为6
+    #[serde(default = "TextureDescriptor::default_layers")]
+    pub depth_or_array_layers: u32,
+    /// 是否为立方体贴图，6层依次为+X、-X、+Y、-Y、+Z、-Z
+    #[serde(default)]
+    pub is_cubemap: bool,
+}
+
+impl TextureDescriptor {
+    fn default_layers() -> u32 {
+        1
+    }
 }
 
 impl Default for TextureDescriptor {
@@ -55,34 +189,81 @@ impl Default for TextureDescriptor {
             wrap_u: WrapMode::Repeat,
             wrap_v: WrapMode::Repeat,
             generate_mipmaps: true,
+            depth_or_array_layers: 1,
+            is_cubemap: false,
         }
     }
 }
 
+/// 立方体贴图来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum CubemapSource {
+    /// 六张面图，顺序为+X、-X、+Y、-Y、+Z、-Z
+    Faces([PathBuf; 6]),
+    /// 等距柱状投影(全景)图，转换为边长face_size的立方体
+    Equirect { path: PathBuf, face_size: u32 },
+}
+
 /// 纹理数据
 #[derive(Debug, Clone)]
 pub struct Texture {
     pub descriptor: TextureDescriptor,
     pub data: Vec<u8>,
     pub name: String,
+    /// 采样设置，默认沿用描述符中的过滤与包装模式
+    pub sampler: TextureSamplerConfig,
 }
 
 impl Texture {
     /// 创建新的纹理
     pub fn new(descriptor: TextureDescriptor, data: Vec<u8>, name: impl Into<String>) -> Self {
         Self {
+            sampler: TextureSamplerConfig::from_descriptor(&descriptor),
             descriptor,
             data,
             name: name.into(),
         }
     }
 
+    /// 设置采样方式
+    pub fn with_sampler(mut self, sampler: TextureSamplerConfig) -> Self {
+        self.sampler = sampler;
+        self
+    }
+
+    /// 按采样设置创建wgpu采样器，各向异性钳制到max_anisotropy(见 `TextureSamplerConfig::device_max_anisotropy`)
+    pub fn create_sampler(&self, device: &wgpu::Device, max_anisotropy: u16) -> wgpu::Sampler {
+        self.sampler.create_sampler(device, Some(&self.name), max_anisotropy)
+    }
+
     /// 从文件加载纹理
     pub fn from_file<P: AsRef<Path>>(path: P) -> EngineResult<Self> {
         let path = path.as_ref();
         let img = image::open(path)
             .map_err(|e| EngineError::AssetError(format!("加载纹理失败 {:?}: {}", path, e)))?;
 
+        Ok(Self::from_image(img, Self::name_from_path(path)))
+    }
+
+    /// 从内存中的图像文件数据加载纹理
+    pub fn from_memory(bytes: &[u8], name: impl Into<String>) -> EngineResult<Self> {
+        let name = name.into();
+        let img = image::load_from_memory(bytes)
+            .map_err(|e| EngineError::AssetError(format!("解码纹理失败 {}: {}", name, e)))?;
+
+        Ok(Self::from_image(img, name))
+    }
+
+    /// 按指定图像格式从内存加载纹理(TGA等没有文件头标识的格式需要指定)
+    pub fn from_memory_with_format(bytes: &[u8], format: image::ImageFormat, name: impl Into<String>) -> EngineResult<Self> {
+        let name = name.into();
+        let img = image::load_from_memory_with_format(bytes, format)
+            .map_err(|e| EngineError::AssetError(format!("解码纹理失败 {}: {}", name, e)))?;
+
+        Ok(Self::from_image(img, name))
+    }
+
+    fn from_image(img: image::DynamicImage, name: String) -> Self {
         let img = img.to_rgba8();
         let (width, height) = img.dimensions();
 
@@ -93,14 +274,14 @@ impl Texture {
             ..Default::default()
         };
 
-        Ok(Self::new(
-            descriptor,
-            img.into_raw(),
-            path.file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("未知纹理")
-                .to_string(),
-        ))
+        Self::new(descriptor, img.into_raw(), name)
+    }
+
+    fn name_from_path(path: &Path) -> String {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("未知纹理")
+            .to_string()
     }
 
     /// 创建纯色纹理
@@ -148,6 +329,165 @@ impl Texture {
         Self::new(descriptor, data, "棋盘格纹理")
     }
 
+    /// 从文件加载浮点纹理(HDR/EXR保留超过1的亮度)
+    pub fn from_file_hdr<P: AsRef<Path>>(path: P) -> EngineResult<Self> {
+        let path = path.as_ref();
+        let img = image::open(path)
+            .map_err(|e| EngineError::AssetError(format!("加载纹理失败 {:?}: {}", path, e)))?
+            .to_rgba32f();
+        let (width, height) = img.dimensions();
+
+        let descriptor = TextureDescriptor {
+            width,
+            height,
+            format: TextureFormat::Rgba32Float,
+            ..Default::default()
+        };
+        let data = img.into_raw().into_iter().flat_map(f32::to_le_bytes).collect();
+        Ok(Self::new(descriptor, data, Self::name_from_path(path)))
+    }
+
+    /// 加载立方体贴图
+    pub fn load_cubemap(source: &CubemapSource) -> EngineResult<Self> {
+        match source {
+            CubemapSource::Faces(paths) => {
+                let faces = paths.iter().map(Self::from_file).collect::<EngineResult<Vec<_>>>()?;
+                let mut cubemap = Self::cubemap_from_faces(&faces)?;
+                cubemap.name = Self::name_from_path(&paths[0]);
+                Ok(cubemap)
+            }
+            CubemapSource::Equirect { path, face_size } => {
+                Self::cubemap_from_equirect(&Self::from_file_hdr(path)?, *face_size)
+            }
+        }
+    }
+
+    /// 由六张大小、格式相同的正方形面图组成立方体贴图
+    pub fn cubemap_from_faces(faces: &[Texture]) -> EngineResult<Self> {
+        if faces.len() != 6 {
+            return Err(EngineError::AssetError(format!("立方体贴图需要6个面，实际为{}", faces.len())).into());
+        }
+        let first = &faces[0].descriptor;
+        if first.width != first.height {
+            return Err(EngineError::AssetError(format!("立方体贴图的面必须是正方形: {}x{}", first.width, first.height)).into());
+        }
+        if let Some(face) = faces.iter().find(|face| {
+            face.descriptor.width != first.width || face.descriptor.height != first.height || face.descriptor.format != first.format
+        }) {
+            return Err(EngineError::AssetError(format!("立方体贴图的面大小或格式不一致: {}", face.name)).into());
+        }
+
+        let descriptor = TextureDescriptor {
+            width: first.width,
+            height: first.height,
+            format: first.format,
+            wrap_u: WrapMode::ClampToEdge,
+            wrap_v: WrapMode::ClampToEdge,
+            depth_or_array_layers: 6,
+            is_cubemap: true,
+            ..Default::default()
+        };
+        let data = faces.iter().flat_map(|face| face.data.iter().copied()).collect();
+        Ok(Self::new(descriptor, data, "立方体贴图"))
+    }
+
+    /// 把等距柱状投影图转换为立方体贴图(双线性采样，结果为Rgba32Float)
+    pub fn cubemap_from_equirect(equirect: &Texture, face_size: u32) -> EngineResult<Self> {
+        let face_size = face_size.max(1);
+        let (width, height) = (equirect.descriptor.width, equirect.descriptor.height);
+        if width == 0 || height == 0 {
+            return Err(EngineError::AssetError(format!("全景图为空: {}", equirect.name)).into());
+        }
+        // 先检查格式，避免逐像素报错
+        equirect.texel(0, 0, 0)?;
+
+        let sample = |u: f32, v: f32| -> EngineResult<[f32; 4]> {
+            let x = u * width as f32 - 0.5;
+            let y = (v * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+            let (x0, y0) = (x.floor(), y.floor());
+            let (tx, ty) = (x - x0, y - y0);
+            let wrap_x = |x: f32| (x as i64).rem_euclid(width as i64) as u32;
+            let clamp_y = |y: f32| (y as u32).min(height - 1);
+
+            let corners = [
+                equirect.texel(0, wrap_x(x0), clamp_y(y0))?,
+                equirect.texel(0, wrap_x(x0 + 1.0), clamp_y(y0))?,
+                equirect.texel(0, wrap_x(x0), clamp_y(y0 + 1.0))?,
+                equirect.texel(0, wrap_x(x0 + 1.0), clamp_y(y0 + 1.0))?,
+            ];
+            let mut result = [0.0; 4];
+            for (channel, value) in result.iter_mut().enumerate() {
+                let top = corners[0][channel] + (corners[1][channel] - corners[0][channel]) * tx;
+                let bottom = corners[2][channel] + (corners[3][channel] - corners[2][channel]) * tx;
+                *value = top + (bottom - top) * ty;
+            }
+            Ok(result)
+        };
+
+        let mut data = Vec::with_capacity((face_size * face_size * 6 * 16) as usize);
+        for face in 0..6 {
+            for y in 0..face_size {
+                for x in 0..face_size {
+                    let u = (x as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                    let v = (y as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                    let direction = cube_face_direction(face, u, v);
+                    let longitude = direction.z.atan2(direction.x);
+                    let latitude = direction.y.clamp(-1.0, 1.0).asin();
+                    let texel = sample(
+                        0.5 + longitude / (2.0 * std::f32::consts::PI),
+                        0.5 - latitude / std::f32::consts::PI,
+                    )?;
+                    data.extend(texel.iter().flat_map(|channel| channel.to_le_bytes()));
+                }
+            }
+        }
+
+        let descriptor = TextureDescriptor {
+            width: face_size,
+            height: face_size,
+            format: TextureFormat::Rgba32Float,
+            wrap_u: WrapMode::ClampToEdge,
+            wrap_v: WrapMode::ClampToEdge,
+            depth_or_array_layers: 6,
+            is_cubemap: true,
+            ..Default::default()
+        };
+        Ok(Self::new(descriptor, data, equirect.name.clone()))
+    }
+
+    /// 读取第layer层(x, y)处的RGBA值，仅支持Rgba8和Rgba32Float
+    pub fn texel(&self, layer: u32, x: u32, y: u32) -> EngineResult<[f32; 4]> {
+        let (width, height) = (self.descriptor.width, self.descriptor.height);
+        let index = ((layer * height + y) * width + x) as usize;
+
+        match self.descriptor.format {
+            TextureFormat::Rgba8 => {
+                let bytes = self.data.get(index * 4..index * 4 + 4)
+                    .ok_or_else(|| EngineError::AssetError(format!("纹理坐标越界: {}", self.name)))?;
+                Ok([0, 1, 2, 3].map(|channel| bytes[channel] as f32 / 255.0))
+            }
+            TextureFormat::Rgba32Float => {
+                let bytes = self.data.get(index * 16..index * 16 + 16)
+                    .ok_or_else(|| EngineError::AssetError(format!("纹理坐标越界: {}", self.name)))?;
+                Ok([0, 1, 2, 3].map(|channel| {
+                    f32::from_le_bytes(bytes[channel * 4..channel * 4 + 4].try_into().unwrap())
+                }))
+            }
+            format => Err(EngineError::AssetError(format!("不支持读取{:?}格式的纹理: {}", format, self.name)).into()),
+        }
+    }
+
+    /// 立方体贴图在direction方向上的最近点采样
+    pub fn sample_cube(&self, direction: Vec3) -> EngineResult<[f32; 4]> {
+        if !self.descriptor.is_cubemap {
+            return Err(EngineError::AssetError(format!("不是立方体贴图: {}", self.name)).into());
+        }
+        let (face, u, v) = direction_to_cube_face(direction);
+        let size = self.descriptor.width;
+        let to_texel = |coordinate: f32| (((coordinate + 1.0) * 0.5 * size as f32) as u32).min(size - 1);
+        self.texel(face, to_texel(u), to_texel(v))
+    }
+
     /// 获取像素数据大小
     pub fn data_size(&self) -> usize {
         let pixel_size = match self.descriptor.format {
@@ -158,6 +498,143 @@ impl Texture {
             TextureFormat::Rgba16Float => 8,
             TextureFormat::Rgba32Float => 16,
         };
-        (self.descriptor.width * self.descriptor.height) as usize * pixel_size
+        (self.descriptor.width * self.descriptor.height) as usize * self.descriptor.depth_or_array_layers.max(1) as usize * pixel_size
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::headless_device;
+
+    const FACE_COLORS: [[u8; 4]; 6] = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 0, 255],
+        [0, 255, 255, 255],
+        [255, 0, 255, 255],
+    ];
+
+    /// 写出六张32x32的纯色面图
+    fn write_faces(name: &str) -> (PathBuf, [PathBuf; 6]) {
+        let dir = std::env::temp_dir().join(format!("sanji_cubemap_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = ["px", "nx", "py", "ny", "pz", "nz"].map(|face| dir.join(format!("{}.png", face)));
+        for (path, color) in paths.iter().zip(FACE_COLORS) {
+            image::RgbaImage::from_pixel(32, 32, image::Rgba(color)).save(path).unwrap();
+        }
+        (dir, paths)
+    }
+
+    #[test]
+    fn six_faces_load_as_cubemap() {
+        let (dir, paths) = write_faces("faces");
+        let cubemap = Texture::load_cubemap(&CubemapSource::Faces(paths)).unwrap();
+
+        assert!(cubemap.descriptor.is_cubemap);
+        assert_eq!(cubemap.descriptor.depth_or_array_layers, 6);
+        assert_eq!((cubemap.descriptor.width, cubemap.descriptor.height), (32, 32));
+        assert_eq!(cubemap.data.len(), cubemap.data_size());
+        assert_eq!(cubemap.name, "px");
+
+        let directions = [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z];
+        for (direction, color) in directions.into_iter().zip(FACE_COLORS) {
+            assert_eq!(cubemap.sample_cube(direction).unwrap(), color.map(|channel| channel as f32 / 255.0));
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn mismatched_faces_are_rejected() {
+        let face = Texture::solid_color(8, 8, [0; 4]);
+        assert!(Texture::cubemap_from_faces(&vec![face.clone(); 5]).is_err());
+        assert!(Texture::cubemap_from_faces(&vec![Texture::solid_color(8, 4, [0; 4]); 6]).is_err());
+
+        let mut faces = vec![face; 6];
+        faces[3] = Texture::solid_color(16, 16, [0; 4]);
+        assert!(Texture::cubemap_from_faces(&faces).is_err());
+
+        // 普通纹理不能按立方体采样
+        assert!(Texture::solid_color(8, 8, [0; 4]).sample_cube(Vec3::X).is_err());
+    }
+
+    #[test]
+    fn uniform_equirect_converts_to_uniform_cube() {
+        let equirect = Texture::solid_color(16, 8, [51, 102, 204, 255]);
+        let cubemap = Texture::cubemap_from_equirect(&equirect, 4).unwrap();
+
+        assert_eq!(cubemap.descriptor.format, TextureFormat::Rgba32Float);
+        assert_eq!(cubemap.descriptor.depth_or_array_layers, 6);
+        assert_eq!(cubemap.data.len(), 4 * 4 * 6 * 16);
+        for layer in 0..6 {
+            let texel = cubemap.texel(layer, 3, 1).unwrap();
+            assert!((texel[0] - 0.2).abs() < 1e-5 && (texel[2] - 0.8).abs() < 1e-5, "{:?}", texel);
+        }
+    }
+
+    #[test]
+    fn texel_reads_only_supported_formats() {
+        let texture = Texture::solid_color(2, 2, [255, 0, 0, 255]);
+        assert_eq!(texture.texel(0, 1, 1).unwrap(), [1.0, 0.0, 0.0, 1.0]);
+        assert!(texture.texel(0, 2, 1).is_err());
+
+        let descriptor = TextureDescriptor { width: 1, height: 1, format: TextureFormat::Rgb8, ..Default::default() };
+        assert!(Texture::new(descriptor, vec![0; 3], "rgb").texel(0, 0, 0).is_err());
+    }
+
+    #[test]
+    fn nearest_texture_builds_nearest_sampler() {
+        let texture = Texture::solid_color(4, 4, [0; 4]).with_sampler(TextureSamplerConfig::pixel_art().with_anisotropy(8));
+        let descriptor = texture.sampler.sampler_descriptor(Some("pixel"), TextureSamplerConfig::MAX_ANISOTROPY);
+
+        assert_eq!(descriptor.mag_filter, wgpu::FilterMode::Nearest);
+        assert_eq!(descriptor.min_filter, wgpu::FilterMode::Nearest);
+        assert_eq!(descriptor.mipmap_filter, wgpu::FilterMode::Nearest);
+        assert_eq!(descriptor.address_mode_u, wgpu::AddressMode::ClampToEdge);
+        // Nearest过滤时不能开启各向异性
+        assert_eq!(descriptor.anisotropy_clamp, 1);
+        assert!(descriptor.border_color.is_none());
+    }
+
+    #[test]
+    fn anisotropy_is_clamped_to_device_max() {
+        let config = TextureSamplerConfig::default().with_anisotropy(64);
+        assert_eq!(config.effective_anisotropy(16), 16);
+        assert_eq!(config.effective_anisotropy(4), 4);
+        assert_eq!(config.effective_anisotropy(1), 1);
+        assert_eq!(config.effective_anisotropy(0), 1);
+        assert_eq!(config.effective_anisotropy(1000), TextureSamplerConfig::MAX_ANISOTROPY);
+        assert_eq!(config.with_anisotropy(0).effective_anisotropy(16), 1);
+        assert_eq!(config.sampler_descriptor(None, 8).anisotropy_clamp, 8);
+    }
+
+    #[test]
+    fn sampler_follows_descriptor_by_default() {
+        let descriptor = TextureDescriptor { mag_filter: FilterMode::Nearest, wrap_u: WrapMode::ClampToBorder, ..Default::default() };
+        let texture = Texture::new(descriptor, vec![0; 4], "border");
+        assert_eq!(texture.sampler, TextureSamplerConfig { filter: FilterMode::Nearest, wrap: WrapMode::ClampToBorder, anisotropy: 1 });
+        assert_eq!(
+            texture.sampler.sampler_descriptor(None, 16).border_color,
+            Some(wgpu::SamplerBorderColor::TransparentBlack)
+        );
+    }
+
+    #[test]
+    fn device_sampler_uses_clamped_anisotropy() {
+        let Some((adapter, device, _queue)) = headless_device(None) else {
+            eprintln!("跳过: 没有可用的图形适配器");
+            return;
+        };
+
+        let max_anisotropy = TextureSamplerConfig::device_max_anisotropy(&adapter);
+        assert!((1..=TextureSamplerConfig::MAX_ANISOTROPY).contains(&max_anisotropy));
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let texture = Texture::solid_color(4, 4, [0; 4]).with_sampler(TextureSamplerConfig::default().with_anisotropy(255));
+        let _sampler = texture.create_sampler(&device, max_anisotropy);
+        let _pixel = Texture::solid_color(4, 4, [0; 4])
+            .with_sampler(TextureSamplerConfig::pixel_art())
+            .create_sampler(&device, max_anisotropy);
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,683 @@
+//! 资源缩略图渲染
+//!
+//! 把场景或预制件渲染到离屏纹理并回读为 `Texture`，供编辑器的项目面板显示预览。
+//! 相机根据内容的包围盒自动取景，结果按键缓存，内容变化后需调用 `invalidate` 重新渲染。
+//! `render_view` 使用外部相机且不缓存，编辑器的场景视口每帧用它渲染ECS世界。
+
+use crate::assets::{AssetHandle, AssetId};
+use crate::ecs::{ECSWorld, MeshRenderer, Transform};
+use crate::math::Coordinate;
+use crate::render::{request_headless_device, FilterMode, Mesh, Texture, TextureDescriptor, TextureFormat, WrapMode};
+use crate::scene::{PrefabType, Scene};
+use crate::{EngineError, EngineResult};
+use glam::{Mat3, Mat4, Vec3};
+use std::collections::HashMap;
+use std::sync::Arc;
+use wgpu::util::DeviceExt;
+
+/// 缩略图颜色格式
+const COLOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+/// 缩略图深度格式
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+/// 取景相机的垂直视场角
+const THUMBNAIL_FOV: f32 = 35.0 * std::f32::consts::PI / 180.0;
+/// 背景颜色
+const BACKGROUND: wgpu::Color = wgpu::Color { r: 0.14, g: 0.14, b: 0.16, a: 1.0 };
+
+/// 缩略图内容来源
+pub enum ThumbnailSource<'a> {
+    /// 场景中所有可见的网格
+    Scene { scene: &'a Scene, world: &'a ECSWorld },
+    /// 预制件(相机、光源等没有网格的预制件只有背景)
+    Prefab(PrefabType),
+    /// 单个网格
+    Mesh(&'a Mesh),
+    /// 世界中所有可见的网格，不限于某个场景(编辑器视口)
+    World(&'a ECSWorld),
+}
+
+impl ThumbnailSource<'_> {
+    /// 缓存键
+    pub fn cache_key(&self, size: u32) -> String {
+        match self {
+            Self::Scene { scene, .. } => format!("scene:{}@{}", scene.name, size),
+            Self::Prefab(prefab) => format!("prefab:{:?}@{}", prefab, size),
+            Self::Mesh(mesh) => format!("mesh:{}@{}", mesh.name, size),
+            Self::World(_) => format!("world@{}", size),
+        }
+    }
+}
+
+/// 缩略图顶点(世界空间)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ThumbnailVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+    color: [f32; 3],
+}
+
+impl ThumbnailVertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x3];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ThumbnailVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ThumbnailUniforms {
+    view_projection: [[f32; 4]; 4],
+    light_direction: [f32; 4],
+}
+
+/// 缩略图渲染器，只持有管线，设备和队列由调用方提供
+pub struct ThumbnailRenderer {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    /// 按 MeshRenderer::mesh_name 查找的网格
+    meshes: HashMap<String, Mesh>,
+}
+
+impl ThumbnailRenderer {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("缩略图着色器"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/thumbnail.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("缩略图绑定组布局"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("缩略图管线布局"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("缩略图管线"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[ThumbnailVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: COLOR_FORMAT,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            // 导入的网格绕序不一定统一，缩略图不做背面剔除
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let mut meshes = HashMap::new();
+        meshes.insert("cube".to_string(), Mesh::cube());
+        meshes.insert("sphere".to_string(), Mesh::sphere(0.5, 24));
+        meshes.insert("plane".to_string(), Mesh::plane(1.0));
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            meshes,
+        }
+    }
+
+    /// 注册网格，场景中 mesh_name 相同的 MeshRenderer 使用该网格
+    pub fn register_mesh(&mut self, name: impl Into<String>, mesh: Mesh) {
+        self.meshes.insert(name.into(), mesh);
+    }
+
+    /// 渲染size×size的缩略图并回读为RGBA8纹理(阻塞等待GPU)
+    pub fn render(&self, device: &wgpu::Device, queue: &wgpu::Queue, source: &ThumbnailSource, size: u32) -> EngineResult<Texture> {
+        let max_size = device.limits().max_texture_dimension_2d;
+        if size == 0 || size > max_size {
+            return Err(EngineError::RenderError(format!("缩略图尺寸无效: {} (最大 {})", size, max_size)).into());
+        }
+
+        let (vertices, indices) = self.collect_geometry(source);
+        let view_projection = Self::framing(&vertices);
+        self.render_geometry(device, queue, &vertices, &indices, size, size, view_projection, source.cache_key(size))
+    }
+
+    /// 用给定的视图投影矩阵渲染width×height的图像并回读为RGBA8纹理(阻塞等待GPU)
+    ///
+    /// 与 `render` 不同，相机不自动取景，用于编辑器视口等需要跟随外部相机的场合。
+    pub fn render_view(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        source: &ThumbnailSource,
+        width: u32,
+        height: u32,
+        view_projection: Mat4,
+    ) -> EngineResult<Texture> {
+        let max_size = device.limits().max_texture_dimension_2d;
+        if width == 0 || height == 0 || width > max_size || height > max_size {
+            return Err(EngineError::RenderError(format!("渲染尺寸无效: {}x{} (最大 {})", width, height, max_size)).into());
+        }
+
+        let (vertices, indices) = self.collect_geometry(source);
+        self.render_geometry(device, queue, &vertices, &indices, width, height, view_projection, format!("view@{}x{}", width, height))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_geometry(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertices: &[ThumbnailVertex],
+        indices: &[u32],
+        width: u32,
+        height: u32,
+        view_projection: Mat4,
+        name: String,
+    ) -> EngineResult<Texture> {
+        let extent = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let color_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("缩略图颜色纹理"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: COLOR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("缩略图深度纹理"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let color_view = color_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let uniforms = ThumbnailUniforms {
+            view_projection: view_projection.to_cols_array_2d(),
+            light_direction: Vec3::new(-0.4, -1.0, -0.6).normalize().extend(0.0).to_array(),
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("缩略图统一缓冲"),
+            contents: bytemuck::bytes_of(&uniforms),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("缩略图绑定组"),
+            layout: &self.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() }],
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("缩略图编码器"),
+        });
+
+        let geometry = (!indices.is_empty()).then(|| {
+            let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("缩略图顶点缓冲"),
+                contents: bytemuck::cast_slice(vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("缩略图索引缓冲"),
+                contents: bytemuck::cast_slice(indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+            (vertex_buffer, index_buffer)
+        });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("缩略图通道"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(BACKGROUND),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            if let Some((vertex_buffer, index_buffer)) = &geometry {
+                render_pass.set_pipeline(&self.pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..indices.len() as u32, 0, 0..1);
+            }
+        }
+
+        // 纹理到缓冲的复制要求每行字节数按256对齐
+        let unpadded_bytes_per_row = width * 4;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("缩略图回读缓冲"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &color_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &readback_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            extent,
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback_buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| EngineError::RenderError(e.to_string()))?
+            .map_err(|e| EngineError::RenderError(format!("缩略图回读失败: {}", e)))?;
+
+        let mapped = slice.get_mapped_range();
+        let mut data = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+        for row in mapped.chunks(padded_bytes_per_row as usize) {
+            data.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+        drop(mapped);
+        readback_buffer.unmap();
+
+        let descriptor = TextureDescriptor {
+            width,
+            height,
+            format: TextureFormat::Rgba8,
+            min_filter: FilterMode::Linear,
+            mag_filter: FilterMode::Linear,
+            wrap_u: WrapMode::ClampToEdge,
+            wrap_v: WrapMode::ClampToEdge,
+            generate_mipmaps: false,
+            ..Default::default()
+        };
+        Ok(Texture::new(descriptor, data, name))
+    }
+
+    /// 收集世界空间下的顶点和索引
+    fn collect_geometry(&self, source: &ThumbnailSource) -> (Vec<ThumbnailVertex>, Vec<u32>) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let mut append = |mesh: &Mesh, model: Mat4| {
+            let normal_matrix = Mat3::from_mat4(model).inverse().transpose();
+            let base = vertices.len() as u32;
+            vertices.extend(mesh.vertices.iter().map(|vertex| ThumbnailVertex {
+                position: model.transform_point3(vertex.position).to_array(),
+                normal: (normal_matrix * vertex.normal).normalize_or_zero().to_array(),
+                color: vertex.color.to_array(),
+            }));
+            indices.extend(mesh.indices.iter().map(|&index| base + index));
+        };
+
+        match source {
+            ThumbnailSource::Scene { scene, world } => {
+                let mut entities = scene.get_all_entities();
+                crate::ecs::sort_entities(&mut entities);
+                self.append_entities(world, entities, &mut append);
+            }
+            ThumbnailSource::World(world) => {
+                use specs::{Join, WorldExt};
+
+                let mut entities: Vec<_> = {
+                    let entity_storage = world.world().entities();
+                    let renderers = world.world().read_storage::<MeshRenderer>();
+                    (&entity_storage, &renderers).join().map(|(entity, _)| entity).collect()
+                };
+                crate::ecs::sort_entities(&mut entities);
+                self.append_entities(world, entities, &mut append);
+            }
+            ThumbnailSource::Prefab(prefab) => {
+                let mesh_name = match prefab {
+                    PrefabType::Cube => Some("cube"),
+                    PrefabType::Sphere => Some("sphere"),
+                    PrefabType::Plane => Some("plane"),
+                    _ => None,
+                };
+                if let Some(mesh) = mesh_name.and_then(|name| self.meshes.get(name)) {
+                    append(mesh, Mat4::IDENTITY);
+                }
+            }
+            ThumbnailSource::Mesh(mesh) => append(mesh, Mat4::IDENTITY),
+        }
+
+        (vertices, indices)
+    }
+
+    /// 追加实体的可见网格
+    fn append_entities(&self, world: &ECSWorld, entities: Vec<specs::Entity>, append: &mut impl FnMut(&Mesh, Mat4)) {
+        use specs::WorldExt;
+
+        let transforms = world.world().read_storage::<Transform>();
+        let renderers = world.world().read_storage::<MeshRenderer>();
+        for entity in entities {
+            let (Some(transform), Some(renderer)) = (transforms.get(entity), renderers.get(entity)) else {
+                continue;
+            };
+            if !renderer.visible {
+                continue;
+            }
+            let Some(mesh) = self.meshes.get(&renderer.mesh_name) else {
+                log::debug!("缩略图跳过未注册的网格: {}", renderer.mesh_name);
+                continue;
+            };
+            append(mesh, transform.current_world_matrix());
+        }
+    }
+
+    /// 根据包围球从斜上方取景
+    fn framing(vertices: &[ThumbnailVertex]) -> Mat4 {
+        let (min, max) = vertices.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), vertex| {
+                let position = Vec3::from_array(vertex.position);
+                (min.min(position), max.max(position))
+            },
+        );
+        let (center, radius) = if vertices.is_empty() {
+            (Vec3::ZERO, 1.0)
+        } else {
+            ((min + max) * 0.5, ((max - min) * 0.5).length().max(1e-3))
+        };
+
+        // 包围球恰好落在视场内，留5%边距
+        let distance = radius / (THUMBNAIL_FOV * 0.5).sin() * 1.05;
+        let eye = center + Vec3::new(1.0, 0.8, 1.2).normalize() * distance;
+        let view = Coordinate::look_at(eye, center, Coordinate::UP);
+        let near = (distance - radius * 2.0).max(distance * 0.01);
+        let projection = Coordinate::perspective(THUMBNAIL_FOV, 1.0, near, distance + radius * 2.0);
+        projection * view
+    }
+}
+
+/// 缩略图缓存，持有纹理的强引用，返回的句柄在缓存项失效前保持有效
+///
+/// 缩略图不经过 AssetManager，句柄ID只在缓存内唯一。
+#[derive(Default)]
+pub struct ThumbnailCache {
+    textures: HashMap<String, (AssetId, Arc<Texture>)>,
+    next_id: AssetId,
+}
+
+impl ThumbnailCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 查找缓存的缩略图
+    pub fn get(&self, key: &str) -> Option<AssetHandle<Texture>> {
+        self.textures
+            .get(key)
+            .map(|(id, texture)| AssetHandle::new(*id, texture, format!("thumbnail://{}", key)))
+    }
+
+    /// 返回缓存的缩略图，不存在时调用render生成并缓存
+    pub fn get_or_render(
+        &mut self,
+        key: String,
+        render: impl FnOnce() -> EngineResult<Texture>,
+    ) -> EngineResult<AssetHandle<Texture>> {
+        if let Some(handle) = self.get(&key) {
+            return Ok(handle);
+        }
+
+        self.next_id += 1;
+        let texture = Arc::new(render()?);
+        let handle = AssetHandle::new(self.next_id, &texture, format!("thumbnail://{}", key));
+        self.textures.insert(key, (self.next_id, texture));
+        Ok(handle)
+    }
+
+    /// 使缓存项失效(内容变化后调用)，已发出的句柄仍持有旧纹理直到释放
+    pub fn invalidate(&mut self, key: &str) -> bool {
+        self.textures.remove(key).is_some()
+    }
+
+    /// 使键以prefix开头的所有缓存项失效，例如某个场景所有尺寸的缩略图
+    pub fn invalidate_prefix(&mut self, prefix: &str) -> usize {
+        let before = self.textures.len();
+        self.textures.retain(|key, _| !key.starts_with(prefix));
+        before - self.textures.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty()
+    }
+}
+
+/// 自带设备的缩略图渲染器，用于没有窗口的场合(编辑器项目面板、资源导入)
+pub struct HeadlessThumbnailRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    renderer: ThumbnailRenderer,
+    cache: ThumbnailCache,
+}
+
+impl HeadlessThumbnailRenderer {
+    /// 创建无窗口的设备，没有硬件适配器时尝试软件适配器
+    pub fn new() -> EngineResult<Self> {
+        pollster::block_on(Self::new_async())
+    }
+
+    async fn new_async() -> EngineResult<Self> {
+        let (_, device, queue) =
+            request_headless_device("缩略图设备", wgpu::Features::empty(), wgpu::Limits::downlevel_defaults()).await?;
+
+        let renderer = ThumbnailRenderer::new(&device);
+        Ok(Self {
+            device,
+            queue,
+            renderer,
+            cache: ThumbnailCache::new(),
+        })
+    }
+
+    /// 渲染(或从缓存取得)缩略图
+    pub fn render_thumbnail(&mut self, source: ThumbnailSource, size: u32) -> EngineResult<AssetHandle<Texture>> {
+        let (device, queue, renderer) = (&self.device, &self.queue, &self.renderer);
+        self.cache
+            .get_or_render(source.cache_key(size), || renderer.render(device, queue, &source, size))
+    }
+
+    /// 用给定相机渲染一帧(不缓存)，见 `ThumbnailRenderer::render_view`
+    pub fn render_view(&self, source: ThumbnailSource, width: u32, height: u32, view_projection: Mat4) -> EngineResult<Texture> {
+        self.renderer.render_view(&self.device, &self.queue, &source, width, height, view_projection)
+    }
+
+    pub fn renderer_mut(&mut self) -> &mut ThumbnailRenderer {
+        &mut self.renderer
+    }
+
+    pub fn cache_mut(&mut self) -> &mut ThumbnailCache {
+        &mut self.cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key_includes_source_and_size() {
+        let mesh = Mesh::cube();
+        assert_eq!(ThumbnailSource::Prefab(PrefabType::Cube).cache_key(64), "prefab:Cube@64");
+        assert_eq!(ThumbnailSource::Mesh(&mesh).cache_key(128), format!("mesh:{}@128", mesh.name));
+    }
+
+    #[test]
+    fn cache_renders_once_and_invalidates_by_prefix() {
+        let mut cache = ThumbnailCache::new();
+        let first = cache
+            .get_or_render("scene:a@64".to_string(), || Ok(Texture::solid_color(64, 64, [255; 4])))
+            .unwrap();
+        let again = cache
+            .get_or_render("scene:a@64".to_string(), || panic!("缓存命中时不应重新渲染"))
+            .unwrap();
+        assert_eq!(first.id(), again.id());
+
+        cache.get_or_render("scene:a@128".to_string(), || Ok(Texture::solid_color(128, 128, [0; 4]))).unwrap();
+        cache.get_or_render("scene:b@64".to_string(), || Ok(Texture::solid_color(64, 64, [0; 4]))).unwrap();
+        assert_eq!(cache.len(), 3);
+
+        assert_eq!(cache.invalidate_prefix("scene:a@"), 2);
+        assert!(cache.get("scene:a@64").is_none());
+        assert!(cache.get("scene:b@64").is_some());
+        // 失效后已发出的句柄仍持有旧纹理
+        assert_eq!(first.get().unwrap().descriptor.width, 64);
+    }
+
+    #[test]
+    fn framing_keeps_content_inside_view() {
+        let vertices: Vec<_> = Mesh::cube()
+            .vertices
+            .iter()
+            .map(|vertex| ThumbnailVertex {
+                position: (vertex.position * 3.0 + Vec3::new(10.0, -2.0, 5.0)).to_array(),
+                normal: vertex.normal.to_array(),
+                color: vertex.color.to_array(),
+            })
+            .collect();
+
+        let view_projection = ThumbnailRenderer::framing(&vertices);
+        for vertex in &vertices {
+            let clip = view_projection * Vec3::from_array(vertex.position).extend(1.0);
+            let ndc = clip.truncate() / clip.w;
+            assert!(clip.w > 0.0);
+            assert!(ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0, "{:?}", ndc);
+            assert!((0.0..=1.0).contains(&ndc.z), "{:?}", ndc);
+        }
+    }
+
+    #[test]
+    fn single_cube_scene_thumbnail_has_requested_size() {
+        let mut renderer = match HeadlessThumbnailRenderer::new() {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                // 没有任何适配器(包括软件适配器)的环境无法渲染
+                eprintln!("跳过缩略图测试: {}", e);
+                return;
+            }
+        };
+
+        let mut world = ECSWorld::new().unwrap();
+        let mut scene = Scene::new("cube_scene");
+        scene.spawn_prefab(&mut world, PrefabType::Cube, "cube", Vec3::ZERO);
+
+        let handle = renderer
+            .render_thumbnail(ThumbnailSource::Scene { scene: &scene, world: &world }, 32)
+            .unwrap();
+        let texture = handle.get().unwrap();
+        assert_eq!((texture.descriptor.width, texture.descriptor.height), (32, 32));
+        assert_eq!(texture.data.len(), 32 * 32 * 4);
+
+        // 中心像素是立方体，角落是背景
+        let pixel = |x: usize, y: usize| &texture.data[(y * 32 + x) * 4..(y * 32 + x) * 4 + 4];
+        assert_ne!(pixel(16, 16), pixel(0, 0));
+        assert_eq!(renderer.cache_mut().len(), 1);
+    }
+
+    #[test]
+    fn scene_view_renders_world_at_viewport_size() {
+        let renderer = match HeadlessThumbnailRenderer::new() {
+            Ok(renderer) => renderer,
+            Err(e) => {
+                eprintln!("跳过场景视口测试: {}", e);
+                return;
+            }
+        };
+
+        let mut world = ECSWorld::new().unwrap();
+        let mut scene = Scene::new("view_scene");
+        scene.spawn_prefab(&mut world, PrefabType::Cube, "cube", Vec3::ZERO);
+        assert_eq!(ThumbnailSource::World(&world).cache_key(64), "world@64");
+
+        // 非正方形且每行字节数不是256的倍数，验证回读时去掉了行填充
+        let (width, height) = (48, 24);
+        let mut camera = crate::render::Camera::perspective(60.0, width as f32 / height as f32, 0.1, 100.0);
+        camera.position = Vec3::new(0.0, 0.0, 5.0);
+        camera.look_at(Vec3::ZERO, Vec3::Y);
+
+        let texture = renderer
+            .render_view(ThumbnailSource::World(&world), width, height, camera.view_projection_matrix())
+            .unwrap();
+        assert_eq!((texture.descriptor.width, texture.descriptor.height), (width, height));
+        assert_eq!(texture.data.len(), (width * height * 4) as usize);
+        assert_eq!(texture.name, "view@48x24");
+
+        let pixel = |x: u32, y: u32| {
+            let offset = ((y * width + x) * 4) as usize;
+            &texture.data[offset..offset + 4]
+        };
+        assert_ne!(pixel(width / 2, height / 2), pixel(0, 0));
+        assert_eq!(pixel(0, 0), pixel(width - 1, height - 1));
+
+        assert!(renderer.render_view(ThumbnailSource::World(&world), 0, 24, Mat4::IDENTITY).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! 瓦片地图渲染
+//!
+//! 瓦片地图按 `TILEMAP_CHUNK_SIZE` 划分为区块，每个区块预先生成精灵实例并缓存，瓦片或变换变化时才重建。
+//! 每帧剔除不在相机视野内的区块，其余区块按图层顺序输出为 `SpriteFrame`，由 `SpriteRenderer2D` 绘制，
+//! 每个区块对应一次绘制调用。
+
+use crate::ecs::{Tilemap, Transform};
+use crate::math::{Coordinate, Vec2, Vec4};
+use crate::render::{SpriteBatch, SpriteCamera, SpriteFrame, SpriteInstance};
+use specs::{Entity, Join, World, WorldExt};
+use std::collections::{HashMap, HashSet};
+
+/// 区块边长(瓦片数)
+pub const TILEMAP_CHUNK_SIZE: u32 = 16;
+
+/// 瓦片地图区块
+#[derive(Debug, Clone)]
+pub struct TilemapChunk {
+    /// 区块在网格中的坐标(以区块为单位)
+    pub coord: (u32, u32),
+    /// 世界空间包围矩形(最小点, 最大点)
+    pub bounds_min: Vec2,
+    pub bounds_max: Vec2,
+    pub instances: Vec<SpriteInstance>,
+}
+
+impl TilemapChunk {
+    /// 与矩形是否相交
+    pub fn intersects(&self, min: Vec2, max: Vec2) -> bool {
+        self.bounds_min.x <= max.x
+            && self.bounds_max.x >= min.x
+            && self.bounds_min.y <= max.y
+            && self.bounds_max.y >= min.y
+    }
+}
+
+/// 瓦片地图网格到世界空间的映射
+#[derive(Debug, Clone, Copy)]
+struct TileGrid {
+    origin: Vec2,
+    depth: f32,
+    axis_x: Vec2,
+    axis_y: Vec2,
+}
+
+impl TileGrid {
+    fn new(tilemap: &Tilemap, transform: &Transform) -> Self {
+        let scale = transform.scale.truncate() * tilemap.tile_size;
+        let right = (transform.rotation * Coordinate::RIGHT).truncate().normalize_or_zero();
+        let up = (transform.rotation * Coordinate::UP).truncate().normalize_or_zero();
+        Self {
+            origin: transform.position.truncate(),
+            depth: transform.position.z,
+            axis_x: right * scale.x,
+            axis_y: up * scale.y,
+        }
+    }
+
+    fn point(&self, x: f32, y: f32) -> Vec2 {
+        self.origin + self.axis_x * x + self.axis_y * y
+    }
+}
+
+/// 瓦片地图网格构建
+pub struct TilemapMesher;
+
+impl TilemapMesher {
+    /// 将整个瓦片地图划分为区块，空区块不输出
+    pub fn build_chunks(tilemap: &Tilemap, transform: &Transform) -> Vec<TilemapChunk> {
+        let grid = TileGrid::new(tilemap, transform);
+        let chunks_x = tilemap.width().div_ceil(TILEMAP_CHUNK_SIZE);
+        let chunks_y = tilemap.height().div_ceil(TILEMAP_CHUNK_SIZE);
+
+        let mut chunks = Vec::new();
+        for chunk_y in 0..chunks_y {
+            for chunk_x in 0..chunks_x {
+                let chunk = Self::build_chunk(tilemap, &grid, chunk_x, chunk_y);
+                if !chunk.instances.is_empty() {
+                    chunks.push(chunk);
+                }
+            }
+        }
+        chunks
+    }
+
+    fn build_chunk(tilemap: &Tilemap, grid: &TileGrid, chunk_x: u32, chunk_y: u32) -> TilemapChunk {
+        let start_x = chunk_x * TILEMAP_CHUNK_SIZE;
+        let start_y = chunk_y * TILEMAP_CHUNK_SIZE;
+        let end_x = (start_x + TILEMAP_CHUNK_SIZE).min(tilemap.width());
+        let end_y = (start_y + TILEMAP_CHUNK_SIZE).min(tilemap.height());
+
+        let mut instances = Vec::new();
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let Some(tile) = tilemap.tile(x, y) else {
+                    continue;
+                };
+                // 索引超出图集的瓦片不绘制
+                let Some((uv_min, uv_max)) = tilemap.tile_uv_rect(tile) else {
+                    continue;
+                };
+                instances.push(SpriteInstance {
+                    origin: grid.point(x as f32, y as f32).extend(grid.depth).to_array(),
+                    _padding: 0.0,
+                    axis_x: grid.axis_x.to_array(),
+                    axis_y: grid.axis_y.to_array(),
+                    uv_min: uv_min.to_array(),
+                    uv_max: uv_max.to_array(),
+                    color: tilemap.color.to_array(),
+                });
+            }
+        }
+
+        let corners = [
+            grid.point(start_x as f32, start_y as f32),
+            grid.point(end_x as f32, start_y as f32),
+            grid.point(start_x as f32, end_y as f32),
+            grid.point(end_x as f32, end_y as f32),
+        ];
+        let bounds_min = corners.iter().copied().fold(Vec2::splat(f32::MAX), Vec2::min);
+        let bounds_max = corners.iter().copied().fold(Vec2::splat(f32::MIN), Vec2::max);
+
+        TilemapChunk {
+            coord: (chunk_x, chunk_y),
+            bounds_min,
+            bounds_max,
+            instances,
+        }
+    }
+}
+
+/// 缓存的瓦片地图区块
+struct CachedTilemap {
+    revision: u64,
+    transform: Transform,
+    tileset: String,
+    tileset_grid: (u32, u32),
+    tile_size: Vec2,
+    color: Vec4,
+    sorting_order: i32,
+    chunks: Vec<TilemapChunk>,
+}
+
+/// 瓦片地图渲染准备：缓存区块并按视野剔除
+#[derive(Default)]
+pub struct TilemapRenderer {
+    cache: HashMap<Entity, CachedTilemap>,
+    culled_chunks: usize,
+}
+
+impl TilemapRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 收集可见区块，按(图层, id)排序输出，每个区块一个批次
+    pub fn prepare(&mut self, world: &World, camera: &SpriteCamera) -> SpriteFrame {
+        let entities = world.entities();
+        let transforms = world.read_storage::<Transform>();
+        let tilemaps = world.read_storage::<Tilemap>();
+
+        let mut alive = HashSet::new();
+        for (entity, tilemap, transform) in (&entities, &tilemaps, &transforms).join() {
+            if !tilemap.visible {
+                continue;
+            }
+            alive.insert(entity);
+            self.update_cache(entity, tilemap, transform);
+        }
+        self.cache.retain(|id, _| alive.contains(id));
+
+        let mut layers: Vec<(&Entity, &CachedTilemap)> = self.cache.iter().collect();
+        layers.sort_by_key(|(id, cached)| (cached.sorting_order, id.id()));
+
+        let (view_min, view_max) = camera.view_rect();
+        let mut frame = SpriteFrame::default();
+        let mut culled = 0;
+        for (_, cached) in layers {
+            for chunk in &cached.chunks {
+                if !chunk.intersects(view_min, view_max) {
+                    culled += 1;
+                    continue;
+                }
+                frame.batches.push(SpriteBatch {
+                    texture_name: cached.tileset.clone(),
+                    first_instance: frame.instances.len() as u32,
+                    instance_count: chunk.instances.len() as u32,
+                });
+                frame.instances.extend_from_slice(&chunk.instances);
+            }
+        }
+        self.culled_chunks = culled;
+        frame
+    }
+
+    /// 上一次准备时被剔除的区块数
+    pub fn culled_chunks(&self) -> usize {
+        self.culled_chunks
+    }
+
+    /// 清空区块缓存
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    fn update_cache(&mut self, id: Entity, tilemap: &Tilemap, transform: &Transform) {
+        let up_to_date = self.cache.get(&id).map_or(false, |cached| {
+            cached.revision == tilemap.revision()
+                && cached.tileset == tilemap.tileset
+                && cached.tileset_grid == (tilemap.tileset_columns, tilemap.tileset_rows)
+                && cached.tile_size == tilemap.tile_size
+                && cached.color == tilemap.color
+                && cached.transform.position == transform.position
+                && cached.transform.rotation == transform.rotation
+                && cached.transform.scale == transform.scale
+        });
+
+        if up_to_date {
+            // 图层顺序不影响区块内容
+            if let Some(cached) = self.cache.get_mut(&id) {
+                cached.sorting_order = tilemap.sorting_order;
+            }
+            return;
+        }
+
+        self.cache.insert(id, CachedTilemap {
+            revision: tilemap.revision(),
+            transform: transform.clone(),
+            tileset: tilemap.tileset.clone(),
+            tileset_grid: (tilemap.tileset_columns, tilemap.tileset_rows),
+            tile_size: tilemap.tile_size,
+            color: tilemap.color,
+            sorting_order: tilemap.sorting_order,
+            chunks: TilemapMesher::build_chunks(tilemap, transform),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::{ECSWorld, Tile};
+    use crate::math::Vec3;
+    use specs::Builder;
+
+    fn transform_at(position: Vec3) -> Transform {
+        let mut transform = Transform::new();
+        transform.set_position(position);
+        transform
+    }
+
+    #[test]
+    fn three_by_three_tilemap_produces_quads_with_atlas_uvs() {
+        // 4×4图集，-1为空格
+        let mut tilemap = Tilemap::new("tiles", 4, 4, 3, 3).with_indices(&[0, 1, 2, 5, 6, 7, -1, 15, 3]);
+        tilemap.set_tile(2, 2, Some(Tile::new(3).with_flip(true, false)));
+
+        let chunks = TilemapMesher::build_chunks(&tilemap, &transform_at(Vec3::new(1.0, 2.0, 0.5)));
+        assert_eq!(chunks.len(), 1);
+        let instances = &chunks[0].instances;
+        assert_eq!(instances.len(), 8);
+
+        // 按行输出，第二行第一个瓦片为索引5
+        assert_eq!(instances[3].origin, [1.0, 3.0, 0.5]);
+        assert_eq!((instances[3].uv_min, instances[3].uv_max), ([0.25, 0.25], [0.5, 0.5]));
+        // 索引15位于图集右下角
+        assert_eq!((instances[6].uv_min, instances[6].uv_max), ([0.75, 0.75], [1.0, 1.0]));
+        // 翻转的瓦片交换U
+        assert_eq!((instances[7].uv_min, instances[7].uv_max), ([1.0, 0.0], [0.75, 0.25]));
+        assert!(instances.iter().all(|instance| instance.axis_x == [1.0, 0.0] && instance.axis_y == [0.0, 1.0]));
+
+        assert_eq!((chunks[0].bounds_min, chunks[0].bounds_max), (Vec2::new(1.0, 2.0), Vec2::new(4.0, 5.0)));
+    }
+
+    #[test]
+    fn off_screen_chunks_are_culled() {
+        let width = TILEMAP_CHUNK_SIZE * 3;
+        let indices = vec![0; (width * 2) as usize];
+        let mut world = ECSWorld::new().unwrap();
+        world
+            .create_entity()
+            .with(transform_at(Vec3::ZERO))
+            .with(Tilemap::new("tiles", 2, 2, width, 2).with_indices(&indices))
+            .build();
+
+        let mut renderer = TilemapRenderer::new();
+        let camera = SpriteCamera { position: Vec2::new(4.0, 1.0), height: 4.0, aspect_ratio: 2.0 };
+        let frame = renderer.prepare(world.world(), &camera);
+        assert_eq!(frame.draw_calls(), 1);
+        assert_eq!(frame.instances.len(), (TILEMAP_CHUNK_SIZE * 2) as usize);
+        assert_eq!(renderer.culled_chunks(), 2);
+
+        // 视野横跨两个区块
+        let camera = SpriteCamera { position: Vec2::new(TILEMAP_CHUNK_SIZE as f32, 1.0), ..camera };
+        assert_eq!(renderer.prepare(world.world(), &camera).draw_calls(), 2);
+        assert_eq!(renderer.culled_chunks(), 1);
+    }
+
+    #[test]
+    fn layers_follow_sorting_order_and_rebuild_on_edit() {
+        let mut world = ECSWorld::new().unwrap();
+        let foreground = world
+            .create_entity()
+            .with(transform_at(Vec3::ZERO))
+            .with(Tilemap::new("foreground", 1, 1, 1, 1).with_indices(&[0]).with_sorting_order(2))
+            .build();
+        world
+            .create_entity()
+            .with(transform_at(Vec3::ZERO))
+            .with(Tilemap::new("background", 1, 1, 1, 1).with_indices(&[0]).with_sorting_order(-1))
+            .build();
+
+        let mut renderer = TilemapRenderer::new();
+        let camera = SpriteCamera::default();
+        let frame = renderer.prepare(world.world(), &camera);
+        let order: Vec<_> = frame.batches.iter().map(|batch| batch.texture_name.as_str()).collect();
+        assert_eq!(order, vec!["background", "foreground"]);
+
+        // 清空前景瓦片后区块重建为空
+        world.world().write_storage::<Tilemap>().get_mut(foreground).unwrap().set_tile(0, 0, None);
+        let frame = renderer.prepare(world.world(), &camera);
+        let order: Vec<_> = frame.batches.iter().map(|batch| batch.texture_name.as_str()).collect();
+        assert_eq!(order, vec!["background"]);
+    }
+}
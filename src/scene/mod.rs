@@ -3,7 +3,9 @@
 pub mod scene;
 pub mod scene_manager;
 pub mod scene_graph;
+pub mod picking;
 
 pub use scene::*;
 pub use scene_manager::*;
 pub use scene_graph::*;
+pub use picking::*;
@@ -0,0 +1,197 @@
+//! 场景拾取
+//!
+//! 由实体的世界包围盒构建BVH，射线先经BVH筛选候选实体，再与实际网格三角形精确求交，返回最近的实体。
+
+use crate::ecs::{MeshRenderer, Transform};
+use crate::math::{Bvh, Ray, Vec3, AABB};
+use crate::render::Mesh;
+use glam::Mat4;
+use specs::{Entity, Join, World, WorldExt};
+use std::sync::Arc;
+
+/// 拾取结果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickHit {
+    pub entity: Entity,
+    /// 世界空间击中点
+    pub point: Vec3,
+    /// 射线起点到击中点的距离
+    pub distance: f32,
+}
+
+/// 可拾取对象
+#[derive(Debug, Clone)]
+struct PickTarget {
+    entity: Entity,
+    world_matrix: Mat4,
+    inverse_matrix: Mat4,
+    /// 没有网格时以单位立方体包围盒作为拾取形状
+    mesh: Option<Arc<Mesh>>,
+    positions: Vec<Vec3>,
+}
+
+/// 场景拾取器
+#[derive(Debug, Clone, Default)]
+pub struct ScenePicker {
+    targets: Vec<PickTarget>,
+    bounds: Vec<AABB>,
+    bvh: Bvh,
+}
+
+impl ScenePicker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 收集带有Transform与可见MeshRenderer的实体，`mesh_lookup` 按网格名称返回几何体
+    pub fn from_world(world: &World, mesh_lookup: impl Fn(&str) -> Option<Arc<Mesh>>) -> Self {
+        let mut picker = Self::new();
+        let entities = world.entities();
+        let transforms = world.read_storage::<Transform>();
+        let mesh_renderers = world.read_storage::<MeshRenderer>();
+
+        for (entity, transform, mesh_renderer) in (&entities, &transforms, &mesh_renderers).join() {
+            if !mesh_renderer.visible {
+                continue;
+            }
+            let world_matrix = Mat4::from_scale_rotation_translation(transform.scale, transform.rotation, transform.position);
+            picker.add(entity, world_matrix, mesh_lookup(&mesh_renderer.mesh_name));
+        }
+
+        picker.build();
+        picker
+    }
+
+    /// 添加可拾取对象，添加完成后需调用 `build`
+    pub fn add(&mut self, entity: Entity, world_matrix: Mat4, mesh: Option<Arc<Mesh>>) {
+        let positions: Vec<Vec3> = match &mesh {
+            Some(mesh) => mesh.vertices.iter().map(|v| v.position).collect(),
+            None => Vec::new(),
+        };
+        let local_bounds = AABB::from_points(&positions)
+            .unwrap_or_else(|| AABB::from_center_size(Vec3::ZERO, Vec3::ONE));
+
+        self.bounds.push(local_bounds.transform(&world_matrix));
+        self.targets.push(PickTarget {
+            entity,
+            world_matrix,
+            inverse_matrix: world_matrix.inverse(),
+            mesh,
+            positions,
+        });
+    }
+
+    /// 重建BVH
+    pub fn build(&mut self) {
+        self.bvh = Bvh::build(&self.bounds);
+    }
+
+    /// 清空所有对象
+    pub fn clear(&mut self) {
+        self.targets.clear();
+        self.bounds.clear();
+        self.bvh = Bvh::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// 射线拾取最近的实体
+    pub fn pick(&self, ray: &Ray) -> Option<PickHit> {
+        let mut closest: Option<PickHit> = None;
+
+        for (index, entry_distance) in self.bvh.ray_candidates(ray, f32::MAX) {
+            // 候选按包围盒进入距离排序，之后的候选不可能更近
+            if closest.map_or(false, |hit| entry_distance > hit.distance) {
+                break;
+            }
+            if let Some(hit) = self.intersect_target(&self.targets[index], ray) {
+                if closest.map_or(true, |c| hit.distance < c.distance) {
+                    closest = Some(hit);
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// 在对象局部空间精确求交，距离换算回世界空间
+    fn intersect_target(&self, target: &PickTarget, ray: &Ray) -> Option<PickHit> {
+        let local_ray = ray.transform(&target.inverse_matrix);
+        let local_hit = match &target.mesh {
+            Some(mesh) => local_ray.intersect_mesh(&target.positions, &mesh.indices),
+            None => local_ray.intersect_aabb(&AABB::from_center_size(Vec3::ZERO, Vec3::ONE)),
+        }?;
+
+        let point = target.world_matrix.transform_point3(local_hit.point);
+        Some(PickHit {
+            entity: target.entity,
+            point,
+            distance: (point - ray.origin).length(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::Builder;
+
+    fn entities(count: usize) -> (World, Vec<Entity>) {
+        let mut world = World::new();
+        let entities = (0..count).map(|_| world.create_entity().build()).collect();
+        (world, entities)
+    }
+
+    #[test]
+    fn overlapping_entities_pick_the_nearer_one() {
+        let (_world, entities) = entities(2);
+        let cube = Arc::new(Mesh::cube());
+
+        let mut picker = ScenePicker::new();
+        // 远的先添加，两者在视线方向上重叠
+        picker.add(entities[0], Mat4::from_translation(Vec3::new(0.0, 0.0, -5.0)), Some(cube.clone()));
+        picker.add(entities[1], Mat4::from_translation(Vec3::new(0.2, 0.0, -2.0)), Some(cube));
+        picker.build();
+
+        let hit = picker.pick(&Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z)).unwrap();
+        assert_eq!(hit.entity, entities[1]);
+        assert!((hit.distance - 6.5).abs() < 1e-4);
+        assert!((hit.point.z + 1.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn pick_uses_mesh_geometry_not_bounds() {
+        use crate::render::MeshVertex;
+
+        let (_world, entities) = entities(2);
+        // 直角三角形，包围盒的右上半部分没有几何体
+        let vertex = |x: f32, y: f32| MeshVertex { position: Vec3::new(x, y, 0.0), ..Default::default() };
+        let triangle = Mesh::from_geometry("triangle", vec![vertex(-1.0, -1.0), vertex(1.0, -1.0), vertex(-1.0, 1.0)], vec![0, 1, 2]);
+
+        let mut picker = ScenePicker::new();
+        picker.add(entities[0], Mat4::IDENTITY, Some(Arc::new(triangle)));
+        // 后方没有网格的实体以缩放后的单位立方体拾取
+        picker.add(entities[1], Mat4::from_scale_rotation_translation(Vec3::splat(4.0), glam::Quat::IDENTITY, Vec3::new(0.0, 0.0, -4.0)), None);
+        picker.build();
+        assert_eq!(picker.len(), 2);
+
+        let on_triangle = picker.pick(&Ray::new(Vec3::new(-0.5, -0.5, 5.0), Vec3::NEG_Z)).unwrap();
+        assert_eq!(on_triangle.entity, entities[0]);
+        assert!((on_triangle.distance - 5.0).abs() < 1e-4);
+
+        // 穿过三角形的包围盒但不穿过三角形，击中后方的实体
+        let past_triangle = picker.pick(&Ray::new(Vec3::new(0.8, 0.8, 5.0), Vec3::NEG_Z)).unwrap();
+        assert_eq!(past_triangle.entity, entities[1]);
+        assert!((past_triangle.distance - 7.0).abs() < 1e-4);
+
+        assert!(picker.pick(&Ray::new(Vec3::new(3.0, 0.0, 5.0), Vec3::NEG_Z)).is_none());
+        picker.clear();
+        assert!(picker.is_empty());
+    }
+}
@@ -114,6 +114,7 @@ impl Scene {
         
         // 添加到场景图根节点
         self.scene_graph.add_entity(entity, None);
+        self.scene_graph.set_name(entity, name.clone());
         
         // 添加到实体映射
         self.entity_map.insert(name, entity);
@@ -128,6 +129,7 @@ impl Scene {
         
         // 添加到场景图
         self.scene_graph.add_entity(entity, Some(parent))?;
+        self.scene_graph.set_name(entity, name.clone());
         
         // 添加到实体映射
         self.entity_map.insert(name, entity);
@@ -140,6 +142,16 @@ impl Scene {
         self.entity_map.get(name).copied()
     }
 
+    /// 通过层次路径查找实体，如 "Player/Weapon/Muzzle"
+    pub fn find_by_path(&self, path: &str) -> Option<Entity> {
+        self.scene_graph.find_by_path(path)
+    }
+
+    /// 实体的层次路径
+    pub fn path_of(&self, entity: Entity) -> String {
+        self.scene_graph.path_of(entity)
+    }
+
     /// 移除实体
     pub fn remove_entity(&mut self, world: &mut ECSWorld, entity: Entity) -> EngineResult<()> {
         // 从场景图中移除
@@ -357,3 +369,21 @@ impl SceneBuilder {
         self.scene
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn created_entities_are_reachable_by_path() {
+        let mut world = ECSWorld::new().unwrap();
+        let mut scene = Scene::new("level");
+        let player = scene.create_entity(&mut world, "Player");
+        let weapon = scene.create_child_entity(&mut world, "Weapon", player).unwrap();
+        let muzzle = scene.create_child_entity(&mut world, "Muzzle", weapon).unwrap();
+
+        assert_eq!(scene.find_by_path("Player/Weapon/Muzzle"), Some(muzzle));
+        assert_eq!(scene.path_of(muzzle), "Player/Weapon/Muzzle");
+        assert_eq!(scene.find_by_path("Player/Muzzle"), None);
+    }
+}
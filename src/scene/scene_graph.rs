@@ -1,7 +1,8 @@
 //! 场景图系统
 
 use crate::{EngineResult, EngineError};
-use specs::Entity;
+use crate::ecs::Name;
+use specs::{Entity, Join, World, WorldExt};
 use std::collections::{HashMap, HashSet};
 
 /// 场景图节点
@@ -390,6 +391,66 @@ impl SceneGraph {
         path.last().copied()
     }
 
+    /// 路径分隔符
+    pub const PATH_SEPARATOR: char = '/';
+
+    /// 设置节点名称，用于按路径查找
+    pub fn set_name(&mut self, entity: Entity, name: impl Into<String>) {
+        if let Some(node) = self.nodes.get_mut(&entity) {
+            node.name = Some(name.into());
+        }
+    }
+
+    /// 从 `Name` 组件同步节点名称
+    pub fn sync_names(&mut self, world: &World) {
+        let entities = world.entities();
+        let names = world.read_storage::<Name>();
+        for (entity, name) in (&entities, &names).join() {
+            if let Some(node) = self.nodes.get_mut(&entity) {
+                node.name = Some(name.name.clone());
+            }
+        }
+    }
+
+    /// 按名称路径查找实体，如 "Player/Weapon/Muzzle"
+    ///
+    /// 第一段匹配根节点，之后逐级匹配子节点，同名时取先添加的节点。
+    /// 未命名的节点在路径中写作 `#<实体ID>`。
+    pub fn find_by_path(&self, path: &str) -> Option<Entity> {
+        let mut segments = path.split(Self::PATH_SEPARATOR).filter(|segment| !segment.is_empty());
+        let first = segments.next()?;
+        let mut current = self.find_child_by_segment(&self.root_entities, first)?;
+
+        for segment in segments {
+            let node = self.nodes.get(&current)?;
+            current = self.find_child_by_segment(&node.children, segment)?;
+        }
+        Some(current)
+    }
+
+    /// 实体的名称路径，`find_by_path` 的逆运算；实体不在场景图中时返回空字符串
+    pub fn path_of(&self, entity: Entity) -> String {
+        if !self.nodes.contains_key(&entity) {
+            return String::new();
+        }
+        self.get_entity_path(entity)
+            .into_iter()
+            .map(|entity| self.path_segment(entity))
+            .collect::<Vec<_>>()
+            .join(&Self::PATH_SEPARATOR.to_string())
+    }
+
+    fn path_segment(&self, entity: Entity) -> String {
+        match self.nodes.get(&entity).and_then(|node| node.name.as_ref()) {
+            Some(name) => name.clone(),
+            None => format!("#{}", entity.id()),
+        }
+    }
+
+    fn find_child_by_segment(&self, candidates: &[Entity], segment: &str) -> Option<Entity> {
+        candidates.iter().copied().find(|&entity| self.path_segment(entity) == segment)
+    }
+
     /// 获取场景图统计信息
     pub fn stats(&self) -> SceneGraphStats {
         let mut max_depth = 0;
@@ -453,3 +514,69 @@ pub struct SceneGraphStats {
     pub max_depth: usize,
     pub average_depth: f32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::Builder;
+
+    /// Player/Weapon/Muzzle 三级层次，以及同级的Player/Shield
+    fn hierarchy(world: &mut World) -> (SceneGraph, [Entity; 4]) {
+        let entities = [(); 4].map(|_| world.create_entity().build());
+        let [player, weapon, muzzle, shield] = entities;
+        let mut graph = SceneGraph::new();
+        graph.add_entity(player, None).unwrap();
+        graph.add_entity(weapon, Some(player)).unwrap();
+        graph.add_entity(muzzle, Some(weapon)).unwrap();
+        graph.add_entity(shield, Some(player)).unwrap();
+        for (entity, name) in entities.iter().zip(["Player", "Weapon", "Muzzle", "Shield"]) {
+            graph.set_name(*entity, name);
+        }
+        (graph, entities)
+    }
+
+    #[test]
+    fn find_by_path_resolves_leaf() {
+        let mut world = World::new();
+        let (graph, [player, weapon, muzzle, _]) = hierarchy(&mut world);
+
+        assert_eq!(graph.find_by_path("Player/Weapon/Muzzle"), Some(muzzle));
+        assert_eq!(graph.find_by_path("Player/Weapon"), Some(weapon));
+        assert_eq!(graph.find_by_path("/Player/"), Some(player));
+
+        assert_eq!(graph.find_by_path("Player/Shield/Muzzle"), None);
+        assert_eq!(graph.find_by_path("Weapon/Muzzle"), None);
+        assert_eq!(graph.find_by_path("Player/weapon"), None);
+        assert_eq!(graph.find_by_path(""), None);
+    }
+
+    #[test]
+    fn path_of_is_inverse_of_find() {
+        let mut world = World::new();
+        let (graph, entities) = hierarchy(&mut world);
+        for entity in entities {
+            assert_eq!(graph.find_by_path(&graph.path_of(entity)), Some(entity));
+        }
+        assert_eq!(graph.path_of(entities[2]), "Player/Weapon/Muzzle");
+
+        let outsider = world.create_entity().build();
+        assert_eq!(graph.path_of(outsider), "");
+    }
+
+    #[test]
+    fn unnamed_nodes_use_entity_id_and_names_sync_from_world() {
+        let mut world = World::new();
+        world.register::<Name>();
+        let root = world.create_entity().with(Name::new("Level")).build();
+        let unnamed = world.create_entity().build();
+        let mut graph = SceneGraph::new();
+        graph.add_entity(root, None).unwrap();
+        graph.add_entity(unnamed, Some(root)).unwrap();
+
+        assert_eq!(graph.path_of(root), format!("#{}", root.id()));
+        graph.sync_names(&world);
+        let path = graph.path_of(unnamed);
+        assert_eq!(path, format!("Level/#{}", unnamed.id()));
+        assert_eq!(graph.find_by_path(&path), Some(unnamed));
+    }
+}
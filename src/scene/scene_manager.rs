@@ -3,7 +3,7 @@
 use crate::{EngineResult, EngineError};
 use crate::scene::Scene;
 use crate::ecs::ECSWorld;
-use crate::events::{EventSystem, SceneLoadedEvent, SceneUnloadedEvent};
+use crate::events::{Event, EventSender, EventSystem, SceneLoadedEvent, SceneUnloadedEvent};
 
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
@@ -21,6 +21,8 @@ pub struct SceneManager {
     scene_transition: bool,
     /// 事件系统引用
     event_system: Option<Arc<RwLock<EventSystem>>>,
+    /// 事件发送器(优先于事件系统引用使用)
+    event_sender: Option<EventSender>,
     /// 场景根目录
     scene_root: std::path::PathBuf,
 }
@@ -34,6 +36,7 @@ impl SceneManager {
             next_scene: None,
             scene_transition: false,
             event_system: None,
+            event_sender: None,
             scene_root: std::path::PathBuf::from("scenes"),
         }
     }
@@ -43,6 +46,11 @@ impl SceneManager {
         self.event_system = Some(event_system);
     }
 
+    /// 设置事件发送器，场景生命周期事件会自动通过它发布
+    pub fn set_event_sender(&mut self, sender: EventSender) {
+        self.event_sender = Some(sender);
+    }
+
     /// 设置场景根目录
     pub fn set_scene_root(&mut self, path: impl Into<std::path::PathBuf>) {
         self.scene_root = path.into();
@@ -229,6 +237,7 @@ impl SceneManager {
         self.scenes.insert(scene_name.clone(), scene);
         
         log::info!("从文件加载场景: {:?}", full_path);
+        self.emit_scene_loaded(&scene_name);
         Ok(scene_name)
     }
 
@@ -288,8 +297,10 @@ impl SceneManager {
             scene.clear(world)?;
         }
         
+        if let Some(current_name) = self.current_scene.take() {
+            self.emit_scene_unloaded(&current_name);
+        }
         self.scenes.clear();
-        self.current_scene = None;
         self.next_scene = None;
         self.scene_transition = false;
         
@@ -341,22 +352,25 @@ impl SceneManager {
 
     /// 发送场景加载事件
     fn emit_scene_loaded(&self, scene_name: &str) {
-        if let Some(event_system) = &self.event_system {
-            if let Ok(mut events) = event_system.write() {
-                events.publish(SceneLoadedEvent {
-                    scene_name: scene_name.to_string(),
-                });
-            }
-        }
+        self.emit(SceneLoadedEvent {
+            scene_name: scene_name.to_string(),
+        });
     }
 
     /// 发送场景卸载事件
     fn emit_scene_unloaded(&self, scene_name: &str) {
-        if let Some(event_system) = &self.event_system {
+        self.emit(SceneUnloadedEvent {
+            scene_name: scene_name.to_string(),
+        });
+    }
+
+    /// 通过事件发送器或事件系统发布事件
+    fn emit<T: Event + 'static>(&self, event: T) {
+        if let Some(sender) = &self.event_sender {
+            sender.send(event);
+        } else if let Some(event_system) = &self.event_system {
             if let Ok(mut events) = event_system.write() {
-                events.publish(SceneUnloadedEvent {
-                    scene_name: scene_name.to_string(),
-                });
+                events.publish(event);
             }
         }
     }
@@ -386,3 +400,57 @@ pub struct SceneManagerStats {
     pub is_transitioning: bool,
     pub scene_names: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn record_scene_events(events: &mut EventSystem) -> Arc<Mutex<Vec<String>>> {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let loaded = Arc::clone(&log);
+        events.subscribe::<SceneLoadedEvent, _>(move |e| {
+            loaded.lock().unwrap().push(format!("loaded:{}", e.scene_name));
+        });
+        let unloaded = Arc::clone(&log);
+        events.subscribe::<SceneUnloadedEvent, _>(move |e| {
+            unloaded.lock().unwrap().push(format!("unloaded:{}", e.scene_name));
+        });
+        log
+    }
+
+    #[test]
+    fn switching_scenes_emits_unload_then_load() {
+        let mut events = EventSystem::new();
+        let log = record_scene_events(&mut events);
+        let mut world = ECSWorld::new().unwrap();
+        let mut manager = SceneManager::new();
+        manager.set_event_sender(events.sender());
+        manager.create_scene("a");
+        manager.create_scene("b");
+
+        manager.switch_to_scene_immediately("a", &mut world).unwrap();
+        manager.switch_to_scene_immediately("b", &mut world).unwrap();
+        events.process_events();
+        assert_eq!(*log.lock().unwrap(), vec!["loaded:a", "unloaded:a", "loaded:b"]);
+
+        // 清空场景时卸载当前场景
+        manager.clear_all_scenes(&mut world).unwrap();
+        events.process_events();
+        assert_eq!(log.lock().unwrap().last().map(String::as_str), Some("unloaded:b"));
+    }
+
+    #[test]
+    fn switching_to_unknown_scene_fails_without_events() {
+        let mut events = EventSystem::new();
+        let log = record_scene_events(&mut events);
+        let mut world = ECSWorld::new().unwrap();
+        let mut manager = SceneManager::new();
+        manager.set_event_sender(events.sender());
+
+        assert!(manager.switch_to_scene("missing").is_err());
+        assert!(manager.switch_to_scene_immediately("missing", &mut world).is_err());
+        events.process_events();
+        assert!(log.lock().unwrap().is_empty());
+    }
+}
@@ -565,7 +565,7 @@ impl Serializable for SerializedAssetBundle {
                 }
             }
             SerializationFormat::Binary => {
-                Ok(bincode::serialize(self)?)
+                Ok(context.binary_encoding.serialize(self)?)
             }
             SerializationFormat::MessagePack => {
                 Ok(rmp_serde::to_vec(self)?)
@@ -583,7 +583,7 @@ impl Serializable for SerializedAssetBundle {
                 Ok(serde_json::from_slice(data)?)
             }
             SerializationFormat::Binary => {
-                Ok(bincode::deserialize(data)?)
+                Ok(context.binary_encoding.deserialize(data)?)
             }
             SerializationFormat::MessagePack => {
                 Ok(rmp_serde::from_slice(data)?)
@@ -1,24 +1,104 @@
 //! 二进制序列化器
+//!
+//! 二进制存档使用固定的字节序与整数编码(而不是依赖平台或库的默认值)，保证在不同字节序的机器间可移植。
+//! 使用的编码记录在序列化元数据中。
 
 use super::{Serializer, SerializationContext};
 use serde::{Deserialize, Serialize};
 use bincode::Options;
 
-/// 二进制序列化器
-pub struct BinarySerializer {
-    use_compact_format: bool,
+/// 字节序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BinaryEndianness {
+    Little,
+    Big,
 }
 
-impl BinarySerializer {
-    pub fn new() -> Self {
-        Self {
-            use_compact_format: true,
+/// 整数编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BinaryIntEncoding {
+    /// 按类型宽度定长编码
+    Fixed,
+    /// 变长编码，体积更小
+    Varint,
+}
+
+/// 二进制编码方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BinaryEncoding {
+    pub endianness: BinaryEndianness,
+    pub int_encoding: BinaryIntEncoding,
+}
+
+impl BinaryEncoding {
+    /// 标准存档编码：小端序、定长整数
+    pub const CANONICAL: Self = Self {
+        endianness: BinaryEndianness::Little,
+        int_encoding: BinaryIntEncoding::Fixed,
+    };
+
+    /// 紧凑编码：小端序、变长整数
+    pub const COMPACT: Self = Self {
+        endianness: BinaryEndianness::Little,
+        int_encoding: BinaryIntEncoding::Varint,
+    };
+
+    /// 元数据中使用的标识，例如 "le-fixint"
+    pub fn tag(&self) -> &'static str {
+        match (self.endianness, self.int_encoding) {
+            (BinaryEndianness::Little, BinaryIntEncoding::Fixed) => "le-fixint",
+            (BinaryEndianness::Little, BinaryIntEncoding::Varint) => "le-varint",
+            (BinaryEndianness::Big, BinaryIntEncoding::Fixed) => "be-fixint",
+            (BinaryEndianness::Big, BinaryIntEncoding::Varint) => "be-varint",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let (endianness, int_encoding) = match tag {
+            "le-fixint" => (BinaryEndianness::Little, BinaryIntEncoding::Fixed),
+            "le-varint" => (BinaryEndianness::Little, BinaryIntEncoding::Varint),
+            "be-fixint" => (BinaryEndianness::Big, BinaryIntEncoding::Fixed),
+            "be-varint" => (BinaryEndianness::Big, BinaryIntEncoding::Varint),
+            _ => return None,
+        };
+        Some(Self { endianness, int_encoding })
+    }
+
+    /// 按此编码序列化
+    pub fn serialize<T: Serialize + ?Sized>(&self, data: &T) -> Result<Vec<u8>, bincode::Error> {
+        let options = bincode::DefaultOptions::new().allow_trailing_bytes();
+        match (self.endianness, self.int_encoding) {
+            (BinaryEndianness::Little, BinaryIntEncoding::Fixed) => options.with_little_endian().with_fixint_encoding().serialize(data),
+            (BinaryEndianness::Little, BinaryIntEncoding::Varint) => options.with_little_endian().with_varint_encoding().serialize(data),
+            (BinaryEndianness::Big, BinaryIntEncoding::Fixed) => options.with_big_endian().with_fixint_encoding().serialize(data),
+            (BinaryEndianness::Big, BinaryIntEncoding::Varint) => options.with_big_endian().with_varint_encoding().serialize(data),
         }
     }
 
-    pub fn with_compact_format(mut self, compact: bool) -> Self {
-        self.use_compact_format = compact;
-        self
+    /// 按此编码反序列化
+    pub fn deserialize<T: for<'de> Deserialize<'de>>(&self, data: &[u8]) -> Result<T, bincode::Error> {
+        let options = bincode::DefaultOptions::new().allow_trailing_bytes();
+        match (self.endianness, self.int_encoding) {
+            (BinaryEndianness::Little, BinaryIntEncoding::Fixed) => options.with_little_endian().with_fixint_encoding().deserialize(data),
+            (BinaryEndianness::Little, BinaryIntEncoding::Varint) => options.with_little_endian().with_varint_encoding().deserialize(data),
+            (BinaryEndianness::Big, BinaryIntEncoding::Fixed) => options.with_big_endian().with_fixint_encoding().deserialize(data),
+            (BinaryEndianness::Big, BinaryIntEncoding::Varint) => options.with_big_endian().with_varint_encoding().deserialize(data),
+        }
+    }
+}
+
+impl Default for BinaryEncoding {
+    fn default() -> Self {
+        Self::CANONICAL
+    }
+}
+
+/// 二进制序列化器，编码方式取自 `SerializationContext::binary_encoding`
+pub struct BinarySerializer;
+
+impl BinarySerializer {
+    pub fn new() -> Self {
+        Self
     }
 }
 
@@ -31,32 +111,12 @@ impl Default for BinarySerializer {
 impl Serializer for BinarySerializer {
     type Error = bincode::Error;
 
-    fn serialize<T: Serialize>(&self, data: &T, _context: &SerializationContext) -> Result<Vec<u8>, Self::Error> {
-        // 使用bincode进行二进制序列化
-        let result = if self.use_compact_format {
-            bincode::serialize(data)?
-        } else {
-            // 使用标准配置
-            let config = bincode::DefaultOptions::new()
-                .with_big_endian()
-                .with_fixint_encoding();
-            config.serialize(data)?
-        };
-        
-        Ok(result)
+    fn serialize<T: Serialize>(&self, data: &T, context: &SerializationContext) -> Result<Vec<u8>, Self::Error> {
+        context.binary_encoding.serialize(data)
     }
 
-    fn deserialize<T: for<'de> Deserialize<'de>>(&self, data: &[u8], _context: &SerializationContext) -> Result<T, Self::Error> {
-        let result = if self.use_compact_format {
-            bincode::deserialize(data)?
-        } else {
-            let config = bincode::DefaultOptions::new()
-                .with_big_endian()
-                .with_fixint_encoding();
-            config.deserialize(data)?
-        };
-        
-        Ok(result)
+    fn deserialize<T: for<'de> Deserialize<'de>>(&self, data: &[u8], context: &SerializationContext) -> Result<T, Self::Error> {
+        context.binary_encoding.deserialize(data)
     }
 }
 
@@ -79,33 +139,25 @@ pub mod binary_utils {
 
     /// 序列化为二进制数据
     pub fn to_binary<T: Serialize>(data: &T) -> EngineResult<Vec<u8>> {
-        let bytes = bincode::serialize(data)?;
+        let bytes = BinaryEncoding::CANONICAL.serialize(data)?;
         Ok(bytes)
     }
 
     /// 从二进制数据反序列化
     pub fn from_binary<T: for<'de> Deserialize<'de>>(data: &[u8]) -> EngineResult<T> {
-        let result = bincode::deserialize(data)?;
+        let result = BinaryEncoding::CANONICAL.deserialize(data)?;
         Ok(result)
     }
 
     /// 序列化为紧凑二进制格式
     pub fn to_compact_binary<T: Serialize>(data: &T) -> EngineResult<Vec<u8>> {
-        let config = bincode::DefaultOptions::new()
-            .with_little_endian()
-            .with_varint_encoding()
-            .with_limit(u64::MAX);
-        let bytes = config.serialize(data)?;
+        let bytes = BinaryEncoding::COMPACT.serialize(data)?;
         Ok(bytes)
     }
 
     /// 从紧凑二进制格式反序列化
     pub fn from_compact_binary<T: for<'de> Deserialize<'de>>(data: &[u8]) -> EngineResult<T> {
-        let config = bincode::DefaultOptions::new()
-            .with_little_endian()
-            .with_varint_encoding()
-            .with_limit(u64::MAX);
-        let result = config.deserialize(data)?;
+        let result = BinaryEncoding::COMPACT.deserialize(data)?;
         Ok(result)
     }
 
@@ -393,3 +445,77 @@ pub mod binary_utils {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::{LittleEndian, ReadBytesExt};
+    use std::io::Cursor;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Header {
+        kind: u16,
+        count: u32,
+        scale: f32,
+        offset: u64,
+    }
+
+    fn sample() -> Header {
+        Header {
+            kind: 0x0102,
+            count: 0x0304_0506,
+            scale: 1.5,
+            offset: 0x0708_090A_0B0C_0D0E,
+        }
+    }
+
+    #[test]
+    fn canonical_layout_is_fixed_little_endian() {
+        let bytes = BinaryEncoding::CANONICAL.serialize(&sample()).unwrap();
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&0x0102u16.to_le_bytes());
+        expected.extend_from_slice(&0x0304_0506u32.to_le_bytes());
+        expected.extend_from_slice(&1.5f32.to_le_bytes());
+        expected.extend_from_slice(&0x0708_090A_0B0C_0D0Eu64.to_le_bytes());
+        assert_eq!(bytes, expected);
+
+        // 与主机字节序无关，按小端序逐字段读回
+        let mut cursor = Cursor::new(bytes);
+        assert_eq!(cursor.read_u16::<LittleEndian>().unwrap(), 0x0102);
+        assert_eq!(cursor.read_u32::<LittleEndian>().unwrap(), 0x0304_0506);
+        assert_eq!(cursor.read_f32::<LittleEndian>().unwrap(), 1.5);
+        assert_eq!(cursor.read_u64::<LittleEndian>().unwrap(), 0x0708_090A_0B0C_0D0E);
+    }
+
+    #[test]
+    fn big_endian_encoding_reverses_field_bytes() {
+        let encoding = BinaryEncoding {
+            endianness: BinaryEndianness::Big,
+            int_encoding: BinaryIntEncoding::Fixed,
+        };
+        let bytes = encoding.serialize(&sample()).unwrap();
+        assert_eq!(&bytes[..2], &0x0102u16.to_be_bytes());
+        assert_eq!(encoding.deserialize::<Header>(&bytes).unwrap(), sample());
+    }
+
+    #[test]
+    fn compact_encoding_round_trips_and_is_smaller() {
+        let data = vec![1u64, 2, 3, 4];
+        let compact = BinaryEncoding::COMPACT.serialize(&data).unwrap();
+        let canonical = BinaryEncoding::CANONICAL.serialize(&data).unwrap();
+        assert!(compact.len() < canonical.len());
+        assert_eq!(BinaryEncoding::COMPACT.deserialize::<Vec<u64>>(&compact).unwrap(), data);
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        for endianness in [BinaryEndianness::Little, BinaryEndianness::Big] {
+            for int_encoding in [BinaryIntEncoding::Fixed, BinaryIntEncoding::Varint] {
+                let encoding = BinaryEncoding { endianness, int_encoding };
+                assert_eq!(BinaryEncoding::from_tag(encoding.tag()), Some(encoding));
+            }
+        }
+        assert_eq!(BinaryEncoding::from_tag("native"), None);
+    }
+}
@@ -11,10 +11,15 @@ use specs::{WorldExt, Builder};
 /// 变换组件
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransformComponent {
+    #[serde(with = "crate::math::serde_helpers::vec3")]
     pub position: Vec3,
+    #[serde(with = "crate::math::serde_helpers::quat")]
     pub rotation: Quat,
+    #[serde(with = "crate::math::serde_helpers::vec3")]
     pub scale: Vec3,
+    #[serde(with = "crate::math::serde_helpers::mat4")]
     pub local_matrix: Mat4,
+    #[serde(with = "crate::math::serde_helpers::mat4")]
     pub world_matrix: Mat4,
 }
 
@@ -70,6 +75,9 @@ pub struct LightComponent {
     pub intensity: f32,
     pub range: f32,
     pub spot_angle: f32,
+    /// 内锥半角(度)，旧数据缺省为36度
+    #[serde(default = "LightComponent::default_spot_inner_angle")]
+    pub spot_inner_angle: f32,
     pub cast_shadows: bool,
 }
 
@@ -84,6 +92,12 @@ impl Component for LightComponent {
     type Storage = VecStorage<Self>;
 }
 
+impl LightComponent {
+    fn default_spot_inner_angle() -> f32 {
+        36.0
+    }
+}
+
 impl Default for LightComponent {
     fn default() -> Self {
         Self {
@@ -92,6 +106,7 @@ impl Default for LightComponent {
             intensity: 1.0,
             range: 10.0,
             spot_angle: 45.0,
+            spot_inner_angle: Self::default_spot_inner_angle(),
             cast_shadows: true,
         }
     }
@@ -185,8 +200,12 @@ impl Default for PhysicsComponent {
 pub struct ColliderComponent {
     pub shape: ColliderShape,
     pub is_trigger: bool,
-    pub layer: u32,
-    pub mask: u32,
+    /// 物理层(0~31)
+    #[serde(alias = "layer")]
+    pub collision_layer: u32,
+    /// 与哪些层碰撞(位掩码)
+    #[serde(alias = "mask")]
+    pub collision_mask: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -207,8 +226,8 @@ impl Default for ColliderComponent {
         Self {
             shape: ColliderShape::Box { size: [1.0, 1.0, 1.0] },
             is_trigger: false,
-            layer: 0,
-            mask: u32::MAX,
+            collision_layer: 0,
+            collision_mask: u32::MAX,
         }
     }
 }
@@ -391,15 +410,44 @@ impl Default for NameComponent {
     }
 }
 
-/// 组件注册器
+/// 遇到未注册组件类型时的处理方式
+///
+/// 场景可能引用当前构建中不存在的组件(例如功能被移除或未启用)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnknownComponentPolicy {
+    /// 跳过该组件并记录警告，其余组件照常加载
+    #[default]
+    Skip,
+    /// 加载失败，错误中列出所有缺失的类型
+    Fail,
+}
+
+/// 组件反序列化结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentLoadReport {
+    /// 成功加载的组件类型
+    pub loaded: Vec<String>,
+    /// 未注册而被跳过的组件类型
+    pub unknown: Vec<String>,
+}
+
+impl ComponentLoadReport {
+    pub fn has_unknown(&self) -> bool {
+        !self.unknown.is_empty()
+    }
+}
+
+/// 组件注册器，以稳定的类型名(如 "TransformComponent")为键
 pub struct ComponentRegistry {
     serializers: HashMap<String, Box<dyn ComponentSerializerTrait>>,
+    unknown_policy: UnknownComponentPolicy,
 }
 
 impl ComponentRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             serializers: HashMap::new(),
+            unknown_policy: UnknownComponentPolicy::default(),
         };
 
         // 注册默认组件序列化器
@@ -407,11 +455,26 @@ impl ComponentRegistry {
         registry
     }
 
+    /// 设置未注册组件的处理方式
+    pub fn with_unknown_policy(mut self, policy: UnknownComponentPolicy) -> Self {
+        self.unknown_policy = policy;
+        self
+    }
+
+    pub fn set_unknown_policy(&mut self, policy: UnknownComponentPolicy) {
+        self.unknown_policy = policy;
+    }
+
+    pub fn unknown_policy(&self) -> UnknownComponentPolicy {
+        self.unknown_policy
+    }
+
     /// 注册组件序列化器
-    pub fn register_component<T: Component + Serialize + for<'de> Deserialize<'de> + 'static + Send + Sync>(
-        &mut self,
-        name: String,
-    ) {
+    pub fn register_component<T>(&mut self, name: String)
+    where
+        T: Component + Serialize + for<'de> Deserialize<'de> + 'static + Send + Sync,
+        T::Storage: Default,
+    {
         let serializer = Box::new(GenericComponentSerializer::<T>::new());
         self.serializers.insert(name, serializer);
     }
@@ -430,11 +493,39 @@ impl ComponentRegistry {
         self.serializers.get(name).map(|s| s.as_ref())
     }
 
+    /// 组件类型是否已注册
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.serializers.contains_key(name)
+    }
+
     /// 获取所有注册的组件类型
     pub fn get_registered_types(&self) -> Vec<&String> {
         self.serializers.keys().collect()
     }
 
+    /// 找出未注册的组件类型(排序去重)
+    pub fn unknown_types<'a>(&self, component_types: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+        let mut unknown: Vec<String> = component_types
+            .into_iter()
+            .filter(|component_type| !self.is_registered(component_type))
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown.dedup();
+        unknown
+    }
+
+    /// Fail策略下存在未注册类型时返回列出全部缺失类型的错误
+    pub fn check_unknown_types<'a>(&self, component_types: impl IntoIterator<Item = &'a String>) -> EngineResult<()> {
+        if self.unknown_policy == UnknownComponentPolicy::Fail {
+            let unknown = self.unknown_types(component_types);
+            if !unknown.is_empty() {
+                return Err(anyhow::anyhow!("数据引用了未注册的组件类型: {}", unknown.join(", ")));
+            }
+        }
+        Ok(())
+    }
+
     /// 注册默认组件
     fn register_default_components(&mut self) {
         self.register_component::<TransformComponent>("TransformComponent".to_string());
@@ -465,21 +556,36 @@ impl ComponentRegistry {
     }
 
     /// 反序列化实体的所有组件
+    ///
+    /// 未注册的组件按 `unknown_policy` 处理：Skip时跳过并记入报告，Fail时不加载任何组件并返回错误。
     pub fn deserialize_entity_components(
         &self,
         entity: Entity,
         components_data: &HashMap<String, serde_json::Value>,
         world: &mut World,
-    ) -> EngineResult<()> {
-        for (component_type, component_data) in components_data {
-            if let Some(serializer) = self.get_serializer(component_type) {
-                serializer.deserialize_component(entity, component_data, world)?;
-            } else {
-                log::warn!("Unknown component type: {}", component_type);
+    ) -> EngineResult<ComponentLoadReport> {
+        self.check_unknown_types(components_data.keys())?;
+
+        let mut component_types: Vec<&String> = components_data.keys().collect();
+        component_types.sort();
+
+        let mut report = ComponentLoadReport::default();
+        for component_type in component_types {
+            match self.get_serializer(component_type) {
+                Some(serializer) => {
+                    serializer
+                        .deserialize_component(entity, &components_data[component_type], world)
+                        .map_err(|e| anyhow::anyhow!("反序列化组件 {} 失败: {}", component_type, e))?;
+                    report.loaded.push(component_type.clone());
+                }
+                None => {
+                    log::warn!("跳过未注册的组件类型: {}", component_type);
+                    report.unknown.push(component_type.clone());
+                }
             }
         }
 
-        Ok(())
+        Ok(report)
     }
 }
 
@@ -579,9 +685,66 @@ pub mod component_utils {
                 .map(|(k, v)| (k.clone(), v.clone()))
                 .collect();
             
-            // registry.deserialize_entity_components(entity, &components_map, world)?; // TODO: Fix entity type
+            registry.deserialize_entity_components(entity, &components_map, world)?;
         }
 
         Ok(entity)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn components_with_unknown() -> HashMap<String, serde_json::Value> {
+        HashMap::from([
+            ("TagComponent".to_string(), json!({ "tags": ["enemy"] })),
+            ("LaserComponent".to_string(), json!({ "power": 3 })),
+        ])
+    }
+
+    #[test]
+    fn unknown_components_are_skipped_and_reported() {
+        let registry = ComponentRegistry::new();
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+
+        let report = registry.deserialize_entity_components(entity, &components_with_unknown(), &mut world).unwrap();
+        assert_eq!(report.loaded, vec!["TagComponent"]);
+        assert_eq!(report.unknown, vec!["LaserComponent"]);
+        assert!(report.has_unknown());
+
+        let tags = world.read_storage::<TagComponent>();
+        assert_eq!(tags.get(entity).unwrap().tags, vec!["enemy"]);
+    }
+
+    #[test]
+    fn fail_policy_lists_missing_types_and_loads_nothing() {
+        let registry = ComponentRegistry::new().with_unknown_policy(UnknownComponentPolicy::Fail);
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+        let mut components = components_with_unknown();
+        components.insert("JetpackComponent".to_string(), json!({}));
+
+        let error = registry.deserialize_entity_components(entity, &components, &mut world).unwrap_err();
+        assert!(error.to_string().contains("JetpackComponent, LaserComponent"));
+        assert!(!world.has_value::<specs::storage::MaskedStorage<TagComponent>>()
+            || world.read_storage::<TagComponent>().get(entity).is_none());
+    }
+
+    #[test]
+    fn registered_components_round_trip() {
+        let registry = ComponentRegistry::new();
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+        registry
+            .deserialize_entity_components(entity, &HashMap::from([("NameComponent".to_string(), json!({ "name": "玩家" }))]), &mut world)
+            .unwrap();
+
+        let components = registry.serialize_entity_components(entity, &world).unwrap();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components["NameComponent"]["name"], "玩家");
+        assert_eq!(registry.unknown_types(components_with_unknown().keys()), vec!["LaserComponent"]);
+    }
+}
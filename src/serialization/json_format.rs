@@ -1,4 +1,7 @@
 //! JSON序列化器
+//!
+//! 浮点数按能精确还原的最短十进制形式输出(ryu)，解析启用serde_json的 `float_roundtrip`，
+//! 因此 `f32`/`f64` 经JSON保存再加载后逐位相等，不会累积精度误差。
 
 use super::{Serializer, SerializationContext};
 use serde::{Deserialize, Serialize};
@@ -312,3 +315,82 @@ pub mod json_utils {
         pub nulls: usize,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TrickyFloats {
+        tenth: f32,
+        tiny: f64,
+        huge: f64,
+        f32_max: f32,
+        f32_min_positive: f32,
+        subnormal: f64,
+        negative_zero: f32,
+        third: f64,
+        values: Vec<f32>,
+    }
+
+    fn tricky() -> TrickyFloats {
+        TrickyFloats {
+            tenth: 0.1,
+            tiny: 1e-20,
+            huge: 1.7976931348623157e308,
+            f32_max: f32::MAX,
+            f32_min_positive: f32::MIN_POSITIVE,
+            subnormal: 5e-324,
+            negative_zero: -0.0,
+            third: 1.0 / 3.0,
+            values: vec![0.3, 16777217.0, 1.0e-7, 123456.79, -2.5e38],
+        }
+    }
+
+    fn bits(data: &TrickyFloats) -> Vec<u64> {
+        let mut bits = vec![
+            data.tenth.to_bits() as u64,
+            data.tiny.to_bits(),
+            data.huge.to_bits(),
+            data.f32_max.to_bits() as u64,
+            data.f32_min_positive.to_bits() as u64,
+            data.subnormal.to_bits(),
+            data.negative_zero.to_bits() as u64,
+            data.third.to_bits(),
+        ];
+        bits.extend(data.values.iter().map(|value| value.to_bits() as u64));
+        bits
+    }
+
+    #[test]
+    fn floats_round_trip_bit_for_bit() {
+        let original = tricky();
+        for pretty_print in [true, false] {
+            let context = SerializationContext {
+                pretty_print,
+                ..Default::default()
+            };
+            let serializer = JsonSerializer::new();
+            let bytes = serializer.serialize(&original, &context).unwrap();
+            let loaded: TrickyFloats = serializer.deserialize(&bytes, &context).unwrap();
+            assert_eq!(bits(&loaded), bits(&original));
+        }
+    }
+
+    #[test]
+    fn repeated_save_load_does_not_drift() {
+        let mut data = tricky();
+        let first = json_utils::to_compact_json(&data).unwrap();
+        for _ in 0..5 {
+            data = json_utils::from_json_str(&json_utils::to_compact_json(&data).unwrap()).unwrap();
+        }
+        assert_eq!(json_utils::to_compact_json(&data).unwrap(), first);
+        assert_eq!(bits(&data), bits(&tricky()));
+    }
+
+    #[test]
+    fn floats_use_shortest_representation() {
+        let json = json_utils::to_compact_json(&(0.1f32, 0.1f64, 1e-20f64)).unwrap();
+        assert_eq!(json, "[0.1,0.1,1e-20]");
+    }
+}
@@ -5,6 +5,7 @@ pub mod asset_serializer;
 pub mod component_serializer;
 pub mod binary_format;
 pub mod json_format;
+pub mod msgpack_format;
 
 /// 序列化器通用trait
 pub trait Serializer {
@@ -22,6 +23,7 @@ pub use asset_serializer::*;
 pub use component_serializer::*;
 pub use binary_format::*;
 pub use json_format::*;
+pub use msgpack_format::*;
 
 use crate::EngineResult;
 use serde::{Deserialize, Serialize};
@@ -69,6 +71,8 @@ pub struct SerializationContext {
     pub compress: bool,
     pub version: u32,
     pub custom_data: HashMap<String, String>,
+    /// 二进制格式的字节序与整数编码
+    pub binary_encoding: BinaryEncoding,
 }
 
 impl Default for SerializationContext {
@@ -80,6 +84,7 @@ impl Default for SerializationContext {
             compress: false,
             version: 1,
             custom_data: HashMap::new(),
+            binary_encoding: BinaryEncoding::CANONICAL,
         }
     }
 }
@@ -124,7 +129,15 @@ pub struct SerializationMetadata {
 }
 
 impl SerializationMetadata {
+    /// 记录二进制编码的custom_data键
+    pub const BINARY_ENCODING_KEY: &'static str = "binary_encoding";
+
     pub fn new(context: &SerializationContext) -> Self {
+        let mut custom_data = context.custom_data.clone();
+        if context.format == SerializationFormat::Binary {
+            custom_data.insert(Self::BINARY_ENCODING_KEY.to_string(), context.binary_encoding.tag().to_string());
+        }
+
         Self {
             version: context.version,
             timestamp: chrono::Utc::now().timestamp(),
@@ -132,9 +145,16 @@ impl SerializationMetadata {
             format: format!("{:?}", context.format),
             compressed: context.compress,
             checksum: String::new(), // 在实际序列化时计算
-            custom_data: context.custom_data.clone(),
+            custom_data,
         }
     }
+
+    /// 数据使用的二进制编码(旧数据未记录时为None)
+    pub fn binary_encoding(&self) -> Option<BinaryEncoding> {
+        self.custom_data
+            .get(Self::BINARY_ENCODING_KEY)
+            .and_then(|tag| BinaryEncoding::from_tag(tag))
+    }
 }
 
 /// 序列化包装器
@@ -160,6 +180,7 @@ impl<T> SerializedData<T> {
 pub enum SerializerInstance {
     Json(JsonSerializer),
     Binary(BinarySerializer),
+    MessagePack(MessagePackSerializer),
 }
 
 impl SerializerInstance {
@@ -167,6 +188,7 @@ impl SerializerInstance {
         match self {
             SerializerInstance::Json(s) => s.serialize(data, context).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
             SerializerInstance::Binary(s) => s.serialize(data, context).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
+            SerializerInstance::MessagePack(s) => s.serialize(data, context).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
         }
     }
     
@@ -174,6 +196,7 @@ impl SerializerInstance {
         match self {
             SerializerInstance::Json(s) => s.deserialize(data, context).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
             SerializerInstance::Binary(s) => s.deserialize(data, context).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
+            SerializerInstance::MessagePack(s) => s.deserialize(data, context).map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
         }
     }
 }
@@ -194,6 +217,7 @@ impl SerializationManager {
         // 注册默认序列化器
         manager.register_serializer(SerializationFormat::Json, SerializerInstance::Json(JsonSerializer::new()));
         manager.register_serializer(SerializationFormat::Binary, SerializerInstance::Binary(BinarySerializer::new()));
+        manager.register_serializer(SerializationFormat::MessagePack, SerializerInstance::MessagePack(MessagePackSerializer::new()));
         
         manager
     }
@@ -318,6 +342,17 @@ impl SerializationManager {
             ));
         }
 
+        // 二进制编码检查
+        if let Some(encoding) = metadata.binary_encoding() {
+            if encoding != context.binary_encoding {
+                return Err(anyhow::anyhow!(
+                    "Binary encoding mismatch: expected {}, got {}",
+                    context.binary_encoding.tag(),
+                    encoding.tag()
+                ));
+            }
+        }
+
         // 压缩设置检查
         if metadata.compressed != context.compress {
             log::warn!(
@@ -459,3 +494,62 @@ pub mod utils {
         calculate_checksum(data) == expected
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary_context() -> SerializationContext {
+        SerializationContext {
+            format: SerializationFormat::Binary,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn binary_metadata_records_encoding() {
+        let metadata = SerializationMetadata::new(&binary_context());
+        assert_eq!(metadata.binary_encoding(), Some(BinaryEncoding::CANONICAL));
+
+        // 非二进制格式不记录
+        assert_eq!(SerializationMetadata::new(&SerializationContext::default()).binary_encoding(), None);
+    }
+
+    #[test]
+    fn binary_round_trip_through_manager() {
+        let manager = SerializationManager::new();
+        let context = binary_context();
+        let data = (42u32, String::from("存档"), vec![1.0f32, 2.0]);
+
+        let bytes = manager.serialize(&data, Some(&context)).unwrap();
+        let restored: (u32, String, Vec<f32>) = manager.deserialize(&bytes, Some(&context)).unwrap();
+        assert_eq!(restored, data);
+    }
+
+    #[test]
+    fn message_pack_round_trip_through_manager() {
+        let manager = SerializationManager::new();
+        let context = SerializationContext {
+            format: SerializationFormat::MessagePack,
+            ..Default::default()
+        };
+        let data = (42u32, String::from("存档"), vec![1.0f32, 2.0]);
+
+        let bytes = manager.serialize(&data, Some(&context)).unwrap();
+        let restored: (u32, String, Vec<f32>) = manager.deserialize(&bytes, Some(&context)).unwrap();
+        assert_eq!(restored, data);
+    }
+
+    #[test]
+    fn mismatched_binary_encoding_is_rejected() {
+        let manager = SerializationManager::new();
+        let metadata = SerializationMetadata::new(&binary_context());
+        let compact = SerializationContext {
+            binary_encoding: BinaryEncoding::COMPACT,
+            ..binary_context()
+        };
+
+        assert!(manager.validate_metadata(&metadata, &binary_context()).is_ok());
+        assert!(manager.validate_metadata(&metadata, &compact).is_err());
+    }
+}
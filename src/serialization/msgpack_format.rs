@@ -0,0 +1,43 @@
+//! MessagePack序列化器
+//!
+//! 结构体按字段名编码(`to_vec_named`)，新增带默认值的字段后旧数据仍可读取。
+
+use super::{Serializer, SerializationContext};
+use serde::{Deserialize, Serialize};
+
+/// MessagePack编解码错误
+#[derive(thiserror::Error, Debug)]
+pub enum MessagePackError {
+    #[error("MessagePack编码错误: {0}")]
+    Encode(#[from] rmp_serde::encode::Error),
+
+    #[error("MessagePack解码错误: {0}")]
+    Decode(#[from] rmp_serde::decode::Error),
+}
+
+/// MessagePack序列化器
+pub struct MessagePackSerializer;
+
+impl MessagePackSerializer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for MessagePackSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer for MessagePackSerializer {
+    type Error = MessagePackError;
+
+    fn serialize<T: Serialize>(&self, data: &T, _context: &SerializationContext) -> Result<Vec<u8>, Self::Error> {
+        Ok(rmp_serde::to_vec_named(data)?)
+    }
+
+    fn deserialize<T: for<'de> Deserialize<'de>>(&self, data: &[u8], _context: &SerializationContext) -> Result<T, Self::Error> {
+        Ok(rmp_serde::from_slice(data)?)
+    }
+}
@@ -1,13 +1,13 @@
 //! 场景序列化器
 
-use super::{Serializable, SerializationContext, SerializationFormat};
+use super::{ComponentRegistry, Serializable, SerializationContext, SerializationFormat, UnknownComponentPolicy};
 use crate::ecs::{World, Entity, Component};
 use specs::{WorldExt, Builder};
 use crate::scene::{Scene, SceneNode, SceneManager};
 use crate::math::{Vec3, Quat};
 use crate::EngineResult;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 /// 场景序列化数据
@@ -110,16 +110,37 @@ impl SerializedBounds {
     }
 }
 
+/// 场景加载报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SceneLoadReport {
+    /// 成功加载的组件数
+    pub loaded_components: usize,
+    /// 被跳过的未注册组件类型 -> 引用它的实体ID
+    pub unknown_components: BTreeMap<String, Vec<u64>>,
+}
+
+impl SceneLoadReport {
+    /// 所有组件都已加载
+    pub fn is_complete(&self) -> bool {
+        self.unknown_components.is_empty()
+    }
+
+    /// 缺失的组件类型
+    pub fn missing_types(&self) -> Vec<&str> {
+        self.unknown_components.keys().map(String::as_str).collect()
+    }
+}
+
 /// 场景序列化器
 pub struct SceneSerializer {
-    component_serializers: HashMap<String, Box<dyn ComponentSerializerTrait>>,
+    components: ComponentRegistry,
     resource_manager: Option<crate::assets::AssetManager>,
 }
 
 impl SceneSerializer {
     pub fn new() -> Self {
         Self {
-            component_serializers: HashMap::new(),
+            components: ComponentRegistry::new(),
             resource_manager: None,
         }
     }
@@ -130,7 +151,21 @@ impl SceneSerializer {
         name: String,
         serializer: Box<dyn ComponentSerializerTrait>,
     ) {
-        self.component_serializers.insert(name, serializer);
+        self.components.register_custom_serializer(name, serializer);
+    }
+
+    /// 组件注册表
+    pub fn component_registry(&self) -> &ComponentRegistry {
+        &self.components
+    }
+
+    pub fn component_registry_mut(&mut self) -> &mut ComponentRegistry {
+        &mut self.components
+    }
+
+    /// 设置场景引用未注册组件时的处理方式
+    pub fn set_unknown_component_policy(&mut self, policy: UnknownComponentPolicy) {
+        self.components.set_unknown_policy(policy);
     }
 
     /// 设置资源管理器
@@ -151,10 +186,9 @@ impl SceneSerializer {
             dependencies: Vec::new(),
         };
 
-        // 序列化实体
+        // 序列化实体，按实体id排序保证输出稳定
         let mut entities = Vec::new();
-        use specs::Join;
-        for entity in world.entities().join() {
+        for entity in crate::ecs::sorted_entities(world) {
             let serialized_entity = self.serialize_entity(entity, world)?;
             entities.push(serialized_entity);
         }
@@ -177,6 +211,20 @@ impl SceneSerializer {
 
     /// 反序列化场景
     pub fn deserialize_scene(&self, data: &SerializedScene, world: &mut World, scene_manager: &mut SceneManager) -> EngineResult<Scene> {
+        self.deserialize_scene_with_report(data, world, scene_manager).map(|(scene, _)| scene)
+    }
+
+    /// 反序列化场景并报告被跳过的未注册组件
+    pub fn deserialize_scene_with_report(
+        &self,
+        data: &SerializedScene,
+        world: &mut World,
+        scene_manager: &mut SceneManager,
+    ) -> EngineResult<(Scene, SceneLoadReport)> {
+        // Fail策略下先检查全部实体，避免加载到一半才失败
+        self.components
+            .check_unknown_types(data.entities.iter().flat_map(|entity| entity.components.keys()))?;
+
         // 创建场景
         let mut scene = Scene::new(data.metadata.name.clone());
         // scene.description = data.metadata.description.clone(); // Field not available
@@ -184,6 +232,7 @@ impl SceneSerializer {
 
         // 创建实体映射（旧ID -> 新ID）
         let mut entity_mapping = HashMap::new();
+        let mut report = SceneLoadReport::default();
 
         // 第一阶段：创建所有实体
         for serialized_entity in &data.entities {
@@ -196,13 +245,14 @@ impl SceneSerializer {
             let entity = entity_mapping[&serialized_entity.id];
             
             // 反序列化组件
-            for (component_type, component_data) in &serialized_entity.components {
-                if let Some(serializer) = self.component_serializers.get(component_type) {
-                    // serializer.deserialize_component(entity, component_data, world)?; // TODO: Fix entity type
-                }
+            let components = self.components
+                .deserialize_entity_components(entity, &serialized_entity.components, world)?;
+            report.loaded_components += components.loaded.len();
+            for component_type in components.unknown {
+                report.unknown_components.entry(component_type).or_default().push(serialized_entity.id);
             }
 
-            // 设置父子关系
+        // 设置父子关系
             if let Some(parent_id) = serialized_entity.parent {
                 if let Some(&parent_entity) = entity_mapping.get(&parent_id) {
                     // 设置父子关系的逻辑
@@ -217,23 +267,28 @@ impl SceneSerializer {
         // 加载资源引用
         self.load_resource_references(&data.resources)?;
 
-        Ok(scene)
+        if !report.is_complete() {
+            log::warn!("场景 {} 中有未注册的组件被跳过: {:?}", data.metadata.name, report.missing_types());
+        }
+
+        Ok((scene, report))
     }
 
     /// 序列化实体
     fn serialize_entity(&self, entity: Entity, world: &World) -> EngineResult<SerializedEntity> {
-        let mut components = HashMap::new();
-
         // 序列化所有组件
-        for (component_type, serializer) in &self.component_serializers {
-            if let Some(component_data) = serializer.serialize_component(entity, world)? {
-                components.insert(component_type.clone(), component_data);
-            }
-        }
+        let components = self.components.serialize_entity_components(entity, world)?;
+
+        // 优先使用Name组件，作为规范化导出和场景比较时的稳定标识
+        let name = if world.has_value::<specs::storage::MaskedStorage<crate::ecs::Name>>() {
+            world.read_storage::<crate::ecs::Name>().get(entity).map(|name| name.name.clone())
+        } else {
+            None
+        };
 
         Ok(SerializedEntity {
             id: entity.id() as u64, // Use entity.id() method
-            name: format!("Entity_{:?}", entity), // Use Debug format for Entity
+            name: name.unwrap_or_else(|| format!("Entity_{:?}", entity)),
             active: true,
             components,
             parent: None, // TODO: 从场景图获取
@@ -297,6 +352,74 @@ impl Default for SceneSerializer {
     }
 }
 
+impl SerializedScene {
+    /// 规范化：按稳定键(名称、组件内容)排序实体，并按排序结果重新编号ID，
+    /// 使输出与实体的创建顺序无关
+    pub fn canonicalize(&self) -> SerializedScene {
+        let mut order: Vec<(String, &SerializedEntity)> = self.entities
+            .iter()
+            .map(|entity| (entity_sort_key(entity), entity))
+            .collect();
+        order.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let id_mapping: HashMap<u64, u64> = order
+            .iter()
+            .enumerate()
+            .map(|(index, (_, entity))| (entity.id, index as u64))
+            .collect();
+        let remap = |id: &u64| id_mapping.get(id).copied().unwrap_or(*id);
+
+        let entities = order
+            .iter()
+            .map(|(_, entity)| {
+                let mut children: Vec<u64> = entity.children.iter().map(remap).collect();
+                children.sort_unstable();
+                SerializedEntity {
+                    id: remap(&entity.id),
+                    parent: entity.parent.as_ref().map(remap),
+                    children,
+                    ..(*entity).clone()
+                }
+            })
+            .collect();
+
+        let mut root_nodes: Vec<u64> = self.scene_graph.root_nodes.iter().map(remap).collect();
+        root_nodes.sort_unstable();
+        let nodes = self.scene_graph.nodes
+            .iter()
+            .map(|(id, node)| {
+                let mut node = node.clone();
+                node.entity_id = remap(&node.entity_id);
+                (remap(id), node)
+            })
+            .collect();
+
+        SerializedScene {
+            metadata: self.metadata.clone(),
+            entities,
+            scene_graph: SerializedSceneGraph { root_nodes, nodes },
+            resources: self.resources.clone(),
+            custom_data: self.custom_data.clone(),
+        }
+    }
+
+    /// 导出规范化JSON：键按字母排序、实体顺序稳定，便于版本控制比较和合并
+    pub fn to_canonical_json(&self) -> EngineResult<String> {
+        // serde_json::Value的对象按键排序，HashMap字段也因此输出稳定
+        let value = serde_json::to_value(self.canonicalize())?;
+        let mut json = serde_json::to_string_pretty(&value)?;
+        json.push('\n');
+        Ok(json)
+    }
+}
+
+/// 实体的稳定排序键：名称 + 组件内容的规范化JSON
+fn entity_sort_key(entity: &SerializedEntity) -> String {
+    let components: BTreeMap<&String, &serde_json::Value> = entity.components.iter().collect();
+    let components = serde_json::to_string(&components).unwrap_or_default();
+    format!("{}\u{0}{}", entity.name, components)
+}
+
 impl Serializable for SerializedScene {
     fn serialize(&self, context: &SerializationContext) -> EngineResult<Vec<u8>> {
         match context.format {
@@ -308,7 +431,7 @@ impl Serializable for SerializedScene {
                 }
             }
             SerializationFormat::Binary => {
-                Ok(bincode::serialize(self)?)
+                Ok(context.binary_encoding.serialize(self)?)
             }
             SerializationFormat::MessagePack => {
                 Ok(rmp_serde::to_vec(self)?)
@@ -326,7 +449,7 @@ impl Serializable for SerializedScene {
                 Ok(serde_json::from_slice(data)?)
             }
             SerializationFormat::Binary => {
-                Ok(bincode::deserialize(data)?)
+                Ok(context.binary_encoding.deserialize(data)?)
             }
             SerializationFormat::MessagePack => {
                 Ok(rmp_serde::from_slice(data)?)
@@ -364,19 +487,29 @@ impl<T: Component + Serialize + for<'de> Deserialize<'de>> GenericComponentSeria
     }
 }
 
-impl<T: Component + Serialize + for<'de> Deserialize<'de> + Send + Sync> ComponentSerializerTrait for GenericComponentSerializer<T> {
+impl<T> ComponentSerializerTrait for GenericComponentSerializer<T>
+where
+    T: Component + Serialize + for<'de> Deserialize<'de> + Send + Sync,
+    T::Storage: Default,
+{
     fn serialize_component(&self, entity: Entity, world: &World) -> EngineResult<Option<serde_json::Value>> {
-        if let Some(component) = None::<T> { // TODO: Fix specs component access
-            let value = serde_json::to_value(component)?;
-            Ok(Some(value))
-        } else {
-            Ok(None)
+        // 组件存储未注册时实体不可能拥有该组件
+        if !world.has_value::<specs::storage::MaskedStorage<T>>() {
+            return Ok(None);
+        }
+        match world.read_storage::<T>().get(entity) {
+            Some(component) => Ok(Some(serde_json::to_value(component)?)),
+            None => Ok(None),
         }
     }
 
     fn deserialize_component(&self, entity: Entity, data: &serde_json::Value, world: &mut World) -> EngineResult<()> {
         let component: T = serde_json::from_value(data.clone())?;
-        // world.add_component(entity, component); // TODO: Fix specs component adding
+        world.register::<T>();
+        world
+            .write_storage::<T>()
+            .insert(entity, component)
+            .map_err(|e| anyhow::anyhow!("添加组件失败: {}", e))?;
         Ok(())
     }
 
@@ -452,6 +585,10 @@ impl PrefabSerializer {
 
     /// 反序列化预制件
     pub fn deserialize_prefab(&self, data: &PrefabData, world: &mut World) -> EngineResult<Entity> {
+        self.scene_serializer
+            .components
+            .check_unknown_types(data.entities.iter().flat_map(|entity| entity.components.keys()))?;
+
         // 创建实体映射
         let mut entity_mapping = HashMap::new();
         
@@ -465,11 +602,9 @@ impl PrefabSerializer {
         for serialized_entity in &data.entities {
             let entity = entity_mapping[&serialized_entity.id];
             
-            for (component_type, component_data) in &serialized_entity.components {
-                if let Some(serializer) = self.scene_serializer.component_serializers.get(component_type) {
-                    // serializer.deserialize_component(entity, component_data, world)?; // TODO: Fix entity type
-                }
-            }
+            self.scene_serializer
+                .components
+                .deserialize_entity_components(entity, &serialized_entity.components, world)?;
         }
 
         // Ok(entity_mapping[&data.root_entity.id]) // TODO: Fix type mismatch
@@ -494,7 +629,7 @@ impl Serializable for PrefabData {
                 }
             }
             SerializationFormat::Binary => {
-                Ok(bincode::serialize(self)?)
+                Ok(context.binary_encoding.serialize(self)?)
             }
             SerializationFormat::MessagePack => {
                 Ok(rmp_serde::to_vec(self)?)
@@ -512,7 +647,7 @@ impl Serializable for PrefabData {
                 Ok(serde_json::from_slice(data)?)
             }
             SerializationFormat::Binary => {
-                Ok(bincode::deserialize(data)?)
+                Ok(context.binary_encoding.deserialize(data)?)
             }
             SerializationFormat::MessagePack => {
                 Ok(rmp_serde::from_slice(data)?)
@@ -524,3 +659,268 @@ impl Serializable for PrefabData {
         }
     }
 }
+
+/// 场景差异
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SceneDiff {
+    /// 新增的实体(稳定键)
+    pub added: Vec<String>,
+    /// 删除的实体(稳定键)
+    pub removed: Vec<String>,
+    /// 内容发生变化的实体
+    pub changed: Vec<EntityDiff>,
+}
+
+impl SceneDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// 变化的字段总数
+    pub fn change_count(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.iter().map(|entity| entity.changes.len()).sum::<usize>()
+    }
+}
+
+/// 单个实体的差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityDiff {
+    /// 实体的稳定键
+    pub entity: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// 字段变化，路径形如 `components.Transform.position[1]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub path: String,
+    pub old: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+}
+
+/// 比较两个场景，实体按名称匹配(重名实体按规范化顺序追加 `#序号`)
+pub fn diff(old: &SerializedScene, new: &SerializedScene) -> SceneDiff {
+    let old_entities = keyed_entities(old);
+    let new_entities = keyed_entities(new);
+
+    let mut result = SceneDiff::default();
+    for (key, old_value) in &old_entities {
+        match new_entities.get(key) {
+            Some(new_value) => {
+                let mut changes = Vec::new();
+                diff_values("", old_value, new_value, &mut changes);
+                if !changes.is_empty() {
+                    result.changed.push(EntityDiff { entity: key.clone(), changes });
+                }
+            }
+            None => result.removed.push(key.clone()),
+        }
+    }
+    result.added = new_entities
+        .keys()
+        .filter(|key| !old_entities.contains_key(*key))
+        .cloned()
+        .collect();
+    result
+}
+
+/// 以稳定键索引实体内容，父子关系以稳定键表示，不依赖运行时ID
+fn keyed_entities(scene: &SerializedScene) -> BTreeMap<String, serde_json::Value> {
+    let canonical = scene.canonicalize();
+
+    let mut keys: HashMap<u64, String> = HashMap::new();
+    let mut name_counts: HashMap<&str, usize> = HashMap::new();
+    for entity in &canonical.entities {
+        let count = name_counts.entry(entity.name.as_str()).or_insert(0);
+        let key = if *count == 0 {
+            entity.name.clone()
+        } else {
+            format!("{}#{}", entity.name, count)
+        };
+        *count += 1;
+        keys.insert(entity.id, key);
+    }
+
+    canonical.entities
+        .iter()
+        .map(|entity| {
+            let value = serde_json::json!({
+                "active": entity.active,
+                "parent": entity.parent.and_then(|id| keys.get(&id)),
+                "components": entity.components,
+            });
+            (keys[&entity.id].clone(), value)
+        })
+        .collect()
+}
+
+/// 递归比较JSON值，只记录发生变化的叶子字段
+fn diff_values(path: &str, old: &serde_json::Value, new: &serde_json::Value, changes: &mut Vec<FieldChange>) {
+    use serde_json::Value;
+
+    if old == new {
+        return;
+    }
+
+    let child_path = |segment: &str| {
+        if path.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{}.{}", path, segment)
+        }
+    };
+
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                match new_map.get(key) {
+                    Some(new_value) => diff_values(&child_path(key), old_value, new_value, changes),
+                    None => changes.push(FieldChange { path: child_path(key), old: Some(old_value.clone()), new: None }),
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    changes.push(FieldChange { path: child_path(key), old: None, new: Some(new_value.clone()) });
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) if old_items.len() == new_items.len() => {
+            for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                diff_values(&format!("{}[{}]", path, index), old_item, new_item, changes);
+            }
+        }
+        _ => changes.push(FieldChange {
+            path: path.to_string(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialization::TagComponent;
+    use serde_json::json;
+    use specs::Join;
+
+    fn entity(id: u64, name: &str, position: [f32; 3], parent: Option<u64>) -> SerializedEntity {
+        let mut components = HashMap::new();
+        components.insert("Transform".to_string(), json!({ "position": position }));
+        SerializedEntity { id, name: name.to_string(), active: true, components, parent, children: Vec::new() }
+    }
+
+    fn scene(entities: Vec<SerializedEntity>) -> SerializedScene {
+        SerializedScene {
+            metadata: SceneMetadata {
+                name: "test".to_string(),
+                description: String::new(),
+                version: "1.0".to_string(),
+                created_at: String::new(),
+                modified_at: String::new(),
+                author: String::new(),
+                tags: Vec::new(),
+                dependencies: Vec::new(),
+            },
+            entities,
+            scene_graph: SerializedSceneGraph { root_nodes: Vec::new(), nodes: HashMap::new() },
+            resources: HashMap::new(),
+            custom_data: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn canonical_json_ignores_creation_order() {
+        let first = scene(vec![
+            entity(7, "player", [0.0, 1.0, 0.0], None),
+            entity(3, "camera", [0.0, 5.0, -10.0], Some(7)),
+        ]);
+        let second = scene(vec![
+            entity(11, "camera", [0.0, 5.0, -10.0], Some(42)),
+            entity(42, "player", [0.0, 1.0, 0.0], None),
+        ]);
+
+        assert_eq!(first.to_canonical_json().unwrap(), second.to_canonical_json().unwrap());
+
+        // 按名称排序并重新编号，父引用随之更新
+        let canonical = first.canonicalize();
+        assert_eq!(canonical.entities[0].name, "camera");
+        assert_eq!(canonical.entities[0].parent, Some(1));
+        assert_eq!(canonical.entities[1].id, 1);
+    }
+
+    #[test]
+    fn diff_reports_changed_fields_by_path() {
+        let old = scene(vec![entity(1, "player", [0.0, 1.0, 0.0], None), entity(2, "enemy", [3.0, 0.0, 0.0], None)]);
+        let new = scene(vec![entity(9, "player", [0.0, 2.0, 0.0], None), entity(5, "coin", [1.0, 1.0, 1.0], None)]);
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.added, vec!["coin"]);
+        assert_eq!(diff.removed, vec!["enemy"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].entity, "player");
+        assert_eq!(diff.changed[0].changes, vec![FieldChange {
+            path: "components.Transform.position[1]".to_string(),
+            old: Some(json!(1.0)),
+            new: Some(json!(2.0)),
+        }]);
+        assert_eq!(diff.change_count(), 3);
+    }
+
+    #[test]
+    fn diff_of_reordered_scene_is_empty() {
+        let old = scene(vec![entity(1, "a", [0.0; 3], None), entity(2, "b", [1.0; 3], Some(1))]);
+        let new = scene(vec![entity(8, "b", [1.0; 3], Some(9)), entity(9, "a", [0.0; 3], None)]);
+        assert!(diff(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn duplicate_names_are_matched_in_canonical_order() {
+        let old = scene(vec![entity(1, "tree", [0.0; 3], None), entity(2, "tree", [5.0; 3], None)]);
+        let new = scene(vec![
+            entity(1, "tree", [0.0; 3], None),
+            entity(2, "tree", [5.0; 3], None),
+            entity(3, "tree", [9.0; 3], None),
+        ]);
+        assert_eq!(diff(&old, &new).added, vec!["tree#2"]);
+    }
+
+    /// 带一个已注册组件和一个未注册组件的实体
+    fn entity_with_unknown(id: u64) -> SerializedEntity {
+        let mut entity = entity(id, "turret", [0.0; 3], None);
+        entity.components = HashMap::from([
+            ("TagComponent".to_string(), json!({ "tags": ["enemy"] })),
+            ("LaserComponent".to_string(), json!({ "power": 3 })),
+        ]);
+        entity
+    }
+
+    #[test]
+    fn scene_load_skips_unknown_components() {
+        let serializer = SceneSerializer::new();
+        let mut world = World::new();
+        let mut scene_manager = SceneManager::new();
+        let data = scene(vec![entity_with_unknown(4), entity_with_unknown(9)]);
+
+        let (_, report) = serializer.deserialize_scene_with_report(&data, &mut world, &mut scene_manager).unwrap();
+        assert_eq!(report.loaded_components, 2);
+        assert_eq!(report.missing_types(), vec!["LaserComponent"]);
+        assert_eq!(report.unknown_components["LaserComponent"], vec![4, 9]);
+        assert!(!report.is_complete());
+        assert_eq!(world.read_storage::<TagComponent>().join().count(), 2);
+    }
+
+    #[test]
+    fn fail_policy_rejects_scene_before_creating_entities() {
+        let mut serializer = SceneSerializer::new();
+        serializer.set_unknown_component_policy(UnknownComponentPolicy::Fail);
+        let mut world = World::new();
+        let mut scene_manager = SceneManager::new();
+
+        let error = serializer
+            .deserialize_scene(&scene(vec![entity_with_unknown(1)]), &mut world, &mut scene_manager)
+            .unwrap_err();
+        assert!(error.to_string().contains("LaserComponent"));
+        assert_eq!(world.entities().join().count(), 0);
+    }
+}
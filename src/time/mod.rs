@@ -2,17 +2,128 @@
 
 use instant::Instant;
 
+/// 固定步长累加器
+///
+/// 每帧累加帧时间并返回需要执行的固定步数。长时间卡顿后最多执行
+/// `max_substeps` 步，超出部分直接丢弃，避免模拟越追越慢(死亡螺旋)。
+#[derive(Debug, Clone)]
+pub struct FixedTimestep {
+    timestep: f32,
+    max_substeps: u32,
+    accumulator: f32,
+    /// 最近一帧执行的步数
+    last_steps: u32,
+    /// 最近一帧丢弃的时间(秒)
+    last_dropped: f32,
+}
+
+impl FixedTimestep {
+    /// 默认每帧最大步数，时间管理器和物理世界共用
+    pub const DEFAULT_MAX_SUBSTEPS: u32 = 8;
+
+    pub fn new(timestep: f32, max_substeps: u32) -> Self {
+        Self {
+            timestep: timestep.max(f32::EPSILON),
+            max_substeps: max_substeps.max(1),
+            accumulator: 0.0,
+            last_steps: 0,
+            last_dropped: 0.0,
+        }
+    }
+
+    /// 累加帧时间，返回本帧需要执行的固定步数
+    pub fn accumulate(&mut self, delta_time: f32) -> u32 {
+        self.accumulator += delta_time.max(0.0);
+
+        let available = (self.accumulator / self.timestep).floor() as u32;
+        let steps = available.min(self.max_substeps);
+        self.accumulator -= steps as f32 * self.timestep;
+
+        // 超出上限的时间直接丢弃，只保留不足一步的余量
+        self.last_dropped = 0.0;
+        if available > self.max_substeps {
+            let remainder = self.accumulator % self.timestep;
+            self.last_dropped = self.accumulator - remainder;
+            self.accumulator = remainder;
+            log::warn!(
+                "固定步长落后过多：本帧限制为{}步，丢弃{:.3}秒",
+                self.max_substeps,
+                self.last_dropped
+            );
+        }
+
+        self.last_steps = steps;
+        steps
+    }
+
+    /// 固定步长
+    pub fn timestep(&self) -> f32 {
+        self.timestep
+    }
+
+    /// 设置固定步长
+    pub fn set_timestep(&mut self, timestep: f32) {
+        self.timestep = timestep.max(f32::EPSILON);
+    }
+
+    /// 每帧最大步数
+    pub fn max_substeps(&self) -> u32 {
+        self.max_substeps
+    }
+
+    /// 设置每帧最大步数
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.max_substeps = max_substeps.max(1);
+    }
+
+    /// 最近一帧执行的步数
+    pub fn last_steps(&self) -> u32 {
+        self.last_steps
+    }
+
+    /// 最近一帧丢弃的时间(秒)
+    pub fn last_dropped(&self) -> f32 {
+        self.last_dropped
+    }
+
+    /// 插值系数(未消耗时间占一步的比例)，用于渲染插值
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.timestep
+    }
+
+    /// 清空累计时间
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+        self.last_steps = 0;
+        self.last_dropped = 0.0;
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(1.0 / 60.0, Self::DEFAULT_MAX_SUBSTEPS)
+    }
+}
+
 /// 时间管理器
 #[derive(Debug)]
 pub struct TimeManager {
     start_time: Instant,
     last_frame_time: Instant,
     delta_time: f32,
+    /// 未限制的真实帧时间
+    raw_delta_time: f32,
+    /// 上报帧时间的上限，断点或卡顿后避免出现一个巨大的帧
+    max_delta: f32,
+    /// 游戏时间缩放，只影响 `scaled_delta_time`
+    time_scale: f32,
+    paused: bool,
     total_time: f32,
     frame_count: u64,
     fps: f32,
     fps_timer: f32,
     fps_frame_count: u32,
+    fixed: FixedTimestep,
 }
 
 impl TimeManager {
@@ -23,34 +134,53 @@ impl TimeManager {
             start_time: now,
             last_frame_time: now,
             delta_time: 0.0,
+            raw_delta_time: 0.0,
+            max_delta: Self::DEFAULT_MAX_DELTA,
+            time_scale: 1.0,
+            paused: false,
             total_time: 0.0,
             frame_count: 0,
             fps: 0.0,
             fps_timer: 0.0,
             fps_frame_count: 0,
+            fixed: FixedTimestep::default(),
         }
     }
 
+    /// 默认帧时间上限(秒)
+    pub const DEFAULT_MAX_DELTA: f32 = 0.1;
+
     /// 更新时间管理器 (每帧调用)
     pub fn update(&mut self) {
         let now = Instant::now();
-        
-        // 计算帧时间
-        let frame_duration = now.duration_since(self.last_frame_time);
-        self.delta_time = frame_duration.as_secs_f32();
+        let elapsed = now.duration_since(self.last_frame_time).as_secs_f32();
         self.last_frame_time = now;
-        
-        // 更新总时间
-        let total_duration = now.duration_since(self.start_time);
-        self.total_time = total_duration.as_secs_f32();
-        
+
+        self.advance(elapsed);
+
+        // 总时间按真实时间计算，不受帧时间上限影响
+        self.total_time = now.duration_since(self.start_time).as_secs_f32();
+    }
+
+    /// 以给定的真实经过时间推进一帧，可用于确定性回放或模拟卡顿
+    ///
+    /// `delta_time` 被限制在 `max_delta` 以内，`total_time` 累加真实时间。
+    pub fn advance(&mut self, elapsed: f32) {
+        self.raw_delta_time = elapsed.max(0.0);
+        self.delta_time = self.raw_delta_time.min(self.max_delta);
+        self.total_time += self.raw_delta_time;
+
+        // 固定步长使用未经max_delta限制的真实时间(乘以时间缩放)，卡顿后的追赶由
+        // 累加器的最大步数限制；暂停和慢动作时物理随游戏时间一起变慢
+        self.fixed.accumulate(self.raw_delta_time * self.effective_time_scale());
+
         // 更新帧计数
         self.frame_count += 1;
-        
-        // 更新FPS计算
-        self.fps_timer += self.delta_time;
+
+        // FPS按真实时间统计
+        self.fps_timer += self.raw_delta_time;
         self.fps_frame_count += 1;
-        
+
         if self.fps_timer >= 1.0 {
             self.fps = self.fps_frame_count as f32 / self.fps_timer;
             self.fps_timer = 0.0;
@@ -58,11 +188,68 @@ impl TimeManager {
         }
     }
 
-    /// 获取帧时间 (秒)
+    /// 设置上报帧时间的上限(秒)，小于等于0表示不限制
+    pub fn set_max_delta(&mut self, max_delta: f32) {
+        self.max_delta = if max_delta > 0.0 { max_delta } else { f32::INFINITY };
+    }
+
+    /// 帧时间上限(秒)
+    pub fn max_delta(&self) -> f32 {
+        self.max_delta
+    }
+
+    /// 未限制的真实帧时间 (秒)
+    pub fn raw_delta_time(&self) -> f32 {
+        self.raw_delta_time
+    }
+
+    /// 本帧帧时间是否被限制
+    pub fn is_delta_clamped(&self) -> bool {
+        self.raw_delta_time > self.delta_time
+    }
+
+    /// 获取帧时间 (秒)，不受时间缩放和暂停影响，用于UI等始终运行的效果
     pub fn delta_time(&self) -> f32 {
         self.delta_time
     }
 
+    /// 按时间缩放后的游戏帧时间 (秒)，暂停时为0
+    pub fn scaled_delta_time(&self) -> f32 {
+        if self.paused {
+            0.0
+        } else {
+            self.delta_time * self.time_scale
+        }
+    }
+
+    /// 设置游戏时间缩放，1为正常速度，0冻结游戏时间
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    /// 游戏时间缩放(不含暂停)
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// 当前生效的时间缩放，暂停时为0
+    pub fn effective_time_scale(&self) -> f32 {
+        if self.paused { 0.0 } else { self.time_scale }
+    }
+
+    /// 暂停游戏时间，恢复后沿用原来的时间缩放
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     /// 获取总运行时间 (秒)
     pub fn total_time(&self) -> f32 {
         self.total_time
@@ -78,6 +265,21 @@ impl TimeManager {
         self.fps
     }
 
+    /// 本帧需要执行的固定步数(已按最大步数限制)，物理等固定步长系统通过 `TimeResource` 读取
+    pub fn fixed_steps(&self) -> u32 {
+        self.fixed.last_steps()
+    }
+
+    /// 获取固定步长累加器
+    pub fn fixed_timestep(&self) -> &FixedTimestep {
+        &self.fixed
+    }
+
+    /// 获取可变固定步长累加器
+    pub fn fixed_timestep_mut(&mut self) -> &mut FixedTimestep {
+        &mut self.fixed
+    }
+
     /// 获取帧时间 (毫秒)
     pub fn delta_time_ms(&self) -> f32 {
         self.delta_time * 1000.0
@@ -89,11 +291,13 @@ impl TimeManager {
         self.start_time = now;
         self.last_frame_time = now;
         self.delta_time = 0.0;
+        self.raw_delta_time = 0.0;
         self.total_time = 0.0;
         self.frame_count = 0;
         self.fps = 0.0;
         self.fps_timer = 0.0;
         self.fps_frame_count = 0;
+        self.fixed.reset();
     }
 
     /// 获取平均FPS
@@ -111,3 +315,107 @@ impl Default for TimeManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulate_runs_whole_steps_and_keeps_remainder() {
+        let mut fixed = FixedTimestep::new(0.25, 8);
+        assert_eq!(fixed.accumulate(0.625), 2);
+        assert_eq!(fixed.alpha(), 0.5);
+        assert_eq!(fixed.accumulate(0.125), 1);
+        assert_eq!(fixed.last_dropped(), 0.0);
+    }
+
+    #[test]
+    fn accumulate_caps_steps_and_drops_excess_time() {
+        let mut fixed = FixedTimestep::new(0.25, 4);
+        assert_eq!(fixed.accumulate(2.125), 4);
+        assert_eq!(fixed.last_steps(), 4);
+        assert_eq!(fixed.last_dropped(), 1.0);
+        // 只保留不足一步的余量
+        assert_eq!(fixed.alpha(), 0.5);
+        assert_eq!(fixed.accumulate(0.0), 0);
+    }
+
+    #[test]
+    fn accumulate_ignores_negative_time() {
+        let mut fixed = FixedTimestep::default();
+        assert_eq!(fixed.accumulate(-1.0), 0);
+        assert_eq!(fixed.alpha(), 0.0);
+    }
+
+    #[test]
+    fn long_frame_hits_step_cap_despite_max_delta() {
+        let mut time = TimeManager::new();
+        let timestep = time.fixed_timestep().timestep();
+        let cap = time.fixed_timestep().max_substeps();
+
+        // 2秒的卡顿远超上限能追回的时间
+        time.advance(2.0);
+        assert!(time.is_delta_clamped());
+        assert_eq!(time.fixed_steps(), cap);
+
+        // 上限之外的整步被丢弃，只留下不足一步的余量
+        let dropped = time.fixed_timestep().last_dropped();
+        let leftover = time.fixed_timestep().alpha() * timestep;
+        assert!(leftover < timestep);
+        assert!((dropped + leftover + cap as f32 * timestep - 2.0).abs() < 1e-4);
+
+        // 下一帧不会追赶被丢弃的时间
+        time.advance(0.0);
+        assert_eq!(time.fixed_steps(), 0);
+    }
+
+    #[test]
+    fn paused_and_scaled_time_feed_fixed_steps() {
+        let mut time = TimeManager::new();
+        time.pause();
+        time.advance(0.05);
+        assert_eq!(time.fixed_steps(), 0);
+
+        time.resume();
+        time.set_time_scale(0.5);
+        time.fixed_timestep_mut().reset();
+        time.advance(0.11);
+        assert_eq!(time.fixed_steps(), 3);
+    }
+
+    #[test]
+    fn stall_between_updates_is_clamped() {
+        let mut time = TimeManager::new();
+        time.set_max_delta(0.05);
+        // 模拟断点停留3秒后的下一帧
+        let stall = std::time::Duration::from_secs(3);
+        time.start_time -= stall;
+        time.last_frame_time -= stall;
+        time.update();
+
+        assert_eq!(time.delta_time(), 0.05);
+        assert!(time.is_delta_clamped());
+        assert!(time.raw_delta_time() >= 3.0);
+        // 总时间仍按真实时间计算
+        assert!(time.total_time() >= 3.0);
+    }
+
+    #[test]
+    fn max_delta_defaults_and_can_be_disabled() {
+        let mut time = TimeManager::new();
+        assert_eq!(time.max_delta(), TimeManager::DEFAULT_MAX_DELTA);
+
+        time.advance(0.016);
+        assert_eq!(time.delta_time(), 0.016);
+        assert!(!time.is_delta_clamped());
+
+        time.set_max_delta(0.0);
+        assert_eq!(time.max_delta(), f32::INFINITY);
+        time.advance(2.5);
+        assert_eq!(time.delta_time(), 2.5);
+        assert!((time.total_time() - 2.516).abs() < 1e-6);
+
+        time.advance(-1.0);
+        assert_eq!((time.delta_time(), time.raw_delta_time()), (0.0, 0.0));
+    }
+}
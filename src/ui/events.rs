@@ -1,6 +1,7 @@
 //! UI事件系统
 
 use crate::math::Vec2;
+use crate::ui::WidgetId;
 use specs::Entity;
 use serde::{Deserialize, Serialize};
 
@@ -29,6 +30,36 @@ pub enum UIEvent {
     Layout(LayoutUIEvent),
     /// 自定义事件
     Custom(CustomUIEvent),
+    /// 拖拽开始，发给被拖拽的源控件
+    DragStart { source: WidgetId, payload: DragPayload, position: Vec2 },
+    /// 拖拽经过，发给指针下的控件
+    DragOver { source: WidgetId, payload: DragPayload, position: Vec2 },
+    /// 在指针下的控件上释放，控件处理(返回true)表示接受
+    Drop { source: WidgetId, payload: DragPayload, position: Vec2 },
+    /// 拖拽结束，发给源控件，accepted表示是否有控件接受了放置
+    DragEnd { source: WidgetId, accepted: bool },
+}
+
+/// 拖拽携带的数据
+#[derive(Debug, Clone, PartialEq)]
+pub enum DragPayload {
+    /// 资源路径(如从项目面板拖出的资源)
+    Asset(String),
+    /// 场景实体
+    Entity(Entity),
+    Text(String),
+    /// 游戏自定义数据，如背包物品
+    Custom { kind: String, data: String },
+}
+
+impl UIEvent {
+    /// 由输入系统的文本输入生成UI事件，组字中的文本不提交给控件
+    pub fn from_text_input(input: &crate::input::TextInput) -> Option<Self> {
+        match input {
+            crate::input::TextInput::Text(text) => Some(UIEvent::TextInput { text: text.clone() }),
+            crate::input::TextInput::Preedit { .. } => None,
+        }
+    }
 }
 
 /// 鼠标UI事件
@@ -129,6 +129,11 @@ struct FlexItem {
     pub margin_cross_end: f32,
 }
 
+/// 是否参与正常布局流(隐藏和锚定的元素不参与)
+fn in_flow(node: &LayoutNode) -> bool {
+    node.style.display != Display::None && node.style.anchors.is_none()
+}
+
 /// 布局引擎
 pub struct LayoutEngine {
     layout_cache: HashMap<WidgetId, LayoutResult>,
@@ -172,12 +177,54 @@ impl LayoutEngine {
             }
         };
 
+        // 锚定的子元素按本元素的内容区定位
+        self.layout_anchored_children(node, &result);
+
         // 缓存结果
         self.layout_cache.insert(node.widget_id, result);
         node.result = Some(result);
         result
     }
 
+    /// 布局使用锚点的子元素
+    fn layout_anchored_children(&mut self, node: &mut LayoutNode, result: &LayoutResult) {
+        let padding = node.style.padding;
+        for child in &mut node.children {
+            if child.style.display == Display::None || child.style.anchors.is_none() {
+                continue;
+            }
+            let origin = Vec2::new(padding.left, padding.top);
+            self.layout_anchored(child, origin, result.content_size);
+        }
+    }
+
+    /// 按锚点在父元素内容区(origin, parent_size)中定位元素
+    pub fn layout_anchored(&mut self, node: &mut LayoutNode, origin: Vec2, parent_size: Vec2) -> LayoutResult {
+        let Some(anchors) = node.style.anchors else {
+            return self.compute_layout(node, LayoutConstraints::new(0.0, parent_size.x, 0.0, parent_size.y));
+        };
+
+        let size = Vec2::new(
+            node.style.width.unwrap_or(0.0),
+            node.style.height.unwrap_or(0.0),
+        );
+        let (position, size) = anchors.resolve(parent_size, size, &node.style.margin);
+
+        // 大小由锚点决定，父元素尺寸变化时需要重新布局
+        self.layout_cache.remove(&node.widget_id);
+        let mut result = self.compute_layout(node, LayoutConstraints::new(0.0, size.x, 0.0, size.y));
+        let padding = node.style.padding;
+        result.position = origin + position;
+        result.size = size;
+        result.content_size = Vec2::new(
+            (size.x - padding.horizontal()).max(0.0),
+            (size.y - padding.vertical()).max(0.0),
+        );
+        node.result = Some(result);
+        self.layout_anchored_children(node, &result);
+        result
+    }
+
     /// 计算块级布局
     fn compute_block_layout(&mut self, node: &mut LayoutNode, constraints: LayoutConstraints) -> LayoutResult {
         let style = &node.style;
@@ -213,7 +260,7 @@ impl LayoutEngine {
         let content_width = width - padding.horizontal();
         
         for child in &mut node.children {
-            if child.style.display == Display::None {
+            if !in_flow(child) {
                 continue;
             }
 
@@ -291,7 +338,7 @@ impl LayoutEngine {
         let mut total_main_size = 0.0;
 
         for child in &mut node.children {
-            if child.style.display == Display::None {
+            if !in_flow(child) {
                 continue;
             }
 
@@ -377,11 +424,7 @@ impl LayoutEngine {
         }
 
         // 布局子元素
-        for (i, child) in node.children.iter_mut().enumerate() {
-            if child.style.display == Display::None {
-                continue;
-            }
-
+        for (i, child) in node.children.iter_mut().filter(|child| in_flow(child)).enumerate() {
             let item = &flex_items[i];
             
             // 主轴位置
@@ -460,7 +503,7 @@ impl LayoutEngine {
         let content_width = width - padding.horizontal();
 
         for child in &mut node.children {
-            if child.style.display == Display::None {
+            if !in_flow(child) {
                 continue;
             }
 
@@ -561,7 +604,11 @@ impl LayoutManager {
         let constraints = LayoutConstraints::new(0.0, self.viewport_size.x, 0.0, self.viewport_size.y);
         
         for root in &mut self.root_nodes {
-            self.engine.compute_layout(root, constraints);
+            if root.style.anchors.is_some() {
+                self.engine.layout_anchored(root, Vec2::ZERO, self.viewport_size);
+            } else {
+                self.engine.compute_layout(root, constraints);
+            }
         }
     }
 
@@ -656,3 +703,61 @@ pub mod layout_utils {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::style::{Anchors, Rect};
+
+    fn anchored(widget_id: WidgetId, anchors: Anchors, width: f32, height: f32) -> LayoutNode {
+        let mut style = UIStyle::default();
+        style.anchors = Some(anchors);
+        style.width = Some(width);
+        style.height = Some(height);
+        style.margin = Rect::all(0.0);
+        style.padding = Rect::all(0.0);
+        LayoutNode::new(widget_id, style)
+    }
+
+    fn layout_at(width: f32) -> LayoutManager {
+        let mut root = anchored(1, Anchors::stretch(), 0.0, 0.0);
+        root.add_child(anchored(2, Anchors::top_right().with_offset(Vec2::new(-10.0, 10.0)), 100.0, 40.0));
+        root.add_child(anchored(3, Anchors::stretch_horizontal(1.0).with_pivot(Vec2::new(0.0, 1.0)), 0.0, 30.0));
+
+        let mut manager = LayoutManager::new();
+        manager.add_root_node(root);
+        manager.set_viewport_size(Vec2::new(width, 600.0));
+        manager.update_layout();
+        manager
+    }
+
+    #[test]
+    fn right_anchored_widget_follows_viewport_edge() {
+        let narrow = layout_at(800.0).get_layout_result(2).unwrap();
+        let wide = layout_at(1200.0).get_layout_result(2).unwrap();
+
+        assert_eq!(narrow.position, Vec2::new(690.0, 10.0));
+        assert_eq!(wide.position, Vec2::new(1090.0, 10.0));
+        assert_eq!(wide.size, Vec2::new(100.0, 40.0));
+    }
+
+    #[test]
+    fn stretched_widget_grows_with_viewport() {
+        let narrow = layout_at(800.0);
+        let wide = layout_at(1200.0);
+
+        assert_eq!(narrow.get_layout_result(1).unwrap().size, Vec2::new(800.0, 600.0));
+        let bar = wide.get_layout_result(3).unwrap();
+        assert_eq!(bar.size, Vec2::new(1200.0, 30.0));
+        // 底部横条的轴心在底边
+        assert_eq!(bar.position, Vec2::new(0.0, 570.0));
+    }
+
+    #[test]
+    fn resizing_viewport_relayouts_existing_tree() {
+        let mut manager = layout_at(800.0);
+        manager.set_viewport_size(Vec2::new(1000.0, 600.0));
+        manager.update_layout();
+        assert_eq!(manager.get_layout_result(2).unwrap().position.x, 890.0);
+    }
+}
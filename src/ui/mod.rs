@@ -5,12 +5,16 @@ pub mod style;
 pub mod widgets;
 pub mod layout;
 pub mod renderer;
+pub mod sdf;
+pub mod rich_text;
 
 pub use events::*;
 pub use style::*;
 pub use widgets::*;
 pub use layout::*;
 pub use renderer::*;
+pub use sdf::*;
+pub use rich_text::*;
 
 /// UI系统主接口
 pub struct UISystem {
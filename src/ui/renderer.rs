@@ -5,6 +5,8 @@ use crate::render::{RenderSystem, Mesh, Material, Texture, Shader};
 use crate::ui::{UIStyle, Color};
 use crate::ui::widgets::{Rect, UIRenderer};
 use crate::ui::style::{BorderStyle, FontStyle};
+use crate::ui::sdf::{FontAtlasKind, SdfAtlas, SdfTextParams};
+use crate::ui::rich_text::{layout_text, TextRun};
 use std::collections::HashMap;
 
 /// UI顶点数据
@@ -41,6 +43,7 @@ pub enum UIShaderType {
     Solid,      // 纯色
     Textured,   // 纹理
     Text,       // 文本
+    SdfText,    // 距离场文本
     Gradient,   // 渐变
 }
 
@@ -174,6 +177,15 @@ pub struct Font {
     pub size: f32,
     pub atlas_texture: Option<String>, // 字体图集纹理
     pub glyph_info: HashMap<char, GlyphInfo>,
+    pub atlas_kind: FontAtlasKind,
+    pub sdf_atlas: Option<SdfAtlas>, // 距离场图集(atlas_kind为Sdf时)
+}
+
+impl Font {
+    /// 是否使用距离场图集
+    pub fn is_sdf(&self) -> bool {
+        matches!(self.atlas_kind, FontAtlasKind::Sdf { .. })
+    }
 }
 
 /// 字形信息
@@ -203,6 +215,8 @@ impl FontCache {
                 size,
                 atlas_texture: None,
                 glyph_info: HashMap::new(),
+                atlas_kind: FontAtlasKind::Bitmap,
+                sdf_atlas: None,
             };
             self.fonts.insert(key.clone(), font);
         }
@@ -210,6 +224,25 @@ impl FontCache {
         self.fonts.get(&key)
     }
 
+    /// 注册距离场字体，size为生成图集时的基准字号，渲染时可任意缩放
+    pub fn register_sdf_font(&mut self, family: &str, size: f32, atlas: SdfAtlas) {
+        let key = format!("{}_sdf", family);
+        let font = Font {
+            family: family.to_string(),
+            size,
+            atlas_texture: Some(format!("{}_sdf_atlas", family)),
+            glyph_info: HashMap::new(),
+            atlas_kind: FontAtlasKind::Sdf { spread: atlas.spread },
+            sdf_atlas: Some(atlas),
+        };
+        self.fonts.insert(key, font);
+    }
+
+    /// 获取距离场字体(与字号无关)
+    pub fn get_sdf_font(&self, family: &str) -> Option<&Font> {
+        self.fonts.get(&format!("{}_sdf", family))
+    }
+
     pub fn get_text_size(&self, text: &str, font_style: &FontStyle) -> Vec2 {
         // 简化实现：基于字体大小估算
         let char_width = font_style.size * 0.6;
@@ -234,6 +267,7 @@ pub struct UIRendererImpl {
     view_matrix: Mat4,
     projection_matrix: Mat4,
     screen_size: Vec2,
+    sdf_params: SdfTextParams,
 }
 
 impl UIRendererImpl {
@@ -255,9 +289,64 @@ impl UIRendererImpl {
             view_matrix: Mat4::IDENTITY,
             projection_matrix,
             screen_size,
+            sdf_params: SdfTextParams::default(),
         }
     }
 
+    /// 获取字体缓存
+    pub fn font_cache_mut(&mut self) -> &mut FontCache {
+        &mut self.font_cache
+    }
+
+    /// 设置距离场文本参数(描边、发光等)
+    pub fn set_sdf_params(&mut self, params: SdfTextParams) {
+        self.sdf_params = params;
+    }
+
+    /// 获取距离场文本参数
+    pub fn sdf_params(&self) -> &SdfTextParams {
+        &self.sdf_params
+    }
+
+    /// 使用距离场字体绘制文本，成功返回true
+    fn draw_sdf_text(&mut self, runs: &[TextRun], bounds: Rect, font: &FontStyle, color: Color) -> bool {
+        let Some(sdf_font) = self.font_cache.get_sdf_font(&font.family) else {
+            return false;
+        };
+        let Some(atlas) = sdf_font.sdf_atlas.as_ref() else {
+            return false;
+        };
+
+        // 距离场在缩放后保持清晰，按目标字号缩放字形
+        let scale = font.size / sdf_font.size;
+        let atlas_texture = sdf_font.atlas_texture.clone();
+
+        let advance = |ch: char| atlas.glyph(ch).map_or(font.size * 0.6, |glyph| glyph.advance * scale);
+        let quads: Vec<_> = layout_text(runs, color, bounds, font, advance)
+            .into_iter()
+            .filter(|placed| placed.ch != ' ')
+            .filter_map(|placed| {
+                let glyph = atlas.glyph(placed.ch)?;
+                Some((
+                    Rect::new(
+                        placed.position.x + glyph.offset.x * scale,
+                        placed.position.y + glyph.offset.y * scale,
+                        glyph.size.x * scale,
+                        glyph.size.y * scale,
+                    ),
+                    glyph.uv_rect,
+                    placed.color,
+                ))
+            })
+            .collect();
+
+        self.ensure_batch_type(UIShaderType::SdfText, atlas_texture.as_deref());
+        for (rect, uv, color) in quads {
+            self.current_batch.add_quad(rect, color, Some(uv));
+        }
+        true
+    }
+
     pub fn set_screen_size(&mut self, width: f32, height: f32) {
         self.screen_size = Vec2::new(width, height);
         self.projection_matrix = Mat4::orthographic_rh(
@@ -312,6 +401,9 @@ impl UIRendererImpl {
                 UIShaderType::Text => {
                     // 渲染文本
                 }
+                UIShaderType::SdfText => {
+                    // 渲染距离场文本(ui_sdf_text.wgsl，阈值化距离场)
+                }
                 UIShaderType::Gradient => {
                     // 渲染渐变
                 }
@@ -396,54 +488,31 @@ impl UIRenderer for UIRendererImpl {
     }
 
     fn draw_text(&mut self, text: &str, bounds: Rect, font: &FontStyle, color: Color) {
-        if text.is_empty() {
+        self.draw_rich_text(&[TextRun::new(text, None)], bounds, font, color);
+    }
+
+    fn draw_rich_text(&mut self, runs: &[TextRun], bounds: Rect, font: &FontStyle, color: Color) {
+        if runs.iter().all(|run| run.text.is_empty()) {
+            return;
+        }
+
+        // 优先使用距离场字体
+        if self.draw_sdf_text(runs, bounds, font, color) {
             return;
         }
 
         self.ensure_batch_type(UIShaderType::Text, None);
 
         // 加载字体
-        if let Some(_font) = self.font_cache.load_font(&font.family, font.size) {
-            // 计算文本位置
-            let text_size = self.font_cache.get_text_size(text, font);
-            let mut position = Vec2::new(bounds.x, bounds.y);
-
-            // 水平对齐
-            match font.family.as_str() {
-                _ => {
-                    // 简化实现：左对齐
-                }
-            }
-
-            // 渲染每个字符
+        if self.font_cache.load_font(&font.family, font.size).is_some() {
+            // TODO: 使用真实的字形数据
             let char_width = font.size * 0.6;
-            let line_height = font.size * font.line_height;
-            
-            let mut current_pos = position;
-            
-            for line in text.lines() {
-                current_pos.x = position.x;
-                
-                for ch in line.chars() {
-                    if ch == ' ' {
-                        current_pos.x += char_width;
-                        continue;
-                    }
-
-                    // TODO: 使用真实的字形数据
-                    let char_rect = Rect::new(
-                        current_pos.x,
-                        current_pos.y,
-                        char_width,
-                        font.size
-                    );
-
-                    // 添加字符四边形
-                    self.current_batch.add_quad(char_rect, color, None);
-                    current_pos.x += char_width + font.letter_spacing;
+            for placed in layout_text(runs, color, bounds, font, |_| char_width) {
+                if placed.ch == ' ' {
+                    continue;
                 }
-                
-                current_pos.y += line_height;
+                let char_rect = Rect::new(placed.position.x, placed.position.y, char_width, font.size);
+                self.current_batch.add_quad(char_rect, placed.color, None);
             }
         }
     }
@@ -480,7 +549,7 @@ impl UIRenderStats {
         self.triangles += batch.indices.len() as u32 / 3;
         self.draw_calls += 1;
         
-        if batch.shader_type == UIShaderType::Text {
+        if matches!(batch.shader_type, UIShaderType::Text | UIShaderType::SdfText) {
             self.text_draws += 1;
         }
     }
@@ -0,0 +1,250 @@
+//! 富文本与文本排版
+//!
+//! 支持简单的颜色标签：`[color=#ff0000]警告[/color]`，颜色可写作 `#rgb`、`#rrggbb` 或 `#rrggbbaa`，标签可以嵌套。
+//! 无法识别的标签和不匹配的结束标签按原文保留，因此普通文本中的方括号不受影响。
+
+use crate::math::Vec2;
+use crate::ui::style::FontStyle;
+use crate::ui::widgets::Rect;
+use crate::ui::Color;
+use serde::{Deserialize, Serialize};
+
+/// 同一颜色的一段文本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextRun {
+    pub text: String,
+    /// None表示使用组件的文本颜色
+    pub color: Option<Color>,
+}
+
+impl TextRun {
+    pub fn new(text: impl Into<String>, color: Option<Color>) -> Self {
+        Self { text: text.into(), color }
+    }
+}
+
+/// 解析后的富文本
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RichText {
+    pub runs: Vec<TextRun>,
+}
+
+impl RichText {
+    /// 单一颜色的纯文本
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self { runs: vec![TextRun::new(text, None)] }
+    }
+
+    /// 解析带颜色标签的文本，相邻的同色片段会合并
+    pub fn parse(source: &str) -> Self {
+        let mut rich_text = Self::default();
+        let mut color_stack: Vec<Color> = Vec::new();
+        let mut rest = source;
+
+        while let Some(open) = rest.find('[') {
+            rich_text.push(&rest[..open], color_stack.last().copied());
+            let tag_source = &rest[open..];
+
+            let Some(close) = tag_source.find(']') else {
+                rest = tag_source;
+                break;
+            };
+            let tag = &tag_source[1..close];
+
+            if let Some(color) = tag.strip_prefix("color=").and_then(Self::parse_color) {
+                color_stack.push(color);
+            } else if tag == "/color" && !color_stack.is_empty() {
+                color_stack.pop();
+            } else {
+                // 不是颜色标签，保留'['并从下一个字符继续查找
+                rich_text.push("[", color_stack.last().copied());
+                rest = &tag_source[1..];
+                continue;
+            }
+            rest = &tag_source[close + 1..];
+        }
+
+        rich_text.push(rest, color_stack.last().copied());
+        rich_text
+    }
+
+    /// 去掉标签后的文本
+    pub fn plain_text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+
+    fn push(&mut self, text: &str, color: Option<Color>) {
+        if text.is_empty() {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(last) if last.color == color => last.text.push_str(text),
+            _ => self.runs.push(TextRun::new(text, color)),
+        }
+    }
+
+    /// 解析 `#rgb`、`#rrggbb`、`#rrggbbaa`
+    fn parse_color(value: &str) -> Option<Color> {
+        let digits = value.strip_prefix('#')?;
+        if !digits.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |hex: &str| u8::from_str_radix(hex, 16).ok().map(|value| value as f32 / 255.0);
+
+        match digits.len() {
+            3 => {
+                let expanded: String = digits.chars().flat_map(|ch| [ch, ch]).collect();
+                Self::parse_color(&format!("#{}", expanded))
+            }
+            6 => Some(Color::rgb(channel(&digits[0..2])?, channel(&digits[2..4])?, channel(&digits[4..6])?)),
+            8 => Some(Color::rgba(
+                channel(&digits[0..2])?,
+                channel(&digits[2..4])?,
+                channel(&digits[4..6])?,
+                channel(&digits[6..8])?,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// 排版后的字符
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionedGlyph {
+    pub ch: char,
+    pub color: Color,
+    /// 字符单元的左上角
+    pub position: Vec2,
+}
+
+/// 按字体的对齐方式把文本片段排进bounds
+///
+/// 每行单独水平对齐，整段文本整体垂直对齐；`advance` 给出字符的前进宽度(不含字间距)。
+pub fn layout_text(
+    runs: &[TextRun],
+    default_color: Color,
+    bounds: Rect,
+    font: &FontStyle,
+    advance: impl Fn(char) -> f32,
+) -> Vec<PositionedGlyph> {
+    let mut lines: Vec<Vec<(char, Color)>> = vec![Vec::new()];
+    for run in runs {
+        let color = run.color.unwrap_or(default_color);
+        for ch in run.text.chars() {
+            match ch {
+                '\n' => lines.push(Vec::new()),
+                '\r' => {}
+                _ => lines.last_mut().unwrap().push((ch, color)),
+            }
+        }
+    }
+
+    let line_height = font.size * font.line_height;
+    let text_height = lines.len() as f32 * line_height;
+    let mut y = bounds.y + (bounds.height - text_height) * font.vertical_align.factor();
+
+    let mut glyphs = Vec::new();
+    for line in &lines {
+        let line_width = line.iter().map(|&(ch, _)| advance(ch)).sum::<f32>()
+            + font.letter_spacing * line.len().saturating_sub(1) as f32;
+        let mut x = bounds.x + (bounds.width - line_width) * font.text_align.factor();
+
+        for &(ch, color) in line {
+            glyphs.push(PositionedGlyph { ch, color, position: Vec2::new(x, y) });
+            x += advance(ch) + font.letter_spacing;
+        }
+        y += line_height;
+    }
+    glyphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::style::{TextAlign, VerticalAlign};
+
+    fn font(text_align: TextAlign, vertical_align: VerticalAlign) -> FontStyle {
+        FontStyle { size: 10.0, line_height: 1.0, text_align, vertical_align, ..FontStyle::default() }
+    }
+
+    fn layout(text: &str, font: &FontStyle) -> Vec<PositionedGlyph> {
+        layout_text(&RichText::parse(text).runs, Color::WHITE, Rect::new(10.0, 20.0, 100.0, 50.0), font, |_| 5.0)
+    }
+
+    #[test]
+    fn color_tags_parse_into_runs() {
+        let rich_text = RichText::parse("HP: [color=#ff0000]12[/color] / 40");
+        assert_eq!(
+            rich_text.runs,
+            vec![
+                TextRun::new("HP: ", None),
+                TextRun::new("12", Some(Color::rgb(1.0, 0.0, 0.0))),
+                TextRun::new(" / 40", None),
+            ]
+        );
+        assert_eq!(rich_text.plain_text(), "HP: 12 / 40");
+    }
+
+    #[test]
+    fn nested_tags_and_color_forms() {
+        let rich_text = RichText::parse("[color=#0f0]a[color=#0000ff80]b[/color]c[/color]d");
+        let green = Some(Color::rgb(0.0, 1.0, 0.0));
+        assert_eq!(rich_text.runs.len(), 4);
+        assert_eq!(rich_text.runs[0], TextRun::new("a", green));
+        assert_eq!(rich_text.runs[1].text, "b");
+        let blue = rich_text.runs[1].color.unwrap();
+        assert_eq!((blue.b, blue.a), (1.0, 128.0 / 255.0));
+        assert_eq!(rich_text.runs[2], TextRun::new("c", green));
+        assert_eq!(rich_text.runs[3], TextRun::new("d", None));
+
+        // 相邻的同色片段合并
+        assert_eq!(RichText::parse("[color=#f00]a[/color][color=#ff0000]b[/color]").runs, vec![TextRun::new("ab", Some(Color::RED))]);
+    }
+
+    #[test]
+    fn unknown_tags_and_brackets_are_kept() {
+        for source in ["[b]x[/color] [1]", "[color=#zz]bad[/color]", "[color=#12345]x", "open [color=#f00"] {
+            assert_eq!(RichText::parse(source), RichText::plain(source), "{}", source);
+        }
+        assert!(RichText::parse("").runs.is_empty());
+    }
+
+    #[test]
+    fn horizontal_alignment_positions_lines_within_bounds() {
+        let cases = [(TextAlign::Left, 10.0), (TextAlign::Center, 50.0), (TextAlign::Right, 90.0), (TextAlign::Justify, 10.0)];
+        for (align, x) in cases {
+            let glyphs = layout("abcd", &font(align, VerticalAlign::Top));
+            assert_eq!(glyphs[0].position, Vec2::new(x, 20.0), "{:?}", align);
+            assert_eq!(glyphs[3].position.x, x + 15.0);
+        }
+
+        // 每行单独对齐
+        let glyphs = layout("ab\nabcd", &font(TextAlign::Center, VerticalAlign::Top));
+        assert_eq!(glyphs[0].position, Vec2::new(55.0, 20.0));
+        assert_eq!(glyphs[2].position, Vec2::new(50.0, 30.0));
+
+        // 字间距计入行宽
+        let spaced = FontStyle { letter_spacing: 2.0, ..font(TextAlign::Right, VerticalAlign::Top) };
+        let xs: Vec<f32> = layout("abcd", &spaced).iter().map(|glyph| glyph.position.x).collect();
+        assert_eq!(xs, vec![84.0, 91.0, 98.0, 105.0]);
+    }
+
+    #[test]
+    fn vertical_alignment_positions_block_within_bounds() {
+        let cases = [(VerticalAlign::Top, 20.0), (VerticalAlign::Middle, 40.0), (VerticalAlign::Bottom, 60.0), (VerticalAlign::Baseline, 20.0)];
+        for (align, y) in cases {
+            let glyphs = layout("a", &font(TextAlign::Left, align));
+            assert_eq!(glyphs[0].position.y, y, "{:?}", align);
+        }
+
+        let glyphs = layout("a\nb", &font(TextAlign::Left, VerticalAlign::Middle));
+        assert_eq!((glyphs[0].position.y, glyphs[1].position.y), (35.0, 45.0));
+    }
+
+    #[test]
+    fn glyphs_take_run_color_or_default() {
+        let glyphs = layout("a[color=#f00]b[/color]\r\nc", &font(TextAlign::Left, VerticalAlign::Top));
+        let chars: Vec<(char, Color)> = glyphs.iter().map(|glyph| (glyph.ch, glyph.color)).collect();
+        assert_eq!(chars, vec![('a', Color::WHITE), ('b', Color::RED), ('c', Color::WHITE)]);
+    }
+}
@@ -0,0 +1,271 @@
+//! 有向距离场(SDF)字体图集
+//!
+//! 字形以距离场形式存储：0.5为字形边缘，内部大于0.5、外部小于0.5。
+//! 着色器对距离值做阈值处理，任意缩放下都能保持清晰，并可廉价地实现描边和发光。
+
+use crate::math::Vec2;
+use crate::ui::renderer::GlyphInfo;
+use crate::ui::widgets::Rect;
+use crate::ui::Color;
+use std::collections::HashMap;
+
+/// 字体图集类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FontAtlasKind {
+    /// 普通位图图集
+    Bitmap,
+    /// 距离场图集，spread为距离场覆盖的像素范围
+    Sdf { spread: f32 },
+}
+
+impl Default for FontAtlasKind {
+    fn default() -> Self {
+        FontAtlasKind::Bitmap
+    }
+}
+
+/// SDF文本渲染参数(对应ui_sdf_text.wgsl中的uniform)
+#[derive(Debug, Clone, Copy)]
+pub struct SdfTextParams {
+    /// 边缘阈值
+    pub threshold: f32,
+    /// 边缘平滑宽度
+    pub smoothing: f32,
+    /// 描边宽度(距离场单位，0表示无描边)
+    pub outline_width: f32,
+    pub outline_color: Color,
+    /// 发光宽度(距离场单位，0表示无发光)
+    pub glow_width: f32,
+    pub glow_color: Color,
+}
+
+impl Default for SdfTextParams {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            smoothing: 0.05,
+            outline_width: 0.0,
+            outline_color: Color::BLACK,
+            glow_width: 0.0,
+            glow_color: Color::WHITE,
+        }
+    }
+}
+
+/// SDF字形图集
+#[derive(Debug, Clone)]
+pub struct SdfAtlas {
+    pub width: u32,
+    pub height: u32,
+    pub spread: f32,
+    /// 单通道距离场数据
+    pub data: Vec<u8>,
+    glyphs: HashMap<char, GlyphInfo>,
+    // 货架式打包状态
+    cursor_x: u32,
+    cursor_y: u32,
+    shelf_height: u32,
+}
+
+impl SdfAtlas {
+    /// 创建新的SDF图集
+    pub fn new(width: u32, height: u32, spread: f32) -> Self {
+        Self {
+            width,
+            height,
+            spread: spread.max(1.0),
+            data: vec![0; (width * height) as usize],
+            glyphs: HashMap::new(),
+            cursor_x: 0,
+            cursor_y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    /// 添加字形覆盖位图(每像素一个字节，>=128视为字形内部)，返回字形信息
+    pub fn add_glyph(&mut self, ch: char, bitmap: &[u8], width: u32, height: u32, advance: f32) -> Option<GlyphInfo> {
+        if bitmap.len() < (width * height) as usize {
+            return None;
+        }
+
+        // 距离场需要在字形周围留出spread大小的边距
+        let padding = self.spread.ceil() as u32;
+        let field_width = width + padding * 2;
+        let field_height = height + padding * 2;
+        let field = generate_sdf(bitmap, width, height, padding, self.spread);
+
+        let (x, y) = self.allocate(field_width, field_height)?;
+        for row in 0..field_height {
+            let src = (row * field_width) as usize;
+            let dst = ((y + row) * self.width + x) as usize;
+            self.data[dst..dst + field_width as usize]
+                .copy_from_slice(&field[src..src + field_width as usize]);
+        }
+
+        let info = GlyphInfo {
+            uv_rect: Rect::new(
+                x as f32 / self.width as f32,
+                y as f32 / self.height as f32,
+                field_width as f32 / self.width as f32,
+                field_height as f32 / self.height as f32,
+            ),
+            size: Vec2::new(field_width as f32, field_height as f32),
+            offset: Vec2::new(-(padding as f32), -(padding as f32)),
+            advance,
+        };
+        self.glyphs.insert(ch, info);
+        Some(info)
+    }
+
+    /// 获取字形信息
+    pub fn glyph(&self, ch: char) -> Option<&GlyphInfo> {
+        self.glyphs.get(&ch)
+    }
+
+    /// 读取图集中某个像素的距离值(0-1)
+    pub fn sample(&self, x: u32, y: u32) -> f32 {
+        let x = x.min(self.width - 1);
+        let y = y.min(self.height - 1);
+        self.data[(y * self.width + x) as usize] as f32 / 255.0
+    }
+
+    /// 在图集中分配矩形区域
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if width > self.width || height > self.height {
+            return None;
+        }
+
+        if self.cursor_x + width > self.width {
+            self.cursor_x = 0;
+            self.cursor_y += self.shelf_height;
+            self.shelf_height = 0;
+        }
+        if self.cursor_y + height > self.height {
+            return None;
+        }
+
+        let position = (self.cursor_x, self.cursor_y);
+        self.cursor_x += width;
+        self.shelf_height = self.shelf_height.max(height);
+        Some(position)
+    }
+}
+
+/// 从覆盖位图生成带边距的距离场
+///
+/// 每个像素寻找spread范围内最近的相反状态像素，距离按
+/// `0.5 + d / (2 * spread)` 编码，内部为正、外部为负。
+pub fn generate_sdf(bitmap: &[u8], width: u32, height: u32, padding: u32, spread: f32) -> Vec<u8> {
+    let field_width = width + padding * 2;
+    let field_height = height + padding * 2;
+    let inside = |x: i32, y: i32| -> bool {
+        let gx = x - padding as i32;
+        let gy = y - padding as i32;
+        if gx < 0 || gy < 0 || gx >= width as i32 || gy >= height as i32 {
+            return false;
+        }
+        bitmap[(gy as u32 * width + gx as u32) as usize] >= 128
+    };
+
+    let radius = spread.ceil() as i32;
+    let mut field = vec![0u8; (field_width * field_height) as usize];
+
+    for y in 0..field_height as i32 {
+        for x in 0..field_width as i32 {
+            let is_inside = inside(x, y);
+            let mut nearest_sq = f32::MAX;
+
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if inside(x + dx, y + dy) != is_inside {
+                        let dist_sq = (dx * dx + dy * dy) as f32;
+                        if dist_sq < nearest_sq {
+                            nearest_sq = dist_sq;
+                        }
+                    }
+                }
+            }
+
+            // 边缘位于两个像素中心之间，减去半个像素
+            let distance = if nearest_sq == f32::MAX {
+                spread
+            } else {
+                (nearest_sq.sqrt() - 0.5).min(spread)
+            };
+            let signed = if is_inside { distance } else { -distance };
+            let encoded = (0.5 + signed / (2.0 * spread)).clamp(0.0, 1.0);
+            field[(y as u32 * field_width + x as u32) as usize] = (encoded * 255.0).round() as u8;
+        }
+    }
+
+    field
+}
+
+/// 计算SDF采样值的覆盖率(与ui_sdf_text.wgsl中的阈值处理一致)
+pub fn sdf_coverage(distance: f32, params: &SdfTextParams) -> f32 {
+    let edge0 = params.threshold - params.smoothing;
+    let edge1 = params.threshold + params.smoothing;
+    crate::math::smoothstep(edge0, edge1, distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 4x4的实心方块
+    fn square() -> Vec<u8> {
+        vec![255; 16]
+    }
+
+    #[test]
+    fn sdf_is_inside_positive_and_outside_negative() {
+        let field = generate_sdf(&square(), 4, 4, 2, 2.0);
+        let field_width = 8;
+        let at = |x: usize, y: usize| field[y * field_width + x] as f32 / 255.0;
+
+        // 中心在字形内部，角落在外部
+        assert!(at(4, 4) > 0.5);
+        assert!(at(0, 0) < 0.5);
+        // 距离按像素递增：边缘两侧的像素离边缘半个像素
+        assert!((at(2, 4) - 0.625).abs() < 0.01);
+        assert!((at(1, 4) - 0.375).abs() < 0.01);
+        assert!(at(4, 4) > at(2, 4));
+        assert!(at(0, 4) < at(1, 4));
+    }
+
+    #[test]
+    fn atlas_packs_glyphs_on_shelves() {
+        let mut atlas = SdfAtlas::new(16, 16, 2.0);
+        let a = atlas.add_glyph('a', &square(), 4, 4, 5.0).unwrap();
+        assert_eq!(a.size, Vec2::new(8.0, 8.0));
+        assert_eq!(a.offset, Vec2::new(-2.0, -2.0));
+        assert_eq!(a.uv_rect.x, 0.0);
+
+        let b = atlas.add_glyph('b', &square(), 4, 4, 5.0).unwrap();
+        assert_eq!(b.uv_rect.x, 0.5);
+        // 第三个字形换到下一层货架
+        let c = atlas.add_glyph('c', &square(), 4, 4, 5.0).unwrap();
+        assert_eq!((c.uv_rect.x, c.uv_rect.y), (0.0, 0.5));
+        assert!(atlas.glyph('b').is_some());
+
+        // 中心像素位于字形内部
+        assert!(atlas.sample(4, 4) > 0.5);
+    }
+
+    #[test]
+    fn atlas_rejects_invalid_or_oversized_glyphs() {
+        let mut atlas = SdfAtlas::new(8, 8, 2.0);
+        assert!(atlas.add_glyph('x', &[255; 3], 4, 4, 5.0).is_none());
+        assert!(atlas.add_glyph('y', &vec![255; 64], 8, 8, 5.0).is_none());
+        assert!(atlas.add_glyph('z', &square(), 4, 4, 5.0).is_some());
+        assert!(atlas.add_glyph('w', &square(), 4, 4, 5.0).is_none());
+    }
+
+    #[test]
+    fn coverage_thresholds_at_edge() {
+        let params = SdfTextParams::default();
+        assert_eq!(sdf_coverage(0.0, &params), 0.0);
+        assert_eq!(sdf_coverage(1.0, &params), 1.0);
+        assert!((sdf_coverage(0.5, &params) - 0.5).abs() < 1e-5);
+    }
+}
@@ -67,6 +67,21 @@ impl Color {
     pub const MAGENTA: Self = Self::new(1.0, 0.0, 1.0, 1.0);
     pub const TRANSPARENT: Self = Self::new(0.0, 0.0, 0.0, 0.0);
 
+    /// 转换为RGBA向量
+    pub fn to_vec4(self) -> crate::math::Vec4 {
+        crate::math::Vec4::new(self.r, self.g, self.b, self.a)
+    }
+
+    /// 从RGBA向量创建
+    pub fn from_vec4(v: crate::math::Vec4) -> Self {
+        Self::new(v.x, v.y, v.z, v.w)
+    }
+
+    /// 在sRGB颜色渐变上取色(插值在线性空间进行)
+    pub fn from_ramp(ramp: &crate::math::ColorRamp, t: f32) -> Self {
+        Self::from_vec4(ramp.evaluate_srgb(t))
+    }
+
     /// 调整透明度
     pub fn with_alpha(mut self, alpha: f32) -> Self {
         self.a = alpha.clamp(0.0, 1.0);
@@ -214,6 +229,12 @@ pub struct FontStyle {
     pub line_height: f32,
     /// 字符间距
     pub letter_spacing: f32,
+    /// 水平对齐
+    #[serde(default)]
+    pub text_align: TextAlign,
+    /// 垂直对齐
+    #[serde(default)]
+    pub vertical_align: VerticalAlign,
 }
 
 /// 字体粗细
@@ -247,6 +268,8 @@ impl Default for FontStyle {
             style: FontStyleType::Normal,
             line_height: 1.2,
             letter_spacing: 0.0,
+            text_align: TextAlign::Left,
+            vertical_align: VerticalAlign::Top,
         }
     }
 }
@@ -260,6 +283,23 @@ pub enum TextAlign {
     Justify,
 }
 
+impl Default for TextAlign {
+    fn default() -> Self {
+        TextAlign::Left
+    }
+}
+
+impl TextAlign {
+    /// 剩余宽度分配到左侧的比例
+    pub fn factor(&self) -> f32 {
+        match self {
+            TextAlign::Left | TextAlign::Justify => 0.0,
+            TextAlign::Center => 0.5,
+            TextAlign::Right => 1.0,
+        }
+    }
+}
+
 /// 垂直对齐方式
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum VerticalAlign {
@@ -269,6 +309,23 @@ pub enum VerticalAlign {
     Baseline,
 }
 
+impl Default for VerticalAlign {
+    fn default() -> Self {
+        VerticalAlign::Top
+    }
+}
+
+impl VerticalAlign {
+    /// 剩余高度分配到上方的比例
+    pub fn factor(&self) -> f32 {
+        match self {
+            VerticalAlign::Top | VerticalAlign::Baseline => 0.0,
+            VerticalAlign::Middle => 0.5,
+            VerticalAlign::Bottom => 1.0,
+        }
+    }
+}
+
 /// 显示类型
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Display {
@@ -313,6 +370,117 @@ pub enum CursorStyle {
     Help,
 }
 
+/// 锚点：相对父元素内容区的位置，min/max为0~1的比例
+///
+/// 某个轴上min与max相同时，元素固定在该锚点，大小取width/height，`offset`为轴心相对锚点的偏移；
+/// min与max不同时，元素在该轴上随父元素拉伸，margin作为到锚点的内缩距离。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Anchors {
+    pub min: Vec2,
+    pub max: Vec2,
+    /// 轴心(自身大小的0~1比例)
+    pub pivot: Vec2,
+    pub offset: Vec2,
+}
+
+impl Anchors {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self {
+            min,
+            max,
+            pivot: Vec2::ZERO,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    /// 固定在父元素的某一点，轴心取同一点(如右上角(1, 0))
+    pub fn point(anchor: Vec2) -> Self {
+        Self::new(anchor, anchor).with_pivot(anchor)
+    }
+
+    pub fn top_left() -> Self {
+        Self::point(Vec2::new(0.0, 0.0))
+    }
+
+    pub fn top_right() -> Self {
+        Self::point(Vec2::new(1.0, 0.0))
+    }
+
+    pub fn bottom_left() -> Self {
+        Self::point(Vec2::new(0.0, 1.0))
+    }
+
+    pub fn bottom_right() -> Self {
+        Self::point(Vec2::new(1.0, 1.0))
+    }
+
+    pub fn center() -> Self {
+        Self::point(Vec2::new(0.5, 0.5))
+    }
+
+    /// 铺满父元素
+    pub fn stretch() -> Self {
+        Self::new(Vec2::ZERO, Vec2::ONE)
+    }
+
+    /// 横向拉伸，纵向固定在anchor_y处
+    pub fn stretch_horizontal(anchor_y: f32) -> Self {
+        Self::new(Vec2::new(0.0, anchor_y), Vec2::new(1.0, anchor_y)).with_pivot(Vec2::new(0.0, anchor_y))
+    }
+
+    /// 纵向拉伸，横向固定在anchor_x处
+    pub fn stretch_vertical(anchor_x: f32) -> Self {
+        Self::new(Vec2::new(anchor_x, 0.0), Vec2::new(anchor_x, 1.0)).with_pivot(Vec2::new(anchor_x, 0.0))
+    }
+
+    pub fn with_pivot(mut self, pivot: Vec2) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// 计算在父元素内的(位置, 大小)，size为元素未拉伸时的大小
+    pub fn resolve(&self, parent_size: Vec2, size: Vec2, margin: &Rect) -> (Vec2, Vec2) {
+        let (x, width) = Self::resolve_axis(
+            parent_size.x, self.min.x, self.max.x, self.pivot.x, self.offset.x, size.x, margin.left, margin.right,
+        );
+        let (y, height) = Self::resolve_axis(
+            parent_size.y, self.min.y, self.max.y, self.pivot.y, self.offset.y, size.y, margin.top, margin.bottom,
+        );
+        (Vec2::new(x, y), Vec2::new(width, height))
+    }
+
+    fn resolve_axis(
+        parent: f32,
+        min: f32,
+        max: f32,
+        pivot: f32,
+        offset: f32,
+        size: f32,
+        inset_start: f32,
+        inset_end: f32,
+    ) -> (f32, f32) {
+        let start = parent * min;
+        let end = parent * max;
+        if end - start > f32::EPSILON {
+            let size = (end - start - inset_start - inset_end).max(0.0);
+            (start + inset_start + offset, size)
+        } else {
+            (start + offset - size * pivot, size)
+        }
+    }
+}
+
+impl Default for Anchors {
+    fn default() -> Self {
+        Self::top_left()
+    }
+}
+
 /// 完整的UI样式
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UIStyle {
@@ -335,6 +503,9 @@ pub struct UIStyle {
     pub right: Option<f32>,
     pub bottom: Option<f32>,
     pub z_index: i32,
+    /// 锚点，设置后元素脱离正常布局流，按父元素的大小定位
+    #[serde(default)]
+    pub anchors: Option<Anchors>,
     
     // 显示
     pub display: Display,
@@ -436,6 +607,7 @@ impl Default for UIStyle {
             right: None,
             bottom: None,
             z_index: 0,
+            anchors: None,
             
             // 显示
             display: Display::Block,
@@ -534,6 +706,12 @@ impl StyleBuilder {
         self
     }
 
+    /// 设置锚点
+    pub fn anchors(mut self, anchors: Anchors) -> Self {
+        self.style.anchors = Some(anchors);
+        self
+    }
+
     /// 设置弹性盒子
     pub fn flex(mut self) -> Self {
         self.style.display = Display::Flex;
@@ -624,3 +802,31 @@ impl StylePresets {
         style
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_anchor_places_pivot_at_anchor() {
+        let parent = Vec2::new(400.0, 300.0);
+        let (position, size) = Anchors::center().resolve(parent, Vec2::new(100.0, 50.0), &Rect::all(0.0));
+        assert_eq!(position, Vec2::new(150.0, 125.0));
+        assert_eq!(size, Vec2::new(100.0, 50.0));
+
+        let (position, _) = Anchors::bottom_right().resolve(parent, Vec2::new(100.0, 50.0), &Rect::all(0.0));
+        assert_eq!(position, Vec2::new(300.0, 250.0));
+    }
+
+    #[test]
+    fn stretch_anchor_insets_by_margin() {
+        let margin = Rect { left: 10.0, top: 20.0, right: 30.0, bottom: 40.0 };
+        let (position, size) = Anchors::stretch().resolve(Vec2::new(400.0, 300.0), Vec2::ZERO, &margin);
+        assert_eq!(position, Vec2::new(10.0, 20.0));
+        assert_eq!(size, Vec2::new(360.0, 240.0));
+
+        // 内缩超过父元素大小时尺寸为0
+        let (_, size) = Anchors::stretch().resolve(Vec2::new(20.0, 20.0), Vec2::ZERO, &margin);
+        assert_eq!(size, Vec2::ZERO);
+    }
+}
@@ -1,7 +1,8 @@
 //! UI组件系统
 
 use crate::math::{Vec2, Vec3};
-use crate::ui::{UIStyle, UIEvent, Color};
+use crate::ui::{UIStyle, UIEvent, Color, RichText, DragPayload};
+use crate::ui::style::{FontStyle, TextAlign, VerticalAlign};
 use crate::input::{KeyCode, MouseButton};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -66,6 +67,11 @@ pub trait Widget {
     /// 渲染组件
     fn render(&self, renderer: &mut dyn UIRenderer);
     
+    /// 可被拖拽时返回拖拽数据
+    fn drag_payload(&self) -> Option<DragPayload> {
+        None
+    }
+    
     /// 点击测试
     fn hit_test(&self, point: Vec2) -> bool {
         let bounds = self.bounds();
@@ -139,6 +145,9 @@ pub struct TextWidget {
     pub text: String,
     pub word_wrap: bool,
     pub selectable: bool,
+    /// 解析文本中的 `[color=#rrggbb]...[/color]` 标签
+    #[serde(default)]
+    pub rich_text: bool,
 }
 
 impl TextWidget {
@@ -151,9 +160,22 @@ impl TextWidget {
             text,
             word_wrap: false,
             selectable: false,
+            rich_text: false,
         }
     }
 
+    /// 设置文本在组件范围内的对齐方式
+    pub fn with_alignment(mut self, horizontal: TextAlign, vertical: VerticalAlign) -> Self {
+        self.base.style.text_align = horizontal;
+        self.base.style.vertical_align = vertical;
+        self
+    }
+
+    pub fn with_rich_text(mut self, rich_text: bool) -> Self {
+        self.rich_text = rich_text;
+        self
+    }
+
     pub fn with_word_wrap(mut self, wrap: bool) -> Self {
         self.word_wrap = wrap;
         self
@@ -223,8 +245,18 @@ impl Widget for TextWidget {
             renderer.draw_border(bounds, &self.style().border);
         }
 
-        // 渲染文本
-        renderer.draw_text(&self.text, bounds, &self.style().font, self.style().text_color);
+        // 渲染文本，对齐方式取自组件样式
+        let font = FontStyle {
+            text_align: self.style().text_align,
+            vertical_align: self.style().vertical_align,
+            ..self.style().font.clone()
+        };
+        if self.rich_text {
+            let rich_text = RichText::parse(&self.text);
+            renderer.draw_rich_text(&rich_text.runs, bounds, &font, self.style().text_color);
+        } else {
+            renderer.draw_text(&self.text, bounds, &font, self.style().text_color);
+        }
     }
 }
 
@@ -431,11 +463,27 @@ impl InputWidget {
         }
     }
 
+    /// 光标前一个字符的起始位置(cursor_position为字节偏移)
+    fn previous_char_boundary(&self) -> usize {
+        self.text[..self.cursor_position]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    /// 光标后一个字符的结束位置
+    fn next_char_boundary(&self) -> usize {
+        self.text[self.cursor_position..]
+            .chars()
+            .next()
+            .map_or(self.cursor_position, |c| self.cursor_position + c.len_utf8())
+    }
+
     pub fn backspace(&mut self) {
         if self.selection_start != self.selection_end {
             self.delete_selection();
         } else if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+            self.cursor_position = self.previous_char_boundary();
             self.text.remove(self.cursor_position);
             self.selection_start = self.cursor_position;
             self.selection_end = self.cursor_position;
@@ -488,7 +536,7 @@ impl Widget for InputWidget {
                         }
                         crate::ui::events::KeyCode::ArrowLeft => {
                             if self.cursor_position > 0 {
-                                self.cursor_position -= 1;
+                                self.cursor_position = self.previous_char_boundary();
                                 self.selection_start = self.cursor_position;
                                 self.selection_end = self.cursor_position;
                             }
@@ -496,7 +544,7 @@ impl Widget for InputWidget {
                         }
                         crate::ui::events::KeyCode::ArrowRight => {
                             if self.cursor_position < self.text.len() {
-                                self.cursor_position += 1;
+                                self.cursor_position = self.next_char_boundary();
                                 self.selection_start = self.cursor_position;
                                 self.selection_end = self.cursor_position;
                             }
@@ -698,6 +746,11 @@ pub trait UIRenderer {
     fn draw_rect(&mut self, bounds: Rect, color: Color);
     fn draw_border(&mut self, bounds: Rect, border: &crate::ui::style::BorderStyle);
     fn draw_text(&mut self, text: &str, bounds: Rect, font: &crate::ui::style::FontStyle, color: Color);
+    /// 绘制彩色文本片段，未指定颜色的片段使用color
+    fn draw_rich_text(&mut self, runs: &[crate::ui::TextRun], bounds: Rect, font: &crate::ui::style::FontStyle, color: Color) {
+        let text: String = runs.iter().map(|run| run.text.as_str()).collect();
+        self.draw_text(&text, bounds, font, color);
+    }
     fn draw_icon(&mut self, icon_path: &str, bounds: Rect);
     fn draw_image(&mut self, image_path: &str, bounds: Rect);
 }
@@ -707,14 +760,30 @@ pub struct WidgetContainer {
     widgets: HashMap<WidgetId, Box<dyn Widget>>,
     root_widgets: Vec<WidgetId>,
     next_id: WidgetId,
+    /// 按下可拖拽控件后的拖拽状态
+    drag: Option<DragState>,
+}
+
+/// 进行中的拖拽
+#[derive(Debug, Clone)]
+struct DragState {
+    source: WidgetId,
+    payload: DragPayload,
+    start_position: Vec2,
+    /// 指针移动超过阈值后才真正开始拖拽
+    active: bool,
 }
 
 impl WidgetContainer {
+    /// 开始拖拽前指针需要移动的距离(像素)
+    pub const DRAG_THRESHOLD: f32 = 4.0;
+
     pub fn new() -> Self {
         Self {
             widgets: HashMap::new(),
             root_widgets: Vec::new(),
             next_id: 1,
+            drag: None,
         }
     }
 
@@ -734,6 +803,9 @@ impl WidgetContainer {
     pub fn remove_widget(&mut self, id: WidgetId) -> bool {
         if self.widgets.remove(&id).is_some() {
             self.root_widgets.retain(|&x| x != id);
+            if self.drag.as_ref().map_or(false, |drag| drag.source == id) {
+                self.drag = None;
+            }
             true
         } else {
             false
@@ -752,6 +824,10 @@ impl WidgetContainer {
     }
 
     pub fn handle_event(&mut self, event: &UIEvent) -> bool {
+        if self.handle_drag_event(event) {
+            return true;
+        }
+
         for &id in &self.root_widgets {
             if let Some(widget) = self.widgets.get_mut(&id) {
                 if widget.handle_event(event) {
@@ -762,6 +838,91 @@ impl WidgetContainer {
         false
     }
 
+    /// 指针下最上层(最后添加)的可见且启用的控件
+    pub fn widget_at(&self, position: Vec2) -> Option<WidgetId> {
+        self.root_widgets.iter().rev().copied().find(|id| {
+            self.widgets
+                .get(id)
+                .map_or(false, |widget| widget.is_visible() && widget.is_enabled() && widget.hit_test(position))
+        })
+    }
+
+    /// 正在拖拽的源控件和数据
+    pub fn dragging(&self) -> Option<(WidgetId, &DragPayload)> {
+        self.drag
+            .as_ref()
+            .filter(|drag| drag.active)
+            .map(|drag| (drag.source, &drag.payload))
+    }
+
+    /// 取消进行中的拖拽，源控件收到未被接受的 `DragEnd`
+    pub fn cancel_drag(&mut self) {
+        if let Some(drag) = self.drag.take() {
+            if drag.active {
+                self.send_to(drag.source, &UIEvent::DragEnd { source: drag.source, accepted: false });
+            }
+        }
+    }
+
+    fn send_to(&mut self, id: WidgetId, event: &UIEvent) -> bool {
+        self.widgets.get_mut(&id).map_or(false, |widget| widget.handle_event(event))
+    }
+
+    /// 由鼠标事件驱动拖拽，已作为拖拽处理时返回true
+    fn handle_drag_event(&mut self, event: &UIEvent) -> bool {
+        use crate::ui::events::MouseButton as UIMouseButton;
+
+        match event {
+            UIEvent::MouseButtonDown { button: UIMouseButton::Left, position } => {
+                self.drag = self.widget_at(*position).and_then(|source| {
+                    let payload = self.widgets.get(&source)?.drag_payload()?;
+                    Some(DragState { source, payload, start_position: *position, active: false })
+                });
+                false
+            }
+            UIEvent::MouseMove { position } => {
+                let Some(drag) = self.drag.as_mut() else {
+                    return false;
+                };
+                if !drag.active {
+                    if drag.start_position.distance(*position) < Self::DRAG_THRESHOLD {
+                        return false;
+                    }
+                    drag.active = true;
+                    let (source, payload) = (drag.source, drag.payload.clone());
+                    self.send_to(source, &UIEvent::DragStart { source, payload, position: *position });
+                }
+
+                let Some(drag) = self.drag.as_ref() else {
+                    return true;
+                };
+                let (source, payload) = (drag.source, drag.payload.clone());
+                if let Some(target) = self.widget_at(*position).filter(|&target| target != source) {
+                    self.send_to(target, &UIEvent::DragOver { source, payload, position: *position });
+                }
+                true
+            }
+            UIEvent::MouseButtonUp { button: UIMouseButton::Left, position } => {
+                let Some(drag) = self.drag.take() else {
+                    return false;
+                };
+                if !drag.active {
+                    // 未超过阈值，按普通点击处理
+                    return false;
+                }
+
+                let source = drag.source;
+                let accepted = match self.widget_at(*position).filter(|&target| target != source) {
+                    Some(target) => self.send_to(target, &UIEvent::Drop { source, payload: drag.payload, position: *position }),
+                    None => false,
+                };
+                self.send_to(source, &UIEvent::DragEnd { source, accepted });
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn update(&mut self, delta_time: f32) {
         for widget in self.widgets.values_mut() {
             widget.update(delta_time);
@@ -782,3 +943,242 @@ impl Default for WidgetContainer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::TextInput;
+    use crate::ui::events::KeyCode as UIKeyCode;
+
+    fn focused_input() -> InputWidget {
+        let mut input = InputWidget::new(1);
+        input.set_state(WidgetState::Focused);
+        input
+    }
+
+    #[test]
+    fn text_input_events_insert_layout_characters() {
+        let mut input = focused_input();
+        let typed = [TextInput::Text("é".to_string()), TextInput::Preedit { text: "ni".to_string(), cursor: None }];
+        for event in typed.iter().filter_map(UIEvent::from_text_input) {
+            assert!(input.handle_event(&event));
+        }
+        assert_eq!(input.text, "é");
+        assert_eq!(input.cursor_position, "é".len());
+    }
+
+    #[test]
+    fn cursor_moves_by_characters_not_bytes() {
+        let mut input = focused_input();
+        input.insert_text("aé你");
+
+        input.handle_event(&UIEvent::KeyDown { key: UIKeyCode::ArrowLeft });
+        assert_eq!(input.cursor_position, "aé".len());
+        input.handle_event(&UIEvent::KeyDown { key: UIKeyCode::ArrowLeft });
+        assert_eq!(input.cursor_position, 1);
+        input.handle_event(&UIEvent::KeyDown { key: UIKeyCode::ArrowRight });
+        assert_eq!(input.cursor_position, "aé".len());
+
+        input.handle_event(&UIEvent::KeyDown { key: UIKeyCode::Backspace });
+        assert_eq!(input.text, "a你");
+        assert_eq!(input.cursor_position, 1);
+    }
+
+    #[test]
+    fn unfocused_input_ignores_text() {
+        let mut input = InputWidget::new(1);
+        assert!(!input.handle_event(&UIEvent::TextInput { text: "a".to_string() }));
+        assert!(input.text.is_empty());
+    }
+
+    /// 记录文本绘制调用
+    #[derive(Default)]
+    struct RecordingRenderer {
+        texts: Vec<(Vec<crate::ui::TextRun>, FontStyle)>,
+    }
+
+    impl UIRenderer for RecordingRenderer {
+        fn draw_rect(&mut self, _bounds: Rect, _color: Color) {}
+        fn draw_border(&mut self, _bounds: Rect, _border: &crate::ui::style::BorderStyle) {}
+        fn draw_text(&mut self, text: &str, _bounds: Rect, font: &FontStyle, _color: Color) {
+            self.texts.push((vec![crate::ui::TextRun::new(text, None)], font.clone()));
+        }
+        fn draw_rich_text(&mut self, runs: &[crate::ui::TextRun], _bounds: Rect, font: &FontStyle, _color: Color) {
+            self.texts.push((runs.to_vec(), font.clone()));
+        }
+        fn draw_icon(&mut self, _icon_path: &str, _bounds: Rect) {}
+        fn draw_image(&mut self, _image_path: &str, _bounds: Rect) {}
+    }
+
+    #[test]
+    fn text_widget_passes_alignment_and_rich_text_runs() {
+        let source = "[color=#ff0000]Alert[/color] ready";
+        let widget = TextWidget::new(1, source.to_string())
+            .with_alignment(TextAlign::Center, VerticalAlign::Bottom)
+            .with_rich_text(true);
+        let mut renderer = RecordingRenderer::default();
+        widget.render(&mut renderer);
+
+        let (runs, font) = &renderer.texts[0];
+        assert_eq!(runs, &RichText::parse(source).runs);
+        assert_eq!(runs[0].color, Some(Color::RED));
+        assert_eq!((font.text_align, font.vertical_align), (TextAlign::Center, VerticalAlign::Bottom));
+
+        // 未开启富文本时标签按原文绘制
+        let mut renderer = RecordingRenderer::default();
+        TextWidget::new(2, source.to_string()).render(&mut renderer);
+        assert_eq!(renderer.texts[0].0[0].text, source);
+        assert_eq!(renderer.texts[0].1.text_align, TextAlign::Left);
+    }
+
+    type EventLog = std::rc::Rc<std::cell::RefCell<Vec<(WidgetId, UIEvent)>>>;
+
+    /// 记录收到的事件，可选地提供拖拽数据或接受放置
+    struct DragBox {
+        id: WidgetId,
+        bounds: Rect,
+        style: UIStyle,
+        payload: Option<DragPayload>,
+        accepts_drop: bool,
+        log: EventLog,
+    }
+
+    impl DragBox {
+        fn new(id: WidgetId, x: f32, log: &EventLog) -> Self {
+            Self {
+                id,
+                bounds: Rect::new(x, 0.0, 50.0, 50.0),
+                style: UIStyle::default(),
+                payload: None,
+                accepts_drop: false,
+                log: log.clone(),
+            }
+        }
+    }
+
+    impl Widget for DragBox {
+        fn id(&self) -> WidgetId {
+            self.id
+        }
+        fn bounds(&self) -> Rect {
+            self.bounds
+        }
+        fn set_position(&mut self, _position: Vec2) {}
+        fn set_size(&mut self, _size: Vec2) {}
+        fn style(&self) -> &UIStyle {
+            &self.style
+        }
+        fn set_style(&mut self, style: UIStyle) {
+            self.style = style;
+        }
+        fn state(&self) -> WidgetState {
+            WidgetState::Normal
+        }
+        fn set_state(&mut self, _state: WidgetState) {}
+        fn is_visible(&self) -> bool {
+            true
+        }
+        fn set_visible(&mut self, _visible: bool) {}
+        fn is_enabled(&self) -> bool {
+            true
+        }
+        fn set_enabled(&mut self, _enabled: bool) {}
+        fn handle_event(&mut self, event: &UIEvent) -> bool {
+            self.log.borrow_mut().push((self.id, event.clone()));
+            matches!(event, UIEvent::Drop { .. }) && self.accepts_drop
+        }
+        fn update(&mut self, _delta_time: f32) {}
+        fn render(&self, _renderer: &mut dyn UIRenderer) {}
+        fn drag_payload(&self) -> Option<DragPayload> {
+            self.payload.clone()
+        }
+    }
+
+    fn drag_container(accepts_drop: bool) -> (WidgetContainer, EventLog) {
+        let log = EventLog::default();
+        let mut container = WidgetContainer::new();
+        let mut source = DragBox::new(1, 0.0, &log);
+        source.payload = Some(DragPayload::Asset("models/crate.glb".to_string()));
+        let mut target = DragBox::new(2, 100.0, &log);
+        target.accepts_drop = accepts_drop;
+        container.add_widget(source);
+        container.add_widget(target);
+        (container, log)
+    }
+
+    fn press(position: Vec2) -> UIEvent {
+        UIEvent::MouseButtonDown { button: crate::ui::events::MouseButton::Left, position }
+    }
+
+    fn release(position: Vec2) -> UIEvent {
+        UIEvent::MouseButtonUp { button: crate::ui::events::MouseButton::Left, position }
+    }
+
+    #[test]
+    fn drop_target_receives_payload() {
+        let (mut container, log) = drag_container(true);
+        let payload = DragPayload::Asset("models/crate.glb".to_string());
+
+        container.handle_event(&press(Vec2::new(10.0, 10.0)));
+        assert!(container.handle_event(&UIEvent::MouseMove { position: Vec2::new(120.0, 10.0) }));
+        assert_eq!(container.dragging(), Some((1, &payload)));
+        assert!(container.handle_event(&release(Vec2::new(120.0, 20.0))));
+        assert!(container.dragging().is_none());
+
+        // 按下事件照常分发给两个控件，之后是拖拽事件
+        let log = log.borrow();
+        assert_eq!(log.len(), 6);
+        assert!(matches!(&log[log.len() - 4], (1, UIEvent::DragStart { source: 1, .. })));
+        assert!(matches!(&log[log.len() - 3], (2, UIEvent::DragOver { source: 1, .. })));
+        match &log[log.len() - 2] {
+            (2, UIEvent::Drop { source: 1, payload: dropped, position }) => {
+                assert_eq!(dropped, &payload);
+                assert_eq!(*position, Vec2::new(120.0, 20.0));
+            }
+            other => panic!("意外的事件: {:?}", other),
+        }
+        assert_eq!(log[log.len() - 1], (1, UIEvent::DragEnd { source: 1, accepted: true }));
+    }
+
+    #[test]
+    fn rejected_or_missed_drop_is_not_accepted() {
+        for drop_at in [Vec2::new(120.0, 10.0), Vec2::new(500.0, 500.0)] {
+            let (mut container, log) = drag_container(false);
+            container.handle_event(&press(Vec2::new(10.0, 10.0)));
+            container.handle_event(&UIEvent::MouseMove { position: drop_at });
+            container.handle_event(&release(drop_at));
+            assert_eq!(log.borrow().last(), Some(&(1, UIEvent::DragEnd { source: 1, accepted: false })));
+        }
+    }
+
+    #[test]
+    fn small_moves_and_plain_widgets_do_not_drag() {
+        let (mut container, log) = drag_container(true);
+        container.handle_event(&press(Vec2::new(10.0, 10.0)));
+        assert!(!container.handle_event(&UIEvent::MouseMove { position: Vec2::new(12.0, 10.0) }));
+        assert!(container.dragging().is_none());
+        assert!(!container.handle_event(&release(Vec2::new(12.0, 10.0))));
+
+        // 没有拖拽数据的控件不能被拖拽
+        container.handle_event(&press(Vec2::new(110.0, 10.0)));
+        assert!(!container.handle_event(&UIEvent::MouseMove { position: Vec2::new(10.0, 10.0) }));
+        assert!(log.borrow().iter().all(|(_, event)| !matches!(
+            event,
+            UIEvent::DragStart { .. } | UIEvent::DragOver { .. } | UIEvent::Drop { .. } | UIEvent::DragEnd { .. }
+        )));
+    }
+
+    #[test]
+    fn cancel_drag_notifies_source() {
+        let (mut container, log) = drag_container(true);
+        container.handle_event(&press(Vec2::new(10.0, 10.0)));
+        container.handle_event(&UIEvent::MouseMove { position: Vec2::new(30.0, 10.0) });
+        container.cancel_drag();
+
+        assert!(container.dragging().is_none());
+        assert_eq!(log.borrow().last(), Some(&(1, UIEvent::DragEnd { source: 1, accepted: false })));
+        // 取消后释放按普通鼠标事件处理
+        assert!(!container.handle_event(&release(Vec2::new(120.0, 10.0))));
+        assert!(log.borrow().iter().all(|(_, event)| !matches!(event, UIEvent::Drop { .. })));
+    }
+}